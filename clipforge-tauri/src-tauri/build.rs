@@ -77,10 +77,7 @@ fn compile_swift_bridge() {
     let dest_lib = lib_dir.join("libScreenCaptureKitBridge.dylib");
     std::fs::copy(&swift_lib, &dest_lib).expect("Failed to copy Swift library to lib directory");
 
-    println!(
-        "cargo:warning=Copied library to: {}",
-        dest_lib.display()
-    );
+    println!("cargo:warning=Copied library to: {}", dest_lib.display());
 
     // Tell cargo to link the Swift library
     println!("cargo:rustc-link-search=native={}", out_dir);
@@ -4,23 +4,127 @@
 //
 // This module provides safe Rust wrappers around the Swift ScreenCaptureKit
 // bridge, handling FFI safety, memory management, and type conversions
+//
+// Raw frames pushed from Swift are copied once into buffers taken from a
+// FramePool and queued. A FrameConsumer reads the queue on its own thread and
+// returns each buffer to the pool once its callback is done with the frame, so
+// steady-state capture does not allocate; consumers that pop frames themselves
+// must hand them back with `recycle_frame`. The pool keeps at most
+// MAX_POOLED_BYTES of idle buffers, whatever the frame size.
 
 use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
 use std::collections::VecDeque;
 use std::ffi::{c_void, CStr};
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 
 // ============================================================================
 // FFI Type Definitions
 // ============================================================================
 
-/// Opaque pointer to Swift bridge instance
-#[repr(transparent)]
-#[derive(Debug)]
-pub struct SwiftBridgePtr(*mut c_void);
+/// Errors reported by the Swift bridge (codes must match Swift BridgeStatus)
+#[derive(Debug, Clone, PartialEq)]
+pub enum BridgeError {
+    /// ScreenCaptureKit is not available on this system
+    Unavailable,
+    /// Rejected before reaching Swift
+    InvalidConfig(String),
+    /// The requested display, window or application doesn't exist
+    NotFound(String),
+    /// The stream or content filter hasn't been configured yet
+    NotConfigured(String),
+    /// Requires a newer macOS version
+    Unsupported(String),
+    /// Any other failure
+    Failed(String),
+}
+
+impl BridgeError {
+    /// Maps a Swift BridgeStatus code to a result
+    fn from_status(status: i32, message: String) -> Result<(), Self> {
+        match status {
+            1 => Ok(()),
+            -1 => Err(BridgeError::NotFound(message)),
+            -2 => Err(BridgeError::NotConfigured(message)),
+            -3 => Err(BridgeError::Unsupported(message)),
+            _ => Err(BridgeError::Failed(message)),
+        }
+    }
+}
+
+impl std::fmt::Display for BridgeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BridgeError::Unavailable => {
+                write!(f, "ScreenCaptureKit is not available on this system")
+            }
+            BridgeError::InvalidConfig(msg)
+            | BridgeError::NotFound(msg)
+            | BridgeError::NotConfigured(msg)
+            | BridgeError::Unsupported(msg)
+            | BridgeError::Failed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for BridgeError {}
+
+impl From<BridgeError> for String {
+    fn from(error: BridgeError) -> Self {
+        error.to_string()
+    }
+}
 
-unsafe impl Send for SwiftBridgePtr {}
-unsafe impl Sync for SwiftBridgePtr {}
+/// Capture session state (must match Swift CaptureSessionState)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CaptureState {
+    /// Stream configuration or content filter missing
+    Unconfigured,
+    /// Configured and ready to start
+    Ready,
+    Capturing,
+}
+
+/// Stream settings applied with `configure_stream`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StreamConfig {
+    pub width: u32,
+    pub height: u32,
+    pub frame_rate: u32,
+    pub capture_audio: bool,
+}
+
+impl StreamConfig {
+    /// Highest frame rate ScreenCaptureKit delivers
+    pub const MAX_FRAME_RATE: u32 = 240;
+
+    pub fn validate(&self) -> Result<(), BridgeError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(BridgeError::InvalidConfig(format!(
+                "Invalid stream size {}x{}",
+                self.width, self.height
+            )));
+        }
+        if self.width > i32::MAX as u32 || self.height > i32::MAX as u32 {
+            return Err(BridgeError::InvalidConfig(format!(
+                "Stream size {}x{} is too large",
+                self.width, self.height
+            )));
+        }
+        if self.frame_rate == 0 || self.frame_rate > Self::MAX_FRAME_RATE {
+            return Err(BridgeError::InvalidConfig(format!(
+                "Frame rate must be between 1 and {} fps",
+                Self::MAX_FRAME_RATE
+            )));
+        }
+        Ok(())
+    }
+}
 
 /// Frame data structure for passing between Swift and Rust
 #[derive(Debug, Clone)]
@@ -37,8 +141,325 @@ pub struct Frame {
     pub pixel_format: u32,
 }
 
+/// Default raw frame queue capacity (4 seconds at 30fps)
+pub const DEFAULT_FRAME_QUEUE_CAPACITY: usize = 120;
+
+/// Which frame is discarded when a frame queue is full (must match Swift FrameDropPolicy)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DropPolicy {
+    /// Discard the oldest queued frame; keeps live consumers like the preview current
+    DropOldest,
+    /// Discard the incoming frame; keeps ordered consumers like an encoder gap-free
+    /// up to the point the queue filled
+    DropNewest,
+}
+
+impl DropPolicy {
+    /// Value passed across the FFI boundary
+    fn bridge_value(self) -> i32 {
+        match self {
+            DropPolicy::DropOldest => 0,
+            DropPolicy::DropNewest => 1,
+        }
+    }
+}
+
+/// Occupancy and drop counters for a frame queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameQueueStats {
+    /// Maximum number of frames the queue holds
+    pub capacity: usize,
+    /// Frames currently queued
+    pub len: usize,
+    /// Largest number of frames queued at once
+    pub high_water_mark: usize,
+    /// Frames discarded because the queue was full
+    pub dropped: u64,
+    pub policy: DropPolicy,
+}
+
+/// Frame queue with a fixed capacity and a drop policy for when it fills up
+#[derive(Debug)]
+pub struct BoundedFrameQueue {
+    frames: VecDeque<Frame>,
+    capacity: usize,
+    policy: DropPolicy,
+    high_water_mark: usize,
+    dropped: u64,
+}
+
+impl BoundedFrameQueue {
+    pub fn new(capacity: usize, policy: DropPolicy) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            frames: VecDeque::with_capacity(capacity),
+            capacity,
+            policy,
+            high_water_mark: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Queues a frame, applying the drop policy when full
+    ///
+    /// # Returns
+    /// - `Ok(evicted)` if the frame was queued, with the oldest frame if it made room
+    /// - `Err(frame)` with the incoming frame if it was dropped
+    pub fn push(&mut self, frame: Frame) -> Result<Option<Frame>, Frame> {
+        let mut evicted = None;
+        if self.frames.len() >= self.capacity {
+            self.dropped += 1;
+            match self.policy {
+                DropPolicy::DropOldest => evicted = self.frames.pop_front(),
+                DropPolicy::DropNewest => return Err(frame),
+            }
+        }
+
+        self.frames.push_back(frame);
+        self.high_water_mark = self.high_water_mark.max(self.frames.len());
+        Ok(evicted)
+    }
+
+    pub fn pop(&mut self) -> Option<Frame> {
+        self.frames.pop_front()
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// Changes capacity and policy
+    ///
+    /// # Returns
+    /// Frames over the new capacity, dropped oldest first
+    pub fn configure(&mut self, capacity: usize, policy: DropPolicy) -> Vec<Frame> {
+        self.capacity = capacity.max(1);
+        self.policy = policy;
+
+        let excess = self.frames.len().saturating_sub(self.capacity);
+        self.dropped += excess as u64;
+        self.frames.drain(..excess).collect()
+    }
+
+    /// Resets the high-water mark and drop count
+    pub fn reset_stats(&mut self) {
+        self.high_water_mark = self.frames.len();
+        self.dropped = 0;
+    }
+
+    pub fn stats(&self) -> FrameQueueStats {
+        FrameQueueStats {
+            capacity: self.capacity,
+            len: self.frames.len(),
+            high_water_mark: self.high_water_mark,
+            dropped: self.dropped,
+            policy: self.policy,
+        }
+    }
+}
+
 /// Thread-safe frame queue for buffering captured frames
-pub type FrameQueue = Arc<Mutex<VecDeque<Frame>>>;
+pub type FrameQueue = Arc<Mutex<BoundedFrameQueue>>;
+
+/// Everything the Swift frame callback needs, shared with the bridge
+///
+/// Swift holds its own reference from `screen_capture_bridge_set_frame_sink`
+/// until it calls `screen_capture_frame_sink_release`, so the context outlives
+/// any callback in flight even if the Rust bridge is dropped first.
+#[derive(Debug)]
+pub struct FrameSinkContext {
+    queue: FrameQueue,
+    pool: Arc<FramePool>,
+}
+
+/// Receives a raw frame from Swift: (context, width, height, data, length, timestamp, format)
+type FrameSinkPushFn =
+    unsafe extern "C" fn(*const c_void, usize, usize, *const u8, usize, f64, u32) -> i32;
+
+/// Retains or releases a frame sink context
+type FrameSinkContextFn = unsafe extern "C" fn(*const c_void);
+
+/// Idle buffer memory kept for reuse: about eight 4K BGRA frames or thirty 1080p ones
+const MAX_POOLED_BYTES: usize = 256 * 1024 * 1024;
+
+/// How long a FrameConsumer waits for new frames when the queue is empty
+const CONSUMER_POLL_INTERVAL: Duration = Duration::from_millis(2);
+
+/// Allocation counters for a frame buffer pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FramePoolStats {
+    /// Buffers allocated because none in the pool were large enough
+    pub allocations: u64,
+    /// Buffers served from the pool without allocating
+    pub reuses: u64,
+    /// Total bytes allocated for frame buffers
+    pub bytes_allocated: u64,
+    /// Buffers currently waiting in the pool
+    pub pooled: usize,
+    /// Capacity of the buffers waiting in the pool, in bytes
+    pub pooled_bytes: u64,
+}
+
+/// Pool of reusable pixel buffers so each captured frame doesn't allocate
+///
+/// At 4K60 a BGRA frame is ~33 MB; allocating one per frame churns ~2 GB/s
+/// through the allocator. Buffers are returned with `release` once a frame
+/// has been consumed or dropped from the queue, and freed instead when the
+/// idle buffers would exceed `max_bytes`.
+#[derive(Debug)]
+pub struct FramePool {
+    buffers: Mutex<Vec<Vec<u8>>>,
+    max_bytes: usize,
+    allocations: AtomicU64,
+    reuses: AtomicU64,
+    bytes_allocated: AtomicU64,
+}
+
+impl FramePool {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            buffers: Mutex::new(Vec::new()),
+            max_bytes,
+            allocations: AtomicU64::new(0),
+            reuses: AtomicU64::new(0),
+            bytes_allocated: AtomicU64::new(0),
+        }
+    }
+
+    /// Takes a buffer holding a copy of `data`, reusing a pooled buffer when one is large enough
+    pub fn copy_from(&self, data: &[u8]) -> Vec<u8> {
+        let pooled = self.buffers.lock().ok().and_then(|mut buffers| {
+            let index = buffers.iter().position(|b| b.capacity() >= data.len())?;
+            Some(buffers.swap_remove(index))
+        });
+
+        let mut buffer = match pooled {
+            Some(buffer) => {
+                self.reuses.fetch_add(1, Ordering::Relaxed);
+                buffer
+            }
+            None => {
+                self.allocations.fetch_add(1, Ordering::Relaxed);
+                self.bytes_allocated
+                    .fetch_add(data.len() as u64, Ordering::Relaxed);
+                Vec::with_capacity(data.len())
+            }
+        };
+
+        buffer.clear();
+        buffer.extend_from_slice(data);
+        buffer
+    }
+
+    /// Returns a buffer to the pool; it is freed if it would push the pool over `max_bytes`
+    pub fn release(&self, buffer: Vec<u8>) {
+        if let Ok(mut buffers) = self.buffers.lock() {
+            let pooled: usize = buffers.iter().map(|b| b.capacity()).sum();
+            if pooled + buffer.capacity() <= self.max_bytes {
+                buffers.push(buffer);
+            }
+        }
+    }
+
+    pub fn stats(&self) -> FramePoolStats {
+        FramePoolStats {
+            allocations: self.allocations.load(Ordering::Relaxed),
+            reuses: self.reuses.load(Ordering::Relaxed),
+            bytes_allocated: self.bytes_allocated.load(Ordering::Relaxed),
+            pooled: self.buffers.lock().map(|b| b.len()).unwrap_or(0),
+            pooled_bytes: self
+                .buffers
+                .lock()
+                .map(|b| b.iter().map(|b| b.capacity() as u64).sum())
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// Passes every queued frame to `on_frame` and returns its buffer to the pool
+///
+/// # Returns
+/// Number of frames consumed
+fn consume_queued<F: FnMut(&Frame)>(
+    queue: &FrameQueue,
+    pool: &FramePool,
+    on_frame: &mut F,
+) -> usize {
+    let mut consumed = 0;
+    // The queue lock is released before the callback so Swift can keep pushing
+    while let Some(frame) = queue.lock().ok().and_then(|mut queue| queue.pop()) {
+        on_frame(&frame);
+        pool.release(frame.data);
+        consumed += 1;
+    }
+    consumed
+}
+
+/// Reads raw frames from a bridge on a background thread
+///
+/// Starting a consumer attaches the frame sink and dropping it detaches the
+/// sink, so frames are only copied out of Swift while something reads them.
+/// Frames left in the queue when it is dropped are still delivered.
+pub struct FrameConsumer<'a> {
+    bridge: &'a ScreenCaptureBridge,
+    should_stop: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl<'a> FrameConsumer<'a> {
+    /// Attaches the frame sink and calls `on_frame` for each captured frame
+    pub fn start<F>(bridge: &'a ScreenCaptureBridge, mut on_frame: F) -> std::io::Result<Self>
+    where
+        F: FnMut(&Frame) + Send + 'static,
+    {
+        let queue = bridge.frame_queue_clone();
+        let pool = Arc::clone(&bridge.frame_pool);
+        let should_stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = Arc::clone(&should_stop);
+
+        let thread = thread::Builder::new()
+            .name("screencapturekit-frames".to_string())
+            .spawn(move || loop {
+                // Checked before draining so frames queued before the stop still go out
+                let stopping = stop_flag.load(Ordering::SeqCst);
+                if consume_queued(&queue, &pool, &mut on_frame) == 0 {
+                    if stopping {
+                        break;
+                    }
+                    thread::sleep(CONSUMER_POLL_INTERVAL);
+                }
+            })?;
+        bridge.attach_frame_sink();
+
+        Ok(Self {
+            bridge,
+            should_stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for FrameConsumer<'_> {
+    fn drop(&mut self) {
+        // Stop new frames first, then let the thread deliver what is queued
+        self.bridge.call(|raw| raw.set_frame_sink_attached(false));
+        self.should_stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
 
 /// Processed JPEG frame from Swift ScreenCaptureKit
 ///
@@ -57,6 +478,21 @@ pub struct ProcessedJpegFrame {
     pub frame_number: u64,
 }
 
+/// PCM audio buffer from Swift ScreenCaptureKit (s16le, interleaved)
+#[derive(Debug, Clone)]
+pub struct AudioBuffer {
+    /// Signed 16-bit little-endian samples
+    pub pcm_data: Vec<u8>,
+    /// Sample rate in Hz
+    pub sample_rate: f64,
+    /// Number of channels
+    pub channels: u32,
+    /// Presentation timestamp in seconds
+    pub timestamp: f64,
+    /// Number of frames in this buffer
+    pub frame_count: u32,
+}
+
 /// Display information from SCDisplay (must match Swift CDisplayInfo)
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
@@ -67,6 +503,8 @@ pub struct CDisplayInfo {
     pub x: i32,
     pub y: i32,
     pub is_primary: u8, // boolean as u8
+    /// Pixels per point (2.0 on Retina displays)
+    pub scale_factor: f64,
 }
 
 /// Window information from SCWindow (must match Swift CWindowInfo)
@@ -97,9 +535,13 @@ extern "C" {
     fn screen_capture_bridge_destroy(bridge: *mut c_void);
 
     /// Starts capture on a bridge instance
-    /// Returns 1 on success, 0 on failure
+    /// Returns a BridgeStatus code (1 on success)
     fn screen_capture_bridge_start(bridge: *mut c_void) -> i32;
 
+    /// Gets the capture session state
+    /// Returns 0 = unconfigured, 1 = ready, 2 = capturing
+    fn screen_capture_bridge_get_state(bridge: *mut c_void) -> i32;
+
     /// Stops capture on a bridge instance
     fn screen_capture_bridge_stop(bridge: *mut c_void);
 
@@ -129,6 +571,29 @@ extern "C" {
     /// Clears the frame queue
     fn screen_capture_bridge_clear_frame_queue(bridge: *mut c_void);
 
+    /// Registers the raw frame consumer; a null context unregisters it
+    /// Swift calls `retain` once on registration and `release` once when done with the context
+    fn screen_capture_bridge_set_frame_sink(
+        bridge: *mut c_void,
+        context: *const c_void,
+        push: Option<FrameSinkPushFn>,
+        retain: Option<FrameSinkContextFn>,
+        release: Option<FrameSinkContextFn>,
+    );
+
+    /// Configures the frame queue size (1-120) and drop policy (0 = oldest, 1 = newest)
+    fn screen_capture_bridge_configure_frame_queue(bridge: *mut c_void, size: i32, policy: i32);
+
+    /// Gets frame queue statistics since capture started
+    /// Returns 1 if successful, 0 otherwise
+    fn screen_capture_bridge_get_frame_queue_stats(
+        bridge: *mut c_void,
+        out_capacity: *mut i32,
+        out_length: *mut i32,
+        out_high_water: *mut i32,
+        out_dropped: *mut u64,
+    ) -> i32;
+
     /// Configures the stream for capture
     fn screen_capture_bridge_configure_stream(
         bridge: *mut c_void,
@@ -138,14 +603,43 @@ extern "C" {
         capture_audio: u8,
     );
 
+    /// Configures the color space used for capture
+    /// Mode: 0 = SDR, 1 = Display P3, 2 = BT.2020 PQ
+    fn screen_capture_bridge_configure_color_mode(bridge: *mut c_void, mode: i32);
+
     /// Configures the content filter to capture a specific display
-    /// Returns 1 if successful, 0 otherwise
+    /// Returns a BridgeStatus code (1 on success)
     fn screen_capture_bridge_configure_display(bridge: *mut c_void, display_id: u32) -> i32;
 
     /// Configures the content filter to capture a specific window
-    /// Returns 1 if successful, 0 otherwise
+    /// Returns a BridgeStatus code (1 on success)
     fn screen_capture_bridge_configure_window(bridge: *mut c_void, window_id: u32) -> i32;
 
+    /// Configures the content filter to capture audio from specific applications
+    /// Bundle ids are newline-separated; exclude: 1 = all but these, 0 = only these
+    /// Returns a BridgeStatus code (1 on success)
+    fn screen_capture_bridge_configure_app_audio(
+        bridge: *mut c_void,
+        display_id: u32,
+        bundle_ids: *const std::os::raw::c_char,
+        exclude: u8,
+    ) -> i32;
+
+    /// Dequeues a PCM audio buffer from the bridge
+    /// Returns 1 if buffer retrieved, 0 if queue is empty
+    fn screen_capture_bridge_dequeue_audio(
+        bridge: *mut c_void,
+        out_data: *mut *mut u8,
+        out_length: *mut i32,
+        out_sample_rate: *mut f64,
+        out_channels: *mut i32,
+        out_timestamp: *mut f64,
+        out_frame_count: *mut i32,
+    ) -> i32;
+
+    /// Frees PCM data returned by screen_capture_bridge_dequeue_audio
+    fn screen_capture_free_audio_data(pcm_data: *mut u8);
+
     /// Checks if ScreenCaptureKit is available on this system
     /// Returns 1 if available, 0 otherwise
     fn screen_capture_is_available() -> i32;
@@ -174,39 +668,376 @@ extern "C" {
     /// Frees memory allocated by enumerate functions
     fn screen_capture_free_array(ptr: *mut c_void);
 
-    // Thumbnail generation functions
-    /// Captures a thumbnail of a display
-    /// Returns 1 on success, 0 on failure
-    fn screen_capture_display_thumbnail(
-        display_id: u32,
-        max_width: i32,
-        out_data: *mut *mut u8,
-        out_length: *mut i32,
-    ) -> i32;
+    // Thumbnail generation functions
+    /// Captures a thumbnail of a display
+    /// Returns 1 on success, 0 on failure
+    fn screen_capture_display_thumbnail(
+        display_id: u32,
+        max_size: i32,
+        out_data: *mut *mut u8,
+        out_length: *mut i32,
+    ) -> i32;
+
+    /// Captures a thumbnail of a window
+    /// Returns 1 on success, 0 on failure
+    fn screen_capture_window_thumbnail(
+        window_id: u32,
+        max_size: i32,
+        out_data: *mut *mut u8,
+        out_length: *mut i32,
+    ) -> i32;
+}
+
+// ============================================================================
+// Safe Rust API
+// ============================================================================
+
+/// A unit of work run against the Swift bridge on its own thread
+type BridgeJob = Box<dyn FnOnce(&RawBridge) + Send>;
+
+/// Owner of the Swift bridge instance, confined to the bridge thread
+///
+/// Holds the raw pointer, so it is neither Send nor Sync: it is created,
+/// used and destroyed on the thread spawned by `ScreenCaptureBridge::new`.
+struct RawBridge {
+    /// Pointer to the Swift bridge instance
+    ptr: *mut c_void,
+    /// Context registered with Swift for raw frame callbacks
+    frame_sink: Arc<FrameSinkContext>,
+    /// Whether Swift currently holds the frame sink
+    ///
+    /// Only registered while a raw frame consumer is attached: with a sink,
+    /// Swift copies every full-resolution frame and skips preview throttling.
+    frame_sink_attached: Cell<bool>,
+}
+
+impl RawBridge {
+    fn new(frame_sink: Arc<FrameSinkContext>) -> Option<Self> {
+        // Create Swift bridge instance
+        let ptr = unsafe { screen_capture_bridge_create() };
+
+        if ptr.is_null() {
+            return None;
+        }
+
+        Some(Self {
+            ptr,
+            frame_sink,
+            frame_sink_attached: Cell::new(false),
+        })
+    }
+
+    /// Registers the frame sink with Swift, or unregisters it
+    fn set_frame_sink_attached(&self, attached: bool) {
+        if self.frame_sink_attached.replace(attached) == attached {
+            return;
+        }
+
+        // Swift takes its own reference through the retain callback and
+        // drops it through the release callback when the sink is cleared
+        unsafe {
+            if attached {
+                screen_capture_bridge_set_frame_sink(
+                    self.ptr,
+                    Arc::as_ptr(&self.frame_sink) as *const c_void,
+                    Some(screen_capture_push_frame),
+                    Some(screen_capture_frame_sink_retain),
+                    Some(screen_capture_frame_sink_release),
+                );
+            } else {
+                screen_capture_bridge_set_frame_sink(self.ptr, std::ptr::null(), None, None, None);
+            }
+        }
+    }
+
+    /// Starts screen capture
+    fn start_capture(&self) -> Result<(), BridgeError> {
+        let status = unsafe { screen_capture_bridge_start(self.ptr) };
+        self.check_status(status, || {
+            "Failed to start capture - check configuration and permissions".to_string()
+        })
+    }
+
+    /// Gets the capture session state
+    fn capture_state(&self) -> CaptureState {
+        match unsafe { screen_capture_bridge_get_state(self.ptr) } {
+            2 => CaptureState::Capturing,
+            1 => CaptureState::Ready,
+            _ => CaptureState::Unconfigured,
+        }
+    }
+
+    /// Converts a Swift status code, preferring Swift's own error message
+    fn check_status(
+        &self,
+        status: i32,
+        fallback: impl FnOnce() -> String,
+    ) -> Result<(), BridgeError> {
+        if status == 1 {
+            return Ok(());
+        }
+        let message = self.take_last_error().unwrap_or_else(fallback);
+        BridgeError::from_status(status, message)
+    }
+
+    /// Stops screen capture
+    fn stop_capture(&self) {
+        unsafe { screen_capture_bridge_stop(self.ptr) };
+    }
+
+    /// Pauses screen capture
+    fn pause_capture(&self) {
+        unsafe { screen_capture_bridge_pause(self.ptr) };
+    }
+
+    /// Dequeues a JPEG-compressed frame from the Swift queue
+    fn dequeue_jpeg_frame(&self) -> Option<ProcessedJpegFrame> {
+        unsafe {
+            let mut data_ptr: *mut u8 = std::ptr::null_mut();
+            let mut length: i32 = 0;
+            let mut width: i32 = 0;
+            let mut height: i32 = 0;
+            let mut timestamp: f64 = 0.0;
+            let mut frame_number: u64 = 0;
+
+            let result = screen_capture_bridge_dequeue_frame(
+                self.ptr,
+                &mut data_ptr,
+                &mut length,
+                &mut width,
+                &mut height,
+                &mut timestamp,
+                &mut frame_number,
+            );
+
+            if result == 1 && !data_ptr.is_null() && length > 0 {
+                // Copy JPEG data from Swift-allocated buffer
+                let jpeg_data = std::slice::from_raw_parts(data_ptr, length as usize).to_vec();
+
+                // Free the Swift-allocated buffer
+                libc::free(data_ptr as *mut libc::c_void);
+
+                Some(ProcessedJpegFrame {
+                    jpeg_data,
+                    width: width as usize,
+                    height: height as usize,
+                    timestamp,
+                    frame_number,
+                })
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Gets the current JPEG frame queue size from Swift
+    fn jpeg_frame_count(&self) -> usize {
+        unsafe {
+            let count = screen_capture_bridge_get_frame_queue_size(self.ptr);
+            if count >= 0 {
+                count as usize
+            } else {
+                0
+            }
+        }
+    }
+
+    /// Clears all JPEG frames from the Swift queue
+    fn clear_jpeg_frames(&self) {
+        unsafe {
+            screen_capture_bridge_clear_frame_queue(self.ptr);
+        }
+    }
+
+    /// Configures capacity (1-120) and drop policy of the Swift JPEG frame queue
+    fn configure_jpeg_frames(&self, capacity: usize, policy: DropPolicy) {
+        unsafe {
+            screen_capture_bridge_configure_frame_queue(
+                self.ptr,
+                capacity.min(i32::MAX as usize) as i32,
+                policy.bridge_value(),
+            );
+        }
+    }
+
+    /// Gets JPEG frame queue statistics from Swift since capture started
+    fn jpeg_frame_queue_stats(&self, policy: DropPolicy) -> Option<FrameQueueStats> {
+        let mut capacity: i32 = 0;
+        let mut length: i32 = 0;
+        let mut high_water: i32 = 0;
+        let mut dropped: u64 = 0;
+
+        let result = unsafe {
+            screen_capture_bridge_get_frame_queue_stats(
+                self.ptr,
+                &mut capacity,
+                &mut length,
+                &mut high_water,
+                &mut dropped,
+            )
+        };
+
+        (result == 1).then(|| FrameQueueStats {
+            capacity: capacity.max(0) as usize,
+            len: length.max(0) as usize,
+            high_water_mark: high_water.max(0) as usize,
+            dropped,
+            policy,
+        })
+    }
+
+    /// Retrieves and clears the last error message from Swift bridge
+    fn take_last_error(&self) -> Option<String> {
+        unsafe {
+            let ptr = screen_capture_bridge_take_last_error(self.ptr);
+            if ptr.is_null() {
+                return None;
+            }
+
+            let message = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+            libc::free(ptr as *mut libc::c_void);
+            Some(message)
+        }
+    }
+
+    /// Configures the stream settings
+    fn configure_stream(&self, config: &StreamConfig) -> Result<(), BridgeError> {
+        unsafe {
+            screen_capture_bridge_configure_stream(
+                self.ptr,
+                config.width as i32,
+                config.height as i32,
+                config.frame_rate as i32,
+                if config.capture_audio { 1 } else { 0 },
+            );
+        }
+        Ok(())
+    }
+
+    /// Configures the capture color space; call before `configure_stream`
+    fn configure_color_mode(&self, mode: i32) {
+        unsafe {
+            screen_capture_bridge_configure_color_mode(self.ptr, mode);
+        }
+    }
+
+    /// Configures to capture a specific display
+    fn configure_display(&self, display_id: u32) -> Result<(), BridgeError> {
+        let status = unsafe { screen_capture_bridge_configure_display(self.ptr, display_id) };
+        self.check_status(status, || {
+            format!(
+                "Failed to configure display filter for display {}",
+                display_id
+            )
+        })
+    }
+
+    /// Configures to capture a specific window
+    fn configure_window(&self, window_id: u32) -> Result<(), BridgeError> {
+        let status = unsafe { screen_capture_bridge_configure_window(self.ptr, window_id) };
+        self.check_status(status, || {
+            format!("Failed to configure window filter for window {}", window_id)
+        })
+    }
+
+    /// Configures to capture audio only from (or from everything but) the given applications
+    fn configure_app_audio(
+        &self,
+        display_id: u32,
+        bundle_ids: &[String],
+        exclude: bool,
+    ) -> Result<(), BridgeError> {
+        let bundle_ids = std::ffi::CString::new(bundle_ids.join("\n")).map_err(|_| {
+            BridgeError::InvalidConfig("Bundle identifiers must not contain NUL bytes".to_string())
+        })?;
+
+        let status = unsafe {
+            screen_capture_bridge_configure_app_audio(
+                self.ptr,
+                display_id,
+                bundle_ids.as_ptr(),
+                if exclude { 1 } else { 0 },
+            )
+        };
+
+        self.check_status(status, || {
+            "Failed to configure application audio filter (requires macOS 13+)".to_string()
+        })
+    }
+
+    /// Dequeues a PCM audio buffer from the Swift queue
+    fn dequeue_audio(&self) -> Option<AudioBuffer> {
+        unsafe {
+            let mut data_ptr: *mut u8 = std::ptr::null_mut();
+            let mut length: i32 = 0;
+            let mut sample_rate: f64 = 0.0;
+            let mut channels: i32 = 0;
+            let mut timestamp: f64 = 0.0;
+            let mut frame_count: i32 = 0;
+
+            let result = screen_capture_bridge_dequeue_audio(
+                self.ptr,
+                &mut data_ptr,
+                &mut length,
+                &mut sample_rate,
+                &mut channels,
+                &mut timestamp,
+                &mut frame_count,
+            );
 
-    /// Captures a thumbnail of a window
-    /// Returns 1 on success, 0 on failure
-    fn screen_capture_window_thumbnail(
-        window_id: u32,
-        max_width: i32,
-        out_data: *mut *mut u8,
-        out_length: *mut i32,
-    ) -> i32;
+            if result != 1 || data_ptr.is_null() {
+                return None;
+            }
+
+            // Copy PCM data, then free the Swift-allocated buffer
+            let pcm_data = std::slice::from_raw_parts(data_ptr, length.max(0) as usize).to_vec();
+            screen_capture_free_audio_data(data_ptr);
+
+            Some(AudioBuffer {
+                pcm_data,
+                sample_rate,
+                channels: channels.max(0) as u32,
+                timestamp,
+                frame_count: frame_count.max(0) as u32,
+            })
+        }
+    }
 }
 
-// ============================================================================
-// Safe Rust API
-// ============================================================================
+impl Drop for RawBridge {
+    fn drop(&mut self) {
+        // Stop capture if still running
+        self.stop_capture();
+
+        // Destroy Swift bridge instance; Swift releases its frame sink reference
+        self.set_frame_sink_attached(false);
+        unsafe {
+            screen_capture_bridge_destroy(self.ptr);
+        }
+    }
+}
 
 /// High-level Rust wrapper for ScreenCaptureKit bridge
 ///
 /// Provides a safe, ergonomic API for screen capture while managing
-/// the underlying Swift bridge lifecycle and FFI complexity
+/// the underlying Swift bridge lifecycle and FFI complexity.
+///
+/// # Concurrency
+/// The Swift bridge lives on a dedicated thread and every call into it is
+/// sent over a command channel and answered on a reply channel, so calls
+/// are serialized and the raw pointer never leaves that thread. The wrapper
+/// itself is Send and Sync without any unsafe impls and can be shared
+/// freely. Raw frames reach the Rust queue through the frame sink context,
+/// which is independent of the bridge thread.
 pub struct ScreenCaptureBridge {
-    /// Pointer to the Swift bridge instance
-    bridge_ptr: SwiftBridgePtr,
+    /// Sends work to the bridge thread; dropped first to shut it down
+    commands: Option<mpsc::Sender<BridgeJob>>,
+    /// Bridge thread, joined on drop
+    thread: Option<thread::JoinHandle<()>>,
     /// Thread-safe queue for captured frames
     frame_queue: FrameQueue,
+    /// Reusable pixel buffers for queued frames
+    frame_pool: Arc<FramePool>,
 }
 
 impl ScreenCaptureBridge {
@@ -225,15 +1056,75 @@ impl ScreenCaptureBridge {
             return None;
         }
 
-        // Create Swift bridge instance
-        let bridge_ptr = unsafe { screen_capture_bridge_create() };
-
-        if bridge_ptr.is_null() {
+        let frame_queue = Arc::new(Mutex::new(BoundedFrameQueue::new(
+            DEFAULT_FRAME_QUEUE_CAPACITY,
+            DropPolicy::DropOldest,
+        )));
+        let frame_pool = Arc::new(FramePool::new(MAX_POOLED_BYTES));
+        let frame_sink = Arc::new(FrameSinkContext {
+            queue: Arc::clone(&frame_queue),
+            pool: Arc::clone(&frame_pool),
+        });
+
+        let (commands, jobs) = mpsc::channel::<BridgeJob>();
+        let (created_tx, created_rx) = mpsc::sync_channel(1);
+
+        let thread = thread::Builder::new()
+            .name("screencapturekit-bridge".to_string())
+            .spawn(move || {
+                let Some(raw) = RawBridge::new(frame_sink) else {
+                    let _ = created_tx.send(false);
+                    return;
+                };
+                let _ = created_tx.send(true);
+
+                // Runs until every sender is dropped
+                for job in jobs {
+                    job(&raw);
+                }
+            })
+            .ok()?;
+
+        if !created_rx.recv().unwrap_or(false) {
+            let _ = thread.join();
             return None;
         }
+
         Some(Self {
-            bridge_ptr: SwiftBridgePtr(bridge_ptr),
-            frame_queue: Arc::new(Mutex::new(VecDeque::with_capacity(60))), // 2 seconds at 30fps
+            commands: Some(commands),
+            thread: Some(thread),
+            frame_queue,
+            frame_pool,
+        })
+    }
+
+    /// Runs `f` on the bridge thread and waits for its result
+    ///
+    /// # Returns
+    /// `None` if the bridge thread has exited
+    fn call<R, F>(&self, f: F) -> Option<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&RawBridge) -> R + Send + 'static,
+    {
+        let (reply_tx, reply_rx) = mpsc::sync_channel(1);
+        let job: BridgeJob = Box::new(move |raw| {
+            let _ = reply_tx.send(f(raw));
+        });
+
+        self.commands.as_ref()?.send(job).ok()?;
+        reply_rx.recv().ok()
+    }
+
+    /// Like `call`, for operations that report a `BridgeError`
+    fn try_call<F>(&self, f: F) -> Result<(), BridgeError>
+    where
+        F: FnOnce(&RawBridge) -> Result<(), BridgeError> + Send + 'static,
+    {
+        self.call(f).unwrap_or_else(|| {
+            Err(BridgeError::Failed(
+                "ScreenCaptureKit bridge thread has stopped".to_string(),
+            ))
         })
     }
 
@@ -249,36 +1140,56 @@ impl ScreenCaptureBridge {
     ///
     /// # Returns
     /// - `Ok(())` if capture started successfully
-    /// - `Err(String)` with error message if start failed
+    /// - `Err(BridgeError::NotConfigured)` if the stream or content filter isn't set
+    /// - `Err(BridgeError)` with the Swift error message otherwise
     ///
     /// # Notes
     /// - Requires stream configuration and content filter to be set first
     /// - Will stop existing capture if already running
-    pub fn start_capture(&self) -> Result<(), String> {
-        let result = unsafe { screen_capture_bridge_start(self.bridge_ptr.0) };
+    pub fn start_capture(&self) -> Result<(), BridgeError> {
+        self.try_call(|raw| raw.start_capture())
+    }
 
-        if result == 1 {
-            Ok(())
-        } else {
-            let error_msg = self.take_last_error().unwrap_or_else(|| {
-                "Failed to start capture - check configuration and permissions".to_string()
-            });
-            Err(error_msg)
-        }
+    /// Gets the capture session state
+    pub fn capture_state(&self) -> CaptureState {
+        self.call(|raw| raw.capture_state())
+            .unwrap_or(CaptureState::Unconfigured)
+    }
+
+    /// Whether the stream is currently delivering frames
+    pub fn is_capturing(&self) -> bool {
+        self.capture_state() == CaptureState::Capturing
     }
 
     /// Stops screen capture
     ///
     /// Safe to call even if capture is not running
     pub fn stop_capture(&self) {
-        unsafe { screen_capture_bridge_stop(self.bridge_ptr.0) };    }
+        self.call(|raw| raw.stop_capture());
+    }
 
     /// Pauses screen capture
     ///
     /// Note: Current implementation stops the stream. True pause/resume
     /// will be implemented in a future task.
     pub fn pause_capture(&self) {
-        unsafe { screen_capture_bridge_pause(self.bridge_ptr.0) };    }
+        self.call(|raw| raw.pause_capture());
+    }
+
+    /// Starts delivering raw frames into the frame queue
+    ///
+    /// Raw frames are only copied out of Swift while a consumer needs them;
+    /// call `detach_frame_sink` when it stops reading the queue. `FrameConsumer`
+    /// does both.
+    pub fn attach_frame_sink(&self) {
+        self.call(|raw| raw.set_frame_sink_attached(true));
+    }
+
+    /// Stops delivering raw frames and returns queued frames to the pool
+    pub fn detach_frame_sink(&self) {
+        self.call(|raw| raw.set_frame_sink_attached(false));
+        self.clear_frames();
+    }
 
     /// Gets reference to the frame queue
     ///
@@ -303,7 +1214,7 @@ impl ScreenCaptureBridge {
     /// - `Some(Frame)` if a frame is available
     /// - `None` if the queue is empty
     pub fn pop_frame(&self) -> Option<Frame> {
-        self.frame_queue.lock().ok()?.pop_front()
+        self.frame_queue.lock().ok()?.pop()
     }
 
     /// Gets the current number of frames in the queue
@@ -314,7 +1225,34 @@ impl ScreenCaptureBridge {
     /// Clears all frames from the queue
     pub fn clear_frames(&self) {
         if let Ok(mut queue) = self.frame_queue.lock() {
-            queue.clear();        }
+            while let Some(frame) = queue.pop() {
+                self.frame_pool.release(frame.data);
+            }
+        }
+    }
+
+    /// Returns a frame's buffer to the pool once the consumer is done with it
+    pub fn recycle_frame(&self, frame: Frame) {
+        self.frame_pool.release(frame.data);
+    }
+
+    /// Gets frame buffer allocation statistics
+    pub fn frame_pool_stats(&self) -> FramePoolStats {
+        self.frame_pool.stats()
+    }
+
+    /// Configures capacity and drop policy of the raw frame queue
+    pub fn configure_frames(&self, capacity: usize, policy: DropPolicy) {
+        if let Ok(mut queue) = self.frame_queue.lock() {
+            for frame in queue.configure(capacity, policy) {
+                self.frame_pool.release(frame.data);
+            }
+        }
+    }
+
+    /// Gets raw frame queue statistics
+    pub fn frame_queue_stats(&self) -> Option<FrameQueueStats> {
+        self.frame_queue.lock().ok().map(|queue| queue.stats())
     }
 
     /// Dequeues a JPEG-compressed frame from the Swift queue
@@ -323,42 +1261,7 @@ impl ScreenCaptureBridge {
     /// - `Some(ProcessedJpegFrame)` if a frame is available
     /// - `None` if the queue is empty
     pub fn dequeue_jpeg_frame(&self) -> Option<ProcessedJpegFrame> {
-        unsafe {
-            let mut data_ptr: *mut u8 = std::ptr::null_mut();
-            let mut length: i32 = 0;
-            let mut width: i32 = 0;
-            let mut height: i32 = 0;
-            let mut timestamp: f64 = 0.0;
-            let mut frame_number: u64 = 0;
-
-            let result = screen_capture_bridge_dequeue_frame(
-                self.bridge_ptr.0,
-                &mut data_ptr,
-                &mut length,
-                &mut width,
-                &mut height,
-                &mut timestamp,
-                &mut frame_number,
-            );
-
-            if result == 1 && !data_ptr.is_null() && length > 0 {
-                // Copy JPEG data from Swift-allocated buffer
-                let jpeg_data = std::slice::from_raw_parts(data_ptr, length as usize).to_vec();
-
-                // Free the Swift-allocated buffer
-                libc::free(data_ptr as *mut libc::c_void);
-
-                Some(ProcessedJpegFrame {
-                    jpeg_data,
-                    width: width as usize,
-                    height: height as usize,
-                    timestamp,
-                    frame_number,
-                })
-            } else {
-                None
-            }
-        }
+        self.call(|raw| raw.dequeue_jpeg_frame()).flatten()
     }
 
     /// Gets the current JPEG frame queue size from Swift
@@ -366,89 +1269,87 @@ impl ScreenCaptureBridge {
     /// # Returns
     /// Number of frames in the Swift queue, or 0 if error
     pub fn jpeg_frame_count(&self) -> usize {
-        unsafe {
-            let count = screen_capture_bridge_get_frame_queue_size(self.bridge_ptr.0);
-            if count >= 0 {
-                count as usize
-            } else {
-                0
-            }
-        }
+        self.call(|raw| raw.jpeg_frame_count()).unwrap_or(0)
     }
 
     /// Clears all JPEG frames from the Swift queue
     pub fn clear_jpeg_frames(&self) {
-        unsafe {
-            screen_capture_bridge_clear_frame_queue(self.bridge_ptr.0);
-        }    }
+        self.call(|raw| raw.clear_jpeg_frames());
+    }
 
-    /// Retrieves and clears the last error message from Swift bridge
-    fn take_last_error(&self) -> Option<String> {
-        unsafe {
-            let ptr = screen_capture_bridge_take_last_error(self.bridge_ptr.0);
-            if ptr.is_null() {
-                return None;
-            }
+    /// Configures capacity (1-120) and drop policy of the Swift JPEG frame queue
+    pub fn configure_jpeg_frames(&self, capacity: usize, policy: DropPolicy) {
+        self.call(move |raw| raw.configure_jpeg_frames(capacity, policy));
+    }
 
-            let message = CStr::from_ptr(ptr).to_string_lossy().into_owned();
-            libc::free(ptr as *mut libc::c_void);
-            Some(message)
-        }
+    /// Gets JPEG frame queue statistics from Swift since capture started
+    ///
+    /// # Returns
+    /// Queue statistics, or None if the bridge could not report them
+    pub fn jpeg_frame_queue_stats(&self, policy: DropPolicy) -> Option<FrameQueueStats> {
+        self.call(move |raw| raw.jpeg_frame_queue_stats(policy))
+            .flatten()
     }
 
     /// Configures the stream settings
-    pub fn configure_stream(&self, width: u32, height: u32, frame_rate: u32, capture_audio: bool) {
-        unsafe {
-            screen_capture_bridge_configure_stream(
-                self.bridge_ptr.0,
-                width as i32,
-                height as i32,
-                frame_rate as i32,
-                if capture_audio { 1 } else { 0 },
-            );
-        }    }
+    ///
+    /// # Returns
+    /// `Err(BridgeError::InvalidConfig)` if the settings are out of range
+    pub fn configure_stream(&self, config: &StreamConfig) -> Result<(), BridgeError> {
+        config.validate()?;
+        let config = *config;
+        self.try_call(move |raw| raw.configure_stream(&config))
+    }
 
-    /// Configures to capture a specific display
-    pub fn configure_display(&self, display_id: u32) -> Result<(), String> {
-        let result =
-            unsafe { screen_capture_bridge_configure_display(self.bridge_ptr.0, display_id) };
+    /// Configures the capture color space; call before `configure_stream`
+    ///
+    /// Mode: 0 = SDR, 1 = Display P3, 2 = BT.2020 PQ
+    pub fn configure_color_mode(&self, mode: i32) {
+        self.call(move |raw| raw.configure_color_mode(mode));
+    }
 
-        if result == 1 {            Ok(())
-        } else {
-            let error_msg = self.take_last_error().unwrap_or_else(|| {
-                format!(
-                    "Failed to configure display filter for display {}",
-                    display_id
-                )
-            });
-            Err(error_msg)
-        }
+    /// Configures to capture a specific display
+    pub fn configure_display(&self, display_id: u32) -> Result<(), BridgeError> {
+        self.try_call(move |raw| raw.configure_display(display_id))
     }
 
     /// Configures to capture a specific window
-    pub fn configure_window(&self, window_id: u32) -> Result<(), String> {
-        let result =
-            unsafe { screen_capture_bridge_configure_window(self.bridge_ptr.0, window_id) };
+    pub fn configure_window(&self, window_id: u32) -> Result<(), BridgeError> {
+        self.try_call(move |raw| raw.configure_window(window_id))
+    }
 
-        if result == 1 {            Ok(())
-        } else {
-            let error_msg = self.take_last_error().unwrap_or_else(|| {
-                format!("Failed to configure window filter for window {}", window_id)
-            });
-            Err(error_msg)
-        }
+    /// Configures to capture audio only from (or from everything but) the given applications
+    ///
+    /// Requires macOS 13.0+. A display id of 0 anchors the filter to the first display.
+    pub fn configure_app_audio(
+        &self,
+        display_id: u32,
+        bundle_ids: &[String],
+        exclude: bool,
+    ) -> Result<(), BridgeError> {
+        let bundle_ids = bundle_ids.to_vec();
+        self.try_call(move |raw| raw.configure_app_audio(display_id, &bundle_ids, exclude))
+    }
+
+    /// Dequeues a PCM audio buffer from the Swift queue
+    ///
+    /// # Returns
+    /// - `Some(AudioBuffer)` if a buffer is available
+    /// - `None` if the queue is empty
+    pub fn dequeue_audio(&self) -> Option<AudioBuffer> {
+        self.call(|raw| raw.dequeue_audio()).flatten()
     }
 }
 
 impl Drop for ScreenCaptureBridge {
     fn drop(&mut self) {
-        // Stop capture if still running
-        self.stop_capture();
-
-        // Destroy Swift bridge instance
-        unsafe {
-            screen_capture_bridge_destroy(self.bridge_ptr.0);
-        }    }
+        // Closing the channel ends the bridge thread, which stops capture and
+        // destroys the Swift bridge
+        self.commands.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
 }
 
 // ============================================================================
@@ -561,23 +1462,76 @@ pub fn get_window_metadata(window_id: u32) -> Result<(String, String), String> {
     }
 }
 
+/// Captures a display at full size as PNG data
+///
+/// Uses the thumbnail bridge without a width limit, so the image matches the
+/// display's size in points
+pub fn capture_display_png(display_id: u32) -> Result<Vec<u8>, String> {
+    unsafe {
+        let mut data_ptr: *mut u8 = std::ptr::null_mut();
+        let mut length: i32 = 0;
+
+        let result = screen_capture_display_thumbnail(
+            display_id,
+            i32::MAX,
+            &mut data_ptr as *mut *mut u8,
+            &mut length as *mut i32,
+        );
+
+        if result != 1 || data_ptr.is_null() || length == 0 {
+            return Err(format!("Failed to capture display {}", display_id));
+        }
+
+        let png_data = std::slice::from_raw_parts(data_ptr, length as usize).to_vec();
+
+        // Free the Swift-allocated buffer
+        screen_capture_free_array(data_ptr as *mut c_void);
+        Ok(png_data)
+    }
+}
+
+/// Captures a window at full size as PNG data
+pub fn capture_window_png(window_id: u32) -> Result<Vec<u8>, String> {
+    unsafe {
+        let mut data_ptr: *mut u8 = std::ptr::null_mut();
+        let mut length: i32 = 0;
+
+        let result = screen_capture_window_thumbnail(
+            window_id,
+            i32::MAX,
+            &mut data_ptr as *mut *mut u8,
+            &mut length as *mut i32,
+        );
+
+        if result != 1 || data_ptr.is_null() || length == 0 {
+            return Err(format!("Failed to capture window {}", window_id));
+        }
+
+        let png_data = std::slice::from_raw_parts(data_ptr, length as usize).to_vec();
+
+        // Free the Swift-allocated buffer
+        screen_capture_free_array(data_ptr as *mut c_void);
+        Ok(png_data)
+    }
+}
+
 /// Captures a thumbnail of a display as base64-encoded PNG
 ///
 /// # Parameters
 /// - `display_id`: The display ID to capture
-/// - `max_width`: Maximum width for the thumbnail (default 200)
+/// - `max_size`: Maximum length of the thumbnail's longest side, in pixels
 ///
 /// # Returns
 /// - `Ok(String)` with base64-encoded PNG data on success
 /// - `Err(String)` with error message on failure
-pub fn capture_display_thumbnail(display_id: u32, max_width: i32) -> Result<String, String> {
+pub fn capture_display_thumbnail(display_id: u32, max_size: i32) -> Result<String, String> {
     unsafe {
         let mut data_ptr: *mut u8 = std::ptr::null_mut();
         let mut length: i32 = 0;
 
         let result = screen_capture_display_thumbnail(
             display_id,
-            max_width,
+            max_size,
             &mut data_ptr as *mut *mut u8,
             &mut length as *mut i32,
         );
@@ -594,7 +1548,8 @@ pub fn capture_display_thumbnail(display_id: u32, max_width: i32) -> Result<Stri
         let base64_string = base64::engine::general_purpose::STANDARD.encode(png_data);
 
         // Free the Swift-allocated buffer
-        screen_capture_free_array(data_ptr as *mut c_void);        Ok(base64_string)
+        screen_capture_free_array(data_ptr as *mut c_void);
+        Ok(base64_string)
     }
 }
 
@@ -602,19 +1557,19 @@ pub fn capture_display_thumbnail(display_id: u32, max_width: i32) -> Result<Stri
 ///
 /// # Parameters
 /// - `window_id`: The window ID to capture
-/// - `max_width`: Maximum width for the thumbnail (default 200)
+/// - `max_size`: Maximum length of the thumbnail's longest side, in pixels
 ///
 /// # Returns
 /// - `Ok(String)` with base64-encoded PNG data on success
 /// - `Err(String)` with error message on failure
-pub fn capture_window_thumbnail(window_id: u32, max_width: i32) -> Result<String, String> {
+pub fn capture_window_thumbnail(window_id: u32, max_size: i32) -> Result<String, String> {
     unsafe {
         let mut data_ptr: *mut u8 = std::ptr::null_mut();
         let mut length: i32 = 0;
 
         let result = screen_capture_window_thumbnail(
             window_id,
-            max_width,
+            max_size,
             &mut data_ptr as *mut *mut u8,
             &mut length as *mut i32,
         );
@@ -631,7 +1586,8 @@ pub fn capture_window_thumbnail(window_id: u32, max_width: i32) -> Result<String
         let base64_string = base64::engine::general_purpose::STANDARD.encode(png_data);
 
         // Free the Swift-allocated buffer
-        screen_capture_free_array(data_ptr as *mut c_void);        Ok(base64_string)
+        screen_capture_free_array(data_ptr as *mut c_void);
+        Ok(base64_string)
     }
 }
 
@@ -639,17 +1595,39 @@ pub fn capture_window_thumbnail(window_id: u32, max_width: i32) -> Result<String
 // Frame Callback Functions (called from Swift)
 // ============================================================================
 
+/// Retains a frame sink context on behalf of Swift
+///
+/// # Safety
+/// `context` must point to a live `FrameSinkContext` created with `Arc::new`
+#[no_mangle]
+pub unsafe extern "C" fn screen_capture_frame_sink_retain(context: *const c_void) {
+    if !context.is_null() {
+        Arc::increment_strong_count(context as *const FrameSinkContext);
+    }
+}
+
+/// Releases a frame sink context reference taken with `screen_capture_frame_sink_retain`
+///
+/// # Safety
+/// Must be called exactly once per retain; `context` must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn screen_capture_frame_sink_release(context: *const c_void) {
+    if !context.is_null() {
+        Arc::decrement_strong_count(context as *const FrameSinkContext);
+    }
+}
+
 /// Pushes a frame into the Rust frame queue
 /// This function is called from Swift when a new frame is captured
 ///
 /// # Safety
 /// This is an unsafe FFI function. The caller (Swift) must ensure:
-/// - `bridge_ptr` is a valid pointer returned from `screen_capture_bridge_create`
+/// - `context` is a frame sink context it has retained and not yet released
 /// - `pixel_data` points to valid memory of size `data_len`
 /// - Memory remains valid for the duration of this call
 ///
 /// # Parameters
-/// - `bridge_ptr`: Pointer to the ScreenCaptureBridge instance
+/// - `context`: Frame sink context registered with `screen_capture_bridge_set_frame_sink`
 /// - `width`, `height`: Frame dimensions
 /// - `pixel_data`: Pointer to pixel data (BGRA format)
 /// - `data_len`: Length of pixel data in bytes
@@ -657,7 +1635,7 @@ pub fn capture_window_thumbnail(window_id: u32, max_width: i32) -> Result<String
 /// - `pixel_format`: FourCC pixel format code
 #[no_mangle]
 pub unsafe extern "C" fn screen_capture_push_frame(
-    bridge_ptr: *mut c_void,
+    context: *const c_void,
     width: usize,
     height: usize,
     pixel_data: *const u8,
@@ -666,7 +1644,7 @@ pub unsafe extern "C" fn screen_capture_push_frame(
     pixel_format: u32,
 ) -> i32 {
     // Validate inputs
-    if bridge_ptr.is_null() {
+    if context.is_null() {
         return 0;
     }
 
@@ -678,8 +1656,13 @@ pub unsafe extern "C" fn screen_capture_push_frame(
         return 0;
     }
 
-    // Copy pixel data into Rust Vec
-    let data = std::slice::from_raw_parts(pixel_data, data_len).to_vec();
+    // Borrow the context; Swift's reference keeps it alive for this call
+    let sink = &*(context as *const FrameSinkContext);
+
+    // Copy pixel data into a pooled buffer
+    let data = sink
+        .pool
+        .copy_from(std::slice::from_raw_parts(pixel_data, data_len));
 
     // Create frame
     let frame = Frame {
@@ -690,22 +1673,20 @@ pub unsafe extern "C" fn screen_capture_push_frame(
         pixel_format,
     };
 
-    // Get bridge instance from pointer
-    // Note: We use ManuallyDrop to prevent double-free since Swift owns the bridge
-    let bridge = std::mem::ManuallyDrop::new(Box::from_raw(bridge_ptr as *mut ScreenCaptureBridge));
-
-    // Push frame to queue
-    if let Ok(mut queue) = bridge.frame_queue.lock() {
-        // Limit queue size to prevent memory bloat
-        const MAX_QUEUE_SIZE: usize = 120; // 4 seconds at 30fps
-
-        if queue.len() >= MAX_QUEUE_SIZE {
-            // Drop oldest frame
-            queue.pop_front();
-        }
-
-        queue.push_back(frame);
-        return 1; // Success
+    // Push frame to queue; the queue's capacity and drop policy limit memory use
+    if let Ok(mut queue) = sink.queue.lock() {
+        return match queue.push(frame) {
+            Ok(evicted) => {
+                if let Some(evicted) = evicted {
+                    sink.pool.release(evicted.data);
+                }
+                1
+            }
+            Err(dropped) => {
+                sink.pool.release(dropped.data);
+                0
+            }
+        };
     }
 
     0 // Failure
@@ -714,15 +1695,15 @@ pub unsafe extern "C" fn screen_capture_push_frame(
 /// Gets the current frame queue size
 ///
 /// # Safety
-/// The caller must ensure `bridge_ptr` is valid
+/// The caller must ensure `context` is a frame sink context it has retained
 #[no_mangle]
-pub unsafe extern "C" fn screen_capture_get_queue_size(bridge_ptr: *mut c_void) -> usize {
-    if bridge_ptr.is_null() {
+pub unsafe extern "C" fn screen_capture_get_queue_size(context: *const c_void) -> usize {
+    if context.is_null() {
         return 0;
     }
 
-    let bridge = std::mem::ManuallyDrop::new(Box::from_raw(bridge_ptr as *mut ScreenCaptureBridge));
-    bridge.frame_count()
+    let sink = &*(context as *const FrameSinkContext);
+    sink.queue.lock().map(|q| q.len()).unwrap_or(0)
 }
 
 // ============================================================================
@@ -748,12 +1729,20 @@ mod tests {
     #[test]
     fn test_bridge_creation() {
         if let Some(bridge) = ScreenCaptureBridge::new() {
-            assert_eq!(bridge.frame_count(), 0);        } else {
+            assert_eq!(bridge.frame_count(), 0);
+        } else {
             // On non-macOS or old macOS versions, this is expected
             println!("Bridge creation skipped (ScreenCaptureKit not available)");
         }
     }
 
+    #[test]
+    fn test_bridge_is_send_sync() {
+        // Guaranteed by the bridge thread rather than unsafe impls
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ScreenCaptureBridge>();
+    }
+
     #[test]
     fn test_frame_queue() {
         if let Some(bridge) = ScreenCaptureBridge::new() {
@@ -766,4 +1755,196 @@ mod tests {
             assert!(Arc::ptr_eq(&bridge.frame_queue, &queue_clone));
         }
     }
+
+    #[test]
+    fn test_stream_config_validation() {
+        let config = StreamConfig {
+            width: 1920,
+            height: 1080,
+            frame_rate: 60,
+            capture_audio: false,
+        };
+        assert!(config.validate().is_ok());
+
+        for invalid in [
+            StreamConfig { width: 0, ..config },
+            StreamConfig {
+                frame_rate: 0,
+                ..config
+            },
+            StreamConfig {
+                frame_rate: StreamConfig::MAX_FRAME_RATE + 1,
+                ..config
+            },
+        ] {
+            assert!(matches!(
+                invalid.validate(),
+                Err(BridgeError::InvalidConfig(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_bridge_status_codes() {
+        assert_eq!(BridgeError::from_status(1, String::new()), Ok(()));
+        assert!(matches!(
+            BridgeError::from_status(-1, "gone".to_string()),
+            Err(BridgeError::NotFound(msg)) if msg == "gone"
+        ));
+        assert!(matches!(
+            BridgeError::from_status(-2, String::new()),
+            Err(BridgeError::NotConfigured(_))
+        ));
+        assert!(matches!(
+            BridgeError::from_status(0, String::new()),
+            Err(BridgeError::Failed(_))
+        ));
+    }
+
+    #[test]
+    fn test_bounded_queue_drop_policies() {
+        let mut oldest = BoundedFrameQueue::new(2, DropPolicy::DropOldest);
+        let mut newest = BoundedFrameQueue::new(2, DropPolicy::DropNewest);
+        for i in 0..2 {
+            assert!(matches!(
+                oldest.push(Frame {
+                    width: 1,
+                    height: 1,
+                    data: vec![0; 4],
+                    timestamp: i as f64,
+                    pixel_format: 0
+                }),
+                Ok(None)
+            ));
+            assert!(matches!(
+                newest.push(Frame {
+                    width: 1,
+                    height: 1,
+                    data: vec![0; 4],
+                    timestamp: i as f64,
+                    pixel_format: 0
+                }),
+                Ok(None)
+            ));
+        }
+
+        let evicted = oldest
+            .push(Frame {
+                width: 1,
+                height: 1,
+                data: vec![0; 4],
+                timestamp: 2.0,
+                pixel_format: 0,
+            })
+            .unwrap()
+            .unwrap();
+        assert_eq!(evicted.timestamp, 0.0);
+        assert_eq!(
+            newest
+                .push(Frame {
+                    width: 1,
+                    height: 1,
+                    data: vec![0; 4],
+                    timestamp: 2.0,
+                    pixel_format: 0
+                })
+                .unwrap_err()
+                .timestamp,
+            2.0
+        );
+
+        assert_eq!(oldest.pop().unwrap().timestamp, 1.0);
+        assert_eq!(newest.pop().unwrap().timestamp, 0.0);
+
+        let stats = newest.stats();
+        assert_eq!(stats.dropped, 1);
+        assert_eq!(stats.high_water_mark, 2);
+        assert_eq!(stats.len, 1);
+    }
+
+    #[test]
+    fn test_frame_sink_context_lifecycle() {
+        let queue = Arc::new(Mutex::new(BoundedFrameQueue::new(
+            2,
+            DropPolicy::DropOldest,
+        )));
+        let sink = Arc::new(FrameSinkContext {
+            queue: Arc::clone(&queue),
+            pool: Arc::new(FramePool::new(1024)),
+        });
+        let context = Arc::as_ptr(&sink) as *const c_void;
+
+        unsafe {
+            // Swift's reference keeps the context alive after the bridge lets go
+            screen_capture_frame_sink_retain(context);
+            drop(sink);
+
+            let pixels = [0u8; 16];
+            let queued =
+                screen_capture_push_frame(context, 2, 2, pixels.as_ptr(), pixels.len(), 0.5, 0);
+            assert_eq!(queued, 1);
+            assert_eq!(screen_capture_get_queue_size(context), 1);
+
+            screen_capture_frame_sink_release(context);
+        }
+
+        assert_eq!(Arc::strong_count(&queue), 1);
+        assert_eq!(queue.lock().unwrap().pop().unwrap().timestamp, 0.5);
+    }
+
+    #[test]
+    fn test_frame_pool_reuses_buffers() {
+        let pool = FramePool::new(100);
+
+        let first = pool.copy_from(&[1; 64]);
+        pool.release(first);
+        let second = pool.copy_from(&[2; 32]);
+        assert_eq!(second, vec![2; 32]);
+
+        // Too large for the pooled buffer
+        let third = pool.copy_from(&[3; 128]);
+        assert_eq!(third.len(), 128);
+
+        let stats = pool.stats();
+        assert_eq!(stats.allocations, 2);
+        assert_eq!(stats.reuses, 1);
+        assert_eq!(stats.bytes_allocated, 192);
+        assert_eq!(stats.pooled, 0);
+
+        // Pooling the 128-byte buffer would exceed the byte limit
+        pool.release(second);
+        pool.release(third);
+        let stats = pool.stats();
+        assert_eq!(stats.pooled, 1);
+        assert_eq!(stats.pooled_bytes, 64);
+    }
+
+    #[test]
+    fn test_consume_queued_recycles_buffers() {
+        let queue = Arc::new(Mutex::new(BoundedFrameQueue::new(
+            4,
+            DropPolicy::DropNewest,
+        )));
+        let pool = FramePool::new(1024);
+        for timestamp in [0.0, 0.5] {
+            let frame = Frame {
+                width: 1,
+                height: 1,
+                data: pool.copy_from(&[0; 16]),
+                timestamp,
+                pixel_format: 0,
+            };
+            queue.lock().unwrap().push(frame).unwrap();
+        }
+
+        let mut timestamps = Vec::new();
+        let consumed = consume_queued(&queue, &pool, &mut |frame: &Frame| {
+            timestamps.push(frame.timestamp)
+        });
+
+        assert_eq!(consumed, 2);
+        assert_eq!(timestamps, vec![0.0, 0.5]);
+        assert!(queue.lock().unwrap().is_empty());
+        assert_eq!(pool.stats().pooled, 2);
+    }
 }
@@ -96,7 +96,7 @@ impl FrameProcessor for PreviewFrameProcessor {
             self.processed_count += 1;
 
             // Log occasionally to avoid spam
-            if self.processed_count % 30 == 0 {            }
+            if self.processed_count % 30 == 0 {}
 
             Ok(())
         } else {
@@ -104,7 +104,8 @@ impl FrameProcessor for PreviewFrameProcessor {
         }
     }
 
-    fn flush(&mut self) -> Result<(), String> {        Ok(())
+    fn flush(&mut self) -> Result<(), String> {
+        Ok(())
     }
 
     fn processor_type(&self) -> &str {
@@ -168,7 +169,7 @@ impl EncodingFrameProcessor {
         self.processed_count += 1;
 
         // Log occasionally
-        if self.processed_count % 30 == 0 {        }
+        if self.processed_count % 30 == 0 {}
 
         Ok(())
     }
@@ -0,0 +1,359 @@
+// Media analysis passes
+//
+// Runs FFmpeg analysis filters (silence, scene changes) over a file and parses
+// their log output
+
+use super::ffmpeg_utils::find_ffmpeg;
+use super::process_runner::{watched_output_async, JobKind};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::process::Command;
+
+/// Kept ranges shorter than this are dropped when cutting silence
+const MIN_KEEP_DURATION: f64 = 0.05;
+
+/// A silent section of a file, in seconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SilentRange {
+    pub start: f64,
+    pub end: f64,
+    pub duration: f64,
+}
+
+/// A detected scene change
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SceneChange {
+    /// Timestamp of the first frame of the new scene (seconds)
+    pub time: f64,
+    /// Scene change score reported by FFmpeg (0.0 - 1.0)
+    pub score: f64,
+}
+
+/// Settings for skipping silent sections during export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SilenceSettings {
+    /// Level below which audio counts as silent (dB)
+    pub threshold_db: f64,
+    /// Minimum length of a silence to cut (seconds)
+    pub min_duration: f64,
+    /// Audio kept on each side of a cut so speech isn't clipped (seconds)
+    pub padding: f64,
+}
+
+impl Default for SilenceSettings {
+    fn default() -> Self {
+        Self {
+            threshold_db: -35.0,
+            min_duration: 0.75,
+            padding: 0.15,
+        }
+    }
+}
+
+/// Run silencedetect over a file and return the silent ranges
+pub async fn detect_silence(
+    ffmpeg_path: &Path,
+    video_path: &str,
+    threshold_db: f64,
+    min_duration: f64,
+) -> Result<Vec<SilentRange>, String> {
+    let mut command = Command::new(ffmpeg_path);
+    command
+        .arg("-hide_banner")
+        .arg("-i")
+        .arg(video_path)
+        .arg("-vn")
+        .arg("-af")
+        .arg(format!(
+            "silencedetect=noise={}dB:d={}",
+            threshold_db, min_duration
+        ))
+        .arg("-f")
+        .arg("null")
+        .arg("-");
+    let output = watched_output_async(&mut command, JobKind::Encode)
+        .await
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(format!("Silence detection failed: {}", stderr));
+    }
+
+    Ok(parse_silencedetect(&stderr, parse_duration(&stderr)))
+}
+
+/// Parse silencedetect log lines into ranges
+///
+/// A trailing silence without a `silence_end` is closed at `media_duration`
+pub fn parse_silencedetect(log: &str, media_duration: Option<f64>) -> Vec<SilentRange> {
+    let mut ranges = Vec::new();
+    let mut current_start: Option<f64> = None;
+
+    for line in log.lines() {
+        if let Some(value) = value_after(line, "silence_start:") {
+            current_start = Some(value.max(0.0));
+        } else if let Some(end) = value_after(line, "silence_end:") {
+            if let Some(start) = current_start.take() {
+                ranges.push(SilentRange {
+                    start,
+                    end,
+                    duration: end - start,
+                });
+            }
+        }
+    }
+
+    if let (Some(start), Some(end)) = (current_start, media_duration) {
+        if end > start {
+            ranges.push(SilentRange {
+                start,
+                end,
+                duration: end - start,
+            });
+        }
+    }
+
+    ranges
+}
+
+/// Parse the input duration from FFmpeg's log ("Duration: 00:01:02.50, ...")
+pub fn parse_duration(log: &str) -> Option<f64> {
+    let line = log
+        .lines()
+        .find(|l| l.trim_start().starts_with("Duration:"))?;
+    let value = line.trim_start().strip_prefix("Duration:")?.trim();
+    let timestamp = value.split(',').next()?.trim();
+
+    let mut seconds = 0.0;
+    for part in timestamp.split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(seconds)
+}
+
+/// Compute the ranges of `start..end` left after removing silences
+///
+/// Each silence is shrunk by `padding` on both sides before being cut
+pub fn keep_ranges(
+    start: f64,
+    end: f64,
+    silences: &[SilentRange],
+    padding: f64,
+) -> Vec<(f64, f64)> {
+    let mut ranges = Vec::new();
+    let mut cursor = start;
+
+    for silence in silences {
+        // Silences touching the range edges are cut without padding
+        let cut_start = if silence.start <= start {
+            start
+        } else {
+            silence.start + padding
+        };
+        let cut_end = if silence.end >= end {
+            end
+        } else {
+            silence.end - padding
+        };
+        if cut_end <= cut_start || cut_end <= cursor {
+            continue;
+        }
+
+        if cut_start - cursor >= MIN_KEEP_DURATION {
+            ranges.push((cursor, cut_start));
+        }
+        cursor = cut_end;
+    }
+
+    if end - cursor >= MIN_KEEP_DURATION {
+        ranges.push((cursor, end));
+    }
+
+    ranges
+}
+
+/// Where a source time lands once only `ranges` are kept
+///
+/// Times inside a cut move to the start of the next kept range; None when no
+/// kept range follows
+pub fn kept_offset(ranges: &[(f64, f64)], time: f64) -> Option<f64> {
+    let mut offset = 0.0;
+    for (start, end) in ranges {
+        if time < *end {
+            return Some(offset + (time - start).max(0.0));
+        }
+        offset += end - start;
+    }
+    None
+}
+
+/// Map a 0.0 - 1.0 sensitivity to a scene score threshold
+///
+/// Higher sensitivity lowers the threshold so smaller changes count as cuts
+pub fn scene_threshold(sensitivity: f64) -> f64 {
+    0.6 - 0.5 * sensitivity.clamp(0.0, 1.0)
+}
+
+/// Parse `metadata=print` output for selected frames into scene changes
+pub fn parse_scene_changes(log: &str) -> Vec<SceneChange> {
+    let mut changes = Vec::new();
+    let mut current_time: Option<f64> = None;
+
+    for line in log.lines() {
+        if let Some(time) = value_after(line, "pts_time:") {
+            current_time = Some(time);
+        } else if let Some(index) = line.find("lavfi.scene_score=") {
+            let score = line[index + "lavfi.scene_score=".len()..]
+                .trim()
+                .parse::<f64>();
+            if let (Some(time), Ok(score)) = (current_time.take(), score) {
+                changes.push(SceneChange { time, score });
+            }
+        }
+    }
+
+    changes
+}
+
+fn value_after(line: &str, key: &str) -> Option<f64> {
+    let rest = &line[line.find(key)? + key.len()..];
+    rest.split_whitespace().next()?.parse().ok()
+}
+
+/// Find silent ranges in a video's audio track
+#[tauri::command]
+pub async fn analyze_silence(
+    video_path: String,
+    threshold_db: Option<f64>,
+    min_duration: Option<f64>,
+) -> Result<Vec<SilentRange>, String> {
+    let defaults = SilenceSettings::default();
+    let ffmpeg_path =
+        find_ffmpeg().ok_or_else(|| "ffmpeg not found. Please install FFmpeg.".to_string())?;
+
+    let ranges = detect_silence(
+        &ffmpeg_path,
+        &video_path,
+        threshold_db.unwrap_or(defaults.threshold_db),
+        min_duration.unwrap_or(defaults.min_duration),
+    )
+    .await?;
+
+    println!("Found {} silent ranges in {}", ranges.len(), video_path);
+    Ok(ranges)
+}
+
+/// Detect scene changes so long recordings can be split at cuts
+///
+/// `sensitivity` ranges from 0.0 (only hard cuts) to 1.0 (subtle changes)
+#[tauri::command]
+pub async fn detect_scenes(
+    video_path: String,
+    sensitivity: Option<f64>,
+) -> Result<Vec<SceneChange>, String> {
+    let ffmpeg_path =
+        find_ffmpeg().ok_or_else(|| "ffmpeg not found. Please install FFmpeg.".to_string())?;
+    let threshold = scene_threshold(sensitivity.unwrap_or(0.5));
+
+    let mut command = Command::new(&ffmpeg_path);
+    command
+        .arg("-hide_banner")
+        .arg("-i")
+        .arg(&video_path)
+        .arg("-an")
+        .arg("-vf")
+        .arg(format!(
+            "select='gt(scene,{})',metadata=print:key=lavfi.scene_score",
+            threshold
+        ))
+        .arg("-f")
+        .arg("null")
+        .arg("-");
+    let output = watched_output_async(&mut command, JobKind::Encode)
+        .await
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(format!("Scene detection failed: {}", stderr));
+    }
+
+    let changes = parse_scene_changes(&stderr);
+    println!(
+        "Found {} scene changes in {} (threshold {:.2})",
+        changes.len(),
+        video_path,
+        threshold
+    );
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "  Duration: 00:00:12.50, start: 0.000000, bitrate: 128 kb/s
+[silencedetect @ 0x600] silence_start: 1.5
+[silencedetect @ 0x600] silence_end: 3.25 | silence_duration: 1.75
+[silencedetect @ 0x600] silence_start: 10.25";
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration(LOG), Some(12.5));
+        assert_eq!(parse_duration("no duration here"), None);
+    }
+
+    #[test]
+    fn test_parse_silencedetect_closes_trailing_silence() {
+        let ranges = parse_silencedetect(LOG, Some(12.5));
+        assert_eq!(ranges.len(), 2);
+        assert_eq!(ranges[0].start, 1.5);
+        assert_eq!(ranges[0].end, 3.25);
+        assert_eq!(ranges[1].end, 12.5);
+
+        assert_eq!(parse_silencedetect(LOG, None).len(), 1);
+    }
+
+    #[test]
+    fn test_keep_ranges_applies_padding() {
+        let silences = parse_silencedetect(LOG, Some(12.5));
+        let kept = keep_ranges(0.0, 12.5, &silences, 0.25);
+
+        assert_eq!(kept, vec![(0.0, 1.75), (3.0, 10.5)]);
+    }
+
+    #[test]
+    fn test_kept_offset_skips_cuts() {
+        let kept = [(0.0, 1.75), (3.0, 10.5)];
+
+        assert_eq!(kept_offset(&kept, 1.0), Some(1.0));
+        assert_eq!(kept_offset(&kept, 2.0), Some(1.75));
+        assert_eq!(kept_offset(&kept, 4.0), Some(2.75));
+        assert_eq!(kept_offset(&kept, 11.0), None);
+    }
+
+    #[test]
+    fn test_parse_scene_changes() {
+        let log = "[Parsed_metadata_1 @ 0x600] frame:0    pts:4000  pts_time:4
+[Parsed_metadata_1 @ 0x600] lavfi.scene_score=0.512000
+[Parsed_metadata_1 @ 0x600] frame:1    pts:9500  pts_time:9.5
+[Parsed_metadata_1 @ 0x600] lavfi.scene_score=0.830000";
+
+        let changes = parse_scene_changes(log);
+        assert_eq!(
+            changes,
+            vec![
+                SceneChange {
+                    time: 4.0,
+                    score: 0.512
+                },
+                SceneChange {
+                    time: 9.5,
+                    score: 0.83
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,168 @@
+// Per-user cache directory
+//
+// Thumbnails, export intermediates and in-progress recordings live under one
+// cache root instead of fixed, world-readable names in /tmp. The root defaults to
+// the app's cache directory (~/Library/Caches/<identifier> on macOS) and can be
+// moved with the `cacheDirectory` setting, which `set_cache_directory` updates.
+// Every directory created here is readable by the current user only.
+
+use super::settings::{update_settings, SettingsState};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State};
+
+/// The app's cache directory, resolved when the settings load
+static DEFAULT_ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Custom cache root from the settings, if one is set
+static LOCATION: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Where the cache currently lives
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheLocation {
+    pub path: String,
+    /// True when no custom location has been set
+    pub is_default: bool,
+}
+
+/// Resolve the default cache root and apply the saved cache location
+pub fn init(app: &AppHandle, location: Option<PathBuf>) {
+    let root = app
+        .path()
+        .app_cache_dir()
+        .unwrap_or_else(|_| std::env::temp_dir().join("clipforge"));
+    let _ = DEFAULT_ROOT.set(root);
+    if let Ok(mut guard) = LOCATION.write() {
+        *guard = location;
+    }
+}
+
+/// Move the cache root; `None` goes back to the default location
+///
+/// Called when the settings change. The new directory is created up front so
+/// an unusable location is rejected before it is saved.
+pub fn set_location(location: Option<PathBuf>) -> Result<(), String> {
+    if let Some(dir) = &location {
+        create_private_dir(dir)?;
+    }
+    if let Ok(mut guard) = LOCATION.write() {
+        *guard = location;
+    }
+    Ok(())
+}
+
+/// Custom cache root, if one has been set
+fn custom_location() -> Option<PathBuf> {
+    LOCATION.read().ok().and_then(|guard| guard.clone())
+}
+
+fn default_root() -> PathBuf {
+    DEFAULT_ROOT
+        .get()
+        .cloned()
+        .unwrap_or_else(|| std::env::temp_dir().join("clipforge"))
+}
+
+/// Root of the app cache
+pub fn cache_root() -> PathBuf {
+    custom_location().unwrap_or_else(default_root)
+}
+
+/// Path of a cache subdirectory, without creating it
+pub fn cache_path(name: &str) -> PathBuf {
+    cache_root().join(name)
+}
+
+/// Create a directory (and its parents) readable by the current user only
+fn create_private_dir(dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create directory {}: {}", dir.display(), e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))
+            .map_err(|e| format!("Failed to restrict {}: {}", dir.display(), e))?;
+    }
+
+    Ok(())
+}
+
+/// Cache subdirectory, created with per-user permissions
+pub fn cache_dir(name: &str) -> Result<PathBuf, String> {
+    let root = cache_root();
+    create_private_dir(&root)?;
+    let dir = root.join(name);
+    create_private_dir(&dir)?;
+    Ok(dir)
+}
+
+/// Fresh, uniquely named directory inside a cache subdirectory
+///
+/// Names include the process id and a nanosecond timestamp so concurrent jobs
+/// never share intermediates.
+pub fn unique_dir(name: &str, prefix: &str) -> Result<PathBuf, String> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+    let dir = cache_dir(name)?.join(format!("{}_{}_{}", prefix, std::process::id(), nanos));
+    create_private_dir(&dir)?;
+    Ok(dir)
+}
+
+/// Get the current cache location
+#[tauri::command]
+pub async fn get_cache_directory() -> Result<CacheLocation, String> {
+    let custom = custom_location();
+    Ok(CacheLocation {
+        path: custom
+            .clone()
+            .unwrap_or_else(default_root)
+            .to_string_lossy()
+            .to_string(),
+        is_default: custom.is_none(),
+    })
+}
+
+/// Move the cache to `path`, or back to the default location when `None`
+///
+/// Existing cache contents stay where they are; thumbnails are regenerated
+/// on demand. Recordings already in progress keep their directory.
+#[tauri::command]
+pub async fn set_cache_directory(
+    app: AppHandle,
+    state: State<'_, SettingsState>,
+    path: Option<String>,
+) -> Result<CacheLocation, String> {
+    let path = path
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty());
+    update_settings(app, state, serde_json::json!({ "cacheDirectory": path })).await?;
+
+    get_cache_directory().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_private_dir_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("clipforge_cache_test_{}", std::process::id()));
+        create_private_dir(&dir.join("nested")).unwrap();
+        let mode = fs::metadata(dir.join("nested"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o700);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,124 @@
+// Audio post filters
+//
+// Optional clean-up chain shared by recording and export: high-pass to remove
+// rumble, noise suppression and loudness normalization
+
+use super::ffmpeg_utils::quote_filter_value;
+use serde::{Deserialize, Serialize};
+
+/// Integrated loudness target for normalization (LUFS)
+pub const LOUDNESS_TARGET_LUFS: f64 = -16.0;
+
+/// Toggleable audio post filters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AudioFilterSettings {
+    /// Remove background noise (RNNoise when a model is set, afftdn otherwise)
+    pub noise_suppression: bool,
+    /// Path to an RNNoise model file for the arnndn filter
+    pub rnnoise_model: Option<String>,
+    /// Normalize loudness to -16 LUFS
+    pub loudness_normalization: bool,
+    /// Cut low-frequency rumble below `high_pass_frequency`
+    pub high_pass: bool,
+    /// High-pass cutoff frequency (Hz)
+    pub high_pass_frequency: u32,
+}
+
+impl Default for AudioFilterSettings {
+    fn default() -> Self {
+        Self {
+            noise_suppression: false,
+            rnnoise_model: None,
+            loudness_normalization: false,
+            high_pass: false,
+            high_pass_frequency: 80,
+        }
+    }
+}
+
+impl AudioFilterSettings {
+    /// FFmpeg audio filter chain, or None when every filter is disabled
+    pub fn filter_chain(&self) -> Option<String> {
+        let mut filters = Vec::new();
+
+        if self.high_pass {
+            filters.push(format!("highpass=f={}", self.high_pass_frequency.max(20)));
+        }
+
+        if self.noise_suppression {
+            match &self.rnnoise_model {
+                Some(model) => filters.push(format!("arnndn=m={}", quote_filter_value(model))),
+                None => filters.push("afftdn=nf=-25".to_string()),
+            }
+        }
+
+        // Loudness last so it measures the cleaned-up signal
+        if self.loudness_normalization {
+            filters.push(format!(
+                "loudnorm=I={}:TP=-1.5:LRA=11",
+                LOUDNESS_TARGET_LUFS
+            ));
+        }
+
+        if filters.is_empty() {
+            None
+        } else {
+            Some(filters.join(","))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_has_no_chain() {
+        let settings = AudioFilterSettings::default();
+        assert_eq!(settings.filter_chain(), None);
+    }
+
+    #[test]
+    fn test_chain_order() {
+        let settings = AudioFilterSettings {
+            noise_suppression: true,
+            loudness_normalization: true,
+            high_pass: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            settings.filter_chain().unwrap(),
+            "highpass=f=80,afftdn=nf=-25,loudnorm=I=-16:TP=-1.5:LRA=11"
+        );
+    }
+
+    #[test]
+    fn test_rnnoise_model() {
+        let settings = AudioFilterSettings {
+            noise_suppression: true,
+            rnnoise_model: Some("/models/std.rnnn".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            settings.filter_chain().unwrap(),
+            "arnndn=m='/models/std.rnnn'"
+        );
+    }
+
+    #[test]
+    fn test_rnnoise_model_with_quote() {
+        let settings = AudioFilterSettings {
+            noise_suppression: true,
+            rnnoise_model: Some("/Users/me/Bob's Models/std.rnnn".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            settings.filter_chain().unwrap(),
+            "arnndn=m='/Users/me/Bob'\\''s Models/std.rnnn'"
+        );
+    }
+}
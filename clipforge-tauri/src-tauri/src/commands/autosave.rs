@@ -0,0 +1,200 @@
+// Project autosave
+//
+// The frontend pushes its latest project state whenever it changes; a
+// background task periodically writes dirty state to a ring of snapshots in
+// the app data directory so a crash loses at most one interval of edits
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+/// How often dirty project state is written
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Number of snapshots kept before the oldest is removed
+pub const MAX_SNAPSHOTS: usize = 10;
+
+/// Snapshot file contents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotFile {
+    /// Project file the state belongs to, if it has been saved
+    pub project_path: Option<String>,
+    /// Milliseconds since epoch
+    pub saved_at: u64,
+    pub project: Value,
+}
+
+/// Snapshot listing entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSnapshot {
+    pub id: String,
+    pub project_path: Option<String>,
+    /// Milliseconds since epoch
+    pub saved_at: u64,
+    pub size: u64,
+}
+
+/// Latest unsaved project state
+#[derive(Debug, Default)]
+pub struct AutosaveManager {
+    pending: Option<(Value, Option<String>)>,
+    /// Most recent state pushed by the frontend, kept after it is snapshotted
+    latest: Option<(Value, Option<String>)>,
+}
+
+pub type AutosaveState = Arc<Mutex<AutosaveManager>>;
+
+impl AutosaveManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Most recent project state and the file it was saved to, if any
+    pub fn latest(&self) -> Option<&(Value, Option<String>)> {
+        self.latest.as_ref()
+    }
+}
+
+/// Directory holding autosave snapshots
+fn snapshot_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?
+        .join("autosave");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create autosave directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Snapshot files in `dir`, newest first
+fn snapshot_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|n| n.to_str())
+                        .map(|n| n.starts_with("snapshot_") && n.ends_with(".json"))
+                        .unwrap_or(false)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // Names embed a zero-padded timestamp so they sort chronologically
+    files.sort();
+    files.reverse();
+    files
+}
+
+/// Write a snapshot and trim the ring to `MAX_SNAPSHOTS`
+pub fn write_snapshot(
+    dir: &Path,
+    project: Value,
+    project_path: Option<String>,
+) -> Result<PathBuf, String> {
+    let saved_at = chrono::Utc::now().timestamp_millis() as u64;
+    let snapshot = SnapshotFile {
+        project_path,
+        saved_at,
+        project,
+    };
+
+    let content = serde_json::to_string(&snapshot)
+        .map_err(|e| format!("Failed to serialize snapshot: {}", e))?;
+    let path = dir.join(format!("snapshot_{:015}.json", saved_at));
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, content).map_err(|e| format!("Failed to write snapshot: {}", e))?;
+    fs::rename(&temp_path, &path).map_err(|e| format!("Failed to write snapshot: {}", e))?;
+
+    for old in snapshot_files(dir).into_iter().skip(MAX_SNAPSHOTS) {
+        let _ = fs::remove_file(old);
+    }
+
+    Ok(path)
+}
+
+/// Start the background task that writes pending state every `AUTOSAVE_INTERVAL`
+pub fn start_autosave(app: AppHandle, state: AutosaveState) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(AUTOSAVE_INTERVAL);
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            let pending = match state.lock() {
+                Ok(mut manager) => manager.pending.take(),
+                Err(_) => continue,
+            };
+
+            if let Some((project, project_path)) = pending {
+                let result =
+                    snapshot_dir(&app).and_then(|dir| write_snapshot(&dir, project, project_path));
+                match result {
+                    Ok(path) => println!("[Autosave] Snapshot written to {}", path.display()),
+                    Err(e) => println!("[Autosave] {}", e),
+                }
+            }
+        }
+    });
+}
+
+/// Record the latest project state for the next autosave
+#[tauri::command]
+pub async fn update_autosave(
+    project_json: Value,
+    project_path: Option<String>,
+    state: State<'_, AutosaveState>,
+) -> Result<(), String> {
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.latest = Some((project_json.clone(), project_path.clone()));
+    manager.pending = Some((project_json, project_path));
+    Ok(())
+}
+
+/// List autosave snapshots, newest first
+#[tauri::command]
+pub async fn list_project_snapshots(app: AppHandle) -> Result<Vec<ProjectSnapshot>, String> {
+    let dir = snapshot_dir(&app)?;
+
+    Ok(snapshot_files(&dir)
+        .into_iter()
+        .filter_map(|path| {
+            let content = fs::read_to_string(&path).ok()?;
+            let snapshot: SnapshotFile = serde_json::from_str(&content).ok()?;
+            Some(ProjectSnapshot {
+                id: path.file_stem()?.to_str()?.to_string(),
+                project_path: snapshot.project_path,
+                saved_at: snapshot.saved_at,
+                size: content.len() as u64,
+            })
+        })
+        .collect())
+}
+
+/// Return the project state stored in a snapshot
+#[tauri::command]
+pub async fn restore_project_snapshot(app: AppHandle, id: String) -> Result<Value, String> {
+    // Ids are file stems; reject anything that could escape the directory
+    if id.contains('/') || id.contains('\\') || id.contains("..") {
+        return Err("Invalid snapshot id".to_string());
+    }
+
+    let path = snapshot_dir(&app)?.join(format!("{}.json", id));
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read snapshot: {}", e))?;
+    let snapshot: SnapshotFile =
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse snapshot: {}", e))?;
+
+    println!("[Autosave] Restored snapshot {}", id);
+    Ok(snapshot.project)
+}
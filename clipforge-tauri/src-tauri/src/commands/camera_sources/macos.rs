@@ -1,6 +1,7 @@
 // macOS camera device enumeration using AVFoundation
 
-use super::{CameraDevice, CameraEnumerator};
+use super::{CameraDevice, CameraEnumerator, CameraFormat};
+use block::ConcreteBlock;
 use cocoa::base::{id, nil};
 use cocoa::foundation::NSString;
 use objc::{class, msg_send, sel, sel_impl};
@@ -12,6 +13,44 @@ impl CameraEnumerator for PlatformEnumerator {
     fn enumerate_cameras() -> Result<Vec<CameraDevice>, String> {
         unsafe { enumerate_camera_devices() }
     }
+
+    fn watch_device_changes(on_change: Box<dyn Fn() + Send + Sync>) -> Result<(), String> {
+        unsafe { observe_device_notifications(on_change) }
+    }
+}
+
+/// Register for AVCaptureDevice connect/disconnect notifications
+///
+/// The observers live for the rest of the process, so their tokens are never removed
+unsafe fn observe_device_notifications(
+    on_change: Box<dyn Fn() + Send + Sync>,
+) -> Result<(), String> {
+    let center: id = msg_send![class!(NSNotificationCenter), defaultCenter];
+    if center == nil {
+        return Err("Notification center unavailable".to_string());
+    }
+
+    let on_change: std::sync::Arc<dyn Fn() + Send + Sync> = on_change.into();
+    for name in [
+        AVCaptureDeviceWasConnectedNotification,
+        AVCaptureDeviceWasDisconnectedNotification,
+    ] {
+        let callback = on_change.clone();
+        let block = ConcreteBlock::new(move |_notification: id| callback());
+        let block = block.copy();
+
+        let _: id = msg_send![
+            center,
+            addObserverForName: name
+            object: nil
+            queue: nil
+            usingBlock: &*block
+        ];
+        // The notification center keeps its own copy of the block
+        std::mem::forget(block);
+    }
+
+    Ok(())
 }
 
 /// Enumerate camera devices using AVFoundation
@@ -80,6 +119,7 @@ unsafe fn enumerate_camera_devices() -> Result<Vec<CameraDevice>, String> {
         let format_count: usize = msg_send![formats, count];
 
         let mut resolutions = Vec::new();
+        let mut capture_formats: Vec<CameraFormat> = Vec::new();
         for j in 0..format_count {
             let format: id = msg_send![formats, objectAtIndex: j];
             let description: id = msg_send![format, formatDescription];
@@ -93,6 +133,25 @@ unsafe fn enumerate_camera_devices() -> Result<Vec<CameraDevice>, String> {
             if !resolutions.contains(&(width, height)) {
                 resolutions.push((width, height));
             }
+
+            // Record the frame rate ranges of each format
+            let ranges: id = msg_send![format, videoSupportedFrameRateRanges];
+            let range_count: usize = msg_send![ranges, count];
+            for k in 0..range_count {
+                let range: id = msg_send![ranges, objectAtIndex: k];
+                let min_frame_rate: f64 = msg_send![range, minFrameRate];
+                let max_frame_rate: f64 = msg_send![range, maxFrameRate];
+
+                let capture_format = CameraFormat {
+                    width,
+                    height,
+                    min_frame_rate,
+                    max_frame_rate,
+                };
+                if !capture_formats.contains(&capture_format) {
+                    capture_formats.push(capture_format);
+                }
+            }
         }
 
         // Sort resolutions by total pixels (largest first)
@@ -102,6 +161,7 @@ unsafe fn enumerate_camera_devices() -> Result<Vec<CameraDevice>, String> {
         let camera = CameraDevice::new(device_id_string, device_name_string)
             .with_default(is_default)
             .with_resolutions(resolutions)
+            .with_formats(capture_formats)
             .with_audio(false); // Cameras don't directly provide audio
 
         cameras.push(camera);
@@ -117,6 +177,12 @@ struct CMVideoDimensions {
     height: i32,
 }
 
+#[link(name = "AVFoundation", kind = "framework")]
+extern "C" {
+    static AVCaptureDeviceWasConnectedNotification: id;
+    static AVCaptureDeviceWasDisconnectedNotification: id;
+}
+
 #[link(name = "CoreMedia", kind = "framework")]
 extern "C" {
     fn CMVideoFormatDescriptionGetDimensions(videoDesc: id) -> CMVideoDimensions;
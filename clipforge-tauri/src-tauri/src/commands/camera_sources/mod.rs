@@ -13,6 +13,32 @@ pub use macos::*;
 pub use stub::*;
 
 use serde::{Deserialize, Serialize};
+use std::sync::mpsc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// Delay used to coalesce bursts of device notifications into one update
+const DEVICE_CHANGE_SETTLE: Duration = Duration::from_millis(500);
+
+/// Capture format supported by a camera
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraFormat {
+    pub width: u32,
+    pub height: u32,
+    pub min_frame_rate: f64,
+    pub max_frame_rate: f64,
+}
+
+/// Camera resolution and frame rate requested for a recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CameraCaptureSettings {
+    /// Camera to use; the default camera when omitted
+    #[serde(default)]
+    pub device_id: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub frame_rate: u32,
+}
 
 /// Camera device for recording
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +53,9 @@ pub struct CameraDevice {
     pub resolutions: Vec<(u32, u32)>,
     /// Whether this device supports audio
     pub has_audio: bool,
+    /// Supported formats with their frame rate ranges
+    #[serde(default)]
+    pub formats: Vec<CameraFormat>,
 }
 
 impl CameraDevice {
@@ -38,6 +67,7 @@ impl CameraDevice {
             is_default: false,
             resolutions: vec![(1920, 1080), (1280, 720), (640, 480)],
             has_audio: false,
+            formats: Vec::new(),
         }
     }
 
@@ -58,6 +88,48 @@ impl CameraDevice {
         self.has_audio = has_audio;
         self
     }
+
+    /// Builder-style method to set supported formats
+    pub fn with_formats(mut self, formats: Vec<CameraFormat>) -> Self {
+        self.formats = formats;
+        self
+    }
+
+    /// Check requested settings against the device's supported formats
+    ///
+    /// Devices that didn't report formats are accepted as-is
+    pub fn validate_settings(&self, settings: &CameraCaptureSettings) -> Result<(), String> {
+        if self.formats.is_empty() {
+            return Ok(());
+        }
+
+        let fps = settings.frame_rate as f64;
+        let supported = self.formats.iter().any(|format| {
+            format.width == settings.width
+                && format.height == settings.height
+                && fps >= format.min_frame_rate - 0.01
+                && fps <= format.max_frame_rate + 0.01
+        });
+
+        if supported {
+            return Ok(());
+        }
+
+        let mut options: Vec<String> = self
+            .formats
+            .iter()
+            .map(|f| format!("{}x{} @ {:.0}fps", f.width, f.height, f.max_frame_rate))
+            .collect();
+        options.dedup();
+        Err(format!(
+            "{} doesn't support {}x{} at {}fps. Supported: {}",
+            self.name,
+            settings.width,
+            settings.height,
+            settings.frame_rate,
+            options.join(", ")
+        ))
+    }
 }
 
 /// Trait for platform-specific camera device enumeration
@@ -70,6 +142,112 @@ pub trait CameraEnumerator {
         let cameras = Self::enumerate_cameras()?;
         Ok(cameras.into_iter().find(|c| c.is_default))
     }
+
+    /// Call `on_change` whenever a camera is connected or disconnected
+    fn watch_device_changes(on_change: Box<dyn Fn() + Send + Sync>) -> Result<(), String>;
+}
+
+/// Payload of the `devices:cameras-changed` event
+#[derive(Debug, Clone, Serialize)]
+pub struct CamerasChanged {
+    /// Cameras currently available
+    pub cameras: Vec<CameraDevice>,
+    /// Cameras connected since the previous update
+    pub added: Vec<CameraDevice>,
+    /// Cameras disconnected since the previous update
+    pub removed: Vec<CameraDevice>,
+}
+
+/// Compare two device lists by id, returning the (added, removed) cameras
+pub fn diff_cameras(
+    previous: &[CameraDevice],
+    current: &[CameraDevice],
+) -> (Vec<CameraDevice>, Vec<CameraDevice>) {
+    let added = current
+        .iter()
+        .filter(|c| !previous.iter().any(|p| p.id == c.id))
+        .cloned()
+        .collect();
+    let removed = previous
+        .iter()
+        .filter(|p| !current.iter().any(|c| c.id == p.id))
+        .cloned()
+        .collect();
+    (added, removed)
+}
+
+/// Watch for camera hot-plugs and emit `devices:cameras-changed`
+///
+/// If the camera of the active recording is removed, the recording falls back to another camera
+pub fn start_camera_watcher(app: AppHandle) {
+    let (tx, rx) = mpsc::channel::<()>();
+
+    if let Err(e) = PlatformEnumerator::watch_device_changes(Box::new(move || {
+        let _ = tx.send(());
+    })) {
+        println!("[Cameras] Failed to watch for device changes: {}", e);
+        return;
+    }
+
+    std::thread::spawn(move || {
+        let mut known = PlatformEnumerator::enumerate_cameras().unwrap_or_default();
+
+        while rx.recv().is_ok() {
+            // Connecting a device often posts several notifications at once
+            std::thread::sleep(DEVICE_CHANGE_SETTLE);
+            while rx.try_recv().is_ok() {}
+
+            let cameras = match PlatformEnumerator::enumerate_cameras() {
+                Ok(cameras) => cameras,
+                Err(e) => {
+                    println!("[Cameras] Failed to enumerate cameras: {}", e);
+                    continue;
+                }
+            };
+
+            let (added, removed) = diff_cameras(&known, &cameras);
+            known = cameras.clone();
+            if added.is_empty() && removed.is_empty() {
+                continue;
+            }
+
+            println!(
+                "[Cameras] {} connected, {} disconnected",
+                added.len(),
+                removed.len()
+            );
+
+            if !removed.is_empty() {
+                super::recording::handle_cameras_removed(&app, &cameras, &removed);
+            }
+
+            let _ = app.emit(
+                "devices:cameras-changed",
+                CamerasChanged {
+                    cameras,
+                    added,
+                    removed,
+                },
+            );
+        }
+    });
+}
+
+/// Find the camera requested by `settings` and check it supports them
+pub fn resolve_camera(settings: &CameraCaptureSettings) -> Result<CameraDevice, String> {
+    let cameras = PlatformEnumerator::enumerate_cameras()?;
+
+    let camera = match &settings.device_id {
+        Some(id) => cameras.into_iter().find(|c| &c.id == id),
+        None => {
+            let default = cameras.iter().position(|c| c.is_default).unwrap_or(0);
+            cameras.into_iter().nth(default)
+        }
+    }
+    .ok_or_else(|| "Requested camera is not available".to_string())?;
+
+    camera.validate_settings(settings)?;
+    Ok(camera)
 }
 
 // ============================================================================
@@ -87,3 +265,93 @@ pub async fn enumerate_cameras() -> Result<Vec<CameraDevice>, String> {
 pub async fn get_default_camera() -> Result<Option<CameraDevice>, String> {
     PlatformEnumerator::get_default_camera()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_settings() {
+        let camera = CameraDevice::new("cam".to_string(), "FaceTime HD Camera".to_string())
+            .with_formats(vec![
+                CameraFormat {
+                    width: 1280,
+                    height: 720,
+                    min_frame_rate: 1.0,
+                    max_frame_rate: 30.0,
+                },
+                CameraFormat {
+                    width: 640,
+                    height: 480,
+                    min_frame_rate: 1.0,
+                    max_frame_rate: 60.0,
+                },
+            ]);
+
+        assert!(camera
+            .validate_settings(&CameraCaptureSettings {
+                device_id: None,
+                width: 1280,
+                height: 720,
+                frame_rate: 30
+            })
+            .is_ok());
+        assert!(camera
+            .validate_settings(&CameraCaptureSettings {
+                device_id: None,
+                width: 640,
+                height: 480,
+                frame_rate: 60
+            })
+            .is_ok());
+        assert!(camera
+            .validate_settings(&CameraCaptureSettings {
+                device_id: None,
+                width: 1280,
+                height: 720,
+                frame_rate: 60
+            })
+            .is_err());
+        assert!(camera
+            .validate_settings(&CameraCaptureSettings {
+                device_id: None,
+                width: 1920,
+                height: 1080,
+                frame_rate: 30
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn test_diff_cameras() {
+        let builtin = CameraDevice::new("builtin".to_string(), "FaceTime HD Camera".to_string());
+        let usb = CameraDevice::new("usb".to_string(), "USB Camera".to_string());
+        let phone = CameraDevice::new("phone".to_string(), "iPhone Camera".to_string());
+
+        let (added, removed) =
+            diff_cameras(&[builtin.clone(), usb.clone()], &[builtin.clone(), phone]);
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].id, "phone");
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].id, "usb");
+
+        let (added, removed) = diff_cameras(
+            std::slice::from_ref(&builtin),
+            std::slice::from_ref(&builtin),
+        );
+        assert!(added.is_empty() && removed.is_empty());
+    }
+
+    #[test]
+    fn test_validate_settings_without_formats() {
+        let camera = CameraDevice::new("cam".to_string(), "Camera".to_string());
+        assert!(camera
+            .validate_settings(&CameraCaptureSettings {
+                device_id: None,
+                width: 1920,
+                height: 1080,
+                frame_rate: 30
+            })
+            .is_ok());
+    }
+}
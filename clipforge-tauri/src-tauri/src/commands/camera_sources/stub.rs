@@ -11,4 +11,9 @@ impl CameraEnumerator for PlatformEnumerator {
         // TODO: Implement for Windows and Linux
         Ok(Vec::new())
     }
+
+    fn watch_device_changes(_on_change: Box<dyn Fn() + Send + Sync>) -> Result<(), String> {
+        // No cameras are enumerated here, so there are no changes to report
+        Ok(())
+    }
 }
@@ -0,0 +1,143 @@
+// Per-clip color correction
+//
+// Brightness, contrast and saturation through the eq filter, optionally followed
+// by a 3D LUT (.cube or .3dl) through lut3d. Useful for dim or flat webcam footage.
+
+use super::ffmpeg_utils::quote_filter_value;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Color adjustments for one clip
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ColorCorrection {
+    /// Added to the luma (-1.0 - 1.0, 0.0 leaves it unchanged)
+    pub brightness: f64,
+    /// Contrast multiplier (0.0 - 2.0, 1.0 leaves it unchanged)
+    pub contrast: f64,
+    /// Saturation multiplier (0.0 - 3.0, 0.0 is grayscale)
+    pub saturation: f64,
+    /// Path to a .cube or .3dl LUT applied after the adjustments
+    pub lut_path: Option<String>,
+}
+
+impl Default for ColorCorrection {
+    fn default() -> Self {
+        Self {
+            brightness: 0.0,
+            contrast: 1.0,
+            saturation: 1.0,
+            lut_path: None,
+        }
+    }
+}
+
+impl ColorCorrection {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(-1.0..=1.0).contains(&self.brightness) {
+            return Err("Brightness must be between -1.0 and 1.0".to_string());
+        }
+        if !(0.0..=2.0).contains(&self.contrast) {
+            return Err("Contrast must be between 0.0 and 2.0".to_string());
+        }
+        if !(0.0..=3.0).contains(&self.saturation) {
+            return Err("Saturation must be between 0.0 and 3.0".to_string());
+        }
+
+        if let Some(lut_path) = &self.lut_path {
+            let path = Path::new(lut_path);
+            let extension = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(|e| e.to_lowercase());
+            if !matches!(extension.as_deref(), Some("cube") | Some("3dl")) {
+                return Err(format!("LUT must be a .cube or .3dl file: {}", lut_path));
+            }
+            if !path.exists() {
+                return Err(format!("LUT file not found: {}", lut_path));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// FFmpeg video filter chain, or None when nothing would change
+    pub fn filter_chain(&self) -> Option<String> {
+        let mut filters = Vec::new();
+
+        if self.brightness != 0.0 || self.contrast != 1.0 || self.saturation != 1.0 {
+            filters.push(format!(
+                "eq=brightness={}:contrast={}:saturation={}",
+                self.brightness, self.contrast, self.saturation
+            ));
+        }
+
+        if let Some(lut_path) = &self.lut_path {
+            filters.push(format!(
+                "lut3d=file={}:interp=tetrahedral",
+                quote_filter_value(lut_path)
+            ));
+        }
+
+        if filters.is_empty() {
+            None
+        } else {
+            Some(filters.join(","))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_has_no_chain() {
+        assert_eq!(ColorCorrection::default().filter_chain(), None);
+    }
+
+    #[test]
+    fn test_chain() {
+        let correction = ColorCorrection {
+            brightness: 0.05,
+            saturation: 1.2,
+            lut_path: Some("/luts/warm.cube".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            correction.filter_chain().unwrap(),
+            "eq=brightness=0.05:contrast=1:saturation=1.2,lut3d=file='/luts/warm.cube':interp=tetrahedral"
+        );
+    }
+
+    #[test]
+    fn test_chain_quotes_lut_path() {
+        let correction = ColorCorrection {
+            lut_path: Some("/luts/director's cut.cube".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            correction.filter_chain().unwrap(),
+            r"lut3d=file='/luts/director'\''s cut.cube':interp=tetrahedral"
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(ColorCorrection::default().validate().is_ok());
+        assert!(ColorCorrection {
+            contrast: 3.0,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+        assert!(ColorCorrection {
+            lut_path: Some("/luts/warm.png".to_string()),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
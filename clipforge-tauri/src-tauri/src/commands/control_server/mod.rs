@@ -0,0 +1,121 @@
+// Local control API
+//
+// An optional server on 127.0.0.1 that lets stream decks, Raycast extensions and
+// scripts read the recording state, start and stop recordings, and run exports
+// while ClipForge is open. It is only compiled with the `control-server` feature.
+//
+// Clients connect to `ws://127.0.0.1:<port>/ws` with the access token either as a
+// `?token=` query parameter or an `Authorization: Bearer` header, then exchange
+// JSON messages:
+//
+//   -> {"id": 1, "method": "startRecording", "params": {"sourceId": "display_1"}}
+//   <- {"id": 1, "result": {...recording state...}}
+//   <- {"event": "recording:paused", "payload": {...}}
+//
+// Methods: getState, startRecording, stopRecording, pauseRecording,
+// resumeRecording, addMarker, exportTimeline. Recording and export events are
+// pushed to every connected client.
+//
+// Stream Deck plugins use the plain HTTP endpoints under `/streamdeck` instead
+// (see streamdeck.rs), authenticated with the same token.
+
+#[cfg(feature = "control-server")]
+mod server;
+#[cfg(feature = "control-server")]
+mod streamdeck;
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+/// Port used when none is given
+pub const DEFAULT_CONTROL_PORT: u16 = 17890;
+
+/// Where a running control server can be reached
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlServerInfo {
+    pub port: u16,
+    /// WebSocket URL, without the token
+    pub url: String,
+    /// Access token clients must present
+    pub token: String,
+}
+
+/// The running control server, if any
+#[derive(Default)]
+pub struct ControlServer {
+    #[cfg(feature = "control-server")]
+    running: Option<server::RunningServer>,
+}
+
+impl ControlServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop the server and disconnect its clients
+    pub fn stop(&mut self) {
+        #[cfg(feature = "control-server")]
+        {
+            self.running = None;
+        }
+    }
+
+    fn info(&self) -> Option<ControlServerInfo> {
+        #[cfg(feature = "control-server")]
+        {
+            self.running.as_ref().map(|running| running.info.clone())
+        }
+        #[cfg(not(feature = "control-server"))]
+        {
+            None
+        }
+    }
+}
+
+/// Thread-safe control server type
+pub type ControlServerState = Arc<Mutex<ControlServer>>;
+
+/// Start the control server on 127.0.0.1, replacing any running instance
+///
+/// The access token is kept in the keychain, so clients stay paired across restarts
+#[tauri::command]
+pub async fn start_control_server(
+    port: Option<u16>,
+    app: AppHandle,
+    state: State<'_, ControlServerState>,
+) -> Result<ControlServerInfo, String> {
+    let port = port.unwrap_or(DEFAULT_CONTROL_PORT);
+
+    #[cfg(feature = "control-server")]
+    {
+        let mut control = state.lock().map_err(|e| e.to_string())?;
+        control.stop();
+
+        let running = server::RunningServer::start(app, port, server::token()?)?;
+        let info = running.info.clone();
+        control.running = Some(running);
+        Ok(info)
+    }
+    #[cfg(not(feature = "control-server"))]
+    {
+        let _ = (port, app, state);
+        Err("This build of ClipForge does not include the control server".to_string())
+    }
+}
+
+/// Stop the control server
+#[tauri::command]
+pub async fn stop_control_server(state: State<'_, ControlServerState>) -> Result<(), String> {
+    state.lock().map_err(|e| e.to_string())?.stop();
+    Ok(())
+}
+
+/// Get the running control server's address and token, if it is running
+#[tauri::command]
+pub async fn get_control_server_info(
+    state: State<'_, ControlServerState>,
+) -> Result<Option<ControlServerInfo>, String> {
+    Ok(state.lock().map_err(|e| e.to_string())?.info())
+}
@@ -0,0 +1,372 @@
+// WebSocket server for the local control API
+//
+// Each connection runs a single loop that reads requests, writes responses and
+// forwards app events. Requests run as their own tasks so a long export does not
+// block the connection.
+
+use super::{streamdeck, ControlServerInfo};
+use crate::commands::export::{export_timeline, ClipData, ExportSettings};
+use crate::commands::recording::{
+    add_marker, get_recording_state, pause_recording, resume_recording, start_recording,
+    stop_recording, AudioAppFilter, QualityPreset, RecordingConfig, RecordingManagerState,
+    RecordingType,
+};
+use crate::commands::uploads::keychain;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use tauri::{AppHandle, EventId, Listener, Manager};
+use tokio::sync::{broadcast, mpsc, watch};
+
+/// Keychain account holding the access token
+const TOKEN_ACCOUNT: &str = "control-server";
+
+/// App events pushed to connected clients
+const FORWARDED_EVENTS: &[&str] = &[
+    "recording:started",
+    "recording:stopped",
+    "recording:paused",
+    "recording:resumed",
+    "recording:marker-added",
+    "recording:duration-update",
+    "export-progress",
+];
+
+/// Events buffered per client before slow clients start missing them
+const EVENT_BUFFER: usize = 64;
+
+/// Load the access token, creating one on first use
+pub fn token() -> Result<String, String> {
+    if let Some(token) = keychain::load::<String>(TOKEN_ACCOUNT)? {
+        return Ok(token);
+    }
+
+    let token = hex::encode(rand::random::<[u8; 32]>());
+    keychain::store(TOKEN_ACCOUNT, &token)?;
+    Ok(token)
+}
+
+/// Compare tokens without leaking how many leading bytes matched
+fn tokens_match(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Token from an `Authorization: Bearer` header or a `token` query parameter
+fn request_token<'a>(
+    headers: &'a HeaderMap,
+    query: &'a HashMap<String, String>,
+) -> Option<&'a str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or_else(|| query.get("token").map(String::as_str))
+}
+
+pub(super) struct Shared {
+    pub(super) app: AppHandle,
+    token: String,
+    events: broadcast::Sender<String>,
+    shutdown: watch::Receiver<bool>,
+}
+
+impl Shared {
+    /// Whether a request carries the access token
+    pub(super) fn authorized(&self, headers: &HeaderMap, query: &HashMap<String, String>) -> bool {
+        request_token(headers, query).is_some_and(|given| tokens_match(&self.token, given))
+    }
+}
+
+/// A control server listening on 127.0.0.1, stopped when dropped
+pub struct RunningServer {
+    pub info: ControlServerInfo,
+    app: AppHandle,
+    listeners: Vec<EventId>,
+    shutdown: watch::Sender<bool>,
+}
+
+impl RunningServer {
+    pub fn start(app: AppHandle, port: u16, token: String) -> Result<Self, String> {
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let listener = std::net::TcpListener::bind(addr)
+            .map_err(|e| format!("Failed to listen on {}: {}", addr, e))?;
+        listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+        let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        let listeners = FORWARDED_EVENTS
+            .iter()
+            .map(|&name| {
+                let events = events.clone();
+                app.listen_any(name, move |event| {
+                    let payload: Value =
+                        serde_json::from_str(event.payload()).unwrap_or(Value::Null);
+                    let _ = events.send(json!({ "event": name, "payload": payload }).to_string());
+                })
+            })
+            .collect();
+
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let shared = Arc::new(Shared {
+            app: app.clone(),
+            token: token.clone(),
+            events,
+            shutdown: shutdown_rx.clone(),
+        });
+        let router = Router::new()
+            .route("/ws", get(upgrade))
+            .merge(streamdeck::routes())
+            .with_state(shared);
+
+        tauri::async_runtime::spawn(async move {
+            let listener = match tokio::net::TcpListener::from_std(listener) {
+                Ok(listener) => listener,
+                Err(e) => {
+                    println!("[ControlServer] {}", e);
+                    return;
+                }
+            };
+            let mut shutdown_rx = shutdown_rx;
+            let result = axum::serve(listener, router)
+                .with_graceful_shutdown(async move {
+                    let _ = shutdown_rx.wait_for(|stopped| *stopped).await;
+                })
+                .await;
+            if let Err(e) = result {
+                println!("[ControlServer] {}", e);
+            }
+        });
+
+        println!("[ControlServer] Listening on 127.0.0.1:{}", port);
+        Ok(Self {
+            info: ControlServerInfo {
+                port,
+                url: format!("ws://127.0.0.1:{}/ws", port),
+                token,
+            },
+            app,
+            listeners,
+            shutdown,
+        })
+    }
+}
+
+impl Drop for RunningServer {
+    fn drop(&mut self) {
+        let _ = self.shutdown.send(true);
+        for id in self.listeners.drain(..) {
+            self.app.unlisten(id);
+        }
+        println!("[ControlServer] Stopped");
+    }
+}
+
+async fn upgrade(
+    ws: WebSocketUpgrade,
+    State(shared): State<Arc<Shared>>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    if !shared.authorized(&headers, &query) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    ws.on_upgrade(move |socket| serve_client(socket, shared))
+}
+
+/// A client request: `{"id": ..., "method": "...", "params": {...}}`
+#[derive(Debug, Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Start a request in the background, or return an error response if it is malformed
+fn handle_request(
+    app: &AppHandle,
+    text: &str,
+    responses: &mpsc::UnboundedSender<String>,
+) -> Option<String> {
+    let request: Request = match serde_json::from_str(text) {
+        Ok(request) => request,
+        Err(e) => {
+            return Some(
+                json!({ "id": null, "error": format!("Invalid request: {}", e) }).to_string(),
+            )
+        }
+    };
+
+    let app = app.clone();
+    let responses = responses.clone();
+    tauri::async_runtime::spawn(async move {
+        let response = match dispatch(&app, &request.method, request.params).await {
+            Ok(result) => json!({ "id": request.id, "result": result }),
+            Err(error) => json!({ "id": request.id, "error": error }),
+        };
+        let _ = responses.send(response.to_string());
+    });
+    None
+}
+
+async fn serve_client(mut socket: WebSocket, shared: Arc<Shared>) {
+    let mut events = shared.events.subscribe();
+    let mut shutdown = shared.shutdown.clone();
+    let (responses_tx, mut responses) = mpsc::unbounded_channel::<String>();
+
+    loop {
+        let outgoing = tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    match handle_request(&shared.app, text.as_str(), &responses_tx) {
+                        Some(error) => error,
+                        None => continue,
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+            Some(response) = responses.recv() => response,
+            event = events.recv() => match event {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = async { let _ = shutdown.wait_for(|stopped| *stopped).await; } => break,
+        };
+
+        if socket.send(Message::Text(outgoing.into())).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StartRecordingParams {
+    source_id: String,
+    #[serde(default)]
+    recording_type: Option<RecordingType>,
+    #[serde(default)]
+    preset: Option<QualityPreset>,
+    #[serde(default)]
+    config: Option<RecordingConfig>,
+    #[serde(default)]
+    include_audio: Option<bool>,
+    #[serde(default)]
+    audio_app_filter: Option<AudioAppFilter>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MarkerParams {
+    #[serde(default)]
+    label: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportParams {
+    clips: Vec<ClipData>,
+    output_path: String,
+    #[serde(default)]
+    settings: Option<ExportSettings>,
+}
+
+fn params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, String> {
+    serde_json::from_value(params).map_err(|e| format!("Invalid params: {}", e))
+}
+
+fn to_result<T: serde::Serialize>(value: T) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|e| e.to_string())
+}
+
+/// Run a request against the same commands the frontend uses
+async fn dispatch(app: &AppHandle, method: &str, params_value: Value) -> Result<Value, String> {
+    let state = app.state::<RecordingManagerState>();
+
+    match method {
+        "getState" => to_result(get_recording_state(state).await?),
+        "startRecording" => {
+            let params: StartRecordingParams = params(params_value)?;
+            let config = params
+                .config
+                .or_else(|| params.preset.map(|preset| preset.to_config()));
+            to_result(
+                start_recording(
+                    params.recording_type.unwrap_or(RecordingType::Screen),
+                    params.source_id,
+                    config,
+                    params.include_audio.unwrap_or(true),
+                    params.audio_app_filter,
+                    state,
+                    app.clone(),
+                )
+                .await?,
+            )
+        }
+        "stopRecording" => to_result(stop_recording(state, app.clone()).await?),
+        "pauseRecording" => to_result(pause_recording(state, app.clone()).await?),
+        "resumeRecording" => to_result(resume_recording(state, app.clone()).await?),
+        "addMarker" => {
+            let params: MarkerParams = if params_value.is_null() {
+                MarkerParams::default()
+            } else {
+                params(params_value)?
+            };
+            to_result(add_marker(params.label, state, app.clone()).await?)
+        }
+        "exportTimeline" => {
+            let params: ExportParams = params(params_value)?;
+            export_timeline(
+                app.clone(),
+                params.clips,
+                params.output_path,
+                params.settings,
+            )
+            .await?;
+            Ok(Value::Null)
+        }
+        other => Err(format!("Unknown method '{}'", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("abc123", "abc123"));
+        assert!(!tokens_match("abc123", "abc124"));
+        assert!(!tokens_match("abc123", "abc12"));
+        assert!(!tokens_match("abc123", ""));
+    }
+
+    #[test]
+    fn test_request_token() {
+        let mut headers = HeaderMap::new();
+        let mut query = HashMap::new();
+        assert_eq!(request_token(&headers, &query), None);
+
+        query.insert("token".to_string(), "from-query".to_string());
+        assert_eq!(request_token(&headers, &query), Some("from-query"));
+
+        headers.insert(header::AUTHORIZATION, "Bearer from-header".parse().unwrap());
+        assert_eq!(request_token(&headers, &query), Some("from-header"));
+    }
+}
@@ -0,0 +1,250 @@
+// HTTP endpoints for Elgato Stream Deck plugins
+//
+// Stream Deck actions are one-shot HTTP calls from the plugin's JavaScript, and
+// buttons redraw from a polled state, so every endpoint answers with the same
+// small state document:
+//
+//   GET  /streamdeck/state
+//   POST /streamdeck/start?source=display_1&preset=high&type=screen&audio=true
+//   POST /streamdeck/stop
+//   POST /streamdeck/pause     (pauses, or resumes when already paused)
+//   POST /streamdeck/marker?label=Intro
+//
+// Requests carry the control server token as `?token=` or a Bearer header.
+// Failed actions return 409 with `{"error": "...", "code": "..."}`, where `code`
+// is the same stable error code the frontend receives.
+
+use super::server::Shared;
+use crate::commands::recording::{
+    add_marker, get_recording_state, pause_recording, resume_recording, start_recording,
+    stop_recording, QualityPreset, RecordingManagerState, RecordingState, RecordingStatus,
+    RecordingType,
+};
+use crate::error::ClipForgeError;
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+/// Recording state shaped for drawing a button
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ButtonState {
+    /// "idle", "recording" or "paused"
+    state: &'static str,
+    recording_id: Option<String>,
+    /// Duration in seconds
+    duration: f64,
+    /// Duration as "m:ss" or "h:mm:ss" for the button title
+    title: String,
+    marker_count: usize,
+}
+
+impl ButtonState {
+    fn from_recording(recording: Option<&RecordingState>) -> Self {
+        let recording = recording.filter(|recording| {
+            matches!(
+                recording.status,
+                RecordingStatus::Recording | RecordingStatus::Paused
+            )
+        });
+
+        match recording {
+            Some(recording) => {
+                let duration = recording.calculate_duration();
+                Self {
+                    state: if recording.status == RecordingStatus::Paused {
+                        "paused"
+                    } else {
+                        "recording"
+                    },
+                    recording_id: Some(recording.id.clone()),
+                    duration,
+                    title: format_title(duration),
+                    marker_count: recording.markers.len(),
+                }
+            }
+            None => Self {
+                state: "idle",
+                recording_id: None,
+                duration: 0.0,
+                title: String::new(),
+                marker_count: 0,
+            },
+        }
+    }
+}
+
+/// Format a duration for a button title
+fn format_title(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (hours, minutes, seconds) = (total / 3600, (total % 3600) / 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StartQuery {
+    source: String,
+    #[serde(default, rename = "type")]
+    recording_type: Option<RecordingType>,
+    #[serde(default)]
+    preset: Option<QualityPreset>,
+    #[serde(default)]
+    audio: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MarkerQuery {
+    #[serde(default)]
+    label: Option<String>,
+}
+
+pub(super) fn routes() -> Router<Arc<Shared>> {
+    Router::new()
+        .route("/streamdeck/state", get(state))
+        .route("/streamdeck/start", post(start))
+        .route("/streamdeck/stop", post(stop))
+        .route("/streamdeck/pause", post(toggle_pause))
+        .route("/streamdeck/marker", post(marker))
+}
+
+async fn current_state(app: &AppHandle) -> Result<ButtonState, ClipForgeError> {
+    let recording = get_recording_state(app.state::<RecordingManagerState>()).await?;
+    Ok(ButtonState::from_recording(recording.as_ref()))
+}
+
+/// Respond with the state after an action, or the action's error
+async fn respond(app: &AppHandle, result: Result<(), ClipForgeError>) -> Response {
+    match result.and(current_state(app).await) {
+        Ok(state) => Json(state).into_response(),
+        Err(error) => (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({ "error": error.to_string(), "code": error.code() })),
+        )
+            .into_response(),
+    }
+}
+
+async fn state(
+    State(shared): State<Arc<Shared>>,
+    Query(auth): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    if !shared.authorized(&headers, &auth) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    respond(&shared.app, Ok(())).await
+}
+
+async fn start(
+    State(shared): State<Arc<Shared>>,
+    Query(auth): Query<HashMap<String, String>>,
+    Query(query): Query<StartQuery>,
+    headers: HeaderMap,
+) -> Response {
+    if !shared.authorized(&headers, &auth) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let app = &shared.app;
+    let result = start_recording(
+        query.recording_type.unwrap_or(RecordingType::Screen),
+        query.source,
+        query.preset.map(|preset| preset.to_config()),
+        query.audio.unwrap_or(true),
+        None,
+        app.state::<RecordingManagerState>(),
+        app.clone(),
+    )
+    .await
+    .map(|_| ());
+    respond(app, result).await
+}
+
+async fn stop(
+    State(shared): State<Arc<Shared>>,
+    Query(auth): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    if !shared.authorized(&headers, &auth) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let app = &shared.app;
+    let result = stop_recording(app.state::<RecordingManagerState>(), app.clone())
+        .await
+        .map(|_| ());
+    respond(app, result).await
+}
+
+async fn toggle_pause(
+    State(shared): State<Arc<Shared>>,
+    Query(auth): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    if !shared.authorized(&headers, &auth) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let app = &shared.app;
+    let state = app.state::<RecordingManagerState>();
+    let paused = match get_recording_state(state.clone()).await {
+        Ok(recording) => {
+            recording.is_some_and(|recording| recording.status == RecordingStatus::Paused)
+        }
+        Err(e) => return respond(app, Err(e)).await,
+    };
+    let result = if paused {
+        resume_recording(state, app.clone()).await.map(|_| ())
+    } else {
+        pause_recording(state, app.clone()).await.map(|_| ())
+    };
+    respond(app, result).await
+}
+
+async fn marker(
+    State(shared): State<Arc<Shared>>,
+    Query(auth): Query<HashMap<String, String>>,
+    Query(query): Query<MarkerQuery>,
+    headers: HeaderMap,
+) -> Response {
+    if !shared.authorized(&headers, &auth) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let app = &shared.app;
+    let result = add_marker(
+        query.label,
+        app.state::<RecordingManagerState>(),
+        app.clone(),
+    )
+    .await
+    .map(|_| ());
+    respond(app, result).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_title() {
+        assert_eq!(format_title(0.0), "0:00");
+        assert_eq!(format_title(65.4), "1:05");
+        assert_eq!(format_title(3_725.0), "1:02:05");
+    }
+
+    #[test]
+    fn test_idle_without_active_recording() {
+        assert_eq!(ButtonState::from_recording(None).state, "idle");
+    }
+}
@@ -0,0 +1,402 @@
+// Cursor tracking and synthetic cursor overlay
+//
+// Records the global mouse position alongside a screen recording as a JSON
+// sidecar, and turns that track into an FFmpeg overlay so exports can replace
+// the captured cursor with an enlarged, smoothed one
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Name given to the overlay filter so sendcmd can address it
+pub const CURSOR_OVERLAY_FILTER: &str = "overlay@cursor";
+
+/// A single cursor position sample
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct CursorSample {
+    /// Seconds since the recording started
+    pub t: f64,
+    /// Global X position in points
+    pub x: f64,
+    /// Global Y position in points
+    pub y: f64,
+}
+
+/// Area of the desktop covered by the recording, in global points
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct CaptureRegion {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Cursor track stored next to a recording (`<name>.cursor.json`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorTrack {
+    /// Captured desktop region the samples are relative to
+    pub region: CaptureRegion,
+    /// Samples in chronological order
+    pub samples: Vec<CursorSample>,
+}
+
+impl CursorTrack {
+    /// Sidecar path for a recording file
+    pub fn sidecar_path(recording_path: &Path) -> PathBuf {
+        recording_path.with_extension("cursor.json")
+    }
+
+    /// Load a cursor track from a sidecar file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read cursor track: {}", e))?;
+
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse cursor track: {}", e))
+    }
+
+    /// Write the cursor track to a sidecar file
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string(self)
+            .map_err(|e| format!("Failed to serialize cursor track: {}", e))?;
+
+        fs::write(path, content).map_err(|e| format!("Failed to write cursor track: {}", e))
+    }
+
+    /// Move every sample `offset` seconds later
+    pub fn delay(&mut self, offset: f64) {
+        for sample in &mut self.samples {
+            sample.t += offset;
+        }
+    }
+
+    /// Returns the samples smoothed with a centered moving average
+    ///
+    /// `window` is the number of neighbouring samples on each side; 0 disables smoothing
+    pub fn smoothed(&self, window: usize) -> Vec<CursorSample> {
+        if window == 0 {
+            return self.samples.clone();
+        }
+
+        let len = self.samples.len();
+        (0..len)
+            .map(|i| {
+                let start = i.saturating_sub(window);
+                let end = (i + window + 1).min(len);
+                let slice = &self.samples[start..end];
+                let count = slice.len() as f64;
+
+                CursorSample {
+                    t: self.samples[i].t,
+                    x: slice.iter().map(|s| s.x).sum::<f64>() / count,
+                    y: slice.iter().map(|s| s.y).sum::<f64>() / count,
+                }
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// Recording
+// ============================================================================
+
+/// Samples the cursor position on a background thread while recording
+pub struct CursorRecorder {
+    should_stop: Arc<AtomicBool>,
+    /// No samples are taken while set, and the paused time is left out of the track
+    paused: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<Vec<CursorSample>>>,
+    region: CaptureRegion,
+}
+
+impl CursorRecorder {
+    /// Start sampling the cursor at the given rate (samples per second)
+    pub fn start(region: CaptureRegion, sample_rate: u32) -> Self {
+        let should_stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = Arc::clone(&should_stop);
+        let paused = Arc::new(AtomicBool::new(false));
+        let pause_flag = Arc::clone(&paused);
+        let interval = Duration::from_micros(1_000_000 / sample_rate.max(1) as u64);
+
+        let handle = thread::spawn(move || {
+            let started = Instant::now();
+            let mut paused_since: Option<Instant> = None;
+            let mut paused_total = Duration::ZERO;
+            let mut samples = Vec::new();
+
+            while !stop_flag.load(Ordering::SeqCst) {
+                if pause_flag.load(Ordering::SeqCst) {
+                    paused_since.get_or_insert_with(Instant::now);
+                } else {
+                    if let Some(since) = paused_since.take() {
+                        paused_total += since.elapsed();
+                    }
+                    if let Some((x, y)) = current_cursor_position() {
+                        samples.push(CursorSample {
+                            t: (started.elapsed() - paused_total).as_secs_f64(),
+                            x,
+                            y,
+                        });
+                    }
+                }
+                thread::sleep(interval);
+            }
+
+            samples
+        });
+
+        Self {
+            should_stop,
+            paused,
+            handle: Some(handle),
+            region,
+        }
+    }
+
+    /// Stop or continue sampling while the recording is paused
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+    }
+
+    /// Stop sampling and write the sidecar next to the recording
+    pub fn stop(mut self, recording_path: &Path) -> Result<PathBuf, String> {
+        self.should_stop.store(true, Ordering::SeqCst);
+
+        let samples = self
+            .handle
+            .take()
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default();
+
+        let track = CursorTrack {
+            region: self.region,
+            samples,
+        };
+        let sidecar = CursorTrack::sidecar_path(recording_path);
+        track.save(&sidecar)?;
+
+        println!(
+            "[CursorRecorder] Saved {} cursor samples to {}",
+            track.samples.len(),
+            sidecar.display()
+        );
+        Ok(sidecar)
+    }
+}
+
+impl Drop for CursorRecorder {
+    fn drop(&mut self) {
+        self.should_stop.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Read the current global cursor position in points
+#[cfg(target_os = "macos")]
+fn current_cursor_position() -> Option<(f64, f64)> {
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    struct CGPoint {
+        x: f64,
+        y: f64,
+    }
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventCreate(source: *const c_void) -> *mut c_void;
+        fn CGEventGetLocation(event: *const c_void) -> CGPoint;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFRelease(cf: *const c_void);
+    }
+
+    unsafe {
+        let event = CGEventCreate(std::ptr::null());
+        if event.is_null() {
+            return None;
+        }
+
+        let location = CGEventGetLocation(event);
+        CFRelease(event);
+        Some((location.x, location.y))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn current_cursor_position() -> Option<(f64, f64)> {
+    // TODO: Implement cursor sampling for Windows and Linux
+    None
+}
+
+// ============================================================================
+// Export Overlay
+// ============================================================================
+
+/// Settings for the synthetic cursor overlay applied during export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CursorOverlaySettings {
+    /// Size multiplier relative to a normal cursor (1.0 - 4.0)
+    pub scale: f64,
+    /// Moving average window in samples (0 disables smoothing)
+    pub smoothing: usize,
+    /// Fill color of the cursor (FFmpeg color syntax)
+    pub color: String,
+}
+
+impl Default for CursorOverlaySettings {
+    fn default() -> Self {
+        Self {
+            scale: 2.0,
+            smoothing: 4,
+            color: "white".to_string(),
+        }
+    }
+}
+
+impl CursorOverlaySettings {
+    /// Cursor sprite size in pixels for a video of the given height
+    pub fn sprite_size(&self, video_height: u32) -> u32 {
+        // A regular cursor is roughly 1/45th of a 1080p frame
+        let base = video_height as f64 / 45.0;
+        let size = (base * self.scale.clamp(1.0, 4.0)).round() as u32;
+        size.max(8) & !1
+    }
+
+    /// FFmpeg lavfi source producing the cursor sprite (a ringed dot with alpha)
+    pub fn sprite_source(&self, size: u32) -> String {
+        let center = size as f64 / 2.0;
+        let inner = center * 0.75;
+        format!(
+            "color=c={}:s={}x{},format=rgba,geq=r='if(lte(hypot(X-{c},Y-{c}),{inner}),r(X,Y),20)':g='if(lte(hypot(X-{c},Y-{c}),{inner}),g(X,Y),20)':b='if(lte(hypot(X-{c},Y-{c}),{inner}),b(X,Y),20)':a='if(lte(hypot(X-{c},Y-{c}),{c}),235,0)'",
+            self.color,
+            size,
+            size,
+            c = center,
+            inner = inner,
+        )
+    }
+}
+
+/// Build a sendcmd script that moves the cursor overlay along the track
+///
+/// Positions are converted from global points into pixels of a video that is
+/// `video_width` x `video_height` and covers `track.region`
+pub fn build_sendcmd_script(
+    track: &CursorTrack,
+    settings: &CursorOverlaySettings,
+    video_width: u32,
+    video_height: u32,
+    sprite_size: u32,
+) -> String {
+    let region = track.region;
+    if region.width <= 0.0 || region.height <= 0.0 {
+        return String::new();
+    }
+
+    let scale_x = video_width as f64 / region.width;
+    let scale_y = video_height as f64 / region.height;
+    let half = sprite_size as f64 / 2.0;
+
+    track
+        .smoothed(settings.smoothing)
+        .iter()
+        .map(|sample| {
+            let x = ((sample.x - region.x) * scale_x - half).round() as i64;
+            let y = ((sample.y - region.y) * scale_y - half).round() as i64;
+            format!(
+                "{:.3} {f} x {}, {f} y {};",
+                sample.t,
+                x,
+                y,
+                f = CURSOR_OVERLAY_FILTER
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sidecar_path() {
+        let path = CursorTrack::sidecar_path(Path::new("/tmp/rec_1.mp4"));
+        assert_eq!(path, PathBuf::from("/tmp/rec_1.cursor.json"));
+    }
+
+    #[test]
+    fn test_smoothing_averages_neighbours() {
+        let track = CursorTrack {
+            region: CaptureRegion {
+                x: 100.0,
+                y: 50.0,
+                width: 960.0,
+                height: 540.0,
+            },
+            samples: vec![
+                CursorSample {
+                    t: 0.0,
+                    x: 0.0,
+                    y: 0.0,
+                },
+                CursorSample {
+                    t: 0.1,
+                    x: 30.0,
+                    y: 30.0,
+                },
+                CursorSample {
+                    t: 0.2,
+                    x: 0.0,
+                    y: 0.0,
+                },
+            ],
+        };
+
+        let smoothed = track.smoothed(1);
+        assert_eq!(smoothed.len(), 3);
+        assert_eq!(smoothed[1].x, 10.0);
+        assert_eq!(smoothed[1].t, 0.1);
+        assert_eq!(track.smoothed(0), track.samples);
+    }
+
+    #[test]
+    fn test_sendcmd_script_maps_to_video_pixels() {
+        let track = CursorTrack {
+            region: CaptureRegion {
+                x: 100.0,
+                y: 50.0,
+                width: 960.0,
+                height: 540.0,
+            },
+            samples: vec![CursorSample {
+                t: 0.5,
+                x: 580.0,
+                y: 320.0,
+            }],
+        };
+        let settings = CursorOverlaySettings {
+            smoothing: 0,
+            ..Default::default()
+        };
+
+        // Region is 960x540 points recorded at 1920x1080 pixels
+        let script = build_sendcmd_script(&track, &settings, 1920, 1080, 40);
+        assert_eq!(script, "0.500 overlay@cursor x 940, overlay@cursor y 520;");
+    }
+
+    #[test]
+    fn test_sprite_size_is_even_and_scaled() {
+        let settings = CursorOverlaySettings::default();
+        let size = settings.sprite_size(1080);
+        assert_eq!(size % 2, 0);
+        assert_eq!(size, 48);
+    }
+}
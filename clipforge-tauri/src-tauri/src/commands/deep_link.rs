@@ -0,0 +1,263 @@
+// clipforge:// deep links
+//
+// Lets external tools drive recordings through URLs, mapped onto the existing
+// recording commands:
+//
+//   clipforge://record?source=display_1&preset=high&type=screen&audio=false
+//   clipforge://stop
+//   clipforge://pause
+//   clipforge://resume
+//   clipforge://marker?label=Intro
+//
+// Any web page or app can open a link, so `record` is refused unless the user
+// has turned on `allowDeepLinkRecording` in the settings (off by default). The
+// other actions only act on a recording the user already started.
+//
+// Every link is reported to the frontend with "deep-link:handled" or
+// "deep-link:error", so a recording started from outside is always visible.
+
+use super::recording::{
+    add_marker, pause_recording, resume_recording, start_recording, stop_recording, QualityPreset,
+    RecordingManagerState, RecordingType,
+};
+use super::settings::SettingsState;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+use url::Url;
+
+/// URL scheme registered for deep links
+pub const DEEP_LINK_SCHEME: &str = "clipforge";
+
+/// A recording action requested through a deep link
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLinkAction {
+    Record {
+        source_id: String,
+        recording_type: RecordingType,
+        preset: Option<QualityPreset>,
+        include_audio: bool,
+    },
+    Stop,
+    Pause,
+    Resume,
+    Marker {
+        label: Option<String>,
+    },
+}
+
+/// Event payload for "deep-link:handled" and "deep-link:error"
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeepLinkResult {
+    url: String,
+    error: Option<String>,
+}
+
+fn parse_flag(value: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(format!("Invalid boolean value '{}'", value)),
+    }
+}
+
+/// Parse a lowercase enum value the same way the recording commands deserialize it
+fn parse_enum<T: serde::de::DeserializeOwned>(name: &str, value: &str) -> Result<T, String> {
+    serde_json::from_value(serde_json::Value::String(value.to_ascii_lowercase()))
+        .map_err(|_| format!("Invalid {} '{}'", name, value))
+}
+
+/// Map a clipforge:// URL onto a recording action
+pub fn parse_deep_link(url: &Url) -> Result<DeepLinkAction, String> {
+    if url.scheme() != DEEP_LINK_SCHEME {
+        return Err(format!("Unsupported URL scheme '{}'", url.scheme()));
+    }
+
+    // clipforge://record puts the action in the host, clipforge:/record in the path
+    let action = url
+        .host_str()
+        .filter(|host| !host.is_empty())
+        .or_else(|| url.path().trim_start_matches('/').split('/').next())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+
+    match action.as_str() {
+        "record" | "start" => Ok(DeepLinkAction::Record {
+            source_id: param("source").ok_or_else(|| "Missing 'source' parameter".to_string())?,
+            recording_type: param("type")
+                .map(|value| parse_enum("recording type", &value))
+                .transpose()?
+                .unwrap_or(RecordingType::Screen),
+            preset: param("preset")
+                .map(|value| parse_enum("preset", &value))
+                .transpose()?,
+            include_audio: param("audio")
+                .map(|value| parse_flag(&value))
+                .transpose()?
+                .unwrap_or(true),
+        }),
+        "stop" => Ok(DeepLinkAction::Stop),
+        "pause" => Ok(DeepLinkAction::Pause),
+        "resume" => Ok(DeepLinkAction::Resume),
+        "marker" => Ok(DeepLinkAction::Marker {
+            label: param("label"),
+        }),
+        "" => Err("Missing deep link action".to_string()),
+        other => Err(format!("Unknown deep link action '{}'", other)),
+    }
+}
+
+/// Whether the user has allowed links to start recordings
+fn recording_allowed(app: &AppHandle) -> bool {
+    app.try_state::<SettingsState>()
+        .and_then(|settings| {
+            let settings = settings.lock().ok()?;
+            Some(settings.allow_deep_link_recording)
+        })
+        .unwrap_or(false)
+}
+
+async fn run_action(app: &AppHandle, action: DeepLinkAction) -> Result<(), String> {
+    if matches!(action, DeepLinkAction::Record { .. }) && !recording_allowed(app) {
+        return Err(
+            "Starting recordings from links is turned off. Enable it in Settings to allow it."
+                .to_string(),
+        );
+    }
+
+    let state = app.state::<RecordingManagerState>();
+
+    let result = match action {
+        DeepLinkAction::Record {
+            source_id,
+            recording_type,
+            preset,
+            include_audio,
+        } => start_recording(
+            recording_type,
+            source_id,
+            preset.map(|preset| preset.to_config()),
+            include_audio,
+            None,
+            state,
+            app.clone(),
+        )
+        .await
+        .map(|_| ()),
+        DeepLinkAction::Stop => stop_recording(state, app.clone()).await.map(|_| ()),
+        DeepLinkAction::Pause => pause_recording(state, app.clone()).await.map(|_| ()),
+        DeepLinkAction::Resume => resume_recording(state, app.clone()).await.map(|_| ()),
+        DeepLinkAction::Marker { label } => add_marker(label, state, app.clone()).await.map(|_| ()),
+    };
+    Ok(result?)
+}
+
+/// Run the action for a deep link and report the outcome to the frontend
+pub fn handle_url(app: AppHandle, url: Url) {
+    tauri::async_runtime::spawn(async move {
+        println!("[DeepLink] {}", url);
+
+        let result = match parse_deep_link(&url) {
+            Ok(action) => run_action(&app, action).await,
+            Err(e) => Err(e),
+        };
+
+        let (event, error) = match result {
+            Ok(()) => ("deep-link:handled", None),
+            Err(e) => {
+                println!("[DeepLink] {} failed: {}", url, e);
+                ("deep-link:error", Some(e))
+            }
+        };
+        let _ = app.emit(
+            event,
+            DeepLinkResult {
+                url: url.to_string(),
+                error,
+            },
+        );
+    });
+}
+
+/// Handle deep links that launched the app and any opened while it runs
+pub fn register_deep_links(app: &AppHandle) {
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        for url in urls {
+            handle_url(app.clone(), url);
+        }
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            handle_url(handle.clone(), url);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_record() {
+        assert_eq!(
+            parse_deep_link(
+                &Url::parse("clipforge://record?source=display_1&preset=high").unwrap()
+            ),
+            Ok(DeepLinkAction::Record {
+                source_id: "display_1".to_string(),
+                recording_type: RecordingType::Screen,
+                preset: Some(QualityPreset::High),
+                include_audio: true,
+            })
+        );
+        assert_eq!(
+            parse_deep_link(
+                &Url::parse("clipforge://record?source=window_42&type=ScreenAndWebcam&audio=0")
+                    .unwrap()
+            ),
+            Ok(DeepLinkAction::Record {
+                source_id: "window_42".to_string(),
+                recording_type: RecordingType::ScreenAndWebcam,
+                preset: None,
+                include_audio: false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_simple_actions() {
+        assert_eq!(
+            parse_deep_link(&Url::parse("clipforge://stop").unwrap()),
+            Ok(DeepLinkAction::Stop)
+        );
+        assert_eq!(
+            parse_deep_link(&Url::parse("clipforge:pause").unwrap()),
+            Ok(DeepLinkAction::Pause)
+        );
+        assert_eq!(
+            parse_deep_link(&Url::parse("clipforge://marker?label=Intro%20done").unwrap()),
+            Ok(DeepLinkAction::Marker {
+                label: Some("Intro done".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_deep_link(&Url::parse("clipforge://record").unwrap()).is_err());
+        assert!(parse_deep_link(
+            &Url::parse("clipforge://record?source=display_1&preset=ultra").unwrap()
+        )
+        .is_err());
+        assert!(parse_deep_link(&Url::parse("clipforge://launch").unwrap()).is_err());
+        assert!(parse_deep_link(&Url::parse("https://record?source=display_1").unwrap()).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+// FFmpeg dry runs
+//
+// `preview_ffmpeg_command` returns the exact FFmpeg invocations a recording or
+// export would run, without encoding anything, for debugging encoding problems
+// and attaching to bug reports. Exports go through the normal export code with
+// an `FfmpegRunner` that records commands instead of running them; read-only
+// probes (FFprobe and silence detection) still run so the arguments match.
+
+use super::export::{preview_export, ClipData, ExportSettings};
+use super::process_runner::{watched_output_async, JobKind};
+use super::recording::{preview_recording_command, RecordingConfig, RecordingType};
+use crate::error::ClipForgeError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::process::{Command, ExitStatus, Output};
+use std::sync::Mutex;
+
+/// What to build commands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PreviewOperation {
+    Recording,
+    Export,
+}
+
+/// Commands a recording or export would run, in order
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FfmpegPreview {
+    /// Each command as argv, starting with the FFmpeg path
+    pub commands: Vec<Vec<String>>,
+    /// Steps that could not be previewed, e.g. because they depend on earlier output
+    pub notes: Vec<String>,
+}
+
+/// Runs FFmpeg commands, or only records them for a dry run
+pub enum FfmpegRunner {
+    Run,
+    Record(Mutex<FfmpegPreview>),
+}
+
+impl FfmpegRunner {
+    pub fn dry_run() -> Self {
+        FfmpegRunner::Record(Mutex::new(FfmpegPreview::default()))
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        matches!(self, FfmpegRunner::Record(_))
+    }
+
+    /// Run `command`, or record it and report success without running it
+    pub async fn output(&self, command: &mut tokio::process::Command) -> std::io::Result<Output> {
+        match self {
+            FfmpegRunner::Run => watched_output_async(command, JobKind::Encode).await,
+            FfmpegRunner::Record(_) => {
+                self.record(command.as_std());
+                Ok(Output {
+                    status: ExitStatus::default(),
+                    stdout: Vec::new(),
+                    stderr: Vec::new(),
+                })
+            }
+        }
+    }
+
+    /// Add a command to a dry run's preview
+    fn record(&self, command: &Command) {
+        if let FfmpegRunner::Record(preview) = self {
+            if let Ok(mut preview) = preview.lock() {
+                preview.commands.push(command_line(command));
+            }
+        }
+    }
+
+    /// Explain a step a dry run skipped
+    pub fn note(&self, note: impl Into<String>) {
+        if let FfmpegRunner::Record(preview) = self {
+            if let Ok(mut preview) = preview.lock() {
+                preview.notes.push(note.into());
+            }
+        }
+    }
+
+    pub fn into_preview(self) -> FfmpegPreview {
+        match self {
+            FfmpegRunner::Run => FfmpegPreview::default(),
+            FfmpegRunner::Record(preview) => {
+                preview.into_inner().unwrap_or_else(|e| e.into_inner())
+            }
+        }
+    }
+}
+
+/// A command's program and arguments
+pub fn command_line(command: &Command) -> Vec<String> {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|arg| arg.to_string_lossy().to_string())
+        .collect()
+}
+
+/// Parameters of a recording preview, matching start_recording
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordingParams {
+    source_id: String,
+    #[serde(default)]
+    recording_type: Option<RecordingType>,
+    #[serde(default)]
+    config: Option<RecordingConfig>,
+    #[serde(default)]
+    include_audio: bool,
+}
+
+/// Parameters of an export preview, matching export_timeline
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportParams {
+    clips: Vec<ClipData>,
+    output_path: String,
+    #[serde(default)]
+    settings: Option<ExportSettings>,
+}
+
+fn parse_params<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T, ClipForgeError> {
+    serde_json::from_value(params)
+        .map_err(|e| ClipForgeError::InvalidInput(format!("Invalid preview parameters: {}", e)))
+}
+
+/// Build the FFmpeg commands an operation would run, without running them
+///
+/// `params` are the arguments the operation's own command takes: start_recording's
+/// `sourceId`, `recordingType`, `config` and `includeAudio`, or export_timeline's
+/// `clips`, `outputPath` and `settings`.
+#[tauri::command]
+pub async fn preview_ffmpeg_command(
+    operation: PreviewOperation,
+    params: Value,
+) -> Result<FfmpegPreview, ClipForgeError> {
+    match operation {
+        PreviewOperation::Recording => {
+            let params: RecordingParams = parse_params(params)?;
+            let command = preview_recording_command(
+                params.recording_type.unwrap_or(RecordingType::Screen),
+                params.source_id,
+                params.config.unwrap_or_default(),
+                params.include_audio,
+            )?;
+            Ok(FfmpegPreview {
+                commands: vec![command],
+                notes: Vec::new(),
+            })
+        }
+        PreviewOperation::Export => {
+            let params: ExportParams = parse_params(params)?;
+            preview_export(
+                params.clips,
+                params.output_path,
+                params.settings.unwrap_or_default(),
+            )
+            .await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_dry_run_records_instead_of_running() {
+        let runner = FfmpegRunner::dry_run();
+        let mut command = tokio::process::Command::new("/nonexistent/ffmpeg");
+        command.arg("-i").arg("in put.mp4").arg("out.mp4");
+
+        let output = runner.output(&mut command).await.unwrap();
+        assert!(output.status.success());
+        runner.note("Skipped a step");
+
+        let preview = runner.into_preview();
+        assert_eq!(
+            preview.commands,
+            vec![vec!["/nonexistent/ffmpeg", "-i", "in put.mp4", "out.mp4"]]
+        );
+        assert_eq!(preview.notes, vec!["Skipped a step"]);
+    }
+}
@@ -1,9 +1,31 @@
-use super::ffmpeg_utils::find_ffmpeg;
+use super::analysis::{detect_silence, keep_ranges, kept_offset, SilenceSettings};
+use super::app_cache;
+use super::audio_filters::AudioFilterSettings;
+use super::color_correction::ColorCorrection;
+use super::cursor::{
+    build_sendcmd_script, CursorOverlaySettings, CursorTrack, CURSOR_OVERLAY_FILTER,
+};
+use super::dry_run::{FfmpegPreview, FfmpegRunner};
+use super::ffmpeg_args;
+use super::ffmpeg_utils::{find_ffmpeg, quote_filter_value};
+use super::metadata::{extract_metadata, is_gif, MediaKind};
+use super::pip::{self, PiPMetadata, PipMode, PipOverride};
+use super::recording::{ffmetadata_chapters, Chapter, SessionManifest};
+use super::reframe::{FocusRegion, ReframeSettings};
+use super::render_cache;
+use super::stingers::StingerSettings;
+use super::storage;
+use super::subtitles::{
+    burn_in_filter, sidecar_path, write_subtitles, SubtitleFormat, SubtitleSettings,
+};
+use super::target_size;
+use super::webhooks::{self, WebhookEvent, WebhookPayload};
+use super::window_frame::WindowFrameSettings;
+use crate::error::ClipForgeError;
 use serde::{Deserialize, Serialize};
-use serde_json;
 use std::fs;
-use std::process::Command;
 use tauri::{AppHandle, Emitter};
+use tokio::process::Command;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ClipData {
@@ -24,291 +46,834 @@ pub struct ClipData {
     pub media_type: Option<String>,
     #[serde(rename = "pipMetadataPath")]
     pub pip_metadata_path: Option<String>,
+    /// Moves or resizes the webcam overlay of a PiP clip
+    #[serde(rename = "pipOverride")]
+    pub pip_override: Option<PipOverride>,
+    /// Brightness, contrast, saturation or LUT applied to this clip
+    #[serde(rename = "colorCorrection")]
+    pub color_correction: Option<ColorCorrection>,
+    #[serde(rename = "cursorDataPath")]
+    pub cursor_data_path: Option<String>,
+    /// Area a vertical reframe keeps in view instead of the center
+    #[serde(rename = "reframeFocus")]
+    pub reframe_focus: Option<FocusRegion>,
 }
 
-#[derive(Debug, Clone, Serialize)]
-struct ExportProgress {
-    current: usize,
-    total: usize,
-    message: String,
+/// Export-wide options applied on top of the clip list
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportSettings {
+    /// Replace the captured cursor with a synthetic one for clips that have a cursor track
+    pub cursor_overlay: Option<CursorOverlaySettings>,
+    /// Place the video on a background with padding, rounded corners and a shadow
+    pub window_frame: Option<WindowFrameSettings>,
+    /// Audio post filters applied to every clip
+    pub audio_filters: Option<AudioFilterSettings>,
+    /// Cut silent sections out of every clip
+    pub skip_silence: Option<SilenceSettings>,
+    /// Burned-in and/or sidecar subtitles
+    pub subtitles: Option<SubtitleSettings>,
+    /// Tone-map HDR (PQ or HLG) clips to SDR BT.709
+    pub tone_map_hdr: bool,
+    /// Intro and outro joined onto the exported video
+    pub stingers: Option<StingerSettings>,
+    /// Re-encode the finished video to fit into this many MB
+    pub target_size_mb: Option<f64>,
+    /// Keep transparency, encoding with a codec that has an alpha channel
+    pub alpha: Option<AlphaCodec>,
+    /// Crop to a portrait frame (9:16 by default)
+    pub reframe: Option<ReframeSettings>,
+    /// Encoder tuning flags appended to every segment encode (see ffmpeg_args.rs)
+    pub extra_ffmpeg_args: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct PiPConfiguration {
-    position: String,
-    size: String,
-    #[serde(rename = "cameraId")]
-    camera_id: Option<String>,
-    #[serde(rename = "includeAudio")]
-    include_audio: bool,
-    #[serde(rename = "audioDeviceId")]
-    audio_device_id: Option<String>,
+impl ExportSettings {
+    /// Reject settings no export could use; the output path is checked separately
+    ///
+    /// Everything that renders with these settings (exports, the export queue and
+    /// background renders) runs this first.
+    pub fn validate(&self) -> Result<(), ClipForgeError> {
+        let invalid = ClipForgeError::InvalidInput;
+        if let Some(reframe) = &self.reframe {
+            reframe.validate().map_err(invalid)?;
+        }
+        ffmpeg_args::validate_extra_args(&self.extra_ffmpeg_args).map_err(invalid)?;
+        let stingers = self
+            .stingers
+            .as_ref()
+            .filter(|stingers| !stingers.is_empty());
+        if let Some(stingers) = stingers {
+            stingers.validate().map_err(invalid)?;
+        }
+        if let Some(target_mb) = self.target_size_mb {
+            if !(target_mb > 0.0 && target_mb <= target_size::MAX_TARGET_MB) {
+                return Err(ClipForgeError::InvalidInput(format!(
+                    "Target size must be between 0 and {} MB",
+                    target_size::MAX_TARGET_MB
+                )));
+            }
+        }
+        if self.alpha.is_some() {
+            // Both re-encode the finished video without an alpha channel
+            if stingers.is_some() {
+                return Err(ClipForgeError::InvalidInput(
+                    "Intro and outro stingers cannot be added to a transparent export".to_string(),
+                ));
+            }
+            if self.target_size_mb.is_some() {
+                return Err(ClipForgeError::InvalidInput(
+                    "A target file size cannot be used with a transparent export".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ScreenDimensions {
-    width: u32,
-    height: u32,
+/// Codec used for exports that keep transparency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AlphaCodec {
+    /// ProRes 4444 in a QuickTime movie, for editors such as Final Cut Pro
+    ProRes4444,
+    /// VP9 with alpha in WebM, for browsers and OBS
+    Vp9,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct PiPMetadata {
-    id: String,
-    #[serde(rename = "startTime")]
-    start_time: u64,
-    duration: f64,
-    #[serde(rename = "screenFilePath")]
-    screen_file_path: String,
-    #[serde(rename = "webcamFilePath")]
-    webcam_file_path: String,
-    #[serde(rename = "pipConfig")]
-    pip_config: PiPConfiguration,
-    #[serde(rename = "screenDimensions")]
-    screen_dimensions: ScreenDimensions,
-    #[serde(rename = "webcamDimensions")]
-    webcam_dimensions: ScreenDimensions,
-}
+impl AlphaCodec {
+    /// Container the codec is written to
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AlphaCodec::ProRes4444 => "mov",
+            AlphaCodec::Vp9 => "webm",
+        }
+    }
+
+    fn video_args(&self) -> &'static [&'static str] {
+        match self {
+            AlphaCodec::ProRes4444 => &[
+                "-c:v",
+                "prores_ks",
+                "-profile:v",
+                "4444",
+                "-pix_fmt",
+                "yuva444p10le",
+            ],
+            AlphaCodec::Vp9 => &[
+                "-c:v",
+                "libvpx-vp9",
+                "-pix_fmt",
+                "yuva420p",
+                "-b:v",
+                "0",
+                "-crf",
+                "30",
+                "-auto-alt-ref",
+                "0",
+            ],
+        }
+    }
 
-#[derive(Debug)]
-struct PiPCoordinates {
-    x: u32,
-    y: u32,
-    width: u32,
-    height: u32,
+    fn audio_codec(&self) -> &'static str {
+        match self {
+            AlphaCodec::ProRes4444 => "pcm_s16le",
+            AlphaCodec::Vp9 => "libopus",
+        }
+    }
 }
 
-/// Calculate PiP overlay coordinates based on configuration
-fn calculate_pip_coordinates(
-    pip_config: &PiPConfiguration,
-    screen_width: u32,
-    screen_height: u32,
-) -> PiPCoordinates {
-    // Size percentages matching frontend
-    let size_percent = match pip_config.size.as_str() {
-        "small" => 0.15,
-        "medium" => 0.25,
-        "large" => 0.35,
-        _ => 0.25,
-    };
+/// Video encoder arguments for timeline segments
+fn video_codec_args(alpha: Option<AlphaCodec>) -> &'static [&'static str] {
+    match alpha {
+        Some(codec) => codec.video_args(),
+        None => &["-c:v", "libx264", "-preset", "medium"],
+    }
+}
 
-    const EDGE_PADDING: u32 = 20;
-
-    // Calculate overlay dimensions (16:9 aspect ratio)
-    let overlay_width = (screen_width as f64 * size_percent) as u32;
-    let overlay_height = (overlay_width as f64 / (16.0 / 9.0)) as u32;
-
-    // Calculate position based on corner
-    let (x, y) = match pip_config.position.as_str() {
-        "topLeft" => (EDGE_PADDING, EDGE_PADDING),
-        "topRight" => (screen_width - overlay_width - EDGE_PADDING, EDGE_PADDING),
-        "bottomLeft" => (EDGE_PADDING, screen_height - overlay_height - EDGE_PADDING),
-        "bottomRight" => (
-            screen_width - overlay_width - EDGE_PADDING,
-            screen_height - overlay_height - EDGE_PADDING,
-        ),
-        _ => (
-            screen_width - overlay_width - EDGE_PADDING,
-            screen_height - overlay_height - EDGE_PADDING,
-        ),
-    };
+/// Audio encoder for timeline segments
+fn audio_codec(alpha: Option<AlphaCodec>) -> &'static str {
+    alpha.map_or("aac", |codec| codec.audio_codec())
+}
 
-    PiPCoordinates {
-        x,
-        y,
-        width: overlay_width,
-        height: overlay_height,
-    }
+/// Extension of intermediate segment files
+fn segment_extension(alpha: Option<AlphaCodec>) -> &'static str {
+    alpha.map_or("mp4", |codec| codec.extension())
 }
 
-/// Load PiP metadata from JSON file
-fn load_pip_metadata(metadata_path: &str) -> Result<PiPMetadata, String> {
-    let content = fs::read_to_string(metadata_path)
-        .map_err(|e| format!("Failed to read PiP metadata file: {}", e))?;
+/// HDR to SDR conversion: linearize, convert to BT.709 primaries, then Hable tone-map
+const TONE_MAP_SDR_FILTER: &str = "zscale=t=linear:npl=100,format=gbrpf32le,zscale=p=bt709,tonemap=hable:desat=0,zscale=t=bt709:m=bt709:r=tv,format=yuv420p";
+
+/// Payload of "export-progress"; `current` counts completed steps out of `total`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportProgress {
+    pub current: usize,
+    pub total: usize,
+    pub message: String,
+}
 
-    let metadata: PiPMetadata = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse PiP metadata: {}", e))?;
+/// Media kind of a clip, from its declared type or its file extension
+pub fn clip_media_kind(clip: &ClipData) -> MediaKind {
+    match clip.media_type.as_deref() {
+        Some("image") => MediaKind::Image,
+        Some("audio") => MediaKind::Audio,
+        _ => MediaKind::from_path(std::path::Path::new(&clip.video_path)),
+    }
+}
 
-    Ok(metadata)
+/// Input options that loop an image for as long as its clip lasts
+fn image_input_args(path: &str, fps: f64) -> Vec<String> {
+    if is_gif(std::path::Path::new(path)) {
+        vec!["-stream_loop".to_string(), "-1".to_string()]
+    } else {
+        vec![
+            "-loop".to_string(),
+            "1".to_string(),
+            "-framerate".to_string(),
+            fps.to_string(),
+        ]
+    }
 }
 
-/// Composite a PiP recording into a single video file
-fn composite_pip_recording(
+/// Mix audio-only clips over the exported video's audio at their timeline positions
+async fn mix_audio_clips(
     ffmpeg_path: &std::path::Path,
-    metadata: &PiPMetadata,
-    output_path: &std::path::Path,
+    video_path: &std::path::Path,
+    audio_clips: &[ClipData],
+    timeline_start: f64,
+    output_path: &str,
+    audio_codec: &str,
+    runner: &FfmpegRunner,
 ) -> Result<(), String> {
-    // Calculate overlay coordinates
-    let coordinates = calculate_pip_coordinates(
-        &metadata.pip_config,
-        metadata.screen_dimensions.width,
-        metadata.screen_dimensions.height,
-    );
+    let mut command = Command::new(ffmpeg_path);
+    command.arg("-i").arg(video_path);
 
-    println!(
-        "Overlay position: {}x{} at ({}, {})",
-        coordinates.width, coordinates.height, coordinates.x, coordinates.y
-    );
+    let mut filters = Vec::new();
+    let mut mix_inputs = "[0:a]".to_string();
+    for (i, clip) in audio_clips.iter().enumerate() {
+        command
+            .arg("-ss")
+            .arg(clip.trim_start.to_string())
+            .arg("-t")
+            .arg((clip.trim_end - clip.trim_start).to_string())
+            .arg("-i")
+            .arg(&clip.video_path);
 
-    // Build FFmpeg filter_complex for PiP overlay
-    let filter_complex = format!(
-        "[1:v]scale={}:{}[webcam];[0:v][webcam]overlay={}:{}[outv]",
-        coordinates.width, coordinates.height, coordinates.x, coordinates.y
-    );
+        let delay_ms = ((clip.start_time - timeline_start).max(0.0) * 1000.0).round() as u64;
+        filters.push(format!(
+            "[{}:a]aresample=48000,adelay={}:all=1[voice{}]",
+            i + 1,
+            delay_ms,
+            i
+        ));
+        mix_inputs.push_str(&format!("[voice{}]", i));
+    }
 
-    // Execute FFmpeg compositing
-    let output = Command::new(ffmpeg_path)
-        .arg("-i")
-        .arg(&metadata.screen_file_path)
-        .arg("-i")
-        .arg(&metadata.webcam_file_path)
+    // normalize=0 keeps the original audio at full volume instead of dividing by input count
+    filters.push(format!(
+        "{}amix=inputs={}:duration=first:dropout_transition=0:normalize=0[aout]",
+        mix_inputs,
+        audio_clips.len() + 1
+    ));
+
+    command
         .arg("-filter_complex")
-        .arg(&filter_complex)
+        .arg(filters.join(";"))
         .arg("-map")
-        .arg("[outv]")
+        .arg("0:v")
         .arg("-map")
-        .arg("0:a?") // Screen audio only (ignore webcam audio)
+        .arg("[aout]")
         .arg("-c:v")
-        .arg("libx264")
-        .arg("-preset")
-        .arg("medium")
-        .arg("-crf")
-        .arg("23")
+        .arg("copy")
         .arg("-c:a")
-        .arg("aac")
+        .arg(audio_codec)
         .arg("-b:a")
         .arg("192k")
-        .arg("-movflags")
-        .arg("+faststart")
         .arg("-y")
-        .arg(output_path)
-        .output()
-        .map_err(|e| format!("Failed to execute FFmpeg for PiP compositing: {}", e))?;
+        .arg(output_path);
+    let output = runner
+        .output(&mut command)
+        .await
+        .map_err(|e| format!("Failed to run FFmpeg audio mix: {}", e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("FFmpeg PiP compositing failed: {}", stderr));
+        return Err(format!("FFmpeg audio mix failed: {}", stderr));
     }
 
-    println!("PiP compositing completed: {}", output_path.display());
     Ok(())
 }
 
+/// Build chapters from the markers in each clip's session manifest
+///
+/// `ranges` holds the source ranges kept for each clip. Markers outside them
+/// are dropped, except that a marker in a cut silence moves to where the cut
+/// ends; times are mapped onto the exported timeline, which starts at the
+/// first clip and is shortened by every cut
+fn collect_chapters(clips: &[ClipData], ranges: &[Vec<(f64, f64)>]) -> Vec<Chapter> {
+    let timeline_start = clips.first().map(|c| c.start_time).unwrap_or(0.0);
+    let timeline_end = clips
+        .iter()
+        .map(|c| c.start_time + (c.trim_end - c.trim_start))
+        .fold(timeline_start, f64::max);
+
+    let mut starts: Vec<(f64, String)> = Vec::new();
+    // Seconds removed from the clips placed so far
+    let mut removed = 0.0;
+    for (clip, kept) in clips.iter().zip(ranges) {
+        let clip_start = clip.start_time - timeline_start - removed;
+        removed += (clip.trim_end - clip.trim_start)
+            - kept.iter().map(|(start, end)| end - start).sum::<f64>();
+
+        let Ok(manifest) = SessionManifest::load(std::path::Path::new(&clip.video_path)) else {
+            continue;
+        };
+
+        for marker in manifest.markers {
+            if marker.time < clip.trim_start || marker.time >= clip.trim_end {
+                continue;
+            }
+            if let Some(offset) = kept_offset(kept, marker.time) {
+                starts.push((clip_start + offset, marker.label));
+            }
+        }
+    }
+    starts.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let total = timeline_end - timeline_start - removed;
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, (start, title))| Chapter {
+            start: *start,
+            end: starts.get(i + 1).map(|next| next.0).unwrap_or(total),
+            title: title.clone(),
+        })
+        .collect()
+}
+
 #[tauri::command]
 pub async fn export_timeline(
     app: AppHandle,
     clips: Vec<ClipData>,
     output_path: String,
-) -> Result<(), String> {
-    println!("Exporting {} clips to: {}", clips.len(), output_path);
+    settings: Option<ExportSettings>,
+) -> Result<(), ClipForgeError> {
+    let report = |progress: ExportProgress| {
+        let _ = app.emit("export-progress", progress);
+        Ok(())
+    };
+    run_export(clips, output_path, settings.unwrap_or_default(), &report).await
+}
 
-    if clips.is_empty() {
-        return Err("No clips to export".to_string());
+/// Size and frame rate every timeline segment is normalized to
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SegmentFormat {
+    pub width: u32,
+    pub height: u32,
+    pub fps: f64,
+}
+
+impl SegmentFormat {
+    /// The first clip's resolution and frame rate, which the export uses for its output
+    ///
+    /// When reframing, the size is the first clip's portrait crop instead.
+    pub fn for_timeline(clips: &[ClipData], reframe: Option<&ReframeSettings>) -> Option<Self> {
+        clips.first().map(|clip| {
+            let (width, height) = match reframe {
+                Some(reframe) => reframe.crop_size(clip.width, clip.height),
+                None => (clip.width, clip.height),
+            };
+            Self {
+                width,
+                height,
+                fps: clip.frame_rate,
+            }
+        })
     }
+}
 
-    // Find ffmpeg executable
-    let ffmpeg_path =
-        find_ffmpeg().ok_or_else(|| "ffmpeg not found. Please install FFmpeg.".to_string())?;
-    // Get first clip's resolution and framerate to use for the output
-    let target_width = clips[0].width;
-    let target_height = clips[0].height;
-    let target_fps = clips[0].frame_rate;
+/// Whether a clip is a PiP recording that must be composited before it is trimmed
+pub fn is_pip_clip(clip: &ClipData) -> bool {
+    clip.media_type.as_deref() == Some("pip") && clip.pip_metadata_path.is_some()
+}
 
-    // Create temp directory for intermediate files
-    let temp_dir = std::env::temp_dir().join("clipforge_export");
-    fs::create_dir_all(&temp_dir).map_err(|e| format!("Failed to create temp directory: {}", e))?;
+/// Segments rendered for a clip and the source ranges they were cut from
+#[derive(Debug, Clone)]
+pub struct RenderedClip {
+    pub segments: Vec<std::path::PathBuf>,
+    pub ranges: Vec<(f64, f64)>,
+}
 
-    // Calculate total steps for progress (clips + gaps + concat)
-    let mut gaps_needed = 0;
-    for i in 0..clips.len() - 1 {
-        let current_end = clips[i].start_time + (clips[i].trim_end - clips[i].trim_start);
-        let next_start = clips[i + 1].start_time;
-        if next_start > current_end {
-            gaps_needed += 1;
+/// Trim and normalize one video or image clip into segments in `work_dir`
+///
+/// Renders one segment per kept range: the whole trim, or the non-silent parts
+/// when silence is being cut.
+pub async fn render_clip(
+    ffmpeg_path: &std::path::Path,
+    clip: &ClipData,
+    index: usize,
+    format: SegmentFormat,
+    settings: &ExportSettings,
+    work_dir: &std::path::Path,
+    runner: &FfmpegRunner,
+) -> Result<RenderedClip, ClipForgeError> {
+    // Background renders reach this without going through export_with
+    ffmpeg_args::validate_extra_args(&settings.extra_ffmpeg_args)?;
+
+    let target_width = format.width;
+    let target_height = format.height;
+    let target_fps = format.fps;
+    let mut segments = Vec::new();
+
+    // Determine the actual video path - composite PiP if needed
+    let actual_video_path: String;
+    // Center of the webcam overlay, which a reframe can follow
+    let mut webcam_focus = None;
+
+    if is_pip_clip(clip) {
+        // This is a PiP recording - composite it first
+        let metadata_path = clip.pip_metadata_path.as_ref().unwrap();
+        let pip_metadata = PiPMetadata::load(metadata_path)?;
+        let composite_output = work_dir.join(format!("pip_composite_{:03}.mp4", index));
+
+        let composition = pip_metadata.composition(clip.pip_override.as_ref())?;
+        if composition.mode == PipMode::Overlay {
+            let layout = composition.layout;
+            webcam_focus = Some((
+                layout.x as f64 + layout.width as f64 / 2.0,
+                layout.y as f64 + layout.height as f64 / 2.0,
+            ));
+        }
+
+        // Screen audio only; the webcam track is left out of exports
+        let mut command = Command::from(pip::composite_command(
+            ffmpeg_path,
+            &pip_metadata.screen_file_path,
+            &pip_metadata.webcam_file_path,
+            &composition,
+            false,
+            &composite_output,
+        ));
+        let output = runner
+            .output(&mut command)
+            .await
+            .map_err(|e| format!("Failed to execute FFmpeg for PiP compositing: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(ClipForgeError::Ffmpeg(format!(
+                "FFmpeg PiP compositing failed: {}",
+                stderr
+            )));
         }
+
+        actual_video_path = composite_output
+            .to_str()
+            .ok_or_else(|| "Failed to convert composite path to string".to_string())?
+            .to_string();
+    } else {
+        // Regular video clip
+        actual_video_path = clip.video_path.clone();
     }
-    let total_steps = clips.len() + gaps_needed + 1; // clips + gaps + final concat
-    let mut current_step = 0;
 
-    // Process each clip - trim and normalize to target resolution/fps
-    let mut segment_files = Vec::new();
-    for (i, clip) in clips.iter().enumerate() {
-        current_step += 1;
-        let _ = app.emit(
-            "export-progress",
-            ExportProgress {
-                current: current_step,
-                total: total_steps,
-                message: format!("Processing clip {} of {}", i + 1, clips.len()),
-            },
-        );
+    let trimmed_duration = clip.trim_end - clip.trim_start;
+    let media_kind = clip_media_kind(clip);
 
-        // Determine the actual video path - composite PiP if needed
-        let actual_video_path: String;
+    println!(
+        "Processing clip {}: {} (trim: {}-{}, duration: {}s)",
+        index, actual_video_path, clip.trim_start, clip.trim_end, trimmed_duration
+    );
 
-        if clip.media_type.as_deref() == Some("pip") && clip.pip_metadata_path.is_some() {
-            // This is a PiP recording - composite it first
-            let metadata_path = clip.pip_metadata_path.as_ref().unwrap();
-            let _ = app.emit(
-                "export-progress",
-                ExportProgress {
-                    current: current_step,
-                    total: total_steps,
-                    message: format!("Compositing PiP clip {} of {}", i + 1, clips.len()),
-                },
-            );
-
-            let pip_metadata = load_pip_metadata(metadata_path)?;
-            let composite_output = temp_dir.join(format!("pip_composite_{:03}.mp4", i));
-
-            composite_pip_recording(&ffmpeg_path, &pip_metadata, &composite_output)?;
-
-            actual_video_path = composite_output
-                .to_str()
-                .ok_or_else(|| "Failed to convert composite path to string".to_string())?
-                .to_string();
-        } else {
-            // Regular video clip
-            actual_video_path = clip.video_path.clone();
+    // Ranges of the source to keep - the whole trim unless silence is being cut
+    let ranges = match &settings.skip_silence {
+        Some(silence) if media_kind != MediaKind::Image => {
+            let silences = detect_silence(
+                ffmpeg_path,
+                &actual_video_path,
+                silence.threshold_db,
+                silence.min_duration,
+            )
+            .await?;
+            keep_ranges(clip.trim_start, clip.trim_end, &silences, silence.padding)
         }
+        _ => vec![(clip.trim_start, clip.trim_end)],
+    };
 
-        let temp_output = temp_dir.join(format!("segment_{:03}.mp4", segment_files.len()));
-        let trimmed_duration = clip.trim_end - clip.trim_start;
+    let input_args = match media_kind {
+        MediaKind::Image => image_input_args(&actual_video_path, target_fps),
+        // FFmpeg's built-in VP9 decoder drops the alpha channel
+        _ if settings.alpha.is_some() && actual_video_path.to_lowercase().ends_with(".webm") => {
+            vec!["-c:v".to_string(), "libvpx-vp9".to_string()]
+        }
+        _ => Vec::new(),
+    };
 
-        println!(
-            "Processing clip {}: {} (trim: {}-{}, duration: {}s)",
-            i, actual_video_path, clip.trim_start, clip.trim_end, trimmed_duration
+    // With alpha, letterboxing is transparent rather than black
+    let (to_rgba, pad_color) = match settings.alpha {
+        Some(_) => ("format=rgba,", ":color=black@0"),
+        None => ("", ""),
+    };
+    let normalize_filter = format!(
+        "{}scale={}:{}:force_original_aspect_ratio=decrease,pad={}:{}:(ow-iw)/2:(oh-ih)/2{},fps={}",
+        to_rgba, target_width, target_height, target_width, target_height, pad_color, target_fps
+    );
+
+    // Load the cursor track if a synthetic cursor was requested for this clip
+    let cursor_overlay = match (&settings.cursor_overlay, &clip.cursor_data_path) {
+        (Some(cursor_settings), Some(track_path)) => {
+            let track = CursorTrack::load(std::path::Path::new(track_path))?;
+            Some((cursor_settings, track))
+        }
+        _ => None,
+    };
+
+    // Inputs after the clip itself and the filter graph shared by every range
+    let mut extra_inputs: Vec<String> = Vec::new();
+    let mut filters = Vec::new();
+    let mut next_input = 1;
+    let mut video_label = "0:v".to_string();
+
+    // HDR clips look washed out when concatenated into an SDR export
+    let tone_map = settings.tone_map_hdr
+        && media_kind == MediaKind::Video
+        && extract_metadata(actual_video_path.clone())
+            .await
+            .is_ok_and(|metadata| metadata.is_hdr);
+    if tone_map {
+        filters.push(format!("[{}]{}[sdr]", video_label, TONE_MAP_SDR_FILTER));
+        video_label = "sdr".to_string();
+    }
+
+    // Grade the footage itself, before the cursor and frame are drawn on top
+    if let Some(correction) = &clip.color_correction {
+        correction.validate()?;
+    }
+    if let Some(chain) = clip
+        .color_correction
+        .as_ref()
+        .and_then(|correction| correction.filter_chain())
+    {
+        filters.push(format!("[{}]{}[graded]", video_label, chain));
+        video_label = "graded".to_string();
+    }
+
+    if let Some((cursor_settings, track)) = cursor_overlay {
+        // Move a generated cursor sprite along the smoothed track before normalizing
+        let sprite_size = cursor_settings.sprite_size(clip.height);
+        let script = build_sendcmd_script(
+            &track,
+            cursor_settings,
+            clip.width,
+            clip.height,
+            sprite_size,
         );
+        let script_path = work_dir.join(format!("cursor_{:03}.cmd", index));
+        fs::write(&script_path, script)
+            .map_err(|e| format!("Failed to write cursor script: {}", e))?;
+
+        extra_inputs.extend([
+            "-f".to_string(),
+            "lavfi".to_string(),
+            "-i".to_string(),
+            cursor_settings.sprite_source(sprite_size),
+        ]);
+
+        filters.push(format!(
+            "[{}]sendcmd=f={}[src];[src][{}:v]{}=x=-{}:y=-{}:shortest=1[cursor]",
+            video_label,
+            quote_filter_value(&script_path.to_string_lossy()),
+            next_input,
+            CURSOR_OVERLAY_FILTER,
+            sprite_size,
+            sprite_size
+        ));
+        next_input += 1;
+        video_label = "cursor".to_string();
+    }
+
+    // Crop video to the portrait frame; images are letterboxed by the normalize step
+    if let Some(reframe) = settings
+        .reframe
+        .as_ref()
+        .filter(|_| media_kind == MediaKind::Video)
+    {
+        let (width, height) = match extract_metadata(actual_video_path.clone()).await {
+            Ok(metadata) => (metadata.width, metadata.height),
+            Err(_) => (clip.width, clip.height),
+        };
+        let focus = match clip.reframe_focus {
+            Some(region) => Some(region.center(width, height)),
+            None => webcam_focus.filter(|_| reframe.follow_webcam),
+        };
+        let crop = reframe.crop(width, height, focus);
+        filters.push(format!("[{}]{}[reframed]", video_label, crop.filter()));
+        video_label = "reframed".to_string();
+    }
+
+    filters.push(format!("[{}]{}[norm]", video_label, normalize_filter));
+    video_label = "norm".to_string();
+
+    if let Some(frame) = &settings.window_frame {
+        let background_input = frame.background_input_args().map(|args| {
+            extra_inputs.extend(args);
+            format!("{}:v", next_input)
+        });
+
+        filters.push(frame.filter_graph(
+            &video_label,
+            background_input.as_deref(),
+            target_width,
+            target_height,
+            target_fps,
+            "framed",
+        ));
+        if background_input.is_some() {
+            next_input += 1;
+        }
+        video_label = "framed".to_string();
+    }
+
+    // Images have no audio; give them silence so every segment concatenates cleanly
+    let audio_map = if media_kind == MediaKind::Image {
+        extra_inputs.extend([
+            "-f".to_string(),
+            "lavfi".to_string(),
+            "-i".to_string(),
+            "anullsrc=r=48000:cl=stereo".to_string(),
+        ]);
+        format!("{}:a", next_input)
+    } else {
+        "0:a?".to_string()
+    };
+
+    let audio_chain = settings
+        .audio_filters
+        .as_ref()
+        .and_then(|filters| filters.filter_chain());
 
-        // Use FFmpeg to trim and normalize the clip
-        let output = Command::new(&ffmpeg_path)
+    for &(range_start, range_end) in &ranges {
+        let temp_output = work_dir.join(format!(
+            "segment_{:03}_{:03}.{}",
+            index,
+            segments.len(),
+            segment_extension(settings.alpha)
+        ));
+
+        // Use FFmpeg to trim and normalize the range
+        let mut command = Command::new(ffmpeg_path);
+        command
+            .args(&input_args)
             .arg("-i")
             .arg(&actual_video_path)
+            .args(&extra_inputs)
             .arg("-ss")
-            .arg(clip.trim_start.to_string())
+            .arg(range_start.to_string())
             .arg("-t")
-            .arg(trimmed_duration.to_string())
-            .arg("-vf")
-            .arg(format!("scale={}:{}:force_original_aspect_ratio=decrease,pad={}:{}:(ow-iw)/2:(oh-ih)/2,fps={}",
-                target_width, target_height, target_width, target_height, target_fps))
-            .arg("-c:v")
-            .arg("libx264")
-            .arg("-preset")
-            .arg("medium")
+            .arg((range_end - range_start).to_string())
+            .arg("-filter_complex")
+            .arg(filters.join(";"))
+            .arg("-map")
+            .arg(format!("[{}]", video_label))
+            .arg("-map")
+            .arg(&audio_map);
+
+        if let Some(chain) = &audio_chain {
+            command.arg("-af").arg(chain);
+        }
+
+        command
+            .args(video_codec_args(settings.alpha))
+            .args(&settings.extra_ffmpeg_args)
             .arg("-c:a")
-            .arg("aac")
+            .arg(audio_codec(settings.alpha))
             .arg("-ar")
             .arg("48000")
             .arg("-y")
-            .arg(&temp_output)
-            .output()
+            .arg(&temp_output);
+        let output = runner
+            .output(&mut command)
+            .await
             .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("FFmpeg failed for clip {}: {}", i, stderr));
+            return Err(ClipForgeError::Ffmpeg(format!(
+                "FFmpeg failed for clip {}: {}",
+                index, stderr
+            )));
+        }
+
+        segments.push(temp_output);
+    }
+
+    Ok(RenderedClip { segments, ranges })
+}
+
+/// Check that an alpha export can keep its transparency end to end
+fn validate_alpha_export(codec: AlphaCodec, output_path: &str) -> Result<(), ClipForgeError> {
+    let extension = std::path::Path::new(output_path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_lowercase());
+    if extension.as_deref() != Some(codec.extension()) {
+        return Err(ClipForgeError::InvalidInput(format!(
+            "Transparent exports with this codec must be saved as .{}",
+            codec.extension()
+        )));
+    }
+    Ok(())
+}
+
+/// Export a timeline, passing progress to `report` before each step
+///
+/// An error from `report` stops the export before the step starts; the export
+/// queue uses this to cancel jobs.
+pub async fn run_export(
+    clips: Vec<ClipData>,
+    output_path: String,
+    settings: ExportSettings,
+    report: &(dyn Fn(ExportProgress) -> Result<(), ClipForgeError> + Sync),
+) -> Result<(), ClipForgeError> {
+    export_with(clips, output_path, settings, report, &FfmpegRunner::Run).await
+}
+
+/// The FFmpeg commands exporting a timeline would run, without running them
+pub async fn preview_export(
+    clips: Vec<ClipData>,
+    output_path: String,
+    settings: ExportSettings,
+) -> Result<FfmpegPreview, ClipForgeError> {
+    let runner = FfmpegRunner::dry_run();
+    export_with(clips, output_path, settings, &|_| Ok(()), &runner).await?;
+    Ok(runner.into_preview())
+}
+
+async fn export_with(
+    clips: Vec<ClipData>,
+    output_path: String,
+    settings: ExportSettings,
+    report: &(dyn Fn(ExportProgress) -> Result<(), ClipForgeError> + Sync),
+    runner: &FfmpegRunner,
+) -> Result<(), ClipForgeError> {
+    println!("Exporting {} clips to: {}", clips.len(), output_path);
+
+    // Audio-only clips are mixed over the finished video rather than sequenced
+    let (audio_clips, clips): (Vec<ClipData>, Vec<ClipData>) = clips
+        .into_iter()
+        .partition(|clip| clip_media_kind(clip) == MediaKind::Audio);
+
+    if clips.is_empty() {
+        return Err(ClipForgeError::InvalidInput(
+            "No clips to export".to_string(),
+        ));
+    }
+
+    // Find ffmpeg executable
+    let ffmpeg_path = find_ffmpeg().ok_or_else(ClipForgeError::ffmpeg_missing)?;
+    settings.validate()?;
+    if let Some(codec) = settings.alpha {
+        validate_alpha_export(codec, &output_path)?;
+    }
+    // Get first clip's resolution and framerate to use for the output
+    if !settings.extra_ffmpeg_args.is_empty() {
+        println!(
+            "Extra FFmpeg arguments: {}",
+            ffmpeg_args::describe(&settings.extra_ffmpeg_args)
+        );
+    }
+    let format = SegmentFormat::for_timeline(&clips, settings.reframe.as_ref())
+        .ok_or_else(|| ClipForgeError::InvalidInput("No clips to export".to_string()))?;
+    let target_width = format.width;
+    let target_height = format.height;
+    let target_fps = format.fps;
+
+    // Create temp directory for intermediate files
+    let temp_dir = app_cache::unique_dir("exports", "export")?;
+
+    // A dropped share connection would leave a broken file behind, so exports
+    // to network volumes are built locally and copied over once finished
+    let destination = output_path;
+    let staged = !runner.is_dry_run()
+        && storage::network_volume(std::path::Path::new(&destination)).is_some();
+    let output_path = match std::path::Path::new(&destination).file_name() {
+        Some(file_name) if staged => temp_dir.join(file_name).to_string_lossy().to_string(),
+        _ => destination.clone(),
+    };
+
+    // Calculate total steps for progress (clips + gaps + concat)
+    let mut gaps_needed = 0;
+    for i in 0..clips.len() - 1 {
+        let current_end = clips[i].start_time + (clips[i].trim_end - clips[i].trim_start);
+        let next_start = clips[i + 1].start_time;
+        if next_start > current_end {
+            gaps_needed += 1;
         }
+    }
+    let stingers = settings
+        .stingers
+        .as_ref()
+        .filter(|stingers| !stingers.is_empty());
+    let mix_steps = usize::from(!audio_clips.is_empty());
+    let stinger_steps = usize::from(stingers.is_some());
+    let size_steps = usize::from(settings.target_size_mb.is_some());
+    // clips + gaps + final concat + audio mix + stingers + target size
+    let total_steps = clips.len() + gaps_needed + 1 + mix_steps + stinger_steps + size_steps;
+    let mut current_step = 0;
 
-        segment_files.push(temp_output);
+    // Sources still in iCloud have to be downloaded before FFmpeg can read them
+    if !runner.is_dry_run() {
+        for clip in clips.iter().chain(&audio_clips) {
+            let name = std::path::Path::new(&clip.video_path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| clip.video_path.clone());
+            storage::ensure_local(std::path::Path::new(&clip.video_path), &|progress| {
+                let _ = report(ExportProgress {
+                    current: current_step,
+                    total: total_steps,
+                    message: format!(
+                        "Downloading {} from iCloud ({:.0}%)",
+                        name,
+                        progress.percent()
+                    ),
+                });
+            })
+            .await?;
+        }
+    }
+
+    // Process each clip - trim and normalize to target resolution/fps
+    let mut segment_files = Vec::new();
+    let mut clip_ranges = Vec::new();
+    for (i, clip) in clips.iter().enumerate() {
+        current_step += 1;
+        report(ExportProgress {
+            current: current_step,
+            total: total_steps,
+            message: format!("Processing clip {} of {}", i + 1, clips.len()),
+        })?;
+
+        let trimmed_duration = clip.trim_end - clip.trim_start;
+        let cached = render_cache::lookup(clip, format, &settings);
+        if cached.is_none() && is_pip_clip(clip) {
+            report(ExportProgress {
+                current: current_step,
+                total: total_steps,
+                message: format!("Compositing PiP clip {} of {}", i + 1, clips.len()),
+            })?;
+        }
+        let rendered = match cached {
+            Some(cached) => {
+                if runner.is_dry_run() {
+                    runner.note(format!("Clip {} reuses a pre-rendered segment", i + 1));
+                }
+                cached
+            }
+            None => {
+                render_clip(&ffmpeg_path, clip, i, format, &settings, &temp_dir, runner).await?
+            }
+        };
+        segment_files.extend(rendered.segments);
+        clip_ranges.push(rendered.ranges);
 
         // Check if there's a gap before the next clip
         if i < clips.len() - 1 {
@@ -319,23 +884,29 @@ pub async fn export_timeline(
                 current_step += 1;
                 let gap_duration = next_start - current_end;
 
-                let _ = app.emit(
-                    "export-progress",
-                    ExportProgress {
-                        current: current_step,
-                        total: total_steps,
-                        message: format!("Creating gap ({:.1}s)", gap_duration),
-                    },
-                );
-                // Create black video for the gap
-                let black_output = temp_dir.join(format!("segment_{:03}.mp4", segment_files.len()));
-                let output = Command::new(&ffmpeg_path)
+                report(ExportProgress {
+                    current: current_step,
+                    total: total_steps,
+                    message: format!("Creating gap ({:.1}s)", gap_duration),
+                })?;
+                // Create black video for the gap, or transparent when keeping alpha
+                let black_output = temp_dir.join(format!(
+                    "segment_{:03}.{}",
+                    segment_files.len(),
+                    segment_extension(settings.alpha)
+                ));
+                let (gap_color, gap_format) = match settings.alpha {
+                    Some(_) => ("black@0", ",format=rgba"),
+                    None => ("black", ""),
+                };
+                let mut command = Command::new(&ffmpeg_path);
+                command
                     .arg("-f")
                     .arg("lavfi")
                     .arg("-i")
                     .arg(format!(
-                        "color=c=black:s={}x{}:r={}",
-                        target_width, target_height, target_fps
+                        "color=c={}:s={}x{}:r={}{}",
+                        gap_color, target_width, target_height, target_fps, gap_format
                     ))
                     .arg("-f")
                     .arg("lavfi")
@@ -343,20 +914,23 @@ pub async fn export_timeline(
                     .arg("anullsrc=r=48000:cl=stereo")
                     .arg("-t")
                     .arg(gap_duration.to_string())
-                    .arg("-c:v")
-                    .arg("libx264")
-                    .arg("-preset")
-                    .arg("medium")
+                    .args(video_codec_args(settings.alpha))
+                    .args(&settings.extra_ffmpeg_args)
                     .arg("-c:a")
-                    .arg("aac")
+                    .arg(audio_codec(settings.alpha))
                     .arg("-y")
-                    .arg(&black_output)
-                    .output()
+                    .arg(&black_output);
+                let output = runner
+                    .output(&mut command)
+                    .await
                     .map_err(|e| format!("Failed to create black frame: {}", e))?;
 
                 if !output.status.success() {
                     let stderr = String::from_utf8_lossy(&output.stderr);
-                    return Err(format!("Failed to create gap: {}", stderr));
+                    return Err(ClipForgeError::Ffmpeg(format!(
+                        "Failed to create gap: {}",
+                        stderr
+                    )));
                 }
 
                 segment_files.push(black_output);
@@ -365,14 +939,11 @@ pub async fn export_timeline(
     }
 
     current_step += 1;
-    let _ = app.emit(
-        "export-progress",
-        ExportProgress {
-            current: current_step,
-            total: total_steps,
-            message: "Finalizing export...".to_string(),
-        },
-    );
+    report(ExportProgress {
+        current: current_step,
+        total: total_steps,
+        message: "Finalizing export...".to_string(),
+    })?;
 
     // Create concat file for FFmpeg
     let concat_file = temp_dir.join("concat.txt");
@@ -388,25 +959,201 @@ pub async fn export_timeline(
     println!("Concatenating {} segments...", segment_files.len());
 
     // Concatenate all segments
-    let output = Command::new(&ffmpeg_path)
+    let mut command = Command::new(&ffmpeg_path);
+    command
         .arg("-f")
         .arg("concat")
         .arg("-safe")
         .arg("0")
         .arg("-i")
-        .arg(&concat_file)
-        .arg("-c")
-        .arg("copy")
-        .arg("-y")
-        .arg(&output_path)
-        .output()
+        .arg(&concat_file);
+
+    // Carry recording markers over as chapters
+    let chapters = collect_chapters(&clips, &clip_ranges);
+    if !chapters.is_empty() {
+        let chapters_file = temp_dir.join("chapters.txt");
+        fs::write(&chapters_file, ffmetadata_chapters(&chapters))
+            .map_err(|e| format!("Failed to write chapters file: {}", e))?;
+
+        command
+            .arg("-i")
+            .arg(&chapters_file)
+            .arg("-map")
+            .arg("0")
+            .arg("-map_metadata")
+            .arg("1")
+            .arg("-map_chapters")
+            .arg("1");
+    }
+
+    let subtitles = settings
+        .subtitles
+        .as_ref()
+        .filter(|subtitles| !subtitles.segments.is_empty());
+
+    match subtitles.filter(|subtitles| subtitles.burn_in) {
+        Some(subtitles) => {
+            // Burning in requires re-encoding the video during concat
+            let srt_path = temp_dir.join("subtitles.srt");
+            write_subtitles(&subtitles.segments, SubtitleFormat::Srt, &srt_path)?;
+
+            command
+                .arg("-vf")
+                .arg(burn_in_filter(&srt_path, &subtitles.style))
+                .args(video_codec_args(settings.alpha))
+                .args(&settings.extra_ffmpeg_args)
+                .arg("-c:a")
+                .arg("copy");
+        }
+        None => {
+            command.arg("-c").arg("copy");
+        }
+    }
+
+    // With audio clips to mix, concatenate to an intermediate file first
+    let concat_output = if audio_clips.is_empty() {
+        std::path::PathBuf::from(&output_path)
+    } else {
+        let extension = std::path::Path::new(&output_path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("mp4");
+        temp_dir.join(format!("concat_output.{}", extension))
+    };
+
+    command.arg("-y").arg(&concat_output);
+    let output = runner
+        .output(&mut command)
+        .await
         .map_err(|e| format!("Failed to run FFmpeg concat: {}", e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("FFmpeg concat failed: {}", stderr));
+        return Err(ClipForgeError::Ffmpeg(format!(
+            "FFmpeg concat failed: {}",
+            stderr
+        )));
     }
 
+    if !audio_clips.is_empty() {
+        current_step += 1;
+        report(ExportProgress {
+            current: current_step,
+            total: total_steps,
+            message: format!("Mixing {} audio clip(s)...", audio_clips.len()),
+        })?;
+
+        mix_audio_clips(
+            &ffmpeg_path,
+            &concat_output,
+            &audio_clips,
+            clips[0].start_time,
+            &output_path,
+            audio_codec(settings.alpha),
+            runner,
+        )
+        .await?;
+    }
+
+    // Later steps probe the finished file, which a dry run never writes
+    if runner.is_dry_run() {
+        if stingers.is_some() {
+            runner.note("Intro and outro stingers are joined after these commands");
+        }
+        if let Some(target_mb) = settings.target_size_mb {
+            runner.note(format!(
+                "The video is then re-encoded in two passes to fit into {} MB",
+                target_mb
+            ));
+        }
+        let _ = fs::remove_dir_all(&temp_dir);
+        return Ok(());
+    }
+
+    // The exported timeline now starts after the intro
+    let mut joined = None;
+    if let Some(stingers) = stingers {
+        current_step += 1;
+        report(ExportProgress {
+            current: current_step,
+            total: total_steps,
+            message: "Adding intro and outro...".to_string(),
+        })?;
+
+        // Stingers and target size encode synchronously, so keep them off the async workers
+        let (stingers, video_path) = (stingers.clone(), output_path.clone());
+        let applied = tokio::task::spawn_blocking(move || {
+            stingers.apply(std::path::Path::new(&video_path), &chapters, &[])
+        })
+        .await
+        .map_err(|e| format!("Stinger task failed: {}", e))?;
+        joined = Some(applied?);
+    }
+    let content_offset = joined.map_or(0.0, |joined| joined.content_offset);
+
+    let timeline_end = clips
+        .iter()
+        .map(|clip| clip.start_time + (clip.trim_end - clip.trim_start))
+        .fold(0.0, f64::max);
+    let mut duration = joined.map_or(timeline_end - clips[0].start_time, |joined| joined.duration);
+    // Skipped silence makes the video shorter than the timeline, so measure what was written
+    if joined.is_none() && settings.skip_silence.is_some() {
+        if let Ok(metadata) = extract_metadata(output_path.clone()).await {
+            duration = metadata.duration;
+        }
+    }
+
+    if let Some(target_mb) = settings.target_size_mb {
+        current_step += 1;
+        report(ExportProgress {
+            current: current_step,
+            total: total_steps,
+            message: format!("Fitting video into {} MB...", target_mb),
+        })?;
+
+        let (video_path, work_dir) = (output_path.clone(), temp_dir.clone());
+        tokio::task::spawn_blocking(move || {
+            target_size::fit_to_size(
+                std::path::Path::new(&video_path),
+                duration,
+                target_mb,
+                &work_dir,
+            )
+        })
+        .await
+        .map_err(|e| format!("Target size task failed: {}", e))??;
+    }
+
+    if staged {
+        storage::copy_to_volume(
+            std::path::Path::new(&output_path),
+            std::path::Path::new(&destination),
+        )
+        .await?;
+    }
+
+    if let Some((subtitles, format)) =
+        subtitles.and_then(|subtitles| subtitles.sidecar.map(|format| (subtitles, format)))
+    {
+        let mut segments = subtitles.segments.clone();
+        for segment in &mut segments {
+            segment.start += content_offset;
+            segment.end += content_offset;
+        }
+        let path = sidecar_path(std::path::Path::new(&destination), format);
+        write_subtitles(&segments, format, &path)?;
+        println!("Wrote subtitles to: {}", path.display());
+    }
+
+    // Notify webhooks with the timeline's exported length
+    webhooks::fire(WebhookPayload::new(
+        WebhookEvent::ExportFinished,
+        None,
+        Some(destination),
+        Some(duration),
+    ));
+
     // Clean up temp files
-    fs::remove_dir_all(&temp_dir).map_err(|e| format!("Failed to clean up temp files: {}", e))?;    Ok(())
+    fs::remove_dir_all(&temp_dir).map_err(|e| format!("Failed to clean up temp files: {}", e))?;
+    Ok(())
 }
@@ -0,0 +1,487 @@
+// Export queue
+//
+// Lets several exports (different timelines, or one timeline with different
+// presets) be queued at once. Jobs start in the order they were added, with at
+// most `max_parallel` running together. Each job reports progress through
+// "export-job:progress" and its final state through "export-job:finished";
+// queued jobs can be cancelled outright and running ones are stopped along
+// with the FFmpeg they are waiting on.
+
+use super::export::{run_export, ClipData, ExportProgress, ExportSettings};
+use crate::error::ClipForgeError;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Exports that run at the same time unless changed
+pub const DEFAULT_MAX_PARALLEL: usize = 1;
+
+/// Upper bound for `set_export_parallelism`; each export runs its own FFmpeg
+pub const MAX_PARALLEL_LIMIT: usize = 4;
+
+/// Finished jobs kept in the list before the oldest are dropped
+const MAX_FINISHED_JOBS: usize = 50;
+
+/// How often a running job checks whether it was cancelled
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Lifecycle of a queued export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportJobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl ExportJobStatus {
+    fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            ExportJobStatus::Completed | ExportJobStatus::Failed | ExportJobStatus::Cancelled
+        )
+    }
+}
+
+/// A job as reported to the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportJob {
+    pub id: String,
+    /// Shown in the queue, e.g. the project or preset name
+    pub label: Option<String>,
+    pub output_path: String,
+    pub status: ExportJobStatus,
+    pub progress: Option<ExportProgress>,
+    pub error: Option<ClipForgeError>,
+    /// Milliseconds since epoch
+    pub queued_at: u64,
+    pub started_at: Option<u64>,
+    pub finished_at: Option<u64>,
+}
+
+/// Payload of "export-job:progress"
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportJobProgress {
+    pub job_id: String,
+    #[serde(flatten)]
+    pub progress: ExportProgress,
+}
+
+struct QueuedExport {
+    job: ExportJob,
+    /// Taken when the job starts
+    input: Option<(Vec<ClipData>, ExportSettings)>,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// A job taken off the queue to run
+struct StartedExport {
+    id: String,
+    output_path: String,
+    clips: Vec<ClipData>,
+    settings: ExportSettings,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Queued, running and recently finished exports
+pub struct ExportQueue {
+    jobs: Vec<QueuedExport>,
+    max_parallel: usize,
+    next_id: u64,
+}
+
+pub type ExportQueueState = Arc<Mutex<ExportQueue>>;
+
+impl Default for ExportQueue {
+    fn default() -> Self {
+        Self {
+            jobs: Vec::new(),
+            max_parallel: DEFAULT_MAX_PARALLEL,
+            next_id: 1,
+        }
+    }
+}
+
+impl ExportQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push(
+        &mut self,
+        clips: Vec<ClipData>,
+        output_path: String,
+        settings: ExportSettings,
+        label: Option<String>,
+        now: u64,
+    ) -> ExportJob {
+        let job = ExportJob {
+            id: format!("export_{}_{}", now, self.next_id),
+            label,
+            output_path,
+            status: ExportJobStatus::Queued,
+            progress: None,
+            error: None,
+            queued_at: now,
+            started_at: None,
+            finished_at: None,
+        };
+        self.next_id += 1;
+        self.jobs.push(QueuedExport {
+            job: job.clone(),
+            input: Some((clips, settings)),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        });
+        job
+    }
+
+    fn get_mut(&mut self, id: &str) -> Option<&mut QueuedExport> {
+        self.jobs.iter_mut().find(|queued| queued.job.id == id)
+    }
+
+    /// Mark queued jobs as running while there is room, returning what to start
+    fn start_ready(&mut self, now: u64) -> Vec<StartedExport> {
+        let running = self
+            .jobs
+            .iter()
+            .filter(|queued| queued.job.status == ExportJobStatus::Running)
+            .count();
+        let mut started = Vec::new();
+
+        for queued in &mut self.jobs {
+            if running + started.len() >= self.max_parallel {
+                break;
+            }
+            if queued.job.status != ExportJobStatus::Queued {
+                continue;
+            }
+            if let Some((clips, settings)) = queued.input.take() {
+                queued.job.status = ExportJobStatus::Running;
+                queued.job.started_at = Some(now);
+                started.push(StartedExport {
+                    id: queued.job.id.clone(),
+                    output_path: queued.job.output_path.clone(),
+                    clips,
+                    settings,
+                    cancelled: queued.cancelled.clone(),
+                });
+            }
+        }
+        started
+    }
+
+    /// Record how a job ended and drop the oldest finished jobs past the limit
+    fn finish(
+        &mut self,
+        id: &str,
+        result: Result<(), ClipForgeError>,
+        now: u64,
+    ) -> Option<ExportJob> {
+        let queued = self.get_mut(id)?;
+        let cancelled = queued.cancelled.load(Ordering::SeqCst);
+        queued.job.finished_at = Some(now);
+        match result {
+            Ok(()) => queued.job.status = ExportJobStatus::Completed,
+            Err(_) if cancelled => queued.job.status = ExportJobStatus::Cancelled,
+            Err(e) => {
+                queued.job.status = ExportJobStatus::Failed;
+                queued.job.error = Some(e);
+            }
+        }
+        let job = queued.job.clone();
+
+        let finished = self
+            .jobs
+            .iter()
+            .filter(|queued| queued.job.status.is_finished())
+            .count();
+        let mut excess = finished.saturating_sub(MAX_FINISHED_JOBS);
+        self.jobs.retain(|queued| {
+            if excess > 0 && queued.job.status.is_finished() {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+
+        Some(job)
+    }
+
+    fn list(&self) -> Vec<ExportJob> {
+        self.jobs.iter().map(|queued| queued.job.clone()).collect()
+    }
+}
+
+fn now_millis() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+/// Start as many queued jobs as the parallelism limit allows
+fn pump(app: &AppHandle) {
+    let state = app.state::<ExportQueueState>();
+    let started = match state.lock() {
+        Ok(mut queue) => queue.start_ready(now_millis()),
+        Err(_) => return,
+    };
+
+    for export in started {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            run_job(&app, export).await;
+            pump(&app);
+        });
+    }
+}
+
+/// Resolve once a job has been cancelled
+async fn wait_for_cancel(cancelled: &AtomicBool) {
+    while !cancelled.load(Ordering::SeqCst) {
+        tokio::time::sleep(CANCEL_POLL_INTERVAL).await;
+    }
+}
+
+async fn run_job(app: &AppHandle, export: StartedExport) {
+    let StartedExport {
+        id,
+        output_path,
+        clips,
+        settings,
+        cancelled,
+    } = export;
+    println!("[ExportQueue] Starting {} -> {}", id, output_path);
+
+    let report = |progress: ExportProgress| {
+        if cancelled.load(Ordering::SeqCst) {
+            return Err(ClipForgeError::Other("Export cancelled".to_string()));
+        }
+        if let Ok(mut queue) = app.state::<ExportQueueState>().lock() {
+            if let Some(queued) = queue.get_mut(&id) {
+                queued.job.progress = Some(progress.clone());
+            }
+        }
+        let _ = app.emit(
+            "export-job:progress",
+            ExportJobProgress {
+                job_id: id.clone(),
+                progress,
+            },
+        );
+        Ok(())
+    };
+    // An existing file at the output path is the user's until the export replaces it
+    let existed = std::path::Path::new(&output_path).exists();
+
+    // Dropping the export kills the FFmpeg process it is waiting on
+    let result = tokio::select! {
+        result = run_export(clips, output_path.clone(), settings, &report) => result,
+        _ = wait_for_cancel(&cancelled) => {
+            Err(ClipForgeError::Other("Export cancelled".to_string()))
+        }
+    };
+
+    // Don't leave a half-written file behind a cancelled or failed job
+    if result.is_err() && !existed {
+        let _ = std::fs::remove_file(&output_path);
+    }
+
+    let job = app
+        .state::<ExportQueueState>()
+        .lock()
+        .ok()
+        .and_then(|mut queue| queue.finish(&id, result, now_millis()));
+    if let Some(job) = job {
+        println!("[ExportQueue] {} finished: {:?}", id, job.status);
+        let _ = app.emit("export-job:finished", &job);
+    }
+}
+
+/// Add an export to the queue; it starts as soon as a slot is free
+///
+/// Returns the queued job, whose id is used by `cancel_export_job` and the
+/// "export-job:progress" / "export-job:finished" events
+#[tauri::command]
+pub async fn enqueue_export(
+    clips: Vec<ClipData>,
+    output_path: String,
+    settings: Option<ExportSettings>,
+    label: Option<String>,
+    app: AppHandle,
+    state: State<'_, ExportQueueState>,
+) -> Result<ExportJob, ClipForgeError> {
+    if clips.is_empty() {
+        return Err(ClipForgeError::InvalidInput(
+            "No clips to export".to_string(),
+        ));
+    }
+    let settings = settings.unwrap_or_default();
+    settings.validate()?;
+
+    let job = {
+        let mut queue = state.lock()?;
+        let busy = queue.jobs.iter().any(|queued| {
+            !queued.job.status.is_finished() && queued.job.output_path == output_path
+        });
+        if busy {
+            return Err(ClipForgeError::InvalidInput(format!(
+                "An export to {} is already queued",
+                output_path
+            )));
+        }
+        queue.push(clips, output_path, settings, label, now_millis())
+    };
+
+    pump(&app);
+    Ok(job)
+}
+
+/// List queued, running and recently finished exports in queue order
+#[tauri::command]
+pub async fn list_export_jobs(
+    state: State<'_, ExportQueueState>,
+) -> Result<Vec<ExportJob>, ClipForgeError> {
+    Ok(state.lock()?.list())
+}
+
+/// Cancel a job; queued jobs are dropped at once, running ones stop before their next step
+#[tauri::command]
+pub async fn cancel_export_job(
+    id: String,
+    app: AppHandle,
+    state: State<'_, ExportQueueState>,
+) -> Result<ExportJob, ClipForgeError> {
+    let job = {
+        let mut queue = state.lock()?;
+        let queued = queue
+            .get_mut(&id)
+            .ok_or_else(|| ClipForgeError::NotFound(format!("Export job {} not found", id)))?;
+
+        match queued.job.status {
+            ExportJobStatus::Queued => {
+                queued.input = None;
+                queued.job.status = ExportJobStatus::Cancelled;
+                queued.job.finished_at = Some(now_millis());
+            }
+            ExportJobStatus::Running => queued.cancelled.store(true, Ordering::SeqCst),
+            _ => {
+                return Err(ClipForgeError::InvalidInput(format!(
+                    "Export job {} has already finished",
+                    id
+                )))
+            }
+        }
+        queued.job.clone()
+    };
+
+    if job.status == ExportJobStatus::Cancelled {
+        let _ = app.emit("export-job:finished", &job);
+    }
+    Ok(job)
+}
+
+/// Set how many exports may run at once (1-4)
+#[tauri::command]
+pub async fn set_export_parallelism(
+    max_parallel: usize,
+    app: AppHandle,
+    state: State<'_, ExportQueueState>,
+) -> Result<usize, ClipForgeError> {
+    let max_parallel = max_parallel.clamp(1, MAX_PARALLEL_LIMIT);
+    state.lock()?.max_parallel = max_parallel;
+
+    pump(&app);
+    Ok(max_parallel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_starts_up_to_max_parallel_in_order() {
+        let mut queue = ExportQueue::new();
+        queue.max_parallel = 2;
+        queue.push(
+            Vec::new(),
+            "/tmp/out_0.mp4".to_string(),
+            ExportSettings::default(),
+            None,
+            1000,
+        );
+        queue.push(
+            Vec::new(),
+            "/tmp/out_1.mp4".to_string(),
+            ExportSettings::default(),
+            None,
+            1000,
+        );
+        queue.push(
+            Vec::new(),
+            "/tmp/out_2.mp4".to_string(),
+            ExportSettings::default(),
+            None,
+            1000,
+        );
+        let started = queue.start_ready(2000);
+        assert_eq!(started.len(), 2);
+        assert_eq!(started[0].output_path, "/tmp/out_0.mp4");
+        assert_eq!(started[1].output_path, "/tmp/out_1.mp4");
+        assert!(queue.start_ready(2000).is_empty());
+
+        let id = started[0].id.clone();
+        let job = queue.finish(&id, Ok(()), 3000).unwrap();
+        assert_eq!(job.status, ExportJobStatus::Completed);
+
+        let started = queue.start_ready(3000);
+        assert_eq!(started.len(), 1);
+        assert_eq!(started[0].output_path, "/tmp/out_2.mp4");
+    }
+
+    #[test]
+    fn test_cancelled_running_job_is_not_failed() {
+        let mut queue = ExportQueue::new();
+        queue.max_parallel = 1;
+        queue.push(
+            Vec::new(),
+            "/tmp/out_0.mp4".to_string(),
+            ExportSettings::default(),
+            None,
+            1000,
+        );
+        let export = queue.start_ready(2000).remove(0);
+        export.cancelled.store(true, Ordering::SeqCst);
+
+        let job = queue
+            .finish(&export.id, Err("Export cancelled".into()), 3000)
+            .unwrap();
+        assert_eq!(job.status, ExportJobStatus::Cancelled);
+        assert!(job.error.is_none());
+    }
+
+    #[test]
+    fn test_ids_are_unique() {
+        let mut queue = ExportQueue::new();
+        queue.push(
+            Vec::new(),
+            "/tmp/out_0.mp4".to_string(),
+            ExportSettings::default(),
+            None,
+            1000,
+        );
+        queue.push(
+            Vec::new(),
+            "/tmp/out_1.mp4".to_string(),
+            ExportSettings::default(),
+            None,
+            1000,
+        );
+        let jobs = queue.list();
+        assert_ne!(jobs[0].id, jobs[1].id);
+    }
+}
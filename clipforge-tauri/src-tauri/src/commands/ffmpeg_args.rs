@@ -0,0 +1,178 @@
+// Extra FFmpeg arguments
+//
+// Power users can pass encoder tuning flags (e.g. `-tune animation` or
+// `-x264-params keyint=60`) through to recording and export encodes. Arguments
+// come in flag/value pairs; only encoder tuning flags are accepted, values are
+// limited to plain characters (no slashes, quotes or spaces), and encoder
+// sub-options that read or write files (`stats=`, `dump-yuv=`, `qpfile=`, ...)
+// are rejected, so the escape hatch cannot add inputs, outputs or filters. They
+// are appended after ClipForge's own encoder arguments, so a repeated flag like
+// `-preset` overrides the default. Every encode that applies them validates
+// them first, including background renders.
+
+/// Flags that may be passed through, each followed by exactly one value
+const ALLOWED_FLAGS: &[&str] = &[
+    "-preset",
+    "-tune",
+    "-crf",
+    "-qp",
+    "-profile:v",
+    "-level",
+    "-x264-params",
+    "-x265-params",
+    "-g",
+    "-keyint_min",
+    "-bf",
+    "-refs",
+    "-sc_threshold",
+    "-b:v",
+    "-maxrate",
+    "-minrate",
+    "-bufsize",
+    "-rc-lookahead",
+    "-aq-mode",
+    "-aq-strength",
+    "-threads",
+    "-cpu-used",
+    "-deadline",
+    "-row-mt",
+    "-tile-columns",
+    "-b:a",
+];
+
+/// Flags whose value is a list of encoder `key=value` sub-options
+const SUB_OPTION_FLAGS: &[&str] = &["-x264-params", "-x265-params"];
+
+/// x264 and x265 sub-options that take a file path
+const PATH_SUB_OPTIONS: &[&str] = &[
+    "stats",
+    "dump-yuv",
+    "qpfile",
+    "cqmfile",
+    "tcfile-in",
+    "tcfile-out",
+    "input",
+    "output",
+    "recon",
+    "csv",
+    "zonefile",
+    "scaling-list",
+    "lambda-file",
+    "analysis-save",
+    "analysis-load",
+    "analysis-reuse-file",
+    "dolby-vision-rpu",
+    "dhdr10-info",
+    "hdr10-opt-file",
+];
+
+/// Longest accepted value, in bytes
+const MAX_VALUE_LENGTH: usize = 256;
+
+fn is_allowed_value(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_VALUE_LENGTH
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._:=,+-".contains(c))
+        // A leading dash would be read as another flag unless it is a number
+        && (!value.starts_with('-') || value.parse::<f64>().is_ok())
+}
+
+/// The first sub-option in a `-x264-params` style value that names a file
+fn path_sub_option(value: &str) -> Option<&str> {
+    value
+        .split(':')
+        .filter_map(|option| option.split('=').next())
+        .find(|key| {
+            // x264 accepts underscores in place of dashes
+            let key = key.to_ascii_lowercase().replace('_', "-");
+            PATH_SUB_OPTIONS.contains(&key.as_str())
+        })
+}
+
+/// Check extra arguments against the allowlist
+pub fn validate_extra_args(args: &[String]) -> Result<(), String> {
+    if !args.len().is_multiple_of(2) {
+        return Err("Extra FFmpeg arguments must be flag and value pairs".to_string());
+    }
+
+    for pair in args.chunks(2) {
+        let (flag, value) = (&pair[0], &pair[1]);
+        if !ALLOWED_FLAGS.contains(&flag.as_str()) {
+            return Err(format!(
+                "FFmpeg flag '{}' is not allowed; allowed flags are {}",
+                flag,
+                ALLOWED_FLAGS.join(", ")
+            ));
+        }
+        if !is_allowed_value(value) {
+            return Err(format!(
+                "Invalid value '{}' for FFmpeg flag {}",
+                value, flag
+            ));
+        }
+        if SUB_OPTION_FLAGS.contains(&flag.as_str()) {
+            if let Some(key) = path_sub_option(value) {
+                return Err(format!(
+                    "Encoder option '{}' in {} reads or writes a file and is not allowed",
+                    key, flag
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Arguments as they are passed to FFmpeg, for logs
+pub fn describe(args: &[String]) -> String {
+    args.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accepts_tuning_flags() {
+        assert!(validate_extra_args(&[
+            "-tune".to_string(),
+            "animation".to_string(),
+            "-x264-params".to_string(),
+            "keyint=60:min-keyint=30:no-scenecut=1".to_string(),
+            "-level".to_string(),
+            "4.2".to_string(),
+            "-threads".to_string(),
+            "0".to_string()
+        ])
+        .is_ok());
+        assert!(validate_extra_args(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_unsafe_arguments() {
+        // Inputs, outputs and filters are not tuning flags
+        assert!(validate_extra_args(&["-i".to_string(), "other.mp4".to_string()]).is_err());
+        assert!(validate_extra_args(&["-vf".to_string(), "scale=2:2".to_string()]).is_err());
+        // Values cannot name files or smuggle in another flag
+        assert!(
+            validate_extra_args(&["-x264-params".to_string(), "stats=/tmp/x.log".to_string()])
+                .is_err()
+        );
+        assert!(
+            validate_extra_args(&["-x264-params".to_string(), "stats=x.log".to_string()]).is_err()
+        );
+        assert!(validate_extra_args(&[
+            "-x264-params".to_string(),
+            "keyint=60:dump_yuv=a.yuv".to_string()
+        ])
+        .is_err());
+        assert!(
+            validate_extra_args(&["-x265-params".to_string(), "csv=frames.csv".to_string()])
+                .is_err()
+        );
+        assert!(validate_extra_args(&["-tune".to_string(), "-y".to_string()]).is_err());
+        assert!(validate_extra_args(&["-tune".to_string()]).is_err());
+        assert!(validate_extra_args(&["-sc_threshold".to_string(), "-1".to_string()]).is_ok());
+    }
+}
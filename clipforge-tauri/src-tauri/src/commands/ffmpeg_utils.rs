@@ -11,6 +11,26 @@ pub fn find_ffmpeg() -> Option<PathBuf> {
     find_executable("ffmpeg")
 }
 
+/// Find a whisper.cpp command line binary in common locations
+pub fn find_whisper() -> Option<PathBuf> {
+    ["whisper-cli", "whisper-cpp", "whisper"]
+        .iter()
+        .find_map(|name| find_executable(name))
+}
+
+/// Find the virtual camera companion in common locations
+pub fn find_virtual_camera_helper() -> Option<PathBuf> {
+    find_executable("clipforge-vcam")
+}
+
+/// Quote a filter option value such as a file path
+///
+/// Nothing is special inside FFmpeg's single quotes, so a quote in the value
+/// closes the quoting, is escaped on its own and then reopens it
+pub fn quote_filter_value(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
 fn find_executable(name: &str) -> Option<PathBuf> {
     // First, try to find it in PATH
     if let Ok(output) = Command::new("which").arg(name).output() {
@@ -0,0 +1,141 @@
+// Global shortcuts from the settings
+//
+// The shortcuts in `HotkeySettings` are registered when the settings load and
+// again whenever they change. Toggle Recording stops the current recording;
+// with nothing recording it emits "hotkey:start-recording" so the frontend can
+// start one with the source it has selected. The microphone shortcut is not
+// registered here: recordings without a hotkey of their own use it for as long
+// as they run.
+
+use super::recording::replay_buffer::save_replay;
+use super::recording::{
+    add_marker, pause_recording, resume_recording, stop_recording, RecordingManagerState,
+    RecordingStatus,
+};
+use super::settings::HotkeySettings;
+use crate::error::ClipForgeError;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+/// App action bound to a shortcut
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HotkeyAction {
+    ToggleRecording,
+    TogglePause,
+    AddMarker,
+    SaveReplay,
+}
+
+/// Shortcuts that are set, with the action each one runs
+fn bindings(hotkeys: &HotkeySettings) -> Vec<(&str, HotkeyAction)> {
+    [
+        (&hotkeys.toggle_recording, HotkeyAction::ToggleRecording),
+        (&hotkeys.toggle_pause, HotkeyAction::TogglePause),
+        (&hotkeys.add_marker, HotkeyAction::AddMarker),
+        (&hotkeys.save_replay, HotkeyAction::SaveReplay),
+    ]
+    .into_iter()
+    .filter_map(|(shortcut, action)| Some((shortcut.as_deref()?, action)))
+    .collect()
+}
+
+async fn run_action(app: &AppHandle, action: HotkeyAction) -> Result<(), ClipForgeError> {
+    let state = app.state::<RecordingManagerState>();
+    let status = state
+        .lock()?
+        .get_current_recording()
+        .map(|recording| recording.status);
+
+    match (action, status) {
+        (
+            HotkeyAction::ToggleRecording,
+            Some(RecordingStatus::Recording | RecordingStatus::Paused),
+        ) => stop_recording(state, app.clone()).await.map(|_| ()),
+        (HotkeyAction::ToggleRecording, _) => {
+            let _ = app.emit("hotkey:start-recording", ());
+            Ok(())
+        }
+        (HotkeyAction::TogglePause, Some(RecordingStatus::Paused)) => {
+            resume_recording(state, app.clone()).await.map(|_| ())
+        }
+        (HotkeyAction::TogglePause, _) => pause_recording(state, app.clone()).await.map(|_| ()),
+        (HotkeyAction::AddMarker, _) => add_marker(None, state, app.clone()).await.map(|_| ()),
+        (HotkeyAction::SaveReplay, _) => save_replay(None, app.clone()).await.map(|_| ()),
+    }
+}
+
+/// Register every shortcut that is set
+fn register(app: &AppHandle, hotkeys: &HotkeySettings) -> Result<(), String> {
+    for (shortcut, action) in bindings(hotkeys) {
+        app.global_shortcut()
+            .on_shortcut(shortcut, move |app, _shortcut, event| {
+                if event.state != ShortcutState::Pressed {
+                    return;
+                }
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = run_action(&app, action).await {
+                        println!("[Hotkeys] {:?} failed: {}", action, e);
+                    }
+                });
+            })
+            .map_err(|e| format!("Failed to register hotkey {}: {}", shortcut, e))?;
+    }
+    Ok(())
+}
+
+fn unregister(app: &AppHandle, hotkeys: &HotkeySettings) {
+    for (shortcut, _) in bindings(hotkeys) {
+        // Shortcuts that failed to register are not an error here
+        let _ = app.global_shortcut().unregister(shortcut);
+    }
+}
+
+/// Register the shortcuts from saved settings at startup
+pub fn register_hotkeys(app: &AppHandle, hotkeys: &HotkeySettings) {
+    if let Err(e) = register(app, hotkeys) {
+        println!("[Hotkeys] {}", e);
+    }
+}
+
+/// Swap the registered shortcuts for a new set
+///
+/// If any new shortcut can't be registered the previous set is restored.
+pub fn replace_hotkeys(
+    app: &AppHandle,
+    previous: &HotkeySettings,
+    hotkeys: &HotkeySettings,
+) -> Result<(), String> {
+    if previous == hotkeys {
+        return Ok(());
+    }
+
+    unregister(app, previous);
+    if let Err(e) = register(app, hotkeys) {
+        unregister(app, hotkeys);
+        register_hotkeys(app, previous);
+        return Err(e);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bindings_skip_unset_shortcuts() {
+        let hotkeys = HotkeySettings {
+            toggle_recording: Some("CmdOrCtrl+Shift+R".to_string()),
+            add_marker: Some("CmdOrCtrl+Shift+M".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            bindings(&hotkeys),
+            vec![
+                ("CmdOrCtrl+Shift+R", HotkeyAction::ToggleRecording),
+                ("CmdOrCtrl+Shift+M", HotkeyAction::AddMarker),
+            ]
+        );
+    }
+}
@@ -0,0 +1,453 @@
+// Project interchange export
+//
+// Converts the timeline clip list into EDL, FCPXML or Premiere (xmeml) so a
+// project can be finished in an external editor
+
+use super::export::ClipData;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Supported interchange formats
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InterchangeFormat {
+    /// CMX 3600 edit decision list
+    Edl,
+    /// Final Cut Pro X XML (1.9)
+    Fcpxml,
+    /// Premiere Pro / Final Cut 7 XML (xmeml v4)
+    Premiere,
+}
+
+/// Timeline clip expressed in whole frames
+struct FrameClip<'a> {
+    path: &'a str,
+    name: String,
+    /// Position on the timeline (relative to the first clip)
+    record_in: u64,
+    /// First source frame used
+    source_in: u64,
+    /// Number of frames used
+    length: u64,
+    /// Length of the whole source file
+    source_length: u64,
+    has_audio: bool,
+}
+
+/// Timeline frame rate as editors describe it
+///
+/// NTSC rates such as 29.97 run at `timebase` * 1000/1001 frames per second
+/// but count timecode in whole `timebase` frames
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FrameRate {
+    timebase: u64,
+    ntsc: bool,
+}
+
+impl FrameRate {
+    fn of(fps: f64) -> Self {
+        let timebase = fps.round() as u64;
+        if timebase == 0 {
+            return Self {
+                timebase: 30,
+                ntsc: false,
+            };
+        }
+        let ntsc_fps = timebase as f64 * 1000.0 / 1001.0;
+        Self {
+            timebase,
+            ntsc: (fps - ntsc_fps).abs() < (fps - timebase as f64).abs(),
+        }
+    }
+
+    fn fps(self) -> f64 {
+        if self.ntsc {
+            self.timebase as f64 * 1000.0 / 1001.0
+        } else {
+            self.timebase as f64
+        }
+    }
+
+    /// Length of one frame in seconds as (numerator, denominator)
+    fn frame_duration(self) -> (u64, u64) {
+        if self.ntsc {
+            (1001, self.timebase * 1000)
+        } else {
+            (1, self.timebase)
+        }
+    }
+}
+
+fn timeline_rate(clips: &[ClipData]) -> FrameRate {
+    FrameRate::of(clips.first().map_or(30.0, |clip| clip.frame_rate))
+}
+
+fn to_frames(seconds: f64, rate: FrameRate) -> u64 {
+    (seconds.max(0.0) * rate.fps()).round() as u64
+}
+
+fn frame_clips(clips: &[ClipData], rate: FrameRate) -> Vec<FrameClip<'_>> {
+    let timeline_start = clips.first().map(|c| c.start_time).unwrap_or(0.0);
+
+    clips
+        .iter()
+        .map(|clip| FrameClip {
+            path: &clip.video_path,
+            name: Path::new(&clip.video_path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("clip")
+                .to_string(),
+            record_in: to_frames(clip.start_time - timeline_start, rate),
+            source_in: to_frames(clip.trim_start, rate),
+            length: to_frames(clip.trim_end - clip.trim_start, rate),
+            source_length: to_frames(clip.duration, rate),
+            has_audio: clip.media_type.as_deref() != Some("image"),
+        })
+        .collect()
+}
+
+/// Format a frame count as a non-drop-frame SMPTE timecode
+pub fn timecode(frames: u64, fps: u64) -> String {
+    let seconds = frames / fps;
+    format!(
+        "{:02}:{:02}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds / 60) % 60,
+        seconds % 60,
+        frames % fps
+    )
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Percent-encode a filesystem path as a file:// URL
+fn file_url(path: &str) -> String {
+    let mut url = String::from("file://");
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                url.push(byte as char)
+            }
+            _ => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    url
+}
+
+/// Render a CMX 3600 EDL
+pub fn build_edl(clips: &[ClipData], title: &str) -> String {
+    let rate = timeline_rate(clips);
+    let fps = rate.timebase;
+    let mut edl = format!("TITLE: {}\nFCM: NON-DROP FRAME\n", title);
+
+    for (i, clip) in frame_clips(clips, rate).iter().enumerate() {
+        let channels = if clip.has_audio { "B" } else { "V" };
+        edl.push_str(&format!(
+            "\n{:03}  AX       {:<4} C        {} {} {} {}\n* FROM CLIP NAME: {}\n* SOURCE FILE: {}\n",
+            i + 1,
+            channels,
+            timecode(clip.source_in, fps),
+            timecode(clip.source_in + clip.length, fps),
+            timecode(clip.record_in, fps),
+            timecode(clip.record_in + clip.length, fps),
+            clip.name,
+            clip.path
+        ));
+    }
+
+    edl
+}
+
+/// Render an FCPXML 1.9 document
+pub fn build_fcpxml(clips: &[ClipData], title: &str) -> String {
+    let rate = timeline_rate(clips);
+    let frame_clips = frame_clips(clips, rate);
+    let (width, height) = clips
+        .first()
+        .map(|c| (c.width, c.height))
+        .unwrap_or((1920, 1080));
+    let (frame_num, frame_den) = rate.frame_duration();
+    let time = |frames: u64| format!("{}/{}s", frames * frame_num, frame_den);
+
+    let mut resources = format!(
+        "    <format id=\"r0\" frameDuration=\"{}\" width=\"{}\" height=\"{}\"/>\n",
+        time(1),
+        width,
+        height
+    );
+    let mut spine = String::new();
+    let mut position = 0;
+
+    for (i, clip) in frame_clips.iter().enumerate() {
+        let asset_id = format!("r{}", i + 1);
+        resources.push_str(&format!(
+            "    <asset id=\"{}\" name=\"{}\" src=\"{}\" start=\"0s\" duration=\"{}\" hasVideo=\"1\" hasAudio=\"{}\" format=\"r0\"/>\n",
+            asset_id,
+            escape_xml(&clip.name),
+            escape_xml(&file_url(clip.path)),
+            time(clip.source_length.max(clip.source_in + clip.length)),
+            if clip.has_audio { 1 } else { 0 }
+        ));
+
+        // The spine is contiguous, so empty timeline space becomes a gap
+        if clip.record_in > position {
+            spine.push_str(&format!(
+                "            <gap name=\"Gap\" offset=\"{}\" duration=\"{}\"/>\n",
+                time(position),
+                time(clip.record_in - position)
+            ));
+        }
+
+        spine.push_str(&format!(
+            "            <asset-clip ref=\"{}\" name=\"{}\" offset=\"{}\" start=\"{}\" duration=\"{}\"/>\n",
+            asset_id,
+            escape_xml(&clip.name),
+            time(clip.record_in.max(position)),
+            time(clip.source_in),
+            time(clip.length)
+        ));
+        position = clip.record_in.max(position) + clip.length;
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE fcpxml>\n<fcpxml version=\"1.9\">\n  <resources>\n{}  </resources>\n  <library>\n    <event name=\"ClipForge\">\n      <project name=\"{}\">\n        <sequence format=\"r0\" duration=\"{}\" tcStart=\"0s\" tcFormat=\"NDF\">\n          <spine>\n{}          </spine>\n        </sequence>\n      </project>\n    </event>\n  </library>\n</fcpxml>\n",
+        resources,
+        escape_xml(title),
+        time(position),
+        spine
+    )
+}
+
+/// Render a Premiere Pro compatible xmeml (v4) document
+pub fn build_premiere_xml(clips: &[ClipData], title: &str) -> String {
+    let frame_rate = timeline_rate(clips);
+    let frame_clips = frame_clips(clips, frame_rate);
+    let (width, height) = clips
+        .first()
+        .map(|c| (c.width, c.height))
+        .unwrap_or((1920, 1080));
+    let rate = format!(
+        "<rate><timebase>{}</timebase><ntsc>{}</ntsc></rate>",
+        frame_rate.timebase,
+        if frame_rate.ntsc { "TRUE" } else { "FALSE" }
+    );
+    let sequence_length = frame_clips
+        .iter()
+        .map(|c| c.record_in + c.length)
+        .max()
+        .unwrap_or(0);
+
+    let mut video_items = String::new();
+    let mut audio_items = String::new();
+
+    for (i, clip) in frame_clips.iter().enumerate() {
+        let file_id = format!("file-{}", i + 1);
+        let source_length = clip.source_length.max(clip.source_in + clip.length);
+
+        // The first reference to a file carries its full description
+        video_items.push_str(&format!(
+            "          <clipitem id=\"clipitem-v{n}\">\n            <name>{name}</name>\n            <duration>{dur}</duration>\n            {rate}\n            <start>{start}</start>\n            <end>{end}</end>\n            <in>{in_}</in>\n            <out>{out}</out>\n            <file id=\"{file_id}\">\n              <name>{name}</name>\n              <pathurl>{url}</pathurl>\n              {rate}\n              <duration>{dur}</duration>\n            </file>\n          </clipitem>\n",
+            n = i + 1,
+            name = escape_xml(&clip.name),
+            dur = source_length,
+            rate = rate,
+            start = clip.record_in,
+            end = clip.record_in + clip.length,
+            in_ = clip.source_in,
+            out = clip.source_in + clip.length,
+            file_id = file_id,
+            url = escape_xml(&file_url(clip.path)),
+        ));
+
+        if clip.has_audio {
+            audio_items.push_str(&format!(
+                "          <clipitem id=\"clipitem-a{n}\">\n            <name>{name}</name>\n            <duration>{dur}</duration>\n            {rate}\n            <start>{start}</start>\n            <end>{end}</end>\n            <in>{in_}</in>\n            <out>{out}</out>\n            <file id=\"{file_id}\"/>\n          </clipitem>\n",
+                n = i + 1,
+                name = escape_xml(&clip.name),
+                dur = source_length,
+                rate = rate,
+                start = clip.record_in,
+                end = clip.record_in + clip.length,
+                in_ = clip.source_in,
+                out = clip.source_in + clip.length,
+                file_id = file_id,
+            ));
+        }
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE xmeml>\n<xmeml version=\"4\">\n  <sequence id=\"sequence-1\">\n    <name>{}</name>\n    <duration>{}</duration>\n    {}\n    <media>\n      <video>\n        <format>\n          <samplecharacteristics>\n            <width>{}</width>\n            <height>{}</height>\n          </samplecharacteristics>\n        </format>\n        <track>\n{}        </track>\n      </video>\n      <audio>\n        <track>\n{}        </track>\n      </audio>\n    </media>\n  </sequence>\n</xmeml>\n",
+        escape_xml(title),
+        sequence_length,
+        rate,
+        width,
+        height,
+        video_items,
+        audio_items
+    )
+}
+
+/// Write the clip list in an interchange format for external editors
+#[tauri::command]
+pub async fn export_project_interchange(
+    format: InterchangeFormat,
+    clips: Vec<ClipData>,
+    output_path: String,
+) -> Result<(), String> {
+    if clips.is_empty() {
+        return Err("No clips to export".to_string());
+    }
+
+    let title = Path::new(&output_path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("ClipForge Project")
+        .to_string();
+
+    let content = match format {
+        InterchangeFormat::Edl => build_edl(&clips, &title),
+        InterchangeFormat::Fcpxml => build_fcpxml(&clips, &title),
+        InterchangeFormat::Premiere => build_premiere_xml(&clips, &title),
+    };
+
+    fs::write(&output_path, content).map_err(|e| format!("Failed to write project file: {}", e))?;
+
+    println!(
+        "Exported {} clips as {:?} to: {}",
+        clips.len(),
+        format,
+        output_path
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timecode() {
+        assert_eq!(timecode(0, 30), "00:00:00:00");
+        assert_eq!(timecode(30 * 3661 + 15, 30), "01:01:01:15");
+    }
+
+    #[test]
+    fn test_edl_event() {
+        let clip = ClipData {
+            video_path: "/media/a b.mp4".to_string(),
+            start_time: 2.0,
+            trim_start: 1.0,
+            trim_end: 3.0,
+            duration: 20.0,
+            width: 1920,
+            height: 1080,
+            frame_rate: 30.0,
+            media_type: None,
+            pip_metadata_path: None,
+            pip_override: None,
+            color_correction: None,
+            cursor_data_path: None,
+            reframe_focus: None,
+        };
+        let edl = build_edl(&[clip], "Demo");
+        assert!(edl.starts_with("TITLE: Demo\nFCM: NON-DROP FRAME\n"));
+        assert!(edl.contains(
+            "001  AX       B    C        00:00:01:00 00:00:03:00 00:00:00:00 00:00:02:00"
+        ));
+        assert!(edl.contains("* FROM CLIP NAME: a b.mp4"));
+    }
+
+    #[test]
+    fn test_fcpxml_inserts_gaps() {
+        let clips = [
+            ClipData {
+                video_path: "/a.mp4".to_string(),
+                start_time: 0.0,
+                trim_start: 0.0,
+                trim_end: 2.0,
+                duration: 20.0,
+                width: 1920,
+                height: 1080,
+                frame_rate: 30.0,
+                media_type: None,
+                pip_metadata_path: None,
+                pip_override: None,
+                color_correction: None,
+                cursor_data_path: None,
+                reframe_focus: None,
+            },
+            ClipData {
+                video_path: "/b.mp4".to_string(),
+                start_time: 3.0,
+                trim_start: 0.0,
+                trim_end: 1.0,
+                duration: 20.0,
+                width: 1920,
+                height: 1080,
+                frame_rate: 30.0,
+                media_type: None,
+                pip_metadata_path: None,
+                pip_override: None,
+                color_correction: None,
+                cursor_data_path: None,
+                reframe_focus: None,
+            },
+        ];
+        let xml = build_fcpxml(&clips, "Demo");
+
+        assert!(xml.contains("<gap name=\"Gap\" offset=\"60/30s\" duration=\"30/30s\"/>"));
+        assert!(xml.contains("offset=\"90/30s\" start=\"0/30s\" duration=\"30/30s\""));
+        assert!(xml.contains("src=\"file:///a.mp4\""));
+    }
+
+    #[test]
+    fn test_ntsc_rates_stay_rational() {
+        assert_eq!(
+            FrameRate::of(29.97),
+            FrameRate {
+                timebase: 30,
+                ntsc: true
+            }
+        );
+        assert_eq!(
+            FrameRate::of(25.0),
+            FrameRate {
+                timebase: 25,
+                ntsc: false
+            }
+        );
+
+        let clip = ClipData {
+            video_path: "/a.mp4".to_string(),
+            start_time: 0.0,
+            trim_start: 0.0,
+            trim_end: 2.0,
+            duration: 20.0,
+            width: 1920,
+            height: 1080,
+            frame_rate: 29.97,
+            media_type: None,
+            pip_metadata_path: None,
+            pip_override: None,
+            color_correction: None,
+            cursor_data_path: None,
+            reframe_focus: None,
+        };
+        let clips = [clip];
+        let xml = build_fcpxml(&clips, "Demo");
+        assert!(xml.contains("frameDuration=\"1001/30000s\""));
+        assert!(xml.contains("duration=\"60060/30000s\""));
+
+        let xml = build_premiere_xml(&clips, "Demo");
+        assert!(xml.contains("<rate><timebase>30</timebase><ntsc>TRUE</ntsc></rate>"));
+    }
+}
@@ -0,0 +1,514 @@
+// Content-addressable media cache
+//
+// Derived media (thumbnails, waveforms, proxies, ffprobe results, rendered
+// export segments) is cached under `media/` in the app cache, keyed by a hash
+// of the source file's contents rather than its path, so renamed or
+// re-imported copies of a file share one set of artifacts. Each item is a
+// (hash, kind, variant) triple with its files and optional JSON data; a single
+// index tracks sizes and last use so the whole cache can be evicted least
+// recently used first.
+
+use super::app_cache;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+/// Total cache size before the least recently used items are evicted
+pub const DEFAULT_CACHE_LIMIT_BYTES: u64 = 10 * 1024 * 1024 * 1024;
+
+/// Bytes read from each end of a file when hashing it
+const HASH_SAMPLE_BYTES: u64 = 1024 * 1024;
+
+/// App cache subdirectory holding the media cache
+const MEDIA_DIR: &str = "media";
+
+/// Serializes access to the index between concurrent commands
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+/// Kind of derived media
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheKind {
+    Thumbnail,
+    Waveform,
+    Proxy,
+    Probe,
+    /// Pre-rendered export segments
+    Render,
+}
+
+impl CacheKind {
+    pub const ALL: [CacheKind; 5] = [
+        CacheKind::Thumbnail,
+        CacheKind::Waveform,
+        CacheKind::Proxy,
+        CacheKind::Probe,
+        CacheKind::Render,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            CacheKind::Thumbnail => "thumbnail",
+            CacheKind::Waveform => "waveform",
+            CacheKind::Proxy => "proxy",
+            CacheKind::Probe => "probe",
+            CacheKind::Render => "render",
+        }
+    }
+}
+
+/// A cached item returned by `lookup`
+#[derive(Debug, Clone)]
+pub struct CachedItem {
+    pub files: Vec<String>,
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheEntry {
+    kind: CacheKind,
+    /// File the item was derived from, for reporting
+    source_path: String,
+    files: Vec<String>,
+    #[serde(default)]
+    data: Option<Value>,
+    /// Bytes used by the files and data
+    size: u64,
+    /// Last time the item was stored or returned (seconds since epoch)
+    last_used: u64,
+}
+
+/// Content hash of a file as of a given size and modification time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Fingerprint {
+    size: u64,
+    modified: u64,
+    hash: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheIndex {
+    #[serde(default)]
+    entries: BTreeMap<String, CacheEntry>,
+    /// Hashes by source path, so unchanged files aren't read again
+    #[serde(default)]
+    fingerprints: BTreeMap<String, Fingerprint>,
+}
+
+/// Usage of one kind of cached media
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheKindStats {
+    pub kind: CacheKind,
+    pub count: usize,
+    pub bytes: u64,
+}
+
+/// Media cache usage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub path: String,
+    pub count: usize,
+    pub total_bytes: u64,
+    pub limit_bytes: u64,
+    pub kinds: Vec<CacheKindStats>,
+}
+
+fn media_root() -> PathBuf {
+    app_cache::cache_path(MEDIA_DIR)
+}
+
+fn index_path() -> PathBuf {
+    media_root().join("index.json")
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+fn load_index() -> CacheIndex {
+    fs::read_to_string(index_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_index(index: &CacheIndex) {
+    if app_cache::cache_dir(MEDIA_DIR).is_err() {
+        return;
+    }
+    if let Ok(content) = serde_json::to_string(index) {
+        let temp_path = index_path().with_extension("json.tmp");
+        if fs::write(&temp_path, content).is_ok() {
+            let _ = fs::rename(&temp_path, index_path());
+        }
+    }
+}
+
+fn entry_key(hash: &str, kind: CacheKind, variant: &str) -> String {
+    format!("{}:{}:{}", hash, kind.name(), variant)
+}
+
+/// SHA-256 of a file's size and its first and last megabyte
+///
+/// Reading the whole file would take seconds for long recordings; the sampled
+/// ends plus the exact size identify media files in practice.
+fn hash_file(path: &Path, size: u64) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open media file: {}", e))?;
+    let mut hasher = Sha256::new();
+    hasher.update(size.to_le_bytes());
+
+    let mut buffer = Vec::new();
+    file.by_ref()
+        .take(HASH_SAMPLE_BYTES)
+        .read_to_end(&mut buffer)
+        .map_err(|e| format!("Failed to read media file: {}", e))?;
+    hasher.update(&buffer);
+
+    if size > HASH_SAMPLE_BYTES * 2 {
+        buffer.clear();
+        file.seek(SeekFrom::End(-(HASH_SAMPLE_BYTES as i64)))
+            .and_then(|_| file.take(HASH_SAMPLE_BYTES).read_to_end(&mut buffer))
+            .map_err(|e| format!("Failed to read media file: {}", e))?;
+        hasher.update(&buffer);
+    }
+
+    Ok(hex::encode(&hasher.finalize()[..16]))
+}
+
+/// Content hash identifying a media file in the cache
+pub fn content_hash(path: &Path) -> Result<String, String> {
+    let metadata = fs::metadata(path).map_err(|e| format!("Failed to read media file: {}", e))?;
+    let size = metadata.len();
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let source = path.to_string_lossy().to_string();
+
+    if let Ok(_guard) = INDEX_LOCK.lock() {
+        let index = load_index();
+        if let Some(fingerprint) = index.fingerprints.get(&source) {
+            if fingerprint.size == size && fingerprint.modified == modified {
+                return Ok(fingerprint.hash.clone());
+            }
+        }
+    }
+
+    let hash = hash_file(path, size)?;
+    if let Ok(_guard) = INDEX_LOCK.lock() {
+        let mut index = load_index();
+        index.fingerprints.insert(
+            source,
+            Fingerprint {
+                size,
+                modified,
+                hash: hash.clone(),
+            },
+        );
+        save_index(&index);
+    }
+    Ok(hash)
+}
+
+/// Directory for the files of items derived from `hash`
+pub fn item_dir(hash: &str) -> Result<PathBuf, String> {
+    let dir = app_cache::cache_dir(MEDIA_DIR)?.join(hash);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create cache directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Return a cached item if all of its files still exist, marking it as used
+pub fn lookup(hash: &str, kind: CacheKind, variant: &str) -> Option<CachedItem> {
+    let _guard = INDEX_LOCK.lock().ok()?;
+    let mut index = load_index();
+    let key = entry_key(hash, kind, variant);
+
+    let entry = index.entries.get_mut(&key)?;
+    if !entry.files.iter().all(|file| Path::new(file).exists()) {
+        if let Some(entry) = index.entries.remove(&key) {
+            remove_entry_files(&entry);
+        }
+        save_index(&index);
+        return None;
+    }
+
+    entry.last_used = now_secs();
+    let item = CachedItem {
+        files: entry.files.clone(),
+        data: entry.data.clone(),
+    };
+    save_index(&index);
+    Some(item)
+}
+
+/// Record an item, then evict old items if the cache is over its limit
+pub fn store(
+    hash: &str,
+    kind: CacheKind,
+    variant: &str,
+    source_path: &str,
+    files: Vec<String>,
+    data: Option<Value>,
+) {
+    let size = files
+        .iter()
+        .filter_map(|file| fs::metadata(file).ok())
+        .map(|metadata| metadata.len())
+        .sum::<u64>()
+        + data
+            .as_ref()
+            .map(|data| data.to_string().len() as u64)
+            .unwrap_or(0);
+
+    let Ok(_guard) = INDEX_LOCK.lock() else {
+        return;
+    };
+    let mut index = load_index();
+    index.entries.insert(
+        entry_key(hash, kind, variant),
+        CacheEntry {
+            kind,
+            source_path: source_path.to_string(),
+            files,
+            data,
+            size,
+            last_used: now_secs(),
+        },
+    );
+    let (evicted, freed) = evict_entries(&mut index, None, None, DEFAULT_CACHE_LIMIT_BYTES);
+    if evicted > 0 {
+        println!("[MediaCache] Evicted {} items ({} bytes)", evicted, freed);
+    }
+    save_index(&index);
+}
+
+/// Remove an item's files and any directories they leave empty
+fn remove_entry_files(entry: &CacheEntry) -> u64 {
+    let root = media_root();
+    let mut freed = 0;
+    for file in &entry.files {
+        let path = Path::new(file);
+        freed += fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        let _ = fs::remove_file(path);
+
+        let mut parent = path.parent();
+        while let Some(dir) = parent.filter(|dir| dir.starts_with(&root) && *dir != root) {
+            if fs::remove_dir(dir).is_err() {
+                break;
+            }
+            parent = dir.parent();
+        }
+    }
+    freed
+}
+
+/// Drop items of `kind` (all kinds when `None`) unused for `max_age` seconds,
+/// then the least recently used until they fit in `max_bytes`
+///
+/// Returns the number of items removed and the bytes freed
+fn evict_entries(
+    index: &mut CacheIndex,
+    kind: Option<CacheKind>,
+    max_age: Option<u64>,
+    max_bytes: u64,
+) -> (usize, u64) {
+    let cutoff = max_age.map(|age| now_secs().saturating_sub(age));
+    let mut candidates: Vec<(String, u64, u64)> = index
+        .entries
+        .iter()
+        .filter(|(_, entry)| kind.is_none_or(|kind| entry.kind == kind))
+        .map(|(key, entry)| (key.clone(), entry.last_used, entry.size))
+        .collect();
+    candidates.sort_by_key(|(_, last_used, _)| *last_used);
+
+    let mut total: u64 = candidates.iter().map(|(_, _, size)| size).sum();
+    let mut evicted = 0;
+    let mut freed = 0;
+    for (key, last_used, size) in candidates {
+        let expired = cutoff.is_some_and(|cutoff| last_used < cutoff);
+        if !expired && total <= max_bytes {
+            continue;
+        }
+        if let Some(entry) = index.entries.remove(&key) {
+            remove_entry_files(&entry);
+            total = total.saturating_sub(size);
+            freed += size;
+            evicted += 1;
+        }
+    }
+
+    // Forget hashes of files that no longer have anything cached
+    let live: std::collections::HashSet<String> = index
+        .entries
+        .keys()
+        .filter_map(|key| key.split(':').next().map(|hash| hash.to_string()))
+        .collect();
+    index
+        .fingerprints
+        .retain(|_, fingerprint| live.contains(&fingerprint.hash));
+    (evicted, freed)
+}
+
+/// Evict items of one kind (or all) by age and total size
+///
+/// Returns the number of items removed and the bytes freed
+pub fn evict(kind: Option<CacheKind>, max_age: Option<u64>, max_bytes: u64) -> (usize, u64) {
+    let Ok(_guard) = INDEX_LOCK.lock() else {
+        return (0, 0);
+    };
+    let mut index = load_index();
+    let result = evict_entries(&mut index, kind, max_age, max_bytes);
+    save_index(&index);
+    result
+}
+
+/// Usage of the cache, overall and per kind
+pub fn stats() -> CacheStats {
+    let index = INDEX_LOCK
+        .lock()
+        .map(|_guard| load_index())
+        .unwrap_or_default();
+
+    let kinds: Vec<CacheKindStats> = CacheKind::ALL
+        .iter()
+        .map(|kind| {
+            let entries = index.entries.values().filter(|entry| entry.kind == *kind);
+            CacheKindStats {
+                kind: *kind,
+                count: entries.clone().count(),
+                bytes: entries.map(|entry| entry.size).sum(),
+            }
+        })
+        .collect();
+
+    CacheStats {
+        path: media_root().to_string_lossy().to_string(),
+        count: index.entries.len(),
+        total_bytes: kinds.iter().map(|kind| kind.bytes).sum(),
+        limit_bytes: DEFAULT_CACHE_LIMIT_BYTES,
+        kinds,
+    }
+}
+
+/// Report media cache usage
+#[tauri::command]
+pub async fn get_cache_stats() -> Result<CacheStats, String> {
+    Ok(stats())
+}
+
+/// Delete cached media of one kind, or everything when `kind` is omitted
+///
+/// Returns the number of bytes freed
+#[tauri::command]
+pub async fn clear_cache(kind: Option<CacheKind>) -> Result<u64, String> {
+    let (count, freed) = evict(kind, None, 0);
+
+    if kind.is_none() {
+        // Folders used by earlier versions before the shared cache existed
+        for legacy in ["clipforge_thumbnails", "clipforge_proxies"] {
+            let _ = fs::remove_dir_all(std::env::temp_dir().join(legacy));
+        }
+        let _ = fs::remove_dir_all(app_cache::cache_path("thumbnails"));
+
+        let _guard = INDEX_LOCK.lock().map_err(|e| e.to_string())?;
+        let mut index = load_index();
+        index.fingerprints.clear();
+        save_index(&index);
+    }
+
+    println!("[MediaCache] Cleared {} items ({} bytes)", count, freed);
+    Ok(freed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evict_least_recently_used() {
+        let now = now_secs();
+        let mut index = CacheIndex::default();
+        index.entries.insert(
+            "a".to_string(),
+            CacheEntry {
+                kind: CacheKind::Proxy,
+                source_path: "/videos/clip.mp4".to_string(),
+                files: Vec::new(),
+                data: None,
+                size: 400,
+                last_used: now - 30,
+            },
+        );
+        index.entries.insert(
+            "b".to_string(),
+            CacheEntry {
+                kind: CacheKind::Proxy,
+                source_path: "/videos/clip.mp4".to_string(),
+                files: Vec::new(),
+                data: None,
+                size: 400,
+                last_used: now - 10,
+            },
+        );
+        index.entries.insert(
+            "c".to_string(),
+            CacheEntry {
+                kind: CacheKind::Thumbnail,
+                source_path: "/videos/clip.mp4".to_string(),
+                files: Vec::new(),
+                data: None,
+                size: 400,
+                last_used: now - 50,
+            },
+        );
+
+        // Only proxies are considered, oldest first
+        assert_eq!(
+            evict_entries(&mut index, Some(CacheKind::Proxy), None, 500),
+            (1, 400)
+        );
+        assert!(!index.entries.contains_key("a"));
+        assert!(index.entries.contains_key("c"));
+
+        // Expired items go even when under the size limit
+        assert_eq!(
+            evict_entries(&mut index, None, Some(40), u64::MAX),
+            (1, 400)
+        );
+        assert_eq!(index.entries.keys().collect::<Vec<_>>(), vec!["b"]);
+    }
+
+    #[test]
+    fn test_hash_depends_on_content() {
+        let dir =
+            std::env::temp_dir().join(format!("clipforge_media_cache_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let first = dir.join("first.mp4");
+        let copy = dir.join("copy.mp4");
+        let other = dir.join("other.mp4");
+        fs::write(&first, b"same bytes").unwrap();
+        fs::write(&copy, b"same bytes").unwrap();
+        fs::write(&other, b"different!").unwrap();
+
+        let hash = |path: &Path| hash_file(path, fs::metadata(path).unwrap().len()).unwrap();
+        assert_eq!(hash(&first), hash(&copy));
+        assert_ne!(hash(&first), hash(&other));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
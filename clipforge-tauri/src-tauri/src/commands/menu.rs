@@ -0,0 +1,209 @@
+// App menu actions
+//
+// Every File/Edit/View menu selection is broadcast as a "menu:action" event.
+// Save and Export are first handled here: Save writes the latest project state
+// straight to its file when it has one, and Export checks that FFmpeg is
+// available and suggests an output path before the frontend opens its dialog.
+// Undo and Redo are left to the frontend, which knows whether a text field has
+// focus or the project history should step. The event tells the frontend
+// whether anything is left for it to do.
+
+use super::autosave::AutosaveState;
+use super::ffmpeg_utils::find_ffmpeg;
+use super::projects::write_project;
+use super::recents::{self, add_recent, RecentKind};
+use super::settings::SettingsState;
+use crate::error::ClipForgeError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Default file name offered for exports
+const EXPORT_FILE_NAME: &str = "export.mp4";
+
+/// Menu item the user chose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MenuAction {
+    New,
+    Open,
+    Save,
+    SaveAs,
+    Export,
+    Undo,
+    Redo,
+    ToggleFullscreen,
+    ZoomIn,
+    ZoomOut,
+    ZoomReset,
+    /// A project from File > Open Recent; `path` is the project file
+    OpenRecentProject,
+    /// A recording from File > Open Recent; `path` is the video file
+    OpenRecentRecording,
+    ClearRecents,
+}
+
+impl MenuAction {
+    /// Action for a menu item id, `None` for predefined items
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "new" => Some(MenuAction::New),
+            "open" => Some(MenuAction::Open),
+            "save" => Some(MenuAction::Save),
+            "save_as" => Some(MenuAction::SaveAs),
+            "export" => Some(MenuAction::Export),
+            "undo" => Some(MenuAction::Undo),
+            "redo" => Some(MenuAction::Redo),
+            "toggle_fullscreen" => Some(MenuAction::ToggleFullscreen),
+            "zoom_in" => Some(MenuAction::ZoomIn),
+            "zoom_out" => Some(MenuAction::ZoomOut),
+            "zoom_reset" => Some(MenuAction::ZoomReset),
+            recents::CLEAR_MENU_ID => Some(MenuAction::ClearRecents),
+            _ => match recents::menu_target(id) {
+                Some((RecentKind::Project, _)) => Some(MenuAction::OpenRecentProject),
+                Some((RecentKind::Recording, _)) => Some(MenuAction::OpenRecentRecording),
+                None => None,
+            },
+        }
+    }
+}
+
+/// Payload of the "menu:action" event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MenuActionEvent {
+    pub action: MenuAction,
+    /// True when the backend finished the action and the frontend only needs to reflect it
+    pub handled: bool,
+    /// Saved project file, suggested export path, or the recent file chosen
+    pub path: Option<String>,
+    pub error: Option<ClipForgeError>,
+}
+
+impl MenuActionEvent {
+    fn new(action: MenuAction) -> Self {
+        Self {
+            action,
+            handled: false,
+            path: None,
+            error: None,
+        }
+    }
+}
+
+/// Write the latest project state to its file, if it has been saved before
+///
+/// The frontend pushes its state with `update_autosave` whenever it changes.
+/// Returns `None` when there is no file yet and the frontend must ask for one.
+fn save_current_project(app: &AppHandle) -> Result<Option<String>, ClipForgeError> {
+    let latest = {
+        let state = app.state::<AutosaveState>();
+        let manager = state.lock()?;
+        manager.latest().cloned()
+    };
+
+    match latest {
+        Some((project, Some(path))) => {
+            write_project(Path::new(&path), project)?;
+            add_recent(app, RecentKind::Project, &path);
+            println!("[Menu] Saved project to {}", path);
+            Ok(Some(path))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Where an export is offered by default: the library folder when one is set
+fn suggested_export_path(library_path: Option<&str>) -> PathBuf {
+    match library_path {
+        Some(dir) => Path::new(dir).join(EXPORT_FILE_NAME),
+        None => PathBuf::from(EXPORT_FILE_NAME),
+    }
+}
+
+/// Check export can run before the frontend asks for an output path
+fn prepare_export(app: &AppHandle) -> Result<String, ClipForgeError> {
+    find_ffmpeg().ok_or_else(ClipForgeError::ffmpeg_missing)?;
+
+    let state = app.state::<SettingsState>();
+    let settings = state.lock()?;
+    Ok(suggested_export_path(settings.library_path.as_deref())
+        .to_string_lossy()
+        .to_string())
+}
+
+/// Handle a menu selection and emit "menu:action"
+pub fn handle_menu_event(app: &AppHandle, id: &str) {
+    let Some(action) = MenuAction::from_id(id) else {
+        return;
+    };
+
+    let target = recents::menu_target(id).map(|(_, path)| path.to_string());
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut event = MenuActionEvent::new(action);
+        event.path = target;
+
+        match action {
+            MenuAction::Save => match save_current_project(&app) {
+                Ok(path) => {
+                    event.handled = path.is_some();
+                    event.path = path;
+                }
+                Err(e) => event.error = Some(e),
+            },
+            MenuAction::Export => match prepare_export(&app) {
+                Ok(path) => event.path = Some(path),
+                Err(e) => event.error = Some(e),
+            },
+            MenuAction::ClearRecents => match recents::clear(&app, None) {
+                Ok(_) => event.handled = true,
+                Err(e) => event.error = Some(e.into()),
+            },
+            _ => {}
+        }
+
+        if let Some(error) = &event.error {
+            println!("[Menu] {:?} failed: {}", action, error);
+        }
+        let _ = app.emit("menu:action", &event);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_menu_ids() {
+        assert_eq!(MenuAction::from_id("save_as"), Some(MenuAction::SaveAs));
+        assert_eq!(
+            MenuAction::from_id("zoom_reset"),
+            Some(MenuAction::ZoomReset)
+        );
+        assert_eq!(
+            MenuAction::from_id("recent_project:/Users/me/demo.clipforge"),
+            Some(MenuAction::OpenRecentProject)
+        );
+        assert_eq!(MenuAction::from_id("close_window"), None);
+    }
+
+    #[test]
+    fn test_event_payload() {
+        let mut event = MenuActionEvent::new(MenuAction::Export);
+        event.error = Some(ClipForgeError::ffmpeg_missing());
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["action"], "export");
+        assert_eq!(value["handled"], false);
+        assert_eq!(value["error"]["code"], "dependency_missing");
+    }
+
+    #[test]
+    fn test_suggested_export_path() {
+        assert_eq!(
+            suggested_export_path(Some("/Users/me/Movies")),
+            PathBuf::from("/Users/me/Movies/export.mp4")
+        );
+        assert_eq!(suggested_export_path(None), PathBuf::from("export.mp4"));
+    }
+}
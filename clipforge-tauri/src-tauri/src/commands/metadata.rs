@@ -1,8 +1,58 @@
 use super::ffmpeg_utils::find_ffprobe;
+use super::media_cache::{self, CacheKind};
+use super::process_runner::{watched_output_async, JobKind};
+use super::waveform::Waveform;
+use crate::error::ClipForgeError;
 use serde::{Deserialize, Serialize};
-use std::process::Command;
+use std::path::Path;
+use tokio::process::Command;
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Clip length given to still images, which have no duration of their own (seconds)
+pub const DEFAULT_IMAGE_DURATION: f64 = 5.0;
+
+/// Frame rate still images are looped at
+pub const DEFAULT_IMAGE_FRAME_RATE: f64 = 30.0;
+
+/// Media cache variant for full ffprobe output
+const PROBE_VARIANT: &str = "ffprobe";
+
+/// Kind of media a file holds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaKind {
+    #[default]
+    Video,
+    /// Still image (PNG/JPEG) or animated GIF
+    Image,
+    /// Audio with no video stream, used for voiceover and music tracks
+    Audio,
+}
+
+impl MediaKind {
+    /// Media kind implied by a file's extension
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase());
+
+        match extension.as_deref() {
+            Some("png" | "jpg" | "jpeg" | "gif") => MediaKind::Image,
+            Some("mp3" | "wav" | "m4a" | "aac" | "flac" | "ogg") => MediaKind::Audio,
+            _ => MediaKind::Video,
+        }
+    }
+}
+
+/// Whether a path is an animated-capable GIF rather than a still image
+pub fn is_gif(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("gif"))
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VideoMetadata {
     pub path: String,
     pub filename: String,
@@ -10,13 +60,64 @@ pub struct VideoMetadata {
     pub width: u32,
     pub height: u32,
     pub frame_rate: f64,
+    #[serde(default)]
+    pub media_kind: MediaKind,
+    /// Average frame rate; differs from `frame_rate` for variable frame rate video
+    #[serde(default)]
+    pub avg_frame_rate: f64,
+    /// Container format names reported by ffprobe (e.g. "matroska,webm")
+    #[serde(default)]
+    pub container: Option<String>,
     pub thumbnail_path: Option<String>,
     pub file_size: Option<u64>,
+    /// Video codec name (e.g. "h264", "hevc")
+    #[serde(default)]
+    pub video_codec: Option<String>,
+    #[serde(default)]
+    pub pixel_format: Option<String>,
+    #[serde(default)]
+    pub bit_depth: Option<u32>,
+    /// Clockwise display rotation in degrees (0, 90, 180 or 270)
+    #[serde(default)]
+    pub rotation: u32,
+    #[serde(default)]
+    pub color_primaries: Option<String>,
+    #[serde(default)]
+    pub color_transfer: Option<String>,
+    /// True for PQ or HLG transfer characteristics
+    #[serde(default)]
+    pub is_hdr: bool,
+    /// Overall bitrate in bits per second
+    #[serde(default)]
+    pub bitrate: Option<u64>,
+    #[serde(default)]
+    pub audio_stream_count: u32,
+    #[serde(default)]
+    pub audio_codec: Option<String>,
+    #[serde(default)]
+    pub audio_channels: Option<u32>,
+    #[serde(default)]
+    pub audio_sample_rate: Option<u32>,
+    /// Waveform peaks for audio files (populated by import_video)
+    #[serde(default)]
+    pub waveform: Option<Waveform>,
 }
 
 #[derive(Debug, Deserialize)]
 struct FFprobeFormat {
     duration: Option<String>,
+    bit_rate: Option<String>,
+    format_name: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FFprobeTags {
+    rotate: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FFprobeSideData {
+    rotation: Option<f64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -24,7 +125,67 @@ struct FFprobeStream {
     width: Option<u32>,
     height: Option<u32>,
     r_frame_rate: Option<String>,
+    avg_frame_rate: Option<String>,
     codec_type: Option<String>,
+    codec_name: Option<String>,
+    pix_fmt: Option<String>,
+    bits_per_raw_sample: Option<String>,
+    color_primaries: Option<String>,
+    color_transfer: Option<String>,
+    channels: Option<u32>,
+    sample_rate: Option<String>,
+    #[serde(default)]
+    tags: FFprobeTags,
+    #[serde(default)]
+    side_data_list: Vec<FFprobeSideData>,
+}
+
+/// Parse an ffprobe rational such as "30000/1001" or "30/1"
+pub(crate) fn parse_rational(value: &str) -> Option<f64> {
+    let (num, den) = value.split_once('/')?;
+    let num = num.parse::<f64>().ok()?;
+    let den = den.parse::<f64>().ok()?;
+    (den != 0.0).then(|| num / den)
+}
+
+/// Bit depth implied by a pixel format name (e.g. "yuv420p10le" is 10-bit)
+fn pixel_format_bit_depth(pix_fmt: &str) -> u32 {
+    let trimmed = pix_fmt.trim_end_matches("le").trim_end_matches("be");
+    let digits: String = trimmed
+        .chars()
+        .rev()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+
+    // Depth suffixes follow the planar "p" or "gray" ("yuv420p10", "gray12");
+    // other trailing digits are part of the name ("nv12")
+    let prefix = &trimmed[..trimmed.len() - digits.len()];
+    match digits.parse::<u32>() {
+        Ok(depth) if (prefix.ends_with('p') || prefix.ends_with("gray")) && depth > 8 => depth,
+        _ => 8,
+    }
+}
+
+/// Whether a transfer characteristic is PQ (HDR10) or HLG
+fn is_hdr_transfer(transfer: &str) -> bool {
+    matches!(transfer, "smpte2084" | "arib-std-b67")
+}
+
+/// Normalize rotation metadata to clockwise degrees in 0..360
+///
+/// The legacy `rotate` tag is clockwise; display matrix side data is counter-clockwise
+fn stream_rotation(tags: &FFprobeTags, side_data: &[FFprobeSideData]) -> u32 {
+    let degrees = side_data
+        .iter()
+        .find_map(|data| data.rotation)
+        .map(|rotation| -rotation)
+        .or_else(|| tags.rotate.as_ref().and_then(|r| r.parse::<f64>().ok()))
+        .unwrap_or(0.0);
+
+    ((degrees.round() as i64).rem_euclid(360)) as u32
 }
 
 #[derive(Debug, Deserialize)]
@@ -33,74 +194,169 @@ struct FFprobeOutput {
     streams: Option<Vec<FFprobeStream>>,
 }
 
-#[tauri::command]
-pub async fn extract_metadata(file_path: String) -> Result<VideoMetadata, String> {
+/// Run ffprobe on a file, returning its JSON output
+async fn run_ffprobe(file_path: &str) -> Result<serde_json::Value, ClipForgeError> {
     // Find ffprobe executable
-    let ffprobe_path =
-        find_ffprobe().ok_or_else(|| "ffprobe not found. Please install FFmpeg.".to_string())?;
+    let ffprobe_path = find_ffprobe().ok_or_else(ClipForgeError::ffprobe_missing)?;
     // Execute ffprobe with JSON output
-    let output = Command::new(ffprobe_path)
-        .args([
-            "-v",
-            "quiet",
-            "-print_format",
-            "json",
-            "-show_format",
-            "-show_streams",
-            &file_path,
-        ])
-        .output()
+    let mut command = Command::new(ffprobe_path);
+    command.args([
+        "-v",
+        "quiet",
+        "-print_format",
+        "json",
+        "-show_format",
+        "-show_streams",
+        file_path,
+    ]);
+    let output = watched_output_async(&mut command, JobKind::Probe)
+        .await
         .map_err(|e| format!("Failed to execute ffprobe: {}", e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("ffprobe failed: {}", stderr));
+        return Err(ClipForgeError::Ffmpeg(format!(
+            "ffprobe failed: {}",
+            stderr
+        )));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let probe_data: FFprobeOutput = serde_json::from_str(&stdout)
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| ClipForgeError::Ffmpeg(format!("Failed to parse ffprobe output: {}", e)))
+}
+
+#[tauri::command]
+pub async fn extract_metadata(file_path: String) -> Result<VideoMetadata, ClipForgeError> {
+    // Reuse the probe of an identical file from the media cache
+    let hash = media_cache::content_hash(Path::new(&file_path)).ok();
+    let cached = hash
+        .as_ref()
+        .and_then(|hash| media_cache::lookup(hash, CacheKind::Probe, PROBE_VARIANT))
+        .and_then(|item| item.data);
+    let probe_json = match cached {
+        Some(probe_json) => probe_json,
+        None => {
+            let probe_json = run_ffprobe(&file_path).await?;
+            if let Some(hash) = &hash {
+                media_cache::store(
+                    hash,
+                    CacheKind::Probe,
+                    PROBE_VARIANT,
+                    &file_path,
+                    Vec::new(),
+                    Some(probe_json.clone()),
+                );
+            }
+            probe_json
+        }
+    };
+
+    let probe_data: FFprobeOutput = serde_json::from_value(probe_json)
         .map_err(|e| format!("Failed to parse ffprobe output: {}", e))?;
 
-    // Extract duration from format
-    let duration = probe_data
-        .format
-        .and_then(|f| f.duration)
+    // Extract duration and bitrate from format
+    let format = probe_data.format;
+    let duration = format
+        .as_ref()
+        .and_then(|f| f.duration.as_ref())
         .and_then(|d| d.parse::<f64>().ok())
         .unwrap_or(0.0);
+    let bitrate = format
+        .as_ref()
+        .and_then(|f| f.bit_rate.as_ref())
+        .and_then(|b| b.parse::<u64>().ok());
+    let container = format.and_then(|f| f.format_name);
 
-    // Find video stream and extract metadata
-    let video_stream = probe_data
-        .streams
-        .unwrap_or_default()
-        .into_iter()
-        .find(|s| s.codec_type.as_deref() == Some("video"));
+    // Split streams into the first video stream and all audio streams
+    let mut video_stream = None;
+    let mut audio_streams = Vec::new();
+    for stream in probe_data.streams.unwrap_or_default() {
+        match stream.codec_type.as_deref() {
+            Some("video") if video_stream.is_none() => video_stream = Some(stream),
+            Some("audio") => audio_streams.push(stream),
+            _ => {}
+        }
+    }
 
-    let (width, height, frame_rate) = if let Some(stream) = video_stream {
+    let audio_stream_count = audio_streams.len() as u32;
+    let audio_stream = audio_streams.into_iter().next();
+    let audio_codec = audio_stream.as_ref().and_then(|s| s.codec_name.clone());
+    let audio_channels = audio_stream.as_ref().and_then(|s| s.channels);
+    let audio_sample_rate = audio_stream
+        .as_ref()
+        .and_then(|s| s.sample_rate.as_ref())
+        .and_then(|r| r.parse::<u32>().ok());
+
+    // Codec, color and rotation details of the video stream
+    let video_codec = video_stream.as_ref().and_then(|s| s.codec_name.clone());
+    let pixel_format = video_stream.as_ref().and_then(|s| s.pix_fmt.clone());
+    let bit_depth = video_stream.as_ref().and_then(|s| {
+        s.bits_per_raw_sample
+            .as_ref()
+            .and_then(|b| b.parse::<u32>().ok())
+            .or_else(|| s.pix_fmt.as_deref().map(pixel_format_bit_depth))
+    });
+    let rotation = video_stream
+        .as_ref()
+        .map(|s| stream_rotation(&s.tags, &s.side_data_list))
+        .unwrap_or(0);
+    let color_primaries = video_stream
+        .as_ref()
+        .and_then(|s| s.color_primaries.clone());
+    let color_transfer = video_stream.as_ref().and_then(|s| s.color_transfer.clone());
+    let is_hdr = color_transfer
+        .as_deref()
+        .map(is_hdr_transfer)
+        .unwrap_or(false);
+
+    let (width, height, frame_rate, avg_frame_rate) = if let Some(stream) = video_stream {
         let width = stream.width.unwrap_or(0);
         let height = stream.height.unwrap_or(0);
 
-        // Parse frame rate (format: "30000/1001" or "30/1")
+        // Parse frame rates (format: "30000/1001" or "30/1")
         let frame_rate = stream
             .r_frame_rate
-            .and_then(|fr| {
-                let parts: Vec<&str> = fr.split('/').collect();
-                if parts.len() == 2 {
-                    let num = parts[0].parse::<f64>().ok()?;
-                    let den = parts[1].parse::<f64>().ok()?;
-                    Some(num / den)
-                } else {
-                    None
-                }
-            })
+            .as_deref()
+            .and_then(parse_rational)
+            .unwrap_or(0.0);
+        let avg_frame_rate = stream
+            .avg_frame_rate
+            .as_deref()
+            .and_then(parse_rational)
             .unwrap_or(0.0);
 
-        (width, height, frame_rate)
+        (width, height, frame_rate, avg_frame_rate)
     } else {
-        (0, 0, 0.0)
+        (0, 0, 0.0, 0.0)
+    };
+
+    // Images have no (or a single-frame) duration; give them a default clip length
+    let media_kind = if video_codec.is_none() && audio_stream_count > 0 {
+        MediaKind::Audio
+    } else {
+        MediaKind::from_path(Path::new(&file_path))
+    };
+    let (duration, frame_rate, avg_frame_rate) = if media_kind == MediaKind::Image {
+        if duration > 0.0 && is_gif(Path::new(&file_path)) {
+            let fps = if avg_frame_rate > 0.0 {
+                avg_frame_rate
+            } else {
+                DEFAULT_IMAGE_FRAME_RATE
+            };
+            (duration, fps, fps)
+        } else {
+            (
+                DEFAULT_IMAGE_DURATION,
+                DEFAULT_IMAGE_FRAME_RATE,
+                DEFAULT_IMAGE_FRAME_RATE,
+            )
+        }
+    } else {
+        (duration, frame_rate, avg_frame_rate)
     };
 
     // Extract filename
-    let filename = std::path::Path::new(&file_path)
+    let filename = Path::new(&file_path)
         .file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("unknown")
@@ -116,7 +372,83 @@ pub async fn extract_metadata(file_path: String) -> Result<VideoMetadata, String
         width,
         height,
         frame_rate,
+        media_kind,
+        avg_frame_rate,
+        container,
         thumbnail_path: None, // Will be populated by import_video
         file_size,
+        video_codec,
+        pixel_format,
+        bit_depth,
+        rotation,
+        color_primaries,
+        color_transfer,
+        is_hdr,
+        bitrate,
+        audio_stream_count,
+        audio_codec,
+        audio_channels,
+        audio_sample_rate,
+        waveform: None, // Will be populated by import_video
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rational() {
+        assert_eq!(parse_rational("30/1"), Some(30.0));
+        assert!((parse_rational("30000/1001").unwrap() - 29.97).abs() < 0.01);
+        assert_eq!(parse_rational("0/0"), None);
+        assert_eq!(parse_rational("30"), None);
+    }
+
+    #[test]
+    fn test_media_kind_from_path() {
+        assert_eq!(
+            MediaKind::from_path(Path::new("/a/title.PNG")),
+            MediaKind::Image
+        );
+        assert_eq!(
+            MediaKind::from_path(Path::new("/a/meme.gif")),
+            MediaKind::Image
+        );
+        assert_eq!(
+            MediaKind::from_path(Path::new("/a/clip.mp4")),
+            MediaKind::Video
+        );
+        assert_eq!(MediaKind::from_path(Path::new("/a/clip")), MediaKind::Video);
+    }
+
+    #[test]
+    fn test_pixel_format_bit_depth() {
+        assert_eq!(pixel_format_bit_depth("yuv420p"), 8);
+        assert_eq!(pixel_format_bit_depth("yuv420p10le"), 10);
+        assert_eq!(pixel_format_bit_depth("p010le"), 10);
+        assert_eq!(pixel_format_bit_depth("yuv444p12be"), 12);
+        assert_eq!(pixel_format_bit_depth("nv12"), 8);
+    }
+
+    #[test]
+    fn test_is_hdr_transfer() {
+        assert!(is_hdr_transfer("smpte2084"));
+        assert!(is_hdr_transfer("arib-std-b67"));
+        assert!(!is_hdr_transfer("bt709"));
+    }
+
+    #[test]
+    fn test_stream_rotation() {
+        let side_data = vec![FFprobeSideData {
+            rotation: Some(-90.0),
+        }];
+        assert_eq!(stream_rotation(&FFprobeTags::default(), &side_data), 90);
+
+        let tags = FFprobeTags {
+            rotate: Some("270".to_string()),
+        };
+        assert_eq!(stream_rotation(&tags, &[]), 270);
+        assert_eq!(stream_rotation(&FFprobeTags::default(), &[]), 0);
+    }
+}
@@ -1,10 +1,47 @@
+pub mod analysis;
+pub mod app_cache;
+pub mod audio_filters;
+pub mod autosave;
 pub mod camera_sources;
+pub mod color_correction;
+pub mod control_server;
+pub mod cursor;
+pub mod deep_link;
+pub mod dry_run;
 pub mod export;
+pub mod export_queue;
+pub mod ffmpeg_args;
 pub mod ffmpeg_utils;
+pub mod hotkeys;
+pub mod interchange;
+pub mod media_cache;
+pub mod menu;
 pub mod metadata;
+pub mod onboarding;
 pub mod permissions;
+pub mod pip;
 pub mod preview;
+pub mod process_runner;
+pub mod projects;
+pub mod proxy;
+pub mod quick_edit;
+pub mod recents;
 pub mod recording;
+pub mod reframe;
+pub mod render_cache;
 pub mod screen_sources;
+pub mod settings;
+pub mod sharing;
+pub mod stingers;
+pub mod storage;
+pub mod subtitles;
+pub mod target_size;
 pub mod thumbnail;
+pub mod transcription;
+pub mod uploads;
 pub mod video_import;
+pub mod virtual_camera;
+pub mod watch_folder;
+pub mod waveform;
+pub mod webhooks;
+pub mod window_frame;
@@ -0,0 +1,245 @@
+// First-run onboarding
+//
+// `get_onboarding_status` gathers everything a setup wizard needs in one call:
+// whether FFmpeg and ffprobe are installed, the state of each capture
+// permission, the default display and camera, and free disk space. Each
+// outstanding item is a `SetupStep` the frontend can hand to `run_setup_step`,
+// which requests the permission (or opens the settings pane or download page)
+// and returns the refreshed status.
+
+use super::camera_sources::{get_default_camera, CameraDevice};
+use super::ffmpeg_utils::{find_ffmpeg, find_ffprobe};
+use super::permissions::{PermissionHandler, PlatformPermissions};
+use super::process_runner::{JobKind, WatchedOutput};
+use super::recording::{
+    get_disk_space_info, DiskSpaceInfo, PermissionResult, PermissionStatus, PermissionType,
+};
+use super::screen_sources::{PlatformEnumerator as ScreenEnum, ScreenSource, SourceEnumerator};
+use crate::error::ClipForgeError;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+/// Where users are sent to install FFmpeg
+const FFMPEG_DOWNLOAD_URL: &str = "https://ffmpeg.org/download.html";
+
+/// A step of the setup wizard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SetupStep {
+    Ffmpeg,
+    ScreenPermission,
+    CameraPermission,
+    MicrophonePermission,
+}
+
+impl SetupStep {
+    const ALL: [SetupStep; 4] = [
+        SetupStep::Ffmpeg,
+        SetupStep::ScreenPermission,
+        SetupStep::CameraPermission,
+        SetupStep::MicrophonePermission,
+    ];
+
+    fn permission(&self) -> Option<PermissionType> {
+        match self {
+            SetupStep::Ffmpeg => None,
+            SetupStep::ScreenPermission => Some(PermissionType::Screen),
+            SetupStep::CameraPermission => Some(PermissionType::Camera),
+            SetupStep::MicrophonePermission => Some(PermissionType::Microphone),
+        }
+    }
+
+    /// Steps ClipForge can't record without; the rest only enable optional sources
+    fn required(&self) -> bool {
+        matches!(self, SetupStep::Ffmpeg | SetupStep::ScreenPermission)
+    }
+}
+
+/// An external tool and where it was found
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolStatus {
+    pub available: bool,
+    pub path: Option<String>,
+    /// Version from `-version`, e.g. "6.1.1"
+    pub version: Option<String>,
+}
+
+impl ToolStatus {
+    fn detect(path: Option<&Path>) -> Self {
+        let version = path.and_then(|path| {
+            let output = Command::new(path)
+                .arg("-version")
+                .watched_output(JobKind::Probe)
+                .ok()?;
+            parse_version(&String::from_utf8_lossy(&output.stdout))
+        });
+
+        Self {
+            available: path.is_some(),
+            path: path.map(|path| path.to_string_lossy().to_string()),
+            version,
+        }
+    }
+}
+
+/// Progress of one setup step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupStepStatus {
+    pub step: SetupStep,
+    pub complete: bool,
+    pub required: bool,
+}
+
+/// Everything the setup wizard shows
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStatus {
+    pub ffmpeg: ToolStatus,
+    pub ffprobe: ToolStatus,
+    pub permissions: Vec<PermissionResult>,
+    /// Primary display
+    pub default_display: Option<ScreenSource>,
+    pub default_camera: Option<CameraDevice>,
+    pub disk_space: Option<DiskSpaceInfo>,
+    pub steps: Vec<SetupStepStatus>,
+    /// True once every required step is complete
+    pub ready: bool,
+}
+
+/// Version number from the first line of `ffmpeg -version` / `ffprobe -version`
+fn parse_version(output: &str) -> Option<String> {
+    let line = output.lines().next()?;
+    let rest = &line[line.find(" version ")? + " version ".len()..];
+    rest.split_whitespace()
+        .next()
+        .map(|version| version.to_string())
+}
+
+fn step_complete(
+    step: SetupStep,
+    ffmpeg: &ToolStatus,
+    ffprobe: &ToolStatus,
+    permissions: &[PermissionResult],
+) -> bool {
+    match step.permission() {
+        Some(permission) => permissions.iter().any(|result| {
+            result.permission_type == permission && result.status == PermissionStatus::Granted
+        }),
+        None => ffmpeg.available && ffprobe.available,
+    }
+}
+
+fn collect_status_sync() -> (ToolStatus, ToolStatus, Vec<PermissionResult>) {
+    let ffmpeg = ToolStatus::detect(find_ffmpeg().as_deref());
+    let ffprobe = ToolStatus::detect(find_ffprobe().as_deref());
+    let permissions = SetupStep::ALL
+        .iter()
+        .filter_map(|step| step.permission())
+        .map(|permission| PlatformPermissions::check_permission(&permission))
+        .collect();
+    (ffmpeg, ffprobe, permissions)
+}
+
+/// Report tools, permissions, default devices and disk space for the setup wizard
+#[tauri::command]
+pub async fn get_onboarding_status() -> Result<OnboardingStatus, ClipForgeError> {
+    let (ffmpeg, ffprobe, permissions) = collect_status_sync();
+
+    let steps: Vec<SetupStepStatus> = SetupStep::ALL
+        .iter()
+        .map(|step| SetupStepStatus {
+            step: *step,
+            complete: step_complete(*step, &ffmpeg, &ffprobe, &permissions),
+            required: step.required(),
+        })
+        .collect();
+    let ready = steps.iter().all(|step| step.complete || !step.required);
+
+    let default_display = ScreenEnum::enumerate_screens_fast()
+        .ok()
+        .and_then(|screens| {
+            let primary = screens.iter().position(|screen| screen.is_primary);
+            screens.into_iter().nth(primary.unwrap_or(0))
+        });
+
+    Ok(OnboardingStatus {
+        ffmpeg,
+        ffprobe,
+        permissions,
+        default_display,
+        default_camera: get_default_camera().await.ok().flatten(),
+        disk_space: get_disk_space_info(None, None, None).await.ok(),
+        steps,
+        ready,
+    })
+}
+
+/// Act on a setup step, then return the refreshed status
+///
+/// Permission steps show the system prompt the first time and open the privacy
+/// settings pane once access has been denied; the FFmpeg step opens the
+/// download page when FFmpeg is missing.
+#[tauri::command]
+pub async fn run_setup_step(
+    step: SetupStep,
+    app: AppHandle,
+) -> Result<OnboardingStatus, ClipForgeError> {
+    match step.permission() {
+        Some(permission) => {
+            let result = PlatformPermissions::check_permission(&permission);
+            match result.status {
+                PermissionStatus::Granted => {}
+                PermissionStatus::NotDetermined => {
+                    PlatformPermissions::request_permission(&permission);
+                }
+                PermissionStatus::Denied | PermissionStatus::Restricted => {
+                    PlatformPermissions::open_privacy_settings(&permission)?;
+                }
+            }
+        }
+        None => {
+            if find_ffmpeg().is_none() || find_ffprobe().is_none() {
+                app.opener()
+                    .open_url(FFMPEG_DOWNLOAD_URL, None::<&str>)
+                    .map_err(|e| format!("Failed to open the FFmpeg download page: {}", e))?;
+            }
+        }
+    }
+
+    get_onboarding_status().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(
+            parse_version("ffmpeg version 6.1.1 Copyright (c) 2000-2023 the FFmpeg developers\n"),
+            Some("6.1.1".to_string())
+        );
+        assert_eq!(
+            parse_version("ffprobe version n7.0-14-gabc Copyright"),
+            Some("n7.0-14-gabc".to_string())
+        );
+        assert_eq!(parse_version(""), None);
+    }
+
+    #[test]
+    fn test_required_steps() {
+        let missing = ToolStatus {
+            available: false,
+            path: None,
+            version: None,
+        };
+        assert!(!step_complete(SetupStep::Ffmpeg, &missing, &missing, &[]));
+        assert!(SetupStep::ScreenPermission.required());
+        assert!(!SetupStep::CameraPermission.required());
+    }
+}
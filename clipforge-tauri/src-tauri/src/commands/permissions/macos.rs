@@ -1,9 +1,24 @@
 use super::PermissionHandler;
 use crate::commands::recording::{PermissionResult, PermissionStatus, PermissionType};
 use block::ConcreteBlock;
-use objc::runtime::{BOOL, YES};
+use objc::runtime::{Object, BOOL, YES};
 use objc::{class, msg_send, sel, sel_impl};
 use objc_foundation::{INSString, NSString};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Privacy & Security pane, used when a permission's own pane can't be opened
+const PRIVACY_SETTINGS_URL: &str =
+    "x-apple.systempreferences:com.apple.preference.security?Privacy";
+
+/// Set once screen recording access has been requested in this session
+static SCREEN_ACCESS_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Set once accessibility access has been requested in this session
+static ACCESSIBILITY_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// kIOHIDRequestTypeListenEvent
+const IOHID_REQUEST_TYPE_LISTEN_EVENT: u32 = 1;
 
 /// macOS-specific permission implementation
 pub struct PlatformPermissions;
@@ -39,11 +54,15 @@ impl PlatformPermissions {
 
     /// Check screen recording permission status
     fn check_screen_permission() -> PermissionStatus {
-        // On macOS 10.15+, screen recording requires special permission
-        // Unfortunately, there's no direct API to check screen recording permission status
-        // The only way to truly verify is to attempt screen capture
-        // For now, we'll return NotDetermined to prompt a request
-        PermissionStatus::NotDetermined
+        // CGPreflightScreenCaptureAccess only reports whether access is granted. macOS
+        // prompts once per app, so a refusal after our own request means it was denied
+        if unsafe { CGPreflightScreenCaptureAccess() } {
+            PermissionStatus::Granted
+        } else if SCREEN_ACCESS_REQUESTED.load(Ordering::SeqCst) {
+            PermissionStatus::Denied
+        } else {
+            PermissionStatus::NotDetermined
+        }
     }
 
     /// Request camera permission
@@ -105,12 +124,72 @@ impl PlatformPermissions {
 
     /// Request screen recording permission
     fn request_screen_permission() -> PermissionStatus {
-        // For screen recording on macOS 10.15+, permissions are requested automatically
-        // when you first attempt to capture the screen. There's no direct API to request
-        // permission ahead of time without actually starting a capture session.
-        // We return NotDetermined to indicate the app should attempt capture,
-        // which will trigger the system permission dialog if needed.
-        PermissionStatus::NotDetermined
+        if unsafe { CGPreflightScreenCaptureAccess() } {
+            return PermissionStatus::Granted;
+        }
+
+        // Shows the system prompt the first time only; afterwards it just returns false.
+        // A grant made in System Settings only takes effect after the app restarts
+        SCREEN_ACCESS_REQUESTED.store(true, Ordering::SeqCst);
+        if unsafe { CGRequestScreenCaptureAccess() } {
+            PermissionStatus::Granted
+        } else {
+            PermissionStatus::Denied
+        }
+    }
+
+    /// Check input monitoring (keystroke and click listening) permission status
+    fn check_input_monitoring_permission() -> PermissionStatus {
+        let access = unsafe { IOHIDCheckAccess(IOHID_REQUEST_TYPE_LISTEN_EVENT) };
+        match access {
+            0 => PermissionStatus::Granted,       // kIOHIDAccessTypeGranted
+            1 => PermissionStatus::Denied,        // kIOHIDAccessTypeDenied
+            _ => PermissionStatus::NotDetermined, // kIOHIDAccessTypeUnknown
+        }
+    }
+
+    /// Request input monitoring permission
+    fn request_input_monitoring_permission() -> PermissionStatus {
+        // Shows the system prompt the first time; afterwards it reports the stored choice
+        if unsafe { IOHIDRequestAccess(IOHID_REQUEST_TYPE_LISTEN_EVENT) } {
+            PermissionStatus::Granted
+        } else {
+            PermissionStatus::Denied
+        }
+    }
+
+    /// Check accessibility permission status
+    fn check_accessibility_permission() -> PermissionStatus {
+        // Like screen recording, macOS only reports whether the app is trusted
+        if unsafe { AXIsProcessTrusted() } {
+            PermissionStatus::Granted
+        } else if ACCESSIBILITY_REQUESTED.load(Ordering::SeqCst) {
+            PermissionStatus::Denied
+        } else {
+            PermissionStatus::NotDetermined
+        }
+    }
+
+    /// Request accessibility permission
+    fn request_accessibility_permission() -> PermissionStatus {
+        ACCESSIBILITY_REQUESTED.store(true, Ordering::SeqCst);
+
+        // Passing the prompt option shows the system dialog pointing at System Settings
+        let trusted = unsafe {
+            let prompt: *mut Object = msg_send![class!(NSNumber), numberWithBool: YES];
+            let options: *mut Object = msg_send![
+                class!(NSDictionary),
+                dictionaryWithObject: prompt
+                forKey: kAXTrustedCheckOptionPrompt
+            ];
+            AXIsProcessTrustedWithOptions(options)
+        };
+
+        if trusted {
+            PermissionStatus::Granted
+        } else {
+            PermissionStatus::Denied
+        }
     }
 
     /// Convert AVAuthorizationStatus to our PermissionStatus
@@ -131,6 +210,8 @@ impl PermissionHandler for PlatformPermissions {
             PermissionType::Camera => Self::check_camera_permission(),
             PermissionType::Microphone => Self::check_microphone_permission(),
             PermissionType::Screen => Self::check_screen_permission(),
+            PermissionType::InputMonitoring => Self::check_input_monitoring_permission(),
+            PermissionType::Accessibility => Self::check_accessibility_permission(),
         };
 
         PermissionResult::new(permission_type.clone(), status)
@@ -141,8 +222,46 @@ impl PermissionHandler for PlatformPermissions {
             PermissionType::Camera => Self::request_camera_permission(),
             PermissionType::Microphone => Self::request_microphone_permission(),
             PermissionType::Screen => Self::request_screen_permission(),
+            PermissionType::InputMonitoring => Self::request_input_monitoring_permission(),
+            PermissionType::Accessibility => Self::request_accessibility_permission(),
         };
 
         PermissionResult::new(permission_type.clone(), status)
     }
+
+    fn open_privacy_settings(permission_type: &PermissionType) -> Result<(), String> {
+        // Fall back to the top of Privacy & Security if the exact pane can't be opened
+        for url in [permission_type.settings_url(), PRIVACY_SETTINGS_URL] {
+            let opened = Command::new("open")
+                .arg(url)
+                .status()
+                .map(|status| status.success())
+                .unwrap_or(false);
+            if opened {
+                println!("[Permissions] Opened {}", url);
+                return Ok(());
+            }
+        }
+
+        Err("Failed to open System Settings".to_string())
+    }
+}
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGPreflightScreenCaptureAccess() -> bool;
+    fn CGRequestScreenCaptureAccess() -> bool;
+}
+
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    static kAXTrustedCheckOptionPrompt: *mut Object;
+    fn AXIsProcessTrusted() -> bool;
+    fn AXIsProcessTrustedWithOptions(options: *mut Object) -> bool;
+}
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    fn IOHIDCheckAccess(request_type: u32) -> u32;
+    fn IOHIDRequestAccess(request_type: u32) -> bool;
 }
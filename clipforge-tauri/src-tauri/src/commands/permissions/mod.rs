@@ -12,7 +12,21 @@ pub use macos::*;
 #[cfg(not(target_os = "macos"))]
 pub use stub::*;
 
-use super::recording::{PermissionResult, PermissionType};
+use super::recording::{PermissionResult, PermissionStatus, PermissionType};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+/// Default and minimum interval between permission checks
+const DEFAULT_PERMISSION_POLL_MS: u64 = 2000;
+const MIN_PERMISSION_POLL_MS: u64 = 500;
+
+/// Permissions re-checked by the watcher
+const WATCHED_PERMISSIONS: [PermissionType; 3] = [
+    PermissionType::Screen,
+    PermissionType::Camera,
+    PermissionType::Microphone,
+];
 
 /// Trait for platform-specific permission handling
 pub trait PermissionHandler {
@@ -21,4 +35,87 @@ pub trait PermissionHandler {
 
     /// Request a permission from the user
     fn request_permission(permission_type: &PermissionType) -> PermissionResult;
+
+    /// Open the system settings pane where the permission can be granted
+    fn open_privacy_settings(permission_type: &PermissionType) -> Result<(), String>;
+}
+
+/// Background task that reports permission changes while the recorder is open
+#[derive(Default)]
+pub struct PermissionWatcher {
+    task: Option<tauri::async_runtime::JoinHandle<()>>,
+}
+
+impl PermissionWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop watching for permission changes
+    pub fn stop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
+
+impl Drop for PermissionWatcher {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Thread-safe permission watcher type
+pub type PermissionWatcherState = Arc<Mutex<PermissionWatcher>>;
+
+/// Start re-checking permissions periodically
+///
+/// Emits `permissions:changed` with the new `PermissionResult` whenever a permission's
+/// status differs from the previous check. Starting again replaces the previous watcher
+#[tauri::command]
+pub async fn start_permission_watcher(
+    interval_ms: Option<u64>,
+    app: AppHandle,
+    state: State<'_, PermissionWatcherState>,
+) -> Result<(), String> {
+    let interval_ms = interval_ms
+        .unwrap_or(DEFAULT_PERMISSION_POLL_MS)
+        .max(MIN_PERMISSION_POLL_MS);
+
+    let mut watcher = state.lock().map_err(|e| e.to_string())?;
+    watcher.stop();
+
+    watcher.task = Some(tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
+        let mut last: Vec<Option<PermissionStatus>> = vec![None; WATCHED_PERMISSIONS.len()];
+
+        loop {
+            interval.tick().await;
+
+            for (permission_type, last_status) in WATCHED_PERMISSIONS.iter().zip(last.iter_mut()) {
+                let result = PlatformPermissions::check_permission(permission_type);
+
+                // The first check only records the starting state
+                if last_status.as_ref().is_some_and(|s| *s != result.status) {
+                    println!(
+                        "[Permissions] {:?} changed to {:?}",
+                        permission_type, result.status
+                    );
+                    let _ = app.emit("permissions:changed", &result);
+                }
+                *last_status = Some(result.status);
+            }
+        }
+    }));
+
+    Ok(())
+}
+
+/// Stop the permission watcher
+#[tauri::command]
+pub async fn stop_permission_watcher(
+    state: State<'_, PermissionWatcherState>,
+) -> Result<(), String> {
+    state.lock().map_err(|e| e.to_string())?.stop();
+    Ok(())
 }
@@ -16,4 +16,9 @@ impl PermissionHandler for PlatformPermissions {
         // TODO: Implement Windows and Linux permission requests
         PermissionResult::new(permission_type.clone(), PermissionStatus::Granted)
     }
+
+    fn open_privacy_settings(_permission_type: &PermissionType) -> Result<(), String> {
+        // TODO: Implement Windows and Linux settings links
+        Err("Opening permission settings is not supported on this platform".to_string())
+    }
 }
@@ -0,0 +1,1113 @@
+// Picture-in-picture layout and compositing
+//
+// The overlay layout here is the single source of truth for the
+// composite_pip_recording command and the export path, and must match
+// calculateOverlayLayout in src/hooks/useCompositePreview.js so the live preview
+// lines up with the rendered file.
+//
+// Besides the overlay, a recording can be composited side by side, as a 9:16
+// vertical stack, or as a full-frame webcam with the screen inset (see PipMode).
+
+use super::process_runner::{JobKind, WatchedOutput};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Gap between the overlay and the screen edge (pixels)
+pub const EDGE_PADDING: u32 = 20;
+
+/// Smallest overlay width or height (pixels)
+const MIN_OVERLAY_SIZE: f64 = 32.0;
+
+/// Aspect ratio assumed when the webcam size is unknown
+const DEFAULT_WEBCAM_ASPECT: f64 = 16.0 / 9.0;
+
+/// Overlay width as a fraction of the screen width ("small", "medium", "large")
+pub fn size_factor(size: &str) -> f64 {
+    match size {
+        "small" => 0.12,
+        "large" => 0.24,
+        _ => 0.18,
+    }
+}
+
+/// Overlay rectangle in screen pixels
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PipLayout {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl PipLayout {
+    /// Place the webcam overlay in a corner of the screen
+    ///
+    /// The overlay keeps the webcam's aspect ratio and has even dimensions for H.264.
+    /// Unknown positions fall back to the bottom right.
+    pub fn calculate(
+        position: &str,
+        size: &str,
+        screen_width: u32,
+        screen_height: u32,
+        webcam_size: Option<(u32, u32)>,
+    ) -> Self {
+        let webcam_aspect = match webcam_size {
+            Some((width, height)) if width > 0 && height > 0 => width as f64 / height as f64,
+            _ => DEFAULT_WEBCAM_ASPECT,
+        };
+
+        let mut width = (screen_width as f64 * size_factor(size)).round().clamp(
+            MIN_OVERLAY_SIZE,
+            (screen_width as f64).max(MIN_OVERLAY_SIZE),
+        ) as u32;
+        let mut height = (width as f64 / webcam_aspect).round().clamp(
+            MIN_OVERLAY_SIZE,
+            (screen_height as f64).max(MIN_OVERLAY_SIZE),
+        ) as u32;
+
+        if !width.is_multiple_of(2) {
+            width = width.saturating_sub(1).max(2);
+        }
+        if !height.is_multiple_of(2) {
+            height = height.saturating_sub(1).max(2);
+        }
+
+        let right = screen_width.saturating_sub(width + EDGE_PADDING);
+        let bottom = screen_height.saturating_sub(height + EDGE_PADDING);
+        let (x, y) = match position {
+            "topLeft" => (EDGE_PADDING, EDGE_PADDING),
+            "topRight" => (right, EDGE_PADDING),
+            "bottomLeft" => (EDGE_PADDING, bottom),
+            _ => (right, bottom),
+        };
+
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Move the overlay to a free position
+    ///
+    /// `x` and `y` run from 0.0 (left/top edge) to 1.0 (right/bottom edge) of the
+    /// area the overlay can move in, so any value keeps it fully on screen.
+    pub fn place_at(self, x: f64, y: f64, screen_width: u32, screen_height: u32) -> Self {
+        let offset =
+            |fraction: f64, room: u32| (fraction.clamp(0.0, 1.0) * room as f64).round() as u32;
+        Self {
+            x: offset(x, screen_width.saturating_sub(self.width)),
+            y: offset(y, screen_height.saturating_sub(self.height)),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PiPConfiguration {
+    pub position: String,
+    pub size: String,
+    #[serde(rename = "cameraId")]
+    pub camera_id: Option<String>,
+    #[serde(rename = "includeAudio")]
+    pub include_audio: bool,
+    #[serde(rename = "audioDeviceId")]
+    pub audio_device_id: Option<String>,
+    /// Free position (see `PipLayout::place_at`); replaces the corner when both are set
+    #[serde(default)]
+    pub x: Option<f64>,
+    #[serde(default)]
+    pub y: Option<f64>,
+    #[serde(default)]
+    pub style: PipStyle,
+    #[serde(default)]
+    pub mode: PipMode,
+}
+
+impl PiPConfiguration {
+    /// This configuration with a clip's overrides applied
+    ///
+    /// Choosing a corner in the override drops any free position from the recording.
+    pub fn with_override(&self, pip_override: &PipOverride) -> Self {
+        let (x, y) = match (pip_override.x, pip_override.y) {
+            (Some(x), Some(y)) => (Some(x), Some(y)),
+            _ if pip_override.position.is_some() => (None, None),
+            _ => (self.x, self.y),
+        };
+        Self {
+            position: pip_override
+                .position
+                .clone()
+                .unwrap_or_else(|| self.position.clone()),
+            size: pip_override
+                .size
+                .clone()
+                .unwrap_or_else(|| self.size.clone()),
+            x,
+            y,
+            ..self.clone()
+        }
+    }
+}
+
+/// Per-clip changes to where the webcam overlay is drawn
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PipOverride {
+    pub position: Option<String>,
+    pub size: Option<String>,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+}
+
+/// How the screen and webcam are arranged in the composite
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PipMode {
+    /// Webcam overlay on top of the screen
+    #[default]
+    Overlay,
+    /// Screen and webcam in two halves, webcam on the side named by `position`
+    SideBySide,
+    /// 9:16 frame with the screen on top and the webcam below (or above, for
+    /// top positions) for Shorts and Reels
+    Vertical,
+    /// Full-frame webcam with the screen as the overlay
+    CameraInset,
+}
+
+/// Outline of the webcam overlay
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PipShape {
+    #[default]
+    Rectangle,
+    /// Rectangle with rounded corners (see `PipStyle::corner_radius`)
+    Rounded,
+    /// Circle cropped from the center of the webcam picture
+    Circle,
+}
+
+/// How the webcam overlay is drawn
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PipStyle {
+    pub shape: PipShape,
+    /// Corner radius of `Rounded` overlays as a fraction of the shorter side (0.0 - 0.5)
+    pub corner_radius: f64,
+    /// Border drawn around the overlay (pixels, 0 for none)
+    pub border_width: u32,
+    /// Border color as hex RGB, e.g. "#FFFFFF"
+    pub border_color: String,
+    /// Draw a soft drop shadow beneath the overlay
+    pub shadow: bool,
+}
+
+impl Default for PipStyle {
+    fn default() -> Self {
+        Self {
+            shape: PipShape::Rectangle,
+            corner_radius: 0.15,
+            border_width: 0,
+            border_color: "#FFFFFF".to_string(),
+            shadow: false,
+        }
+    }
+}
+
+impl PipStyle {
+    /// Widest border accepted (pixels)
+    pub const MAX_BORDER_WIDTH: u32 = 40;
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.border_width > Self::MAX_BORDER_WIDTH {
+            return Err(format!(
+                "PiP border width must be at most {}px",
+                Self::MAX_BORDER_WIDTH
+            ));
+        }
+        self.border_rgb().map(|_| ())
+    }
+
+    fn border_rgb(&self) -> Result<(u8, u8, u8), String> {
+        let hex = self
+            .border_color
+            .trim_start_matches('#')
+            .trim_start_matches("0x");
+        let channel = |i: usize| {
+            hex.get(i..i + 2)
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+        };
+        match (hex.len(), channel(0), channel(2), channel(4)) {
+            (6, Some(r), Some(g), Some(b)) => Ok((r, g, b)),
+            _ => Err(format!(
+                "Invalid PiP border color '{}', expected hex like #FFFFFF",
+                self.border_color
+            )),
+        }
+    }
+
+    /// Whether the webcam is drawn as a plain scaled rectangle
+    fn is_plain(&self) -> bool {
+        self.shape == PipShape::Rectangle && self.border_width == 0 && !self.shadow
+    }
+
+    /// Blur radius of the drop shadow for an overlay `height` pixels tall
+    fn shadow_blur(height: u32) -> u32 {
+        (height / 20).max(4)
+    }
+
+    /// How far the styled overlay reaches left of and above the webcam picture
+    fn offset(&self, layout: &PipLayout) -> (u32, u32) {
+        let border = self.border_width;
+        if self.shadow {
+            let blur = Self::shadow_blur(layout.height + border * 2);
+            (border + blur, border + blur / 2)
+        } else {
+            (border, border)
+        }
+    }
+
+    /// Filters turning the webcam stream `source` into the styled overlay `[name]`
+    ///
+    /// The shape and border are cut with a single geq pass over an RGBA frame
+    /// padded by the border width; the shadow is a blurred, darkened copy of the
+    /// result drawn slightly lower.
+    fn overlay_filters(&self, source: &str, layout: &PipLayout, name: &str) -> Vec<String> {
+        let (width, height) = (layout.width, layout.height);
+        if self.is_plain() {
+            return vec![format!("{}scale={}:{}[{}]", source, width, height, name)];
+        }
+
+        let border = self.border_width;
+        let (frame_width, frame_height) = (width + border * 2, height + border * 2);
+        let shorter = width.min(height) as f64;
+        let radius = match self.shape {
+            PipShape::Rectangle => 0.0,
+            PipShape::Rounded => self.corner_radius.clamp(0.0, 0.5) * shorter,
+            PipShape::Circle => shorter / 2.0,
+        };
+        let outer_radius = if radius > 0.0 {
+            radius + border as f64
+        } else {
+            0.0
+        };
+
+        let center = (frame_width as f64 / 2.0, frame_height as f64 / 2.0);
+        let inner = shape_test(center, width as f64 / 2.0, height as f64 / 2.0, radius);
+        let outer = shape_test(
+            center,
+            frame_width as f64 / 2.0,
+            frame_height as f64 / 2.0,
+            outer_radius,
+        );
+
+        let (r, g, b) = self.border_rgb().unwrap_or((255, 255, 255));
+        let color = |channel: &str, value: u8| {
+            if border == 0 {
+                format!("{}='{}(X,Y)'", channel, channel)
+            } else {
+                format!("{}='if({},{}(X,Y),{})'", channel, inner, channel, value)
+            }
+        };
+
+        let mut filters = Vec::new();
+        let mut webcam = format!(
+            "{}scale={w}:{h}:force_original_aspect_ratio=increase,crop={w}:{h},setsar=1,format=rgba,pad={fw}:{fh}:{b}:{b}:color=black@0,geq={}:{}:{}:a='if({},255,0)'",
+            source,
+            color("r", r),
+            color("g", g),
+            color("b", b),
+            outer,
+            w = width,
+            h = height,
+            fw = frame_width,
+            fh = frame_height,
+            b = border
+        );
+
+        if self.shadow {
+            let blur = Self::shadow_blur(frame_height);
+            webcam.push_str(&format!(",split[{0}_fg][{0}_sh]", name));
+            filters.push(webcam);
+            filters.push(format!(
+                "[{0}_sh]colorchannelmixer=rr=0:gg=0:bb=0:aa=0.45,pad=iw+{1}:ih+{1}:{2}:{2}:color=black@0,boxblur={2}:2[{0}_blur]",
+                name,
+                blur * 2,
+                blur
+            ));
+            filters.push(format!(
+                "[{0}_blur][{0}_fg]overlay={1}:{2}:format=auto[{0}]",
+                name,
+                blur,
+                blur / 2
+            ));
+        } else {
+            webcam.push_str(&format!("[{}]", name));
+            filters.push(webcam);
+        }
+        filters
+    }
+}
+
+/// geq expression that is 1 inside a rectangle with rounded corners
+///
+/// The rectangle is centered on `center` with half sizes `half_width` and
+/// `half_height`.
+fn shape_test(center: (f64, f64), half_width: f64, half_height: f64, radius: f64) -> String {
+    let dx = format!("abs(X-{})", center.0);
+    let dy = format!("abs(Y-{})", center.1);
+    let in_box = format!("lte({},{})*lte({},{})", dx, half_width, dy, half_height);
+    if radius <= 0.0 {
+        return in_box;
+    }
+
+    let (corner_x, corner_y) = (half_width - radius, half_height - radius);
+    format!(
+        "{in_box}*if(gt({dx},{cx})*gt({dy},{cy}),lte(hypot({dx}-{cx},{dy}-{cy}),{r}),1)",
+        in_box = in_box,
+        dx = dx,
+        dy = dy,
+        cx = corner_x,
+        cy = corner_y,
+        r = radius
+    )
+}
+
+/// Change to the overlay between two recording times (seconds)
+///
+/// e.g. hide the webcam from 60 to 150, or use the large size for the intro.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PipKeyframe {
+    pub start: f64,
+    pub end: f64,
+    pub hidden: bool,
+    #[serde(flatten)]
+    pub overlay: PipOverride,
+}
+
+/// Time range drawn with its own layout, or with no overlay when `layout` is None
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PipSegment {
+    pub start: f64,
+    pub end: f64,
+    pub layout: Option<PipLayout>,
+}
+
+/// Everything the compositor needs to combine the screen and webcam
+#[derive(Debug, Clone, Default)]
+pub struct PipComposition {
+    pub mode: PipMode,
+    /// Size of the screen recording; the output size for every mode but `Vertical`
+    pub screen_size: (u32, u32),
+    /// Overlay rectangle (the screen's, for `CameraInset`)
+    pub layout: PipLayout,
+    /// Keyframed changes (see `PiPMetadata::segments`)
+    pub segments: Vec<PipSegment>,
+    pub style: PipStyle,
+    /// Put the webcam first (left or top) in `SideBySide` and `Vertical`
+    pub camera_first: bool,
+}
+
+impl PipComposition {
+    /// The webcam drawn with `layout` for the whole recording
+    pub fn fixed(layout: PipLayout) -> Self {
+        Self {
+            layout,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScreenDimensions {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Sidecar written by the frontend for each PiP recording (see save_pip_metadata)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PiPMetadata {
+    pub id: String,
+    #[serde(rename = "startTime")]
+    pub start_time: u64,
+    pub duration: f64,
+    #[serde(rename = "screenFilePath")]
+    pub screen_file_path: String,
+    #[serde(rename = "webcamFilePath")]
+    pub webcam_file_path: String,
+    #[serde(rename = "pipConfig")]
+    pub pip_config: PiPConfiguration,
+    #[serde(rename = "screenDimensions")]
+    pub screen_dimensions: ScreenDimensions,
+    #[serde(rename = "webcamDimensions")]
+    pub webcam_dimensions: ScreenDimensions,
+    #[serde(default)]
+    pub keyframes: Vec<PipKeyframe>,
+}
+
+impl PiPMetadata {
+    /// Load PiP metadata from its JSON file
+    pub fn load(metadata_path: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(metadata_path)
+            .map_err(|e| format!("Failed to read PiP metadata file: {}", e))?;
+
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse PiP metadata: {}", e))
+    }
+
+    /// Layout, keyframes and style for compositing this recording
+    pub fn composition(
+        &self,
+        pip_override: Option<&PipOverride>,
+    ) -> Result<PipComposition, String> {
+        let config = self.config(pip_override);
+        config.style.validate()?;
+
+        let mode = config.mode;
+        if matches!(mode, PipMode::SideBySide | PipMode::Vertical) && !self.keyframes.is_empty() {
+            return Err("PiP keyframes need the overlay or camera inset layout".to_string());
+        }
+        let camera_first = match mode {
+            PipMode::SideBySide => config.position.ends_with("Left"),
+            _ => config.position.starts_with("top"),
+        };
+
+        Ok(PipComposition {
+            mode,
+            screen_size: (self.screen_dimensions.width, self.screen_dimensions.height),
+            layout: self.layout_for(&config),
+            segments: self.segments(pip_override)?,
+            style: config.style,
+            camera_first,
+        })
+    }
+
+    /// Keyframed segments, sorted by start time, on top of a clip's overrides
+    pub fn segments(&self, pip_override: Option<&PipOverride>) -> Result<Vec<PipSegment>, String> {
+        let config = self.config(pip_override);
+        let mut keyframes: Vec<&PipKeyframe> = self.keyframes.iter().collect();
+        keyframes.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+        let mut segments: Vec<PipSegment> = Vec::with_capacity(keyframes.len());
+        for keyframe in keyframes {
+            if !(keyframe.start >= 0.0 && keyframe.end > keyframe.start) {
+                return Err(format!(
+                    "PiP keyframe {}-{}s must end after it starts",
+                    keyframe.start, keyframe.end
+                ));
+            }
+            if let Some(previous) = segments.last() {
+                if keyframe.start < previous.end {
+                    return Err(format!(
+                        "PiP keyframes {}-{}s and {}-{}s overlap",
+                        previous.start, previous.end, keyframe.start, keyframe.end
+                    ));
+                }
+            }
+
+            segments.push(PipSegment {
+                start: keyframe.start,
+                end: keyframe.end,
+                layout: (!keyframe.hidden)
+                    .then(|| self.layout_for(&config.with_override(&keyframe.overlay))),
+            });
+        }
+        Ok(segments)
+    }
+
+    fn config(&self, pip_override: Option<&PipOverride>) -> PiPConfiguration {
+        match pip_override {
+            Some(pip_override) => self.pip_config.with_override(pip_override),
+            None => self.pip_config.clone(),
+        }
+    }
+
+    fn layout_for(&self, config: &PiPConfiguration) -> PipLayout {
+        let (screen_width, screen_height) =
+            (self.screen_dimensions.width, self.screen_dimensions.height);
+
+        // Circles are cropped square from the picture; a camera inset shows the screen
+        let webcam_size = match (config.style.shape, config.mode) {
+            (PipShape::Circle, _) => (1, 1),
+            (_, PipMode::CameraInset) => (screen_width, screen_height),
+            _ => (self.webcam_dimensions.width, self.webcam_dimensions.height),
+        };
+
+        let layout = PipLayout::calculate(
+            &config.position,
+            &config.size,
+            screen_width,
+            screen_height,
+            Some(webcam_size),
+        );
+        match (config.x, config.y) {
+            (Some(x), Some(y)) => layout.place_at(x, y, screen_width, screen_height),
+            _ => layout,
+        }
+    }
+}
+
+/// Filter fitting `input` into a `width`x`height` box, letterboxed or cropped to fill
+fn fit_filter(input: &str, width: u32, height: u32, fill: bool, output: &str) -> String {
+    if fill {
+        format!(
+            "{}scale={w}:{h}:force_original_aspect_ratio=increase,crop={w}:{h},setsar=1,format=yuv420p[{}]",
+            input,
+            output,
+            w = width,
+            h = height
+        )
+    } else {
+        format!(
+            "{}scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2:color=black,setsar=1,format=yuv420p[{}]",
+            input,
+            output,
+            w = width,
+            h = height
+        )
+    }
+}
+
+/// Filters placing the screen (input 0) and webcam (input 1) side by side or stacked
+///
+/// The screen is letterboxed into its half; the webcam is cropped to fill its half.
+fn stacked_filters(composition: &PipComposition) -> Vec<String> {
+    let (screen_width, screen_height) = composition.screen_size;
+    let (stack, half_width, half_height) = if composition.mode == PipMode::Vertical {
+        // 9:16 frame as wide as the screen's shorter side
+        let width = screen_width.min(screen_height).max(2) & !1;
+        let height = (width * 16 / 9) & !1;
+        ("vstack", width, height / 2)
+    } else {
+        (
+            "hstack",
+            (screen_width / 2).max(2) & !1,
+            screen_height.max(2) & !1,
+        )
+    };
+
+    let screen = fit_filter("[0:v]", half_width, half_height, false, "screen");
+    let camera = fit_filter("[1:v]", half_width, half_height, true, "camera");
+    let order = if composition.camera_first {
+        "[camera][screen]"
+    } else {
+        "[screen][camera]"
+    };
+    vec![
+        screen,
+        camera,
+        format!("{}{}=inputs=2:shortest=1[outv]", order, stack),
+    ]
+}
+
+/// FFmpeg filter graph combining the screen (input 0) and webcam (input 1)
+///
+/// In the overlay modes, without segments the overlay is drawn with `layout`
+/// throughout. Otherwise it is drawn with `layout` outside the segments, and each
+/// visible segment gets its own scaled copy enabled only for its time range.
+fn composite_filter(composition: &PipComposition, mix_webcam_audio: bool) -> String {
+    let mut filters = match composition.mode {
+        PipMode::SideBySide | PipMode::Vertical => stacked_filters(composition),
+        PipMode::Overlay | PipMode::CameraInset => overlay_filters(composition),
+    };
+
+    if mix_webcam_audio {
+        filters.push(
+            "[0:a][1:a]amix=inputs=2:duration=longest:dropout_transition=2[outa]".to_string(),
+        );
+    }
+    filters.join(";")
+}
+
+/// Filters drawing the overlay (the webcam, or the screen for `CameraInset`)
+fn overlay_filters(composition: &PipComposition) -> Vec<String> {
+    let mut filters = Vec::new();
+    let (background, inset) = if composition.mode == PipMode::CameraInset {
+        let (width, height) = composition.screen_size;
+        filters.push(fit_filter(
+            "[1:v]",
+            width.max(2) & !1,
+            height.max(2) & !1,
+            true,
+            "bg",
+        ));
+        ("[bg]", "[0:v]")
+    } else {
+        ("[0:v]", "[1:v]")
+    };
+
+    let segments = &composition.segments;
+    let style = &composition.style;
+    let mut overlays = vec![(composition.layout, None)];
+    if !segments.is_empty() {
+        let ranges: Vec<String> = segments
+            .iter()
+            .map(|segment| format!("between(t,{},{})", segment.start, segment.end))
+            .collect();
+        overlays[0].1 = Some(format!("not({})", ranges.join("+")));
+        overlays.extend(
+            segments
+                .iter()
+                .zip(ranges)
+                .filter_map(|(segment, range)| segment.layout.map(|layout| (layout, Some(range)))),
+        );
+    }
+
+    let count = overlays.len();
+    let source = |i: usize| {
+        if count == 1 {
+            inset.to_string()
+        } else {
+            format!("[w{}]", i)
+        }
+    };
+    let cam = |i: usize| {
+        if count == 1 {
+            "cam".to_string()
+        } else {
+            format!("cam{}", i)
+        }
+    };
+
+    if count > 1 {
+        filters.push(format!(
+            "{}split={}{}",
+            inset,
+            count,
+            (0..count).map(source).collect::<String>()
+        ));
+    }
+    for (i, (layout, enable)) in overlays.iter().enumerate() {
+        filters.extend(style.overlay_filters(&source(i), layout, &cam(i)));
+        let input = if i == 0 {
+            background.to_string()
+        } else {
+            format!("[v{}]", i - 1)
+        };
+        let output = if i == count - 1 {
+            "[outv]".to_string()
+        } else {
+            format!("[v{}]", i)
+        };
+        let enable = enable
+            .as_ref()
+            .map(|expr| format!(":enable='{}'", expr))
+            .unwrap_or_default();
+        let (offset_x, offset_y) = style.offset(layout);
+        filters.push(format!(
+            "{}[{}]overlay={}:{}:format=auto{}{}",
+            input,
+            cam(i),
+            layout.x as i64 - offset_x as i64,
+            layout.y as i64 - offset_y as i64,
+            enable,
+            output
+        ));
+    }
+    filters
+}
+
+/// Composite a screen recording and its webcam recording into one H.264 MP4
+///
+/// The output keeps the screen audio, mixed with the webcam audio when
+/// `mix_webcam_audio` is set.
+pub fn composite(
+    ffmpeg_path: &Path,
+    screen_path: &str,
+    webcam_path: &str,
+    composition: &PipComposition,
+    mix_webcam_audio: bool,
+    output_path: &Path,
+) -> Result<(), String> {
+    let layout = &composition.layout;
+    println!(
+        "[PiPComposite] screen={} webcam={} -> {}x{} at ({}, {}) output={}",
+        screen_path,
+        webcam_path,
+        layout.width,
+        layout.height,
+        layout.x,
+        layout.y,
+        output_path.display()
+    );
+
+    let output = composite_command(
+        ffmpeg_path,
+        screen_path,
+        webcam_path,
+        composition,
+        mix_webcam_audio,
+        output_path,
+    )
+    .watched_output(JobKind::Encode)
+    .map_err(|e| format!("Failed to execute FFmpeg for PiP compositing: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("FFmpeg PiP compositing failed: {}", stderr));
+    }
+
+    println!("[PiPComposite] Completed: {}", output_path.display());
+    Ok(())
+}
+
+/// FFmpeg command `composite` runs
+pub fn composite_command(
+    ffmpeg_path: &Path,
+    screen_path: &str,
+    webcam_path: &str,
+    composition: &PipComposition,
+    mix_webcam_audio: bool,
+    output_path: &Path,
+) -> Command {
+    let mut command = Command::new(ffmpeg_path);
+    command
+        .arg("-i")
+        .arg(screen_path)
+        .arg("-i")
+        .arg(webcam_path)
+        .arg("-filter_complex")
+        .arg(composite_filter(composition, mix_webcam_audio))
+        .arg("-map")
+        .arg("[outv]")
+        .arg("-map")
+        .arg(if mix_webcam_audio { "[outa]" } else { "0:a?" })
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-preset")
+        .arg("medium")
+        .arg("-crf")
+        .arg("20")
+        .arg("-c:a")
+        .arg("aac")
+        .arg("-b:a")
+        .arg("192k")
+        .arg("-movflags")
+        .arg("+faststart");
+    if mix_webcam_audio {
+        command.arg("-shortest");
+    }
+    command.arg("-y").arg(output_path);
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_bottom_right_16x9() {
+        let layout = PipLayout::calculate("bottomRight", "medium", 1920, 1080, Some((1280, 720)));
+        // 1920 * 0.18 = 345.6 -> 346; 346 / (16/9) = 194.6 -> 195 -> 194
+        assert_eq!(
+            layout,
+            PipLayout {
+                x: 1920 - 346 - EDGE_PADDING,
+                y: 1080 - 194 - EDGE_PADDING,
+                width: 346,
+                height: 194,
+            }
+        );
+    }
+
+    #[test]
+    fn test_layout_keeps_webcam_aspect() {
+        let layout = PipLayout::calculate("topLeft", "large", 2560, 1440, Some((640, 480)));
+        assert_eq!((layout.x, layout.y), (EDGE_PADDING, EDGE_PADDING));
+        assert_eq!(layout.width, 614);
+        assert_eq!(layout.height, 460);
+    }
+
+    #[test]
+    fn test_layout_defaults() {
+        let unknown = PipLayout::calculate("middle", "huge", 1920, 1080, None);
+        let expected = PipLayout::calculate("bottomRight", "medium", 1920, 1080, Some((16, 9)));
+        assert_eq!(unknown, expected);
+    }
+
+    #[test]
+    fn test_layout_tiny_screen() {
+        let layout = PipLayout::calculate("bottomRight", "small", 100, 60, None);
+        assert!(layout.width >= 32 && layout.height >= 32);
+        assert!(layout.width.is_multiple_of(2));
+        assert!(layout.height.is_multiple_of(2));
+    }
+
+    #[test]
+    fn test_place_at() {
+        let layout = PipLayout::calculate("topLeft", "medium", 1920, 1080, Some((1280, 720)));
+        let centered = layout.place_at(0.5, 0.5, 1920, 1080);
+        assert_eq!((centered.x, centered.y), (787, 443));
+        assert_eq!((centered.width, centered.height), (346, 194));
+
+        let clamped = layout.place_at(1.5, -1.0, 1920, 1080);
+        assert_eq!((clamped.x, clamped.y), (1920 - 346, 0));
+    }
+
+    #[test]
+    fn test_with_override() {
+        let config = PiPConfiguration {
+            position: "topLeft".to_string(),
+            size: "small".to_string(),
+            camera_id: None,
+            include_audio: false,
+            audio_device_id: None,
+            x: Some(0.25),
+            y: Some(0.75),
+            style: PipStyle::default(),
+            mode: PipMode::Overlay,
+        };
+
+        let resized = config.with_override(&PipOverride {
+            size: Some("large".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(resized.size, "large");
+        assert_eq!((resized.x, resized.y), (Some(0.25), Some(0.75)));
+
+        let cornered = config.with_override(&PipOverride {
+            position: Some("bottomRight".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(cornered.position, "bottomRight");
+        assert_eq!((cornered.x, cornered.y), (None, None));
+
+        let moved = config.with_override(&PipOverride {
+            x: Some(0.0),
+            y: Some(1.0),
+            ..Default::default()
+        });
+        assert_eq!((moved.x, moved.y), (Some(0.0), Some(1.0)));
+    }
+
+    #[test]
+    fn test_segments() {
+        let mut metadata = PiPMetadata {
+            id: "pip_1".to_string(),
+            start_time: 0,
+            duration: 300.0,
+            screen_file_path: "screen.mp4".to_string(),
+            webcam_file_path: "webcam.webm".to_string(),
+            pip_config: PiPConfiguration {
+                position: "bottomRight".to_string(),
+                size: "small".to_string(),
+                camera_id: None,
+                include_audio: false,
+                audio_device_id: None,
+                x: None,
+                y: None,
+                style: PipStyle::default(),
+                mode: PipMode::Overlay,
+            },
+            screen_dimensions: ScreenDimensions {
+                width: 1920,
+                height: 1080,
+            },
+            webcam_dimensions: ScreenDimensions {
+                width: 1280,
+                height: 720,
+            },
+            keyframes: vec![
+                PipKeyframe {
+                    start: 60.0,
+                    end: 150.0,
+                    hidden: true,
+                    ..Default::default()
+                },
+                PipKeyframe {
+                    start: 0.0,
+                    end: 10.0,
+                    overlay: PipOverride {
+                        size: Some("large".to_string()),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            ],
+        };
+
+        let segments = metadata.segments(None).unwrap();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].start, 0.0);
+        assert_eq!(
+            segments[0].layout,
+            Some(PipLayout::calculate(
+                "bottomRight",
+                "large",
+                1920,
+                1080,
+                Some((1280, 720))
+            ))
+        );
+        assert_eq!(segments[1].layout, None);
+
+        metadata.keyframes[1].end = 90.0;
+        assert!(metadata.segments(None).is_err());
+    }
+
+    #[test]
+    fn test_keyframed_filter() {
+        let layout = PipLayout {
+            x: 10,
+            y: 20,
+            width: 320,
+            height: 180,
+        };
+        let large = PipLayout {
+            x: 0,
+            y: 0,
+            width: 640,
+            height: 360,
+        };
+        let segments = [
+            PipSegment {
+                start: 0.0,
+                end: 5.0,
+                layout: Some(large),
+            },
+            PipSegment {
+                start: 60.0,
+                end: 150.5,
+                layout: None,
+            },
+        ];
+        assert_eq!(
+            composite_filter(
+                &PipComposition {
+                    layout,
+                    segments: segments.to_vec(),
+                    ..Default::default()
+                },
+                false
+            ),
+            "[1:v]split=2[w0][w1];\
+             [w0]scale=320:180[cam0];\
+             [0:v][cam0]overlay=10:20:format=auto:enable='not(between(t,0,5)+between(t,60,150.5))'[v0];\
+             [w1]scale=640:360[cam1];\
+             [v0][cam1]overlay=0:0:format=auto:enable='between(t,0,5)'[outv]"
+        );
+    }
+
+    #[test]
+    fn test_border_color() {
+        let style = |color: &str| PipStyle {
+            border_color: color.to_string(),
+            ..Default::default()
+        };
+        assert_eq!(style("#FF8000").border_rgb(), Ok((255, 128, 0)));
+        assert_eq!(style("0x00ff00").border_rgb(), Ok((0, 255, 0)));
+        assert!(style("white").validate().is_err());
+        assert!(PipStyle {
+            border_width: 100,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_styled_overlay() {
+        let layout = PipLayout {
+            x: 100,
+            y: 200,
+            width: 200,
+            height: 200,
+        };
+        let style = PipStyle {
+            shape: PipShape::Circle,
+            border_width: 4,
+            shadow: true,
+            ..Default::default()
+        };
+        // Shadow blur for a 208px tall frame is 10
+        assert_eq!(style.offset(&layout), (14, 9));
+
+        let filters = style.overlay_filters("[1:v]", &layout, "cam");
+        assert_eq!(filters.len(), 3);
+        assert!(filters[0]
+            .starts_with("[1:v]scale=200:200:force_original_aspect_ratio=increase,crop=200:200"));
+        assert!(filters[0].contains("pad=208:208:4:4"));
+        assert!(filters[0].contains("lte(hypot(abs(X-104)-0,abs(Y-104)-0),104)"));
+        assert!(filters[2].ends_with("overlay=10:5:format=auto[cam]"));
+
+        let composite = composite_filter(
+            &PipComposition {
+                layout,
+                style,
+                ..Default::default()
+            },
+            false,
+        );
+        assert!(composite.ends_with("[0:v][cam]overlay=86:191:format=auto[outv]"));
+    }
+
+    #[test]
+    fn test_stacked_filters() {
+        let mut composition = PipComposition {
+            mode: PipMode::Vertical,
+            screen_size: (1920, 1080),
+            ..Default::default()
+        };
+        let filters = stacked_filters(&composition);
+        assert!(filters[0].starts_with("[0:v]scale=1080:960:force_original_aspect_ratio=decrease"));
+        assert!(filters[1].starts_with("[1:v]scale=1080:960:force_original_aspect_ratio=increase"));
+        assert_eq!(
+            filters[2],
+            "[screen][camera]vstack=inputs=2:shortest=1[outv]"
+        );
+
+        composition.mode = PipMode::SideBySide;
+        composition.camera_first = true;
+        let filters = stacked_filters(&composition);
+        assert!(filters[0].starts_with("[0:v]scale=960:1080:"));
+        assert_eq!(
+            filters[2],
+            "[camera][screen]hstack=inputs=2:shortest=1[outv]"
+        );
+    }
+
+    #[test]
+    fn test_camera_inset_filter() {
+        let layout = PipLayout {
+            x: 20,
+            y: 20,
+            width: 346,
+            height: 194,
+        };
+        let filter = composite_filter(
+            &PipComposition {
+                mode: PipMode::CameraInset,
+                screen_size: (1920, 1080),
+                layout,
+                ..Default::default()
+            },
+            false,
+        );
+        assert_eq!(
+            filter,
+            "[1:v]scale=1920:1080:force_original_aspect_ratio=increase,crop=1920:1080,setsar=1,format=yuv420p[bg];\
+             [0:v]scale=346:194[cam];\
+             [bg][cam]overlay=20:20:format=auto[outv]"
+        );
+    }
+
+    #[test]
+    fn test_composite_filter() {
+        let layout = PipLayout {
+            x: 10,
+            y: 20,
+            width: 320,
+            height: 180,
+        };
+        assert_eq!(
+            composite_filter(&PipComposition::fixed(layout), false),
+            "[1:v]scale=320:180[cam];[0:v][cam]overlay=10:20:format=auto[outv]"
+        );
+        assert!(composite_filter(&PipComposition::fixed(layout), true)
+            .ends_with("amix=inputs=2:duration=longest:dropout_transition=2[outa]"));
+    }
+}
@@ -3,6 +3,7 @@
 // This module handles streaming JPEG-compressed frames from the capture
 // pipeline to the frontend via Tauri's event system
 
+use crate::error::ClipForgeError;
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
@@ -220,13 +221,15 @@ pub fn emit_preview_metrics(app_handle: &AppHandle, metrics: PreviewMetrics) ->
 pub async fn start_preview(
     app_handle: AppHandle,
     state: tauri::State<'_, SharedPreviewState>,
-) -> Result<(), String> {
+) -> Result<(), ClipForgeError> {
     let mut preview_state = state
         .lock()
         .map_err(|e| format!("Failed to lock preview state: {}", e))?;
 
     if preview_state.is_active {
-        return Err("Preview is already active".to_string());
+        return Err(ClipForgeError::InvalidInput(
+            "Preview is already active".to_string(),
+        ));
     }
 
     preview_state.is_active = true;
@@ -251,13 +254,15 @@ pub async fn start_preview(
 pub async fn stop_preview(
     app_handle: AppHandle,
     state: tauri::State<'_, SharedPreviewState>,
-) -> Result<(), String> {
+) -> Result<(), ClipForgeError> {
     let mut preview_state = state
         .lock()
         .map_err(|e| format!("Failed to lock preview state: {}", e))?;
 
     if !preview_state.is_active {
-        return Err("Preview is not active".to_string());
+        return Err(ClipForgeError::InvalidInput(
+            "Preview is not active".to_string(),
+        ));
     }
 
     preview_state.is_active = false;
@@ -275,7 +280,7 @@ pub async fn stop_preview(
 pub async fn update_preview_settings(
     state: tauri::State<'_, SharedPreviewState>,
     settings: PreviewSettings,
-) -> Result<(), String> {
+) -> Result<(), ClipForgeError> {
     let mut preview_state = state
         .lock()
         .map_err(|e| format!("Failed to lock preview state: {}", e))?;
@@ -291,7 +296,7 @@ pub async fn update_preview_settings(
 #[tauri::command]
 pub async fn get_preview_metrics(
     state: tauri::State<'_, SharedPreviewState>,
-) -> Result<PreviewMetrics, String> {
+) -> Result<PreviewMetrics, ClipForgeError> {
     let preview_state = state
         .lock()
         .map_err(|e| format!("Failed to lock preview state: {}", e))?;
@@ -303,7 +308,7 @@ pub async fn get_preview_metrics(
 #[tauri::command]
 pub async fn get_preview_settings(
     state: tauri::State<'_, SharedPreviewState>,
-) -> Result<PreviewSettings, String> {
+) -> Result<PreviewSettings, ClipForgeError> {
     let preview_state = state
         .lock()
         .map_err(|e| format!("Failed to lock preview state: {}", e))?;
@@ -315,7 +320,10 @@ pub async fn get_preview_settings(
 // Preview Capture Integration
 // ============================================================================
 
-use crate::capture::ffi::ScreenCaptureBridge;
+use super::recording::ColorMode;
+use crate::capture::ffi::{
+    CaptureState, DropPolicy, FramePoolStats, FrameQueueStats, ScreenCaptureBridge, StreamConfig,
+};
 use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::task::JoinHandle;
 
@@ -327,6 +335,10 @@ pub struct PreviewCaptureSession {
     pub polling_task: Option<JoinHandle<()>>,
     /// Flag to signal task shutdown
     pub should_stop: Arc<AtomicBool>,
+    /// Capacity of the JPEG frame queue, applied to each new bridge
+    pub queue_capacity: usize,
+    /// Drop policy of the JPEG frame queue, applied to each new bridge
+    pub queue_policy: DropPolicy,
 }
 
 impl PreviewCaptureSession {
@@ -335,6 +347,8 @@ impl PreviewCaptureSession {
             bridge: None,
             polling_task: None,
             should_stop: Arc::new(AtomicBool::new(false)),
+            queue_capacity: DEFAULT_PREVIEW_QUEUE_CAPACITY,
+            queue_policy: DropPolicy::DropOldest,
         }
     }
 
@@ -365,37 +379,69 @@ impl Drop for PreviewCaptureSession {
 
 pub type SharedPreviewCaptureSession = Arc<Mutex<PreviewCaptureSession>>;
 
+/// Default JPEG frame queue capacity; the preview only wants the latest frames
+const DEFAULT_PREVIEW_QUEUE_CAPACITY: usize = 5;
+
+/// Largest frame queue capacity the Swift bridge accepts
+const MAX_CAPTURE_QUEUE_CAPACITY: usize = 120;
+
+/// Frame queue statistics for the active capture session
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureStats {
+    pub active: bool,
+    pub state: CaptureState,
+    /// JPEG frames waiting for the preview
+    pub jpeg_queue: Option<FrameQueueStats>,
+    /// Raw frames waiting for an encoding consumer
+    pub raw_queue: Option<FrameQueueStats>,
+    /// Buffer allocations behind the raw frame queue
+    pub frame_pool: Option<FramePoolStats>,
+}
+
 /// Starts preview for a selected source
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn start_preview_for_source(
     source_id: String,
     width: u32,
     height: u32,
     frame_rate: u32,
+    color_mode: Option<ColorMode>,
     app_handle: AppHandle,
     preview_state: tauri::State<'_, SharedPreviewState>,
     capture_session: tauri::State<'_, SharedPreviewCaptureSession>,
-) -> Result<(), String> {
+) -> Result<(), ClipForgeError> {
     println!(
         "[PreviewCapture] Starting preview for source: {} ({}x{} @ {}fps)",
         source_id, width, height, frame_rate
     );
 
     // Stop any existing preview session
-    {
+    let (queue_capacity, queue_policy) = {
         let mut session = capture_session
             .lock()
             .map_err(|e| format!("Failed to lock capture session: {}", e))?;
         session.stop();
-    }
+        (session.queue_capacity, session.queue_policy)
+    };
 
     // Create new ScreenCaptureBridge
     let bridge = ScreenCaptureBridge::new().ok_or_else(|| {
         "Failed to create ScreenCaptureBridge (not available on this system)".to_string()
     })?;
 
+    // Match the recording's color space so the preview shows what will be recorded
+    bridge.configure_color_mode(color_mode.unwrap_or_default().bridge_value());
+
     // Configure stream settings (15fps for preview, full resolution)
-    bridge.configure_stream(width, height, frame_rate, false);
+    bridge.configure_stream(&StreamConfig {
+        width,
+        height,
+        frame_rate,
+        capture_audio: false,
+    })?;
+    bridge.configure_jpeg_frames(queue_capacity, queue_policy);
 
     // Configure source filter (display or window)
     if source_id.starts_with("display_") {
@@ -415,7 +461,10 @@ pub async fn start_preview_for_source(
 
         bridge.configure_window(window_id)?;
     } else {
-        return Err(format!("Invalid source ID format: {}", source_id));
+        return Err(ClipForgeError::InvalidInput(format!(
+            "Invalid source ID format: {}",
+            source_id
+        )));
     }
 
     // Start capture
@@ -485,6 +534,9 @@ pub async fn start_preview_for_source(
                     );
                 }
 
+                // Mirror the frame to the virtual camera, if one is running
+                super::virtual_camera::publish_frame(&app_handle_clone, &frame.jpeg_data);
+
                 // Convert JPEG data to base64
                 let base64_data = base64::Engine::encode(
                     &base64::engine::general_purpose::STANDARD,
@@ -577,7 +629,8 @@ pub async fn start_preview_for_source(
             .lock()
             .map_err(|e| format!("Failed to lock capture session: {}", e))?;
         session.polling_task = Some(polling_task);
-    }    Ok(())
+    }
+    Ok(())
 }
 
 /// Stops the preview capture session
@@ -586,7 +639,7 @@ pub async fn stop_preview_for_source(
     app_handle: AppHandle,
     preview_state: tauri::State<'_, SharedPreviewState>,
     capture_session: tauri::State<'_, SharedPreviewCaptureSession>,
-) -> Result<(), String> {
+) -> Result<(), ClipForgeError> {
     // Stop the capture session
     {
         let mut session = capture_session
@@ -602,7 +655,9 @@ pub async fn stop_preview_for_source(
             .map_err(|e| format!("Failed to lock preview state: {}", e))?;
 
         if !state.is_active {
-            return Err("Preview is not active".to_string());
+            return Err(ClipForgeError::InvalidInput(
+                "Preview is not active".to_string(),
+            ));
         }
 
         state.is_active = false;
@@ -616,6 +671,68 @@ pub async fn stop_preview_for_source(
     Ok(())
 }
 
+/// Gets frame queue occupancy, drop counts and buffer allocations for the capture session
+#[tauri::command]
+pub async fn get_capture_stats(
+    capture_session: tauri::State<'_, SharedPreviewCaptureSession>,
+) -> Result<CaptureStats, ClipForgeError> {
+    let session = capture_session
+        .lock()
+        .map_err(|e| format!("Failed to lock capture session: {}", e))?;
+
+    let Some(bridge) = &session.bridge else {
+        return Ok(CaptureStats {
+            active: false,
+            state: CaptureState::Unconfigured,
+            jpeg_queue: None,
+            raw_queue: None,
+            frame_pool: None,
+        });
+    };
+
+    Ok(CaptureStats {
+        active: true,
+        state: bridge.capture_state(),
+        jpeg_queue: bridge.jpeg_frame_queue_stats(session.queue_policy),
+        raw_queue: bridge.frame_queue_stats(),
+        frame_pool: Some(bridge.frame_pool_stats()),
+    })
+}
+
+/// Sets the capture frame queue capacity and drop policy
+///
+/// Applies to the running session immediately and to sessions started later.
+/// Drop-oldest suits the preview; drop-newest suits consumers that need frames in order.
+#[tauri::command]
+pub async fn configure_capture_queue(
+    capacity: usize,
+    policy: DropPolicy,
+    capture_session: tauri::State<'_, SharedPreviewCaptureSession>,
+) -> Result<(), ClipForgeError> {
+    if capacity == 0 || capacity > MAX_CAPTURE_QUEUE_CAPACITY {
+        return Err(ClipForgeError::InvalidInput(format!(
+            "Queue capacity must be between 1 and {}",
+            MAX_CAPTURE_QUEUE_CAPACITY
+        )));
+    }
+
+    let mut session = capture_session
+        .lock()
+        .map_err(|e| format!("Failed to lock capture session: {}", e))?;
+    session.queue_capacity = capacity;
+    session.queue_policy = policy;
+
+    if let Some(bridge) = &session.bridge {
+        bridge.configure_jpeg_frames(capacity, policy);
+    }
+
+    println!(
+        "[PreviewCapture] Frame queue set to {} frames ({:?})",
+        capacity, policy
+    );
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
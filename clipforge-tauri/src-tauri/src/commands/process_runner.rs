@@ -0,0 +1,452 @@
+// Running FFmpeg with a watchdog
+//
+// `Command::output` waits forever, so an FFmpeg that hangs (a stalled network
+// volume, a decoder stuck on a corrupt file) used to hang the command that
+// started it. `watched_output` is a drop-in replacement that captures stdout and
+// stderr the same way, but kills the process once it runs past the time limit
+// for its kind of job, or, for encodes, once it stops writing progress. The
+// limits are part of the app settings and applied with `set_timeouts`.
+//
+// Async commands use `watched_output_async` instead, which runs the process on
+// tokio so it does not block a worker thread, and kills it if the calling future
+// is dropped.
+
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// How often a running process is checked
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Lines of stderr quoted in a timeout error
+const STDERR_TAIL_LINES: usize = 5;
+
+static TIMEOUTS: RwLock<ProcessTimeouts> = RwLock::new(ProcessTimeouts::DEFAULT);
+
+/// What a process is doing, which decides how long it may take
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobKind {
+    /// ffprobe calls and device listings
+    Probe,
+    /// Thumbnails, single frames and waveforms
+    Thumbnail,
+    /// Encodes and full decodes, whose length grows with the media
+    Encode,
+}
+
+/// Time limits for FFmpeg processes, in seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProcessTimeouts {
+    pub probe_secs: u64,
+    pub thumbnail_secs: u64,
+    /// Overall limit for encodes; `None` lets them run as long as they make progress
+    pub encode_secs: Option<u64>,
+    /// How long an encode may go without output before it is considered stuck
+    pub stall_secs: u64,
+}
+
+impl ProcessTimeouts {
+    const DEFAULT: Self = ProcessTimeouts {
+        probe_secs: 30,
+        thumbnail_secs: 120,
+        encode_secs: None,
+        stall_secs: 120,
+    };
+
+    pub fn validate(&self) -> Result<(), String> {
+        let limits = [self.probe_secs, self.thumbnail_secs, self.stall_secs];
+        if limits.contains(&0) || self.encode_secs == Some(0) {
+            return Err("Process timeouts must be at least one second".to_string());
+        }
+        Ok(())
+    }
+
+    /// Overall and stall limits for a kind of job
+    fn limits(&self, kind: JobKind) -> (Option<Duration>, Option<Duration>) {
+        match kind {
+            JobKind::Probe => (Some(Duration::from_secs(self.probe_secs)), None),
+            JobKind::Thumbnail => (Some(Duration::from_secs(self.thumbnail_secs)), None),
+            JobKind::Encode => (
+                self.encode_secs.map(Duration::from_secs),
+                Some(Duration::from_secs(self.stall_secs)),
+            ),
+        }
+    }
+}
+
+impl Default for ProcessTimeouts {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Use `timeouts` for every process started from now on
+pub fn set_timeouts(timeouts: ProcessTimeouts) {
+    if let Ok(mut current) = TIMEOUTS.write() {
+        *current = timeouts;
+    }
+}
+
+fn current_timeouts() -> ProcessTimeouts {
+    TIMEOUTS
+        .read()
+        .map(|timeouts| *timeouts)
+        .unwrap_or_default()
+}
+
+/// `Command::output` with a time limit
+pub trait WatchedOutput {
+    /// Run to completion and collect output, killing the process if it takes too long
+    ///
+    /// A killed process returns an error of kind `TimedOut` whose message ends
+    /// with the last lines it wrote to stderr.
+    fn watched_output(&mut self, kind: JobKind) -> io::Result<Output>;
+}
+
+impl WatchedOutput for Command {
+    fn watched_output(&mut self, kind: JobKind) -> io::Result<Output> {
+        let (limit, stall) = current_timeouts().limits(kind);
+        run_with_limits(self, limit, stall)
+    }
+}
+
+fn run_with_limits(
+    command: &mut Command,
+    limit: Option<Duration>,
+    stall: Option<Duration>,
+) -> io::Result<Output> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let started = Instant::now();
+    let last_output = Arc::new(Mutex::new(started));
+    let stdout = read_pipe(child.stdout.take(), last_output.clone());
+    let stderr = read_pipe(child.stderr.take(), last_output.clone());
+
+    let outcome = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Ok(status),
+            Ok(None) => {}
+            Err(e) => break Err(e.to_string()),
+        }
+
+        if let Some(reason) = exceeded_limit(started, &last_output, limit, stall) {
+            break Err(reason);
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    match outcome {
+        Ok(status) => Ok(Output {
+            status,
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        }),
+        Err(reason) => {
+            let _ = child.kill();
+            let _ = child.wait();
+            let _ = stdout.join();
+            let stderr = stderr.join().unwrap_or_default();
+            Err(killed_error(command.get_program(), &reason, &stderr))
+        }
+    }
+}
+
+/// Async `watched_output` for commands run from async code
+///
+/// Nothing blocks while the process runs, and dropping the returned future
+/// kills the process, so cancelling the calling task stops FFmpeg too.
+pub async fn watched_output_async(
+    command: &mut tokio::process::Command,
+    kind: JobKind,
+) -> io::Result<Output> {
+    let (limit, stall) = current_timeouts().limits(kind);
+    run_with_limits_async(command, limit, stall, |_| {}).await
+}
+
+/// `watched_output_async` that also hands each stdout line to `on_line` as it arrives
+///
+/// For encodes that report `-progress pipe:1`; the lines are still collected
+/// into the returned output.
+pub async fn watched_output_async_with_lines(
+    command: &mut tokio::process::Command,
+    kind: JobKind,
+    on_line: impl FnMut(&str),
+) -> io::Result<Output> {
+    let (limit, stall) = current_timeouts().limits(kind);
+    run_with_limits_async(command, limit, stall, on_line).await
+}
+
+async fn run_with_limits_async(
+    command: &mut tokio::process::Command,
+    limit: Option<Duration>,
+    stall: Option<Duration>,
+    on_stdout_line: impl FnMut(&str),
+) -> io::Result<Output> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    let started = Instant::now();
+    let last_output = Mutex::new(started);
+    let stdout_pipe = child.stdout.take();
+    let stderr_pipe = child.stderr.take();
+
+    let watch = async {
+        loop {
+            tokio::select! {
+                status = child.wait() => return status.map_err(|e| e.to_string()),
+                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            }
+            if let Some(reason) = exceeded_limit(started, &last_output, limit, stall) {
+                // Closes the pipes, which lets the readers finish
+                let _ = child.kill().await;
+                return Err(reason);
+            }
+        }
+    };
+    let (outcome, stdout, stderr) = tokio::join!(
+        watch,
+        read_pipe_async(stdout_pipe, &last_output, on_stdout_line),
+        read_pipe_async(stderr_pipe, &last_output, |_| {})
+    );
+
+    match outcome {
+        Ok(status) => Ok(Output {
+            status,
+            stdout,
+            stderr,
+        }),
+        Err(reason) => Err(killed_error(
+            command.as_std().get_program(),
+            &reason,
+            &stderr,
+        )),
+    }
+}
+
+/// Why a process should be killed, if it has run too long or gone quiet
+fn exceeded_limit(
+    started: Instant,
+    last_output: &Mutex<Instant>,
+    limit: Option<Duration>,
+    stall: Option<Duration>,
+) -> Option<String> {
+    let now = Instant::now();
+    if let Some(limit) = limit.filter(|limit| now.duration_since(started) >= *limit) {
+        return Some(format!("timed out after {}s", limit.as_secs()));
+    }
+    let idle = last_output
+        .lock()
+        .map(|last| now.saturating_duration_since(*last))
+        .unwrap_or_default();
+    stall
+        .filter(|stall| idle >= *stall)
+        .map(|stall| format!("stopped responding (no output for {}s)", stall.as_secs()))
+}
+
+/// The error returned for a process the watchdog killed
+fn killed_error(program: &OsStr, reason: &str, stderr: &[u8]) -> io::Error {
+    let program = Path::new(program)
+        .file_name()
+        .unwrap_or(program)
+        .to_string_lossy()
+        .to_string();
+    println!("[ProcessRunner] Killed {}: {}", program, reason);
+    io::Error::new(
+        io::ErrorKind::TimedOut,
+        format!("{} {}{}", program, reason, stderr_tail(stderr)),
+    )
+}
+
+/// Collect a pipe on its own thread, noting when it last produced output
+fn read_pipe<R: Read + Send + 'static>(
+    pipe: Option<R>,
+    last_output: Arc<Mutex<Instant>>,
+) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut collected = Vec::new();
+        let Some(mut pipe) = pipe else {
+            return collected;
+        };
+        let mut buffer = [0u8; 8192];
+        loop {
+            match pipe.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => {
+                    collected.extend_from_slice(&buffer[..read]);
+                    if let Ok(mut last) = last_output.lock() {
+                        *last = Instant::now();
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            }
+        }
+        collected
+    })
+}
+
+/// Collect an async pipe, noting when it last produced output and passing on each line
+async fn read_pipe_async<R: AsyncRead + Unpin>(
+    pipe: Option<R>,
+    last_output: &Mutex<Instant>,
+    mut on_line: impl FnMut(&str),
+) -> Vec<u8> {
+    let mut collected = Vec::new();
+    let Some(mut pipe) = pipe else {
+        return collected;
+    };
+    let mut buffer = [0u8; 8192];
+    let mut line_start = 0;
+    loop {
+        match pipe.read(&mut buffer).await {
+            Ok(0) => break,
+            Ok(read) => {
+                collected.extend_from_slice(&buffer[..read]);
+                if let Ok(mut last) = last_output.lock() {
+                    *last = Instant::now();
+                }
+                while let Some(end) = collected[line_start..].iter().position(|&b| b == b'\n') {
+                    on_line(
+                        String::from_utf8_lossy(&collected[line_start..line_start + end])
+                            .trim_end(),
+                    );
+                    line_start += end + 1;
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(_) => break,
+        }
+    }
+    collected
+}
+
+/// The last few stderr lines, as a suffix for an error message
+fn stderr_tail(stderr: &[u8]) -> String {
+    let stderr = String::from_utf8_lossy(stderr);
+    // FFmpeg ends progress lines with a carriage return
+    let lines: Vec<&str> = stderr
+        .split(['\n', '\r'])
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() {
+        return String::new();
+    }
+    let tail = &lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..];
+    format!(": {}", tail.join("\n"))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn shell(script: &str) -> Command {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(script);
+        command
+    }
+
+    #[test]
+    fn test_collects_output_like_command_output() {
+        let output = run_with_limits(
+            &mut shell("echo out; echo err >&2; exit 3"),
+            Some(Duration::from_secs(10)),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(output.status.code(), Some(3));
+        assert_eq!(output.stdout, b"out\n");
+        assert_eq!(output.stderr, b"err\n");
+    }
+
+    #[test]
+    fn test_kills_process_past_limit() {
+        let started = Instant::now();
+        let error = run_with_limits(
+            &mut shell("echo frame=1 >&2; exec sleep 10"),
+            Some(Duration::from_millis(200)),
+            None,
+        )
+        .unwrap_err();
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+        assert!(error.to_string().starts_with("sh timed out"));
+        assert!(error.to_string().ends_with(": frame=1"));
+    }
+
+    #[test]
+    fn test_kills_stalled_process() {
+        let error = run_with_limits(
+            &mut shell("exec sleep 10"),
+            None,
+            Some(Duration::from_millis(200)),
+        )
+        .unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+        assert!(error.to_string().contains("stopped responding"));
+    }
+
+    #[tokio::test]
+    async fn test_async_kills_process_past_limit() {
+        let mut command = tokio::process::Command::new("sh");
+        command.arg("-c").arg("echo frame=1 >&2; exec sleep 10");
+
+        let started = Instant::now();
+        let error =
+            run_with_limits_async(&mut command, Some(Duration::from_millis(200)), None, |_| {})
+                .await
+                .unwrap_err();
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+        assert!(error.to_string().ends_with(": frame=1"));
+    }
+
+    #[tokio::test]
+    async fn test_async_passes_on_stdout_lines() {
+        let mut command = tokio::process::Command::new("sh");
+        command
+            .arg("-c")
+            .arg("echo out_time_us=1000000; echo progress=end; echo err >&2");
+
+        let mut lines = Vec::new();
+        let output =
+            run_with_limits_async(&mut command, Some(Duration::from_secs(10)), None, |line| {
+                lines.push(line.to_string())
+            })
+            .await
+            .unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(lines, ["out_time_us=1000000", "progress=end"]);
+        assert_eq!(output.stderr, b"err\n");
+    }
+
+    #[test]
+    fn test_zero_timeouts_rejected() {
+        assert!(ProcessTimeouts::default().validate().is_ok());
+        let timeouts = ProcessTimeouts {
+            encode_secs: Some(0),
+            ..Default::default()
+        };
+        assert!(timeouts.validate().is_err());
+    }
+}
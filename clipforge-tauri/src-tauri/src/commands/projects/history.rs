@@ -0,0 +1,321 @@
+// Project undo history
+//
+// Timeline edits are recorded as operations on the frontend's project state.
+// Each operation stores a JSON merge patch (RFC 7386) that applies it and one
+// that reverts it, so undo and redo only move patches between two stacks
+// instead of keeping a copy of the whole project per step. Note that merge
+// patches use `null` to remove a key, so edits should remove optional fields
+// rather than setting them to `null`.
+
+use super::super::settings::merge_patch;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, State};
+
+/// Operations kept on the undo stack before the oldest is dropped
+pub const DEFAULT_UNDO_DEPTH: usize = 100;
+
+/// Largest depth `set_undo_depth` accepts
+pub const MAX_UNDO_DEPTH: usize = 1000;
+
+/// One recorded edit
+#[derive(Debug, Clone)]
+struct Operation {
+    /// Shown as "Undo <label>" / "Redo <label>"
+    label: String,
+    forward: Value,
+    backward: Value,
+}
+
+/// Current project state with its undo and redo stacks
+#[derive(Debug)]
+pub struct ProjectHistory {
+    project: Value,
+    undo: VecDeque<Operation>,
+    redo: Vec<Operation>,
+    depth: usize,
+}
+
+/// What the frontend needs after any history change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryStatus {
+    pub project: Value,
+    pub can_undo: bool,
+    pub can_redo: bool,
+    pub undo_label: Option<String>,
+    pub redo_label: Option<String>,
+    pub undo_depth: usize,
+}
+
+pub type ProjectHistoryState = Arc<Mutex<ProjectHistory>>;
+
+impl Default for ProjectHistory {
+    fn default() -> Self {
+        Self {
+            project: Value::Object(Map::new()),
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            depth: DEFAULT_UNDO_DEPTH,
+        }
+    }
+}
+
+impl ProjectHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start over from `project`, forgetting all history
+    pub fn reset(&mut self, project: Value) {
+        self.project = project;
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Apply `patch` to the project and record it; clears the redo stack
+    ///
+    /// Returns false when the patch changes nothing, in which case nothing is recorded.
+    pub fn apply(&mut self, label: String, patch: &Value) -> bool {
+        let before = self.project.clone();
+        merge_patch(&mut self.project, patch);
+        if self.project == before {
+            return false;
+        }
+
+        self.undo.push_back(Operation {
+            label,
+            forward: diff(&before, &self.project),
+            backward: diff(&self.project, &before),
+        });
+        while self.undo.len() > self.depth {
+            self.undo.pop_front();
+        }
+        self.redo.clear();
+        true
+    }
+
+    /// Revert the last operation; returns false when there is nothing to undo
+    pub fn undo(&mut self) -> bool {
+        let Some(operation) = self.undo.pop_back() else {
+            return false;
+        };
+        merge_patch(&mut self.project, &operation.backward);
+        self.redo.push(operation);
+        true
+    }
+
+    /// Re-apply the last undone operation; returns false when there is nothing to redo
+    pub fn redo(&mut self) -> bool {
+        let Some(operation) = self.redo.pop() else {
+            return false;
+        };
+        merge_patch(&mut self.project, &operation.forward);
+        self.undo.push_back(operation);
+        true
+    }
+
+    /// Change how many operations are kept, dropping the oldest beyond it
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = depth.clamp(1, MAX_UNDO_DEPTH);
+        while self.undo.len() > self.depth {
+            self.undo.pop_front();
+        }
+    }
+
+    pub fn status(&self) -> HistoryStatus {
+        HistoryStatus {
+            project: self.project.clone(),
+            can_undo: !self.undo.is_empty(),
+            can_redo: !self.redo.is_empty(),
+            undo_label: self.undo.back().map(|operation| operation.label.clone()),
+            redo_label: self.redo.last().map(|operation| operation.label.clone()),
+            undo_depth: self.depth,
+        }
+    }
+}
+
+/// Merge patch that turns `from` into `to`
+fn diff(from: &Value, to: &Value) -> Value {
+    match (from, to) {
+        (Value::Object(from), Value::Object(to)) => {
+            let mut patch = Map::new();
+            for key in from.keys().filter(|key| !to.contains_key(*key)) {
+                patch.insert(key.clone(), Value::Null);
+            }
+            for (key, value) in to {
+                match from.get(key) {
+                    Some(old) if old == value => {}
+                    Some(old) => {
+                        patch.insert(key.clone(), diff(old, value));
+                    }
+                    None => {
+                        patch.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            Value::Object(patch)
+        }
+        _ => to.clone(),
+    }
+}
+
+/// Undo or redo one operation and broadcast the new status
+///
+/// Returns false when there was nothing to do.
+fn step(app: &AppHandle, state: &ProjectHistoryState, redo: bool) -> Result<bool, String> {
+    let status = {
+        let mut history = state.lock().map_err(|e| e.to_string())?;
+        let changed = if redo { history.redo() } else { history.undo() };
+        if !changed {
+            return Ok(false);
+        }
+        history.status()
+    };
+
+    let _ = app.emit("project:history-changed", &status);
+    Ok(true)
+}
+
+/// Replace the project state and clear history, e.g. after opening or creating a project
+#[tauri::command]
+pub async fn reset_project_history(
+    project: Value,
+    state: State<'_, ProjectHistoryState>,
+) -> Result<HistoryStatus, String> {
+    let mut history = state.lock().map_err(|e| e.to_string())?;
+    history.reset(project);
+    Ok(history.status())
+}
+
+/// Apply a merge patch to the project state as one undoable operation
+#[tauri::command]
+pub async fn apply_project_operation(
+    label: String,
+    patch: Value,
+    app: AppHandle,
+    state: State<'_, ProjectHistoryState>,
+) -> Result<HistoryStatus, String> {
+    let status = {
+        let mut history = state.lock().map_err(|e| e.to_string())?;
+        history.apply(label, &patch);
+        history.status()
+    };
+
+    let _ = app.emit("project:history-changed", &status);
+    Ok(status)
+}
+
+/// Revert the last operation
+#[tauri::command]
+pub async fn undo(
+    app: AppHandle,
+    state: State<'_, ProjectHistoryState>,
+) -> Result<HistoryStatus, String> {
+    step(&app, &state, false)?;
+    Ok(state.lock().map_err(|e| e.to_string())?.status())
+}
+
+/// Re-apply the last undone operation
+#[tauri::command]
+pub async fn redo(
+    app: AppHandle,
+    state: State<'_, ProjectHistoryState>,
+) -> Result<HistoryStatus, String> {
+    step(&app, &state, true)?;
+    Ok(state.lock().map_err(|e| e.to_string())?.status())
+}
+
+/// Get the project state and undo/redo availability
+#[tauri::command]
+pub async fn get_history_status(
+    state: State<'_, ProjectHistoryState>,
+) -> Result<HistoryStatus, String> {
+    Ok(state.lock().map_err(|e| e.to_string())?.status())
+}
+
+/// Set how many operations can be undone (1-1000)
+#[tauri::command]
+pub async fn set_undo_depth(
+    depth: usize,
+    state: State<'_, ProjectHistoryState>,
+) -> Result<HistoryStatus, String> {
+    let mut history = state.lock().map_err(|e| e.to_string())?;
+    history.set_depth(depth);
+    Ok(history.status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_undo_redo_round_trip() {
+        let original = json!({ "name": "Demo", "clips": [{ "id": 1 }], "zoom": 10 });
+        let mut history = ProjectHistory::new();
+        history.reset(original.clone());
+
+        assert!(history.apply(
+            "Add Clip".to_string(),
+            &json!({ "clips": [{ "id": 1 }, { "id": 2 }], "zoom": null })
+        ));
+        let edited = history.status().project;
+        assert_eq!(
+            edited,
+            json!({ "name": "Demo", "clips": [{ "id": 1 }, { "id": 2 }] })
+        );
+        assert_eq!(history.status().undo_label.as_deref(), Some("Add Clip"));
+
+        assert!(history.undo());
+        assert_eq!(history.status().project, original);
+        assert!(history.status().can_redo);
+
+        assert!(history.redo());
+        assert_eq!(history.status().project, edited);
+        assert!(!history.redo());
+    }
+
+    #[test]
+    fn test_new_operation_clears_redo() {
+        let mut history = ProjectHistory::new();
+        history.reset(json!({ "a": 1 }));
+        history.apply("One".to_string(), &json!({ "a": 2 }));
+        history.undo();
+        history.apply("Two".to_string(), &json!({ "a": 3 }));
+
+        assert!(!history.status().can_redo);
+        assert!(!history.apply("Nothing".to_string(), &json!({ "a": 3 })));
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let mut history = ProjectHistory::new();
+        history.reset(json!({ "n": 0 }));
+        history.set_depth(3);
+        for n in 1..=5 {
+            history.apply(format!("Step {}", n), &json!({ "n": n }));
+        }
+
+        let mut undone = 0;
+        while history.undo() {
+            undone += 1;
+        }
+        assert_eq!(undone, 3);
+        assert_eq!(history.status().project, json!({ "n": 2 }));
+    }
+
+    #[test]
+    fn test_diff_nested() {
+        let from = json!({ "a": { "b": 1, "c": 2 }, "d": 3 });
+        let to = json!({ "a": { "b": 1, "c": 4 }, "e": 5 });
+        let patch = diff(&from, &to);
+        assert_eq!(patch, json!({ "a": { "c": 4 }, "d": null, "e": 5 }));
+
+        let mut applied = from.clone();
+        merge_patch(&mut applied, &patch);
+        assert_eq!(applied, to);
+    }
+}
@@ -0,0 +1,547 @@
+// Project files
+//
+// Saves the frontend's project state inside a versioned envelope. Media paths
+// are stored both absolute and relative to the project file so a project
+// folder can be moved or shared and still find its media. Media size and a
+// content fingerprint are recorded so moved files can be relinked
+
+use super::recents::{add_recent, RecentKind};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use tauri::AppHandle;
+
+pub mod history;
+
+/// Current project file schema version
+pub const PROJECT_VERSION: u32 = 2;
+
+/// Bytes hashed from each end of a file for its fingerprint
+const FINGERPRINT_CHUNK: u64 = 64 * 1024;
+
+/// Maximum directory depth searched when relinking
+const RELINK_MAX_DEPTH: usize = 6;
+
+/// On-disk project file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectFile {
+    pub version: u32,
+    /// RFC 3339 timestamp of the last save
+    pub saved_at: String,
+    /// Absolute media path -> where to find it again
+    #[serde(default)]
+    pub media: BTreeMap<String, MediaReference>,
+    /// Project state owned by the frontend
+    pub project: Value,
+}
+
+/// Information used to find a media file after it moves
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MediaReference {
+    /// Path relative to the project file
+    pub relative: Option<String>,
+    /// File size in bytes
+    pub size: Option<u64>,
+    /// Hash of the file's first and last 64 KiB
+    pub fingerprint: Option<String>,
+}
+
+/// Whether a JSON key holds a media file path (`videoPath`, `pipMetadataPath`, ...)
+fn is_path_key(key: &str) -> bool {
+    key.ends_with("Path") || key.ends_with("_path")
+}
+
+/// Call `f` on every string value stored under a path key
+fn visit_paths(value: &mut Value, f: &mut impl FnMut(&mut String)) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                match child {
+                    Value::String(path) if is_path_key(key) => f(path),
+                    _ => visit_paths(child, f),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                visit_paths(item, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Express `path` relative to `base`, walking up with `..` where needed
+pub fn relative_path(path: &Path, base: &Path) -> Option<PathBuf> {
+    if !path.is_absolute() || !base.is_absolute() {
+        return None;
+    }
+
+    let path_parts: Vec<Component> = path.components().collect();
+    let base_parts: Vec<Component> = base.components().collect();
+    let common = path_parts
+        .iter()
+        .zip(&base_parts)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    // Only the root in common - an absolute path is more useful
+    if common <= 1 {
+        return None;
+    }
+
+    let mut relative = PathBuf::new();
+    for _ in common..base_parts.len() {
+        relative.push("..");
+    }
+    for part in &path_parts[common..] {
+        relative.push(part);
+    }
+    Some(relative)
+}
+
+/// Upgrade older project files to the current schema
+pub fn migrate(mut raw: Value) -> Result<ProjectFile, String> {
+    // Files without an envelope are treated as version 0
+    let version = match raw.get("project") {
+        Some(_) => raw.get("version").and_then(Value::as_u64).unwrap_or(0) as u32,
+        None => 0,
+    };
+
+    if version > PROJECT_VERSION {
+        return Err(format!(
+            "Project was saved by a newer version of ClipForge (format {}, supported {})",
+            version, PROJECT_VERSION
+        ));
+    }
+
+    // Version 0: bare frontend state without an envelope
+    if version == 0 {
+        raw = serde_json::json!({
+            "version": 1,
+            "savedAt": chrono::Utc::now().to_rfc3339(),
+            "media": {},
+            "project": raw,
+        });
+    }
+
+    // Version 1: media entries were bare relative path strings
+    if version <= 1 {
+        if let Some(Value::Object(media)) = raw.get_mut("media") {
+            for entry in media.values_mut() {
+                if let Value::String(relative) = entry {
+                    *entry = serde_json::json!({ "relative": relative });
+                }
+            }
+        }
+        raw["version"] = Value::from(2);
+    }
+
+    serde_json::from_value(raw).map_err(|e| format!("Invalid project file: {}", e))
+}
+
+/// Save project state to `path`, recording relative media paths
+pub fn write_project(path: &Path, project: Value) -> Result<ProjectFile, String> {
+    let project_dir = path
+        .parent()
+        .ok_or_else(|| "Invalid project path".to_string())?;
+
+    let mut project = project;
+    let mut media = BTreeMap::new();
+    visit_paths(&mut project, &mut |media_path| {
+        let absolute = Path::new(media_path.as_str());
+        let reference = MediaReference {
+            relative: relative_path(absolute, project_dir)
+                .map(|relative| relative.to_string_lossy().to_string()),
+            size: fs::metadata(absolute).ok().map(|m| m.len()),
+            fingerprint: media_fingerprint(absolute).ok(),
+        };
+        if reference != MediaReference::default() {
+            media.insert(media_path.clone(), reference);
+        }
+    });
+
+    let file = ProjectFile {
+        version: PROJECT_VERSION,
+        saved_at: chrono::Utc::now().to_rfc3339(),
+        media,
+        project,
+    };
+
+    let content = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Failed to serialize project: {}", e))?;
+
+    // Write to a temp file first so a failed save never truncates the project
+    let temp_path = path.with_extension("clipforge.tmp");
+    fs::write(&temp_path, content).map_err(|e| format!("Failed to write project: {}", e))?;
+    fs::rename(&temp_path, path).map_err(|e| format!("Failed to save project: {}", e))?;
+
+    Ok(file)
+}
+
+/// Load a project file, migrating it and re-resolving moved media
+pub fn read_project(path: &Path) -> Result<ProjectFile, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read project: {}", e))?;
+    let raw: Value =
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse project: {}", e))?;
+
+    let mut file = migrate(raw)?;
+    let project_dir = path.parent().unwrap_or(Path::new("."));
+
+    // Prefer the relative location when the absolute path no longer exists
+    let media = file.media.clone();
+    visit_paths(&mut file.project, &mut |media_path| {
+        if Path::new(media_path.as_str()).exists() {
+            return;
+        }
+        if let Some(relative) = media
+            .get(media_path.as_str())
+            .and_then(|reference| reference.relative.as_ref())
+        {
+            let resolved = project_dir.join(relative);
+            if resolved.exists() {
+                *media_path = resolved.to_string_lossy().to_string();
+            }
+        }
+    });
+
+    Ok(file)
+}
+
+/// Fingerprint a file by hashing its size plus its first and last 64 KiB (FNV-1a)
+pub fn media_fingerprint(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open media: {}", e))?;
+    let len = file
+        .metadata()
+        .map_err(|e| format!("Failed to read media metadata: {}", e))?
+        .len();
+
+    let mut hash: u64 = 0xcbf29ce484222325;
+    let mut feed = |bytes: &[u8]| {
+        for byte in bytes {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    };
+    feed(&len.to_le_bytes());
+
+    let mut buffer = vec![0u8; FINGERPRINT_CHUNK as usize];
+    let read = file
+        .read(&mut buffer)
+        .map_err(|e| format!("Failed to read media: {}", e))?;
+    feed(&buffer[..read]);
+
+    if len > FINGERPRINT_CHUNK * 2 {
+        file.seek(SeekFrom::End(-(FINGERPRINT_CHUNK as i64)))
+            .map_err(|e| format!("Failed to read media: {}", e))?;
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read media: {}", e))?;
+        feed(&buffer[..read]);
+    }
+
+    Ok(format!("{:016x}", hash))
+}
+
+/// A media reference that could not be found on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingMedia {
+    /// Project key the path was stored under (e.g. `videoPath`)
+    pub key: String,
+    pub path: String,
+}
+
+/// A missing media file that was found in a new location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelinkedMedia {
+    pub old_path: String,
+    pub new_path: String,
+}
+
+/// Result of a relink pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelinkResult {
+    /// Project state with relinked paths substituted
+    pub project: Value,
+    pub relinked: Vec<RelinkedMedia>,
+    /// Paths that are still offline
+    pub missing: Vec<String>,
+    /// Why the saved project file could not be used; files were then matched
+    /// by name alone
+    pub project_error: Option<String>,
+}
+
+/// Collect media references that don't exist on disk
+pub fn find_missing_media(project: &Value) -> Vec<MissingMedia> {
+    let mut missing = Vec::new();
+    collect_missing(project, &mut missing);
+    missing
+}
+
+fn collect_missing(value: &Value, missing: &mut Vec<MissingMedia>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                match child {
+                    Value::String(path) if is_path_key(key) => {
+                        if !path.is_empty() && !Path::new(path).exists() {
+                            missing.push(MissingMedia {
+                                key: key.clone(),
+                                path: path.clone(),
+                            });
+                        }
+                    }
+                    _ => collect_missing(child, missing),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_missing(item, missing);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Recursively index files under `dir` by lowercase file name
+fn index_files(dir: &Path, depth: usize, index: &mut BTreeMap<String, Vec<PathBuf>>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+
+        if path.is_dir() {
+            if depth < RELINK_MAX_DEPTH {
+                index_files(&path, depth + 1, index);
+            }
+        } else {
+            index.entry(name.to_lowercase()).or_default().push(path);
+        }
+    }
+}
+
+/// Pick the candidate matching the recorded size and fingerprint
+fn best_candidate(candidates: &[PathBuf], reference: Option<&MediaReference>) -> Option<PathBuf> {
+    let Some(reference) = reference else {
+        // Without recorded details only an unambiguous name match is safe
+        return match candidates {
+            [only] => Some(only.clone()),
+            _ => None,
+        };
+    };
+
+    candidates
+        .iter()
+        .filter(|candidate| match reference.size {
+            Some(size) => fs::metadata(candidate)
+                .map(|m| m.len() == size)
+                .unwrap_or(false),
+            None => true,
+        })
+        .find(|candidate| match &reference.fingerprint {
+            Some(fingerprint) => media_fingerprint(candidate).ok().as_ref() == Some(fingerprint),
+            None => true,
+        })
+        .cloned()
+}
+
+/// Save the frontend's project state to a file
+#[tauri::command]
+pub async fn save_project(app: AppHandle, path: String, project_json: Value) -> Result<(), String> {
+    let file = write_project(Path::new(&path), project_json)?;
+    add_recent(&app, RecentKind::Project, &path);
+
+    println!(
+        "Saved project to {} ({} media references)",
+        path,
+        file.media.len()
+    );
+    Ok(())
+}
+
+/// Load a project file and return the frontend's project state
+#[tauri::command]
+pub async fn load_project(app: AppHandle, path: String) -> Result<Value, String> {
+    let file = read_project(Path::new(&path))?;
+    add_recent(&app, RecentKind::Project, &path);
+
+    println!("Loaded project from {} (format {})", path, file.version);
+    Ok(file.project)
+}
+
+/// Report media referenced by a project that is missing on disk
+#[tauri::command]
+pub async fn verify_project_media(project: Value) -> Result<Vec<MissingMedia>, String> {
+    let missing = find_missing_media(&project);
+
+    println!("Project media check: {} offline", missing.len());
+    Ok(missing)
+}
+
+/// Search directories for missing media and update the project to point at it
+///
+/// Files are matched by name, then by the size and fingerprint recorded in the
+/// saved project file when `project_path` is given
+#[tauri::command]
+pub async fn relink_media(
+    project: Value,
+    search_dirs: Vec<String>,
+    project_path: Option<String>,
+) -> Result<RelinkResult, String> {
+    let missing = find_missing_media(&project);
+    if missing.is_empty() {
+        return Ok(RelinkResult {
+            project,
+            relinked: Vec::new(),
+            missing: Vec::new(),
+            project_error: None,
+        });
+    }
+
+    // An unreadable project file only loses the size and fingerprint checks
+    let mut project_error = None;
+    let references = match project_path.map(|path| read_project(Path::new(&path))) {
+        Some(Ok(file)) => file.media,
+        Some(Err(e)) => {
+            println!("[Projects] Relinking by name only: {}", e);
+            project_error = Some(e);
+            BTreeMap::new()
+        }
+        None => BTreeMap::new(),
+    };
+
+    let mut index = BTreeMap::new();
+    for dir in &search_dirs {
+        index_files(Path::new(dir), 0, &mut index);
+    }
+
+    let mut replacements: BTreeMap<String, String> = BTreeMap::new();
+    let mut still_missing = Vec::new();
+    for media in missing {
+        if replacements.contains_key(&media.path) || still_missing.contains(&media.path) {
+            continue;
+        }
+
+        let name = Path::new(&media.path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let found = index
+            .get(&name)
+            .and_then(|candidates| best_candidate(candidates, references.get(&media.path)));
+
+        match found {
+            Some(new_path) => {
+                replacements.insert(media.path, new_path.to_string_lossy().to_string());
+            }
+            None => still_missing.push(media.path),
+        }
+    }
+
+    let mut project = project;
+    visit_paths(&mut project, &mut |media_path| {
+        if let Some(new_path) = replacements.get(media_path.as_str()) {
+            *media_path = new_path.clone();
+        }
+    });
+
+    println!(
+        "Relinked {} media files, {} still offline",
+        replacements.len(),
+        still_missing.len()
+    );
+
+    Ok(RelinkResult {
+        project,
+        relinked: replacements
+            .into_iter()
+            .map(|(old_path, new_path)| RelinkedMedia { old_path, new_path })
+            .collect(),
+        missing: still_missing,
+        project_error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(
+            relative_path(
+                Path::new("/Users/me/Movies/clip.mp4"),
+                Path::new("/Users/me/Projects/demo")
+            ),
+            Some(PathBuf::from("../../Movies/clip.mp4"))
+        );
+        assert_eq!(
+            relative_path(Path::new("/Volumes/ext/clip.mp4"), Path::new("/Users/me")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_migrate_bare_project() {
+        let file = migrate(json!({ "clips": [] })).unwrap();
+        assert_eq!(file.version, PROJECT_VERSION);
+        assert_eq!(file.project, json!({ "clips": [] }));
+
+        assert!(migrate(json!({ "version": PROJECT_VERSION + 1, "project": {} })).is_err());
+    }
+
+    #[test]
+    fn test_visit_paths_finds_nested_media() {
+        let mut project = json!({
+            "name": "Demo",
+            "clips": [{ "videoPath": "/a.mp4", "pipMetadataPath": "/a.json", "label": "x" }]
+        });
+
+        let mut found = Vec::new();
+        visit_paths(&mut project, &mut |path| found.push(path.clone()));
+        found.sort();
+        assert_eq!(found, vec!["/a.json", "/a.mp4"]);
+    }
+
+    #[test]
+    fn test_migrate_v1_media_entries() {
+        let file = migrate(json!({
+            "version": 1,
+            "savedAt": "2025-01-01T00:00:00Z",
+            "media": { "/a.mp4": "../a.mp4" },
+            "project": {}
+        }))
+        .unwrap();
+
+        assert_eq!(file.version, 2);
+        assert_eq!(file.media["/a.mp4"].relative.as_deref(), Some("../a.mp4"));
+    }
+
+    #[test]
+    fn test_find_missing_media() {
+        let project = json!({
+            "clips": [{ "videoPath": "/definitely/not/here.mp4" }, { "videoPath": "" }]
+        });
+
+        let missing = find_missing_media(&project);
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].key, "videoPath");
+    }
+}
@@ -0,0 +1,164 @@
+// Proxy media
+//
+// Low-resolution H.264 copies of large recordings for smooth playback while
+// editing. Export keeps using the originals; the frontend looks up proxies
+// for the player only
+
+use super::ffmpeg_utils::find_ffmpeg;
+use super::media_cache::{self, CacheKind};
+use super::process_runner::{JobKind, WatchedOutput};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Default proxy height in pixels
+pub const DEFAULT_PROXY_HEIGHT: u32 = 540;
+
+/// Proxy cache size limit before the least recently used proxies are removed
+pub const MAX_PROXY_CACHE_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+
+/// A generated proxy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyInfo {
+    pub original_path: String,
+    pub proxy_path: String,
+    pub height: u32,
+    pub size: u64,
+}
+
+/// Proxy cache usage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyCacheInfo {
+    pub path: String,
+    pub count: usize,
+    pub total_bytes: u64,
+    pub limit_bytes: u64,
+}
+
+/// Cached proxy for a video at a given height, if one exists
+fn cached_proxy(hash: &str, height: u32) -> Option<String> {
+    media_cache::lookup(hash, CacheKind::Proxy, &format!("{}p", height))
+        .and_then(|item| item.files.into_iter().next())
+}
+
+/// Generate (or reuse) a low-resolution proxy for a video
+#[tauri::command]
+pub async fn generate_proxy(video_path: String, height: Option<u32>) -> Result<ProxyInfo, String> {
+    // Keep the height even so x264 accepts the scaled size
+    let height = height.unwrap_or(DEFAULT_PROXY_HEIGHT).max(144) & !1;
+    let hash = media_cache::content_hash(Path::new(&video_path))?;
+
+    let proxy_path = match cached_proxy(&hash, height) {
+        Some(path) => path,
+        None => generate(&video_path, &hash, height)?,
+    };
+
+    let size = fs::metadata(&proxy_path).map(|m| m.len()).unwrap_or(0);
+    Ok(ProxyInfo {
+        original_path: video_path,
+        proxy_path,
+        height,
+        size,
+    })
+}
+
+/// Encode a proxy into the media cache, returning its path
+fn generate(video_path: &str, hash: &str, height: u32) -> Result<String, String> {
+    let ffmpeg_path =
+        find_ffmpeg().ok_or_else(|| "ffmpeg not found. Please install FFmpeg.".to_string())?;
+    let proxy_path = media_cache::item_dir(hash)?.join(format!("proxy_{}p.mp4", height));
+
+    println!("[Proxy] Generating {}p proxy for {}", height, video_path);
+
+    // Encode to a temp name so an interrupted run never leaves a partial proxy
+    let temp_path = proxy_path.with_extension("partial");
+    let output = Command::new(&ffmpeg_path)
+        .arg("-i")
+        .arg(video_path)
+        .arg("-vf")
+        .arg(format!("scale=-2:{}", height))
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-preset")
+        .arg("veryfast")
+        .arg("-crf")
+        .arg("28")
+        // Short GOP keeps scrubbing responsive
+        .arg("-g")
+        .arg("15")
+        .arg("-c:a")
+        .arg("aac")
+        .arg("-b:a")
+        .arg("96k")
+        .arg("-movflags")
+        .arg("+faststart")
+        .arg("-f")
+        .arg("mp4")
+        .arg("-y")
+        .arg(&temp_path)
+        .watched_output(JobKind::Encode)
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    if !output.status.success() {
+        let _ = fs::remove_file(&temp_path);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Proxy generation failed: {}", stderr));
+    }
+
+    fs::rename(&temp_path, &proxy_path).map_err(|e| format!("Failed to store proxy: {}", e))?;
+
+    let proxy_path = proxy_path.to_string_lossy().to_string();
+    media_cache::store(
+        hash,
+        CacheKind::Proxy,
+        &format!("{}p", height),
+        video_path,
+        vec![proxy_path.clone()],
+        None,
+    );
+
+    let (_, freed) = media_cache::evict(Some(CacheKind::Proxy), None, MAX_PROXY_CACHE_BYTES);
+    if freed > 0 {
+        println!("[Proxy] Evicted {} bytes of old proxies", freed);
+    }
+    Ok(proxy_path)
+}
+
+/// Look up an existing proxy for a video without generating one
+#[tauri::command]
+pub async fn get_proxy(video_path: String, height: Option<u32>) -> Result<Option<String>, String> {
+    let height = height.unwrap_or(DEFAULT_PROXY_HEIGHT).max(144) & !1;
+    let hash = media_cache::content_hash(Path::new(&video_path))?;
+    Ok(cached_proxy(&hash, height))
+}
+
+/// Report proxy cache usage
+#[tauri::command]
+pub async fn get_proxy_cache_info() -> Result<ProxyCacheInfo, String> {
+    let stats = media_cache::stats();
+    let proxies = stats
+        .kinds
+        .iter()
+        .find(|kind| kind.kind == CacheKind::Proxy);
+
+    Ok(ProxyCacheInfo {
+        path: stats.path,
+        count: proxies.map(|kind| kind.count).unwrap_or(0),
+        total_bytes: proxies.map(|kind| kind.bytes).unwrap_or(0),
+        limit_bytes: MAX_PROXY_CACHE_BYTES,
+    })
+}
+
+/// Delete proxies, oldest first, until the cache is under `max_bytes` (all when omitted)
+///
+/// Returns the number of bytes freed
+#[tauri::command]
+pub async fn purge_proxy_cache(max_bytes: Option<u64>) -> Result<u64, String> {
+    let (_, freed) = media_cache::evict(Some(CacheKind::Proxy), None, max_bytes.unwrap_or(0));
+
+    println!("[Proxy] Purged {} bytes from proxy cache", freed);
+    Ok(freed)
+}
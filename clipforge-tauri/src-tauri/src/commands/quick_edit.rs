@@ -0,0 +1,748 @@
+// Quick edits that don't need a timeline
+//
+// Trimming, joining, re-orienting and re-voicing recordings directly with FFmpeg
+// instead of going through `export_timeline`.
+
+use super::ffmpeg_utils::{find_ffmpeg, find_ffprobe};
+use super::metadata::{extract_metadata, VideoMetadata};
+use super::process_runner::{JobKind, WatchedOutput};
+use super::recording::filename_template::unique_path;
+use super::video_import::import_file;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// How far before the trim start to look for a keyframe (seconds)
+const KEYFRAME_SEARCH_WINDOW: f64 = 30.0;
+
+/// Result of a quick trim
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrimResult {
+    pub output_path: String,
+    /// Actual start in the source; earlier than requested when a copy trim snaps to a keyframe
+    pub start: f64,
+    pub end: f64,
+}
+
+/// The last keyframe at or before `time`
+fn keyframe_at_or_before(keyframes: &[f64], time: f64) -> Option<f64> {
+    keyframes
+        .iter()
+        .copied()
+        .filter(|&keyframe| keyframe <= time + 0.001)
+        .max_by(|a, b| a.total_cmp(b))
+}
+
+/// Parse ffprobe `pts_time` lines
+fn parse_keyframe_times(output: &str) -> Vec<f64> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().trim_end_matches(',').parse::<f64>().ok())
+        .collect()
+}
+
+/// Keyframe times in the video stream shortly before `time`
+fn keyframes_near(input: &str, time: f64) -> Result<Vec<f64>, String> {
+    let ffprobe_path =
+        find_ffprobe().ok_or_else(|| "ffprobe not found. Please install FFmpeg.".to_string())?;
+    let output = Command::new(ffprobe_path)
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "v:0",
+            "-skip_frame",
+            "nokey",
+            "-show_entries",
+            "frame=pts_time",
+            "-of",
+            "csv=p=0",
+            "-read_intervals",
+            &format!(
+                "{}%{}",
+                (time - KEYFRAME_SEARCH_WINDOW).max(0.0),
+                time + 0.001
+            ),
+            input,
+        ])
+        .watched_output(JobKind::Probe)
+        .map_err(|e| format!("Failed to execute ffprobe: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("ffprobe failed: {}", stderr));
+    }
+    Ok(parse_keyframe_times(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Trim one file between `start` and `end` (seconds) into `output`
+///
+/// Without `reencode` the streams are copied, which is instant but has to start
+/// on a keyframe, so the start moves back to the previous keyframe. With
+/// `reencode` the cut is frame-accurate.
+#[tauri::command]
+pub async fn quick_trim(
+    input: String,
+    start: f64,
+    end: f64,
+    output: String,
+    reencode: bool,
+) -> Result<TrimResult, String> {
+    if !Path::new(&input).exists() {
+        return Err(format!("File not found: {}", input));
+    }
+    if Path::new(&input) == Path::new(&output) {
+        return Err("Output must be a different file than the input".to_string());
+    }
+    if start < 0.0 || end <= start {
+        return Err(format!("Invalid trim range {:.3}-{:.3}", start, end));
+    }
+
+    let ffmpeg_path =
+        find_ffmpeg().ok_or_else(|| "FFmpeg not found. Please install FFmpeg.".to_string())?;
+
+    let start = if reencode {
+        start
+    } else {
+        keyframe_at_or_before(&keyframes_near(&input, start)?, start).unwrap_or(start)
+    };
+
+    let mut command = Command::new(&ffmpeg_path);
+    command
+        .arg("-v")
+        .arg("error")
+        .arg("-ss")
+        .arg(format!("{:.3}", start))
+        .arg("-i")
+        .arg(&input)
+        .arg("-t")
+        .arg(format!("{:.3}", end - start));
+
+    if reencode {
+        command
+            .arg("-map")
+            .arg("0:v:0")
+            .arg("-map")
+            .arg("0:a?")
+            .arg("-c:v")
+            .arg("libx264")
+            .arg("-preset")
+            .arg("veryfast")
+            .arg("-crf")
+            .arg("18")
+            .arg("-pix_fmt")
+            .arg("yuv420p")
+            .arg("-c:a")
+            .arg("aac")
+            .arg("-b:a")
+            .arg("192k");
+    } else {
+        command
+            .arg("-map")
+            .arg("0")
+            .arg("-c")
+            .arg("copy")
+            .arg("-avoid_negative_ts")
+            .arg("make_zero");
+    }
+
+    let extension = Path::new(&output)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("");
+    if matches!(extension, "mp4" | "mov" | "m4v") {
+        command.arg("-movflags").arg("+faststart");
+    }
+
+    println!(
+        "[QuickEdit] Trimming {} ({:.3}-{:.3}, reencode: {}) to {}",
+        input, start, end, reencode, output
+    );
+    let result = command
+        .arg("-y")
+        .arg(&output)
+        .watched_output(JobKind::Encode)
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    if !result.status.success() {
+        let _ = fs::remove_file(&output);
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(format!("FFmpeg trim failed: {}", stderr));
+    }
+
+    Ok(TrimResult {
+        output_path: output,
+        start,
+        end,
+    })
+}
+
+fn lowercase_extension(path: &str) -> String {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// An unused `<stem>_<suffix>.<extension>` path in the same folder as `input`
+fn output_next_to(input: &str, suffix: &str, extension: &str) -> PathBuf {
+    let input = Path::new(input);
+    let stem = input
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("video");
+    unique_path(
+        input.parent().unwrap_or(Path::new(".")),
+        &format!("{}_{}", stem, suffix),
+        extension,
+    )
+}
+
+/// Contents of an FFmpeg concat demuxer list
+pub fn concat_list(files: &[PathBuf]) -> String {
+    files
+        .iter()
+        .map(|path| format!("file '{}'\n", path.to_string_lossy().replace('\'', "'\\''")))
+        .collect()
+}
+
+fn or_none<T: std::fmt::Display>(value: Option<T>) -> String {
+    value.map_or_else(|| "none".to_string(), |value| value.to_string())
+}
+
+/// Reasons `other` can't be stream-copied onto the end of `first`
+fn concat_mismatches(first: &VideoMetadata, other: &VideoMetadata) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    let mut check = |what: &str, a: String, b: String| {
+        if a != b {
+            mismatches.push(format!("{} {} vs {}", what, a, b));
+        }
+    };
+
+    check(
+        "video codec",
+        or_none(first.video_codec.as_ref()),
+        or_none(other.video_codec.as_ref()),
+    );
+    check(
+        "resolution",
+        format!("{}x{}", first.width, first.height),
+        format!("{}x{}", other.width, other.height),
+    );
+    check(
+        "pixel format",
+        or_none(first.pixel_format.as_ref()),
+        or_none(other.pixel_format.as_ref()),
+    );
+    check(
+        "audio tracks",
+        first.audio_stream_count.to_string(),
+        other.audio_stream_count.to_string(),
+    );
+    check(
+        "audio codec",
+        or_none(first.audio_codec.as_ref()),
+        or_none(other.audio_codec.as_ref()),
+    );
+    check(
+        "sample rate",
+        or_none(first.audio_sample_rate.as_ref()),
+        or_none(other.audio_sample_rate.as_ref()),
+    );
+    check(
+        "channels",
+        or_none(first.audio_channels.as_ref()),
+        or_none(other.audio_channels.as_ref()),
+    );
+    mismatches
+}
+
+/// Check that files can be stream-copied end to end
+///
+/// Every file must match the first one's codecs, resolution, pixel format and
+/// audio layout; the error names the first file that doesn't.
+pub async fn check_concat_compatible(files: &[PathBuf]) -> Result<(), String> {
+    let mut first: Option<VideoMetadata> = None;
+    for path in files {
+        let metadata = extract_metadata(path.to_string_lossy().to_string()).await?;
+        match &first {
+            Some(first) => {
+                let mismatches = concat_mismatches(first, &metadata);
+                if !mismatches.is_empty() {
+                    return Err(format!(
+                        "{} can't be joined to {}: {}",
+                        metadata.filename,
+                        first.filename,
+                        mismatches.join(", ")
+                    ));
+                }
+            }
+            None => first = Some(metadata),
+        }
+    }
+    Ok(())
+}
+
+/// Join files end to end without re-encoding, keeping every stream
+///
+/// Callers check the files with `check_concat_compatible` first unless they
+/// come from the same encoder settings.
+pub fn concat_copy(files: &[PathBuf], output: &Path) -> Result<(), String> {
+    let ffmpeg_path =
+        find_ffmpeg().ok_or_else(|| "FFmpeg not found. Please install FFmpeg.".to_string())?;
+
+    let list_path = output.with_extension("concat.txt");
+    fs::write(&list_path, concat_list(files))
+        .map_err(|e| format!("Failed to write concat list: {}", e))?;
+
+    let mut command = Command::new(&ffmpeg_path);
+    command
+        .arg("-v")
+        .arg("error")
+        .arg("-f")
+        .arg("concat")
+        .arg("-safe")
+        .arg("0")
+        .arg("-i")
+        .arg(&list_path)
+        .arg("-map")
+        .arg("0")
+        .arg("-c")
+        .arg("copy");
+
+    let extension = output.extension().and_then(|e| e.to_str()).unwrap_or("");
+    if matches!(extension, "mp4" | "mov" | "m4v") {
+        command.arg("-movflags").arg("+faststart");
+    }
+
+    let result = command
+        .arg("-y")
+        .arg(output)
+        .watched_output(JobKind::Encode)
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e));
+    let _ = fs::remove_file(&list_path);
+
+    let result = result?;
+    if !result.status.success() {
+        let _ = fs::remove_file(output);
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(format!("FFmpeg concat failed: {}", stderr));
+    }
+    Ok(())
+}
+
+/// Join recordings end to end without re-encoding
+///
+/// Meant for chunks of one long recording, which share codecs and resolution.
+/// Files that don't match the first one are rejected rather than re-encoded.
+#[tauri::command]
+pub async fn concat_recordings(paths: Vec<String>, output: String) -> Result<String, String> {
+    if paths.len() < 2 {
+        return Err("Select at least two recordings to join".to_string());
+    }
+    if paths
+        .iter()
+        .any(|path| Path::new(path) == Path::new(&output))
+    {
+        return Err("Output must be a different file than the inputs".to_string());
+    }
+
+    let files: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+    check_concat_compatible(&files).await?;
+
+    println!(
+        "[QuickEdit] Joining {} recordings into {}",
+        paths.len(),
+        output
+    );
+    concat_copy(&files, Path::new(&output))?;
+    Ok(output)
+}
+
+/// Video filter chain that rotates clockwise by `rotate` degrees, then mirrors
+fn transform_filter(rotate: u32, flip_h: bool, flip_v: bool) -> Option<String> {
+    // A half turn is both flips, so mirroring it again on an axis cancels that flip
+    let half_turn = rotate == 180;
+    let mut filters: Vec<&str> = match rotate {
+        90 => vec!["transpose=clock"],
+        270 => vec!["transpose=cclock"],
+        _ => Vec::new(),
+    };
+    if flip_h != half_turn {
+        filters.push("hflip");
+    }
+    if flip_v != half_turn {
+        filters.push("vflip");
+    }
+
+    (!filters.is_empty()).then(|| filters.join(","))
+}
+
+/// Rotate (clockwise, in 90 degree steps) and/or mirror a clip into a new file
+///
+/// Pure rotations of MP4/MOV files only rewrite the display rotation, without
+/// re-encoding. Mirroring, other containers (e.g. WebM webcam blobs) and FFmpeg
+/// builds without `-display_rotation` re-encode to H.264 MP4. Returns the new
+/// file's metadata.
+#[tauri::command]
+pub async fn transform_video(
+    path: String,
+    rotate: i32,
+    flip_h: bool,
+    flip_v: bool,
+) -> Result<VideoMetadata, String> {
+    let rotate = rotate.rem_euclid(360) as u32;
+    if !rotate.is_multiple_of(90) {
+        return Err(format!(
+            "Rotation must be a multiple of 90 degrees, got {}",
+            rotate
+        ));
+    }
+    let Some(filter) = transform_filter(rotate, flip_h, flip_v) else {
+        return Err("Nothing to change".to_string());
+    };
+
+    let source = extract_metadata(path.clone()).await?;
+    let ffmpeg_path =
+        find_ffmpeg().ok_or_else(|| "FFmpeg not found. Please install FFmpeg.".to_string())?;
+
+    let extension = lowercase_extension(&path);
+
+    // Rotation metadata is a counter-clockwise angle that replaces the existing one
+    if !flip_h && !flip_v && matches!(extension.as_str(), "mp4" | "mov" | "m4v") {
+        let output_path = output_next_to(&path, "fixed", &extension);
+        let counter_clockwise = (360 - (source.rotation + rotate) % 360) % 360;
+        let output = Command::new(&ffmpeg_path)
+            .arg("-v")
+            .arg("error")
+            .arg("-display_rotation:v:0")
+            .arg(counter_clockwise.to_string())
+            .arg("-i")
+            .arg(&path)
+            .arg("-map")
+            .arg("0")
+            .arg("-c")
+            .arg("copy")
+            .arg("-y")
+            .arg(&output_path)
+            .watched_output(JobKind::Encode)
+            .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+        if output.status.success() {
+            return import_file(output_path.to_string_lossy().to_string()).await;
+        }
+        let _ = fs::remove_file(&output_path);
+        println!(
+            "[QuickEdit] Metadata rotation failed, re-encoding: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    // FFmpeg applies the existing rotation while decoding, so the filters work on upright frames
+    let output_path = output_next_to(&path, "fixed", "mp4");
+    let output = Command::new(&ffmpeg_path)
+        .arg("-v")
+        .arg("error")
+        .arg("-i")
+        .arg(&path)
+        .arg("-map")
+        .arg("0:v:0")
+        .arg("-map")
+        .arg("0:a?")
+        .arg("-vf")
+        .arg(&filter)
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-preset")
+        .arg("veryfast")
+        .arg("-crf")
+        .arg("18")
+        .arg("-pix_fmt")
+        .arg("yuv420p")
+        .arg("-c:a")
+        .arg("aac")
+        .arg("-b:a")
+        .arg("192k")
+        .arg("-movflags")
+        .arg("+faststart")
+        .arg("-y")
+        .arg(&output_path)
+        .watched_output(JobKind::Encode)
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    if !output.status.success() {
+        let _ = fs::remove_file(&output_path);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("FFmpeg transform failed: {}", stderr));
+    }
+
+    println!(
+        "[QuickEdit] Transformed {} (rotate {}, flip h/v {}/{}) to {}",
+        path,
+        rotate,
+        flip_h,
+        flip_v,
+        output_path.display()
+    );
+    import_file(output_path.to_string_lossy().to_string()).await
+}
+
+/// Input options that shift the replacement audio by `offset` seconds
+fn audio_offset_args(offset: f64) -> Vec<String> {
+    if offset > 0.0 {
+        vec!["-itsoffset".to_string(), format!("{:.3}", offset)]
+    } else if offset < 0.0 {
+        // Skip the start of the audio instead of shifting it before the video
+        vec!["-ss".to_string(), format!("{:.3}", -offset)]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Audio encoder that fits a container
+fn audio_encoder_for(extension: &str) -> &'static str {
+    match extension {
+        "webm" => "libopus",
+        _ => "aac",
+    }
+}
+
+/// Copy a clip's audio into its own file next to the video
+///
+/// AAC audio is copied into an .m4a; anything else is encoded to AAC. Returns the
+/// audio file's metadata so it can go straight onto the timeline.
+#[tauri::command]
+pub async fn extract_audio(video_path: String) -> Result<VideoMetadata, String> {
+    let source = extract_metadata(video_path.clone()).await?;
+    if source.audio_stream_count == 0 {
+        return Err(format!("{} has no audio", source.filename));
+    }
+
+    let ffmpeg_path =
+        find_ffmpeg().ok_or_else(|| "FFmpeg not found. Please install FFmpeg.".to_string())?;
+    let output_path = output_next_to(&video_path, "audio", "m4a");
+
+    let mut command = Command::new(&ffmpeg_path);
+    command
+        .arg("-v")
+        .arg("error")
+        .arg("-i")
+        .arg(&video_path)
+        .arg("-map")
+        .arg("0:a:0")
+        .arg("-vn");
+    if source.audio_codec.as_deref() == Some("aac") {
+        command.arg("-c:a").arg("copy");
+    } else {
+        command.arg("-c:a").arg("aac").arg("-b:a").arg("192k");
+    }
+
+    let output = command
+        .arg("-y")
+        .arg(&output_path)
+        .watched_output(JobKind::Encode)
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    if !output.status.success() {
+        let _ = fs::remove_file(&output_path);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("FFmpeg audio extraction failed: {}", stderr));
+    }
+
+    println!(
+        "[QuickEdit] Extracted audio from {} to {}",
+        video_path,
+        output_path.display()
+    );
+    import_file(output_path.to_string_lossy().to_string()).await
+}
+
+/// Swap a clip's audio for another file, e.g. re-recorded narration
+///
+/// `offset` (seconds) delays the new audio when positive and skips its start when
+/// negative. The video is copied and the audio cut at the end of the video. Writes a
+/// `_new_audio` copy next to the video and returns its metadata.
+#[tauri::command]
+pub async fn replace_audio(
+    video_path: String,
+    audio_path: String,
+    offset: f64,
+) -> Result<VideoMetadata, String> {
+    if !offset.is_finite() {
+        return Err("Invalid audio offset".to_string());
+    }
+    let source = extract_metadata(video_path.clone()).await?;
+    if source.video_codec.is_none() {
+        return Err(format!("{} has no video", source.filename));
+    }
+    if !Path::new(&audio_path).exists() {
+        return Err(format!("File not found: {}", audio_path));
+    }
+
+    let ffmpeg_path =
+        find_ffmpeg().ok_or_else(|| "FFmpeg not found. Please install FFmpeg.".to_string())?;
+    let extension = lowercase_extension(&video_path);
+    let output_path = output_next_to(&video_path, "new_audio", &extension);
+
+    let mut command = Command::new(&ffmpeg_path);
+    command
+        .arg("-v")
+        .arg("error")
+        .arg("-i")
+        .arg(&video_path)
+        .args(audio_offset_args(offset))
+        .arg("-i")
+        .arg(&audio_path)
+        .arg("-map")
+        .arg("0:v:0")
+        .arg("-map")
+        .arg("1:a:0")
+        .arg("-c:v")
+        .arg("copy")
+        .arg("-c:a")
+        .arg(audio_encoder_for(&extension))
+        .arg("-b:a")
+        .arg("192k")
+        .arg("-t")
+        .arg(format!("{:.3}", source.duration));
+    if matches!(extension.as_str(), "mp4" | "mov" | "m4v") {
+        command.arg("-movflags").arg("+faststart");
+    }
+
+    let output = command
+        .arg("-y")
+        .arg(&output_path)
+        .watched_output(JobKind::Encode)
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    if !output.status.success() {
+        let _ = fs::remove_file(&output_path);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("FFmpeg audio replacement failed: {}", stderr));
+    }
+
+    println!(
+        "[QuickEdit] Replaced audio of {} with {} (offset {:.3}s)",
+        video_path, audio_path, offset
+    );
+    import_file(output_path.to_string_lossy().to_string()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyframe_at_or_before() {
+        let keyframes = [0.0, 2.0, 4.0, 6.0];
+        assert_eq!(keyframe_at_or_before(&keyframes, 5.5), Some(4.0));
+        assert_eq!(keyframe_at_or_before(&keyframes, 4.0), Some(4.0));
+        assert_eq!(keyframe_at_or_before(&[3.0], 1.0), None);
+        assert_eq!(keyframe_at_or_before(&[], 1.0), None);
+    }
+
+    #[test]
+    fn test_concat_list_escapes_quotes() {
+        let segments = vec![
+            PathBuf::from("/tmp/clipforge_replay/replay_001.mp4"),
+            PathBuf::from("/tmp/Nick's clips/replay_002.mp4"),
+        ];
+        assert_eq!(
+            concat_list(&segments),
+            "file '/tmp/clipforge_replay/replay_001.mp4'\nfile '/tmp/Nick'\\''s clips/replay_002.mp4'\n"
+        );
+    }
+
+    #[test]
+    fn test_concat_mismatches() {
+        let first: VideoMetadata = serde_json::from_value(serde_json::json!({
+            "path": "/tmp/chunk.mp4",
+            "filename": "chunk.mp4",
+            "duration": 1800.0,
+            "width": 1920,
+            "height": 1080,
+            "frame_rate": 30.0,
+            "thumbnail_path": null,
+            "file_size": null,
+            "video_codec": "h264",
+            "audio_stream_count": 1,
+            "audio_codec": "aac",
+            "audio_sample_rate": 48000
+        }))
+        .unwrap();
+        let same: VideoMetadata = serde_json::from_value(serde_json::json!({
+            "path": "/tmp/chunk.mp4",
+            "filename": "chunk.mp4",
+            "duration": 1800.0,
+            "width": 1920,
+            "height": 1080,
+            "frame_rate": 30.0,
+            "thumbnail_path": null,
+            "file_size": null,
+            "video_codec": "h264",
+            "audio_stream_count": 1,
+            "audio_codec": "aac",
+            "audio_sample_rate": 48000
+        }))
+        .unwrap();
+        let other: VideoMetadata = serde_json::from_value(serde_json::json!({
+            "path": "/tmp/chunk.mp4",
+            "filename": "chunk.mp4",
+            "duration": 1800.0,
+            "width": 1280,
+            "height": 1080,
+            "frame_rate": 30.0,
+            "thumbnail_path": null,
+            "file_size": null,
+            "video_codec": "hevc",
+            "audio_stream_count": 1,
+            "audio_codec": "aac",
+            "audio_sample_rate": 44100
+        }))
+        .unwrap();
+        assert!(concat_mismatches(&first, &same).is_empty());
+
+        let mismatches = concat_mismatches(&first, &other);
+        assert_eq!(mismatches.len(), 3);
+        assert!(mismatches[1].contains("1920x1080 vs 1280x1080"));
+    }
+
+    #[test]
+    fn test_transform_filter() {
+        assert_eq!(transform_filter(0, false, false), None);
+        assert_eq!(
+            transform_filter(90, false, false).as_deref(),
+            Some("transpose=clock")
+        );
+        assert_eq!(
+            transform_filter(270, true, false).as_deref(),
+            Some("transpose=cclock,hflip")
+        );
+        assert_eq!(
+            transform_filter(180, false, false).as_deref(),
+            Some("hflip,vflip")
+        );
+        assert_eq!(transform_filter(180, false, true).as_deref(), Some("hflip"));
+        assert_eq!(transform_filter(180, true, true), None);
+    }
+
+    #[test]
+    fn test_audio_offset_args() {
+        assert!(audio_offset_args(0.0).is_empty());
+        assert_eq!(audio_offset_args(1.5), vec!["-itsoffset", "1.500"]);
+        assert_eq!(audio_offset_args(-0.25), vec!["-ss", "0.250"]);
+    }
+
+    #[test]
+    fn test_parse_keyframe_times() {
+        assert_eq!(
+            parse_keyframe_times("0.000000\n2.002000,\n\nN/A\n4.004000\n"),
+            vec![0.0, 2.002, 4.004]
+        );
+    }
+}
@@ -0,0 +1,314 @@
+// Recent projects and recordings
+//
+// Most-recently-used lists kept in `recents.json` in the app data directory.
+// Projects are added when they are saved or opened and recordings when they
+// finish. The File > Open Recent submenu is rebuilt from the lists at startup
+// and after every change, which is also broadcast as "recents:changed".
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::menu::{IsMenuItem, MenuItemBuilder, PredefinedMenuItem, Submenu, SubmenuBuilder};
+use tauri::{AppHandle, Emitter, Manager, State, Wry};
+
+/// Entries kept per list
+pub const MAX_RECENTS: usize = 10;
+
+/// Menu id prefix of a recent project item; the rest of the id is its path
+pub const PROJECT_MENU_PREFIX: &str = "recent_project:";
+
+/// Menu id prefix of a recent recording item; the rest of the id is its path
+pub const RECORDING_MENU_PREFIX: &str = "recent_recording:";
+
+/// Menu id of the "Clear Menu" item
+pub const CLEAR_MENU_ID: &str = "clear_recents";
+
+/// Which list an entry belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecentKind {
+    Project,
+    Recording,
+}
+
+/// A recently used file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentItem {
+    pub path: String,
+    /// File name shown in menus
+    pub name: String,
+    /// Milliseconds since epoch
+    pub last_used: u64,
+}
+
+/// Recent projects and recordings, newest first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RecentLists {
+    pub projects: Vec<RecentItem>,
+    pub recordings: Vec<RecentItem>,
+}
+
+impl RecentLists {
+    pub fn list(&self, kind: RecentKind) -> &[RecentItem] {
+        match kind {
+            RecentKind::Project => &self.projects,
+            RecentKind::Recording => &self.recordings,
+        }
+    }
+
+    fn list_mut(&mut self, kind: RecentKind) -> &mut Vec<RecentItem> {
+        match kind {
+            RecentKind::Project => &mut self.projects,
+            RecentKind::Recording => &mut self.recordings,
+        }
+    }
+
+    /// Move `path` to the front of its list, trimming the list to `MAX_RECENTS`
+    pub fn touch(&mut self, kind: RecentKind, path: &str, now: u64) {
+        let list = self.list_mut(kind);
+        list.retain(|item| item.path != path);
+        list.insert(
+            0,
+            RecentItem {
+                path: path.to_string(),
+                name: Path::new(path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.to_string()),
+                last_used: now,
+            },
+        );
+        list.truncate(MAX_RECENTS);
+    }
+
+    /// Empty one list, or both when `kind` is `None`
+    pub fn clear(&mut self, kind: Option<RecentKind>) {
+        match kind {
+            Some(kind) => self.list_mut(kind).clear(),
+            None => *self = Self::default(),
+        }
+    }
+}
+
+pub type RecentsState = Arc<Mutex<RecentLists>>;
+
+/// The File > Open Recent submenu, rebuilt whenever the lists change
+pub struct RecentMenu(pub Submenu<Wry>);
+
+fn recents_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir.join("recents.json"))
+}
+
+fn save_recents(app: &AppHandle, recents: &RecentLists) -> Result<(), String> {
+    let path = recents_path(app)?;
+    let content = serde_json::to_string_pretty(recents)
+        .map_err(|e| format!("Failed to serialize recents: {}", e))?;
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, content).map_err(|e| format!("Failed to write recents: {}", e))?;
+    fs::rename(&temp_path, &path).map_err(|e| format!("Failed to write recents: {}", e))
+}
+
+/// Read the saved lists, starting empty when missing or unreadable
+pub fn load_recents(app: &AppHandle) -> RecentsState {
+    let recents = recents_path(app)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    Arc::new(Mutex::new(recents))
+}
+
+/// File a recent-item menu id points at
+pub fn menu_target(id: &str) -> Option<(RecentKind, &str)> {
+    if let Some(path) = id.strip_prefix(PROJECT_MENU_PREFIX) {
+        Some((RecentKind::Project, path))
+    } else {
+        id.strip_prefix(RECORDING_MENU_PREFIX)
+            .map(|path| (RecentKind::Recording, path))
+    }
+}
+
+/// Replace the submenu's items with the current lists
+fn populate_menu(app: &AppHandle, menu: &Submenu<Wry>, recents: &RecentLists) -> tauri::Result<()> {
+    while menu.remove_at(0)?.is_some() {}
+
+    let mut items: Vec<Box<dyn IsMenuItem<Wry>>> = Vec::new();
+    for (kind, prefix) in [
+        (RecentKind::Project, PROJECT_MENU_PREFIX),
+        (RecentKind::Recording, RECORDING_MENU_PREFIX),
+    ] {
+        let list = recents.list(kind);
+        if list.is_empty() {
+            continue;
+        }
+        if !items.is_empty() {
+            items.push(Box::new(PredefinedMenuItem::separator(app)?));
+        }
+        for item in list {
+            let id = format!("{}{}", prefix, item.path);
+            items.push(Box::new(
+                MenuItemBuilder::with_id(id, &item.name).build(app)?,
+            ));
+        }
+    }
+
+    let has_items = !items.is_empty();
+    if has_items {
+        items.push(Box::new(PredefinedMenuItem::separator(app)?));
+    }
+    items.push(Box::new(
+        MenuItemBuilder::with_id(CLEAR_MENU_ID, "Clear Menu")
+            .enabled(has_items)
+            .build(app)?,
+    ));
+
+    for item in &items {
+        menu.append(item.as_ref())?;
+    }
+    Ok(())
+}
+
+/// Build the Open Recent submenu from the saved lists
+pub fn build_recent_menu(app: &AppHandle, recents: &RecentsState) -> tauri::Result<Submenu<Wry>> {
+    let menu = SubmenuBuilder::with_id(app, "open_recent", "Open Recent").build()?;
+    if let Ok(recents) = recents.lock() {
+        populate_menu(app, &menu, &recents)?;
+    }
+    Ok(menu)
+}
+
+/// Save the lists, rebuild the menu and emit "recents:changed"
+fn publish(app: &AppHandle, recents: &RecentLists) {
+    if let Err(e) = save_recents(app, recents) {
+        println!("[Recents] {}", e);
+    }
+    if let Some(menu) = app.try_state::<RecentMenu>() {
+        if let Err(e) = populate_menu(app, &menu.0, recents) {
+            println!("[Recents] Failed to update Open Recent menu: {}", e);
+        }
+    }
+    let _ = app.emit("recents:changed", recents);
+}
+
+/// Record that a project or recording was just used
+pub fn add_recent(app: &AppHandle, kind: RecentKind, path: &str) {
+    let Some(state) = app.try_state::<RecentsState>() else {
+        return;
+    };
+    let recents = match state.lock() {
+        Ok(mut recents) => {
+            recents.touch(kind, path, chrono::Utc::now().timestamp_millis() as u64);
+            recents.clone()
+        }
+        Err(_) => return,
+    };
+    publish(app, &recents);
+}
+
+/// Empty one list, or both when `kind` is `None`
+pub fn clear(app: &AppHandle, kind: Option<RecentKind>) -> Result<RecentLists, String> {
+    let state = app.state::<RecentsState>();
+    let recents = {
+        let mut recents = state.lock().map_err(|e| e.to_string())?;
+        recents.clear(kind);
+        recents.clone()
+    };
+    publish(app, &recents);
+    Ok(recents)
+}
+
+/// Recently saved or opened projects that still exist, newest first
+#[tauri::command]
+pub async fn get_recent_projects(
+    state: State<'_, RecentsState>,
+) -> Result<Vec<RecentItem>, String> {
+    let recents = state.lock().map_err(|e| e.to_string())?;
+    Ok(existing(recents.list(RecentKind::Project)))
+}
+
+/// Recently finished recordings that still exist, newest first
+#[tauri::command]
+pub async fn get_recent_recordings(
+    state: State<'_, RecentsState>,
+) -> Result<Vec<RecentItem>, String> {
+    let recents = state.lock().map_err(|e| e.to_string())?;
+    Ok(existing(recents.list(RecentKind::Recording)))
+}
+
+/// Clear recent projects, recent recordings, or both when `kind` is omitted
+#[tauri::command]
+pub async fn clear_recents(app: AppHandle, kind: Option<RecentKind>) -> Result<(), String> {
+    clear(&app, kind)?;
+    Ok(())
+}
+
+/// Entries whose files have not been moved or deleted
+fn existing(list: &[RecentItem]) -> Vec<RecentItem> {
+    list.iter()
+        .filter(|item| Path::new(&item.path).exists())
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_touch_moves_to_front_and_trims() {
+        let mut recents = RecentLists::default();
+        for i in 0..MAX_RECENTS + 2 {
+            recents.touch(
+                RecentKind::Project,
+                &format!("/p/{}.clipforge", i),
+                i as u64,
+            );
+        }
+        recents.touch(RecentKind::Project, "/p/5.clipforge", 100);
+
+        let projects = recents.list(RecentKind::Project);
+        assert_eq!(projects.len(), MAX_RECENTS);
+        assert_eq!(projects[0].path, "/p/5.clipforge");
+        assert_eq!(projects[0].name, "5.clipforge");
+        assert_eq!(
+            projects
+                .iter()
+                .filter(|item| item.path == "/p/5.clipforge")
+                .count(),
+            1
+        );
+        assert!(recents.list(RecentKind::Recording).is_empty());
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut recents = RecentLists::default();
+        recents.touch(RecentKind::Project, "/p/a.clipforge", 1);
+        recents.touch(RecentKind::Recording, "/r/a.mp4", 1);
+
+        recents.clear(Some(RecentKind::Recording));
+        assert_eq!(recents.projects.len(), 1);
+        assert!(recents.recordings.is_empty());
+
+        recents.clear(None);
+        assert!(recents.projects.is_empty());
+    }
+
+    #[test]
+    fn test_menu_target() {
+        assert_eq!(
+            menu_target("recent_recording:/r/a:b.mp4"),
+            Some((RecentKind::Recording, "/r/a:b.mp4"))
+        );
+        assert_eq!(menu_target("save"), None);
+    }
+}
@@ -0,0 +1,400 @@
+// Per-application audio capture
+//
+// ScreenCaptureKit (macOS 13+) can limit captured audio to a set of applications,
+// or capture everything except them. While recording, the audio is written as raw
+// s16le PCM next to the temporary file; when the recording stops it is muxed into
+// the finished file, mixed with the microphone track if there is one.
+
+use super::super::ffmpeg_utils::find_ffmpeg;
+use super::super::process_runner::{JobKind, WatchedOutput};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often the capture thread drains the ScreenCaptureKit audio queue
+const DRAIN_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Gaps in the buffer timestamps longer than this are filled with silence
+const MAX_TIMESTAMP_DRIFT: f64 = 0.05;
+
+/// Whether the listed applications are the only audio or are left out of it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AudioAppFilterMode {
+    /// Record audio from these applications only
+    Only,
+    /// Record audio from every application except these
+    Exclude,
+}
+
+/// Applications whose audio is recorded (or left out) during a recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioAppFilter {
+    pub mode: AudioAppFilterMode,
+    /// Bundle identifiers, e.g. "com.google.Chrome"
+    pub bundle_ids: Vec<String>,
+}
+
+impl AudioAppFilter {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.mode == AudioAppFilterMode::Only && self.bundle_ids.is_empty() {
+            return Err("Choose at least one application to record audio from".to_string());
+        }
+        if let Some(id) = self
+            .bundle_ids
+            .iter()
+            .find(|id| id.trim().is_empty() || id.contains(['\n', '\0']))
+        {
+            return Err(format!("Invalid application bundle identifier: {:?}", id));
+        }
+        Ok(())
+    }
+}
+
+/// Sample format of the captured PCM
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PcmFormat {
+    sample_rate: u32,
+    channels: u32,
+}
+
+/// Frames of silence needed to cover the gap between the expected and actual timestamp
+fn silence_frames(expected: f64, actual: f64, sample_rate: u32) -> usize {
+    let gap = actual - expected;
+    if gap > MAX_TIMESTAMP_DRIFT {
+        (gap * sample_rate as f64).round() as usize
+    } else {
+        0
+    }
+}
+
+/// Writes PCM buffers to the sidecar, padding over the gaps ScreenCaptureKit leaves
+/// while the applications are silent
+struct PcmWriter {
+    writer: BufWriter<File>,
+    started: Instant,
+    format: Option<PcmFormat>,
+    next_timestamp: f64,
+    /// Seconds the recording has been paused, left out of the timeline
+    paused_duration: f64,
+}
+
+impl PcmWriter {
+    fn write(
+        &mut self,
+        pcm_data: &[u8],
+        format: PcmFormat,
+        timestamp: f64,
+        frames: u32,
+    ) -> Result<(), String> {
+        let timestamp = timestamp - self.paused_duration;
+        let padding = match self.format {
+            // Line the first buffer up with the start of the recording
+            None => {
+                let elapsed = self.started.elapsed().as_secs_f64() - self.paused_duration;
+                (elapsed.max(0.0) * format.sample_rate as f64) as usize
+            }
+            Some(_) => silence_frames(self.next_timestamp, timestamp, format.sample_rate),
+        };
+
+        if padding > 0 {
+            let silence = vec![0u8; padding * format.channels as usize * 2];
+            self.writer
+                .write_all(&silence)
+                .map_err(|e| format!("Failed to write application audio: {}", e))?;
+        }
+
+        self.writer
+            .write_all(pcm_data)
+            .map_err(|e| format!("Failed to write application audio: {}", e))?;
+
+        self.format = Some(format);
+        self.next_timestamp = timestamp + frames as f64 / format.sample_rate.max(1) as f64;
+        Ok(())
+    }
+}
+
+/// Captures application audio through ScreenCaptureKit on a background thread
+pub struct AppAudioRecorder {
+    should_stop: Arc<AtomicBool>,
+    /// Audio is dropped while set, and the paused time is left out of the track
+    paused: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<Result<Option<PcmFormat>, String>>>,
+    pcm_path: PathBuf,
+    /// Mix with the recording's own audio instead of replacing it
+    mix: bool,
+}
+
+impl AppAudioRecorder {
+    /// Start capturing; `display_id` 0 anchors the filter to the first display
+    #[cfg(target_os = "macos")]
+    pub fn start(
+        filter: &AudioAppFilter,
+        display_id: u32,
+        pcm_path: PathBuf,
+        mix: bool,
+    ) -> Result<Self, String> {
+        use crate::capture::ffi::{BridgeError, ScreenCaptureBridge, StreamConfig};
+
+        filter.validate()?;
+
+        let bridge = ScreenCaptureBridge::new().ok_or(BridgeError::Unavailable)?;
+
+        // The stream always produces video; keep it as small as possible since it is unused
+        bridge.configure_stream(&StreamConfig {
+            width: 2,
+            height: 2,
+            frame_rate: 1,
+            capture_audio: true,
+        })?;
+        bridge.configure_app_audio(
+            display_id,
+            &filter.bundle_ids,
+            filter.mode == AudioAppFilterMode::Exclude,
+        )?;
+
+        let file = File::create(&pcm_path)
+            .map_err(|e| format!("Failed to create application audio file: {}", e))?;
+        bridge.start_capture()?;
+
+        let should_stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = Arc::clone(&should_stop);
+        let paused = Arc::new(AtomicBool::new(false));
+        let pause_flag = Arc::clone(&paused);
+
+        let handle = thread::spawn(move || {
+            let mut writer = PcmWriter {
+                writer: BufWriter::new(file),
+                started: Instant::now(),
+                format: None,
+                next_timestamp: 0.0,
+                paused_duration: 0.0,
+            };
+            let mut paused_since: Option<Instant> = None;
+
+            let drain = |writer: &mut PcmWriter| -> Result<(), String> {
+                while let Some(buffer) = bridge.dequeue_audio() {
+                    let format = PcmFormat {
+                        sample_rate: buffer.sample_rate.round() as u32,
+                        channels: buffer.channels,
+                    };
+                    if writer.format.is_some_and(|current| current != format) {
+                        println!(
+                            "[AppAudio] Skipping buffer with changed format {:?}",
+                            format
+                        );
+                        continue;
+                    }
+                    writer.write(
+                        &buffer.pcm_data,
+                        format,
+                        buffer.timestamp,
+                        buffer.frame_count,
+                    )?;
+                }
+                Ok(())
+            };
+
+            while !stop_flag.load(Ordering::SeqCst) {
+                if pause_flag.load(Ordering::SeqCst) {
+                    paused_since.get_or_insert_with(Instant::now);
+                    while bridge.dequeue_audio().is_some() {}
+                } else {
+                    if let Some(since) = paused_since.take() {
+                        writer.paused_duration += since.elapsed().as_secs_f64();
+                    }
+                    drain(&mut writer)?;
+                }
+                thread::sleep(DRAIN_INTERVAL);
+            }
+
+            bridge.stop_capture();
+            drain(&mut writer)?;
+            writer
+                .writer
+                .flush()
+                .map_err(|e| format!("Failed to write application audio: {}", e))?;
+
+            Ok(writer.format)
+        });
+
+        Ok(Self {
+            should_stop,
+            paused,
+            handle: Some(handle),
+            pcm_path,
+            mix,
+        })
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    pub fn start(
+        _filter: &AudioAppFilter,
+        _display_id: u32,
+        _pcm_path: PathBuf,
+        _mix: bool,
+    ) -> Result<Self, String> {
+        Err("Per-application audio capture is only supported on macOS".to_string())
+    }
+
+    /// Drop or resume capturing audio while the recording is paused
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+    }
+
+    /// Stop capturing and mux the audio into the finished recording
+    pub fn stop(mut self, recording_path: &Path) -> Result<(), String> {
+        self.should_stop.store(true, Ordering::SeqCst);
+
+        let format = self
+            .handle
+            .take()
+            .ok_or_else(|| "Application audio capture already stopped".to_string())?
+            .join()
+            .map_err(|_| "Application audio capture thread panicked".to_string())?;
+
+        let result = match format {
+            Ok(Some(format)) => self.mux(recording_path, format),
+            Ok(None) => {
+                println!("[AppAudio] No application audio was captured");
+                Ok(())
+            }
+            Err(e) => Err(e),
+        };
+
+        let _ = fs::remove_file(&self.pcm_path);
+        result
+    }
+
+    fn mux(&self, recording_path: &Path, format: PcmFormat) -> Result<(), String> {
+        let ffmpeg_path = find_ffmpeg().ok_or_else(|| "FFmpeg not found".to_string())?;
+
+        let extension = recording_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("mp4")
+            .to_lowercase();
+        let audio_codec = if extension == "webm" {
+            "libopus"
+        } else {
+            "aac"
+        };
+        let muxed_path = recording_path.with_extension(format!("app-audio.{}", extension));
+
+        let mut command = Command::new(&ffmpeg_path);
+        command
+            .arg("-y")
+            .arg("-i")
+            .arg(recording_path)
+            .arg("-f")
+            .arg("s16le")
+            .arg("-ar")
+            .arg(format.sample_rate.to_string())
+            .arg("-ac")
+            .arg(format.channels.to_string())
+            .arg("-i")
+            .arg(&self.pcm_path);
+
+        if self.mix {
+            // The recording's own track sets the length
+            command
+                .arg("-filter_complex")
+                .arg("[0:a][1:a]amix=inputs=2:duration=first:normalize=0[outa]")
+                .arg("-map")
+                .arg("0:v")
+                .arg("-map")
+                .arg("[outa]");
+        } else {
+            // Pad the application audio so the video sets the length
+            command
+                .arg("-map")
+                .arg("0:v")
+                .arg("-map")
+                .arg("1:a")
+                .arg("-af")
+                .arg("apad")
+                .arg("-shortest");
+        }
+
+        let output = command
+            .arg("-c:v")
+            .arg("copy")
+            .arg("-c:a")
+            .arg(audio_codec)
+            .arg(&muxed_path)
+            .watched_output(JobKind::Encode)
+            .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+        if !output.status.success() {
+            let _ = fs::remove_file(&muxed_path);
+            return Err(format!(
+                "Failed to add application audio: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        fs::rename(&muxed_path, recording_path)
+            .map_err(|e| format!("Failed to replace recording: {}", e))?;
+
+        println!(
+            "[AppAudio] Added application audio to {}",
+            recording_path.display()
+        );
+        Ok(())
+    }
+}
+
+impl Drop for AppAudioRecorder {
+    fn drop(&mut self) {
+        self.should_stop.store(true, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_silence_frames() {
+        assert_eq!(silence_frames(1.0, 1.01, 48000), 0);
+        assert_eq!(silence_frames(1.0, 1.5, 48000), 24000);
+        assert_eq!(silence_frames(1.0, 0.9, 48000), 0);
+    }
+
+    #[test]
+    fn test_validate() {
+        let only = AudioAppFilter {
+            mode: AudioAppFilterMode::Only,
+            bundle_ids: vec!["com.google.Chrome".to_string()],
+        };
+        assert!(only.validate().is_ok());
+
+        assert!(AudioAppFilter {
+            bundle_ids: Vec::new(),
+            ..only.clone()
+        }
+        .validate()
+        .is_err());
+
+        assert!(AudioAppFilter {
+            mode: AudioAppFilterMode::Exclude,
+            bundle_ids: Vec::new(),
+        }
+        .validate()
+        .is_ok());
+
+        assert!(AudioAppFilter {
+            bundle_ids: vec![" ".to_string()],
+            ..only
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,184 @@
+// Disk space checks and reservation
+//
+// Before a recording starts its expected size (estimated bitrate × the long
+// recording max duration) is compared with the free space on the recording
+// volume, and the recording is refused if it would leave less than the
+// configured floor. With preallocation on, the expected size is also claimed
+// up front in a `.reserve` file next to the recording. The reserve shrinks as
+// the recording grows, so the two together always hold the full estimate, and
+// it is deleted when the recording stops.
+
+use super::super::storage::volume_stats;
+use super::{LongRecordingConfig, RecordingConfig, RecordingError};
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+
+/// Free and total bytes on the volume holding `path`
+pub fn disk_space(path: &Path) -> Option<(u64, u64)> {
+    volume_stats(path).map(|stats| (stats.available_bytes, stats.total_bytes))
+}
+
+/// Expected size of a recording in bytes, or 0 when it has no maximum duration
+pub fn expected_recording_bytes(config: &RecordingConfig, long: &LongRecordingConfig) -> u64 {
+    let kbps = config.estimated_video_bitrate() as u64 + config.audio_bitrate as u64;
+    kbps * 1000 / 8 * long.max_duration_seconds
+}
+
+/// Whether a recording of `expected` bytes leaves at least `floor` bytes free
+fn check_headroom(available: u64, expected: u64, floor: u64) -> Result<(), RecordingError> {
+    let required = expected.saturating_add(floor);
+    if available < required {
+        return Err(RecordingError::DiskSpaceLow {
+            available,
+            required,
+        });
+    }
+    Ok(())
+}
+
+/// Space claimed for a recording that is still being written
+#[derive(Debug)]
+pub struct DiskReservation {
+    path: PathBuf,
+    file: File,
+    reserved: u64,
+}
+
+impl DiskReservation {
+    /// Check the free space for a recording written to `recording_path` and
+    /// reserve its expected size if the long recording settings ask for it
+    pub fn for_recording(
+        recording_path: &Path,
+        config: &RecordingConfig,
+        long: &LongRecordingConfig,
+    ) -> Result<Option<Self>, String> {
+        let directory = recording_path.parent().unwrap_or(Path::new("/"));
+        let Some((available, _)) = disk_space(directory) else {
+            return Ok(None);
+        };
+
+        let expected = expected_recording_bytes(config, long);
+        check_headroom(available, expected, long.min_free_space_mb * 1_048_576)
+            .map_err(|e| e.user_message())?;
+
+        if !long.preallocate || expected == 0 {
+            return Ok(None);
+        }
+        Self::create(recording_path.with_extension("reserve"), expected).map(Some)
+    }
+
+    fn create(path: PathBuf, bytes: u64) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to create disk reservation: {}", e))?;
+
+        if let Err(e) = preallocate(&file, bytes) {
+            let _ = fs::remove_file(&path);
+            return Err(format!(
+                "Failed to reserve {} MB for the recording: {}",
+                bytes / 1_048_576,
+                e
+            ));
+        }
+
+        println!(
+            "[DiskSpace] Reserved {} MB at {}",
+            bytes / 1_048_576,
+            path.display()
+        );
+        Ok(Self {
+            path,
+            file,
+            reserved: bytes,
+        })
+    }
+
+    /// Give back the space the recording has used so far
+    pub fn release_written(&mut self, written: u64) {
+        let remaining = self.reserved.saturating_sub(written);
+        if let Err(e) = self.file.set_len(remaining) {
+            println!("[DiskSpace] Failed to shrink disk reservation: {}", e);
+        }
+    }
+}
+
+impl Drop for DiskReservation {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Allocate `len` bytes of real disk blocks to a file
+///
+/// `set_len` alone makes a sparse file on APFS, which reserves nothing.
+#[cfg(target_os = "macos")]
+fn preallocate(file: &File, len: u64) -> std::io::Result<()> {
+    use std::os::raw::c_int;
+    use std::os::unix::io::AsRawFd;
+
+    /// fstore_t from <sys/fcntl.h>
+    #[repr(C)]
+    struct FStore {
+        fst_flags: u32,
+        fst_posmode: c_int,
+        fst_offset: i64,
+        fst_length: i64,
+        fst_bytesalloc: i64,
+    }
+
+    const F_PREALLOCATE: c_int = 42;
+    const F_ALLOCATEALL: u32 = 0x4;
+    const F_PEOFPOSMODE: c_int = 3;
+
+    extern "C" {
+        fn fcntl(fd: c_int, cmd: c_int, ...) -> c_int;
+    }
+
+    let mut store = FStore {
+        fst_flags: F_ALLOCATEALL,
+        fst_posmode: F_PEOFPOSMODE,
+        fst_offset: 0,
+        fst_length: len as i64,
+        fst_bytesalloc: 0,
+    };
+    if unsafe { fcntl(file.as_raw_fd(), F_PREALLOCATE, &mut store as *mut FStore) } == -1 {
+        return Err(std::io::Error::last_os_error());
+    }
+    file.set_len(len)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn preallocate(file: &File, len: u64) -> std::io::Result<()> {
+    file.set_len(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_size() {
+        let config = RecordingConfig::default();
+        let mut long = LongRecordingConfig::default();
+        assert_eq!(expected_recording_bytes(&config, &long), 0);
+
+        // (5000 + 128) kbps for an hour
+        long.max_duration_seconds = 3600;
+        assert_eq!(expected_recording_bytes(&config, &long), 2_307_600_000);
+    }
+
+    #[test]
+    fn test_headroom() {
+        assert!(check_headroom(10_000, 4_000, 5_000).is_ok());
+        assert!(matches!(
+            check_headroom(10_000, 6_000, 5_000),
+            Err(RecordingError::DiskSpaceLow {
+                available: 10_000,
+                required: 11_000
+            })
+        ));
+    }
+}
@@ -0,0 +1,186 @@
+// Output filename templates
+//
+// Finished recordings are renamed from their temporary `rec_<millis>` name using
+// a template such as `{date}_{source}_{preset}`. Placeholders:
+//
+//   {date}    2024-05-01 (when the recording started)
+//   {time}    14-03-22
+//   {source}  display_1, window_1234
+//   {preset}  low, medium, high, lossless or custom
+//   {type}    screen, webcam or pip
+//   {id}      rec_1714572202000
+//
+// The extension always follows the output format. When a file with the rendered
+// name already exists, " (2)", " (3)", ... is appended.
+
+use std::path::{Path, PathBuf};
+
+/// Placeholder names a template may use
+pub const PLACEHOLDERS: &[&str] = &["date", "time", "source", "preset", "type", "id"];
+
+/// Longest filename stem produced, in characters
+const MAX_STEM_LEN: usize = 200;
+
+/// Characters that can't appear in a filename on macOS or Windows
+fn is_reserved(c: char) -> bool {
+    matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control()
+}
+
+/// Split a template into literal text and placeholder names
+fn parse(template: &str) -> Result<Vec<(bool, &str)>, String> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+
+    while let Some(open) = rest.find(['{', '}']) {
+        if rest[open..].starts_with('}') {
+            return Err(format!(
+                "Unmatched '}}' in filename template '{}'",
+                template
+            ));
+        }
+        let close = rest[open..]
+            .find('}')
+            .map(|close| open + close)
+            .ok_or_else(|| format!("Unclosed '{{' in filename template '{}'", template))?;
+
+        if open > 0 {
+            parts.push((false, &rest[..open]));
+        }
+        parts.push((true, &rest[open + 1..close]));
+        rest = &rest[close + 1..];
+    }
+
+    if !rest.is_empty() {
+        parts.push((false, rest));
+    }
+    Ok(parts)
+}
+
+/// Check that a template only uses known placeholders and filename-safe text
+pub fn validate(template: &str) -> Result<(), String> {
+    let parts = parse(template)?;
+    if !parts.iter().any(|(is_placeholder, _)| *is_placeholder) {
+        return Err(
+            "Filename template must contain at least one placeholder, e.g. {date}".to_string(),
+        );
+    }
+
+    for (is_placeholder, text) in parts {
+        if is_placeholder {
+            if !PLACEHOLDERS.contains(&text) {
+                return Err(format!(
+                    "Unknown placeholder '{{{}}}'. Use one of: {}",
+                    text,
+                    PLACEHOLDERS
+                        .iter()
+                        .map(|name| format!("{{{}}}", name))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+        } else if let Some(c) = text.chars().find(|&c| is_reserved(c)) {
+            return Err(format!(
+                "Filename template can't contain '{}'",
+                c.escape_default()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Render a template into a filename stem
+///
+/// `values` maps placeholder names to their values; reserved characters in values
+/// are replaced with '-'.
+pub fn render(template: &str, values: &[(&str, &str)]) -> Result<String, String> {
+    validate(template)?;
+
+    let mut stem = String::new();
+    for (is_placeholder, text) in parse(template)? {
+        if is_placeholder {
+            let value = values
+                .iter()
+                .find(|(name, _)| *name == text)
+                .map(|(_, value)| *value)
+                .unwrap_or_default();
+            stem.extend(value.chars().map(|c| if is_reserved(c) { '-' } else { c }));
+        } else {
+            stem.push_str(text);
+        }
+    }
+
+    let stem: String = stem.trim().chars().take(MAX_STEM_LEN).collect();
+    let stem = stem.trim_start_matches('.').trim_end();
+    if stem.is_empty() {
+        return Err(format!(
+            "Filename template '{}' produced an empty name",
+            template
+        ));
+    }
+    Ok(stem.to_string())
+}
+
+/// A path in `dir` named `stem.extension` that doesn't exist yet
+pub fn unique_path(dir: &Path, stem: &str, extension: &str) -> PathBuf {
+    let file_name = |suffix: String| {
+        if extension.is_empty() {
+            format!("{}{}", stem, suffix)
+        } else {
+            format!("{}{}.{}", stem, suffix, extension)
+        }
+    };
+
+    let mut path = dir.join(file_name(String::new()));
+    let mut counter = 2;
+    while path.exists() {
+        path = dir.join(file_name(format!(" ({})", counter)));
+        counter += 1;
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(validate("{date}_{source}_{preset}").is_ok());
+        assert!(validate("Recording {date} at {time}").is_ok());
+        assert!(validate("recording").is_err());
+        assert!(validate("{date}_{resolution}").is_err());
+        assert!(validate("{date").is_err());
+        assert!(validate("date}").is_err());
+        assert!(validate("{date}/{source}").is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        let values = [
+            ("date", "2024-05-01"),
+            ("source", "display_1"),
+            ("preset", "high"),
+        ];
+        assert_eq!(
+            render("{date}_{source}_{preset}", &values).unwrap(),
+            "2024-05-01_display_1_high"
+        );
+        assert_eq!(render("{source}", &[("source", "a/b:c")]).unwrap(), "a-b-c");
+        assert!(render("{id}", &[("id", "")]).is_err());
+    }
+
+    #[test]
+    fn test_unique_path() {
+        let dir =
+            std::env::temp_dir().join(format!("clipforge_template_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let first = unique_path(&dir, "clip", "mp4");
+        assert_eq!(first, dir.join("clip.mp4"));
+        std::fs::write(&first, b"").unwrap();
+        assert_eq!(unique_path(&dir, "clip", "mp4"), dir.join("clip (2).mp4"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
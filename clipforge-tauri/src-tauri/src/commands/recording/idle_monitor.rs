@@ -0,0 +1,164 @@
+// Auto-pause on inactivity
+//
+// When a recording sets `idle_pause_minutes`, the system-wide time since the
+// last keyboard or mouse event is polled. Past the threshold the recording is
+// paused and flagged `paused_for_inactivity`; the first input afterwards
+// resumes it. Both transitions emit the usual "recording:paused" and
+// "recording:resumed" events followed by "recording:idle-paused" and
+// "recording:idle-resumed".
+
+use super::{RecordingManagerState, RecordingState, RecordingStatus};
+use std::time::Duration;
+use tauri::AppHandle;
+use tokio::task::JoinHandle;
+
+/// Seconds between idle time samples
+const POLL_INTERVAL_SECS: u64 = 1;
+
+/// Input within this many seconds counts as activity for resuming
+const ACTIVITY_WINDOW_SECS: f64 = 2.0;
+
+/// What the monitor should do with the current recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IdleAction {
+    Pause,
+    Resume,
+}
+
+impl IdleAction {
+    /// Action for a sample, or None to leave the recording alone
+    fn evaluate(idle_secs: f64, threshold_secs: f64, recording: &RecordingState) -> Option<Self> {
+        match recording.status {
+            RecordingStatus::Recording if idle_secs >= threshold_secs => Some(Self::Pause),
+            // Only undo our own pauses, never one the user asked for
+            RecordingStatus::Paused
+                if recording.paused_for_inactivity && idle_secs < ACTIVITY_WINDOW_SECS =>
+            {
+                Some(Self::Resume)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Seconds since the last keyboard or mouse event anywhere on the system
+#[cfg(target_os = "macos")]
+fn seconds_since_last_input() -> Option<f64> {
+    /// kCGEventSourceStateHIDSystemState
+    const HID_SYSTEM_STATE: i32 = 1;
+    /// kCGAnyInputEventType
+    const ANY_INPUT_EVENT: u32 = u32::MAX;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(state_id: i32, event_type: u32) -> f64;
+    }
+
+    let secs = unsafe { CGEventSourceSecondsSinceLastEventType(HID_SYSTEM_STATE, ANY_INPUT_EVENT) };
+    secs.is_finite().then_some(secs)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn seconds_since_last_input() -> Option<f64> {
+    None
+}
+
+/// Pause and resume the current recording on inactivity until it ends
+pub fn spawn(
+    state: RecordingManagerState,
+    app_handle: AppHandle,
+    threshold: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let threshold_secs = threshold.as_secs_f64();
+        let mut interval = tokio::time::interval(Duration::from_secs(POLL_INTERVAL_SECS));
+
+        loop {
+            interval.tick().await;
+
+            let Some(idle_secs) = seconds_since_last_input() else {
+                println!("[IdleMonitor] Idle time is not available on this platform");
+                break;
+            };
+
+            let Ok(mut manager) = state.lock() else {
+                break;
+            };
+            let Some(mut recording) = manager.get_current_recording() else {
+                break;
+            };
+
+            match IdleAction::evaluate(idle_secs, threshold_secs, &recording) {
+                Some(IdleAction::Pause) => {
+                    println!("[IdleMonitor] No input for {:.0}s, pausing", idle_secs);
+                    if let Err(e) = manager.pause_capture() {
+                        println!("[IdleMonitor] {}", e);
+                        continue;
+                    }
+                    recording.pause();
+                    recording.paused_for_inactivity = true;
+                    manager.set_current_recording(Some(recording));
+                    manager.emit_state_change(&app_handle, "recording:paused");
+                    manager.emit_state_change(&app_handle, "recording:idle-paused");
+                }
+                Some(IdleAction::Resume) => {
+                    println!("[IdleMonitor] Input detected, resuming");
+                    if let Err(e) = manager.resume_capture(recording.config.clone()) {
+                        println!("[IdleMonitor] {}", e);
+                        continue;
+                    }
+                    recording.resume();
+                    manager.set_current_recording(Some(recording));
+                    manager.emit_state_change(&app_handle, "recording:resumed");
+                    manager.emit_state_change(&app_handle, "recording:idle-resumed");
+                }
+                None => {}
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{RecordingConfig, RecordingType};
+    use super::*;
+
+    #[test]
+    fn test_pause_after_threshold() {
+        let mut active = RecordingState::new(
+            "test".to_string(),
+            RecordingType::Screen,
+            RecordingConfig::default(),
+        );
+        active.status = RecordingStatus::Recording;
+        assert_eq!(IdleAction::evaluate(30.0, 300.0, &active), None);
+        assert_eq!(
+            IdleAction::evaluate(300.0, 300.0, &active),
+            Some(IdleAction::Pause)
+        );
+    }
+
+    #[test]
+    fn test_resume_only_own_pause() {
+        let mut idle_paused = RecordingState::new(
+            "test".to_string(),
+            RecordingType::Screen,
+            RecordingConfig::default(),
+        );
+        idle_paused.status = RecordingStatus::Paused;
+        idle_paused.paused_for_inactivity = true;
+        assert_eq!(
+            IdleAction::evaluate(0.5, 300.0, &idle_paused),
+            Some(IdleAction::Resume)
+        );
+        assert_eq!(IdleAction::evaluate(400.0, 300.0, &idle_paused), None);
+
+        let mut user_paused = RecordingState::new(
+            "test".to_string(),
+            RecordingType::Screen,
+            RecordingConfig::default(),
+        );
+        user_paused.status = RecordingStatus::Paused;
+        assert_eq!(IdleAction::evaluate(0.5, 300.0, &user_paused), None);
+    }
+}
@@ -0,0 +1,251 @@
+// Recording integrity checks
+//
+// A crash or a full disk can leave a recording without its `moov` atom (MP4/MOV)
+// or cut off mid-packet. `verify_recording` decodes every stream with FFmpeg into
+// the null muxer, sorts the errors it reports into issues, and when asked remuxes
+// whatever is still readable into a `<name>_repaired` file next to the original.
+
+use super::super::ffmpeg_utils::find_ffmpeg;
+use super::super::process_runner::{JobKind, WatchedOutput};
+use super::filename_template::unique_path;
+use crate::error::ClipForgeError;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+/// Most FFmpeg error lines kept in a report
+const MAX_ERROR_LINES: usize = 20;
+
+/// A problem found while decoding a recording
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IntegrityIssue {
+    /// MP4/MOV index was never written; the file can't be opened
+    MissingMoov,
+    /// The file ends in the middle of a packet
+    Truncated,
+    /// Packets or frames failed to decode
+    Corrupt,
+    /// FFmpeg couldn't open the file at all
+    Unreadable,
+}
+
+/// Result of re-muxing a damaged recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairResult {
+    pub path: String,
+    /// Issues still present in the repaired file
+    pub issues: Vec<IntegrityIssue>,
+}
+
+/// Outcome of verifying a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub path: String,
+    pub issues: Vec<IntegrityIssue>,
+    /// First error lines reported by FFmpeg
+    pub errors: Vec<String>,
+    /// Repaired copy, if a repair was attempted and produced a file
+    pub repaired: Option<RepairResult>,
+    /// Why the repair failed, if it did
+    pub repair_error: Option<String>,
+}
+
+impl IntegrityReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Sort FFmpeg error output into issues
+fn classify_errors(stderr: &str) -> Vec<IntegrityIssue> {
+    let mut issues = Vec::new();
+    let mut add = |issue| {
+        if !issues.contains(&issue) {
+            issues.push(issue);
+        }
+    };
+
+    for line in stderr.lines().map(str::to_lowercase) {
+        if line.contains("moov atom not found") {
+            add(IntegrityIssue::MissingMoov);
+        } else if line.contains("invalid data found when processing input")
+            || line.contains("ebml header parsing failed")
+        {
+            add(IntegrityIssue::Unreadable);
+        } else if line.contains("partial file")
+            || line.contains("truncat")
+            || line.contains("unexpected end of file")
+        {
+            add(IntegrityIssue::Truncated);
+        } else if line.contains("corrupt")
+            || line.contains("error while decoding")
+            || line.contains("concealing")
+            || line.contains("invalid nal unit")
+            || line.contains("missing picture")
+        {
+            add(IntegrityIssue::Corrupt);
+        }
+    }
+
+    // A file FFmpeg can't open has nothing else worth reporting
+    if issues.contains(&IntegrityIssue::MissingMoov) {
+        issues.retain(|issue| *issue == IntegrityIssue::MissingMoov);
+    }
+    issues
+}
+
+/// Decode every stream of a file and collect the errors
+fn decode_check(
+    ffmpeg_path: &Path,
+    path: &str,
+) -> Result<(Vec<IntegrityIssue>, Vec<String>), String> {
+    let output = Command::new(ffmpeg_path)
+        .arg("-v")
+        .arg("error")
+        .arg("-i")
+        .arg(path)
+        .arg("-map")
+        .arg("0")
+        .arg("-f")
+        .arg("null")
+        .arg("-")
+        .watched_output(JobKind::Encode)
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let mut issues = classify_errors(&stderr);
+    if issues.is_empty() && !output.status.success() {
+        issues.push(IntegrityIssue::Unreadable);
+    }
+
+    let errors = stderr
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(MAX_ERROR_LINES)
+        .map(str::to_string)
+        .collect();
+    Ok((issues, errors))
+}
+
+/// Copy every readable packet into a new file next to the original
+fn remux(ffmpeg_path: &Path, path: &Path) -> Result<String, String> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("recording");
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("mp4");
+    let output_path = unique_path(dir, &format!("{}_repaired", stem), extension);
+
+    let mut command = Command::new(ffmpeg_path);
+    command
+        .arg("-v")
+        .arg("error")
+        .arg("-err_detect")
+        .arg("ignore_err")
+        .arg("-fflags")
+        .arg("+genpts+discardcorrupt")
+        .arg("-i")
+        .arg(path)
+        .arg("-map")
+        .arg("0")
+        .arg("-c")
+        .arg("copy");
+    if matches!(extension, "mp4" | "mov" | "m4v") {
+        command.arg("-movflags").arg("+faststart");
+    }
+
+    let output = command
+        .arg("-y")
+        .arg(&output_path)
+        .watched_output(JobKind::Encode)
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&output_path);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Remux failed: {}", stderr.trim()));
+    }
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+/// Check a recording for corruption, a missing index or truncation
+///
+/// With `repair` (the default), damaged files are remuxed into a `_repaired` copy,
+/// which is verified in turn. Files without a `moov` atom can't be opened, so they
+/// can't be repaired this way.
+#[tauri::command]
+pub async fn verify_recording(
+    path: String,
+    repair: Option<bool>,
+) -> Result<IntegrityReport, ClipForgeError> {
+    if !Path::new(&path).exists() {
+        return Err(ClipForgeError::NotFound(format!(
+            "File not found: {}",
+            path
+        )));
+    }
+
+    let ffmpeg_path = find_ffmpeg().ok_or_else(ClipForgeError::ffmpeg_missing)?;
+    let (issues, errors) = decode_check(&ffmpeg_path, &path)?;
+
+    let mut report = IntegrityReport {
+        path,
+        issues,
+        errors,
+        repaired: None,
+        repair_error: None,
+    };
+
+    if report.is_ok() || !repair.unwrap_or(true) {
+        return Ok(report);
+    }
+
+    if report.issues.contains(&IntegrityIssue::MissingMoov) {
+        report.repair_error =
+            Some("The file has no moov atom, so FFmpeg can't read it to remux".to_string());
+        return Ok(report);
+    }
+
+    println!(
+        "[Integrity] {} has {:?}; attempting repair",
+        report.path, report.issues
+    );
+    match remux(&ffmpeg_path, Path::new(&report.path)) {
+        Ok(repaired_path) => {
+            let (issues, _) = decode_check(&ffmpeg_path, &repaired_path)?;
+            report.repaired = Some(RepairResult {
+                path: repaired_path,
+                issues,
+            });
+        }
+        Err(e) => report.repair_error = Some(e),
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_errors() {
+        assert!(classify_errors("").is_empty());
+        assert_eq!(
+            classify_errors(
+                "[mov,mp4,m4a,3gp,3g2,mj2 @ 0x1] moov atom not found\nrec.mp4: Invalid data found when processing input"
+            ),
+            vec![IntegrityIssue::MissingMoov]
+        );
+        assert_eq!(
+            classify_errors(
+                "[mov,mp4,m4a,3gp,3g2,mj2 @ 0x1] stream 0, offset 0x3f2a: partial file\n[h264 @ 0x2] error while decoding MB 12 4, bytestream -5\n[h264 @ 0x2] concealing 120 DC, 120 AC, 120 MV errors in P frame"
+            ),
+            vec![IntegrityIssue::Truncated, IntegrityIssue::Corrupt]
+        );
+    }
+}
@@ -0,0 +1,117 @@
+// Recording session manifest
+//
+// Sidecar file (`<name>.session.json`) written next to each finished
+// recording with the markers captured while it was running
+
+use super::summary::RecordingSummary;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A labelled point in a recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordingMarker {
+    /// Seconds since the recording started, excluding pauses
+    pub time: f64,
+    pub label: String,
+}
+
+/// Session manifest stored alongside a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionManifest {
+    pub recording_id: String,
+    pub recording_path: String,
+    /// Final duration (seconds)
+    pub duration: f64,
+    pub markers: Vec<RecordingMarker>,
+    /// Encoding statistics, for recordings made since they were added
+    #[serde(default)]
+    pub summary: Option<RecordingSummary>,
+}
+
+impl SessionManifest {
+    /// Manifest path for a recording file
+    pub fn path_for(recording_path: &Path) -> PathBuf {
+        recording_path.with_extension("session.json")
+    }
+
+    /// Load the manifest next to a recording
+    pub fn load(recording_path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(Self::path_for(recording_path))
+            .map_err(|e| format!("Failed to read session manifest: {}", e))?;
+
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse session manifest: {}", e))
+    }
+
+    /// Write the manifest next to its recording
+    pub fn save(&self) -> Result<PathBuf, String> {
+        let path = Self::path_for(Path::new(&self.recording_path));
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize session manifest: {}", e))?;
+
+        fs::write(&path, content)
+            .map_err(|e| format!("Failed to write session manifest: {}", e))?;
+        Ok(path)
+    }
+}
+
+/// A chapter in an exported video, in output seconds
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    pub start: f64,
+    pub end: f64,
+    pub title: String,
+}
+
+fn escape_ffmetadata(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Render chapters as an FFmpeg metadata file (used with `-map_chapters`)
+pub fn ffmetadata_chapters(chapters: &[Chapter]) -> String {
+    let mut content = String::from(";FFMETADATA1\n");
+
+    for chapter in chapters {
+        content.push_str(&format!(
+            "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            (chapter.start * 1000.0).round() as u64,
+            (chapter.end * 1000.0).round() as u64,
+            escape_ffmetadata(&chapter.title)
+        ));
+    }
+
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_path() {
+        let path = SessionManifest::path_for(Path::new("/tmp/recording_1.mp4"));
+        assert_eq!(path, PathBuf::from("/tmp/recording_1.session.json"));
+    }
+
+    #[test]
+    fn test_ffmetadata_chapters() {
+        let chapters = vec![Chapter {
+            start: 1.5,
+            end: 10.0,
+            title: "Intro; a=b".to_string(),
+        }];
+
+        assert_eq!(
+            ffmetadata_chapters(&chapters),
+            ";FFMETADATA1\n\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=1500\nEND=10000\ntitle=Intro\\; a\\=b\n"
+        );
+    }
+}
@@ -0,0 +1,301 @@
+// Memory monitoring for long recordings
+//
+// While recording, samples the resident memory of the app and of the FFmpeg
+// encoder, plus the preview frame queue. Past the thresholds in
+// LongRecordingConfig it emits "recording:memory-warning", sheds preview work
+// (halving the preview frame rate and flushing its queue) and, when the encoder
+// itself has grown too large, rolls the recording over to a new chunk. Rollovers
+// are at least MIN_ROLLOVER_INTERVAL apart, and stop for the rest of the
+// recording once a freshly started encoder is already over the limit.
+
+use super::{LongRecordingConfig, RecordingManagerState};
+use crate::commands::preview::{SharedPreviewCaptureSession, SharedPreviewState};
+use serde::Serialize;
+use std::process::Command;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::task::JoinHandle;
+
+/// Seconds between samples
+const SAMPLE_INTERVAL_SECS: u64 = 5;
+
+/// Preview frames waiting in the queue before the preview is throttled
+const PREVIEW_QUEUE_LIMIT: usize = 30;
+
+/// Slowest frame rate the preview is throttled to
+const MIN_PREVIEW_FPS: u32 = 5;
+
+/// Shortest time between two memory rollovers
+const MIN_ROLLOVER_INTERVAL: Duration = Duration::from_secs(120);
+
+/// What the monitor did about a threshold being crossed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MemoryAction {
+    /// Halved the preview frame rate and dropped its queued frames
+    ShedPreview,
+    /// Restarted the encoder on a new chunk
+    RolloverChunk,
+}
+
+/// Payload of "recording:memory-warning"
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryWarning {
+    pub app_rss_mb: u64,
+    pub encoder_rss_mb: Option<u64>,
+    pub preview_queue_size: usize,
+    pub actions: Vec<MemoryAction>,
+}
+
+impl MemoryWarning {
+    /// Warning for a sample, or None when everything is under its threshold
+    fn evaluate(
+        app_rss_mb: u64,
+        encoder_rss_mb: Option<u64>,
+        preview_queue_size: usize,
+        config: &LongRecordingConfig,
+    ) -> Option<Self> {
+        let mut actions = Vec::new();
+
+        if app_rss_mb > config.memory_warning_mb || preview_queue_size > PREVIEW_QUEUE_LIMIT {
+            actions.push(MemoryAction::ShedPreview);
+        }
+        if encoder_rss_mb.is_some_and(|rss| rss > config.encoder_memory_limit_mb) {
+            actions.push(MemoryAction::RolloverChunk);
+        }
+
+        if actions.is_empty() {
+            return None;
+        }
+        Some(Self {
+            app_rss_mb,
+            encoder_rss_mb,
+            preview_queue_size,
+            actions,
+        })
+    }
+}
+
+/// Decides whether an oversized encoder is restarted
+#[derive(Debug, Default)]
+struct RolloverGuard {
+    last_rollover: Option<Instant>,
+    /// The encoder started by the last rollover hasn't been sampled yet
+    fresh_encoder: bool,
+    /// A fresh encoder was already over the limit, so restarting doesn't help
+    futile: bool,
+}
+
+impl RolloverGuard {
+    /// Whether to roll over after a sample, given if the encoder is over its limit
+    fn should_roll_over(&mut self, over_limit: bool, now: Instant) -> bool {
+        let fresh = std::mem::take(&mut self.fresh_encoder);
+        if !over_limit || self.futile {
+            return false;
+        }
+        if fresh {
+            println!(
+                "[MemoryMonitor] New encoder is already over the limit; not rolling over again"
+            );
+            self.futile = true;
+            return false;
+        }
+        if self
+            .last_rollover
+            .is_some_and(|last| now.duration_since(last) < MIN_ROLLOVER_INTERVAL)
+        {
+            return false;
+        }
+
+        self.last_rollover = Some(now);
+        self.fresh_encoder = true;
+        true
+    }
+}
+
+/// Resident memory of a process in MB
+fn resident_mb(pid: u32) -> Option<u64> {
+    let output = Command::new("ps")
+        .arg("-o")
+        .arg("rss=")
+        .arg("-p")
+        .arg(pid.to_string())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    // ps reports kilobytes
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(|kb| kb / 1024)
+}
+
+/// Halve the preview frame rate and drop the frames it hasn't sent yet
+fn shed_preview(app_handle: &AppHandle) {
+    if let Some(preview_state) = app_handle.try_state::<SharedPreviewState>() {
+        if let Ok(mut preview) = preview_state.lock() {
+            let fps = (preview.settings.target_fps / 2).max(MIN_PREVIEW_FPS);
+            if preview.is_active && fps < preview.settings.target_fps {
+                println!("[MemoryMonitor] Preview throttled to {} fps", fps);
+                preview.update_target_fps(fps);
+            }
+        }
+    }
+
+    if let Some(session) = app_handle.try_state::<SharedPreviewCaptureSession>() {
+        if let Ok(session) = session.lock() {
+            if let Some(bridge) = &session.bridge {
+                bridge.clear_jpeg_frames();
+            }
+        }
+    }
+}
+
+/// Continue the recording in a new chunk so the encoder starts fresh
+fn rollover_chunk(state: &RecordingManagerState) {
+    let Ok(mut manager) = state.lock() else {
+        return;
+    };
+    let Some(recording) = manager.get_current_recording() else {
+        return;
+    };
+    if let Err(e) = manager.start_next_chunk(recording.config) {
+        println!("[MemoryMonitor] {}", e);
+    }
+}
+
+/// Sample memory until the recording ends
+pub fn spawn(
+    state: RecordingManagerState,
+    app_handle: AppHandle,
+    config: LongRecordingConfig,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(SAMPLE_INTERVAL_SECS));
+        // The first tick completes immediately
+        interval.tick().await;
+        let mut rollover_guard = RolloverGuard::default();
+
+        loop {
+            interval.tick().await;
+
+            let encoder_pid = {
+                let Ok(mut manager) = state.lock() else {
+                    break;
+                };
+                if manager.get_current_recording().is_none() {
+                    break;
+                }
+                manager
+                    .get_capture_session_mut()
+                    .and_then(|session| session.process_id())
+            };
+
+            let sample = tokio::task::spawn_blocking(move || {
+                (
+                    resident_mb(std::process::id()),
+                    encoder_pid.and_then(resident_mb),
+                )
+            })
+            .await;
+            let Ok((app_rss_mb, encoder_rss_mb)) = sample else {
+                continue;
+            };
+
+            let preview_queue_size = app_handle
+                .try_state::<SharedPreviewState>()
+                .and_then(|preview| {
+                    let preview = preview.lock().ok()?;
+                    preview.is_active.then_some(preview.metrics.queue_size)
+                })
+                .unwrap_or(0);
+
+            let Some(mut warning) = MemoryWarning::evaluate(
+                app_rss_mb.unwrap_or(0),
+                encoder_rss_mb,
+                preview_queue_size,
+                &config,
+            ) else {
+                rollover_guard.should_roll_over(false, Instant::now());
+                continue;
+            };
+
+            let over_limit = warning.actions.contains(&MemoryAction::RolloverChunk);
+            if !rollover_guard.should_roll_over(over_limit, Instant::now()) {
+                warning
+                    .actions
+                    .retain(|action| *action != MemoryAction::RolloverChunk);
+            }
+            if warning.actions.is_empty() {
+                continue;
+            }
+
+            println!(
+                "[MemoryMonitor] app {} MB, encoder {:?} MB, preview queue {}: {:?}",
+                warning.app_rss_mb,
+                warning.encoder_rss_mb,
+                warning.preview_queue_size,
+                warning.actions
+            );
+
+            for action in &warning.actions {
+                match action {
+                    MemoryAction::ShedPreview => shed_preview(&app_handle),
+                    MemoryAction::RolloverChunk => rollover_chunk(&state),
+                }
+            }
+
+            let _ = app_handle.emit("recording:memory-warning", &warning);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_under_thresholds() {
+        let config = LongRecordingConfig::default();
+        assert_eq!(MemoryWarning::evaluate(200, Some(100), 2, &config), None);
+        assert_eq!(MemoryWarning::evaluate(200, None, 2, &config), None);
+    }
+
+    #[test]
+    fn test_actions() {
+        let config = LongRecordingConfig::default();
+
+        let warning = MemoryWarning::evaluate(config.memory_warning_mb + 1, None, 0, &config);
+        assert_eq!(warning.unwrap().actions, vec![MemoryAction::ShedPreview]);
+
+        let warning = MemoryWarning::evaluate(100, None, PREVIEW_QUEUE_LIMIT + 1, &config);
+        assert_eq!(warning.unwrap().actions, vec![MemoryAction::ShedPreview]);
+
+        let warning =
+            MemoryWarning::evaluate(100, Some(config.encoder_memory_limit_mb + 1), 0, &config);
+        assert_eq!(warning.unwrap().actions, vec![MemoryAction::RolloverChunk]);
+    }
+
+    #[test]
+    fn test_rollover_guard() {
+        let start = Instant::now();
+        let mut guard = RolloverGuard::default();
+        assert!(!guard.should_roll_over(false, start));
+        assert!(guard.should_roll_over(true, start));
+
+        // The new encoder sampled under the limit, but the last chunk is too short
+        assert!(!guard.should_roll_over(false, start + Duration::from_secs(5)));
+        assert!(!guard.should_roll_over(true, start + Duration::from_secs(60)));
+        assert!(guard.should_roll_over(true, start + MIN_ROLLOVER_INTERVAL));
+
+        // A fresh encoder over the limit ends rollovers for the recording
+        let later = start + MIN_ROLLOVER_INTERVAL * 3;
+        assert!(!guard.should_roll_over(true, later));
+        assert!(!guard.should_roll_over(true, later + MIN_ROLLOVER_INTERVAL));
+    }
+}
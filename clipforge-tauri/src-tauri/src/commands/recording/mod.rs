@@ -1,13 +1,47 @@
+use super::audio_filters::AudioFilterSettings;
+use super::camera_sources::{resolve_camera, CameraCaptureSettings, CameraDevice};
+use super::cursor::{CaptureRegion, CursorRecorder, CursorTrack};
+use super::ffmpeg_args;
 use super::permissions::{PermissionHandler, PlatformPermissions};
+use super::pip::{self, PipComposition, PipLayout};
+use super::process_runner::{JobKind, WatchedOutput};
+use super::quick_edit::{check_concat_compatible, concat_copy};
+use super::recents::{add_recent, RecentKind};
+use super::settings::SettingsState;
+use super::stingers::{JoinedStingers, StingerSettings};
+use super::video_import::import_file;
+use super::webhooks::{self, WebhookEvent, WebhookPayload};
+use crate::error::ClipForgeError;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use tauri::{AppHandle, Emitter, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 use tokio::task::JoinHandle;
 
+mod app_audio;
+mod disk_space;
+pub mod filename_template;
+mod idle_monitor;
+pub mod integrity;
+mod manifest;
+mod memory_monitor;
+pub mod replay_buffer;
 mod screen_capture;
+mod summary;
+mod temp_files;
+pub mod timestamp_overlay;
+mod trim;
+use app_audio::AppAudioRecorder;
+pub use app_audio::AudioAppFilter;
+use disk_space::DiskReservation;
+pub use manifest::{ffmetadata_chapters, Chapter, RecordingMarker, SessionManifest};
 use screen_capture::ScreenCaptureSession;
+pub use summary::RecordingSummary;
+pub use temp_files::TempFileManager;
+use timestamp_overlay::TimestampOverlay;
+pub use trim::RecordingTrim;
+use trim::TrimmedRange;
 
 // ============================================================================
 // Data Structures
@@ -56,6 +90,65 @@ pub struct RecordingConfig {
     pub audio_codec: String,
     /// Output format (e.g., "mp4", "webm")
     pub output_format: String,
+    /// Whether the system cursor is drawn into the captured frames
+    #[serde(default = "default_true")]
+    pub capture_cursor: bool,
+    /// Record the cursor position to a sidecar track for synthetic cursor export
+    #[serde(default)]
+    pub record_cursor: bool,
+    /// Audio post filters applied while recording
+    #[serde(default)]
+    pub audio_filters: AudioFilterSettings,
+    /// Import the finished recording and emit "timeline:add-clip" when it stops
+    #[serde(default)]
+    pub add_to_timeline: bool,
+    /// Camera resolution and frame rate for webcam and PiP recordings
+    #[serde(default)]
+    pub camera: Option<CameraCaptureSettings>,
+    /// Live stream target (rtmp://, rtmps:// or srt://); the local file is still recorded
+    #[serde(default)]
+    pub stream_url: Option<String>,
+    /// Archival-quality encoding; replaces the video bitrate and codec settings
+    #[serde(default)]
+    pub lossless: Option<LosslessMode>,
+    /// Color space the recording is encoded and tagged in
+    #[serde(default)]
+    pub color_mode: ColorMode,
+    /// Name for the finished file, e.g. "{date}_{source}_{preset}" (see filename_template.rs)
+    #[serde(default)]
+    pub filename_template: Option<String>,
+    /// Global shortcut that mutes or unmutes the microphone while recording
+    #[serde(default)]
+    pub mic_hotkey: Option<MicHotkey>,
+    /// Pause after this many minutes without keyboard or mouse input, resuming on activity
+    #[serde(default)]
+    pub idle_pause_minutes: Option<u32>,
+    /// Wall-clock or elapsed time burned into the video
+    #[serde(default)]
+    pub timestamp_overlay: Option<TimestampOverlay>,
+    /// Seconds cut from the start and end of the finished file
+    #[serde(default)]
+    pub trim: Option<RecordingTrim>,
+    /// Intro and outro joined onto the finished file
+    #[serde(default)]
+    pub stingers: Option<StingerSettings>,
+    /// Encoder tuning flags appended to the encode, e.g. ["-tune", "animation"]
+    #[serde(default)]
+    pub extra_ffmpeg_args: Vec<String>,
+}
+
+/// Global shortcut for the microphone during a recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MicHotkey {
+    /// Shortcut, e.g. "CmdOrCtrl+Shift+M"
+    pub shortcut: String,
+    /// Muted unless the shortcut is held; otherwise each press toggles mute
+    #[serde(default)]
+    pub push_to_talk: bool,
+}
+
+pub(crate) fn default_true() -> bool {
+    true
 }
 
 impl Default for RecordingConfig {
@@ -71,6 +164,21 @@ impl Default for RecordingConfig {
             audio_bitrate: 128,
             audio_codec: "aac".to_string(),
             output_format: "mp4".to_string(),
+            capture_cursor: true,
+            record_cursor: false,
+            audio_filters: AudioFilterSettings::default(),
+            add_to_timeline: false,
+            camera: None,
+            stream_url: None,
+            lossless: None,
+            color_mode: ColorMode::Sdr,
+            filename_template: None,
+            mic_hotkey: None,
+            idle_pause_minutes: None,
+            timestamp_overlay: None,
+            trim: None,
+            stingers: None,
+            extra_ffmpeg_args: Vec::new(),
         }
     }
 }
@@ -119,8 +227,61 @@ impl RecordingConfig {
             return Err("Audio sample rate must be between 8000 and 192000 Hz".to_string());
         }
 
-        // Check codec compatibility
-        self.validate_codec_compatibility()?;
+        if self.idle_pause_minutes == Some(0) {
+            return Err("Idle pause must be at least 1 minute".to_string());
+        }
+
+        if let Some(overlay) = &self.timestamp_overlay {
+            overlay.validate()?;
+        }
+
+        if let Some(trim) = &self.trim {
+            trim.validate()?;
+        }
+
+        if let Some(stingers) = &self.stingers {
+            stingers.validate()?;
+        }
+
+        ffmpeg_args::validate_extra_args(&self.extra_ffmpeg_args)?;
+
+        // Check codec compatibility (lossless modes pick their own codec)
+        if self.lossless.is_none() {
+            self.validate_codec_compatibility()?;
+        }
+
+        // Lossless codecs only fit some containers
+        if let Some(mode) = self.lossless {
+            if !mode.containers().contains(&self.output_format.as_str()) {
+                return Err(format!(
+                    "{} recordings must use {} output",
+                    mode.display_name(),
+                    mode.containers().join(" or ")
+                ));
+            }
+        }
+
+        // PQ needs a 10-bit codec
+        if self.color_mode == ColorMode::Pq
+            && self.lossless.is_none()
+            && !matches!(self.video_codec.as_str(), "h265" | "hevc")
+        {
+            return Err("PQ recordings must use the h265 codec or a lossless mode".to_string());
+        }
+
+        if let Some(template) = &self.filename_template {
+            filename_template::validate(template)?;
+        }
+
+        // Check the stream target, if any
+        if let Some(url) = &self.stream_url {
+            if stream_muxer(url).is_none() {
+                return Err(format!(
+                    "Unsupported stream URL '{}'. Use rtmp://, rtmps://, or srt://.",
+                    url
+                ));
+            }
+        }
 
         Ok(())
     }
@@ -178,10 +339,12 @@ impl RecordingConfig {
                 // MOV (QuickTime) is similar to MP4
                 match self.video_codec.as_str() {
                     "h264" | "h265" | "hevc" | "prores" => {}
-                    _ => return Err(format!(
+                    _ => {
+                        return Err(format!(
                         "MOV format does not support '{}' video codec. Use h264, h265, or prores.",
                         self.video_codec
-                    )),
+                    ))
+                    }
                 }
             }
             _ => {
@@ -195,6 +358,61 @@ impl RecordingConfig {
         Ok(())
     }
 
+    /// Expected video bitrate (kbps), estimated from the frame size for lossless modes
+    pub fn estimated_video_bitrate(&self) -> u32 {
+        match self.lossless {
+            Some(mode) => {
+                let pixels_per_second =
+                    self.width as f64 * self.height as f64 * self.frame_rate as f64;
+                (pixels_per_second * mode.bits_per_pixel() / 1000.0) as u32
+            }
+            None => self.video_bitrate,
+        }
+    }
+
+    /// FFmpeg output arguments that re-encode a finished recording with its own
+    /// codec, quality, color tags and audio settings
+    pub fn file_encoder_args(&self) -> Vec<String> {
+        let mut args: Vec<String> = Vec::new();
+        match self.lossless {
+            Some(mode) => {
+                args.extend(["-c:v", mode.video_codec()].map(String::from));
+                args.extend(mode.encoder_args().iter().map(|arg| arg.to_string()));
+            }
+            None => {
+                args.extend(["-c:v".to_string(), self.video_codec.clone()]);
+                args.extend(["-b:v".to_string(), format!("{}k", self.video_bitrate)]);
+                if matches!(self.video_codec.as_str(), "h264" | "libx264") {
+                    args.extend(
+                        ["-preset", "medium", "-profile:v", "high", "-crf", "18"].map(String::from),
+                    );
+                }
+            }
+        }
+
+        args.extend(self.color_mode.tag_args().map(String::from));
+        if let Some(pix_fmt) = self.color_mode.pixel_format(self.lossless.is_some()) {
+            args.extend(["-pix_fmt", pix_fmt].map(String::from));
+        }
+        args.extend(self.extra_ffmpeg_args.iter().cloned());
+        if self.color_mode == ColorMode::Pq
+            && self.lossless.is_none()
+            && matches!(self.output_format.as_str(), "mp4" | "mov")
+        {
+            args.extend(["-tag:v", "hvc1"].map(String::from));
+        }
+
+        if !self.audio_codec.is_empty() {
+            args.extend([
+                "-c:a".to_string(),
+                self.audio_codec.clone(),
+                "-b:a".to_string(),
+                format!("{}k", self.audio_bitrate),
+            ]);
+        }
+        args
+    }
+
     /// Apply platform-specific adjustments
     #[cfg(target_os = "macos")]
     #[allow(dead_code)]
@@ -218,6 +436,16 @@ impl RecordingConfig {
     }
 }
 
+/// FFmpeg muxer used for a live stream URL, or None if the scheme is unsupported
+pub fn stream_muxer(url: &str) -> Option<&'static str> {
+    let scheme = url.split_once("://")?.0.to_ascii_lowercase();
+    match scheme.as_str() {
+        "rtmp" | "rtmps" => Some("flv"),
+        "srt" => Some("mpegts"),
+        _ => None,
+    }
+}
+
 /// Builder for RecordingConfig
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -289,6 +517,81 @@ impl RecordingConfigBuilder {
         self
     }
 
+    pub fn capture_cursor(mut self, capture: bool) -> Self {
+        self.config.capture_cursor = capture;
+        self
+    }
+
+    pub fn record_cursor(mut self, record: bool) -> Self {
+        self.config.record_cursor = record;
+        self
+    }
+
+    pub fn audio_filters(mut self, filters: AudioFilterSettings) -> Self {
+        self.config.audio_filters = filters;
+        self
+    }
+
+    pub fn add_to_timeline(mut self, add: bool) -> Self {
+        self.config.add_to_timeline = add;
+        self
+    }
+
+    pub fn camera(mut self, camera: CameraCaptureSettings) -> Self {
+        self.config.camera = Some(camera);
+        self
+    }
+
+    pub fn stream_url(mut self, url: impl Into<String>) -> Self {
+        self.config.stream_url = Some(url.into());
+        self
+    }
+
+    pub fn lossless(mut self, mode: LosslessMode) -> Self {
+        self.config.lossless = Some(mode);
+        self
+    }
+
+    pub fn color_mode(mut self, mode: ColorMode) -> Self {
+        self.config.color_mode = mode;
+        self
+    }
+
+    pub fn filename_template(mut self, template: impl Into<String>) -> Self {
+        self.config.filename_template = Some(template.into());
+        self
+    }
+
+    pub fn mic_hotkey(mut self, hotkey: MicHotkey) -> Self {
+        self.config.mic_hotkey = Some(hotkey);
+        self
+    }
+
+    pub fn idle_pause_minutes(mut self, minutes: u32) -> Self {
+        self.config.idle_pause_minutes = Some(minutes);
+        self
+    }
+
+    pub fn timestamp_overlay(mut self, overlay: TimestampOverlay) -> Self {
+        self.config.timestamp_overlay = Some(overlay);
+        self
+    }
+
+    pub fn trim(mut self, trim: RecordingTrim) -> Self {
+        self.config.trim = Some(trim);
+        self
+    }
+
+    pub fn stingers(mut self, stingers: StingerSettings) -> Self {
+        self.config.stingers = Some(stingers);
+        self
+    }
+
+    pub fn extra_ffmpeg_args(mut self, args: Vec<String>) -> Self {
+        self.config.extra_ffmpeg_args = args;
+        self
+    }
+
     pub fn preset(mut self, preset: QualityPreset) -> Self {
         self.config = preset.to_config();
         self
@@ -311,12 +614,14 @@ impl Default for RecordingConfigBuilder {
 }
 
 /// Quality presets for easy configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum QualityPreset {
     Low,
     Medium,
     High,
+    /// Lossless FFV1 in MKV for footage that will be heavily edited
+    Lossless,
     Custom,
 }
 
@@ -345,9 +650,199 @@ impl QualityPreset {
                 video_bitrate: 10000,
                 ..Default::default()
             },
+            QualityPreset::Lossless => RecordingConfig {
+                width: 2560,
+                height: 1440,
+                frame_rate: 60,
+                video_codec: LosslessMode::Ffv1.video_codec().to_string(),
+                audio_codec: "flac".to_string(),
+                output_format: "mkv".to_string(),
+                lossless: Some(LosslessMode::Ffv1),
+                ..Default::default()
+            },
             QualityPreset::Custom => RecordingConfig::default(),
         }
     }
+
+    /// The preset a configuration matches, or Custom if its settings were changed
+    pub fn matching(config: &RecordingConfig) -> QualityPreset {
+        [
+            QualityPreset::Low,
+            QualityPreset::Medium,
+            QualityPreset::High,
+            QualityPreset::Lossless,
+        ]
+        .into_iter()
+        .find(|preset| {
+            let preset_config = preset.to_config();
+            preset_config.width == config.width
+                && preset_config.height == config.height
+                && preset_config.frame_rate == config.frame_rate
+                && preset_config.video_bitrate == config.video_bitrate
+                && preset_config.video_codec == config.video_codec
+                && preset_config.lossless == config.lossless
+        })
+        .unwrap_or(QualityPreset::Custom)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            QualityPreset::Low => "low",
+            QualityPreset::Medium => "medium",
+            QualityPreset::High => "high",
+            QualityPreset::Lossless => "lossless",
+            QualityPreset::Custom => "custom",
+        }
+    }
+}
+
+/// Lossless or visually lossless encoders for archival-grade recordings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LosslessMode {
+    /// H.264 at CRF 0 with full chroma; lossless and the smallest of the three
+    H264,
+    /// Apple ProRes 4444; visually lossless and the fastest to edit
+    ProRes,
+    /// FFV1 level 3; lossless archival codec with per-slice checksums
+    Ffv1,
+}
+
+impl LosslessMode {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            LosslessMode::H264 => "Lossless H.264",
+            LosslessMode::ProRes => "ProRes 4444",
+            LosslessMode::Ffv1 => "FFV1",
+        }
+    }
+
+    /// FFmpeg encoder name
+    pub fn video_codec(&self) -> &'static str {
+        match self {
+            LosslessMode::H264 => "libx264",
+            LosslessMode::ProRes => "prores_ks",
+            LosslessMode::Ffv1 => "ffv1",
+        }
+    }
+
+    /// Output formats that can hold this codec
+    pub fn containers(&self) -> &'static [&'static str] {
+        match self {
+            LosslessMode::H264 => &["mkv", "mov"],
+            LosslessMode::ProRes => &["mov", "mkv"],
+            LosslessMode::Ffv1 => &["mkv"],
+        }
+    }
+
+    /// Encoder arguments after `-c:v`
+    pub fn encoder_args(&self) -> &'static [&'static str] {
+        match self {
+            LosslessMode::H264 => &["-preset", "ultrafast", "-crf", "0", "-pix_fmt", "yuv444p"],
+            LosslessMode::ProRes => &["-profile:v", "4444", "-pix_fmt", "yuv444p10le"],
+            LosslessMode::Ffv1 => &[
+                "-level",
+                "3",
+                "-g",
+                "1",
+                "-slicecrc",
+                "1",
+                "-pix_fmt",
+                "yuv444p",
+            ],
+        }
+    }
+
+    /// Rough encoded size per pixel for screen content, used for disk estimates
+    ///
+    /// Deliberately on the high side: camera footage and busy screens compress far
+    /// worse losslessly than static UI.
+    pub fn bits_per_pixel(&self) -> f64 {
+        match self {
+            LosslessMode::H264 => 2.0,
+            LosslessMode::ProRes => 5.3,
+            LosslessMode::Ffv1 => 4.0,
+        }
+    }
+}
+
+/// Primaries and transfer of captured frames
+///
+/// AVFoundation delivers screen frames in the display's color space, which is
+/// Display P3 on current Macs. Every color mode converts from this.
+const CAPTURE_PRIMARIES: &str = "smpte432";
+const CAPTURE_TRANSFER: &str = "iec61966-2-1";
+
+/// Color space for captured video
+///
+/// Screen capture is always SDR; the modes only choose the space it is encoded in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    /// 8-bit BT.709, converted from the display's gamut
+    #[default]
+    Sdr,
+    /// 8-bit Display P3, keeping the wide gamut of Mac displays
+    P3,
+    /// 10-bit BT.2020 with the PQ transfer, so recordings match HDR10 clips on a
+    /// timeline. The content is still SDR, placed at SDR reference white.
+    #[serde(alias = "hdr")]
+    Pq,
+}
+
+impl ColorMode {
+    /// Value passed to the ScreenCaptureKit bridge
+    pub fn bridge_value(&self) -> i32 {
+        match self {
+            ColorMode::Sdr => 0,
+            ColorMode::P3 => 1,
+            ColorMode::Pq => 2,
+        }
+    }
+
+    /// FFmpeg primaries, transfer and matrix tags for the encoded stream
+    pub fn tag_args(&self) -> [&'static str; 6] {
+        let (primaries, transfer, matrix) = match self {
+            ColorMode::Sdr => ("bt709", "bt709", "bt709"),
+            ColorMode::P3 => ("smpte432", "iec61966-2-1", "bt709"),
+            ColorMode::Pq => ("bt2020", "smpte2084", "bt2020nc"),
+        };
+        [
+            "-color_primaries",
+            primaries,
+            "-color_trc",
+            transfer,
+            "-colorspace",
+            matrix,
+        ]
+    }
+
+    /// Filter converting captured frames into this color space, if they need it
+    ///
+    /// SDR maps the P3 gamut into BT.709; PQ maps the frames into BT.2020 PQ with
+    /// SDR white at 203 nits (ITU-R BT.2408).
+    pub fn video_filter(&self) -> Option<String> {
+        match self {
+            ColorMode::Sdr => Some(format!(
+                "colorspace=all=bt709:iprimaries={}:itrc={}:ispace=bt709",
+                CAPTURE_PRIMARIES, CAPTURE_TRANSFER
+            )),
+            ColorMode::P3 => None,
+            ColorMode::Pq => Some(format!(
+                "zscale=tin={}:pin={}:min=bt709:t=smpte2084:p=bt2020:m=bt2020nc:npl=203",
+                CAPTURE_TRANSFER, CAPTURE_PRIMARIES
+            )),
+        }
+    }
+
+    /// Pixel format the encoder must use, overriding the codec default
+    pub fn pixel_format(&self, lossless: bool) -> Option<&'static str> {
+        match (self, lossless) {
+            (ColorMode::Pq, true) => Some("yuv444p10le"),
+            (ColorMode::Pq, false) => Some("yuv420p10le"),
+            _ => None,
+        }
+    }
 }
 
 /// Current state of an active recording
@@ -371,6 +866,21 @@ pub struct RecordingState {
     pub file_path: Option<String>,
     /// Configuration used for this recording
     pub config: RecordingConfig,
+    /// Markers added while recording
+    #[serde(default)]
+    pub markers: Vec<RecordingMarker>,
+    /// Filename stem the recording is renamed to when it stops
+    #[serde(default)]
+    pub output_name: Option<String>,
+    /// Whether the microphone is currently silenced
+    #[serde(default)]
+    pub mic_muted: bool,
+    /// Statistics computed when the recording stops
+    #[serde(default)]
+    pub summary: Option<RecordingSummary>,
+    /// Whether the current pause was triggered by keyboard and mouse inactivity
+    #[serde(default)]
+    pub paused_for_inactivity: bool,
 }
 
 impl RecordingState {
@@ -385,6 +895,11 @@ impl RecordingState {
             duration: 0.0,
             file_path: None,
             config,
+            markers: Vec::new(),
+            output_name: None,
+            mic_muted: false,
+            paused_for_inactivity: false,
+            summary: None,
         }
     }
 
@@ -439,6 +954,7 @@ impl RecordingState {
                 self.paused_at = None;
             }
             self.status = RecordingStatus::Recording;
+            self.paused_for_inactivity = false;
         }
     }
 
@@ -521,12 +1037,24 @@ impl RecordingState {
     }
 }
 
+/// How often a recorded window's position is checked (milliseconds)
+const WINDOW_TRACKING_INTERVAL_MS: u64 = 500;
+
 /// Global recording state manager
 pub struct RecordingManager {
     current_recording: Option<RecordingState>,
     duration_task: Option<JoinHandle<()>>,
     temp_file_manager: Arc<Mutex<TempFileManager>>,
     capture_session: Option<ScreenCaptureSession>,
+    cursor_recorder: Option<CursorRecorder>,
+    app_audio_recorder: Option<AppAudioRecorder>,
+    /// Chunks finished when the encoder settings changed, oldest first
+    finished_chunks: Vec<PathBuf>,
+    window_tracker: Option<JoinHandle<()>>,
+    memory_task: Option<JoinHandle<()>>,
+    idle_task: Option<JoinHandle<()>>,
+    disk_reservation: Option<DiskReservation>,
+    long_recording: LongRecordingConfig,
 }
 
 impl RecordingManager {
@@ -538,6 +1066,14 @@ impl RecordingManager {
             duration_task: None,
             temp_file_manager: Arc::new(Mutex::new(temp_manager)),
             capture_session: None,
+            cursor_recorder: None,
+            app_audio_recorder: None,
+            finished_chunks: Vec::new(),
+            window_tracker: None,
+            memory_task: None,
+            idle_task: None,
+            disk_reservation: None,
+            long_recording: LongRecordingConfig::default(),
         }
     }
 
@@ -557,6 +1093,149 @@ impl RecordingManager {
         self.capture_session.as_mut()
     }
 
+    /// Continue the current recording in a new chunk file encoded with `config`
+    pub fn start_next_chunk(&mut self, config: RecordingConfig) -> Result<(), String> {
+        let session = self
+            .capture_session
+            .as_mut()
+            .ok_or_else(|| "No active capture session".to_string())?;
+        // A paused session picks up the new settings when it resumes
+        if !session.is_recording() {
+            return Ok(());
+        }
+
+        let first = self
+            .finished_chunks
+            .first()
+            .unwrap_or(session.output_path())
+            .clone();
+        let next_path = chunk_path(&first, self.finished_chunks.len() + 1);
+
+        let finished = session
+            .start_next_chunk(config, next_path)
+            .map_err(|e| format!("Failed to start a new chunk: {}", e))?;
+        println!("[RecordingManager] Finished chunk {}", finished.display());
+        self.finished_chunks.push(finished);
+        Ok(())
+    }
+
+    /// Stop encoding while the recording is paused
+    ///
+    /// The encoder finalizes its chunk and the cursor and application audio
+    /// recorders leave the paused time out; `resume_capture` continues in a new chunk.
+    pub fn pause_capture(&mut self) -> Result<(), String> {
+        if let Some(cursor_recorder) = &self.cursor_recorder {
+            cursor_recorder.set_paused(true);
+        }
+        if let Some(app_audio_recorder) = &self.app_audio_recorder {
+            app_audio_recorder.set_paused(true);
+        }
+        let Some(session) = self.capture_session.as_mut() else {
+            return Ok(());
+        };
+
+        let finished = session
+            .pause_chunk()
+            .map_err(|e| format!("Failed to pause capture: {}", e))?;
+        println!(
+            "[RecordingManager] Paused after chunk {}",
+            finished.display()
+        );
+        self.finished_chunks.push(finished);
+        Ok(())
+    }
+
+    /// Continue encoding in a new chunk after `pause_capture`
+    pub fn resume_capture(&mut self, config: RecordingConfig) -> Result<(), String> {
+        if let Some(session) = self.capture_session.as_mut() {
+            let first = self
+                .finished_chunks
+                .first()
+                .unwrap_or(session.output_path())
+                .clone();
+            let next_path = chunk_path(&first, self.finished_chunks.len() + 1);
+            session
+                .resume_chunk(config, next_path)
+                .map_err(|e| format!("Failed to resume capture: {}", e))?;
+        }
+
+        if let Some(cursor_recorder) = &self.cursor_recorder {
+            cursor_recorder.set_paused(false);
+        }
+        if let Some(app_audio_recorder) = &self.app_audio_recorder {
+            app_audio_recorder.set_paused(false);
+        }
+        Ok(())
+    }
+
+    /// Shrink the disk reservation by what the recording has written so far
+    fn update_disk_reservation(&mut self) {
+        let Some(reservation) = self.disk_reservation.as_mut() else {
+            return;
+        };
+        let current = self
+            .capture_session
+            .as_ref()
+            .map(|session| session.output_path());
+        let written = self
+            .finished_chunks
+            .iter()
+            .chain(current)
+            .filter_map(|path| fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum();
+        reservation.release_written(written);
+    }
+
+    /// Start sampling memory if the long recording settings ask for it
+    pub fn start_memory_monitoring(
+        &mut self,
+        state: Arc<Mutex<RecordingManager>>,
+        app_handle: AppHandle,
+    ) {
+        self.stop_memory_monitoring();
+
+        if self.long_recording.enable_memory_monitoring {
+            self.memory_task = Some(memory_monitor::spawn(
+                state,
+                app_handle,
+                self.long_recording.clone(),
+            ));
+        }
+    }
+
+    /// Stop sampling memory
+    pub fn stop_memory_monitoring(&mut self) {
+        if let Some(task) = self.memory_task.take() {
+            task.abort();
+        }
+    }
+
+    /// Start auto-pausing on inactivity if the current recording asks for it
+    pub fn start_idle_monitoring(
+        &mut self,
+        state: Arc<Mutex<RecordingManager>>,
+        app_handle: AppHandle,
+    ) {
+        self.stop_idle_monitoring();
+
+        let minutes = self
+            .current_recording
+            .as_ref()
+            .and_then(|recording| recording.config.idle_pause_minutes);
+        if let Some(minutes) = minutes {
+            let threshold = std::time::Duration::from_secs(u64::from(minutes) * 60);
+            self.idle_task = Some(idle_monitor::spawn(state, app_handle, threshold));
+        }
+    }
+
+    /// Stop auto-pausing on inactivity
+    pub fn stop_idle_monitoring(&mut self) {
+        if let Some(task) = self.idle_task.take() {
+            task.abort();
+        }
+    }
+
     /// Start duration tracking task
     pub fn start_duration_tracking(
         &mut self,
@@ -576,6 +1255,7 @@ impl RecordingManager {
                 // Update duration and emit event
                 let recording_state = {
                     let mut manager = state.lock().unwrap();
+                    manager.update_disk_reservation();
                     if let Some(ref mut recording) = manager.current_recording {
                         // Only update if recording (not paused)
                         if recording.status == RecordingStatus::Recording {
@@ -607,6 +1287,70 @@ impl RecordingManager {
         }
     }
 
+    /// Follow a recorded window as it moves, updating the capture crop
+    ///
+    /// `screen_origin` is the desktop origin of the screen being recorded and
+    /// `position` the window's desktop position when recording started.
+    pub fn start_window_tracking(
+        &mut self,
+        state: Arc<Mutex<RecordingManager>>,
+        window_id: String,
+        screen_origin: (i32, i32),
+        position: (i32, i32),
+    ) {
+        self.stop_window_tracking();
+
+        let task = tokio::spawn(async move {
+            use super::screen_sources::{PlatformEnumerator, SourceEnumerator};
+
+            let mut position = position;
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(
+                WINDOW_TRACKING_INTERVAL_MS,
+            ));
+
+            loop {
+                interval.tick().await;
+
+                // Enumeration blocks on ScreenCaptureKit; minimized windows are skipped
+                let window =
+                    tokio::task::spawn_blocking(PlatformEnumerator::enumerate_windows_fast)
+                        .await
+                        .ok()
+                        .and_then(|windows| windows.ok())
+                        .and_then(|windows| windows.into_iter().find(|w| w.id == window_id));
+                let Some(window) = window else {
+                    continue;
+                };
+                if (window.x, window.y) == position {
+                    continue;
+                }
+                position = (window.x, window.y);
+
+                let Ok(mut manager) = state.lock() else {
+                    break;
+                };
+                if manager.current_recording.is_none() {
+                    break;
+                }
+                if let Some(session) = manager.capture_session.as_mut() {
+                    let (x, y) = (position.0 - screen_origin.0, position.1 - screen_origin.1);
+                    if let Err(e) = session.move_window(x, y) {
+                        println!("[RecordingManager] {}", e);
+                    }
+                }
+            }
+        });
+
+        self.window_tracker = Some(task);
+    }
+
+    /// Stop following the recorded window
+    pub fn stop_window_tracking(&mut self) {
+        if let Some(task) = self.window_tracker.take() {
+            task.abort();
+        }
+    }
+
     /// Emit state change event
     pub fn emit_state_change(&self, app_handle: &AppHandle, event: &str) {
         if let Some(ref recording) = self.current_recording {
@@ -624,27 +1368,92 @@ impl Default for RecordingManager {
 impl Drop for RecordingManager {
     fn drop(&mut self) {
         self.stop_duration_tracking();
+        self.stop_window_tracking();
+        self.stop_memory_monitoring();
+        self.stop_idle_monitoring();
     }
 }
 
 /// Thread-safe recording manager type
 pub type RecordingManagerState = Arc<Mutex<RecordingManager>>;
 
+/// Path of chunk `index` of a recording whose first chunk is `first`
+fn chunk_path(first: &Path, index: usize) -> PathBuf {
+    let stem = first
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "recording".to_string());
+    let extension = first
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_string())
+        .unwrap_or_else(|| "mp4".to_string());
+    first.with_file_name(format!("{}_part{:03}.{}", stem, index, extension))
+}
+
+/// Join the chunks of a recording into the first one and delete the rest
+fn join_chunks(chunks: &[PathBuf]) -> Result<PathBuf, String> {
+    let first = chunks
+        .first()
+        .ok_or_else(|| "No chunks to join".to_string())?;
+    let extension = first
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_string())
+        .unwrap_or_else(|| "mp4".to_string());
+    let joined = first.with_extension(format!("joined.{}", extension));
+
+    // Chunks differ only in bitrate and frame rate; anything else can't be copied
+    tauri::async_runtime::block_on(check_concat_compatible(chunks))?;
+    concat_copy(chunks, &joined)?;
+    fs::rename(&joined, first).map_err(|e| format!("Failed to replace recording: {}", e))?;
+    for chunk in &chunks[1..] {
+        let _ = fs::remove_file(chunk);
+    }
+
+    Ok(first.clone())
+}
+
 // ============================================================================
 // Permission Types
 // ============================================================================
 
 /// Permission types that need to be checked
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum PermissionType {
     Screen,
     Camera,
     Microphone,
+    /// Listening to keystrokes and clicks (keystroke/click overlays)
+    InputMonitoring,
+    /// Observing other apps' UI and cursor events
+    Accessibility,
+}
+
+impl PermissionType {
+    /// System Settings URL for this permission's privacy pane
+    pub fn settings_url(&self) -> &'static str {
+        match self {
+            PermissionType::Screen => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenCapture"
+            }
+            PermissionType::Camera => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Camera"
+            }
+            PermissionType::Microphone => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone"
+            }
+            PermissionType::InputMonitoring => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_ListenEvent"
+            }
+            PermissionType::Accessibility => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility"
+            }
+        }
+    }
 }
 
 /// Permission status
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum PermissionStatus {
     NotDetermined,
@@ -654,7 +1463,7 @@ pub enum PermissionStatus {
 }
 
 /// Result of a permission check
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PermissionResult {
     pub permission_type: PermissionType,
     pub status: PermissionStatus,
@@ -664,6 +1473,9 @@ pub struct PermissionResult {
     pub help_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub instructions: Option<Vec<String>>,
+    /// Deep link to the matching System Settings privacy pane
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings_url: Option<String>,
 }
 
 impl PermissionResult {
@@ -700,16 +1512,42 @@ impl PermissionResult {
                     "4. Click 'Request Permission' to try again".to_string(),
                 ]),
             ),
-            (_, PermissionStatus::Restricted) => (
-                Some("Permission restricted by system policy".to_string()),
-                None,
+            (PermissionType::InputMonitoring, PermissionStatus::Denied) => (
+                Some("Input monitoring permission denied".to_string()),
+                Some("https://support.apple.com/guide/mac-help/control-access-to-input-monitoring-on-mac-mchl4cedafb6/mac".to_string()),
                 Some(vec![
-                    "This permission is restricted by your system administrator or parental controls.".to_string(),
-                    "Contact your administrator for assistance.".to_string(),
+                    "1. Open System Preferences/Settings".to_string(),
+                    "2. Go to Security & Privacy > Privacy > Input Monitoring".to_string(),
+                    "3. Enable ClipForge in the list".to_string(),
+                    "4. Restart ClipForge for changes to take effect".to_string(),
                 ]),
             ),
-            _ => (None, None, None),
-        };
+            (PermissionType::Accessibility, PermissionStatus::Denied) => (
+                Some("Accessibility permission denied".to_string()),
+                Some("https://support.apple.com/guide/mac-help/allow-accessibility-apps-to-access-your-mac-mh43185/mac".to_string()),
+                Some(vec![
+                    "1. Open System Preferences/Settings".to_string(),
+                    "2. Go to Security & Privacy > Privacy > Accessibility".to_string(),
+                    "3. Enable ClipForge in the list".to_string(),
+                    "4. Click 'Request Permission' to try again".to_string(),
+                ]),
+            ),
+            (_, PermissionStatus::Restricted) => (
+                Some("Permission restricted by system policy".to_string()),
+                None,
+                Some(vec![
+                    "This permission is restricted by your system administrator or parental controls.".to_string(),
+                    "Contact your administrator for assistance.".to_string(),
+                ]),
+            ),
+            _ => (None, None, None),
+        };
+
+        // Point denied permissions straight at the pane that fixes them
+        let settings_url = match status {
+            PermissionStatus::Denied => Some(permission_type.settings_url().to_string()),
+            _ => None,
+        };
 
         Self {
             permission_type,
@@ -717,6 +1555,7 @@ impl PermissionResult {
             error_message,
             help_url,
             instructions,
+            settings_url,
         }
     }
 }
@@ -851,139 +1690,6 @@ impl std::fmt::Display for RecordingError {
 // Temporary File Management
 // ============================================================================
 
-/// Manages temporary recording files with automatic cleanup
-pub struct TempFileManager {
-    temp_dir: PathBuf,
-    active_files: Vec<PathBuf>,
-}
-
-impl TempFileManager {
-    /// Create a new temporary file manager
-    pub fn new() -> Result<Self, String> {
-        let temp_dir = std::env::temp_dir().join("clipforge_recordings");
-
-        // Create temp directory if it doesn't exist
-        fs::create_dir_all(&temp_dir)
-            .map_err(|e| format!("Failed to create temp directory: {}", e))?;
-
-        Ok(Self {
-            temp_dir,
-            active_files: Vec::new(),
-        })
-    }
-
-    /// Create a new temporary file for recording
-    pub fn create_temp_file(&mut self, prefix: &str) -> Result<PathBuf, String> {
-        let timestamp = chrono::Utc::now().timestamp_millis();
-        let filename = format!("{}_{}.mp4", prefix, timestamp);
-        let filepath = self.temp_dir.join(filename);
-
-        // Track this file for cleanup
-        self.active_files.push(filepath.clone());
-
-        Ok(filepath)
-    }
-
-    /// Clean up a specific temporary file
-    #[allow(dead_code)]
-    pub fn cleanup_file(&mut self, path: &Path) -> Result<(), String> {
-        if path.exists() {
-            fs::remove_file(path).map_err(|e| format!("Failed to remove temp file: {}", e))?;
-        }
-
-        self.active_files.retain(|p| p != path);
-        Ok(())
-    }
-
-    /// Clean up all active temporary files
-    pub fn cleanup_all(&mut self) -> Result<(), String> {
-        let mut errors = Vec::new();
-
-        for file in &self.active_files {
-            if file.exists() {
-                if let Err(e) = fs::remove_file(file) {
-                    errors.push(format!("Failed to remove {}: {}", file.display(), e));
-                }
-            }
-        }
-
-        self.active_files.clear();
-
-        if !errors.is_empty() {
-            return Err(format!("Cleanup errors: {}", errors.join(", ")));
-        }
-
-        Ok(())
-    }
-
-    /// Clean up orphaned temporary files from previous sessions
-    pub fn cleanup_orphaned_files() -> Result<usize, String> {
-        let temp_dir = std::env::temp_dir().join("clipforge_recordings");
-
-        if !temp_dir.exists() {
-            return Ok(0);
-        }
-
-        let mut cleaned = 0;
-        let entries =
-            fs::read_dir(&temp_dir).map_err(|e| format!("Failed to read temp directory: {}", e))?;
-
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_file() {
-                // Check if file is older than 1 hour (likely orphaned)
-                if let Ok(metadata) = fs::metadata(&path) {
-                    if let Ok(modified) = metadata.modified() {
-                        let age = std::time::SystemTime::now()
-                            .duration_since(modified)
-                            .unwrap_or_default();
-
-                        if age.as_secs() > 3600 {
-                            // File is older than 1 hour, remove it
-                            if fs::remove_file(&path).is_ok() {
-                                cleaned += 1;
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        Ok(cleaned)
-    }
-
-    /// Check available disk space
-    pub fn check_disk_space(&self, _required_mb: u64) -> Result<(), RecordingError> {
-        // This is a simplified check - in production you'd use platform-specific APIs
-        // For now, we'll just check if temp dir is writable
-        let test_file = self.temp_dir.join(".diskcheck");
-        match fs::write(&test_file, b"test") {
-            Ok(_) => {
-                let _ = fs::remove_file(test_file);
-                // TODO: Implement actual disk space check using platform APIs
-                Ok(())
-            }
-            Err(e) => Err(RecordingError::IoError(format!(
-                "Cannot write to temp directory: {}",
-                e
-            ))),
-        }
-    }
-}
-
-impl Default for TempFileManager {
-    fn default() -> Self {
-        Self::new().expect("Failed to create temp file manager")
-    }
-}
-
-impl Drop for TempFileManager {
-    fn drop(&mut self) {
-        // Clean up all temporary files when dropped
-        let _ = self.cleanup_all();
-    }
-}
-
 /// Wrapper for recording resources that need cleanup
 #[allow(dead_code)]
 pub struct RecordingResources {
@@ -1024,7 +1730,7 @@ pub struct DeviceAvailability {
 pub async fn validate_device_availability(
     device_type: String,
     device_id: Option<String>,
-) -> Result<DeviceAvailability, String> {
+) -> Result<DeviceAvailability, ClipForgeError> {
     use crate::commands::camera_sources::{CameraEnumerator, PlatformEnumerator as CameraEnum};
     use crate::commands::screen_sources::{PlatformEnumerator as ScreenEnum, SourceEnumerator};
 
@@ -1161,7 +1867,101 @@ pub async fn validate_device_availability(
                 })
             }
         }
-        _ => Err(format!("Unknown device type: {}", device_type)),
+        _ => Err(ClipForgeError::InvalidInput(format!(
+            "Unknown device type: {}",
+            device_type
+        ))),
+    }
+}
+
+/// Fall back to another camera when the active recording's camera is disconnected
+///
+/// Emits `recording:camera-fallback` with the resulting availability. Picture-in-picture
+/// cameras are switched by the frontend. A native webcam capture continues in a new
+/// chunk from the fallback camera, or is stopped when no other camera is connected.
+pub fn handle_cameras_removed(
+    app_handle: &AppHandle,
+    cameras: &[CameraDevice],
+    removed: &[CameraDevice],
+) {
+    let state = app_handle.state::<RecordingManagerState>();
+    let Ok(mut manager) = state.lock() else {
+        return;
+    };
+    let Some(mut recording_state) = manager.get_current_recording() else {
+        return;
+    };
+
+    if !matches!(
+        recording_state.status,
+        RecordingStatus::Recording | RecordingStatus::Paused
+    ) {
+        return;
+    }
+    let Some(settings) = recording_state.config.camera.as_mut() else {
+        return;
+    };
+    let Some(lost) = removed
+        .iter()
+        .find(|c| settings.device_id.as_deref() == Some(c.id.as_str()))
+    else {
+        return;
+    };
+
+    let fallback = cameras
+        .iter()
+        .find(|c| c.is_default)
+        .or_else(|| cameras.first());
+
+    let availability = DeviceAvailability {
+        device_type: "camera".to_string(),
+        device_id: Some(lost.id.clone()),
+        is_available: false,
+        error_message: Some(format!("Camera '{}' was disconnected", lost.name)),
+        fallback_available: fallback.is_some(),
+        fallback_device_id: fallback.map(|c| c.id.clone()),
+    };
+
+    let native_webcam = recording_state.recording_type == RecordingType::Webcam;
+    match fallback {
+        Some(device) => {
+            println!(
+                "[RecordingManager] Camera '{}' disconnected, falling back to '{}'",
+                lost.name, device.name
+            );
+            settings.device_id = Some(device.id.clone());
+            let settings = settings.clone();
+            if native_webcam {
+                if let Some(session) = manager.get_capture_session_mut() {
+                    session.set_camera(device.name.clone(), settings);
+                }
+                // The encoder's input is gone, so switch cameras in a new chunk now
+                if let Err(e) = manager.start_next_chunk(recording_state.config.clone()) {
+                    println!("[RecordingManager] {}", e);
+                }
+            }
+            manager.set_current_recording(Some(recording_state));
+        }
+        None => {
+            println!(
+                "[RecordingManager] Camera '{}' disconnected with no fallback available",
+                lost.name
+            );
+        }
+    }
+    drop(manager);
+
+    let _ = app_handle.emit("recording:camera-fallback", availability);
+
+    // Nothing left to record from; keep what was captured
+    if native_webcam && fallback.is_none() {
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            let state = app_handle.state::<RecordingManagerState>();
+            if let Err(e) = stop_recording(state, app_handle.clone()).await {
+                println!("[RecordingManager] Failed to stop recording: {}", e);
+            }
+        });
     }
 }
 
@@ -1182,6 +1982,51 @@ pub struct LongRecordingConfig {
     pub max_chunk_size_mb: u64,
     /// Enable memory monitoring
     pub enable_memory_monitoring: bool,
+    /// App memory in MB above which preview work is shed
+    #[serde(default = "default_memory_warning_mb")]
+    pub memory_warning_mb: u64,
+    /// FFmpeg memory in MB above which the recording rolls over to a new chunk
+    #[serde(default = "default_encoder_memory_limit_mb")]
+    pub encoder_memory_limit_mb: u64,
+    /// Free space in MB a recording must leave, counting its expected size
+    #[serde(default = "default_min_free_space_mb")]
+    pub min_free_space_mb: u64,
+    /// Claim the expected size (bitrate × max duration) on disk before recording
+    #[serde(default)]
+    pub preallocate: bool,
+    /// Largest total size in MB of temporary recording files (0 = unlimited)
+    #[serde(default)]
+    pub temp_quota_mb: u64,
+}
+
+fn default_memory_warning_mb() -> u64 {
+    1024
+}
+
+fn default_encoder_memory_limit_mb() -> u64 {
+    1536
+}
+
+fn default_min_free_space_mb() -> u64 {
+    500
+}
+
+impl LongRecordingConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.chunk_duration_seconds < 60 {
+            return Err("Chunk duration must be at least 60 seconds".to_string());
+        }
+        if self.max_chunk_size_mb < 100 {
+            return Err("Max chunk size must be at least 100 MB".to_string());
+        }
+        if self.max_duration_seconds > 0 && self.max_duration_seconds < 60 {
+            return Err("Max duration must be at least 60 seconds if set".to_string());
+        }
+        if self.memory_warning_mb < 256 || self.encoder_memory_limit_mb < 256 {
+            return Err("Memory thresholds must be at least 256 MB".to_string());
+        }
+        Ok(())
+    }
 }
 
 impl Default for LongRecordingConfig {
@@ -1192,28 +2037,47 @@ impl Default for LongRecordingConfig {
             chunk_duration_seconds: 1800, // 30 minutes
             max_chunk_size_mb: 2048,      // 2 GB
             enable_memory_monitoring: true,
+            memory_warning_mb: default_memory_warning_mb(),
+            encoder_memory_limit_mb: default_encoder_memory_limit_mb(),
+            min_free_space_mb: default_min_free_space_mb(),
+            preallocate: false,
+            temp_quota_mb: 0,
         }
     }
 }
 
-/// Get default long recording configuration
+/// Get the long recording configuration
 #[tauri::command]
-pub async fn get_long_recording_config() -> Result<LongRecordingConfig, String> {
-    Ok(LongRecordingConfig::default())
+pub async fn get_long_recording_config(
+    state: State<'_, RecordingManagerState>,
+) -> Result<LongRecordingConfig, ClipForgeError> {
+    let manager = state.lock()?;
+    Ok(manager.long_recording.clone())
+}
+
+/// Set the long recording configuration used by the next recording
+#[tauri::command]
+pub async fn set_long_recording_config(
+    config: LongRecordingConfig,
+    state: State<'_, RecordingManagerState>,
+) -> Result<LongRecordingConfig, ClipForgeError> {
+    config.validate()?;
+    let mut manager = state.lock()?;
+    manager
+        .get_temp_manager()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .set_quota_mb(config.temp_quota_mb);
+    manager.long_recording = config.clone();
+    Ok(config)
 }
 
 /// Validate long recording configuration
 #[tauri::command]
-pub async fn validate_long_recording_config(config: LongRecordingConfig) -> Result<bool, String> {
-    if config.chunk_duration_seconds < 60 {
-        return Err("Chunk duration must be at least 60 seconds".to_string());
-    }
-    if config.max_chunk_size_mb < 100 {
-        return Err("Max chunk size must be at least 100 MB".to_string());
-    }
-    if config.max_duration_seconds > 0 && config.max_duration_seconds < 60 {
-        return Err("Max duration must be at least 60 seconds if set".to_string());
-    }
+pub async fn validate_long_recording_config(
+    config: LongRecordingConfig,
+) -> Result<bool, ClipForgeError> {
+    config.validate()?;
     Ok(true)
 }
 
@@ -1234,17 +2098,20 @@ pub fn cleanup_stuck_ffmpeg_processes() {
 
     match result {
         Ok(output) => {
-            if output.status.success() {            } else {
+            if output.status.success() {
+            } else {
                 let stderr = String::from_utf8_lossy(&output.stderr);
-                if stderr.contains("No matching processes") {                } else {                }
+                if stderr.contains("No matching processes") {
+                } else {
+                }
             }
         }
-        Err(e) => {        }
+        Err(e) => {}
     }
 
     // Also clean up temporary files older than 1 hour
     if let Ok(count) = TempFileManager::cleanup_orphaned_files() {
-        if count > 0 {        }
+        if count > 0 {}
     }
 }
 
@@ -1265,7 +2132,9 @@ pub fn initialize_recording_module() {
 
 /// Check the status of a specific permission
 #[tauri::command]
-pub async fn check_permission(permission_type: PermissionType) -> Result<PermissionResult, String> {
+pub async fn check_permission(
+    permission_type: PermissionType,
+) -> Result<PermissionResult, ClipForgeError> {
     // Use platform-specific implementation
     Ok(PlatformPermissions::check_permission(&permission_type))
 }
@@ -1274,20 +2143,177 @@ pub async fn check_permission(permission_type: PermissionType) -> Result<Permiss
 #[tauri::command]
 pub async fn request_permission(
     permission_type: PermissionType,
-) -> Result<PermissionResult, String> {
+) -> Result<PermissionResult, ClipForgeError> {
     // Use platform-specific implementation
     Ok(PlatformPermissions::request_permission(&permission_type))
 }
 
+/// Open the System Settings privacy pane for a permission
+/// (Screen Recording, Camera or Microphone under Privacy & Security)
+#[tauri::command]
+pub async fn open_privacy_settings(permission_type: PermissionType) -> Result<(), ClipForgeError> {
+    Ok(PlatformPermissions::open_privacy_settings(
+        &permission_type,
+    )?)
+}
+
 /// Get the current recording state
 #[tauri::command]
 pub async fn get_recording_state(
     state: State<'_, RecordingManagerState>,
-) -> Result<Option<RecordingState>, String> {
-    let manager = state.lock().map_err(|e| e.to_string())?;
+) -> Result<Option<RecordingState>, ClipForgeError> {
+    let manager = state.lock()?;
     Ok(manager.get_current_recording())
 }
 
+/// Point a capture session at a window: record the screen it is on, cropped to the window
+///
+/// Returns the window's desktop region and the origin of the screen the crop is
+/// relative to, or None if the window could not be found
+fn configure_window_capture(
+    capture_session: &mut ScreenCaptureSession,
+    source_id: &str,
+) -> Option<(CaptureRegion, (i32, i32))> {
+    if let Some(_window_id) = source_id
+        .strip_prefix("window_")
+        .and_then(|s| s.parse::<u32>().ok())
+    {
+        // Get window bounds and screens from the system
+        use super::screen_sources::{PlatformEnumerator, SourceEnumerator};
+        if let Ok(windows) = PlatformEnumerator::enumerate_windows() {
+            if let Some(window) = windows.iter().find(|w| w.id == source_id) {
+                let mut screen_origin = (0, 0);
+                let capture_region = CaptureRegion {
+                    x: window.x as f64,
+                    y: window.y as f64,
+                    width: window.width as f64,
+                    height: window.height as f64,
+                };
+
+                // Get all screens to find which one contains the window
+                if let Ok(screens) = PlatformEnumerator::enumerate_screens() {
+                    // Find which screen contains the window center point
+                    let window_center_x = window.x + (window.width as i32 / 2);
+                    let window_center_y = window.y + (window.height as i32 / 2);
+
+                    println!(
+                        "[RecordingManager] Window center: ({}, {})",
+                        window_center_x, window_center_y
+                    );
+
+                    // Find the screen that contains this point
+                    let mut found_screen = None;
+                    for screen in &screens {
+                        let screen_right = screen.x + screen.width as i32;
+                        let screen_bottom = screen.y + screen.height as i32;
+
+                        println!("[RecordingManager] Checking screen {}: x={}, y={}, w={}, h={} (bounds: {}-{}, {}-{})",
+                            screen.id, screen.x, screen.y, screen.width, screen.height,
+                            screen.x, screen_right, screen.y, screen_bottom);
+
+                        if window_center_x >= screen.x
+                            && window_center_x < screen_right
+                            && window_center_y >= screen.y
+                            && window_center_y < screen_bottom
+                        {
+                            found_screen = Some(screen);
+                            break;
+                        }
+                    }
+
+                    if let Some(screen) = found_screen {
+                        // Extract device number from screen ID (e.g., "screen_4" -> "4")
+                        if let Some(device_num) = screen.id.strip_prefix("screen_") {
+                            capture_session.set_screen_device(device_num.to_string());
+                        } else if let Some(display_id) = screen
+                            .id
+                            .strip_prefix("display_")
+                            .and_then(|id| id.parse::<u32>().ok())
+                        {
+                            capture_session.set_screen_display(display_id);
+                        }
+
+                        // Adjust crop coordinates to be relative to screen origin,
+                        // scaled to the screen's pixels
+                        screen_origin = (screen.x, screen.y);
+                        let relative_x = window.x - screen.x;
+                        let relative_y = window.y - screen.y;
+                        capture_session.set_window_bounds(
+                            relative_x,
+                            relative_y,
+                            window.width,
+                            window.height,
+                            screen.scale_factor,
+                        );
+                    } else {
+                        capture_session.set_window_bounds(
+                            window.x,
+                            window.y,
+                            window.width,
+                            window.height,
+                            1.0,
+                        );
+                    }
+                }
+
+                return Some((capture_region, screen_origin));
+            }
+        }
+    }
+
+    None
+}
+
+/// Validate the requested camera format against the device before starting
+///
+/// Native webcam recordings are encoded at the camera's own size and rate, so
+/// `config` is updated to match.
+fn resolve_recording_camera(
+    recording_type: &RecordingType,
+    config: &mut RecordingConfig,
+) -> Result<Option<(CameraDevice, CameraCaptureSettings)>, String> {
+    let camera = match (recording_type, &mut config.camera) {
+        (RecordingType::Webcam | RecordingType::ScreenAndWebcam, Some(settings)) => {
+            let device = resolve_camera(settings)?;
+            // Pin the resolved device so a disconnect mid-recording can be detected
+            settings.device_id = Some(device.id.clone());
+            Some((device, settings.clone()))
+        }
+        _ => None,
+    };
+
+    if let (RecordingType::Webcam, Some((_, settings))) = (recording_type, &camera) {
+        config.width = settings.width;
+        config.height = settings.height;
+        config.frame_rate = settings.frame_rate;
+    }
+    Ok(camera)
+}
+
+/// FFmpeg command start_recording would run for these settings (see dry_run.rs)
+///
+/// The output path is a placeholder; real recordings get a new temp file.
+pub fn preview_recording_command(
+    recording_type: RecordingType,
+    source_id: String,
+    mut config: RecordingConfig,
+    include_audio: bool,
+) -> Result<Vec<String>, ClipForgeError> {
+    config.validate()?;
+    let camera = resolve_recording_camera(&recording_type, &mut config)?;
+
+    let output_path = std::env::temp_dir().join(format!("recording.{}", config.output_format));
+    let mut session = ScreenCaptureSession::new(source_id.clone(), output_path, config);
+    if let (RecordingType::Webcam, Some((device, settings))) = (&recording_type, camera) {
+        session.set_camera(device.name, settings);
+    }
+    if source_id.starts_with("window_") {
+        configure_window_capture(&mut session, &source_id);
+    }
+
+    Ok(session.preview_command(include_audio)?)
+}
+
 /// Start a new recording session
 #[tauri::command]
 pub async fn start_recording(
@@ -1295,187 +2321,681 @@ pub async fn start_recording(
     source_id: String,
     config: Option<RecordingConfig>,
     include_audio: bool,
+    audio_app_filter: Option<AudioAppFilter>,
     state: State<'_, RecordingManagerState>,
     app_handle: AppHandle,
-) -> Result<RecordingState, String> {
+) -> Result<RecordingState, ClipForgeError> {
     // Check if there's already an active recording
     {
-        let manager = state.lock().map_err(|e| e.to_string())?;
+        let manager = state.lock()?;
         if let Some(current) = manager.get_current_recording() {
-            if current.status == RecordingStatus::Recording {
-                return Err("A recording is already in progress".to_string());
+            // A stopping recording still owns its files until it is finalized
+            if matches!(
+                current.status,
+                RecordingStatus::Recording | RecordingStatus::Stopping
+            ) {
+                return Err(ClipForgeError::AlreadyRecording);
             }
         }
     }
 
-    // Use provided config or default
-    let config = config.unwrap_or_default();
+    // Use the provided config, or the default from the settings
+    let settings = app_handle
+        .try_state::<SettingsState>()
+        .and_then(|settings| settings.lock().ok().map(|settings| settings.clone()));
+    let mut config = config
+        .or_else(|| Some(settings.as_ref()?.default_recording_config.clone()))
+        .unwrap_or_default();
+    if config.mic_hotkey.is_none() {
+        config.mic_hotkey = settings.and_then(|settings| settings.hotkeys.mic);
+    }
+
+    if let Some(filter) = &audio_app_filter {
+        filter.validate()?;
+    }
+
+    let camera = resolve_recording_camera(&recording_type, &mut config)?;
 
     // Generate a unique ID for this recording
     let id = format!("rec_{}", chrono::Utc::now().timestamp_millis());
 
+    // Name the finished file up front so {date} and {time} are the start time
+    let output_name = match &config.filename_template {
+        Some(template) => {
+            let now = chrono::Local::now();
+            let date = now.format("%Y-%m-%d").to_string();
+            let time = now.format("%H-%M-%S").to_string();
+            let recording_kind = match recording_type {
+                RecordingType::Screen => "screen",
+                RecordingType::Webcam => "webcam",
+                RecordingType::ScreenAndWebcam => "pip",
+            };
+            Some(filename_template::render(
+                template,
+                &[
+                    ("date", &date),
+                    ("time", &time),
+                    ("source", &source_id),
+                    ("preset", QualityPreset::matching(&config).name()),
+                    ("type", recording_kind),
+                    ("id", &id),
+                ],
+            )?)
+        }
+        None => None,
+    };
+
     // Create new recording state and start it
     let mut recording_state = RecordingState::new(id.clone(), recording_type, config.clone());
+    recording_state.output_name = output_name;
     recording_state.start();
 
     // Create temporary file for recording
     let temp_path = {
-        let manager = state.lock().map_err(|e| e.to_string())?;
+        let manager = state.lock()?;
         let temp_manager = manager.get_temp_manager();
-        let mut temp = temp_manager.lock().map_err(|e| e.to_string())?;
+        let mut temp = temp_manager.lock()?;
         temp.create_temp_file(&id)
             .map_err(|e| format!("Failed to create temp file: {}", e))?
     };
 
+    // Refuse to start if the recording would fill the disk
+    let disk_reservation = {
+        let manager = state.lock()?;
+        DiskReservation::for_recording(&temp_path, &config, &manager.long_recording)?
+    };
+
     // Create and start screen capture session
     let mut capture_session =
-        ScreenCaptureSession::new(source_id.clone(), temp_path.clone(), config);
+        ScreenCaptureSession::new(source_id.clone(), temp_path.clone(), config.clone());
 
-    // If recording a window, get window bounds and determine which screen it's on
-    if source_id.starts_with("window_") {
-        if let Some(_window_id) = source_id
-            .strip_prefix("window_")
-            .and_then(|s| s.parse::<u32>().ok())
-        {
-            // Get window bounds and screens from the system
-            use super::screen_sources::{PlatformEnumerator, SourceEnumerator};
-            if let Ok(windows) = PlatformEnumerator::enumerate_windows() {
-                if let Some(window) = windows.iter().find(|w| w.id == source_id) {
-                    // Get all screens to find which one contains the window
-                    if let Ok(screens) = PlatformEnumerator::enumerate_screens() {
-                        // Find which screen contains the window center point
-                        let window_center_x = window.x + (window.width as i32 / 2);
-                        let window_center_y = window.y + (window.height as i32 / 2);
-
-                        println!(
-                            "[RecordingManager] Window center: ({}, {})",
-                            window_center_x, window_center_y
-                        );
+    // PiP cameras are captured by the frontend; webcam-only recordings capture natively
+    if let (RecordingType::Webcam, Some((device, settings))) =
+        (&recording_state.recording_type, camera)
+    {
+        println!(
+            "[RecordingManager] Camera '{}' at {}x{} @ {}fps",
+            device.name, settings.width, settings.height, settings.frame_rate
+        );
+        capture_session.set_camera(device.name, settings);
+    }
 
-                        // Find the screen that contains this point
-                        let mut found_screen = None;
-                        for screen in &screens {
-                            let screen_right = screen.x + screen.width as i32;
-                            let screen_bottom = screen.y + screen.height as i32;
-
-                            println!("[RecordingManager] Checking screen {}: x={}, y={}, w={}, h={} (bounds: {}-{}, {}-{})",
-                                screen.id, screen.x, screen.y, screen.width, screen.height,
-                                screen.x, screen_right, screen.y, screen_bottom);
-
-                            if window_center_x >= screen.x
-                                && window_center_x < screen_right
-                                && window_center_y >= screen.y
-                                && window_center_y < screen_bottom
-                            {                                found_screen = Some(screen);
-                                break;
-                            }
-                        }
+    // Desktop region covered by the recording (used for cursor tracking)
+    let mut capture_region: Option<CaptureRegion> = None;
 
-                        if let Some(screen) = found_screen {
-                            // Extract device number from screen ID (e.g., "screen_4" -> "4")
-                            if let Some(device_num) = screen.id.strip_prefix("screen_") {                                capture_session.set_screen_device(device_num.to_string());
-
-                                // Adjust crop coordinates to be relative to screen origin
-                                let relative_x = window.x - screen.x;
-                                let relative_y = window.y - screen.y;
-                                capture_session.set_window_bounds(
-                                    relative_x,
-                                    relative_y,
-                                    window.width,
-                                    window.height,
-                                );
-                            }
-                        } else {                            capture_session.set_window_bounds(
-                                window.x,
-                                window.y,
-                                window.width,
-                                window.height,
-                            );
-                        }
-                    }
-                }
-            }
+    // Origin of the screen a window recording is cropped from, to follow the window
+    let mut window_screen_origin: Option<(i32, i32)> = None;
+
+    // If recording a window, get window bounds and determine which screen it's on
+    if source_id.starts_with("window_") {
+        if let Some((region, origin)) = configure_window_capture(&mut capture_session, &source_id) {
+            capture_region = Some(region);
+            window_screen_origin = Some(origin);
         }
+    } else if config.record_cursor {
+        use super::screen_sources::{PlatformEnumerator, SourceEnumerator};
+        if let Ok(screens) = PlatformEnumerator::enumerate_screens() {
+            capture_region = screens
+                .iter()
+                .find(|s| s.id == source_id)
+                .or_else(|| screens.iter().find(|s| s.is_primary))
+                .map(|screen| CaptureRegion {
+                    x: screen.x as f64,
+                    y: screen.y as f64,
+                    width: screen.width as f64,
+                    height: screen.height as f64,
+                });
+        }
+    }
+
+    // Push-to-talk recordings start with the microphone muted
+    if config
+        .mic_hotkey
+        .as_ref()
+        .is_some_and(|hotkey| hotkey.push_to_talk)
+    {
+        capture_session
+            .set_mic_muted(true)
+            .map_err(|e| format!("Failed to mute microphone: {}", e))?;
+        recording_state.mic_muted = true;
     }
 
     capture_session
         .start(include_audio)
         .map_err(|e| format!("Failed to start capture: {}", e))?;
 
+    // Start sampling the cursor once FFmpeg is running so timestamps line up
+    let cursor_recorder = match capture_region {
+        Some(region) if config.record_cursor => {
+            Some(CursorRecorder::start(region, config.frame_rate))
+        }
+        _ => None,
+    };
+
+    // Capture the chosen applications' audio alongside FFmpeg
+    let app_audio_recorder = match &audio_app_filter {
+        Some(filter) => {
+            let display_id = source_id
+                .strip_prefix("display_")
+                .and_then(|id| id.parse().ok())
+                .unwrap_or(0);
+            match AppAudioRecorder::start(
+                filter,
+                display_id,
+                temp_path.with_extension("app-audio.pcm"),
+                include_audio,
+            ) {
+                Ok(recorder) => Some(recorder),
+                Err(e) => {
+                    let _ = capture_session.stop();
+                    return Err(ClipForgeError::Capture(format!(
+                        "Failed to start application audio capture: {}",
+                        e
+                    )));
+                }
+            }
+        }
+        None => None,
+    };
+
     // Update recording state with file path
     recording_state.file_path = Some(temp_path.to_string_lossy().to_string());
 
     // Update manager state and start duration tracking
     {
-        let mut manager = state.lock().map_err(|e| e.to_string())?;
+        let mut manager = state.lock()?;
         manager.capture_session = Some(capture_session);
+        manager.cursor_recorder = cursor_recorder;
+        manager.app_audio_recorder = app_audio_recorder;
+        manager.disk_reservation = disk_reservation;
+        manager.finished_chunks.clear();
         manager.set_current_recording(Some(recording_state.clone()));
         manager.emit_state_change(&app_handle, "recording:started");
 
         // Start duration tracking task
         let state_clone = state.inner().clone();
-        manager.start_duration_tracking(state_clone, app_handle);
+        manager.start_duration_tracking(state_clone, app_handle.clone());
+        manager.start_memory_monitoring(state.inner().clone(), app_handle.clone());
+        manager.start_idle_monitoring(state.inner().clone(), app_handle.clone());
+
+        // Keep the crop on the window if it is moved
+        if let (Some(region), Some(origin)) = (&capture_region, window_screen_origin) {
+            manager.start_window_tracking(
+                state.inner().clone(),
+                source_id.clone(),
+                origin,
+                (region.x as i32, region.y as i32),
+            );
+        }
+    }
+
+    webhooks::fire(WebhookPayload::new(
+        WebhookEvent::RecordingStarted,
+        Some(recording_state.id.clone()),
+        recording_state.file_path.clone(),
+        Some(0.0),
+    ));
+
+    if let Some(hotkey) = &config.mic_hotkey {
+        register_mic_hotkey(&app_handle, hotkey);
+    }
+
+    Ok(recording_state)
+}
+
+/// Mute or unmute the microphone of the current recording; `None` toggles it
+fn update_mic_muted(
+    state: &RecordingManagerState,
+    app_handle: &AppHandle,
+    muted: Option<bool>,
+) -> Result<RecordingState, ClipForgeError> {
+    let mut manager = state.lock()?;
+
+    let mut recording_state = manager
+        .get_current_recording()
+        .ok_or(ClipForgeError::NotRecording)?;
+    let muted = muted.unwrap_or(!recording_state.mic_muted);
+
+    if let Some(session) = manager.get_capture_session_mut() {
+        session
+            .set_mic_muted(muted)
+            .map_err(|e| format!("Failed to mute microphone: {}", e))?;
+    }
+
+    recording_state.mic_muted = muted;
+    manager.set_current_recording(Some(recording_state.clone()));
+    manager.emit_state_change(app_handle, "recording:mic-muted");
+    Ok(recording_state)
+}
+
+/// Register the microphone shortcut for the length of the recording
+fn register_mic_hotkey(app_handle: &AppHandle, hotkey: &MicHotkey) {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+    let push_to_talk = hotkey.push_to_talk;
+    let registered = app_handle.global_shortcut().on_shortcut(
+        hotkey.shortcut.as_str(),
+        move |app, _shortcut, event| {
+            let muted = match (push_to_talk, event.state) {
+                (true, ShortcutState::Pressed) => Some(false),
+                (true, ShortcutState::Released) => Some(true),
+                (false, ShortcutState::Pressed) => None,
+                (false, ShortcutState::Released) => return,
+            };
+            let state = app.state::<RecordingManagerState>();
+            if let Err(e) = update_mic_muted(&state, app, muted) {
+                println!("[Recording] {}", e);
+            }
+        },
+    );
+
+    if let Err(e) = registered {
+        println!(
+            "[Recording] Failed to register microphone hotkey {}: {}",
+            hotkey.shortcut, e
+        );
+    }
+}
+
+fn unregister_mic_hotkey(app_handle: &AppHandle, hotkey: &MicHotkey) {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    if let Err(e) = app_handle
+        .global_shortcut()
+        .unregister(hotkey.shortcut.as_str())
+    {
+        println!(
+            "[Recording] Failed to remove microphone hotkey {}: {}",
+            hotkey.shortcut, e
+        );
+    }
+}
+
+/// Change the video bitrate or frame rate of the current recording
+///
+/// The encoder can't be reconfigured while it runs, so the recording continues in
+/// a new chunk with the new settings; the chunks are joined when it stops.
+#[tauri::command]
+pub async fn update_recording_encoder(
+    video_bitrate: Option<u32>,
+    frame_rate: Option<u32>,
+    state: State<'_, RecordingManagerState>,
+    app_handle: AppHandle,
+) -> Result<RecordingState, ClipForgeError> {
+    let mut manager = state.lock()?;
+
+    let mut recording_state = manager
+        .get_current_recording()
+        .ok_or(ClipForgeError::NotRecording)?;
+
+    if recording_state.status != RecordingStatus::Recording
+        && recording_state.status != RecordingStatus::Paused
+    {
+        return Err(ClipForgeError::NotRecording);
+    }
+
+    let mut config = recording_state.config.clone();
+    config.video_bitrate = video_bitrate.unwrap_or(config.video_bitrate);
+    config.frame_rate = frame_rate.unwrap_or(config.frame_rate);
+    if config.video_bitrate == recording_state.config.video_bitrate
+        && config.frame_rate == recording_state.config.frame_rate
+    {
+        return Ok(recording_state);
     }
+    config.validate()?;
 
+    manager.start_next_chunk(config.clone())?;
+
+    println!(
+        "[Recording] Encoder changed to {} kbps @ {} fps",
+        config.video_bitrate, config.frame_rate
+    );
+    recording_state.config = config;
+    manager.set_current_recording(Some(recording_state.clone()));
+    manager.emit_state_change(&app_handle, "recording:encoder-changed");
     Ok(recording_state)
 }
 
+/// Mute or unmute the microphone while recording
+#[tauri::command]
+pub async fn set_mic_muted(
+    muted: bool,
+    state: State<'_, RecordingManagerState>,
+    app_handle: AppHandle,
+) -> Result<RecordingState, ClipForgeError> {
+    update_mic_muted(&state, &app_handle, Some(muted))
+}
+
+/// What post-processing a stopped capture produced
+struct FinishedCapture {
+    output_path: PathBuf,
+    trimmed: Option<TrimmedRange>,
+    joined: Option<JoinedStingers>,
+    summary: RecordingSummary,
+}
+
+/// Stop the encoder and post-process the finished file
+///
+/// Runs without the recording manager lock: joining chunks, muxing application
+/// audio, trimming and adding stingers can each take a while.
+fn finish_capture(
+    recording_state: &RecordingState,
+    mut capture_session: ScreenCaptureSession,
+    mut chunks: Vec<PathBuf>,
+    app_audio_recorder: Option<AppAudioRecorder>,
+    cursor_recorder: Option<CursorRecorder>,
+    temp_manager: Arc<Mutex<TempFileManager>>,
+) -> Result<FinishedCapture, ClipForgeError> {
+    let mut output_path = if capture_session.is_recording() {
+        capture_session
+            .stop()
+            .map_err(|e| format!("Failed to stop capture: {}", e))?
+    } else {
+        // Stopped while paused; the last chunk was finalized by the pause
+        chunks
+            .pop()
+            .ok_or_else(|| "The paused recording has no finished chunks".to_string())?
+    };
+    let encoder_progress = capture_session.encoder_progress();
+
+    // Join the chunks written before the encoder settings changed
+    if !chunks.is_empty() {
+        chunks.push(output_path.clone());
+        match join_chunks(&chunks) {
+            Ok(joined) => output_path = joined,
+            Err(e) => println!("[Recording] Failed to join recording chunks: {}", e),
+        }
+    }
+
+    // Apply the filename template; keep the temporary name if the rename fails
+    if let Some(name) = &recording_state.output_name {
+        let mut temp = temp_manager.lock()?;
+        match temp.rename_file(&output_path, name) {
+            Ok(renamed) => output_path = renamed,
+            Err(e) => println!("[Recording] Failed to rename recording: {}", e),
+        }
+    }
+
+    // Mux the application audio into the finished recording
+    if let Some(app_audio_recorder) = app_audio_recorder {
+        if let Err(e) = app_audio_recorder.stop(&output_path) {
+            println!("[Recording] Failed to add application audio: {}", e);
+        }
+    }
+
+    // Summarise before trimming and stingers so the encoder counters and the
+    // file describe the same capture
+    let wall_duration = recording_state
+        .start_time
+        .map(|start| {
+            let now = chrono::Utc::now().timestamp_millis() as u64;
+            now.saturating_sub(start) as f64 / 1000.0
+        })
+        .unwrap_or(0.0);
+    let summary = RecordingSummary::compute(wall_duration, encoder_progress, &output_path);
+    println!("[Recording] Summary: {:?}", summary);
+
+    // Cut the pre-roll and post-roll off the finished recording
+    let mut trimmed = None;
+    if let Some(trim) = &recording_state.config.trim {
+        match trim.apply(&output_path) {
+            Ok(range) => trimmed = range,
+            Err(e) => println!("[Recording] Failed to trim recording: {}", e),
+        }
+    }
+
+    // Write the cursor track next to the finished recording
+    if let Some(cursor_recorder) = cursor_recorder {
+        if let Err(e) = cursor_recorder.stop(&output_path) {
+            println!("[Recording] Failed to save cursor track: {}", e);
+        } else if let Some(range) = trimmed {
+            if let Err(e) = trim::shift_cursor_track(&output_path, range) {
+                println!("[Recording] Failed to trim cursor track: {}", e);
+            }
+        }
+    }
+
+    // Join the intro and outro; the recording itself moves after the intro
+    let mut joined = None;
+    if let Some(stingers) = recording_state
+        .config
+        .stingers
+        .as_ref()
+        .filter(|stingers| !stingers.is_empty())
+    {
+        let encoder_args = recording_state.config.file_encoder_args();
+        match stingers.apply(&output_path, &[], &encoder_args) {
+            Ok(result) => joined = Some(result),
+            Err(e) => println!("[Recording] Failed to add stingers: {}", e),
+        }
+    }
+    if let Some(joined) = joined {
+        let sidecar = CursorTrack::sidecar_path(&output_path);
+        if let Ok(mut track) = CursorTrack::load(&sidecar) {
+            track.delay(joined.content_offset);
+            if let Err(e) = track.save(&sidecar) {
+                println!("[Recording] Failed to move cursor track: {}", e);
+            }
+        }
+    }
+
+    Ok(FinishedCapture {
+        output_path,
+        trimmed,
+        joined,
+        summary,
+    })
+}
+
 /// Stop the current recording
+///
+/// The recording is marked as stopping while the file is finalized, so the
+/// manager lock is only held to take the capture out and to publish the result.
 #[tauri::command]
 pub async fn stop_recording(
     state: State<'_, RecordingManagerState>,
     app_handle: AppHandle,
-) -> Result<RecordingState, String> {
-    let recording_state = {
-        let mut manager = state.lock().map_err(|e| e.to_string())?;
+) -> Result<RecordingState, ClipForgeError> {
+    let (mut recording_state, capture) = {
+        let mut manager = state.lock()?;
 
         let mut recording_state = manager
             .get_current_recording()
-            .ok_or_else(|| "No active recording".to_string())?;
-
-        // Stop the capture session
-        if let Some(mut capture_session) = manager.capture_session.take() {
-            let output_path = capture_session
-                .stop()
-                .map_err(|e| format!("Failed to stop capture: {}", e))?;
-            recording_state.file_path = Some(output_path.to_string_lossy().to_string());
+            .ok_or(ClipForgeError::NotRecording)?;
+        if recording_state.status == RecordingStatus::Stopping {
+            return Err(ClipForgeError::NotRecording);
         }
 
-        recording_state.stop();
+        // Free the reserved space for FFmpeg to finalize the file in
+        manager.disk_reservation = None;
 
         // Stop duration tracking
         manager.stop_duration_tracking();
-        manager.set_current_recording(None);
-        manager.emit_state_change(&app_handle, "recording:stopped");
+        manager.stop_window_tracking();
+        manager.stop_memory_monitoring();
+        manager.stop_idle_monitoring();
 
-        recording_state
+        recording_state.update_duration();
+        recording_state.status = RecordingStatus::Stopping;
+        manager.set_current_recording(Some(recording_state.clone()));
+        manager.emit_state_change(&app_handle, "recording:stopping");
+
+        let capture = manager.capture_session.take().map(|capture_session| {
+            (
+                capture_session,
+                std::mem::take(&mut manager.finished_chunks),
+                manager.app_audio_recorder.take(),
+                manager.cursor_recorder.take(),
+                manager.get_temp_manager(),
+            )
+        });
+        (recording_state, capture)
     };
 
+    let finished = match capture {
+        Some((capture_session, chunks, app_audio_recorder, cursor_recorder, temp_manager)) => {
+            let stopping = recording_state.clone();
+            let finished = tauri::async_runtime::spawn_blocking(move || {
+                finish_capture(
+                    &stopping,
+                    capture_session,
+                    chunks,
+                    app_audio_recorder,
+                    cursor_recorder,
+                    temp_manager,
+                )
+            })
+            .await
+            .map_err(|e| ClipForgeError::Internal(format!("Recording finalization failed: {}", e)))
+            .and_then(|finished| finished);
+            match finished {
+                Ok(finished) => Some(finished),
+                Err(e) => {
+                    // The session is gone, so the recording can't be stopped again
+                    state.lock()?.set_current_recording(None);
+                    recording_state.status = RecordingStatus::Error;
+                    let _ = app_handle.emit("recording:stopped", &recording_state);
+                    return Err(e);
+                }
+            }
+        }
+        None => None,
+    };
+
+    recording_state.stop();
+    if let Some(finished) = finished {
+        recording_state.file_path = Some(finished.output_path.to_string_lossy().to_string());
+        recording_state.summary = Some(finished.summary);
+        if let Some(range) = finished.trimmed {
+            recording_state.duration = range.duration();
+            trim::shift_markers(&mut recording_state.markers, range);
+        }
+        if let Some(joined) = finished.joined {
+            recording_state.duration = joined.duration;
+            for marker in &mut recording_state.markers {
+                marker.time += joined.content_offset;
+            }
+        }
+    }
+
+    // Save markers in the session manifest next to the recording
+    if let Some(file_path) = &recording_state.file_path {
+        let manifest = SessionManifest {
+            recording_id: recording_state.id.clone(),
+            recording_path: file_path.clone(),
+            duration: recording_state.duration,
+            markers: recording_state.markers.clone(),
+            summary: recording_state.summary.clone(),
+        };
+        if let Err(e) = manifest.save() {
+            println!("[Recording] Failed to save session manifest: {}", e);
+        }
+    }
+
+    state.lock()?.set_current_recording(None);
+    if let Some(hotkey) = &recording_state.config.mic_hotkey {
+        unregister_mic_hotkey(&app_handle, hotkey);
+    }
+    let _ = app_handle.emit("recording:stopped", &recording_state);
+    webhooks::fire(WebhookPayload::new(
+        WebhookEvent::RecordingStopped,
+        Some(recording_state.id.clone()),
+        recording_state.file_path.clone(),
+        Some(recording_state.duration),
+    ));
+
+    if let Some(file_path) = &recording_state.file_path {
+        add_recent(&app_handle, RecentKind::Recording, file_path);
+    }
+
+    // Import the finished file in the background and hand it to the timeline
+    if let Some(file_path) = recording_state
+        .file_path
+        .clone()
+        .filter(|_| recording_state.config.add_to_timeline)
+    {
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            match import_file(file_path.clone()).await {
+                Ok(metadata) => {
+                    let _ = app_handle.emit("timeline:add-clip", &metadata);
+                    println!("[Recording] Added {} to the timeline", file_path);
+                }
+                Err(e) => println!("[Recording] Failed to import recording: {}", e),
+            }
+        });
+    }
+
     Ok(recording_state)
 }
 
+/// Add a timestamped marker to the current recording
+#[tauri::command]
+pub async fn add_marker(
+    label: Option<String>,
+    state: State<'_, RecordingManagerState>,
+    app_handle: AppHandle,
+) -> Result<RecordingMarker, ClipForgeError> {
+    let mut manager = state.lock()?;
+
+    let mut recording_state = manager
+        .get_current_recording()
+        .ok_or(ClipForgeError::NotRecording)?;
+
+    if recording_state.status != RecordingStatus::Recording
+        && recording_state.status != RecordingStatus::Paused
+    {
+        return Err(ClipForgeError::NotRecording);
+    }
+
+    let marker = RecordingMarker {
+        time: recording_state.calculate_duration(),
+        label: label
+            .filter(|label| !label.trim().is_empty())
+            .unwrap_or_else(|| format!("Marker {}", recording_state.markers.len() + 1)),
+    };
+
+    recording_state.markers.push(marker.clone());
+    manager.set_current_recording(Some(recording_state));
+
+    let _ = app_handle.emit("recording:marker-added", &marker);
+    println!(
+        "[Recording] Marker '{}' at {:.2}s",
+        marker.label, marker.time
+    );
+    Ok(marker)
+}
+
+/// Get the markers saved with a finished recording
+#[tauri::command]
+pub async fn get_recording_markers(
+    file_path: String,
+) -> Result<Vec<RecordingMarker>, ClipForgeError> {
+    let path = Path::new(&file_path);
+    if !SessionManifest::path_for(path).exists() {
+        return Ok(Vec::new());
+    }
+
+    Ok(SessionManifest::load(path)?.markers)
+}
+
 /// Pause the current recording
 #[tauri::command]
 pub async fn pause_recording(
     state: State<'_, RecordingManagerState>,
     app_handle: AppHandle,
-) -> Result<RecordingState, String> {
-    let mut manager = state.lock().map_err(|e| e.to_string())?;
+) -> Result<RecordingState, ClipForgeError> {
+    let mut manager = state.lock()?;
 
     let mut recording_state = manager
         .get_current_recording()
-        .ok_or_else(|| "No active recording".to_string())?;
+        .ok_or(ClipForgeError::NotRecording)?;
 
     // Validate state transition
     recording_state.validate_can_pause()?;
 
-    // Pause the capture session
-    if let Some(_session) = manager.get_capture_session_mut() {
-        // Note: stop() in FFmpeg session, pause() in Swift via FFI
-        // For now we just track the pause state - actual pausing will be
-        // implemented when we connect the Swift FFI bridge
-        println!("[Recording] Screen capture paused (state tracked, FFI pause pending)");
-    }
+    // Stop encoding; nothing is recorded until the recording resumes
+    manager.pause_capture()?;
 
     // Update state
     recording_state.pause();
@@ -1489,23 +3009,18 @@ pub async fn pause_recording(
 pub async fn resume_recording(
     state: State<'_, RecordingManagerState>,
     app_handle: AppHandle,
-) -> Result<RecordingState, String> {
-    let mut manager = state.lock().map_err(|e| e.to_string())?;
+) -> Result<RecordingState, ClipForgeError> {
+    let mut manager = state.lock()?;
 
     let mut recording_state = manager
         .get_current_recording()
-        .ok_or_else(|| "No active recording".to_string())?;
+        .ok_or(ClipForgeError::NotRecording)?;
 
     // Validate state transition
     recording_state.validate_can_resume()?;
 
-    // Resume the capture session
-    if let Some(_session) = manager.get_capture_session_mut() {
-        // Note: For now we just track the resume state - actual resuming will be
-        // implemented when we connect the Swift FFI bridge
-        // In the future this will call the Swift bridge start method
-        println!("[Recording] Screen capture resumed (state tracked, FFI resume pending)");
-    }
+    // Continue encoding in a new chunk, joined to the others when the recording stops
+    manager.resume_capture(recording_state.config.clone())?;
 
     // Update state (this adds pause duration to total)
     recording_state.resume();
@@ -1516,31 +3031,32 @@ pub async fn resume_recording(
 
 /// Validate a recording configuration
 #[tauri::command]
-pub async fn validate_config(config: RecordingConfig) -> Result<bool, String> {
+pub async fn validate_config(config: RecordingConfig) -> Result<bool, ClipForgeError> {
     config.validate()?;
     Ok(true)
 }
 
 /// Get a configuration from a quality preset
 #[tauri::command]
-pub async fn get_preset_config(preset: QualityPreset) -> Result<RecordingConfig, String> {
+pub async fn get_preset_config(preset: QualityPreset) -> Result<RecordingConfig, ClipForgeError> {
     Ok(preset.to_config())
 }
 
 /// List all available quality presets
 #[tauri::command]
-pub async fn list_quality_presets() -> Result<Vec<String>, String> {
+pub async fn list_quality_presets() -> Result<Vec<String>, ClipForgeError> {
     Ok(vec![
         "low".to_string(),
         "medium".to_string(),
         "high".to_string(),
+        "lossless".to_string(),
         "custom".to_string(),
     ])
 }
 
 /// Get supported codecs for a given output format
 #[tauri::command]
-pub async fn get_supported_codecs(format: String) -> Result<SupportedCodecs, String> {
+pub async fn get_supported_codecs(format: String) -> Result<SupportedCodecs, ClipForgeError> {
     let (video_codecs, audio_codecs) = match format.as_str() {
         "mp4" => (
             vec!["h264".to_string(), "h265".to_string(), "hevc".to_string()],
@@ -1556,12 +3072,15 @@ pub async fn get_supported_codecs(format: String) -> Result<SupportedCodecs, Str
                 "h265".to_string(),
                 "vp8".to_string(),
                 "vp9".to_string(),
+                "ffv1".to_string(),
+                "prores".to_string(),
             ],
             vec![
                 "aac".to_string(),
                 "opus".to_string(),
                 "vorbis".to_string(),
                 "mp3".to_string(),
+                "flac".to_string(),
             ],
         ),
         "mov" => (
@@ -1573,7 +3092,12 @@ pub async fn get_supported_codecs(format: String) -> Result<SupportedCodecs, Str
             ],
             vec!["aac".to_string()],
         ),
-        _ => return Err(format!("Unsupported format: {}", format)),
+        _ => {
+            return Err(ClipForgeError::InvalidInput(format!(
+                "Unsupported format: {}",
+                format
+            )))
+        }
     };
 
     Ok(SupportedCodecs {
@@ -1595,17 +3119,19 @@ pub struct SupportedCodecs {
 
 /// Clean up orphaned temporary files from previous sessions
 #[tauri::command]
-pub async fn cleanup_orphaned_files() -> Result<usize, String> {
-    TempFileManager::cleanup_orphaned_files()
+pub async fn cleanup_orphaned_files() -> Result<usize, ClipForgeError> {
+    Ok(TempFileManager::cleanup_orphaned_files()?)
 }
 
 /// Clean up all temporary files for current session
 #[tauri::command]
-pub async fn cleanup_temp_files(state: State<'_, RecordingManagerState>) -> Result<(), String> {
-    let manager = state.lock().map_err(|e| e.to_string())?;
+pub async fn cleanup_temp_files(
+    state: State<'_, RecordingManagerState>,
+) -> Result<(), ClipForgeError> {
+    let manager = state.lock()?;
     let temp_manager = manager.get_temp_manager();
-    let mut temp_mgr = temp_manager.lock().map_err(|e| e.to_string())?;
-    temp_mgr.cleanup_all()
+    let mut temp_mgr = temp_manager.lock()?;
+    Ok(temp_mgr.cleanup_all()?)
 }
 
 /// Check available disk space before recording
@@ -1613,20 +3139,18 @@ pub async fn cleanup_temp_files(state: State<'_, RecordingManagerState>) -> Resu
 pub async fn check_disk_space(
     required_mb: u64,
     state: State<'_, RecordingManagerState>,
-) -> Result<bool, String> {
-    let manager = state.lock().map_err(|e| e.to_string())?;
+) -> Result<bool, ClipForgeError> {
+    let manager = state.lock()?;
     let temp_manager = manager.get_temp_manager();
-    let temp_mgr = temp_manager.lock().map_err(|e| e.to_string())?;
+    let temp_mgr = temp_manager.lock()?;
 
-    match temp_mgr.check_disk_space(required_mb) {
-        Ok(_) => Ok(true),
-        Err(e) => Err(e.to_string()),
-    }
+    temp_mgr.check_disk_space(required_mb)?;
+    Ok(true)
 }
 
 /// Get error details with recovery suggestions
 #[tauri::command]
-pub async fn get_error_details(error_type: String) -> Result<ErrorDetails, String> {
+pub async fn get_error_details(error_type: String) -> Result<ErrorDetails, ClipForgeError> {
     // This is a helper command for the frontend to get error details
     let error = match error_type.as_str() {
         "permission_denied" => RecordingError::PermissionDenied("resource".to_string()),
@@ -1661,6 +3185,8 @@ pub struct DiskSpaceInfo {
     pub percent_free: f64,
     pub has_sufficient_space: bool,
     pub estimated_recording_minutes: f64,
+    /// Expected disk usage (MB) per minute of recording
+    pub estimated_mb_per_minute: f64,
     pub warning_level: String, // "ok", "low", "critical"
 }
 
@@ -1679,6 +3205,11 @@ impl DiskSpaceInfo {
         (available_mb as f64 / total_bitrate_mbps) / 60.0 // Return minutes
     }
 
+    /// Estimate disk usage per minute of recording at the given bitrates
+    pub fn estimate_mb_per_minute(video_bitrate_kbps: u32, audio_bitrate_kbps: u32) -> f64 {
+        (video_bitrate_kbps + audio_bitrate_kbps) as f64 / 8.0 / 1024.0 * 60.0
+    }
+
     /// Determine warning level based on available space
     pub fn get_warning_level(available_mb: u64) -> String {
         if available_mb < 500 {
@@ -1692,77 +3223,50 @@ impl DiskSpaceInfo {
 }
 
 /// Get detailed disk space information
+///
+/// Estimates use `config` when given, so lossless recordings are sized from their
+/// resolution and frame rate rather than a bitrate
 #[tauri::command]
 pub async fn get_disk_space_info(
     video_bitrate_kbps: Option<u32>,
     audio_bitrate_kbps: Option<u32>,
-) -> Result<DiskSpaceInfo, String> {
-    // Get the temp directory path
-    let temp_dir = std::env::temp_dir();
+    config: Option<RecordingConfig>,
+) -> Result<DiskSpaceInfo, ClipForgeError> {
+    // Recordings are written to the app cache until they are saved
+    let temp_dir = super::app_cache::cache_dir("recordings")?;
+
+    let (video_br, audio_br) = match &config {
+        Some(config) => (config.estimated_video_bitrate(), config.audio_bitrate),
+        None => (
+            video_bitrate_kbps.unwrap_or(5000),
+            audio_bitrate_kbps.unwrap_or(128),
+        ),
+    };
 
     // Use platform-specific disk space check
     #[cfg(target_os = "macos")]
     {
-        use std::ffi::CString;
-        use std::mem;
-        use std::os::raw::{c_char, c_int};
-
-        #[repr(C)]
-        struct StatFs {
-            f_bsize: u32,
-            f_iosize: i32,
-            f_blocks: u64,
-            f_bfree: u64,
-            f_bavail: u64,
-            f_files: u64,
-            f_ffree: u64,
-            f_fsid: [i32; 2],
-            f_owner: u32,
-            f_type: u32,
-            f_flags: u32,
-            f_fssubtype: u32,
-            f_fstypename: [c_char; 16],
-            f_mntonname: [c_char; 1024],
-            f_mntfromname: [c_char; 1024],
-            f_reserved: [u32; 8],
-        }
-
-        extern "C" {
-            fn statfs(path: *const c_char, buf: *mut StatFs) -> c_int;
-        }
-
-        let path_str = temp_dir.to_str().ok_or("Invalid path")?;
-        let c_path = CString::new(path_str).map_err(|e| e.to_string())?;
-
-        unsafe {
-            let mut stat: StatFs = mem::zeroed();
-            if statfs(c_path.as_ptr(), &mut stat) == 0 {
-                let available_bytes = stat.f_bavail * stat.f_bsize as u64;
-                let total_bytes = stat.f_blocks * stat.f_bsize as u64;
-                let available_mb = available_bytes / 1_048_576;
-                let total_mb = total_bytes / 1_048_576;
-                let percent_free = (available_bytes as f64 / total_bytes as f64) * 100.0;
-
-                let video_br = video_bitrate_kbps.unwrap_or(5000);
-                let audio_br = audio_bitrate_kbps.unwrap_or(128);
-                let estimated_minutes =
-                    DiskSpaceInfo::estimate_recording_time(available_mb, video_br, audio_br);
-                let warning_level = DiskSpaceInfo::get_warning_level(available_mb);
-
-                Ok(DiskSpaceInfo {
-                    available_bytes,
-                    total_bytes,
-                    available_mb,
-                    total_mb,
-                    percent_free,
-                    has_sufficient_space: available_mb > 1000, // At least 1GB
-                    estimated_recording_minutes: estimated_minutes,
-                    warning_level,
-                })
-            } else {
-                Err("Failed to get disk space information".to_string())
-            }
-        }
+        let (available_bytes, total_bytes) = disk_space::disk_space(&temp_dir)
+            .ok_or_else(|| "Failed to get disk space information".to_string())?;
+        let available_mb = available_bytes / 1_048_576;
+        let total_mb = total_bytes / 1_048_576;
+        let percent_free = (available_bytes as f64 / total_bytes as f64) * 100.0;
+
+        let estimated_minutes =
+            DiskSpaceInfo::estimate_recording_time(available_mb, video_br, audio_br);
+        let warning_level = DiskSpaceInfo::get_warning_level(available_mb);
+
+        Ok(DiskSpaceInfo {
+            available_bytes,
+            total_bytes,
+            available_mb,
+            total_mb,
+            percent_free,
+            has_sufficient_space: available_mb > 1000, // At least 1GB
+            estimated_recording_minutes: estimated_minutes,
+            estimated_mb_per_minute: DiskSpaceInfo::estimate_mb_per_minute(video_br, audio_br),
+            warning_level,
+        })
     }
 
     #[cfg(not(target_os = "macos"))]
@@ -1777,23 +3281,243 @@ pub async fn get_disk_space_info(
             percent_free: 10.0,
             has_sufficient_space: true,
             estimated_recording_minutes: 300.0,
+            estimated_mb_per_minute: DiskSpaceInfo::estimate_mb_per_minute(video_br, audio_br),
             warning_level: "ok".to_string(),
         })
     }
 }
 
+// ============================================================================
+// Preflight Checks
+// ============================================================================
+
+/// A single problem found while preparing to record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightIssue {
+    /// Which check raised the issue ("config", "permission", "device", "disk", "ffmpeg")
+    pub check: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+impl PreflightIssue {
+    fn new(check: &str, message: impl Into<String>, suggestion: Option<String>) -> Self {
+        Self {
+            check: check.to_string(),
+            message: message.into(),
+            suggestion,
+        }
+    }
+}
+
+/// Combined result of every check run before a recording starts
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightReport {
+    /// True when there are no blockers
+    pub ready: bool,
+    /// Problems that will stop the recording from starting
+    pub blockers: Vec<PreflightIssue>,
+    /// Problems worth showing that don't prevent recording
+    pub warnings: Vec<PreflightIssue>,
+    pub disk_space: Option<DiskSpaceInfo>,
+}
+
+impl PreflightReport {
+    fn block(&mut self, issue: PreflightIssue) {
+        self.blockers.push(issue);
+    }
+
+    fn warn(&mut self, issue: PreflightIssue) {
+        self.warnings.push(issue);
+    }
+
+    /// Record a permission result as a blocker or warning as appropriate
+    fn check_permission(&mut self, result: PermissionResult) {
+        let suggestion = result
+            .instructions
+            .as_ref()
+            .map(|steps| steps.join("\n"))
+            .or(result.settings_url.clone());
+
+        match result.status {
+            PermissionStatus::Granted => {}
+            PermissionStatus::NotDetermined => self.warn(PreflightIssue::new(
+                "permission",
+                format!(
+                    "{:?} permission has not been requested yet",
+                    result.permission_type
+                ),
+                Some("macOS will ask for access when recording starts".to_string()),
+            )),
+            PermissionStatus::Denied | PermissionStatus::Restricted => {
+                let message = result
+                    .error_message
+                    .clone()
+                    .unwrap_or_else(|| format!("{:?} permission denied", result.permission_type));
+                self.block(PreflightIssue::new("permission", message, suggestion))
+            }
+        }
+    }
+
+    /// Record a device availability result as a blocker or warning as appropriate
+    fn check_device(&mut self, availability: DeviceAvailability) {
+        if availability.is_available {
+            return;
+        }
+
+        let message = availability
+            .error_message
+            .unwrap_or_else(|| format!("{} is not available", availability.device_type));
+        match availability.fallback_device_id {
+            Some(fallback) => self.warn(PreflightIssue::new(
+                "device",
+                message,
+                Some(format!("Recording will use '{}' instead", fallback)),
+            )),
+            None => self.block(PreflightIssue::new("device", message, None)),
+        }
+    }
+}
+
+/// Run every check needed before recording in one call
+///
+/// Covers config validation, permissions, source and camera availability, disk space
+/// and FFmpeg. `ready` is false if anything would stop `start_recording`
+#[tauri::command]
+pub async fn preflight_recording(
+    recording_type: RecordingType,
+    source_id: String,
+    config: Option<RecordingConfig>,
+    include_audio: Option<bool>,
+    state: State<'_, RecordingManagerState>,
+) -> Result<PreflightReport, ClipForgeError> {
+    use crate::commands::screen_sources::{PlatformEnumerator as ScreenEnum, SourceEnumerator};
+
+    let config = config.unwrap_or_default();
+    let mut report = PreflightReport::default();
+
+    // Configuration
+    if let Err(e) = config.validate() {
+        report.block(PreflightIssue::new("config", e, None));
+    }
+
+    // Permissions
+    let captures_screen = recording_type != RecordingType::Webcam;
+    let captures_camera = recording_type != RecordingType::Screen;
+    if captures_screen {
+        report.check_permission(PlatformPermissions::check_permission(
+            &PermissionType::Screen,
+        ));
+    }
+    if captures_camera {
+        report.check_permission(PlatformPermissions::check_permission(
+            &PermissionType::Camera,
+        ));
+    }
+    if include_audio.unwrap_or(false) {
+        report.check_permission(PlatformPermissions::check_permission(
+            &PermissionType::Microphone,
+        ));
+    }
+
+    // Devices
+    if captures_screen {
+        if source_id.starts_with("window_") {
+            let window_open = ScreenEnum::enumerate_windows_fast()
+                .map(|windows| windows.iter().any(|w| w.id == source_id))
+                .unwrap_or(false);
+            if !window_open {
+                report.block(PreflightIssue::new(
+                    "device",
+                    format!("Window '{}' is no longer open", source_id),
+                    Some("Choose another window to record".to_string()),
+                ));
+            }
+        } else {
+            report.check_device(
+                validate_device_availability("screen".to_string(), Some(source_id.clone())).await?,
+            );
+        }
+    }
+    if captures_camera {
+        let device_id = config.camera.as_ref().and_then(|c| c.device_id.clone());
+        report.check_device(validate_device_availability("camera".to_string(), device_id).await?);
+
+        if let Some(settings) = &config.camera {
+            if let Err(e) = resolve_camera(settings) {
+                report.block(PreflightIssue::new("device", e, None));
+            }
+        }
+    }
+
+    // Disk space
+    {
+        let manager = state.lock()?;
+        let temp_manager = manager.get_temp_manager();
+        let temp_mgr = temp_manager.lock()?;
+        if let Err(e) = temp_mgr.check_disk_space(0) {
+            report.block(PreflightIssue::new(
+                "disk",
+                e.user_message(),
+                e.recovery_suggestion(),
+            ));
+        }
+    }
+    match get_disk_space_info(None, None, Some(config.clone())).await {
+        Ok(info) => {
+            let message = format!(
+                "Only {} MB free (about {:.0} minutes of recording)",
+                info.available_mb, info.estimated_recording_minutes
+            );
+            let suggestion = Some("Free up disk space before recording".to_string());
+            match info.warning_level.as_str() {
+                "critical" => report.block(PreflightIssue::new("disk", message, suggestion)),
+                "low" => report.warn(PreflightIssue::new("disk", message, suggestion)),
+                _ if config.lossless.is_some() && info.estimated_recording_minutes < 30.0 => {
+                    report.warn(PreflightIssue::new(
+                        "disk",
+                        format!(
+                            "Lossless recording uses about {:.0} MB per minute; only {:.0} minutes fit on disk",
+                            info.estimated_mb_per_minute, info.estimated_recording_minutes
+                        ),
+                        Some("Free up disk space or use a lossy preset".to_string()),
+                    ))
+                }
+                _ => {}
+            }
+            report.disk_space = Some(info);
+        }
+        Err(e) => report.warn(PreflightIssue::new("disk", e, None)),
+    }
+
+    // FFmpeg
+    if super::ffmpeg_utils::find_ffmpeg().is_none() {
+        report.block(PreflightIssue::new(
+            "ffmpeg",
+            "FFmpeg not found",
+            Some("Install FFmpeg (for example with `brew install ffmpeg`)".to_string()),
+        ));
+    }
+
+    report.ready = report.blockers.is_empty();
+    Ok(report)
+}
+
 /// Save PiP recording metadata to JSON file
 #[tauri::command]
 pub async fn save_pip_metadata(
     metadata: String,
     state: State<'_, RecordingManagerState>,
-) -> Result<String, String> {
+) -> Result<String, ClipForgeError> {
     use std::fs;
     use std::io::Write;
 
-    let manager = state.lock().map_err(|e| e.to_string())?;
+    let manager = state.lock()?;
     let temp_manager = manager.get_temp_manager();
-    let temp_mgr = temp_manager.lock().map_err(|e| e.to_string())?;
+    let temp_mgr = temp_manager.lock()?;
 
     // Create unique filename with timestamp
     let timestamp = std::time::SystemTime::now()
@@ -1803,7 +3527,7 @@ pub async fn save_pip_metadata(
     let filename = format!("pip_metadata_{}.json", timestamp);
 
     // Get temp directory path
-    let file_path = temp_mgr.temp_dir.join(&filename);
+    let file_path = temp_mgr.root().join(&filename);
 
     // Write metadata to file
     let mut file = fs::File::create(&file_path)
@@ -1823,12 +3547,13 @@ pub async fn save_pip_metadata(
     // Return absolute file path
     file_path
         .to_str()
-        .ok_or_else(|| "Failed to convert path to string".to_string())
         .map(|s| s.to_string())
+        .ok_or_else(|| "Failed to convert path to string".into())
 }
 
 /// Composite screen + webcam recordings into a single PiP video
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn composite_pip_recording(
     screen_path: String,
     webcam_path: String,
@@ -1839,60 +3564,19 @@ pub async fn composite_pip_recording(
     screen_height: u32,
     webcam_width: Option<u32>,
     webcam_height: Option<u32>,
-) -> Result<String, String> {
+) -> Result<String, ClipForgeError> {
     use std::path::{Path, PathBuf};
-    use std::process::Command;
 
     let ffmpeg_path =
-        super::ffmpeg_utils::find_ffmpeg().ok_or_else(|| "FFmpeg not found".to_string())?;
-
-    let size_factor = match size.as_str() {
-        "small" => 0.12,
-        "large" => 0.24,
-        "medium" => 0.18,
-        _ => 0.18,
-    };
-
-    let mut overlay_width =
-        (screen_width as f64 * size_factor).round().clamp(32.0, screen_width as f64) as u32;
-
-    let webcam_aspect = if let (Some(w), Some(h)) = (webcam_width, webcam_height) {
-        if h > 0 {
-            w as f64 / h as f64
-        } else {
-            16.0 / 9.0
-        }
-    } else {
-        16.0 / 9.0
-    };
-
-    let mut overlay_height =
-        (overlay_width as f64 / webcam_aspect).round().clamp(32.0, screen_height as f64) as u32;
-
-    // Ensure even dimensions for H.264 compatibility
-    if overlay_width % 2 != 0 {
-        overlay_width = overlay_width.saturating_sub(1).max(2);
-    }
-    if overlay_height % 2 != 0 {
-        overlay_height = overlay_height.saturating_sub(1).max(2);
-    }
-
-    let edge_padding: u32 = 20;
-    let (overlay_x, overlay_y) = match position.as_str() {
-        "topLeft" => (edge_padding, edge_padding),
-        "topRight" => (
-            screen_width.saturating_sub(overlay_width + edge_padding),
-            edge_padding,
-        ),
-        "bottomLeft" => (
-            edge_padding,
-            screen_height.saturating_sub(overlay_height + edge_padding),
-        ),
-        "bottomRight" | _ => (
-            screen_width.saturating_sub(overlay_width + edge_padding),
-            screen_height.saturating_sub(overlay_height + edge_padding),
-        ),
-    };
+        super::ffmpeg_utils::find_ffmpeg().ok_or_else(ClipForgeError::ffmpeg_missing)?;
+
+    let layout = PipLayout::calculate(
+        &position,
+        &size,
+        screen_width,
+        screen_height,
+        webcam_width.zip(webcam_height),
+    );
 
     let screen_path_buf = PathBuf::from(&screen_path);
     let output_path = {
@@ -1904,102 +3588,43 @@ pub async fn composite_pip_recording(
         parent.join(format!("{}_pip.mp4", stem))
     };
 
-    println!(
-        "[PiPComposite] screen={} webcam={} output={}",
-        screen_path,
-        webcam_path,
-        output_path.display()
-    );
-    println!(
-        "[PiPComposite] position={} size={} -> {}x{} at ({}, {})",
-        position, size, overlay_width, overlay_height, overlay_x, overlay_y
-    );
-
-    let mut filter_segments = vec![format!(
-        "[1:v]scale={}:{}[cam]",
-        overlay_width, overlay_height
-    ), format!(
-        "[0:v][cam]overlay={}:{}:format=auto[outv]",
-        overlay_x, overlay_y
-    )];
-
-    let include_audio = include_webcam_audio.unwrap_or(false);
-    if include_audio {
-        filter_segments.push(
-            "[0:a][1:a]amix=inputs=2:duration=longest:dropout_transition=2[outa]".to_string(),
-        );
-    }
-
-    let filter_complex = filter_segments.join(";");
-
-    let mut command = Command::new(&ffmpeg_path);
-    command
-        .arg("-i")
-        .arg(&screen_path)
-        .arg("-i")
-        .arg(&webcam_path)
-        .arg("-filter_complex")
-        .arg(&filter_complex)
-        .arg("-map")
-        .arg("[outv]")
-        .arg("-c:v")
-        .arg("libx264")
-        .arg("-preset")
-        .arg("medium")
-        .arg("-crf")
-        .arg("20")
-        .arg("-movflags")
-        .arg("+faststart");
-
-    if include_audio {
-        command
-            .arg("-map")
-            .arg("[outa]")
-            .arg("-c:a")
-            .arg("aac")
-            .arg("-b:a")
-            .arg("192k")
-            .arg("-shortest");
-    } else {
-        command
-            .arg("-map")
-            .arg("0:a?")
-            .arg("-c:a")
-            .arg("copy");
-    }
-
-    command.arg("-y").arg(&output_path);
-
-    let output = command
-        .output()
-        .map_err(|e| format!("Failed to execute FFmpeg for PiP compositing: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("FFmpeg PiP compositing failed: {}", stderr));
-    }
+    pip::composite(
+        &ffmpeg_path,
+        &screen_path,
+        &webcam_path,
+        &PipComposition::fixed(layout),
+        include_webcam_audio.unwrap_or(false),
+        &output_path,
+    )?;
 
     output_path
         .to_str()
-        .ok_or_else(|| "Failed to convert output path to string".to_string())
         .map(|s| s.to_string())
+        .ok_or_else(|| "Failed to convert output path to string".into())
 }
 
 /// Save webcam recording from blob data
+///
+/// With `frame_rate`, the variable frame rate MediaRecorder output is re-encoded
+/// to a constant-rate H.264 MP4 so it stays in sync with the screen recording.
 #[tauri::command]
 pub async fn save_webcam_recording(
     data: Vec<u8>,
     mime_type: String,
     duration: f64,
+    audio_filters: Option<AudioFilterSettings>,
+    frame_rate: Option<u32>,
     state: State<'_, RecordingManagerState>,
-) -> Result<String, String> {
+) -> Result<String, ClipForgeError> {
     use std::fs;
     use std::io::Write;
     use std::process::Command;
 
-    let manager = state.lock().map_err(|e| e.to_string())?;
-    let temp_manager = manager.get_temp_manager();
-    let mut temp_mgr = temp_manager.lock().map_err(|e| e.to_string())?;
+    if frame_rate.is_some_and(|fps| fps == 0 || fps > 120) {
+        return Err(ClipForgeError::InvalidInput(
+            "Frame rate must be between 1 and 120 fps".to_string(),
+        ));
+    }
 
     // Determine file extension from MIME type
     let extension = if mime_type.contains("webm") {
@@ -2016,11 +3641,23 @@ pub async fn save_webcam_recording(
         .unwrap()
         .as_secs();
     let temp_filename = format!("webcam_recording_{}_temp.{}", timestamp, extension);
-    let final_filename = format!("webcam_recording_{}.{}", timestamp, extension);
-
-    // Get temp directory path (direct field access)
-    let temp_file_path = temp_mgr.temp_dir.join(&temp_filename);
-    let final_file_path = temp_mgr.temp_dir.join(&final_filename);
+    // Constant frame rate output is always re-encoded to MP4
+    let final_extension = if frame_rate.is_some() {
+        "mp4"
+    } else {
+        extension
+    };
+    let final_filename = format!("webcam_recording_{}.{}", timestamp, final_extension);
+
+    // Both files live in a session directory of their own. The locks are only
+    // held to create it, so the re-encode below doesn't block other commands.
+    let session_dir = {
+        let temp_manager = state.lock()?.get_temp_manager();
+        let mut temp_mgr = temp_manager.lock()?;
+        temp_mgr.create_session("webcam_recording")?
+    };
+    let temp_file_path = session_dir.join(&temp_filename);
+    let mut final_file_path = session_dir.join(&final_filename);
 
     // Write blob data to temporary file
     let mut file =
@@ -2037,22 +3674,72 @@ pub async fn save_webcam_recording(
     // Remux with FFmpeg to embed duration metadata
     // This ensures the file has proper duration information
     let ffmpeg_path =
-        super::ffmpeg_utils::find_ffmpeg().ok_or_else(|| "FFmpeg not found".to_string())?;
+        super::ffmpeg_utils::find_ffmpeg().ok_or_else(ClipForgeError::ffmpeg_missing)?;
 
-    let ffmpeg_output = Command::new(&ffmpeg_path)
-        .arg("-i")
-        .arg(&temp_file_path)
-        .arg("-c")
-        .arg("copy") // Copy streams without re-encoding
+    let mut command = Command::new(&ffmpeg_path);
+    command.arg("-i").arg(&temp_file_path);
+
+    let audio_chain = audio_filters
+        .as_ref()
+        .and_then(|filters| filters.filter_chain());
+    if let Some(fps) = frame_rate {
+        // Duplicate or drop frames onto a fixed cadence, then re-encode
+        command
+            .arg("-vf")
+            .arg(format!("fps={}", fps))
+            .arg("-vsync")
+            .arg("cfr")
+            .arg("-c:v")
+            .arg("libx264")
+            .arg("-preset")
+            .arg("veryfast")
+            .arg("-crf")
+            .arg("18")
+            .arg("-pix_fmt")
+            .arg("yuv420p");
+        if let Some(chain) = &audio_chain {
+            command.arg("-af").arg(chain);
+        }
+        command
+            .arg("-c:a")
+            .arg("aac")
+            .arg("-movflags")
+            .arg("+faststart");
+    } else {
+        match audio_chain {
+            Some(chain) => {
+                // Audio has to be re-encoded to apply filters; video is still copied
+                let audio_codec = if extension == "webm" {
+                    "libopus"
+                } else {
+                    "aac"
+                };
+                command
+                    .arg("-c:v")
+                    .arg("copy")
+                    .arg("-af")
+                    .arg(chain)
+                    .arg("-c:a")
+                    .arg(audio_codec);
+            }
+            None => {
+                command.arg("-c").arg("copy"); // Copy streams without re-encoding
+            }
+        }
+    }
+
+    let ffmpeg_output = command
         .arg("-t")
         .arg(duration.to_string()) // Set duration
         .arg("-y") // Overwrite output file
         .arg(&final_file_path)
-        .output()
+        .watched_output(JobKind::Encode)
         .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
 
     if !ffmpeg_output.status.success() {
-        let stderr = String::from_utf8_lossy(&ffmpeg_output.stderr);        // If FFmpeg fails, use the original file anyway
+        let stderr = String::from_utf8_lossy(&ffmpeg_output.stderr); // If FFmpeg fails, use the original file anyway
+        let _ = fs::remove_file(&final_file_path);
+        final_file_path.set_extension(extension);
         fs::rename(&temp_file_path, &final_file_path)
             .map_err(|e| format!("Failed to rename temp file: {}", e))?;
     } else {
@@ -2060,12 +3747,9 @@ pub async fn save_webcam_recording(
         let _ = fs::remove_file(&temp_file_path);
     }
 
-    // Track final file in the temp manager
-    temp_mgr.active_files.push(final_file_path.clone());
-
     // Return absolute file path
     final_file_path
         .to_str()
-        .ok_or_else(|| "Failed to convert path to string".to_string())
         .map(|s| s.to_string())
+        .ok_or_else(|| "Failed to convert path to string".into())
 }
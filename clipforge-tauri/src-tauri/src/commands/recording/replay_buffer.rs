@@ -0,0 +1,368 @@
+// Replay buffer ("clip that!")
+//
+// Captures continuously into a ring of short fragmented MP4 segments on disk, so
+// the last few minutes can be kept after the fact. save_replay, or the global
+// hotkey, stitches the newest segments into a normal recording without
+// re-encoding.
+
+use super::screen_capture::{ScreenCaptureSession, SegmentOutput};
+use super::{configure_window_capture, default_true, RecordingConfig, RecordingManagerState};
+use crate::commands::app_cache;
+use crate::commands::metadata::extract_metadata;
+use crate::commands::quick_edit::concat_copy;
+use crate::commands::recents::{add_recent, RecentKind};
+use crate::commands::video_import::import_file;
+use crate::error::ClipForgeError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+/// Length of each buffered segment; a multiple of the 2 second keyframe interval
+const SEGMENT_SECS: u32 = 4;
+
+/// Seconds of history kept when none is given
+pub const DEFAULT_REPLAY_SECS: u32 = 120;
+
+/// Longest history the buffer will keep
+const MAX_REPLAY_SECS: u32 = 30 * 60;
+
+/// Segment file name pattern inside the buffer directory
+const SEGMENT_PATTERN: &str = "replay_%03d.mp4";
+
+fn default_replay_secs() -> u32 {
+    DEFAULT_REPLAY_SECS
+}
+
+fn default_hotkey() -> Option<String> {
+    Some("CmdOrCtrl+Shift+R".to_string())
+}
+
+/// What the replay buffer captures and how much it keeps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayBufferSettings {
+    /// Screen or window source to capture
+    pub source_id: String,
+    /// Seconds of history to keep
+    #[serde(default = "default_replay_secs")]
+    pub buffer_secs: u32,
+    /// Encoding settings; the default recording config when omitted
+    #[serde(default)]
+    pub config: Option<RecordingConfig>,
+    #[serde(default = "default_true")]
+    pub include_audio: bool,
+    /// Global shortcut that saves a replay, or None for no shortcut
+    #[serde(default = "default_hotkey")]
+    pub hotkey: Option<String>,
+}
+
+/// Current state of the replay buffer
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayBufferStatus {
+    pub active: bool,
+    pub source_id: Option<String>,
+    pub buffer_secs: u32,
+    /// Seconds of history currently available
+    pub buffered_secs: f64,
+    pub hotkey: Option<String>,
+}
+
+/// A replay written to disk, payload of "replay:saved"
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedReplay {
+    pub file_path: String,
+    /// Duration in seconds
+    pub duration: f64,
+}
+
+/// Rolling capture session and its segment directory
+pub struct ReplayBuffer {
+    session: Option<ScreenCaptureSession>,
+    settings: Option<ReplayBufferSettings>,
+    segment_dir: PathBuf,
+    started_at: Option<Instant>,
+}
+
+impl ReplayBuffer {
+    pub fn new() -> Self {
+        Self {
+            session: None,
+            settings: None,
+            segment_dir: app_cache::cache_path("replay"),
+            started_at: None,
+        }
+    }
+
+    fn start(&mut self, settings: ReplayBufferSettings) -> Result<(), String> {
+        self.stop();
+
+        if settings.buffer_secs == 0 || settings.buffer_secs > MAX_REPLAY_SECS {
+            return Err(format!(
+                "Replay length must be between 1 and {} seconds",
+                MAX_REPLAY_SECS
+            ));
+        }
+
+        let mut config = settings.config.clone().unwrap_or_default();
+        if config.lossless.is_some() {
+            return Err("The replay buffer does not support lossless recording".to_string());
+        }
+        config.stream_url = None;
+        config.validate()?;
+
+        // Start from an empty ring so old segments never end up in a replay
+        let _ = fs::remove_dir_all(&self.segment_dir);
+        self.segment_dir = app_cache::cache_dir("replay")?;
+
+        let mut session = ScreenCaptureSession::new(
+            settings.source_id.clone(),
+            self.segment_dir.join(SEGMENT_PATTERN),
+            config,
+        );
+        if settings.source_id.starts_with("window_") {
+            configure_window_capture(&mut session, &settings.source_id);
+        }
+        // One extra segment covers the one FFmpeg is currently writing
+        session.set_segment_output(SegmentOutput {
+            segment_secs: SEGMENT_SECS,
+            segment_count: settings.buffer_secs.div_ceil(SEGMENT_SECS) + 1,
+        });
+        session
+            .start(settings.include_audio)
+            .map_err(|e| format!("Failed to start replay buffer: {}", e))?;
+
+        println!(
+            "[ReplayBuffer] Buffering the last {}s of {}",
+            settings.buffer_secs, settings.source_id
+        );
+        self.session = Some(session);
+        self.settings = Some(settings);
+        self.started_at = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Stop capturing and delete the buffered segments
+    pub fn stop(&mut self) {
+        if let Some(mut session) = self.session.take() {
+            if let Err(e) = session.stop() {
+                println!("[ReplayBuffer] Failed to stop capture: {}", e);
+            }
+            let _ = fs::remove_dir_all(&self.segment_dir);
+            println!("[ReplayBuffer] Stopped");
+        }
+        self.settings = None;
+        self.started_at = None;
+    }
+
+    fn status(&self) -> ReplayBufferStatus {
+        let buffer_secs = self
+            .settings
+            .as_ref()
+            .map_or(DEFAULT_REPLAY_SECS, |settings| settings.buffer_secs);
+
+        ReplayBufferStatus {
+            active: self.session.is_some(),
+            source_id: self
+                .settings
+                .as_ref()
+                .map(|settings| settings.source_id.clone()),
+            buffer_secs,
+            buffered_secs: self.started_at.map_or(0.0, |started| {
+                started.elapsed().as_secs_f64().min(buffer_secs as f64)
+            }),
+            hotkey: self
+                .settings
+                .as_ref()
+                .and_then(|settings| settings.hotkey.clone()),
+        }
+    }
+
+    /// Buffered segments, oldest first
+    fn segments(&self) -> Result<Vec<PathBuf>, String> {
+        let entries = fs::read_dir(&self.segment_dir)
+            .map_err(|e| format!("Failed to read replay directory: {}", e))?;
+
+        // Segment numbers wrap around, so order by when each was last written
+        let mut segments: Vec<(std::time::SystemTime, PathBuf)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                let path = entry.path();
+                (path.extension().is_some_and(|ext| ext == "mp4") && metadata.len() > 0)
+                    .then_some((metadata.modified().ok()?, path))
+            })
+            .collect();
+        segments.sort();
+        Ok(segments.into_iter().map(|(_, path)| path).collect())
+    }
+}
+
+impl Default for ReplayBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ReplayBuffer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Thread-safe replay buffer type
+pub type ReplayBufferState = Arc<Mutex<ReplayBuffer>>;
+
+/// Write the buffered history to a recording file
+async fn save(app: &AppHandle, output_path: Option<String>) -> Result<SavedReplay, String> {
+    let (segments, add_to_timeline) = {
+        let buffer = app.state::<ReplayBufferState>();
+        let buffer = buffer.lock().map_err(|e| e.to_string())?;
+        let settings = buffer
+            .settings
+            .as_ref()
+            .ok_or_else(|| "The replay buffer is not running".to_string())?;
+        let add_to_timeline = settings
+            .config
+            .as_ref()
+            .is_some_and(|config| config.add_to_timeline);
+        (buffer.segments()?, add_to_timeline)
+    };
+    if segments.is_empty() {
+        return Err("Nothing has been buffered yet".to_string());
+    }
+
+    let output_path = match output_path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let manager = app.state::<RecordingManagerState>();
+            let manager = manager.lock().map_err(|e| e.to_string())?;
+            let temp_manager = manager.get_temp_manager();
+            let mut temp = temp_manager.lock().map_err(|e| e.to_string())?;
+            temp.create_temp_file("replay")
+                .map_err(|e| format!("Failed to create replay file: {}", e))?
+        }
+    };
+    concat_copy(&segments, &output_path)?;
+
+    let file_path = output_path.to_string_lossy().to_string();
+    let duration = extract_metadata(file_path.clone()).await?.duration;
+    let replay = SavedReplay {
+        file_path,
+        duration,
+    };
+    println!(
+        "[ReplayBuffer] Saved {:.1}s replay to {}",
+        replay.duration, replay.file_path
+    );
+    let _ = app.emit("replay:saved", &replay);
+    add_recent(app, RecentKind::Recording, &replay.file_path);
+
+    // Hand the replay to the timeline like a finished recording
+    if add_to_timeline {
+        match import_file(replay.file_path.clone()).await {
+            Ok(metadata) => {
+                let _ = app.emit("timeline:add-clip", &metadata);
+            }
+            Err(e) => println!("[ReplayBuffer] Failed to import replay: {}", e),
+        }
+    }
+
+    Ok(replay)
+}
+
+fn unregister_hotkey(app: &AppHandle, hotkey: Option<&str>) {
+    if let Some(hotkey) = hotkey {
+        if let Err(e) = app.global_shortcut().unregister(hotkey) {
+            println!("[ReplayBuffer] Failed to remove hotkey {}: {}", hotkey, e);
+        }
+    }
+}
+
+/// Start buffering a source, replacing any running replay buffer
+#[tauri::command]
+pub async fn start_replay_buffer(
+    settings: ReplayBufferSettings,
+    state: State<'_, ReplayBufferState>,
+    app: AppHandle,
+) -> Result<ReplayBufferStatus, ClipForgeError> {
+    let hotkey = settings.hotkey.clone();
+    let status = {
+        let mut buffer = state.lock().map_err(|e| e.to_string())?;
+        unregister_hotkey(
+            &app,
+            buffer
+                .settings
+                .as_ref()
+                .and_then(|settings| settings.hotkey.as_deref()),
+        );
+        buffer.start(settings)?;
+        buffer.status()
+    };
+
+    if let Some(hotkey) = hotkey {
+        let registered =
+            app.global_shortcut()
+                .on_shortcut(hotkey.as_str(), |app, _shortcut, event| {
+                    if event.state != ShortcutState::Pressed {
+                        return;
+                    }
+                    let app = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = save(&app, None).await {
+                            println!("[ReplayBuffer] {}", e);
+                            let _ = app.emit("replay:error", e);
+                        }
+                    });
+                });
+        if let Err(e) = registered {
+            state.lock().map_err(|e| e.to_string())?.stop();
+            return Err(ClipForgeError::InvalidInput(format!(
+                "Failed to register hotkey {}: {}",
+                hotkey, e
+            )));
+        }
+    }
+
+    Ok(status)
+}
+
+/// Stop the replay buffer and discard its history
+#[tauri::command]
+pub async fn stop_replay_buffer(
+    state: State<'_, ReplayBufferState>,
+    app: AppHandle,
+) -> Result<(), ClipForgeError> {
+    let mut buffer = state.lock().map_err(|e| e.to_string())?;
+    unregister_hotkey(
+        &app,
+        buffer
+            .settings
+            .as_ref()
+            .and_then(|settings| settings.hotkey.as_deref()),
+    );
+    buffer.stop();
+    Ok(())
+}
+
+/// Save the buffered history as a recording, to `output_path` or a new temp file
+#[tauri::command]
+pub async fn save_replay(
+    output_path: Option<String>,
+    app: AppHandle,
+) -> Result<SavedReplay, ClipForgeError> {
+    Ok(save(&app, output_path).await?)
+}
+
+/// Get the replay buffer status
+#[tauri::command]
+pub async fn get_replay_buffer_status(
+    state: State<'_, ReplayBufferState>,
+) -> Result<ReplayBufferStatus, ClipForgeError> {
+    Ok(state.lock().map_err(|e| e.to_string())?.status())
+}
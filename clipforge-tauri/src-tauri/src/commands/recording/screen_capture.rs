@@ -2,16 +2,28 @@
 
 // Screen capture implementation using FFmpeg with AVFoundation on macOS
 
+use super::super::camera_sources::CameraCaptureSettings;
+use super::super::dry_run;
+use super::super::ffmpeg_args;
 use super::super::ffmpeg_utils;
-use super::{RecordingConfig, RecordingError};
+use super::super::process_runner::{JobKind, WatchedOutput};
+use super::summary::EncoderProgress;
+use super::{ColorMode, RecordingConfig, RecordingError};
 #[cfg(target_os = "macos")]
 use crate::capture::ffi;
 use std::io::{BufRead, BufReader, ErrorKind, Write};
 use std::path::PathBuf;
 use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
+/// Name of the crop filter instance used for window recording, so it can be moved
+const WINDOW_CROP_FILTER: &str = "crop@window";
+
+/// Name of the volume filter that mutes the microphone while recording
+const MIC_VOLUME_FILTER: &str = "volume@mic";
+
 /// Input mode for FFmpeg
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum InputMode {
@@ -32,6 +44,64 @@ pub enum EncodingMode {
     RealTime,
 }
 
+/// Rolling segment output used by the replay buffer
+///
+/// FFmpeg cycles through `segment_count` files of `segment_secs` each, so the
+/// newest `segment_secs * segment_count` seconds are always on disk.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentOutput {
+    pub segment_secs: u32,
+    pub segment_count: u32,
+}
+
+/// Maps capture timestamps onto the constant rate FFmpeg reads raw frames at
+///
+/// ScreenCaptureKit only delivers a frame when the screen changes, while raw
+/// stdin input is read at a fixed `-framerate`. Frames are repeated to cover
+/// gaps between presentation timestamps and dropped when they arrive faster
+/// than the frame rate, so the video lasts as long as the capture did and stays
+/// in step with the audio input.
+#[derive(Debug)]
+struct FramePacer {
+    frame_rate: f64,
+    /// Presentation timestamp that lands on the first frame slot
+    first_pts: Option<f64>,
+    /// Presentation timestamp of the previous frame
+    last_pts: f64,
+    /// Next frame slot FFmpeg will read
+    next_slot: u64,
+}
+
+impl FramePacer {
+    fn new(frame_rate: u32) -> Self {
+        Self {
+            frame_rate: frame_rate.max(1) as f64,
+            first_pts: None,
+            last_pts: 0.0,
+            next_slot: 0,
+        }
+    }
+
+    /// How many times to write the frame with presentation timestamp `pts`
+    fn copies_for(&mut self, pts: f64) -> u64 {
+        let mut first_pts = *self.first_pts.get_or_insert(pts);
+        // Timestamps going backwards mean the source restarted; carry on from here
+        if pts < self.last_pts {
+            first_pts = pts - self.next_slot as f64 / self.frame_rate;
+            self.first_pts = Some(first_pts);
+        }
+        self.last_pts = pts;
+
+        let slot = ((pts - first_pts) * self.frame_rate).round() as u64;
+        if slot < self.next_slot {
+            return 0;
+        }
+        let copies = slot - self.next_slot + 1;
+        self.next_slot = slot + 1;
+        copies
+    }
+}
+
 /// Platform-specific screen capture implementation
 pub struct ScreenCaptureSession {
     /// FFmpeg process handle
@@ -42,14 +112,34 @@ pub struct ScreenCaptureSession {
     config: RecordingConfig,
     /// Source ID (screen or window)
     source_id: String,
-    /// Window bounds for cropping (x, y, width, height)
+    /// Window bounds for cropping (x, y, width, height), in points
     window_bounds: Option<(i32, i32, u32, u32)>,
+    /// Pixels per point of the screen being cropped (2.0 on Retina displays)
+    window_scale_factor: f64,
     /// Screen device to record from (for window recording)
     screen_device: Option<String>,
     /// Input mode (AVFoundation or raw stdin)
     input_mode: InputMode,
     /// Encoding mode (CFR, VFR, or real-time)
     encoding_mode: EncodingMode,
+    /// Camera device name and format to capture instead of a screen
+    camera: Option<(String, CameraCaptureSettings)>,
+    /// Write rolling segments (output path is a `%03d` pattern) instead of one file
+    segment_output: Option<SegmentOutput>,
+    /// Whether the microphone is silenced
+    mic_muted: bool,
+    /// Audio flag passed to `start`, reused when continuing in a new chunk
+    include_audio: bool,
+    /// Latest progress counters reported by the running FFmpeg process
+    progress: Arc<Mutex<EncoderProgress>>,
+    /// Counters from earlier chunks of this recording
+    previous_progress: EncoderProgress,
+    /// Seconds of video in earlier chunks, where the elapsed-time overlay resumes
+    elapsed_offset: f64,
+    /// Thread forwarding FFmpeg's stderr, joined once FFmpeg exits
+    stderr_thread: Option<thread::JoinHandle<()>>,
+    /// Spaces raw stdin frames by their capture timestamps
+    frame_pacer: FramePacer,
 }
 
 impl ScreenCaptureSession {
@@ -58,12 +148,22 @@ impl ScreenCaptureSession {
         Self {
             ffmpeg_process: None,
             output_path,
-            config,
             source_id,
             window_bounds: None,
+            window_scale_factor: 1.0,
             screen_device: None,
             input_mode: InputMode::AVFoundation, // Default to AVFoundation for backward compatibility
             encoding_mode: EncodingMode::ConstantFrameRate, // Default to CFR
+            camera: None,
+            segment_output: None,
+            mic_muted: false,
+            include_audio: false,
+            progress: Arc::new(Mutex::new(EncoderProgress::default())),
+            previous_progress: EncoderProgress::default(),
+            elapsed_offset: 0.0,
+            stderr_thread: None,
+            frame_pacer: FramePacer::new(config.frame_rate),
+            config,
         }
     }
 
@@ -77,6 +177,16 @@ impl ScreenCaptureSession {
         self.encoding_mode = mode;
     }
 
+    /// Capture from a camera (by AVFoundation device name) with the given format
+    pub fn set_camera(&mut self, device_name: String, settings: CameraCaptureSettings) {
+        self.camera = Some((device_name, settings));
+    }
+
+    /// Write rolling segments instead of a single file
+    pub fn set_segment_output(&mut self, segments: SegmentOutput) {
+        self.segment_output = Some(segments);
+    }
+
     /// Detect the number of camera devices before screens in AVFoundation
     #[cfg(target_os = "macos")]
     fn detect_camera_count() -> usize {
@@ -89,7 +199,7 @@ impl ScreenCaptureSession {
                 .arg("-i")
                 .arg("")
                 .stderr(Stdio::piped())
-                .output()
+                .watched_output(JobKind::Probe)
             {
                 let stderr = String::from_utf8_lossy(&output.stderr);
                 let mut camera_count = 0;
@@ -122,8 +232,85 @@ impl ScreenCaptureSession {
     }
 
     /// Set window bounds for cropping (used for window recording)
-    pub fn set_window_bounds(&mut self, x: i32, y: i32, width: u32, height: u32) {
+    ///
+    /// Bounds are in points relative to the screen; `scale_factor` converts them to
+    /// the pixels AVFoundation delivers for that screen.
+    pub fn set_window_bounds(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        scale_factor: f64,
+    ) {
+        self.window_bounds = Some((x, y, width, height));
+        self.window_scale_factor = if scale_factor > 0.0 {
+            scale_factor
+        } else {
+            1.0
+        };
+    }
+
+    /// Move the window crop while recording
+    ///
+    /// `x` and `y` are in points relative to the recorded screen, like the bounds
+    /// given to `set_window_bounds`. The crop size stays the same; the new position
+    /// is clamped to the screen and sent to FFmpeg's crop filter over stdin.
+    pub fn move_window(&mut self, x: i32, y: i32) -> Result<(), RecordingError> {
+        let Some((_, _, width, height)) = self.window_bounds else {
+            return Ok(());
+        };
         self.window_bounds = Some((x, y, width, height));
+
+        if !matches!(self.input_mode, InputMode::AVFoundation) {
+            return Ok(());
+        }
+
+        // 'c' reads one "<target> <time> <command> <argument>" line; time -1 applies it now
+        let scale = self.window_scale_factor;
+        let x = (x as f64 * scale).round() as i64;
+        let y = (y as f64 * scale).round() as i64;
+        let commands = format!(
+            "c{filter} -1 x clip({x},0,iw-ow)\nc{filter} -1 y clip({y},0,ih-oh)\n",
+            filter = WINDOW_CROP_FILTER,
+            x = x,
+            y = y
+        );
+
+        let Some(stdin) = self.stdin_mut() else {
+            return Ok(());
+        };
+        stdin
+            .write_all(commands.as_bytes())
+            .and_then(|_| stdin.flush())
+            .map_err(|e| RecordingError::IoError(format!("Failed to move window crop: {}", e)))
+    }
+
+    /// Mute or unmute the microphone
+    ///
+    /// Before the recording starts this sets the initial state; while recording
+    /// the change is sent to FFmpeg's volume filter over stdin.
+    pub fn set_mic_muted(&mut self, muted: bool) -> Result<(), RecordingError> {
+        self.mic_muted = muted;
+
+        // Raw stdin mode uses stdin for frames, not commands
+        if !matches!(self.input_mode, InputMode::AVFoundation) {
+            return Ok(());
+        }
+
+        let command = format!(
+            "c{} -1 volume {}\n",
+            MIC_VOLUME_FILTER,
+            if muted { 0 } else { 1 }
+        );
+
+        let Some(stdin) = self.stdin_mut() else {
+            return Ok(());
+        };
+        stdin
+            .write_all(command.as_bytes())
+            .and_then(|_| stdin.flush())
+            .map_err(|e| RecordingError::IoError(format!("Failed to mute microphone: {}", e)))
     }
 
     /// Set the screen device to record from (used for window recording)
@@ -131,6 +318,16 @@ impl ScreenCaptureSession {
         self.screen_device = Some(device);
     }
 
+    /// Set the screen to record from by its display ID (used for window recording)
+    pub fn set_screen_display(&mut self, display_id: u32) {
+        #[cfg(target_os = "macos")]
+        if let Some(device) = Self::display_to_avfoundation_device(display_id) {
+            self.screen_device = Some(device.to_string());
+        }
+        #[cfg(not(target_os = "macos"))]
+        let _ = display_id;
+    }
+
     /// Start the screen capture
     pub fn start(&mut self, include_audio: bool) -> Result<(), RecordingError> {
         if self.ffmpeg_process.is_some() {
@@ -145,6 +342,8 @@ impl ScreenCaptureSession {
 
         println!("[ScreenCapture] FFmpeg found at: {}", ffmpeg_path.display());
 
+        self.include_audio = include_audio;
+        self.frame_pacer = FramePacer::new(self.config.frame_rate);
         let mut command = self.build_ffmpeg_command(&ffmpeg_path, include_audio)?;
 
         // Start FFmpeg process with stdin piped so we can send commands
@@ -159,11 +358,22 @@ impl ScreenCaptureSession {
 
         if let Some(stderr) = child.stderr.take() {
             let output_path = self.output_path.clone();
-            thread::spawn(move || {
+            let progress = Arc::clone(&self.progress);
+            *progress.lock().unwrap_or_else(|e| e.into_inner()) = EncoderProgress::default();
+            self.stderr_thread = Some(thread::spawn(move || {
                 let reader = BufReader::new(stderr);
                 for line in reader.lines() {
                     match line {
-                        Ok(line) => println!("[ScreenCapture][ffmpeg] {}", line),
+                        Ok(line) => {
+                            // Progress updates are separated by carriage returns
+                            if let Some(latest) = line.rsplit('\r').find_map(EncoderProgress::parse)
+                            {
+                                if let Ok(mut progress) = progress.lock() {
+                                    *progress = latest;
+                                }
+                            }
+                            println!("[ScreenCapture][ffmpeg] {}", line);
+                        }
                         Err(err) => {
                             println!(
                                 "[ScreenCapture][ffmpeg] Error reading stderr for {}: {}",
@@ -178,7 +388,7 @@ impl ScreenCaptureSession {
                     "[ScreenCapture][ffmpeg] Stderr stream closed for {}",
                     output_path.display()
                 );
-            });
+            }));
         }
 
         // If FFmpeg exits immediately, surface the failure instead of pretending the session started.
@@ -194,13 +404,22 @@ impl ScreenCaptureSession {
         Ok(())
     }
 
+    /// The FFmpeg command `start` would run, as argv, without starting it
+    pub fn preview_command(&self, include_audio: bool) -> Result<Vec<String>, RecordingError> {
+        let ffmpeg_path = ffmpeg_utils::find_ffmpeg().unwrap_or_else(|| PathBuf::from("ffmpeg"));
+        let command = self.build_ffmpeg_command(&ffmpeg_path, include_audio)?;
+        Ok(dry_run::command_line(&command))
+    }
+
     /// Build the FFmpeg command for screen recording
     fn build_ffmpeg_command(
         &self,
         ffmpeg_path: &PathBuf,
         include_audio: bool,
     ) -> Result<Command, RecordingError> {
-        let mut command = Command::new(ffmpeg_path);        println!("[ScreenCapture]   FFmpeg path: {}", ffmpeg_path.display());        println!(
+        let mut command = Command::new(ffmpeg_path);
+        println!("[ScreenCapture]   FFmpeg path: {}", ffmpeg_path.display());
+        println!(
             "[ScreenCapture]   Output path: {}",
             self.output_path.display()
         );
@@ -223,7 +442,39 @@ impl ScreenCaptureSession {
 
         // Add output file
         command.arg("-y"); // Overwrite output file if it exists
-        command.arg(self.output_path.to_str().unwrap());
+        match (self.segment_output, self.config.stream_url.as_deref()) {
+            (Some(segments), _) => {
+                // Fragmented segments stay readable while FFmpeg is still writing them
+                command.arg("-f").arg("segment");
+                command
+                    .arg("-segment_time")
+                    .arg(segments.segment_secs.to_string());
+                command
+                    .arg("-segment_wrap")
+                    .arg(segments.segment_count.to_string());
+                command.arg("-segment_format").arg("mp4");
+                command
+                    .arg("-segment_format_options")
+                    .arg("movflags=+frag_keyframe+empty_moov+default_base_moof");
+                command.arg("-reset_timestamps").arg("1");
+                command.arg(self.output_path.to_str().unwrap());
+            }
+            (None, Some(stream_url)) => {
+                // Record locally and stream from one encode. A failed stream leg is
+                // dropped instead of taking the local recording down with it.
+                command.arg("-map").arg("0:v").arg("-map").arg("0:a?");
+                command.arg("-flags").arg("+global_header");
+                command.arg("-f").arg("tee");
+                command.arg(tee_output_target(
+                    self.output_path.to_str().unwrap(),
+                    &self.config.output_format,
+                    stream_url,
+                ));
+            }
+            (None, None) => {
+                command.arg(self.output_path.to_str().unwrap());
+            }
+        }
 
         // Log the complete command for debugging
         Ok(command)
@@ -235,6 +486,24 @@ impl ScreenCaptureSession {
         // Set input format to avfoundation
         command.arg("-f").arg("avfoundation");
 
+        // Cameras are opened by name at their requested format
+        if let Some((device_name, camera)) = &self.camera {
+            command
+                .arg("-framerate")
+                .arg(camera.frame_rate.to_string())
+                .arg("-video_size")
+                .arg(format!("{}x{}", camera.width, camera.height));
+
+            let input_device = if include_audio {
+                format!("{}:0", device_name)
+            } else {
+                device_name.clone()
+            };
+            command.arg("-i").arg(input_device);
+            command.arg("-pix_fmt").arg("yuv420p");
+            return;
+        }
+
         // Set frame rate
         command
             .arg("-framerate")
@@ -243,14 +512,21 @@ impl ScreenCaptureSession {
         // Use wallclock timestamps to keep frame timing stable
         command.arg("-use_wallclock_as_timestamps").arg("1");
 
+        // Omit the cursor when a synthetic cursor will be rendered on export
+        command
+            .arg("-capture_cursor")
+            .arg(if self.config.capture_cursor { "1" } else { "0" });
+
         // Parse source ID to determine capture type
         if self.source_id.starts_with("screen_") || self.source_id.starts_with("display_") {
             // Determine the correct AVFoundation device index
-            let av_index = if let Some(screen_id) = self.source_id.strip_prefix("screen_") {                screen_id.parse::<usize>().ok()
+            let av_index = if let Some(screen_id) = self.source_id.strip_prefix("screen_") {
+                screen_id.parse::<usize>().ok()
             } else if let Some(display_str) = self.source_id.strip_prefix("display_") {
                 if let Ok(display_id) = display_str.parse::<u32>() {
                     Self::display_to_avfoundation_device(display_id)
-                } else {                    None
+                } else {
+                    None
                 }
             } else {
                 None
@@ -275,7 +551,8 @@ impl ScreenCaptureSession {
                 format!("{}:0", resolved_index)
             } else {
                 resolved_index.to_string()
-            };            command.arg("-i").arg(input_device);
+            };
+            command.arg("-i").arg(input_device);
         } else if self.source_id.starts_with("window_") {
             // Window capture: record the screen containing the window, then crop
             let screen_device = self
@@ -292,7 +569,8 @@ impl ScreenCaptureSession {
                 format!("{}:0", screen_device)
             } else {
                 screen_device.to_string()
-            };            command.arg("-i").arg(input_device);
+            };
+            command.arg("-i").arg(input_device);
         } else {
             // Default to first available screen
             // Detect camera count to find first screen device
@@ -324,10 +602,12 @@ impl ScreenCaptureSession {
             Ok(displays) => {
                 for (idx, display) in displays.iter().enumerate() {
                     if display.display_id == display_id {
-                        let av_index = camera_count + idx;                        return Some(av_index);
+                        let av_index = camera_count + idx;
+                        return Some(av_index);
                     }
-                }            }
-            Err(e) => {            }
+                }
+            }
+            Err(e) => {}
         }
 
         None
@@ -346,7 +626,8 @@ impl ScreenCaptureSession {
         let video_size = format!("{}x{}", self.config.width, self.config.height);
         command.arg("-video_size").arg(&video_size);
 
-        // Set frame rate
+        // Frames are read at a constant rate; write_frame repeats or drops them
+        // to follow their capture timestamps
         command
             .arg("-framerate")
             .arg(self.config.frame_rate.to_string());
@@ -359,50 +640,8 @@ impl ScreenCaptureSession {
         // This will be added as part of encoding args, but we note it here for clarity
     }
 
-    /// Add encoding arguments based on configuration
-    fn add_encoding_args(&self, command: &mut Command) {
-        // Build video filters to satisfy codec requirements (even dimensions, optional crop)
-        let mut video_filters: Vec<String> = Vec::new();
-
-        if let Some((x, y, width, height)) = self.window_bounds {
-            let mut crop_width = width;
-            let mut crop_height = height;
-
-            if crop_width % 2 != 0 && crop_width > 1 {
-                crop_width -= 1;            }
-
-            if crop_height % 2 != 0 && crop_height > 1 {
-                crop_height -= 1;            }            video_filters.push(format!("crop={}:{}:{}:{}", crop_width, crop_height, x, y));
-        }
-
-        // Normalize timestamps and frame cadence
-        video_filters.push("setpts=PTS-STARTPTS".to_string());
-        video_filters.push(format!("fps={}", self.config.frame_rate));
-
-        let mut target_width = self.config.width;
-        if target_width % 2 != 0 {
-            let adjusted = if target_width > 1 {
-                target_width - 1
-            } else {
-                2
-            };            target_width = adjusted;
-        }
-
-        let mut target_height = self.config.height;
-        if target_height % 2 != 0 {
-            let adjusted = if target_height > 1 {
-                target_height - 1
-            } else {
-                2
-            };            target_height = adjusted;
-        }
-
-        video_filters.push(format!("scale={}:{}", target_width, target_height));
-
-        if !video_filters.is_empty() {
-            command.arg("-vf").arg(video_filters.join(","));
-        }
-
+    /// Add video codec, bitrate and keyframe arguments for lossy encoding
+    fn add_video_codec_args(&self, command: &mut Command) {
         // Video codec
         command.arg("-c:v").arg(&self.config.video_codec);
 
@@ -442,12 +681,110 @@ impl ScreenCaptureSession {
             };
             command.arg("-crf").arg(crf_value);
         }
+    }
+
+    /// Add encoding arguments based on configuration
+    fn add_encoding_args(&self, command: &mut Command) {
+        // Build video filters to satisfy codec requirements (even dimensions, optional crop)
+        let mut video_filters: Vec<String> = Vec::new();
+
+        if let Some((x, y, width, height)) = self.window_bounds {
+            let scale = self.window_scale_factor;
+            let x = (x as f64 * scale).round().max(0.0) as i32;
+            let y = (y as f64 * scale).round().max(0.0) as i32;
+            let mut crop_width = (width as f64 * scale).round() as u32;
+            let mut crop_height = (height as f64 * scale).round() as u32;
+
+            if !crop_width.is_multiple_of(2) && crop_width > 1 {
+                crop_width -= 1;
+            }
+
+            if !crop_height.is_multiple_of(2) && crop_height > 1 {
+                crop_height -= 1;
+            }
+            video_filters.push(format!(
+                "{}={}:{}:{}:{}",
+                WINDOW_CROP_FILTER, crop_width, crop_height, x, y
+            ));
+        }
+
+        // Normalize timestamps and frame cadence
+        video_filters.push("setpts=PTS-STARTPTS".to_string());
+        video_filters.push(format!("fps={}", self.config.frame_rate));
+
+        let mut target_width = self.config.width;
+        if target_width % 2 != 0 {
+            let adjusted = if target_width > 1 {
+                target_width - 1
+            } else {
+                2
+            };
+            target_width = adjusted;
+        }
+
+        let mut target_height = self.config.height;
+        if target_height % 2 != 0 {
+            let adjusted = if target_height > 1 {
+                target_height - 1
+            } else {
+                2
+            };
+            target_height = adjusted;
+        }
+
+        video_filters.push(format!("scale={}:{}", target_width, target_height));
+
+        // Drawn after scaling so the font size is in output pixels
+        if let Some(overlay) = &self.config.timestamp_overlay {
+            video_filters.push(overlay.video_filter(self.elapsed_offset));
+        }
+
+        if let Some(filter) = self.config.color_mode.video_filter() {
+            video_filters.push(filter);
+        }
+
+        if !video_filters.is_empty() {
+            command.arg("-vf").arg(video_filters.join(","));
+        }
+
+        match self.config.lossless {
+            Some(mode) => {
+                // Lossless modes use fixed quality settings instead of a bitrate
+                command.arg("-c:v").arg(mode.video_codec());
+                command.args(mode.encoder_args());
+            }
+            None => self.add_video_codec_args(command),
+        }
+
+        // Color tags, so players don't guess BT.709 for wide-gamut or PQ recordings
+        let color_mode = self.config.color_mode;
+        command.args(color_mode.tag_args());
+        if let Some(pix_fmt) = color_mode.pixel_format(self.config.lossless.is_some()) {
+            command.arg("-pix_fmt").arg(pix_fmt);
+        }
+
+        // User tuning flags go last so they override the defaults above
+        if !self.config.extra_ffmpeg_args.is_empty() {
+            println!(
+                "[ScreenCapture]   Extra args: {}",
+                ffmpeg_args::describe(&self.config.extra_ffmpeg_args)
+            );
+            command.args(&self.config.extra_ffmpeg_args);
+        }
+        if color_mode == ColorMode::Pq
+            && self.config.lossless.is_none()
+            && matches!(self.config.output_format.as_str(), "mp4" | "mov")
+        {
+            // QuickTime only plays HEVC tagged as hvc1
+            command.arg("-tag:v").arg("hvc1");
+        }
 
         // Variable frame rate support
         if self.encoding_mode == EncodingMode::VariableFrameRate {
             // Enable variable frame rate (VFR) mode
             // This allows FFmpeg to encode frames at their actual timestamps
-            command.arg("-vsync").arg("vfr");        } else {
+            command.arg("-vsync").arg("vfr");
+        } else {
             // Constant frame rate (CFR) mode - default
             command.arg("-vsync").arg("cfr");
         }
@@ -459,7 +796,8 @@ impl ScreenCaptureSession {
             // Reduce buffer size for lower latency
             command
                 .arg("-bufsize")
-                .arg(format!("{}k", self.config.video_bitrate / 2));        }
+                .arg(format!("{}k", self.config.video_bitrate / 2));
+        }
 
         // Audio codec (if configured)
         if !self.config.audio_codec.is_empty() {
@@ -473,9 +811,21 @@ impl ScreenCaptureSession {
             command
                 .arg("-ac")
                 .arg(self.config.audio_channels.to_string());
-            command
-                .arg("-af")
-                .arg("aresample=async=1:first_pts=0");
+            let mut audio_filter = format!(
+                "{}=volume={},aresample=async=1:first_pts=0",
+                MIC_VOLUME_FILTER,
+                if self.mic_muted { 0 } else { 1 }
+            );
+            if let Some(chain) = self.config.audio_filters.filter_chain() {
+                audio_filter.push(',');
+                audio_filter.push_str(&chain);
+            }
+            command.arg("-af").arg(audio_filter);
+        }
+
+        // Streaming and segmented sessions set the format on their own outputs
+        if self.config.stream_url.is_some() || self.segment_output.is_some() {
+            return;
         }
 
         // Output format
@@ -503,7 +853,8 @@ impl ScreenCaptureSession {
                 use std::io::Write;
 
                 // Method 1: Try sending 'q' to stdin (FFmpeg's quit command)
-                if let Some(mut stdin) = child.stdin.take() {                    let _ = stdin.write_all(b"q\n");
+                if let Some(mut stdin) = child.stdin.take() {
+                    let _ = stdin.write_all(b"q\n");
                     let _ = stdin.flush();
                     drop(stdin); // Close stdin
 
@@ -515,11 +866,13 @@ impl ScreenCaptureSession {
                 let mut exited = false;
                 for _ in 0..50 {
                     match child.try_wait() {
-                        Ok(Some(status)) => {                            exited = true;
+                        Ok(Some(status)) => {
+                            exited = true;
                             break;
                         }
                         Ok(None) => thread::sleep(Duration::from_millis(100)),
-                        Err(e) => {                            return Err(RecordingError::CaptureStopFailed(e.to_string()));
+                        Err(e) => {
+                            return Err(RecordingError::CaptureStopFailed(e.to_string()));
                         }
                     }
                 }
@@ -546,10 +899,7 @@ impl ScreenCaptureSession {
                                 let _ = Command::new("pkill")
                                     .arg("-9")
                                     .arg("-f")
-                                    .arg(&format!(
-                                        "ffmpeg.*{}",
-                                        self.output_path.to_string_lossy()
-                                    ))
+                                    .arg(&format!("ffmpeg.*{}", self.output_path.to_string_lossy()))
                                     .output();
                             }
                             Ok(None) => continue,
@@ -574,11 +924,22 @@ impl ScreenCaptureSession {
                 .wait()
                 .map_err(|e| RecordingError::CaptureStopFailed(e.to_string()))?;
 
-            if !status.success() {                return Err(RecordingError::CaptureStopFailed(format!(
+            // Let the stderr thread record FFmpeg's final progress line
+            if let Some(stderr_thread) = self.stderr_thread.take() {
+                let _ = stderr_thread.join();
+            }
+
+            if !status.success() {
+                return Err(RecordingError::CaptureStopFailed(format!(
                     "FFmpeg exited with status: {status}"
                 )));
             }
 
+            // Segmented output is a pattern, not a single file
+            if self.segment_output.is_some() {
+                return Ok(self.output_path.clone());
+            }
+
             // Verify the file exists and has content
             if !self.output_path.exists() {
                 return Err(RecordingError::CaptureStopFailed(
@@ -587,9 +948,7 @@ impl ScreenCaptureSession {
             }
 
             let file_metadata = std::fs::metadata(&self.output_path).map_err(|e| {
-                RecordingError::CaptureStopFailed(format!(
-                    "Failed to read output metadata: {e}"
-                ))
+                RecordingError::CaptureStopFailed(format!("Failed to read output metadata: {e}"))
             })?;
 
             if file_metadata.len() == 0 {
@@ -608,6 +967,79 @@ impl ScreenCaptureSession {
         }
     }
 
+    /// Check that the running encoder can be stopped and continued in a new chunk
+    fn check_can_split(&self) -> Result<(), RecordingError> {
+        if self.ffmpeg_process.is_none() {
+            return Err(RecordingError::NotRecording);
+        }
+        if self.segment_output.is_some() {
+            return Err(RecordingError::InvalidConfig(
+                "Segmented captures can't be split into chunks".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Stop the encoder and finalize the current chunk, keeping the session so
+    /// `resume_chunk` can continue it. Returns the path of the finished chunk.
+    pub fn pause_chunk(&mut self) -> Result<PathBuf, RecordingError> {
+        self.check_can_split()?;
+
+        let finished = self.stop();
+        let chunk = self
+            .progress
+            .lock()
+            .map(|mut progress| std::mem::take(&mut *progress))
+            .unwrap_or_default();
+        self.previous_progress = self.previous_progress + chunk;
+        self.elapsed_offset += chunk.frames as f64 / self.config.frame_rate.max(1) as f64;
+        finished
+    }
+
+    /// Continue a paused session in a new chunk at `output_path`
+    pub fn resume_chunk(
+        &mut self,
+        config: RecordingConfig,
+        output_path: PathBuf,
+    ) -> Result<(), RecordingError> {
+        if self.ffmpeg_process.is_some() {
+            return Err(RecordingError::AlreadyRecording);
+        }
+        self.config = config;
+        self.output_path = output_path;
+        self.start(self.include_audio)
+    }
+
+    /// Continue the recording in a new file with different encoder settings
+    ///
+    /// A running FFmpeg encoder can't change its bitrate or frame rate, so this
+    /// one is stopped and its file finalized before a new process starts on
+    /// `output_path`. The chunks never overlap; the moments spent restarting
+    /// the encoder are not recorded. Returns the path of the finished chunk.
+    pub fn start_next_chunk(
+        &mut self,
+        config: RecordingConfig,
+        output_path: PathBuf,
+    ) -> Result<PathBuf, RecordingError> {
+        self.check_can_split()?;
+
+        // Resume even if the old chunk failed to finalize, so the recording goes on
+        let finished = self.pause_chunk();
+        self.resume_chunk(config, output_path)?;
+        finished
+    }
+
+    /// Frame counters reported by FFmpeg, including earlier chunks
+    pub fn encoder_progress(&self) -> EncoderProgress {
+        let current = self.progress.lock().map(|p| *p).unwrap_or_default();
+        self.previous_progress + current
+    }
+
+    /// PID of the running FFmpeg process
+    pub fn process_id(&self) -> Option<u32> {
+        self.ffmpeg_process.as_ref().map(|child| child.id())
+    }
+
     /// Check if currently recording
     pub fn is_recording(&self) -> bool {
         self.ffmpeg_process.is_some()
@@ -624,15 +1056,19 @@ impl ScreenCaptureSession {
         self.ffmpeg_process.as_mut()?.stdin.as_mut()
     }
 
-    /// Write a raw frame to FFmpeg stdin
+    /// Write a raw frame to FFmpeg stdin at the time given by its capture timestamp
+    ///
+    /// The frame is written once per frame slot it covers, so it may be
+    /// repeated to fill a gap since the previous frame or skipped entirely.
     ///
     /// # Arguments
     /// * `frame_data` - Raw RGB24 pixel data (width * height * 3 bytes)
+    /// * `pts` - Presentation timestamp in seconds (e.g. `Frame::timestamp`)
     ///
     /// # Returns
     /// * `Ok(())` - Frame written successfully
     /// * `Err(RecordingError)` - Error writing frame (EPIPE = FFmpeg terminated)
-    pub fn write_frame(&mut self, frame_data: &[u8]) -> Result<(), RecordingError> {
+    pub fn write_frame(&mut self, frame_data: &[u8], pts: f64) -> Result<(), RecordingError> {
         if self.input_mode != InputMode::RawStdin {
             return Err(RecordingError::CaptureStopFailed(
                 "Cannot write frames in AVFoundation mode".to_string(),
@@ -650,13 +1086,19 @@ impl ScreenCaptureSession {
             )));
         }
 
+        let copies = self.frame_pacer.copies_for(pts);
+        if copies == 0 {
+            return Ok(());
+        }
+
         // Now get mutable borrow for stdin
         let stdin = self.stdin_mut().ok_or_else(|| {
             RecordingError::CaptureStopFailed("FFmpeg stdin not available".to_string())
         })?;
 
-        // Write frame data to stdin
-        match stdin.write_all(frame_data) {
+        // Write frame data to stdin, once per frame slot it covers
+        let written = (0..copies).try_for_each(|_| stdin.write_all(frame_data));
+        match written {
             Ok(()) => {
                 // Flush to ensure frame is sent to FFmpeg
                 stdin.flush().map_err(|e| {
@@ -712,3 +1154,62 @@ impl Drop for ScreenCaptureSession {
         }
     }
 }
+
+/// Build the tee muxer target that writes the local file and the live stream.
+/// The stream slave runs through a FIFO that drops packets when the network
+/// stalls and ignores failures, so only the local file can abort the recording.
+fn tee_output_target(local_path: &str, output_format: &str, stream_url: &str) -> String {
+    let escape = |value: &str| {
+        value
+            .replace('\\', "\\\\")
+            .replace('|', "\\|")
+            .replace('[', "\\[")
+            .replace(']', "\\]")
+    };
+
+    let mut local_options = format!("f={}", output_format);
+    if output_format == "mp4" {
+        local_options.push_str(":movflags=+faststart+frag_keyframe+empty_moov");
+    }
+
+    let stream_format = super::stream_muxer(stream_url).unwrap_or("flv");
+    format!(
+        "[{}]{}|[f={}:onfail=ignore:use_fifo=1:fifo_options=drop_pkts_on_overflow=1\\:attempt_recovery=1\\:recover_any_error=1]{}",
+        local_options,
+        escape(local_path),
+        stream_format,
+        escape(stream_url)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_pacer_follows_pts_gaps() {
+        let mut pacer = FramePacer::new(30);
+
+        // First frame fills the first slot
+        assert_eq!(pacer.copies_for(12.0), 1);
+        // Next frame on time
+        assert_eq!(pacer.copies_for(12.0 + 1.0 / 30.0), 1);
+        // Screen idle for 100ms: the previous frame is held for the gap
+        assert_eq!(pacer.copies_for(12.0 + 4.0 / 30.0), 3);
+        // Arrives before the next slot is due: dropped
+        assert_eq!(pacer.copies_for(12.0 + 4.2 / 30.0), 0);
+        // Next slot after the drop
+        assert_eq!(pacer.copies_for(12.0 + 5.0 / 30.0), 1);
+    }
+
+    #[test]
+    fn test_frame_pacer_restarted_source() {
+        let mut pacer = FramePacer::new(30);
+
+        assert_eq!(pacer.copies_for(50.0), 1);
+        assert_eq!(pacer.copies_for(50.0 + 1.0 / 30.0), 1);
+        // Timestamps jump back; pacing carries on from the next slot
+        assert_eq!(pacer.copies_for(1.0), 1);
+        assert_eq!(pacer.copies_for(1.0 + 2.0 / 30.0), 2);
+    }
+}
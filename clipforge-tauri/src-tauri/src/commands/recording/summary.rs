@@ -0,0 +1,166 @@
+// Recording statistics summary
+//
+// FFmpeg prints a progress line (`frame=  300 fps= 30 ... drop=2 speed=1x`) while
+// encoding; the capture session keeps the latest counters. When a recording stops
+// they are combined with the captured file's size and probed duration into a
+// summary that is returned, emitted with "recording:stopped" and saved in the
+// session manifest. The summary is taken before pre-roll trimming and stingers,
+// so it describes what was captured rather than the edited file.
+
+use super::super::ffmpeg_utils::find_ffprobe;
+use super::super::process_runner::{JobKind, WatchedOutput};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::ops::Add;
+use std::path::Path;
+use std::process::Command;
+
+/// Frame counters from FFmpeg's progress output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EncoderProgress {
+    /// Frames written to the output
+    pub frames: u64,
+    /// Input frames dropped to keep the output frame rate
+    pub dropped: u64,
+    /// Frames duplicated to fill gaps in the input
+    pub duplicated: u64,
+}
+
+impl EncoderProgress {
+    /// Counters from an FFmpeg progress line, or None if it isn't one
+    pub fn parse(line: &str) -> Option<Self> {
+        // Values may be padded after the `=`, e.g. "frame=  300"
+        let field = |key: &str| -> Option<u64> {
+            let start = line.find(&format!("{}=", key))? + key.len() + 1;
+            line[start..].split_whitespace().next()?.parse().ok()
+        };
+
+        Some(Self {
+            frames: field("frame")?,
+            dropped: field("drop").unwrap_or(0),
+            duplicated: field("dup").unwrap_or(0),
+        })
+    }
+}
+
+impl Add for EncoderProgress {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            frames: self.frames + other.frames,
+            dropped: self.dropped + other.dropped,
+            duplicated: self.duplicated + other.duplicated,
+        }
+    }
+}
+
+/// Statistics for a finished recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingSummary {
+    /// Seconds from start to stop, including pauses
+    pub wall_duration: f64,
+    /// Duration of the finished file in seconds, if it could be probed
+    pub encoded_duration: Option<f64>,
+    pub encoded_frames: u64,
+    pub average_fps: Option<f64>,
+    pub dropped_frames: u64,
+    pub duplicated_frames: u64,
+    /// Average total bitrate of the file in kbit/s
+    pub average_bitrate_kbps: Option<f64>,
+    pub file_size_bytes: u64,
+}
+
+impl RecordingSummary {
+    /// Summarise a finished recording
+    pub fn compute(wall_duration: f64, progress: EncoderProgress, recording_path: &Path) -> Self {
+        let file_size_bytes = fs::metadata(recording_path).map(|m| m.len()).unwrap_or(0);
+        Self::from_measurements(
+            wall_duration,
+            probe_duration(recording_path),
+            progress,
+            file_size_bytes,
+        )
+    }
+
+    fn from_measurements(
+        wall_duration: f64,
+        encoded_duration: Option<f64>,
+        progress: EncoderProgress,
+        file_size_bytes: u64,
+    ) -> Self {
+        let encoded_duration = encoded_duration.filter(|d| *d > 0.0);
+
+        Self {
+            wall_duration,
+            encoded_duration,
+            encoded_frames: progress.frames,
+            average_fps: encoded_duration
+                .filter(|_| progress.frames > 0)
+                .map(|d| progress.frames as f64 / d),
+            dropped_frames: progress.dropped,
+            duplicated_frames: progress.duplicated,
+            average_bitrate_kbps: encoded_duration
+                .map(|d| file_size_bytes as f64 * 8.0 / d / 1000.0),
+            file_size_bytes,
+        }
+    }
+}
+
+/// Container duration of a media file in seconds
+pub(super) fn probe_duration(path: &Path) -> Option<f64> {
+    let ffprobe_path = find_ffprobe()?;
+    let output = Command::new(ffprobe_path)
+        .arg("-v")
+        .arg("error")
+        .arg("-show_entries")
+        .arg("format=duration")
+        .arg("-of")
+        .arg("default=noprint_wrappers=1:nokey=1")
+        .arg(path)
+        .watched_output(JobKind::Probe)
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_progress_line() {
+        let line = "frame=  300 fps= 30 q=23.0 size=    1024kB time=00:00:10.00 \
+                    bitrate= 838.9kbits/s dup=1 drop=4 speed=1.00x";
+        assert_eq!(
+            EncoderProgress::parse(line),
+            Some(EncoderProgress {
+                frames: 300,
+                dropped: 4,
+                duplicated: 1,
+            })
+        );
+        assert_eq!(EncoderProgress::parse("Stream #0:0: Video: h264"), None);
+    }
+
+    #[test]
+    fn test_summary_rates() {
+        let progress = EncoderProgress {
+            frames: 300,
+            dropped: 2,
+            duplicated: 0,
+        };
+        let summary = RecordingSummary::from_measurements(11.0, Some(10.0), progress, 1_250_000);
+        assert_eq!(summary.average_fps, Some(30.0));
+        assert_eq!(summary.average_bitrate_kbps, Some(1000.0));
+        assert_eq!(summary.dropped_frames, 2);
+
+        let unprobed = RecordingSummary::from_measurements(11.0, None, progress, 1_250_000);
+        assert_eq!(unprobed.average_fps, None);
+        assert_eq!(unprobed.average_bitrate_kbps, None);
+    }
+}
@@ -0,0 +1,428 @@
+// Temporary recording files
+//
+// Every recording, replay or webcam upload gets its own session directory under
+// `recordings/` in the app cache, so the files written alongside it (cursor
+// track, session manifest, chunks, application audio) stay together and are
+// removed together. Sessions in use are listed in `sessions.json` with the id
+// of the process that owns them; orphan cleanup skips sessions whose owner is
+// still running, so a second instance never deletes another's recording. Edits
+// to the list hold an exclusive lock on `sessions.lock`, so two instances
+// starting sessions at once can't drop each other's entries.
+//
+// The total size of the directory can be capped. When a new session would
+// start over the quota, sessions no running instance owns are removed oldest
+// first; if that isn't enough the session is refused.
+
+use super::super::app_cache;
+use super::disk_space::disk_space;
+use super::{filename_template, RecordingError};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Lists the sessions in use, in the temp root
+const MANIFEST_FILE: &str = "sessions.json";
+
+/// Locked while the session list is edited
+const MANIFEST_LOCK_FILE: &str = "sessions.lock";
+
+/// App cache subdirectory holding the sessions
+const RECORDINGS_DIR: &str = "recordings";
+
+/// Unowned files and sessions untouched for this long are removed at startup
+const ORPHAN_AGE: Duration = Duration::from_secs(3600);
+
+/// A session directory and the process using it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionEntry {
+    name: String,
+    pid: u32,
+}
+
+/// Manages temporary recording files with automatic cleanup
+pub struct TempFileManager {
+    root: PathBuf,
+    /// Session directories created by this instance, oldest first
+    sessions: Vec<PathBuf>,
+    /// Largest total size of the temp root in bytes (0 = unlimited)
+    quota_bytes: u64,
+}
+
+impl TempFileManager {
+    /// Create a new temporary file manager
+    pub fn new() -> Result<Self, String> {
+        // Create temp directory if it doesn't exist
+        let root = app_cache::cache_dir(RECORDINGS_DIR)?;
+
+        Ok(Self {
+            root,
+            sessions: Vec::new(),
+            quota_bytes: 0,
+        })
+    }
+
+    /// Directory holding every session
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Cap the total size of temporary files (0 = unlimited)
+    pub fn set_quota_mb(&mut self, quota_mb: u64) {
+        self.quota_bytes = quota_mb * 1_048_576;
+    }
+
+    /// Create a session directory named after `prefix`
+    pub fn create_session(&mut self, prefix: &str) -> Result<PathBuf, String> {
+        self.enforce_quota()?;
+
+        let timestamp = chrono::Utc::now().timestamp_millis();
+        let stem = format!("{}_{}", prefix, timestamp);
+        let dir = filename_template::unique_path(&self.root, &stem, "");
+        fs::create_dir(&dir).map_err(|e| format!("Failed to create session directory: {}", e))?;
+
+        let name = dir_name(&dir);
+        update_manifest(&self.root, |entries| {
+            entries.push(SessionEntry {
+                name,
+                pid: std::process::id(),
+            })
+        })?;
+
+        self.sessions.push(dir.clone());
+        Ok(dir)
+    }
+
+    /// Create a new temporary file for recording, in a session of its own
+    pub fn create_temp_file(&mut self, prefix: &str) -> Result<PathBuf, String> {
+        let dir = self.create_session(prefix)?;
+        let filename = format!("{}.mp4", dir_name(&dir));
+        Ok(dir.join(filename))
+    }
+
+    /// Rename a file within its directory, adding a suffix if the name is taken
+    pub fn rename_file(&mut self, path: &Path, stem: &str) -> Result<PathBuf, String> {
+        let dir = path.parent().unwrap_or(&self.root);
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        let renamed = filename_template::unique_path(dir, stem, extension);
+
+        fs::rename(path, &renamed).map_err(|e| format!("Failed to rename file: {}", e))?;
+        Ok(renamed)
+    }
+
+    /// Clean up a temporary file, along with its session
+    #[allow(dead_code)]
+    pub fn cleanup_file(&mut self, path: &Path) -> Result<(), String> {
+        let session = self.sessions.iter().position(|dir| path.starts_with(dir));
+        match session {
+            Some(index) => {
+                let dir = self.sessions.remove(index);
+                remove_sessions(&self.root, &[dir])
+            }
+            None if path.exists() => {
+                fs::remove_file(path).map_err(|e| format!("Failed to remove temp file: {}", e))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Clean up every session this instance created
+    pub fn cleanup_all(&mut self) -> Result<(), String> {
+        let sessions = std::mem::take(&mut self.sessions);
+        remove_sessions(&self.root, &sessions)
+    }
+
+    /// Clean up sessions and files left behind by instances that are no longer running
+    pub fn cleanup_orphaned_files() -> Result<usize, String> {
+        let root = app_cache::cache_path(RECORDINGS_DIR);
+        if !root.exists() {
+            return Ok(0);
+        }
+
+        let in_use = live_sessions(&root);
+        let mut cleaned = 0;
+        let entries =
+            fs::read_dir(&root).map_err(|e| format!("Failed to read temp directory: {}", e))?;
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = dir_name(&path);
+            if name == MANIFEST_FILE || name == MANIFEST_LOCK_FILE || in_use.contains(&name) {
+                continue;
+            }
+            if last_modified_age(&path) < ORPHAN_AGE {
+                continue;
+            }
+
+            let removed = if path.is_dir() {
+                fs::remove_dir_all(&path)
+            } else {
+                fs::remove_file(&path)
+            };
+            if removed.is_ok() {
+                cleaned += 1;
+            }
+        }
+
+        // Forget sessions whose owner has exited; checked again under the lock
+        // so sessions added since `in_use` was read are kept
+        update_manifest(&root, |entries| {
+            entries.retain(|entry| process_alive(entry.pid))
+        })?;
+
+        Ok(cleaned)
+    }
+
+    /// Check the temp directory is writable and has `required_mb` free
+    pub fn check_disk_space(&self, required_mb: u64) -> Result<(), RecordingError> {
+        let test_file = self.root.join(".diskcheck");
+        if let Err(e) = fs::write(&test_file, b"test") {
+            return Err(RecordingError::IoError(format!(
+                "Cannot write to temp directory: {}",
+                e
+            )));
+        }
+        let _ = fs::remove_file(test_file);
+
+        match disk_space(&self.root) {
+            Some((available, _)) if available < required_mb * 1_048_576 => {
+                Err(RecordingError::DiskSpaceLow {
+                    available,
+                    required: required_mb * 1_048_576,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Make room for a new session, or refuse it if the quota can't be met
+    fn enforce_quota(&self) -> Result<(), String> {
+        if self.quota_bytes == 0 {
+            return Ok(());
+        }
+
+        let mut used = dir_size(&self.root);
+        if used < self.quota_bytes {
+            return Ok(());
+        }
+
+        // Oldest sessions no running instance owns go first
+        let in_use = live_sessions(&self.root);
+        let mut unowned: Vec<(Duration, PathBuf)> = fs::read_dir(&self.root)
+            .map_err(|e| format!("Failed to read temp directory: {}", e))?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir() && !in_use.contains(&dir_name(path)))
+            .map(|path| (last_modified_age(&path), path))
+            .collect();
+        unowned.sort_by_key(|(age, _)| std::cmp::Reverse(*age));
+
+        for (_, path) in unowned {
+            if used < self.quota_bytes {
+                break;
+            }
+            let size = dir_size(&path);
+            if fs::remove_dir_all(&path).is_ok() {
+                println!("[TempFiles] Removed {} to stay under quota", path.display());
+                used = used.saturating_sub(size);
+            }
+        }
+
+        if used >= self.quota_bytes {
+            return Err(format!(
+                "Temporary recordings use {} MB, over the {} MB limit. \
+                 Save or delete earlier recordings first.",
+                used / 1_048_576,
+                self.quota_bytes / 1_048_576
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for TempFileManager {
+    fn default() -> Self {
+        Self::new().expect("Failed to create temp file manager")
+    }
+}
+
+impl Drop for TempFileManager {
+    fn drop(&mut self) {
+        // Clean up all temporary files when dropped
+        let _ = self.cleanup_all();
+    }
+}
+
+fn dir_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Delete session directories and drop them from the manifest
+fn remove_sessions(root: &Path, sessions: &[PathBuf]) -> Result<(), String> {
+    let mut errors = Vec::new();
+    for dir in sessions.iter().filter(|dir| dir.exists()) {
+        if let Err(e) = fs::remove_dir_all(dir) {
+            errors.push(format!("Failed to remove {}: {}", dir.display(), e));
+        }
+    }
+
+    let names: Vec<String> = sessions.iter().map(|dir| dir_name(dir)).collect();
+    if let Err(e) = update_manifest(root, |entries| {
+        entries.retain(|entry| !names.contains(&entry.name))
+    }) {
+        errors.push(e);
+    }
+
+    if !errors.is_empty() {
+        return Err(format!("Cleanup errors: {}", errors.join(", ")));
+    }
+    Ok(())
+}
+
+fn load_manifest(root: &Path) -> Vec<SessionEntry> {
+    fs::read_to_string(root.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Take the session list lock; it is released when the file is closed
+fn lock_manifest(root: &Path) -> Result<File, String> {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(root.join(MANIFEST_LOCK_FILE))
+        .map_err(|e| format!("Failed to open session list lock: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::io::AsRawFd;
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+            return Err(format!(
+                "Failed to lock session list: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+    }
+
+    Ok(file)
+}
+
+/// Edit the session list under its lock, replacing the file atomically
+fn update_manifest(root: &Path, edit: impl FnOnce(&mut Vec<SessionEntry>)) -> Result<(), String> {
+    let _lock = lock_manifest(root)?;
+    let mut entries = load_manifest(root);
+    edit(&mut entries);
+
+    let content = serde_json::to_string_pretty(&entries)
+        .map_err(|e| format!("Failed to serialize session list: {}", e))?;
+    let staging = root.join(format!("{}.{}", MANIFEST_FILE, std::process::id()));
+    fs::write(&staging, content).map_err(|e| format!("Failed to write session list: {}", e))?;
+    fs::rename(&staging, root.join(MANIFEST_FILE))
+        .map_err(|e| format!("Failed to write session list: {}", e))
+}
+
+/// Names of the sessions owned by running processes
+fn live_sessions(root: &Path) -> Vec<String> {
+    sessions_in_use(&load_manifest(root), process_alive)
+}
+
+fn sessions_in_use(entries: &[SessionEntry], alive: impl Fn(u32) -> bool) -> Vec<String> {
+    entries
+        .iter()
+        .filter(|entry| alive(entry.pid))
+        .map(|entry| entry.name.clone())
+        .collect()
+}
+
+/// Whether a process with this id is running
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    // Signal 0 only checks the process exists; EPERM means it does but isn't ours
+    unsafe {
+        libc::kill(pid as libc::pid_t, 0) == 0
+            || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+    }
+}
+
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    // Without a way to check, assume the owner is still running
+    true
+}
+
+/// Time since a file, or anything in a directory, was last written
+fn last_modified_age(path: &Path) -> Duration {
+    let newest = if path.is_dir() {
+        fs::read_dir(path)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| entry.metadata().ok()?.modified().ok())
+            .max()
+    } else {
+        None
+    };
+
+    newest
+        .or_else(|| fs::metadata(path).ok()?.modified().ok())
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .unwrap_or_default()
+}
+
+/// Total size of the files under a directory
+fn dir_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sessions_in_use() {
+        let entries = vec![
+            SessionEntry {
+                name: "rec_1".to_string(),
+                pid: 100,
+            },
+            SessionEntry {
+                name: "rec_2".to_string(),
+                pid: 200,
+            },
+        ];
+        assert_eq!(sessions_in_use(&entries, |pid| pid == 200), vec!["rec_2"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_process_alive() {
+        assert!(process_alive(std::process::id()));
+        // Above the largest pid any unix hands out
+        assert!(!process_alive(i32::MAX as u32));
+    }
+
+    #[test]
+    fn test_dir_size() {
+        let dir = std::env::temp_dir().join(format!("clipforge_dir_size_{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("a.bin"), [0u8; 10]).unwrap();
+        fs::write(dir.join("nested").join("b.bin"), [0u8; 5]).unwrap();
+
+        assert_eq!(dir_size(&dir), 15);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,154 @@
+// Timestamp burn-in
+//
+// Draws a clock into the recorded video with FFmpeg's drawtext filter, for
+// compliance and QA recordings where the footage itself has to show when
+// something happened. Wall-clock mode prints the local time each frame is
+// encoded (`%{localtime}`); elapsed mode prints the frame's timestamp
+// (`%{pts}`) plus the length of any earlier chunks, so it keeps counting from
+// the start of the recording when the encoder is restarted.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Monospaced font shipped with macOS; without it drawtext falls back to fontconfig
+const OVERLAY_FONT: &str = "/System/Library/Fonts/Menlo.ttc";
+
+/// Distance of the text box from the frame edges, in output pixels
+const OVERLAY_MARGIN: u32 = 16;
+
+/// What the overlay shows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TimestampMode {
+    /// Local date and time, e.g. "2024-05-01 14:03:22"
+    #[default]
+    WallClock,
+    /// Time since the recording started, e.g. "0:01:23.456"
+    Elapsed,
+}
+
+/// Corner of the frame the overlay is drawn in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OverlayPosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+/// Clock burned into a recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimestampOverlay {
+    #[serde(default)]
+    pub mode: TimestampMode,
+    #[serde(default)]
+    pub position: OverlayPosition,
+    /// Text height in output pixels
+    #[serde(default = "default_font_size")]
+    pub font_size: u32,
+}
+
+fn default_font_size() -> u32 {
+    24
+}
+
+impl Default for TimestampOverlay {
+    fn default() -> Self {
+        Self {
+            mode: TimestampMode::default(),
+            position: OverlayPosition::default(),
+            font_size: default_font_size(),
+        }
+    }
+}
+
+impl TimestampOverlay {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(8..=200).contains(&self.font_size) {
+            return Err("Timestamp overlay font size must be between 8 and 200".to_string());
+        }
+        Ok(())
+    }
+
+    /// drawtext filter for the overlay, applied after scaling to the output size
+    ///
+    /// `elapsed_offset` is the recording time, in seconds, before this chunk started.
+    pub fn video_filter(&self, elapsed_offset: f64) -> String {
+        if Path::new(OVERLAY_FONT).exists() {
+            self.filter_with_font(&format!("fontfile={}:", OVERLAY_FONT), elapsed_offset)
+        } else {
+            self.filter_with_font("", elapsed_offset)
+        }
+    }
+
+    fn filter_with_font(&self, font: &str, elapsed_offset: f64) -> String {
+        // Colons inside the expansion are escaped; %X avoids needing any in the format
+        let text = match self.mode {
+            TimestampMode::WallClock => r"%{localtime\:%Y-%m-%d %X}".to_string(),
+            TimestampMode::Elapsed if elapsed_offset > 0.0 => {
+                format!(r"%{{pts\:hms\:{:.3}}}", elapsed_offset)
+            }
+            TimestampMode::Elapsed => r"%{pts\:hms}".to_string(),
+        };
+        let m = OVERLAY_MARGIN;
+        let (x, y) = match self.position {
+            OverlayPosition::TopLeft => (m.to_string(), m.to_string()),
+            OverlayPosition::TopRight => (format!("w-tw-{}", m), m.to_string()),
+            OverlayPosition::BottomLeft => (m.to_string(), format!("h-th-{}", m)),
+            OverlayPosition::BottomRight => (format!("w-tw-{}", m), format!("h-th-{}", m)),
+        };
+
+        format!(
+            "drawtext={}text='{}':x={}:y={}:fontsize={}:fontcolor=white:\
+             box=1:boxcolor=black@0.5:boxborderw={}",
+            font,
+            text,
+            x,
+            y,
+            self.font_size,
+            m / 2
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wall_clock_filter() {
+        let overlay = TimestampOverlay::default();
+        assert_eq!(
+            overlay.filter_with_font("", 0.0),
+            "drawtext=text='%{localtime\\:%Y-%m-%d %X}':x=w-tw-16:y=h-th-16:fontsize=24:\
+              fontcolor=white:box=1:boxcolor=black@0.5:boxborderw=8"
+        );
+    }
+
+    #[test]
+    fn test_elapsed_top_left() {
+        let overlay = TimestampOverlay {
+            mode: TimestampMode::Elapsed,
+            position: OverlayPosition::TopLeft,
+            font_size: 32,
+        };
+        let filter = overlay.filter_with_font("fontfile=/f.ttc:", 0.0);
+        assert!(filter.starts_with(r"drawtext=fontfile=/f.ttc:text='%{pts\:hms}':x=16:y=16:"));
+        assert!(filter.contains("fontsize=32"));
+
+        // Later chunks continue from where the earlier ones ended
+        let filter = overlay.filter_with_font("", 90.5);
+        assert!(filter.contains(r"text='%{pts\:hms\:90.500}'"));
+    }
+
+    #[test]
+    fn test_font_size_limits() {
+        let mut overlay = TimestampOverlay::default();
+        assert!(overlay.validate().is_ok());
+        overlay.font_size = 4;
+        assert!(overlay.validate().is_err());
+    }
+}
@@ -0,0 +1,261 @@
+// Pre-roll and post-roll trimming
+//
+// The first and last seconds of a recording are usually the user reaching for
+// the record and stop buttons. When a recording asks for it, stop_recording
+// cuts them off the finished file with a stream copy, so nothing is
+// re-encoded. A stream copy can only start on a keyframe, so the cut moves
+// forward to the first keyframe at or after the pre-roll (at most one keyframe
+// interval, 2 seconds, later). Markers and the cursor track are shifted to
+// match.
+
+use super::super::cursor::CursorTrack;
+use super::super::ffmpeg_utils::{find_ffmpeg, find_ffprobe};
+use super::super::process_runner::{JobKind, WatchedOutput};
+use super::summary::probe_duration;
+use super::RecordingMarker;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Longest pre-roll or post-roll that can be trimmed, in seconds
+const MAX_TRIM_SECONDS: f64 = 60.0;
+
+/// Shortest recording left after trimming; shorter results keep the whole file
+const MIN_REMAINING_SECONDS: f64 = 1.0;
+
+/// Seconds after the pre-roll searched for a keyframe; several keyframe intervals
+const KEYFRAME_SEARCH_SECONDS: f64 = 10.0;
+
+/// Seconds cut from the start and end of every recording
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingTrim {
+    #[serde(default)]
+    pub pre_roll_seconds: f64,
+    #[serde(default)]
+    pub post_roll_seconds: f64,
+}
+
+/// Part of the original recording that was kept, in seconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrimmedRange {
+    pub start: f64,
+    pub end: f64,
+}
+
+impl TrimmedRange {
+    pub fn duration(&self) -> f64 {
+        self.end - self.start
+    }
+}
+
+impl RecordingTrim {
+    pub fn validate(&self) -> Result<(), String> {
+        for value in [self.pre_roll_seconds, self.post_roll_seconds] {
+            if !(0.0..=MAX_TRIM_SECONDS).contains(&value) {
+                return Err(format!(
+                    "Pre-roll and post-roll trims must be between 0 and {} seconds",
+                    MAX_TRIM_SECONDS
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Range to keep of a recording, or None if it should be left alone
+    fn plan(&self, duration: f64, keyframes: &[f64]) -> Option<TrimmedRange> {
+        if self.pre_roll_seconds <= 0.0 && self.post_roll_seconds <= 0.0 {
+            return None;
+        }
+
+        let start = if self.pre_roll_seconds > 0.0 {
+            *keyframes.iter().find(|t| **t >= self.pre_roll_seconds)?
+        } else {
+            0.0
+        };
+        let end = duration - self.post_roll_seconds;
+
+        (end - start >= MIN_REMAINING_SECONDS).then_some(TrimmedRange { start, end })
+    }
+
+    /// Trim the recording in place, returning the range that was kept
+    pub fn apply(&self, recording_path: &Path) -> Result<Option<TrimmedRange>, String> {
+        let duration = probe_duration(recording_path)
+            .ok_or_else(|| "Failed to read the recording duration".to_string())?;
+        let keyframes = if self.pre_roll_seconds > 0.0 {
+            keyframe_times(recording_path, self.pre_roll_seconds)?
+        } else {
+            Vec::new()
+        };
+
+        let Some(range) = self.plan(duration, &keyframes) else {
+            println!(
+                "[Trim] Recording too short to trim, keeping all {:.1}s",
+                duration
+            );
+            return Ok(None);
+        };
+
+        let ffmpeg_path = find_ffmpeg().ok_or_else(|| "FFmpeg not found".to_string())?;
+        let extension = recording_path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_string())
+            .unwrap_or_else(|| "mp4".to_string());
+        let trimmed_path = recording_path.with_extension(format!("trimmed.{}", extension));
+
+        let output = Command::new(&ffmpeg_path)
+            .arg("-y")
+            .arg("-ss")
+            .arg(format!("{:.3}", range.start))
+            .arg("-i")
+            .arg(recording_path)
+            .arg("-t")
+            .arg(format!("{:.3}", range.duration()))
+            .arg("-map")
+            .arg("0")
+            .arg("-c")
+            .arg("copy")
+            .arg("-avoid_negative_ts")
+            .arg("make_zero")
+            .arg(&trimmed_path)
+            .watched_output(JobKind::Encode)
+            .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+        if !output.status.success() {
+            let _ = fs::remove_file(&trimmed_path);
+            return Err(format!(
+                "Failed to trim recording: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        fs::rename(&trimmed_path, recording_path)
+            .map_err(|e| format!("Failed to replace recording: {}", e))?;
+
+        println!(
+            "[Trim] Kept {:.2}s-{:.2}s of {:.2}s",
+            range.start, range.end, duration
+        );
+        Ok(Some(range))
+    }
+}
+
+/// Timestamps of the video keyframes in a file from `from` seconds on
+///
+/// Only packets within `KEYFRAME_SEARCH_SECONDS` of `from` are read, so long
+/// recordings are not probed end to end.
+fn keyframe_times(path: &Path, from: f64) -> Result<Vec<f64>, String> {
+    let ffprobe_path = find_ffprobe().ok_or_else(|| "FFprobe not found".to_string())?;
+    let output = Command::new(ffprobe_path)
+        .arg("-v")
+        .arg("error")
+        .arg("-select_streams")
+        .arg("v:0")
+        .arg("-read_intervals")
+        .arg(format!("{:.3}%+{}", from, KEYFRAME_SEARCH_SECONDS))
+        .arg("-show_entries")
+        .arg("packet=pts_time,flags")
+        .arg("-of")
+        .arg("csv=p=0")
+        .arg(path)
+        .watched_output(JobKind::Probe)
+        .map_err(|e| format!("Failed to run FFprobe: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to read keyframes: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(parse_keyframes(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Keyframe times from `pts_time,flags` lines, sorted
+fn parse_keyframes(csv: &str) -> Vec<f64> {
+    let mut times: Vec<f64> = csv
+        .lines()
+        .filter_map(|line| {
+            let (time, flags) = line.split_once(',')?;
+            if !flags.contains('K') {
+                return None;
+            }
+            time.trim().parse().ok()
+        })
+        .collect();
+    times.sort_by(|a, b| a.total_cmp(b));
+    times
+}
+
+/// Move markers onto the trimmed timeline, dropping those that were cut
+pub fn shift_markers(markers: &mut Vec<RecordingMarker>, range: TrimmedRange) {
+    markers.retain(|marker| marker.time >= range.start && marker.time <= range.end);
+    for marker in markers.iter_mut() {
+        marker.time -= range.start;
+    }
+}
+
+/// Move the cursor track next to a trimmed recording onto its new timeline
+pub fn shift_cursor_track(recording_path: &Path, range: TrimmedRange) -> Result<(), String> {
+    let sidecar = CursorTrack::sidecar_path(recording_path);
+    let mut track = CursorTrack::load(&sidecar)?;
+    track
+        .samples
+        .retain(|sample| sample.t >= range.start && sample.t <= range.end);
+    for sample in track.samples.iter_mut() {
+        sample.t -= range.start;
+    }
+    track.save(&sidecar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keyframes() {
+        let csv = "2.000000,K__\n0.000000,K__\n0.033333,___\n1.000000,__\n";
+        assert_eq!(parse_keyframes(csv), vec![0.0, 2.0]);
+    }
+
+    #[test]
+    fn test_plan_snaps_to_next_keyframe() {
+        let trim = RecordingTrim {
+            pre_roll_seconds: 1.5,
+            post_roll_seconds: 2.0,
+        };
+        let keyframes = [0.0, 2.0, 4.0, 6.0];
+        assert_eq!(
+            trim.plan(10.0, &keyframes),
+            Some(TrimmedRange {
+                start: 2.0,
+                end: 8.0
+            })
+        );
+        // Nothing would be left
+        assert_eq!(trim.plan(3.0, &keyframes), None);
+    }
+
+    #[test]
+    fn test_shift_markers() {
+        let mut markers = vec![
+            RecordingMarker {
+                time: 1.0,
+                label: "cut".to_string(),
+            },
+            RecordingMarker {
+                time: 5.0,
+                label: "kept".to_string(),
+            },
+        ];
+        shift_markers(
+            &mut markers,
+            TrimmedRange {
+                start: 2.0,
+                end: 8.0,
+            },
+        );
+        assert_eq!(markers.len(), 1);
+        assert_eq!(markers[0].time, 3.0);
+    }
+}
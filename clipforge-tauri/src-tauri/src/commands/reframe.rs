@@ -0,0 +1,145 @@
+// Vertical reframing
+//
+// Crops landscape video clips to a portrait frame (9:16 by default) for Shorts,
+// TikTok and Reels exports. The crop keeps the full height and slides
+// horizontally to follow a focus region set on the clip, the webcam overlay of
+// a PiP recording, or the center of the frame. Images are letterboxed into the
+// portrait frame instead, since title cards rarely survive being cropped.
+
+use serde::{Deserialize, Serialize};
+
+/// Portrait output for an export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReframeSettings {
+    /// Output aspect ratio as width:height
+    pub aspect_width: u32,
+    pub aspect_height: u32,
+    /// Center PiP recordings on the webcam overlay when a clip has no focus region
+    pub follow_webcam: bool,
+}
+
+impl Default for ReframeSettings {
+    fn default() -> Self {
+        Self {
+            aspect_width: 9,
+            aspect_height: 16,
+            follow_webcam: true,
+        }
+    }
+}
+
+/// Part of a clip the crop should keep in view, as fractions of the frame (0.0 - 1.0)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusRegion {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl FocusRegion {
+    /// Center of the region in pixels of a `width` x `height` frame
+    pub fn center(&self, width: u32, height: u32) -> (f64, f64) {
+        (
+            (self.x + self.width / 2.0).clamp(0.0, 1.0) * width as f64,
+            (self.y + self.height / 2.0).clamp(0.0, 1.0) * height as f64,
+        )
+    }
+}
+
+/// Rectangle cropped out of a clip, in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CropRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl CropRect {
+    /// FFmpeg crop filter for this rectangle
+    pub fn filter(&self) -> String {
+        format!("crop={}:{}:{}:{}", self.width, self.height, self.x, self.y)
+    }
+}
+
+fn even(value: f64) -> u32 {
+    ((value / 2.0).floor() as u32 * 2).max(2)
+}
+
+impl ReframeSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.aspect_width == 0 || self.aspect_height == 0 {
+            return Err("Reframe aspect ratio must not be zero".to_string());
+        }
+        Ok(())
+    }
+
+    fn aspect(&self) -> f64 {
+        self.aspect_width as f64 / self.aspect_height as f64
+    }
+
+    /// Largest crop of a `width` x `height` frame with the output aspect ratio
+    pub fn crop_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let aspect = self.aspect();
+        if width as f64 / height as f64 > aspect {
+            (even(height as f64 * aspect).min(width), height - height % 2)
+        } else {
+            (width - width % 2, even(width as f64 / aspect).min(height))
+        }
+    }
+
+    /// Crop centered on `focus` (pixels), or on the frame when None, kept inside the frame
+    pub fn crop(&self, width: u32, height: u32, focus: Option<(f64, f64)>) -> CropRect {
+        let (crop_width, crop_height) = self.crop_size(width, height);
+        let (center_x, center_y) = focus.unwrap_or((width as f64 / 2.0, height as f64 / 2.0));
+        let place = |center: f64, size: u32, limit: u32| {
+            (center - size as f64 / 2.0)
+                .round()
+                .clamp(0.0, limit.saturating_sub(size) as f64) as u32
+        };
+
+        CropRect {
+            x: place(center_x, crop_width, width),
+            y: place(center_y, crop_height, height),
+            width: crop_width,
+            height: crop_height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crop_size() {
+        let reframe = ReframeSettings::default();
+        assert_eq!(reframe.crop_size(1920, 1080), (606, 1080));
+        assert_eq!(reframe.crop_size(3840, 2160), (1214, 2160));
+        // Already taller than 9:16: keep the width
+        assert_eq!(reframe.crop_size(720, 1600), (720, 1280));
+    }
+
+    #[test]
+    fn test_crop_follows_focus_and_stays_inside() {
+        let reframe = ReframeSettings::default();
+        assert_eq!(reframe.crop(1920, 1080, None).x, 657);
+
+        let focus = FocusRegion {
+            x: 0.7,
+            y: 0.7,
+            width: 0.2,
+            height: 0.2,
+        };
+        let crop = reframe.crop(1920, 1080, Some(focus.center(1920, 1080)));
+        assert_eq!(crop.x, 1536 - 303);
+        assert_eq!(crop.y, 0);
+
+        let edge = reframe.crop(1920, 1080, Some((1900.0, 540.0)));
+        assert_eq!(edge.x + edge.width, 1920);
+        assert_eq!(reframe.crop(1920, 1080, Some((0.0, 0.0))).x, 0);
+    }
+}
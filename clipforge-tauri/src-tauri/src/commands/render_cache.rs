@@ -0,0 +1,359 @@
+// Background segment rendering
+//
+// Exports spend most of their time trimming and normalizing each clip. The
+// frontend calls `update_render_cache` whenever the timeline changes; once it
+// has stayed unchanged for `IDLE_DELAY`, clips are rendered one at a time into
+// the media cache, keyed by the source file's content hash plus everything
+// that affects the rendered segment. `run_export` then reuses those segments,
+// so exporting a long timeline that was edited gradually mostly concatenates.
+
+use super::dry_run::FfmpegRunner;
+use super::export::{
+    clip_media_kind, render_clip, ClipData, ExportSettings, RenderedClip, SegmentFormat,
+};
+use super::ffmpeg_utils::find_ffmpeg;
+use super::media_cache::{self, CacheKind};
+use super::metadata::MediaKind;
+use crate::error::ClipForgeError;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// How long the timeline must stay unchanged before rendering starts
+const IDLE_DELAY: Duration = Duration::from_secs(3);
+
+/// Bumped when segment rendering changes so stale segments are not reused
+const RENDER_VERSION: u32 = 2;
+
+/// Export settings that change how a single segment is rendered
+const SEGMENT_SETTINGS: [&str; 8] = [
+    "cursorOverlay",
+    "windowFrame",
+    "audioFilters",
+    "skipSilence",
+    "toneMapHdr",
+    "alpha",
+    "reframe",
+    "extraFfmpegArgs",
+];
+
+/// Payload of "render-cache:progress"
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderCacheStatus {
+    /// Clips whose segments are ready
+    pub cached: usize,
+    /// Video and image clips on the timeline
+    pub total: usize,
+    pub rendering: bool,
+}
+
+/// Latest timeline waiting to be rendered
+#[derive(Default)]
+pub struct RenderCache {
+    /// Incremented on every update; a render stops once it no longer matches
+    generation: u64,
+    pending: Option<(Vec<ClipData>, ExportSettings)>,
+    status: RenderCacheStatus,
+    /// Held while rendering so only one background render runs at a time
+    render_lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl RenderCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+pub type RenderCacheState = Arc<Mutex<RenderCache>>;
+
+/// Audio-only clips are mixed over the export instead of rendered as segments
+fn is_rendered(clip: &ClipData) -> bool {
+    clip_media_kind(clip) != MediaKind::Audio
+}
+
+/// Cache variant for a clip: everything except its position on the timeline
+fn segment_variant(clip: &ClipData, format: SegmentFormat, settings: &ExportSettings) -> String {
+    let mut clip = serde_json::to_value(clip).unwrap_or(Value::Null);
+    if let Value::Object(fields) = &mut clip {
+        fields.remove("startTime");
+    }
+    let settings = serde_json::to_value(settings).unwrap_or(Value::Null);
+    let settings: Value = SEGMENT_SETTINGS
+        .iter()
+        .map(|key| (key.to_string(), settings[*key].clone()))
+        .collect::<serde_json::Map<_, _>>()
+        .into();
+
+    let spec = json!({
+        "version": RENDER_VERSION,
+        "clip": clip,
+        "format": format,
+        "settings": settings,
+    });
+    let digest = Sha256::digest(spec.to_string().as_bytes());
+    hex::encode(&digest[..16])
+}
+
+/// Segments rendered earlier for this clip, format and settings
+pub fn lookup(
+    clip: &ClipData,
+    format: SegmentFormat,
+    settings: &ExportSettings,
+) -> Option<RenderedClip> {
+    let hash = media_cache::content_hash(Path::new(&clip.video_path)).ok()?;
+    let variant = segment_variant(clip, format, settings);
+    let item = media_cache::lookup(&hash, CacheKind::Render, &variant)?;
+    // The kept ranges are stored alongside so chapters can follow silence cuts
+    let ranges = serde_json::from_value(item.data?).ok()?;
+    Some(RenderedClip {
+        segments: item.files.into_iter().map(PathBuf::from).collect(),
+        ranges,
+    })
+}
+
+/// Render a clip's segments into the cache
+async fn render_into_cache(
+    ffmpeg_path: &Path,
+    clip: &ClipData,
+    index: usize,
+    format: SegmentFormat,
+    settings: &ExportSettings,
+) -> Result<(), ClipForgeError> {
+    let hash = media_cache::content_hash(Path::new(&clip.video_path))?;
+    let variant = segment_variant(clip, format, settings);
+    let dir = media_cache::item_dir(&hash)?.join(format!("render_{}", variant));
+    fs::create_dir_all(&dir)?;
+
+    let runner = FfmpegRunner::Run;
+    let rendered =
+        match render_clip(ffmpeg_path, clip, index, format, settings, &dir, &runner).await {
+            Ok(rendered) => rendered,
+            Err(e) => {
+                let _ = fs::remove_dir_all(&dir);
+                return Err(e);
+            }
+        };
+
+    // Keep only the segments; PiP composites and cursor scripts were intermediates
+    if let Ok(entries) = fs::read_dir(&dir) {
+        for entry in entries.flatten() {
+            if !rendered.segments.contains(&entry.path()) {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    media_cache::store(
+        &hash,
+        CacheKind::Render,
+        &variant,
+        &clip.video_path,
+        rendered
+            .segments
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect(),
+        Some(json!(rendered.ranges)),
+    );
+    Ok(())
+}
+
+fn publish(app: &AppHandle, status: RenderCacheStatus) {
+    if let Ok(mut cache) = app.state::<RenderCacheState>().lock() {
+        cache.status = status.clone();
+    }
+    let _ = app.emit("render-cache:progress", &status);
+}
+
+fn is_current(app: &AppHandle, generation: u64) -> bool {
+    app.state::<RenderCacheState>()
+        .lock()
+        .map(|cache| cache.generation == generation)
+        .unwrap_or(false)
+}
+
+/// Render every uncached clip, stopping early if the timeline changes again
+async fn render_timeline(
+    app: &AppHandle,
+    generation: u64,
+    clips: Vec<ClipData>,
+    settings: ExportSettings,
+) {
+    let Some(ffmpeg_path) = find_ffmpeg() else {
+        return;
+    };
+    let clips: Vec<ClipData> = clips.into_iter().filter(is_rendered).collect();
+    let Some(format) = SegmentFormat::for_timeline(&clips, settings.reframe.as_ref()) else {
+        publish(app, RenderCacheStatus::default());
+        return;
+    };
+
+    let mut status = RenderCacheStatus {
+        cached: 0,
+        total: clips.len(),
+        rendering: true,
+    };
+    publish(app, status.clone());
+
+    for (index, clip) in clips.iter().enumerate() {
+        if !is_current(app, generation) {
+            return;
+        }
+
+        if lookup(clip, format, &settings).is_some() {
+            status.cached += 1;
+            continue;
+        }
+        match render_into_cache(&ffmpeg_path, clip, index, format, &settings).await {
+            Ok(()) => status.cached += 1,
+            Err(e) => println!("[RenderCache] Failed to render {}: {}", clip.video_path, e),
+        }
+        publish(app, status.clone());
+    }
+
+    status.rendering = false;
+    publish(app, status);
+}
+
+/// Hand the current timeline to the background renderer
+///
+/// Rendering starts once the timeline has been unchanged for a few seconds;
+/// progress is reported through "render-cache:progress".
+#[tauri::command]
+pub async fn update_render_cache(
+    clips: Vec<ClipData>,
+    settings: Option<ExportSettings>,
+    app: AppHandle,
+    state: State<'_, RenderCacheState>,
+) -> Result<RenderCacheStatus, String> {
+    // Rendered segments are reused by exports, so they must pass the same checks
+    let settings = settings.unwrap_or_default();
+    settings.validate()?;
+
+    let (generation, render_lock, status) = {
+        let mut cache = state.lock().map_err(|e| e.to_string())?;
+        cache.generation += 1;
+        cache.pending = Some((clips, settings));
+        (
+            cache.generation,
+            cache.render_lock.clone(),
+            cache.status.clone(),
+        )
+    };
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(IDLE_DELAY).await;
+        if !is_current(&app, generation) {
+            return;
+        }
+
+        let _guard = render_lock.lock().await;
+        let pending = app
+            .state::<RenderCacheState>()
+            .lock()
+            .ok()
+            .filter(|cache| cache.generation == generation)
+            .and_then(|mut cache| cache.pending.take());
+        if let Some((clips, settings)) = pending {
+            render_timeline(&app, generation, clips, settings).await;
+        }
+    });
+
+    Ok(status)
+}
+
+/// How much of the current timeline is pre-rendered
+#[tauri::command]
+pub async fn get_render_cache_status(
+    state: State<'_, RenderCacheState>,
+) -> Result<RenderCacheStatus, String> {
+    Ok(state.lock().map_err(|e| e.to_string())?.status.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variant_ignores_timeline_position() {
+        let clip: ClipData = serde_json::from_value(json!({
+            "videoPath": "/Users/me/Movies/demo.mp4",
+            "startTime": 0.0,
+            "trimStart": 0.0,
+            "trimEnd": 5.0,
+            "duration": 10.0,
+            "width": 1920,
+            "height": 1080,
+            "frameRate": 30.0,
+            "mediaType": null,
+            "pipMetadataPath": null,
+            "pipOverride": null,
+            "colorCorrection": null,
+            "cursorDataPath": null,
+            "reframeFocus": null
+        }))
+        .unwrap();
+        let moved: ClipData = serde_json::from_value(json!({
+            "videoPath": "/Users/me/Movies/demo.mp4",
+            "startTime": 12.0,
+            "trimStart": 0.0,
+            "trimEnd": 5.0,
+            "duration": 10.0,
+            "width": 1920,
+            "height": 1080,
+            "frameRate": 30.0,
+            "mediaType": null,
+            "pipMetadataPath": null,
+            "pipOverride": null,
+            "colorCorrection": null,
+            "cursorDataPath": null,
+            "reframeFocus": null
+        }))
+        .unwrap();
+        let longer: ClipData = serde_json::from_value(json!({
+            "videoPath": "/Users/me/Movies/demo.mp4",
+            "startTime": 0.0,
+            "trimStart": 0.0,
+            "trimEnd": 6.0,
+            "duration": 10.0,
+            "width": 1920,
+            "height": 1080,
+            "frameRate": 30.0,
+            "mediaType": null,
+            "pipMetadataPath": null,
+            "pipOverride": null,
+            "colorCorrection": null,
+            "cursorDataPath": null,
+            "reframeFocus": null
+        }))
+        .unwrap();
+        let clips = [clip];
+        let format = SegmentFormat::for_timeline(&clips, None).unwrap();
+        let settings = ExportSettings::default();
+        let clip = &clips[0];
+
+        assert_eq!(
+            segment_variant(clip, format, &settings),
+            segment_variant(&moved, format, &settings)
+        );
+        assert_ne!(
+            segment_variant(clip, format, &settings),
+            segment_variant(&longer, format, &settings)
+        );
+
+        let tone_mapped = ExportSettings {
+            tone_map_hdr: true,
+            ..Default::default()
+        };
+        assert_ne!(
+            segment_variant(clip, format, &settings),
+            segment_variant(clip, format, &tone_mapped)
+        );
+    }
+}
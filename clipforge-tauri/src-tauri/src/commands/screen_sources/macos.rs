@@ -1,9 +1,55 @@
 #![allow(dead_code)]
 
-use super::{ScreenSource, SourceEnumerator, SourceType};
-use base64::Engine as _;
+use super::{ScreenSource, ScreenshotFormat, SourceEnumerator, SourceType};
 use crate::capture::ffi;
+use crate::commands::cursor::CaptureRegion;
+use crate::commands::process_runner::{JobKind, WatchedOutput};
+use cocoa::base::{id, nil};
+use objc::{class, msg_send, sel, sel_impl};
+use std::collections::HashMap;
+use std::path::Path;
 use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+
+/// Bundle identifier of each window's app keyed by window id, reused across
+/// enumerations; a window never changes owner, unlike its title
+fn bundle_id_cache() -> &'static Mutex<HashMap<u32, Option<String>>> {
+    static CACHE: OnceLock<Mutex<HashMap<u32, Option<String>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Look up the bundle identifier of a running application
+unsafe fn bundle_id_for_pid(pid: i32) -> Option<String> {
+    if pid < 0 {
+        return None;
+    }
+
+    let app: id = msg_send![
+        class!(NSRunningApplication),
+        runningApplicationWithProcessIdentifier: pid
+    ];
+    if app == nil {
+        return None;
+    }
+
+    let bundle_id: id = msg_send![app, bundleIdentifier];
+    if bundle_id == nil {
+        return None;
+    }
+
+    let bundle_id_str: *const std::os::raw::c_char = msg_send![bundle_id, UTF8String];
+    if bundle_id_str.is_null() {
+        return None;
+    }
+    Some(
+        std::ffi::CStr::from_ptr(bundle_id_str)
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+/// Longest side of source thumbnails, in pixels
+const THUMBNAIL_MAX_SIZE: i32 = 200;
 
 /// macOS-specific screen source enumerator
 pub struct PlatformEnumerator;
@@ -22,7 +68,7 @@ impl PlatformEnumerator {
                 .arg("-i")
                 .arg("")
                 .stderr(Stdio::piped())
-                .output()
+                .watched_output(JobKind::Probe)
             {
                 let stderr = String::from_utf8_lossy(&output.stderr);
                 let mut camera_count = 0;
@@ -73,141 +119,24 @@ impl PlatformEnumerator {
             return false;
         }
 
-        // Check if window is on screen
-        if window.is_on_screen == 0 {
-            return false;
-        }
-
+        // Off-screen windows are kept and left to SourceFilter
         true
     }
-
-    /// Capture thumbnail for a window by window ID
-    fn capture_window_thumbnail(window_id: u32) -> Option<String> {
-        use std::fs;
-        use std::process::Command;
-        use std::time::{SystemTime, UNIX_EPOCH};
-
-        // Create temp file path
-        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
-        let temp_path = format!("/tmp/window_thumb_{}_{}.png", window_id, timestamp);
-
-        // Use macOS screencapture to capture window
-        // -l <windowid> captures a specific window
-        // -x disables sound
-        let output = Command::new("screencapture")
-            .arg("-l")
-            .arg(window_id.to_string())
-            .arg("-x") // No sound
-            .arg(&temp_path)
-            .output()
-            .ok()?;
-
-        if !output.status.success() {
-            println!(
-                "[WindowThumbnail] screencapture failed for window {}: {:?}",
-                window_id,
-                String::from_utf8_lossy(&output.stderr)
-            );
-            return None;
-        }
-
-        // Check if file was created
-        if !std::path::Path::new(&temp_path).exists() {            return None;
-        }
-
-        // Resize the image using sips (built-in macOS image tool)
-        let resize_output = Command::new("sips")
-            .arg("-Z")
-            .arg("200") // Max dimension 200px
-            .arg(&temp_path)
-            .output()
-            .ok()?;
-
-        if !resize_output.status.success() {        }
-
-        // Read PNG file and base64 encode
-        let png_data = fs::read(&temp_path).ok()?;
-        let base64_string = base64::engine::general_purpose::STANDARD.encode(&png_data);
-
-        // Clean up temp file
-        let _ = fs::remove_file(&temp_path);
-
-        Some(base64_string)
-    }
-
-    /// Capture thumbnail for a screen by AVFoundation device index
-    fn capture_screen_thumbnail(avf_device_index: usize) -> Option<String> {
-        use std::fs;
-        use std::process::Command;
-        use std::time::{SystemTime, UNIX_EPOCH};
-
-        // Find FFmpeg
-        let ffmpeg_path = super::super::ffmpeg_utils::find_ffmpeg()?;
-
-        // Create temp file path
-        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
-        let temp_path = format!("/tmp/screen_thumb_{}_{}.png", avf_device_index, timestamp);
-
-        // Capture single frame using FFmpeg with AVFoundation device index
-        let output = Command::new(&ffmpeg_path)
-            .arg("-f")
-            .arg("avfoundation")
-            .arg("-framerate")
-            .arg("30") // Use 30fps for screen capture
-            .arg("-i")
-            .arg(format!("{}:", avf_device_index)) // No audio
-            .arg("-frames:v")
-            .arg("1") // Single frame
-            .arg("-vf")
-            .arg("scale=200:-1") // Scale to width 200, maintain aspect ratio
-            .arg("-y") // Overwrite
-            .arg(&temp_path)
-            .output()
-            .ok()?;
-
-        if !output.status.success() {
-            println!(
-                "[ScreenThumbnail] FFmpeg failed for device {}: {:?}",
-                avf_device_index,
-                String::from_utf8_lossy(&output.stderr)
-            );
-            return None;
-        }
-
-        // Read PNG file and base64 encode
-        let png_data = fs::read(&temp_path).ok()?;
-        let base64_string = base64::engine::general_purpose::STANDARD.encode(&png_data);
-
-        // Clean up temp file
-        let _ = fs::remove_file(&temp_path);
-
-        Some(base64_string)
-    }
 }
 
 impl SourceEnumerator for PlatformEnumerator {
-    fn enumerate_screens() -> Result<Vec<ScreenSource>, String> {
+    fn enumerate_screens_fast() -> Result<Vec<ScreenSource>, String> {
         // Use ScreenCaptureKit to enumerate displays
         let displays = ffi::enumerate_displays()?;
 
         let mut sources = Vec::with_capacity(displays.len());
 
         for (i, display) in displays.iter().enumerate() {
-            let display_id = display.display_id;
             let is_primary = display.is_primary != 0;
 
             // Use display ID directly as the screen identifier
-            let screen_id = format!("display_{}", display_id);
-            println!(
-                "[ScreenEnumeration SCK] Display {}: {}x{} @ ({}, {}), primary: {}",
-                display_id, display.width, display.height, display.x, display.y, is_primary
-            );
-
-            // Generate thumbnail using SCScreenshotManager
-            let thumbnail = ffi::capture_display_thumbnail(display_id, 200).ok();
-
-            let mut source = ScreenSource::new(
-                screen_id,
+            let source = ScreenSource::new(
+                format!("display_{}", display.display_id),
                 format!("Display {}", i + 1),
                 SourceType::Screen,
                 display.width,
@@ -215,11 +144,11 @@ impl SourceEnumerator for PlatformEnumerator {
             )
             .with_position(display.x, display.y)
             .with_primary(is_primary)
-            .with_scale_factor(1.0); // SCDisplay already provides pixel dimensions
-
-            if let Some(thumb) = thumbnail {
-                source = source.with_thumbnail(thumb);
-            }
+            .with_scale_factor(if display.scale_factor > 0.0 {
+                display.scale_factor
+            } else {
+                1.0
+            });
 
             sources.push(source);
         }
@@ -227,23 +156,33 @@ impl SourceEnumerator for PlatformEnumerator {
         Ok(sources)
     }
 
-    fn enumerate_windows() -> Result<Vec<ScreenSource>, String> {
+    fn enumerate_windows_fast() -> Result<Vec<ScreenSource>, String> {
         // Use ScreenCaptureKit to enumerate windows
         let windows = ffi::enumerate_windows()?;
+        let windows: Vec<_> = windows
+            .into_iter()
+            .filter(Self::should_include_window_sck)
+            .collect();
 
-        let mut sources = Vec::new();
+        let mut cache = bundle_id_cache()
+            .lock()
+            .map_err(|e| format!("Failed to lock bundle id cache: {}", e))?;
 
-        for window in windows {
-            // Filter out windows we don't want
-            if !Self::should_include_window_sck(&window) {
-                continue;
-            }
+        // Forget windows that have closed since the last enumeration
+        cache.retain(|window_id, _| windows.iter().any(|w| w.window_id == *window_id));
+
+        let mut sources = Vec::with_capacity(windows.len());
 
+        for window in windows {
             let window_id = window.window_id;
 
-            // Get window metadata (title and owner name)
+            // Titles change as tabs and documents switch, so ask every time
             let (title, owner) = ffi::get_window_metadata(window_id)
                 .unwrap_or_else(|_| (String::new(), String::from("Unknown")));
+            let bundle_id = cache
+                .entry(window_id)
+                .or_insert_with(|| unsafe { bundle_id_for_pid(window.owner_pid) })
+                .clone();
 
             // Create display name
             let display_name = if title.is_empty() {
@@ -252,15 +191,7 @@ impl SourceEnumerator for PlatformEnumerator {
                 format!("{} - {}", owner, title)
             };
 
-            println!(
-                "[WindowEnumeration SCK] Window {}: '{}' ({}x{} @ {}, {})",
-                window_id, display_name, window.width, window.height, window.x, window.y
-            );
-
-            // Generate thumbnail using SCScreenshotManager
-            let thumbnail = ffi::capture_window_thumbnail(window_id, 200).ok();
-
-            let mut source = ScreenSource::new(
+            let source = ScreenSource::new(
                 format!("window_{}", window_id),
                 display_name,
                 SourceType::Window,
@@ -268,15 +199,70 @@ impl SourceEnumerator for PlatformEnumerator {
                 window.height,
             )
             .with_position(window.x, window.y)
-            .with_app_name(owner);
+            .with_app_name(owner)
+            .with_on_screen(window.is_on_screen != 0);
 
-            if let Some(thumb) = thumbnail {
-                source = source.with_thumbnail(thumb);
-            }
+            let source = match bundle_id {
+                Some(bundle_id) => source.with_bundle_id(bundle_id),
+                None => source,
+            };
 
             sources.push(source);
         }
 
         Ok(sources)
     }
+
+    fn capture_thumbnail(source_id: &str) -> Option<String> {
+        // Generate thumbnail using SCScreenshotManager, downscaled in Swift
+        if let Some(id) = source_id.strip_prefix("window_") {
+            ffi::capture_window_thumbnail(id.parse().ok()?, THUMBNAIL_MAX_SIZE).ok()
+        } else if let Some(id) = source_id.strip_prefix("display_") {
+            ffi::capture_display_thumbnail(id.parse().ok()?, THUMBNAIL_MAX_SIZE).ok()
+        } else {
+            None
+        }
+    }
+
+    fn capture_screenshot(
+        source_id: &str,
+        region: Option<&CaptureRegion>,
+        format: ScreenshotFormat,
+        output_path: &Path,
+    ) -> Result<(), String> {
+        let png_data = if let Some(id) = source_id.strip_prefix("display_") {
+            let display_id = id
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid display id: {}", source_id))?;
+            ffi::capture_display_png(display_id)?
+        } else if let Some(id) = source_id.strip_prefix("window_") {
+            let window_id = id
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid window id: {}", source_id))?;
+            ffi::capture_window_png(window_id)?
+        } else {
+            return Err(format!("Unknown source: {}", source_id));
+        };
+
+        let mut image = image::load_from_memory(&png_data)
+            .map_err(|e| format!("Failed to decode screenshot: {}", e))?;
+
+        if let Some(region) = region {
+            // Clamp the crop to the captured image
+            let x = (region.x.max(0.0) as u32).min(image.width().saturating_sub(1));
+            let y = (region.y.max(0.0) as u32).min(image.height().saturating_sub(1));
+            let width = (region.width.max(1.0) as u32).min(image.width() - x);
+            let height = (region.height.max(1.0) as u32).min(image.height() - y);
+            image = image.crop_imm(x, y, width, height);
+        }
+
+        let result = match format {
+            ScreenshotFormat::Png => image.save_with_format(output_path, image::ImageFormat::Png),
+            // JPEG has no alpha channel
+            ScreenshotFormat::Jpeg => image
+                .to_rgb8()
+                .save_with_format(output_path, image::ImageFormat::Jpeg),
+        };
+        result.map_err(|e| format!("Failed to save screenshot: {}", e))
+    }
 }
@@ -12,7 +12,21 @@ pub use macos::*;
 #[cfg(not(target_os = "macos"))]
 pub use stub::*;
 
+use super::cursor::CaptureRegion;
+use super::recording::default_true;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+/// Maximum number of thumbnails captured at once
+const MAX_THUMBNAIL_WORKERS: usize = 4;
+
+/// Default and minimum interval between live thumbnail refreshes
+const DEFAULT_THUMBNAIL_REFRESH_MS: u64 = 2000;
+const MIN_THUMBNAIL_REFRESH_MS: u64 = 500;
 
 /// Type of screen source
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -22,6 +36,24 @@ pub enum SourceType {
     Window,
 }
 
+/// Image format for screenshots
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScreenshotFormat {
+    #[default]
+    Png,
+    Jpeg,
+}
+
+impl ScreenshotFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ScreenshotFormat::Png => "png",
+            ScreenshotFormat::Jpeg => "jpg",
+        }
+    }
+}
+
 /// Screen or window source for recording
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScreenSource {
@@ -47,6 +79,12 @@ pub struct ScreenSource {
     pub thumbnail: Option<String>,
     /// Application name (for windows)
     pub app_name: Option<String>,
+    /// Bundle identifier of the owning application (for windows)
+    #[serde(default)]
+    pub bundle_id: Option<String>,
+    /// Whether the source is currently visible (false for minimized or hidden windows)
+    #[serde(default = "default_true")]
+    pub is_on_screen: bool,
 }
 
 impl ScreenSource {
@@ -64,6 +102,8 @@ impl ScreenSource {
             scale_factor: 1.0,
             thumbnail: None,
             app_name: None,
+            bundle_id: None,
+            is_on_screen: true,
         }
     }
 
@@ -97,42 +137,522 @@ impl ScreenSource {
         self.app_name = Some(app_name);
         self
     }
+
+    /// Builder-style method to set bundle id
+    pub fn with_bundle_id(mut self, bundle_id: String) -> Self {
+        self.bundle_id = Some(bundle_id);
+        self
+    }
+
+    /// Builder-style method to set on-screen state
+    pub fn with_on_screen(mut self, is_on_screen: bool) -> Self {
+        self.is_on_screen = is_on_screen;
+        self
+    }
+}
+
+/// Order of enumerated sources
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum SourceSortOrder {
+    /// Order reported by the system (front-to-back for windows)
+    #[default]
+    System,
+    /// Alphabetically by display name
+    Name,
+    /// Alphabetically by application name, then display name
+    AppName,
+    /// Largest first
+    Area,
+}
+
+/// Filter and sort options for source enumeration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SourceFilter {
+    /// Case-insensitive match against the application name
+    pub app_name: Option<String>,
+    /// Exact bundle identifier of the owning application
+    pub bundle_id: Option<String>,
+    /// Case-insensitive search over the display name and application name
+    pub query: Option<String>,
+    /// Minimum width in pixels
+    pub min_width: Option<u32>,
+    /// Minimum height in pixels
+    pub min_height: Option<u32>,
+    /// Include minimized and hidden windows
+    pub include_off_screen: bool,
+    pub sort: SourceSortOrder,
+}
+
+impl SourceFilter {
+    /// Whether `source` passes every filter
+    pub fn matches(&self, source: &ScreenSource) -> bool {
+        let contains = |haystack: Option<&str>, needle: &str| {
+            haystack.is_some_and(|h| h.to_lowercase().contains(&needle.to_lowercase()))
+        };
+
+        if !self.include_off_screen && !source.is_on_screen {
+            return false;
+        }
+        if self.min_width.is_some_and(|w| source.width < w)
+            || self.min_height.is_some_and(|h| source.height < h)
+        {
+            return false;
+        }
+        if let Some(app_name) = &self.app_name {
+            if !contains(source.app_name.as_deref(), app_name) {
+                return false;
+            }
+        }
+        if let Some(bundle_id) = &self.bundle_id {
+            if source.bundle_id.as_deref() != Some(bundle_id.as_str()) {
+                return false;
+            }
+        }
+        if let Some(query) = &self.query {
+            if !contains(Some(&source.name), query) && !contains(source.app_name.as_deref(), query)
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Drop sources that don't match and sort the rest
+    pub fn apply(&self, sources: Vec<ScreenSource>) -> Vec<ScreenSource> {
+        let mut sources: Vec<ScreenSource> =
+            sources.into_iter().filter(|s| self.matches(s)).collect();
+
+        match self.sort {
+            SourceSortOrder::System => {}
+            SourceSortOrder::Name => {
+                sources.sort_by_key(|s| s.name.to_lowercase());
+            }
+            SourceSortOrder::AppName => {
+                sources.sort_by_key(|s| {
+                    (
+                        s.app_name.clone().unwrap_or_default().to_lowercase(),
+                        s.name.to_lowercase(),
+                    )
+                });
+            }
+            SourceSortOrder::Area => {
+                sources.sort_by_key(|s| std::cmp::Reverse(s.width as u64 * s.height as u64));
+            }
+        }
+        sources
+    }
 }
 
 /// Trait for platform-specific screen source enumeration
 pub trait SourceEnumerator {
-    /// Enumerate all available screens
-    fn enumerate_screens() -> Result<Vec<ScreenSource>, String>;
+    /// Enumerate all available screens without capturing thumbnails
+    fn enumerate_screens_fast() -> Result<Vec<ScreenSource>, String>;
+
+    /// Enumerate all available windows, including off-screen ones, without capturing thumbnails
+    fn enumerate_windows_fast() -> Result<Vec<ScreenSource>, String>;
+
+    /// Capture a small base64 PNG thumbnail of a screen or window
+    fn capture_thumbnail(source_id: &str) -> Option<String>;
+
+    /// Enumerate all available screens, with thumbnails
+    fn enumerate_screens() -> Result<Vec<ScreenSource>, String> {
+        Ok(with_thumbnails::<Self>(Self::enumerate_screens_fast()?))
+    }
+
+    /// Enumerate all visible windows, with thumbnails
+    fn enumerate_windows() -> Result<Vec<ScreenSource>, String> {
+        let sources = SourceFilter::default().apply(Self::enumerate_windows_fast()?);
+        Ok(with_thumbnails::<Self>(sources))
+    }
 
-    /// Enumerate all available windows
-    fn enumerate_windows() -> Result<Vec<ScreenSource>, String>;
+    /// Capture a still of a screen or window and write it to `output_path`
+    ///
+    /// `region` crops the capture and is relative to the source's top-left corner, in points
+    fn capture_screenshot(
+        source_id: &str,
+        region: Option<&CaptureRegion>,
+        format: ScreenshotFormat,
+        output_path: &Path,
+    ) -> Result<(), String>;
 
-    /// Enumerate both screens and windows
-    fn enumerate_all() -> Result<Vec<ScreenSource>, String> {
-        let mut sources = Self::enumerate_screens()?;
-        sources.extend(Self::enumerate_windows()?);
+    /// Enumerate both screens and windows without capturing thumbnails
+    fn enumerate_all_fast() -> Result<Vec<ScreenSource>, String> {
+        let mut sources = Self::enumerate_screens_fast()?;
+        sources.extend(Self::enumerate_windows_fast()?);
         Ok(sources)
     }
 }
 
+/// Capture thumbnails for `source_ids` on a bounded pool of worker threads
+///
+/// `on_thumbnail` is called from the workers with each source's index as it finishes
+pub fn capture_thumbnails<E: SourceEnumerator + ?Sized>(
+    source_ids: &[String],
+    on_thumbnail: impl Fn(usize, Option<String>) + Sync,
+) {
+    let next = AtomicUsize::new(0);
+    let workers = MAX_THUMBNAIL_WORKERS.min(source_ids.len());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let Some(source_id) = source_ids.get(index) else {
+                    break;
+                };
+                on_thumbnail(index, E::capture_thumbnail(source_id));
+            });
+        }
+    });
+}
+
+/// Fill in thumbnails for `sources`, capturing them concurrently
+fn with_thumbnails<E: SourceEnumerator + ?Sized>(sources: Vec<ScreenSource>) -> Vec<ScreenSource> {
+    let ids: Vec<String> = sources.iter().map(|s| s.id.clone()).collect();
+
+    let thumbnails = Mutex::new(vec![None; ids.len()]);
+    capture_thumbnails::<E>(&ids, |index, thumbnail| {
+        if let Ok(mut thumbnails) = thumbnails.lock() {
+            thumbnails[index] = thumbnail;
+        }
+    });
+
+    let thumbnails = thumbnails.into_inner().unwrap_or_else(|e| e.into_inner());
+    sources
+        .into_iter()
+        .zip(thumbnails)
+        .map(|(source, thumbnail)| match thumbnail {
+            Some(thumbnail) => source.with_thumbnail(thumbnail),
+            None => source,
+        })
+        .collect()
+}
+
+/// Payload of the `sources:window-thumbnail` and `sources:thumbnail-updated` events
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceThumbnail {
+    pub source_id: String,
+    pub thumbnail: Option<String>,
+}
+
+/// Background task that keeps source-picker thumbnails live
+#[derive(Default)]
+pub struct SourceThumbnailRefresher {
+    task: Option<tauri::async_runtime::JoinHandle<()>>,
+}
+
+impl SourceThumbnailRefresher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop refreshing thumbnails
+    pub fn stop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
+
+impl Drop for SourceThumbnailRefresher {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Thread-safe thumbnail refresher type
+pub type SourceThumbnailState = Arc<Mutex<SourceThumbnailRefresher>>;
+
 // ============================================================================
 // Tauri Commands
 // ============================================================================
 
 /// Enumerate all available screen sources (screens and windows)
+///
+/// Sources are filtered before thumbnails are captured
 #[tauri::command]
-pub async fn enumerate_sources() -> Result<Vec<ScreenSource>, String> {
-    PlatformEnumerator::enumerate_all()
+pub async fn enumerate_sources(filter: Option<SourceFilter>) -> Result<Vec<ScreenSource>, String> {
+    let sources = filter
+        .unwrap_or_default()
+        .apply(PlatformEnumerator::enumerate_all_fast()?);
+    Ok(with_thumbnails::<PlatformEnumerator>(sources))
 }
 
 /// Enumerate only screens/displays
 #[tauri::command]
-pub async fn enumerate_screens() -> Result<Vec<ScreenSource>, String> {
-    PlatformEnumerator::enumerate_screens()
+pub async fn enumerate_screens(filter: Option<SourceFilter>) -> Result<Vec<ScreenSource>, String> {
+    let sources = filter
+        .unwrap_or_default()
+        .apply(PlatformEnumerator::enumerate_screens_fast()?);
+    Ok(with_thumbnails::<PlatformEnumerator>(sources))
 }
 
 /// Enumerate only windows
 #[tauri::command]
-pub async fn enumerate_windows() -> Result<Vec<ScreenSource>, String> {
-    PlatformEnumerator::enumerate_windows()
+pub async fn enumerate_windows(filter: Option<SourceFilter>) -> Result<Vec<ScreenSource>, String> {
+    let sources = filter
+        .unwrap_or_default()
+        .apply(PlatformEnumerator::enumerate_windows_fast()?);
+    Ok(with_thumbnails::<PlatformEnumerator>(sources))
+}
+
+/// Enumerate windows without waiting for thumbnails
+///
+/// Thumbnails are captured in the background and delivered through
+/// `sources:window-thumbnail` events, followed by `sources:window-thumbnails-complete`
+#[tauri::command]
+pub async fn enumerate_windows_fast(
+    app: AppHandle,
+    filter: Option<SourceFilter>,
+) -> Result<Vec<ScreenSource>, String> {
+    let sources = filter
+        .unwrap_or_default()
+        .apply(PlatformEnumerator::enumerate_windows_fast()?);
+    let ids: Vec<String> = sources.iter().map(|s| s.id.clone()).collect();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        capture_thumbnails::<PlatformEnumerator>(&ids, |index, thumbnail| {
+            let _ = app.emit(
+                "sources:window-thumbnail",
+                SourceThumbnail {
+                    source_id: ids[index].clone(),
+                    thumbnail,
+                },
+            );
+        });
+        let _ = app.emit("sources:window-thumbnails-complete", ids.len());
+    });
+
+    Ok(sources)
+}
+
+/// Periodically refresh thumbnails of all screens and windows
+///
+/// Each refreshed thumbnail is emitted as a `sources:thumbnail-updated` event until
+/// `stop_source_thumbnails` is called. Starting again replaces the previous refresh
+#[tauri::command]
+pub async fn start_source_thumbnails(
+    interval_ms: Option<u64>,
+    app: AppHandle,
+    state: State<'_, SourceThumbnailState>,
+) -> Result<(), String> {
+    let interval_ms = interval_ms
+        .unwrap_or(DEFAULT_THUMBNAIL_REFRESH_MS)
+        .max(MIN_THUMBNAIL_REFRESH_MS);
+
+    let mut refresher = state.lock().map_err(|e| e.to_string())?;
+    refresher.stop();
+
+    refresher.task = Some(tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
+
+        loop {
+            interval.tick().await;
+
+            let app = app.clone();
+            let refresh = tauri::async_runtime::spawn_blocking(move || {
+                let mut ids: Vec<String> = Vec::new();
+                for sources in [
+                    PlatformEnumerator::enumerate_screens_fast(),
+                    PlatformEnumerator::enumerate_windows_fast(),
+                ] {
+                    match sources {
+                        Ok(sources) => ids.extend(
+                            SourceFilter::default()
+                                .apply(sources)
+                                .into_iter()
+                                .map(|s| s.id),
+                        ),
+                        Err(e) => println!("[SourceThumbnails] {}", e),
+                    }
+                }
+
+                capture_thumbnails::<PlatformEnumerator>(&ids, |index, thumbnail| {
+                    if thumbnail.is_some() {
+                        let _ = app.emit(
+                            "sources:thumbnail-updated",
+                            SourceThumbnail {
+                                source_id: ids[index].clone(),
+                                thumbnail,
+                            },
+                        );
+                    }
+                });
+            });
+
+            // Wait for this round so slow captures never pile up
+            let _ = refresh.await;
+        }
+    }));
+
+    println!("[SourceThumbnails] Refreshing every {}ms", interval_ms);
+    Ok(())
+}
+
+/// Stop refreshing source thumbnails
+#[tauri::command]
+pub async fn stop_source_thumbnails(state: State<'_, SourceThumbnailState>) -> Result<(), String> {
+    state.lock().map_err(|e| e.to_string())?.stop();
+    Ok(())
+}
+
+/// Directory screenshots are saved to, alongside recordings
+fn screenshot_dir() -> Result<PathBuf, String> {
+    super::app_cache::cache_dir("recordings")
+}
+
+/// Capture a screenshot of a screen or window (optionally cropped to a region)
+/// Returns the path of the saved image
+#[tauri::command]
+pub async fn capture_screenshot(
+    source_id: String,
+    region: Option<CaptureRegion>,
+    format: Option<ScreenshotFormat>,
+) -> Result<String, String> {
+    let format = format.unwrap_or_default();
+    let filename = format!(
+        "screenshot_{}.{}",
+        chrono::Utc::now().timestamp_millis(),
+        format.extension()
+    );
+    let output_path = screenshot_dir()?.join(filename);
+
+    PlatformEnumerator::capture_screenshot(&source_id, region.as_ref(), format, &output_path)?;
+
+    println!(
+        "[Screenshot] Captured {} to {}",
+        source_id,
+        output_path.display()
+    );
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_filter_matches() {
+        let safari = ScreenSource::new(
+            "window_1".to_string(),
+            "Safari - Document".to_string(),
+            SourceType::Window,
+            1200,
+            800,
+        )
+        .with_app_name("Safari".to_string())
+        .with_bundle_id("com.apple.Safari".into());
+        let hidden = ScreenSource::new(
+            "window_2".to_string(),
+            "Notes - Document".to_string(),
+            SourceType::Window,
+            600,
+            400,
+        )
+        .with_app_name("Notes".to_string())
+        .with_on_screen(false);
+
+        let default = SourceFilter::default();
+        assert!(default.matches(&safari));
+        assert!(!default.matches(&hidden));
+
+        let off_screen = SourceFilter {
+            include_off_screen: true,
+            ..Default::default()
+        };
+        assert!(off_screen.matches(&hidden));
+
+        let by_app = SourceFilter {
+            app_name: Some("safari".to_string()),
+            ..Default::default()
+        };
+        assert!(by_app.matches(&safari));
+
+        let by_bundle = SourceFilter {
+            bundle_id: Some("com.apple.Notes".to_string()),
+            include_off_screen: true,
+            ..Default::default()
+        };
+        assert!(!by_bundle.matches(&safari));
+        assert!(!by_bundle.matches(&hidden));
+
+        let min_size = SourceFilter {
+            min_width: Some(1000),
+            ..Default::default()
+        };
+        let small = ScreenSource::new(
+            "window_3".to_string(),
+            "Finder - Document".to_string(),
+            SourceType::Window,
+            800,
+            600,
+        )
+        .with_app_name("Finder".to_string());
+        assert!(min_size.matches(&safari));
+        assert!(!min_size.matches(&small));
+
+        let query = SourceFilter {
+            query: Some("DOCUMENT".to_string()),
+            ..Default::default()
+        };
+        assert!(query.matches(&safari));
+    }
+
+    #[test]
+    fn test_source_filter_sort() {
+        let sources = vec![
+            ScreenSource::new(
+                "window_1".to_string(),
+                "Safari - Document".to_string(),
+                SourceType::Window,
+                800,
+                600,
+            )
+            .with_app_name("Safari".to_string()),
+            ScreenSource::new(
+                "window_2".to_string(),
+                "Finder - Document".to_string(),
+                SourceType::Window,
+                1920,
+                1080,
+            )
+            .with_app_name("Finder".to_string()),
+            ScreenSource::new(
+                "window_3".to_string(),
+                "Mail - Document".to_string(),
+                SourceType::Window,
+                400,
+                300,
+            )
+            .with_app_name("Mail".to_string()),
+        ];
+
+        let ids = |sort: SourceSortOrder| -> Vec<String> {
+            let filter = SourceFilter {
+                sort,
+                ..Default::default()
+            };
+            filter
+                .apply(sources.clone())
+                .into_iter()
+                .map(|s| s.id)
+                .collect()
+        };
+
+        assert_eq!(
+            ids(SourceSortOrder::System),
+            ["window_1", "window_2", "window_3"]
+        );
+        assert_eq!(
+            ids(SourceSortOrder::AppName),
+            ["window_2", "window_3", "window_1"]
+        );
+        assert_eq!(
+            ids(SourceSortOrder::Area),
+            ["window_2", "window_1", "window_3"]
+        );
+    }
 }
@@ -1,16 +1,32 @@
-use super::{ScreenSource, SourceEnumerator};
+use super::{ScreenSource, ScreenshotFormat, SourceEnumerator};
+use crate::commands::cursor::CaptureRegion;
+use std::path::Path;
 
 /// Stub implementation for non-macOS platforms
 pub struct PlatformEnumerator;
 
 impl SourceEnumerator for PlatformEnumerator {
-    fn enumerate_screens() -> Result<Vec<ScreenSource>, String> {
+    fn enumerate_screens_fast() -> Result<Vec<ScreenSource>, String> {
         // TODO: Implement Windows and Linux screen enumeration
         Err("Screen enumeration not implemented for this platform".to_string())
     }
 
-    fn enumerate_windows() -> Result<Vec<ScreenSource>, String> {
+    fn enumerate_windows_fast() -> Result<Vec<ScreenSource>, String> {
         // TODO: Implement Windows and Linux window enumeration
         Err("Window enumeration not implemented for this platform".to_string())
     }
+
+    fn capture_thumbnail(_source_id: &str) -> Option<String> {
+        None
+    }
+
+    fn capture_screenshot(
+        _source_id: &str,
+        _region: Option<&CaptureRegion>,
+        _format: ScreenshotFormat,
+        _output_path: &Path,
+    ) -> Result<(), String> {
+        // TODO: Implement Windows and Linux screenshots
+        Err("Screenshots not implemented for this platform".to_string())
+    }
 }
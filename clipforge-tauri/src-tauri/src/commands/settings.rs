@@ -0,0 +1,254 @@
+// Application settings
+//
+// Preferences that outlive a single command call (the default recording
+// configuration, library, watch and cache folders, hotkeys, FFmpeg time limits,
+// whether links may start recordings) are kept in `settings.json` in the app
+// data directory. `update_settings` takes a JSON merge patch so the frontend can
+// change one field without resending the rest, and every change is broadcast as
+// "settings:changed". Settings that drive running services (hotkeys, the watch
+// folder, the cache location, process timeouts) are applied when they load and
+// whenever they change.
+
+use super::app_cache;
+use super::hotkeys;
+use super::process_runner::{self, ProcessTimeouts};
+use super::recording::{MicHotkey, RecordingConfig};
+use super::watch_folder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Global shortcuts for app actions; unset actions have no shortcut
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HotkeySettings {
+    /// Start or stop a recording
+    pub toggle_recording: Option<String>,
+    /// Pause or resume the current recording
+    pub toggle_pause: Option<String>,
+    /// Add a marker to the current recording
+    pub add_marker: Option<String>,
+    /// Save the replay buffer
+    pub save_replay: Option<String>,
+    /// Mute or unmute the microphone; used by recordings without their own hotkey
+    pub mic: Option<MicHotkey>,
+}
+
+/// Persistent application settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    /// Configuration used for recordings started without one
+    pub default_recording_config: RecordingConfig,
+    /// Folder finished recordings and exports are saved to
+    pub library_path: Option<String>,
+    /// Folder another app records into; new files there are imported automatically
+    pub watch_folder: Option<String>,
+    /// Folder for thumbnails, intermediates and in-progress recordings;
+    /// the platform cache directory when unset
+    pub cache_directory: Option<String>,
+    pub hotkeys: HotkeySettings,
+    /// Time limits after which a hung FFmpeg is killed
+    pub process_timeouts: ProcessTimeouts,
+    /// Whether clipforge://record links may start a recording
+    pub allow_deep_link_recording: bool,
+}
+
+impl AppSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        self.default_recording_config.validate()?;
+
+        if let Some(path) = &self.library_path {
+            if !Path::new(path).is_absolute() {
+                return Err("Library path must be an absolute path".to_string());
+            }
+        }
+        if let Some(folder) = &self.watch_folder {
+            if !Path::new(folder).is_absolute() {
+                return Err("Watch folder must be an absolute path".to_string());
+            }
+            // ClipForge's own recordings would be imported a second time
+            if self.library_path.as_ref() == Some(folder) {
+                return Err("Watch folder cannot be the library folder".to_string());
+            }
+        }
+        if let Some(dir) = &self.cache_directory {
+            if !Path::new(dir).is_absolute() {
+                return Err("Cache directory must be an absolute path".to_string());
+            }
+        }
+        self.process_timeouts.validate()?;
+
+        let hotkeys = &self.hotkeys;
+        let mut shortcuts: Vec<&str> = [
+            &hotkeys.toggle_recording,
+            &hotkeys.toggle_pause,
+            &hotkeys.add_marker,
+            &hotkeys.save_replay,
+        ]
+        .into_iter()
+        .flatten()
+        .map(|shortcut| shortcut.as_str())
+        .chain(hotkeys.mic.as_ref().map(|mic| mic.shortcut.as_str()))
+        .collect();
+        if shortcuts.iter().any(|shortcut| shortcut.trim().is_empty()) {
+            return Err("Hotkeys cannot be empty".to_string());
+        }
+        shortcuts.sort_unstable();
+        if let Some(pair) = shortcuts.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(format!(
+                "Hotkey {} is assigned to more than one action",
+                pair[0]
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+pub type SettingsState = Arc<Mutex<AppSettings>>;
+
+/// Apply an RFC 7386 JSON merge patch: objects merge, `null` removes, anything else replaces
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir.join("settings.json"))
+}
+
+fn save_settings(app: &AppHandle, settings: &AppSettings) -> Result<(), String> {
+    let path = settings_path(app)?;
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, content).map_err(|e| format!("Failed to write settings: {}", e))?;
+    fs::rename(&temp_path, &path).map_err(|e| format!("Failed to write settings: {}", e))
+}
+
+/// Read saved settings, falling back to defaults when missing or unreadable
+pub fn load_settings(app: &AppHandle) -> SettingsState {
+    let settings = settings_path(app)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| {
+            serde_json::from_str(&content)
+                .map_err(|e| println!("[Settings] Ignoring unreadable settings file: {}", e))
+                .ok()
+        })
+        .unwrap_or_default();
+
+    process_runner::set_timeouts(settings.process_timeouts);
+    app_cache::init(app, settings.cache_directory.as_deref().map(PathBuf::from));
+    hotkeys::register_hotkeys(app, &settings.hotkeys);
+    Arc::new(Mutex::new(settings))
+}
+
+/// Get the current settings
+#[tauri::command]
+pub async fn get_settings(state: State<'_, SettingsState>) -> Result<AppSettings, String> {
+    Ok(state.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Merge `patch` into the settings, save them and emit "settings:changed"
+///
+/// Returns the updated settings
+#[tauri::command]
+pub async fn update_settings(
+    app: AppHandle,
+    state: State<'_, SettingsState>,
+    patch: Value,
+) -> Result<AppSettings, String> {
+    let settings = {
+        let mut current = state.lock().map_err(|e| e.to_string())?;
+
+        let mut merged = serde_json::to_value(&*current).map_err(|e| e.to_string())?;
+        merge_patch(&mut merged, &patch);
+        let settings: AppSettings =
+            serde_json::from_value(merged).map_err(|e| format!("Invalid settings: {}", e))?;
+        settings.validate()?;
+        watch_folder::set_watch_folder(&app, settings.watch_folder.as_deref())?;
+        app_cache::set_location(settings.cache_directory.as_deref().map(PathBuf::from))?;
+        hotkeys::replace_hotkeys(&app, &current.hotkeys, &settings.hotkeys)?;
+
+        save_settings(&app, &settings)?;
+        process_runner::set_timeouts(settings.process_timeouts);
+        *current = settings.clone();
+        settings
+    };
+
+    let _ = app.emit("settings:changed", &settings);
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_patch() {
+        let mut settings = json!({
+            "libraryPath": "/Users/me/Movies",
+            "hotkeys": { "addMarker": "CmdOrCtrl+Shift+M", "saveReplay": "F9" },
+            "allowDeepLinkRecording": false
+        });
+        merge_patch(
+            &mut settings,
+            &json!({
+                "libraryPath": null,
+                "hotkeys": { "saveReplay": "F10" },
+                "allowDeepLinkRecording": true
+            }),
+        );
+
+        assert_eq!(
+            settings,
+            json!({
+                "hotkeys": { "addMarker": "CmdOrCtrl+Shift+M", "saveReplay": "F10" },
+                "allowDeepLinkRecording": true
+            })
+        );
+    }
+
+    #[test]
+    fn test_partial_file_uses_defaults() {
+        let settings: AppSettings =
+            serde_json::from_str(r#"{"allowDeepLinkRecording": true}"#).unwrap();
+        assert!(settings.allow_deep_link_recording);
+        assert!(settings.cache_directory.is_none());
+        assert_eq!(settings.hotkeys, HotkeySettings::default());
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_duplicate_hotkeys_rejected() {
+        let mut settings = AppSettings::default();
+        settings.hotkeys.toggle_recording = Some("CmdOrCtrl+Shift+R".to_string());
+        settings.hotkeys.save_replay = Some("CmdOrCtrl+Shift+R".to_string());
+        assert!(settings.validate().is_err());
+    }
+}
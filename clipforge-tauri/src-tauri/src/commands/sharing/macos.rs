@@ -0,0 +1,127 @@
+// macOS sharing using AppKit
+
+use super::{ClipboardContent, ShareHandler};
+use cocoa::base::{id, nil};
+use cocoa::foundation::{NSPoint, NSRect, NSSize, NSString};
+use objc::runtime::{BOOL, NO};
+use objc::{class, msg_send, sel, sel_impl};
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use tauri::WebviewWindow;
+
+/// `NSMaxYEdge`: the share menu opens above its anchor
+const PREFERRED_EDGE: usize = 3;
+
+thread_local! {
+    /// The share menu currently shown, released when the next one opens
+    static SHARE_PICKER: Cell<id> = const { Cell::new(nil) };
+}
+
+/// macOS platform sharing
+pub struct PlatformSharing;
+
+impl ShareHandler for PlatformSharing {
+    fn copy_to_clipboard(path: &Path, content: ClipboardContent) -> Result<(), String> {
+        unsafe { write_to_pasteboard(path, content) }
+    }
+
+    fn share_file(window: &WebviewWindow, path: &Path) -> Result<(), String> {
+        let ns_window = window
+            .ns_window()
+            .map_err(|e| format!("Failed to get the native window: {}", e))?
+            as usize;
+        let path = PathBuf::from(path);
+
+        // AppKit UI has to be created on the main thread
+        let (tx, rx) = mpsc::channel();
+        window
+            .run_on_main_thread(move || {
+                let _ = tx.send(unsafe { show_share_picker(ns_window as id, &path) });
+            })
+            .map_err(|e| format!("Failed to show the share menu: {}", e))?;
+        rx.recv()
+            .map_err(|_| "The share menu was not shown".to_string())?
+    }
+}
+
+/// File URL for a path
+unsafe fn file_url(path: &Path) -> Result<id, String> {
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| format!("Invalid path: {}", path.display()))?;
+    let ns_path = NSString::alloc(nil).init_str(path_str);
+    let url: id = msg_send![class!(NSURL), fileURLWithPath: ns_path];
+    if url == nil {
+        return Err(format!("Invalid path: {}", path.display()));
+    }
+    Ok(url)
+}
+
+/// Replace the general pasteboard's contents with a file URL or image
+unsafe fn write_to_pasteboard(path: &Path, content: ClipboardContent) -> Result<(), String> {
+    let url = file_url(path)?;
+
+    let (object, owned) = match content {
+        ClipboardContent::File => (url, false),
+        ClipboardContent::Image => {
+            let image: id = msg_send![class!(NSImage), alloc];
+            let image: id = msg_send![image, initWithContentsOfURL: url];
+            if image == nil {
+                return Err(format!("Could not read image {}", path.display()));
+            }
+            (image, true)
+        }
+    };
+
+    let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+    let objects: id = msg_send![class!(NSArray), arrayWithObject: object];
+    let _: isize = msg_send![pasteboard, clearContents];
+    let written: BOOL = msg_send![pasteboard, writeObjects: objects];
+    if owned {
+        let _: () = msg_send![object, release];
+    }
+
+    if written == NO {
+        return Err("The clipboard did not accept the file".to_string());
+    }
+    Ok(())
+}
+
+/// Show an NSSharingServicePicker for a file in the middle of a window
+///
+/// Must be called on the main thread.
+unsafe fn show_share_picker(ns_window: id, path: &Path) -> Result<(), String> {
+    let view: id = msg_send![ns_window, contentView];
+    if view == nil {
+        return Err("The window has no content view".to_string());
+    }
+
+    let url = file_url(path)?;
+    let items: id = msg_send![class!(NSArray), arrayWithObject: url];
+    let picker: id = msg_send![class!(NSSharingServicePicker), alloc];
+    let picker: id = msg_send![picker, initWithItems: items];
+    if picker == nil {
+        return Err("Failed to create the share menu".to_string());
+    }
+
+    let bounds: NSRect = msg_send![view, bounds];
+    let anchor = NSRect::new(
+        NSPoint::new(bounds.size.width / 2.0, bounds.size.height / 2.0),
+        NSSize::new(1.0, 1.0),
+    );
+    let _: () = msg_send![
+        picker,
+        showRelativeToRect: anchor
+        ofView: view
+        preferredEdge: PREFERRED_EDGE
+    ];
+
+    // AppKit doesn't retain the picker while its menu is open, so keep it
+    // until the next share replaces it
+    let previous = SHARE_PICKER.with(|current| current.replace(picker));
+    if previous != nil {
+        let _: () = msg_send![previous, release];
+    }
+    Ok(())
+}
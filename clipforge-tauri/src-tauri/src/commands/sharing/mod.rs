@@ -0,0 +1,99 @@
+// Platform-specific sharing of recordings and exports
+#[cfg(target_os = "macos")]
+mod macos;
+
+#[cfg(not(target_os = "macos"))]
+mod stub;
+
+// Re-export the platform-specific implementation
+#[cfg(target_os = "macos")]
+pub use macos::*;
+
+#[cfg(not(target_os = "macos"))]
+pub use stub::*;
+
+use super::metadata::{is_gif, MediaKind};
+use std::path::Path;
+use tauri::WebviewWindow;
+
+/// How a file is placed on the clipboard
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClipboardContent {
+    /// The file itself, pasted as an attachment
+    File,
+    /// The decoded image, pasted inline
+    Image,
+}
+
+impl ClipboardContent {
+    /// Stills are copied as images; GIFs stay files so they keep animating
+    pub fn for_path(path: &Path) -> Self {
+        if MediaKind::from_path(path) == MediaKind::Image && !is_gif(path) {
+            ClipboardContent::Image
+        } else {
+            ClipboardContent::File
+        }
+    }
+}
+
+/// Trait for platform-specific sharing
+pub trait ShareHandler {
+    /// Replace the clipboard contents with a file
+    fn copy_to_clipboard(path: &Path, content: ClipboardContent) -> Result<(), String>;
+
+    /// Show the system share menu for a file, anchored to `window`
+    fn share_file(window: &WebviewWindow, path: &Path) -> Result<(), String>;
+}
+
+/// Copy an exported file or frame image to the clipboard so it can be pasted elsewhere
+#[tauri::command]
+pub async fn copy_to_clipboard(path: String) -> Result<(), String> {
+    let file = Path::new(&path);
+    if !file.is_file() {
+        return Err(format!("File not found: {}", path));
+    }
+
+    let content = ClipboardContent::for_path(file);
+    PlatformSharing::copy_to_clipboard(file, content)?;
+    println!(
+        "[Sharing] Copied {} to the clipboard as {:?}",
+        path, content
+    );
+    Ok(())
+}
+
+/// Open the native share menu (AirDrop, Messages, Mail, ...) for a recording or export
+#[tauri::command]
+pub async fn share_file(window: WebviewWindow, path: String) -> Result<(), String> {
+    let file = Path::new(&path);
+    if !file.is_file() {
+        return Err(format!("File not found: {}", path));
+    }
+
+    PlatformSharing::share_file(&window, file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clipboard_content_for_path() {
+        assert_eq!(
+            ClipboardContent::for_path(Path::new("/tmp/frame.PNG")),
+            ClipboardContent::Image
+        );
+        assert_eq!(
+            ClipboardContent::for_path(Path::new("/tmp/frame.jpg")),
+            ClipboardContent::Image
+        );
+        assert_eq!(
+            ClipboardContent::for_path(Path::new("/tmp/clip.gif")),
+            ClipboardContent::File
+        );
+        assert_eq!(
+            ClipboardContent::for_path(Path::new("/tmp/clip.mp4")),
+            ClipboardContent::File
+        );
+    }
+}
@@ -0,0 +1,20 @@
+// Stub implementation for non-macOS platforms
+
+use super::{ClipboardContent, ShareHandler};
+use std::path::Path;
+use tauri::WebviewWindow;
+
+/// Platform sharing (stub)
+pub struct PlatformSharing;
+
+impl ShareHandler for PlatformSharing {
+    fn copy_to_clipboard(_path: &Path, _content: ClipboardContent) -> Result<(), String> {
+        // TODO: Implement for Windows and Linux
+        Err("Copying files to the clipboard is not supported on this platform".to_string())
+    }
+
+    fn share_file(_window: &WebviewWindow, _path: &Path) -> Result<(), String> {
+        // TODO: Implement for Windows and Linux
+        Err("Sharing files is not supported on this platform".to_string())
+    }
+}
@@ -0,0 +1,587 @@
+// Intro and outro stingers
+//
+// Joins a branded intro and/or outro (a video or a still image) onto a finished
+// recording or export. Every part is scaled and padded to the main video's size
+// and frame rate, stills are held for `image_duration` seconds and parts
+// without audio get silence. Parts are joined with a hard cut, a crossfade or a
+// fade through black; the result is re-encoded in place, in the main video's
+// pixel format and audio layout and with the caller's encoder settings, so a
+// lossless recording stays lossless. Subtitle streams are carried over.
+
+use super::ffmpeg_utils::{find_ffmpeg, find_ffprobe};
+use super::metadata::{is_gif, parse_rational, MediaKind};
+use super::process_runner::{JobKind, WatchedOutput};
+use super::recording::{ffmetadata_chapters, Chapter};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Longest transition between parts, in seconds
+const MAX_TRANSITION_SECONDS: f64 = 5.0;
+
+/// How the stingers are joined to the main video
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StingerTransition {
+    Cut,
+    #[default]
+    Crossfade,
+    FadeThroughBlack,
+}
+
+impl StingerTransition {
+    /// xfade transition name, or None for a hard cut
+    fn xfade_name(&self) -> Option<&'static str> {
+        match self {
+            StingerTransition::Cut => None,
+            StingerTransition::Crossfade => Some("fade"),
+            StingerTransition::FadeThroughBlack => Some("fadeblack"),
+        }
+    }
+}
+
+/// An intro or outro clip
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stinger {
+    /// Video or image file
+    pub path: String,
+    /// Seconds an image is shown for; videos always play in full
+    #[serde(default = "default_image_duration")]
+    pub image_duration: f64,
+}
+
+fn default_image_duration() -> f64 {
+    3.0
+}
+
+/// Stingers joined onto a recording or export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StingerSettings {
+    pub intro: Option<Stinger>,
+    pub outro: Option<Stinger>,
+    pub transition: StingerTransition,
+    /// Length of each transition in seconds (ignored for cuts)
+    pub transition_duration: f64,
+}
+
+impl Default for StingerSettings {
+    fn default() -> Self {
+        Self {
+            intro: None,
+            outro: None,
+            transition: StingerTransition::default(),
+            transition_duration: 0.5,
+        }
+    }
+}
+
+/// Where the main video ended up after joining
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JoinedStingers {
+    /// Seconds the main video now starts at
+    pub content_offset: f64,
+    /// Length of the joined file in seconds
+    pub duration: f64,
+}
+
+/// Length and audio of one part of the joined video
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Part {
+    duration: f64,
+    has_audio: bool,
+}
+
+/// Size, frame rate, formats, length and audio of a media file
+struct ProbedMedia {
+    width: u32,
+    height: u32,
+    frame_rate: f64,
+    pix_fmt: Option<String>,
+    sample_rate: Option<u32>,
+    channels: Option<u32>,
+    duration: f64,
+    has_audio: bool,
+}
+
+impl ProbedMedia {
+    /// Channel layout the joined audio uses
+    fn channel_layout(&self) -> String {
+        match self.channels.unwrap_or(2) {
+            1 => "mono".to_string(),
+            2 => "stereo".to_string(),
+            channels => format!("{}c", channels),
+        }
+    }
+}
+
+impl StingerSettings {
+    pub fn is_empty(&self) -> bool {
+        self.intro.is_none() && self.outro.is_none()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.transition != StingerTransition::Cut
+            && !(self.transition_duration > 0.0
+                && self.transition_duration <= MAX_TRANSITION_SECONDS)
+        {
+            return Err(format!(
+                "Stinger transition must be between 0 and {} seconds",
+                MAX_TRANSITION_SECONDS
+            ));
+        }
+
+        for stinger in self.intro.iter().chain(self.outro.iter()) {
+            if !Path::new(&stinger.path).exists() {
+                return Err(format!("Stinger not found: {}", stinger.path));
+            }
+            if !(0.5..=60.0).contains(&stinger.image_duration) {
+                return Err("Stinger image duration must be between 0.5 and 60 seconds".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Join the stingers onto a video in place
+    ///
+    /// `chapters` are in the video's own time and are moved after the intro.
+    /// `encoder_args` are the output codec arguments (e.g. from
+    /// `RecordingConfig::file_encoder_args`); when empty, H.264/AAC (or VP9/Opus
+    /// for WebM) is used.
+    pub fn apply(
+        &self,
+        video_path: &Path,
+        chapters: &[Chapter],
+        encoder_args: &[String],
+    ) -> Result<JoinedStingers, String> {
+        self.validate()?;
+
+        let ffmpeg_path = find_ffmpeg().ok_or_else(|| "FFmpeg not found".to_string())?;
+        let main = probe(video_path)?;
+        if main.width == 0 || main.height == 0 || main.frame_rate <= 0.0 {
+            return Err("Recording has no video stream to add stingers to".to_string());
+        }
+
+        let mut command = Command::new(&ffmpeg_path);
+        command.arg("-y");
+
+        // Inputs are numbered in playback order: intro, main video, outro
+        let mut parts = Vec::new();
+        if let Some(intro) = &self.intro {
+            parts.push(add_stinger_input(&mut command, intro, main.frame_rate)?);
+        }
+        let main_index = parts.len();
+        command.arg("-i").arg(video_path);
+        parts.push(Part {
+            duration: main.duration,
+            has_audio: main.has_audio,
+        });
+        if let Some(outro) = &self.outro {
+            parts.push(add_stinger_input(&mut command, outro, main.frame_rate)?);
+        }
+
+        let (graph, joined) = join_graph(
+            &parts,
+            main_index,
+            &main,
+            self.transition,
+            self.transition_duration,
+        )?;
+
+        // Chapters are rewritten rather than copied so they follow the intro
+        let chapters_path = video_path.with_extension("stinger-chapters.txt");
+        if chapters.is_empty() {
+            command.arg("-map_chapters").arg("-1");
+        } else {
+            let shifted: Vec<Chapter> = chapters
+                .iter()
+                .map(|chapter| Chapter {
+                    start: chapter.start + joined.content_offset,
+                    end: chapter.end + joined.content_offset,
+                    title: chapter.title.clone(),
+                })
+                .collect();
+            fs::write(&chapters_path, ffmetadata_chapters(&shifted))
+                .map_err(|e| format!("Failed to write chapters file: {}", e))?;
+            command
+                .arg("-i")
+                .arg(&chapters_path)
+                .arg("-map_chapters")
+                .arg(parts.len().to_string());
+        }
+
+        let extension = video_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("mp4")
+            .to_lowercase();
+        let joined_path = video_path.with_extension(format!("stingers.{}", extension));
+
+        command
+            .arg("-filter_complex")
+            .arg(graph)
+            .arg("-map")
+            .arg("[vout]")
+            .arg("-map")
+            .arg("[aout]")
+            .arg("-map")
+            .arg(format!("{}:s?", main_index))
+            .arg("-c:s")
+            .arg("copy");
+        if encoder_args.is_empty() {
+            let (video_codec, audio_codec) = if extension == "webm" {
+                ("libvpx-vp9", "libopus")
+            } else {
+                ("libx264", "aac")
+            };
+            command
+                .arg("-c:v")
+                .arg(video_codec)
+                .arg("-pix_fmt")
+                .arg("yuv420p")
+                .arg("-c:a")
+                .arg(audio_codec);
+        } else {
+            command.args(encoder_args);
+        }
+
+        let output = command
+            .arg(&joined_path)
+            .watched_output(JobKind::Encode)
+            .map_err(|e| format!("Failed to run FFmpeg: {}", e));
+        let _ = fs::remove_file(&chapters_path);
+        let output = output?;
+
+        if !output.status.success() {
+            let _ = fs::remove_file(&joined_path);
+            return Err(format!(
+                "Failed to add stingers: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        fs::rename(&joined_path, video_path)
+            .map_err(|e| format!("Failed to replace video: {}", e))?;
+
+        println!(
+            "[Stingers] Joined {} part(s) onto {}, content starts at {:.2}s",
+            parts.len(),
+            video_path.display(),
+            joined.content_offset
+        );
+        Ok(joined)
+    }
+}
+
+/// Add a stinger as an FFmpeg input, returning its part of the joined video
+fn add_stinger_input(
+    command: &mut Command,
+    stinger: &Stinger,
+    frame_rate: f64,
+) -> Result<Part, String> {
+    let path = Path::new(&stinger.path);
+
+    if MediaKind::from_path(path) == MediaKind::Image {
+        if is_gif(path) {
+            command.arg("-stream_loop").arg("-1");
+        } else {
+            command
+                .arg("-loop")
+                .arg("1")
+                .arg("-framerate")
+                .arg(frame_rate.to_string());
+        }
+        command
+            .arg("-t")
+            .arg(stinger.image_duration.to_string())
+            .arg("-i")
+            .arg(path);
+        return Ok(Part {
+            duration: stinger.image_duration,
+            has_audio: false,
+        });
+    }
+
+    let media = probe(path)?;
+    command.arg("-i").arg(path);
+    Ok(Part {
+        duration: media.duration,
+        has_audio: media.has_audio,
+    })
+}
+
+/// Filter graph joining the parts into `[vout]` and `[aout]` in the main video's format
+fn join_graph(
+    parts: &[Part],
+    main_index: usize,
+    main: &ProbedMedia,
+    transition: StingerTransition,
+    transition_duration: f64,
+) -> Result<(String, JoinedStingers), String> {
+    let mut graph = Vec::new();
+    let pix_fmt = main.pix_fmt.as_deref().unwrap_or("yuv420p");
+    let sample_rate = main.sample_rate.unwrap_or(48000);
+    let channel_layout = main.channel_layout();
+
+    for (i, part) in parts.iter().enumerate() {
+        graph.push(format!(
+            "[{i}:v]scale={w}:{h}:force_original_aspect_ratio=decrease,\
+             pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,setsar=1,fps={fps},format={pix_fmt}[v{i}]",
+            i = i,
+            w = main.width,
+            h = main.height,
+            fps = main.frame_rate,
+            pix_fmt = pix_fmt
+        ));
+        if part.has_audio {
+            graph.push(format!(
+                "[{}:a]aresample={},aformat=channel_layouts={}[a{}]",
+                i, sample_rate, channel_layout, i
+            ));
+        } else {
+            graph.push(format!(
+                "anullsrc=r={}:cl={},atrim=duration={}[a{}]",
+                sample_rate, channel_layout, part.duration, i
+            ));
+        }
+    }
+
+    let Some(xfade) = transition.xfade_name() else {
+        let inputs: String = (0..parts.len())
+            .map(|i| format!("[v{}][a{}]", i, i))
+            .collect();
+        graph.push(format!(
+            "{}concat=n={}:v=1:a=1[vout][aout]",
+            inputs,
+            parts.len()
+        ));
+        return Ok((
+            graph.join(";"),
+            JoinedStingers {
+                content_offset: parts[..main_index].iter().map(|p| p.duration).sum(),
+                duration: parts.iter().map(|p| p.duration).sum(),
+            },
+        ));
+    };
+
+    if parts
+        .iter()
+        .any(|part| part.duration <= transition_duration)
+    {
+        return Err("Stingers must be longer than the transition".to_string());
+    }
+
+    let mut video = "v0".to_string();
+    let mut audio = "a0".to_string();
+    let mut length = parts[0].duration;
+    let mut content_offset = 0.0;
+    for (i, part) in parts.iter().enumerate().skip(1) {
+        let offset = length - transition_duration;
+        if i == main_index {
+            content_offset = offset;
+        }
+
+        let (next_video, next_audio) = if i == parts.len() - 1 {
+            ("vout".to_string(), "aout".to_string())
+        } else {
+            (format!("vx{}", i), format!("ax{}", i))
+        };
+        graph.push(format!(
+            "[{}][v{}]xfade=transition={}:duration={}:offset={}[{}]",
+            video, i, xfade, transition_duration, offset, next_video
+        ));
+        graph.push(format!(
+            "[{}][a{}]acrossfade=d={}[{}]",
+            audio, i, transition_duration, next_audio
+        ));
+
+        video = next_video;
+        audio = next_audio;
+        length += part.duration - transition_duration;
+    }
+
+    Ok((
+        graph.join(";"),
+        JoinedStingers {
+            content_offset,
+            duration: length,
+        },
+    ))
+}
+
+/// Size, frame rate, length and audio of a media file
+fn probe(path: &Path) -> Result<ProbedMedia, String> {
+    #[derive(Deserialize)]
+    struct ProbeOutput {
+        format: Option<ProbeFormat>,
+        #[serde(default)]
+        streams: Vec<ProbeStream>,
+    }
+
+    #[derive(Deserialize)]
+    struct ProbeFormat {
+        duration: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct ProbeStream {
+        codec_type: Option<String>,
+        width: Option<u32>,
+        height: Option<u32>,
+        r_frame_rate: Option<String>,
+        pix_fmt: Option<String>,
+        sample_rate: Option<String>,
+        channels: Option<u32>,
+    }
+
+    let ffprobe_path = find_ffprobe().ok_or_else(|| "FFprobe not found".to_string())?;
+    let output = Command::new(ffprobe_path)
+        .arg("-v")
+        .arg("error")
+        .arg("-show_entries")
+        .arg("format=duration:stream=codec_type,width,height,r_frame_rate,pix_fmt,sample_rate,channels")
+        .arg("-of")
+        .arg("json")
+        .arg(path)
+        .watched_output(JobKind::Probe)
+        .map_err(|e| format!("Failed to run FFprobe: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to read {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let probed: ProbeOutput = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse FFprobe output: {}", e))?;
+    let video = probed
+        .streams
+        .iter()
+        .find(|s| s.codec_type.as_deref() == Some("video"));
+    let audio = probed
+        .streams
+        .iter()
+        .find(|s| s.codec_type.as_deref() == Some("audio"));
+
+    Ok(ProbedMedia {
+        width: video.and_then(|s| s.width).unwrap_or(0),
+        height: video.and_then(|s| s.height).unwrap_or(0),
+        frame_rate: video
+            .and_then(|s| s.r_frame_rate.as_deref())
+            .and_then(parse_rational)
+            .unwrap_or(0.0),
+        pix_fmt: video.and_then(|s| s.pix_fmt.clone()),
+        sample_rate: audio
+            .and_then(|s| s.sample_rate.as_deref())
+            .and_then(|rate| rate.parse().ok()),
+        channels: audio.and_then(|s| s.channels),
+        duration: probed
+            .format
+            .and_then(|f| f.duration)
+            .and_then(|d| d.parse().ok())
+            .unwrap_or(0.0),
+        has_audio: audio.is_some(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crossfade_offsets() {
+        let parts = [
+            Part {
+                duration: 3.0,
+                has_audio: false,
+            },
+            Part {
+                duration: 60.0,
+                has_audio: true,
+            },
+            Part {
+                duration: 4.0,
+                has_audio: true,
+            },
+        ];
+        let main = ProbedMedia {
+            width: 1920,
+            height: 1080,
+            frame_rate: 30.0,
+            pix_fmt: Some("yuv444p".to_string()),
+            sample_rate: None,
+            channels: None,
+            duration: 60.0,
+            has_audio: true,
+        };
+        let (graph, joined) =
+            join_graph(&parts, 1, &main, StingerTransition::Crossfade, 0.5).unwrap();
+
+        assert_eq!(joined.content_offset, 2.5);
+        assert_eq!(joined.duration, 66.0);
+        assert!(graph.contains("anullsrc=r=48000:cl=stereo,atrim=duration=3[a0]"));
+        assert!(graph.contains("fps=30,format=yuv444p[v1]"));
+        assert!(graph.contains("[v0][v1]xfade=transition=fade:duration=0.5:offset=2.5[vx1]"));
+        assert!(graph.contains("[vx1][v2]xfade=transition=fade:duration=0.5:offset=62[vout]"));
+        assert!(graph.contains("[ax1][a2]acrossfade=d=0.5[aout]"));
+    }
+
+    #[test]
+    fn test_cut_outro_only() {
+        let parts = [
+            Part {
+                duration: 60.0,
+                has_audio: true,
+            },
+            Part {
+                duration: 4.0,
+                has_audio: false,
+            },
+        ];
+        let main = ProbedMedia {
+            width: 1280,
+            height: 720,
+            frame_rate: 30.0,
+            pix_fmt: None,
+            sample_rate: Some(44100),
+            channels: Some(1),
+            duration: 60.0,
+            has_audio: true,
+        };
+        let (graph, joined) = join_graph(&parts, 0, &main, StingerTransition::Cut, 0.5).unwrap();
+
+        assert_eq!(joined.content_offset, 0.0);
+        assert_eq!(joined.duration, 64.0);
+        assert!(graph.contains("anullsrc=r=44100:cl=mono,atrim=duration=4[a1]"));
+        assert!(graph.ends_with("[v0][a0][v1][a1]concat=n=2:v=1:a=1[vout][aout]"));
+    }
+
+    #[test]
+    fn test_transition_longer_than_stinger() {
+        let parts = [
+            Part {
+                duration: 0.4,
+                has_audio: false,
+            },
+            Part {
+                duration: 60.0,
+                has_audio: true,
+            },
+        ];
+        let main = ProbedMedia {
+            width: 1920,
+            height: 1080,
+            frame_rate: 30.0,
+            pix_fmt: None,
+            sample_rate: None,
+            channels: None,
+            duration: 60.0,
+            has_audio: true,
+        };
+        assert!(join_graph(&parts, 1, &main, StingerTransition::FadeThroughBlack, 0.5).is_err());
+    }
+}
@@ -0,0 +1,325 @@
+// iCloud and network volume awareness
+//
+// Files in iCloud Drive can be "dataless": the name and size are on disk but
+// the content is still in the cloud, so FFmpeg fails on them with errors that
+// say nothing about iCloud. `ensure_local` detects such placeholders (the
+// dataless flag, or the ".<name>.icloud" stubs older macOS versions leave),
+// asks iCloud to download them and reports progress until the content is on
+// disk. Network shares (SMB, AFP, NFS, WebDAV) are detected so exports can be
+// built on a local disk and copied over once finished.
+//
+// Progress comes from the blocks allocated to the file, which some downloads
+// never update and `.icloud` stubs don't have at all. A download is only
+// considered stalled once it has reported some progress and then stops.
+
+use super::process_runner::{watched_output_async, JobKind};
+use crate::error::ClipForgeError;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// How often a downloading file is checked
+const DOWNLOAD_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long a download that has reported progress may stop before giving up
+const DOWNLOAD_STALL: Duration = Duration::from_secs(60);
+
+/// `st_flags` bit macOS sets on files whose content is not on disk
+#[cfg(target_os = "macos")]
+const SF_DATALESS: u32 = 0x4000_0000;
+
+/// File system types of network shares as reported by statfs
+const NETWORK_FILE_SYSTEMS: [&str; 5] = ["smbfs", "afpfs", "nfs", "webdav", "cifs"];
+
+/// Where a file's content lives
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageStatus {
+    pub path: String,
+    /// Content is in iCloud and has not been downloaded
+    pub in_cloud: bool,
+    /// File system of the network share holding the file, if any
+    pub network_volume: Option<String>,
+}
+
+/// Payload for the "storage:download-progress" event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProgress {
+    pub path: String,
+    pub downloaded_bytes: u64,
+    /// Zero when iCloud has not reported the size yet
+    pub total_bytes: u64,
+}
+
+impl DownloadProgress {
+    /// Percentage downloaded (0 - 100)
+    pub fn percent(&self) -> f64 {
+        if self.total_bytes == 0 {
+            return 0.0;
+        }
+        (self.downloaded_bytes as f64 / self.total_bytes as f64 * 100.0).min(100.0)
+    }
+}
+
+/// Hidden stub older macOS versions leave in place of an evicted file
+fn icloud_stub(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_str()?;
+    Some(path.with_file_name(format!(".{}.icloud", name)))
+}
+
+#[cfg(target_os = "macos")]
+fn is_dataless(metadata: &fs::Metadata) -> bool {
+    use std::os::macos::fs::MetadataExt;
+    metadata.st_flags() & SF_DATALESS != 0
+}
+
+#[cfg(not(target_os = "macos"))]
+fn is_dataless(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+/// Bytes of a file's content actually stored on disk
+#[cfg(unix)]
+fn allocated_bytes(metadata: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn allocated_bytes(metadata: &fs::Metadata) -> u64 {
+    metadata.len()
+}
+
+/// How much of a file has been downloaded, or `None` when its content is on disk
+fn pending_download(path: &Path) -> Option<DownloadProgress> {
+    let progress = |downloaded_bytes, total_bytes| DownloadProgress {
+        path: path.to_string_lossy().to_string(),
+        downloaded_bytes,
+        total_bytes,
+    };
+
+    match fs::metadata(path) {
+        Ok(metadata) if is_dataless(&metadata) => Some(progress(
+            allocated_bytes(&metadata).min(metadata.len()),
+            metadata.len(),
+        )),
+        Ok(_) => None,
+        Err(_) => icloud_stub(path)
+            .filter(|stub| stub.exists())
+            .map(|_| progress(0, 0)),
+    }
+}
+
+/// What statfs reports for a volume
+#[derive(Debug, Clone)]
+pub struct VolumeStats {
+    pub available_bytes: u64,
+    pub total_bytes: u64,
+    /// File system type, e.g. "apfs" or "smbfs"
+    pub fs_type: String,
+}
+
+/// Space and file system type of the volume holding `path`
+///
+/// Paths that don't exist yet, like export outputs, are looked up through
+/// their closest existing folder.
+#[cfg(target_os = "macos")]
+pub fn volume_stats(path: &Path) -> Option<VolumeStats> {
+    use std::ffi::{CStr, CString};
+    use std::os::unix::ffi::OsStrExt;
+
+    let existing = path.ancestors().find(|ancestor| ancestor.exists())?;
+    let c_path = CString::new(existing.as_os_str().as_bytes()).ok()?;
+    let mut stats: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stats) } != 0 {
+        return None;
+    }
+    let name = unsafe { CStr::from_ptr(stats.f_fstypename.as_ptr()) };
+    Some(VolumeStats {
+        available_bytes: stats.f_bavail * stats.f_bsize as u64,
+        total_bytes: stats.f_blocks * stats.f_bsize as u64,
+        fs_type: name.to_string_lossy().to_string(),
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn volume_stats(_path: &Path) -> Option<VolumeStats> {
+    None
+}
+
+/// The network file system holding `path`, if it is on a share
+pub fn network_volume(path: &Path) -> Option<String> {
+    volume_stats(path)
+        .map(|stats| stats.fs_type)
+        .filter(|fs_type| NETWORK_FILE_SYSTEMS.contains(&fs_type.as_str()))
+}
+
+/// Ask iCloud to download a file
+async fn request_download(path: &Path) -> Result<(), ClipForgeError> {
+    if !cfg!(target_os = "macos") {
+        return Err(ClipForgeError::FileUnavailable(format!(
+            "{} is not downloaded",
+            path.display()
+        )));
+    }
+
+    let mut command = tokio::process::Command::new("brctl");
+    command.arg("download").arg(path);
+    let output = watched_output_async(&mut command, JobKind::Probe)
+        .await
+        .map_err(|e| format!("Failed to run brctl: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(ClipForgeError::FileUnavailable(format!(
+            "iCloud could not download {}: {}",
+            path.display(),
+            stderr.trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Make sure a file's content is on disk, downloading it from iCloud if needed
+///
+/// Returns immediately for files that are already local. Otherwise `report`
+/// is called as the download progresses.
+pub async fn ensure_local(
+    path: &Path,
+    report: &(dyn Fn(DownloadProgress) + Sync),
+) -> Result<(), ClipForgeError> {
+    let Some(mut progress) = pending_download(path) else {
+        return Ok(());
+    };
+
+    println!("[Storage] Downloading {} from iCloud", path.display());
+    request_download(path).await?;
+    report(progress.clone());
+
+    let mut last_change = Instant::now();
+    loop {
+        tokio::time::sleep(DOWNLOAD_POLL_INTERVAL).await;
+
+        let Some(current) = pending_download(path) else {
+            let total_bytes = fs::metadata(path).map(|m| m.len()).unwrap_or_default();
+            report(DownloadProgress {
+                downloaded_bytes: total_bytes,
+                total_bytes,
+                ..progress
+            });
+            println!("[Storage] Downloaded {}", path.display());
+            return Ok(());
+        };
+
+        if current.downloaded_bytes != progress.downloaded_bytes {
+            last_change = Instant::now();
+            report(current.clone());
+            progress = current;
+        } else if progress.downloaded_bytes > 0 && last_change.elapsed() >= DOWNLOAD_STALL {
+            return Err(ClipForgeError::FileUnavailable(format!(
+                "{} is in iCloud and stopped downloading at {:.0}%",
+                path.display(),
+                progress.percent()
+            )));
+        }
+    }
+}
+
+/// Copy a finished file onto a network share
+///
+/// The copy is written under a temporary name and renamed into place, so a
+/// dropped connection never leaves a truncated file at `destination`. It runs
+/// on the blocking pool since a large file over a slow share takes minutes.
+pub async fn copy_to_volume(source: &Path, destination: &Path) -> Result<(), ClipForgeError> {
+    let partial = destination.with_extension(format!(
+        "{}.partial",
+        destination
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+    ));
+    println!(
+        "[Storage] Copying {} to {}",
+        source.display(),
+        destination.display()
+    );
+
+    let (source, target) = (source.to_path_buf(), destination.to_path_buf());
+    let result = tokio::task::spawn_blocking(move || {
+        let result = fs::copy(&source, &partial).and_then(|_| fs::rename(&partial, &target));
+        if result.is_err() {
+            let _ = fs::remove_file(&partial);
+        }
+        result
+    })
+    .await
+    .map_err(|e| format!("Copy task failed: {}", e))?;
+    if let Err(e) = result {
+        return Err(ClipForgeError::Io(format!(
+            "Failed to copy to {}: {}",
+            destination.display(),
+            e
+        )));
+    }
+    Ok(())
+}
+
+/// Whether a file is in iCloud or on a network share
+#[tauri::command]
+pub async fn get_storage_status(path: String) -> Result<StorageStatus, ClipForgeError> {
+    let file = Path::new(&path);
+    if !file.exists() && pending_download(file).is_none() {
+        return Err(ClipForgeError::NotFound(format!(
+            "File not found: {}",
+            path
+        )));
+    }
+
+    Ok(StorageStatus {
+        in_cloud: pending_download(file).is_some(),
+        network_volume: network_volume(file),
+        path,
+    })
+}
+
+/// Download a file from iCloud, emitting "storage:download-progress"
+#[tauri::command]
+pub async fn download_from_cloud(app: AppHandle, path: String) -> Result<(), ClipForgeError> {
+    ensure_local(Path::new(&path), &|progress| {
+        let _ = app.emit("storage:download-progress", progress);
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icloud_stub_name() {
+        assert_eq!(
+            icloud_stub(Path::new("/Users/me/Library/Mobile Documents/clip.mov")),
+            Some(PathBuf::from(
+                "/Users/me/Library/Mobile Documents/.clip.mov.icloud"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_local_file_needs_no_download() {
+        let dir = std::env::temp_dir().join(format!("clipforge_storage_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("clip.mov");
+        fs::write(&file, b"frames").unwrap();
+
+        assert!(pending_download(&file).is_none());
+        // A missing file with an iCloud stub is a placeholder
+        let evicted = dir.join("evicted.mov");
+        fs::write(icloud_stub(&evicted).unwrap(), b"").unwrap();
+        assert_eq!(pending_download(&evicted).unwrap().total_bytes, 0);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,241 @@
+// Subtitle generation
+//
+// Turns transcript segments into SRT/WebVTT files and builds the FFmpeg
+// subtitles filter used to burn captions into an export
+
+use super::ffmpeg_utils::quote_filter_value;
+use super::transcription::TranscriptSegment;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Sidecar subtitle file format
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+}
+
+impl SubtitleFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SubtitleFormat::Srt => "srt",
+            SubtitleFormat::Vtt => "vtt",
+        }
+    }
+}
+
+/// Vertical placement of burned-in subtitles
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitlePosition {
+    Top,
+    Middle,
+    Bottom,
+}
+
+/// Appearance of burned-in subtitles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SubtitleStyle {
+    pub font_size: u32,
+    pub position: SubtitlePosition,
+    /// Draw a semi-transparent box behind the text instead of an outline
+    pub background_box: bool,
+}
+
+impl Default for SubtitleStyle {
+    fn default() -> Self {
+        Self {
+            font_size: 24,
+            position: SubtitlePosition::Bottom,
+            background_box: true,
+        }
+    }
+}
+
+impl SubtitleStyle {
+    /// ASS `force_style` value for the subtitles filter
+    pub fn force_style(&self) -> String {
+        // ASS alignment uses numpad positions
+        let alignment = match self.position {
+            SubtitlePosition::Bottom => 2,
+            SubtitlePosition::Middle => 5,
+            SubtitlePosition::Top => 8,
+        };
+        let border = if self.background_box {
+            "BorderStyle=3,Outline=1,Shadow=0,BackColour=&H80000000,OutlineColour=&H80000000"
+        } else {
+            "BorderStyle=1,Outline=2,Shadow=0,OutlineColour=&H00000000"
+        };
+
+        format!(
+            "FontSize={},Alignment={},MarginV=20,{}",
+            self.font_size.max(8),
+            alignment,
+            border
+        )
+    }
+}
+
+/// Subtitle options for an export
+///
+/// Segment times are in output (timeline) seconds
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SubtitleSettings {
+    pub segments: Vec<TranscriptSegment>,
+    /// Render the subtitles into the video frames
+    pub burn_in: bool,
+    /// Write a subtitle file next to the exported video
+    pub sidecar: Option<SubtitleFormat>,
+    pub style: SubtitleStyle,
+}
+
+fn format_timestamp(seconds: f64, decimal_separator: char) -> String {
+    let total_ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        total_ms / 3_600_000,
+        (total_ms / 60_000) % 60,
+        (total_ms / 1000) % 60,
+        decimal_separator,
+        total_ms % 1000
+    )
+}
+
+/// Render segments as SubRip
+pub fn format_srt(segments: &[TranscriptSegment]) -> String {
+    segments
+        .iter()
+        .enumerate()
+        .map(|(i, segment)| {
+            format!(
+                "{}\n{} --> {}\n{}\n",
+                i + 1,
+                format_timestamp(segment.start, ','),
+                format_timestamp(segment.end, ','),
+                segment.text
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Render segments as WebVTT
+pub fn format_vtt(segments: &[TranscriptSegment]) -> String {
+    let cues = segments
+        .iter()
+        .map(|segment| {
+            format!(
+                "{} --> {}\n{}\n",
+                format_timestamp(segment.start, '.'),
+                format_timestamp(segment.end, '.'),
+                segment.text
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!("WEBVTT\n\n{}", cues)
+}
+
+/// Write segments to `path` in the given format
+pub fn write_subtitles(
+    segments: &[TranscriptSegment],
+    format: SubtitleFormat,
+    path: &Path,
+) -> Result<(), String> {
+    let content = match format {
+        SubtitleFormat::Srt => format_srt(segments),
+        SubtitleFormat::Vtt => format_vtt(segments),
+    };
+
+    fs::write(path, content).map_err(|e| format!("Failed to write subtitles: {}", e))
+}
+
+/// Sidecar path for an exported video (`movie.mp4` -> `movie.srt`)
+pub fn sidecar_path(video_path: &Path, format: SubtitleFormat) -> PathBuf {
+    video_path.with_extension(format.extension())
+}
+
+/// Build the subtitles video filter for an SRT file
+pub fn burn_in_filter(srt_path: &Path, style: &SubtitleStyle) -> String {
+    format!(
+        "subtitles={}:force_style='{}'",
+        quote_filter_value(&srt_path.display().to_string()),
+        style.force_style()
+    )
+}
+
+/// Write transcript segments to a subtitle file
+#[tauri::command]
+pub async fn export_subtitles(
+    segments: Vec<TranscriptSegment>,
+    output_path: String,
+    format: SubtitleFormat,
+) -> Result<(), String> {
+    write_subtitles(&segments, format, Path::new(&output_path))?;
+
+    println!(
+        "Exported {} subtitle cues to: {}",
+        segments.len(),
+        output_path
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_srt() {
+        let segments = [
+            TranscriptSegment {
+                start: 0.0,
+                end: 2.5,
+                text: "Hello there.".to_string(),
+            },
+            TranscriptSegment {
+                start: 3661.25,
+                end: 3662.0,
+                text: "An hour later.".to_string(),
+            },
+        ];
+        assert_eq!(
+            format_srt(&segments),
+            "1\n00:00:00,000 --> 00:00:02,500\nHello there.\n\n2\n01:01:01,250 --> 01:01:02,000\nAn hour later.\n"
+        );
+    }
+
+    #[test]
+    fn test_format_vtt() {
+        let segments = [
+            TranscriptSegment {
+                start: 0.0,
+                end: 2.5,
+                text: "Hello there.".to_string(),
+            },
+            TranscriptSegment {
+                start: 3661.25,
+                end: 3662.0,
+                text: "An hour later.".to_string(),
+            },
+        ];
+        let vtt = format_vtt(&segments);
+        assert!(vtt.starts_with("WEBVTT\n\n00:00:00.000 --> 00:00:02.500\nHello there.\n"));
+    }
+
+    #[test]
+    fn test_force_style_position() {
+        let style = SubtitleStyle {
+            position: SubtitlePosition::Top,
+            background_box: false,
+            ..Default::default()
+        };
+        assert!(style.force_style().starts_with("FontSize=24,Alignment=8"));
+        assert!(style.force_style().contains("BorderStyle=1"));
+    }
+}
@@ -0,0 +1,242 @@
+// Target file size exports
+//
+// Re-encodes a finished export so it fits under a size limit, e.g. a chat or
+// email attachment cap. The video bitrate is whatever is left of the target
+// once the audio and a small container allowance are taken out, encoded in
+// two passes so the average lands close to it. If the file still comes out too
+// big, the bitrate is scaled down by the overshoot and the encode runs again.
+
+use super::ffmpeg_utils::find_ffmpeg;
+use super::process_runner::{JobKind, WatchedOutput};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Bytes per MB; decimal so the result also fits limits given in MiB
+const BYTES_PER_MB: f64 = 1_000_000.0;
+
+/// Share of the target kept free for container overhead
+const CONTAINER_OVERHEAD: f64 = 0.03;
+
+/// Lowest video bitrate worth encoding at, in kbit/s
+const MIN_VIDEO_KBPS: u32 = 100;
+
+/// Largest target accepted, in MB
+pub const MAX_TARGET_MB: f64 = 10_000.0;
+
+/// Encodes tried before giving up on an overshooting file
+const MAX_ATTEMPTS: usize = 2;
+
+/// Bitrates that fit a duration into a target size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeBudget {
+    pub video_kbps: u32,
+    pub audio_kbps: u32,
+}
+
+/// Audio bitrate for a total budget: tighter budgets give audio less
+fn audio_kbps(total_kbps: f64) -> u32 {
+    if total_kbps >= 1000.0 {
+        128
+    } else if total_kbps >= 400.0 {
+        96
+    } else {
+        64
+    }
+}
+
+/// Split `target_mb` over `duration` seconds into video and audio bitrates
+pub fn size_budget(target_mb: f64, duration: f64) -> Result<SizeBudget, String> {
+    if !(target_mb > 0.0 && target_mb <= MAX_TARGET_MB) {
+        return Err(format!(
+            "Target size must be between 0 and {} MB",
+            MAX_TARGET_MB
+        ));
+    }
+    if duration <= 0.0 {
+        return Err("Cannot fit an empty video into a target size".to_string());
+    }
+
+    let total_kbps =
+        target_mb * BYTES_PER_MB * 8.0 * (1.0 - CONTAINER_OVERHEAD) / duration / 1000.0;
+    let audio_kbps = audio_kbps(total_kbps);
+    let video_kbps = (total_kbps - audio_kbps as f64).floor();
+
+    if video_kbps < MIN_VIDEO_KBPS as f64 {
+        let min_mb = (MIN_VIDEO_KBPS + audio_kbps) as f64 * 1000.0 * duration
+            / 8.0
+            / (1.0 - CONTAINER_OVERHEAD)
+            / BYTES_PER_MB;
+        return Err(format!(
+            "{:.1} MB is too small for {:.0}s of video; it needs at least {:.1} MB",
+            target_mb, duration, min_mb
+        ));
+    }
+
+    Ok(SizeBudget {
+        video_kbps: video_kbps as u32,
+        audio_kbps,
+    })
+}
+
+/// Two-pass encode of `input` into `output` at the budget's bitrates
+fn encode(
+    ffmpeg_path: &Path,
+    input: &Path,
+    output: &Path,
+    budget: SizeBudget,
+    work_dir: &Path,
+) -> Result<(), String> {
+    let extension = output
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("mp4")
+        .to_lowercase();
+    let is_webm = extension == "webm";
+    let (video_codec, audio_codec) = if is_webm {
+        ("libvpx-vp9", "libopus")
+    } else {
+        ("libx264", "aac")
+    };
+    let video_bitrate = format!("{}k", budget.video_kbps);
+    let pass_log = work_dir.join("target_size_pass");
+
+    for pass in 1..=2 {
+        let mut command = Command::new(ffmpeg_path);
+        command
+            .arg("-y")
+            .arg("-i")
+            .arg(input)
+            .arg("-map")
+            .arg("0:v:0")
+            .arg("-c:v")
+            .arg(video_codec)
+            .arg("-b:v")
+            .arg(&video_bitrate)
+            .arg("-pix_fmt")
+            .arg("yuv420p")
+            .arg("-pass")
+            .arg(pass.to_string())
+            .arg("-passlogfile")
+            .arg(&pass_log);
+
+        if pass == 1 {
+            // The first pass only gathers statistics
+            command.arg("-an").arg("-f").arg("null").arg("-");
+        } else {
+            command
+                .arg("-map")
+                .arg("0:a?")
+                .arg("-c:a")
+                .arg(audio_codec)
+                .arg("-b:a")
+                .arg(format!("{}k", budget.audio_kbps));
+            if !is_webm {
+                command.arg("-movflags").arg("+faststart");
+            }
+            command.arg(output);
+        }
+
+        let result = command
+            .watched_output(JobKind::Encode)
+            .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+        if !result.status.success() {
+            return Err(format!(
+                "Failed to fit video into target size (pass {}): {}",
+                pass,
+                String::from_utf8_lossy(&result.stderr)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Re-encode a video in place so it is at most `target_mb` MB
+///
+/// `work_dir` holds the pass logs and a copy of the original while encoding.
+pub fn fit_to_size(
+    video_path: &Path,
+    duration: f64,
+    target_mb: f64,
+    work_dir: &Path,
+) -> Result<SizeBudget, String> {
+    let ffmpeg_path = find_ffmpeg().ok_or_else(|| "FFmpeg not found".to_string())?;
+    let mut budget = size_budget(target_mb, duration)?;
+    let target_bytes = target_mb * BYTES_PER_MB;
+
+    let extension = video_path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("mp4");
+    let source = work_dir.join(format!("before_target_size.{}", extension));
+    fs::rename(video_path, &source)
+        .or_else(|_| fs::copy(video_path, &source).map(|_| ()))
+        .map_err(|e| format!("Failed to prepare video for re-encoding: {}", e))?;
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        if let Err(e) = encode(&ffmpeg_path, &source, video_path, budget, work_dir) {
+            let _ = fs::rename(&source, video_path);
+            return Err(e);
+        }
+
+        let size = fs::metadata(video_path)
+            .map_err(|e| format!("Failed to read encoded video: {}", e))?
+            .len() as f64;
+        println!(
+            "[TargetSize] Attempt {}: {:.2} MB at {} kbit/s video (target {:.2} MB)",
+            attempt,
+            size / BYTES_PER_MB,
+            budget.video_kbps,
+            target_mb
+        );
+        if size <= target_bytes {
+            break;
+        }
+        if attempt == MAX_ATTEMPTS {
+            println!(
+                "[TargetSize] Still over the target after {} attempts",
+                attempt
+            );
+            break;
+        }
+
+        // Scale the video bitrate down by the overshoot, with a little margin
+        let scale = target_bytes / size * (1.0 - CONTAINER_OVERHEAD);
+        budget.video_kbps = ((budget.video_kbps as f64 * scale) as u32).max(MIN_VIDEO_KBPS);
+    }
+
+    let _ = fs::remove_file(&source);
+    Ok(budget)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_fills_target() {
+        // 10 MB over 60 seconds is about 1293 kbit/s in total
+        let budget = size_budget(10.0, 60.0).unwrap();
+        assert_eq!(budget.audio_kbps, 128);
+        assert_eq!(budget.video_kbps, 1165);
+
+        let bytes = (budget.video_kbps + budget.audio_kbps) as f64 * 1000.0 * 60.0 / 8.0;
+        assert!(bytes <= 10.0 * BYTES_PER_MB);
+    }
+
+    #[test]
+    fn test_budget_lowers_audio_when_tight() {
+        assert_eq!(size_budget(5.0, 60.0).unwrap().audio_kbps, 96);
+        assert_eq!(size_budget(2.0, 60.0).unwrap().audio_kbps, 64);
+    }
+
+    #[test]
+    fn test_budget_rejects_impossible_targets() {
+        assert!(size_budget(1.0, 600.0).is_err());
+        assert!(size_budget(0.0, 60.0).is_err());
+        assert!(size_budget(10.0, 0.0).is_err());
+        assert!(size_budget(MAX_TARGET_MB + 1.0, 60.0).is_err());
+    }
+}
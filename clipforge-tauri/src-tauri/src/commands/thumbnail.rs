@@ -1,6 +1,24 @@
 use super::ffmpeg_utils::find_ffmpeg;
+use super::media_cache::{self, CacheKind};
+use super::metadata::extract_metadata;
+use super::process_runner::{watched_output_async, JobKind};
+use serde::{Deserialize, Serialize};
 use std::path::Path;
-use std::process::Command;
+use tokio::process::Command;
+
+/// Default thumbnail cache size limit (MB)
+pub const DEFAULT_MAX_CACHE_MB: u64 = 500;
+
+/// Evenly spaced frames extracted for hover scrubbing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThumbnailStrip {
+    /// Frame image paths in time order
+    pub paths: Vec<String>,
+    /// Timestamp (seconds) of each frame
+    pub timestamps: Vec<f64>,
+    /// Seconds between frames
+    pub interval: f64,
+}
 
 /// Generate a thumbnail image from a video file at a specific timestamp
 /// Returns the path to the generated thumbnail
@@ -9,52 +27,43 @@ pub async fn generate_thumbnail(
     video_path: String,
     timestamp: Option<f64>, // Timestamp in seconds, defaults to 1.0
 ) -> Result<String, String> {
-    // Find ffmpeg executable
-    let ffmpeg_path =
-        find_ffmpeg().ok_or_else(|| "FFmpeg not found. Please install FFmpeg.".to_string())?;
-
     // Use provided timestamp or default to 1 second
     let ts = timestamp.unwrap_or(1.0);
 
-    // Create thumbnails directory in temp
-    let temp_dir = std::env::temp_dir().join("clipforge_thumbnails");
-    std::fs::create_dir_all(&temp_dir)
-        .map_err(|e| format!("Failed to create thumbnails directory: {}", e))?;
-
-    // Generate unique filename based on video path hash
-    let video_path_obj = Path::new(&video_path);
-    let filename = video_path_obj
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("video");
+    // Return the cached thumbnail if this frame was extracted before
+    let hash = media_cache::content_hash(Path::new(&video_path))?;
+    let variant = format!("frame@{:.3}", ts);
+    if let Some(item) = media_cache::lookup(&hash, CacheKind::Thumbnail, &variant) {
+        return Ok(item.files[0].clone());
+    }
 
-    let timestamp_str = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
+    // Find ffmpeg executable
+    let ffmpeg_path =
+        find_ffmpeg().ok_or_else(|| "FFmpeg not found. Please install FFmpeg.".to_string())?;
 
-    let thumbnail_filename = format!("{}_{}.jpg", filename, timestamp_str);
-    let thumbnail_path = temp_dir.join(thumbnail_filename);
+    let thumbnail_path =
+        media_cache::item_dir(&hash)?.join(format!("frame_{}.jpg", (ts * 1000.0).round()));
 
     println!("[Thumbnail] Output path: {}", thumbnail_path.display());
 
     // Run ffmpeg to extract thumbnail
-    let output = Command::new(&ffmpeg_path)
-        .args([
-            "-ss",
-            &ts.to_string(), // Seek to timestamp
-            "-i",
-            &video_path, // Input file
-            "-vframes",
-            "1", // Extract 1 frame
-            "-vf",
-            "scale=320:-1", // Scale to 320px width, maintain aspect ratio
-            "-q:v",
-            "2",  // High quality (1-31, lower is better)
-            "-y", // Overwrite output file
-            thumbnail_path.to_str().unwrap(),
-        ])
-        .output()
+    let mut command = Command::new(&ffmpeg_path);
+    command.args([
+        "-ss",
+        &ts.to_string(), // Seek to timestamp
+        "-i",
+        &video_path, // Input file
+        "-vframes",
+        "1", // Extract 1 frame
+        "-vf",
+        "scale=320:-1", // Scale to 320px width, maintain aspect ratio
+        "-q:v",
+        "2",  // High quality (1-31, lower is better)
+        "-y", // Overwrite output file
+        thumbnail_path.to_str().unwrap(),
+    ]);
+    let output = watched_output_async(&mut command, JobKind::Thumbnail)
+        .await
         .map_err(|e| format!("Failed to execute ffmpeg: {}", e))?;
 
     if !output.status.success() {
@@ -67,46 +76,207 @@ pub async fn generate_thumbnail(
         return Err("Thumbnail file was not created".to_string());
     }
     // Return absolute path
-    thumbnail_path
+    let thumbnail_path = thumbnail_path
         .to_str()
-        .ok_or_else(|| "Failed to convert path to string".to_string())
-        .map(|s| s.to_string())
+        .ok_or_else(|| "Failed to convert path to string".to_string())?
+        .to_string();
+
+    media_cache::store(
+        &hash,
+        CacheKind::Thumbnail,
+        &variant,
+        &video_path,
+        vec![thumbnail_path.clone()],
+        None,
+    );
+    Ok(thumbnail_path)
 }
 
-/// Clean up old thumbnails from temp directory
-/// Removes thumbnails older than the specified age in hours
+/// Generate a strip of evenly spaced thumbnails in a single FFmpeg pass
+/// Frames are spaced by `interval` seconds, or spread as `count` frames over the video (default 10)
 #[tauri::command]
-pub async fn cleanup_old_thumbnails(max_age_hours: Option<u64>) -> Result<usize, String> {
-    let temp_dir = std::env::temp_dir().join("clipforge_thumbnails");
+pub async fn generate_thumbnail_strip(
+    video_path: String,
+    count: Option<u32>,
+    interval: Option<f64>,
+) -> Result<ThumbnailStrip, String> {
+    let hash = media_cache::content_hash(Path::new(&video_path))?;
+    let variant = format!("strip@{:?}/{:?}", count, interval);
+    let strip_name = format!(
+        "strip_{}_{}ms",
+        count.unwrap_or(0),
+        interval.map(|i| (i * 1000.0).round() as u64).unwrap_or(0)
+    );
+    if let Some(strip) = media_cache::lookup(&hash, CacheKind::Thumbnail, &variant)
+        .and_then(|item| item.data)
+        .and_then(|data| serde_json::from_value(data).ok())
+    {
+        return Ok(strip);
+    }
 
-    if !temp_dir.exists() {
-        return Ok(0);
+    let ffmpeg_path =
+        find_ffmpeg().ok_or_else(|| "FFmpeg not found. Please install FFmpeg.".to_string())?;
+
+    let duration = extract_metadata(video_path.clone()).await?.duration;
+    if duration <= 0.0 {
+        return Err("Video has no duration".to_string());
     }
 
-    let max_age = max_age_hours.unwrap_or(24); // Default to 24 hours
-    let mut cleaned = 0;
-
-    let entries = std::fs::read_dir(&temp_dir)
-        .map_err(|e| format!("Failed to read thumbnails directory: {}", e))?;
-
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("jpg") {
-            // Check file age
-            if let Ok(metadata) = std::fs::metadata(&path) {
-                if let Ok(modified) = metadata.modified() {
-                    let age = std::time::SystemTime::now()
-                        .duration_since(modified)
-                        .unwrap_or_default();
-
-                    if age.as_secs() > max_age * 3600 {
-                        // File is older than max age, remove it
-                        if std::fs::remove_file(&path).is_ok() {
-                            cleaned += 1;
-                        }
-                    }
-                }
-            }
+    let interval = match (interval, count) {
+        (Some(interval), _) if interval > 0.0 => interval,
+        (_, Some(count)) if count > 0 => duration / count as f64,
+        _ => duration / 10.0,
+    };
+    let frame_count = ((duration / interval).ceil() as u32).clamp(1, 500);
+
+    // Each strip gets its own directory so frame numbering can't collide
+    let strip_dir = media_cache::item_dir(&hash)?.join(strip_name);
+    std::fs::create_dir_all(&strip_dir)
+        .map_err(|e| format!("Failed to create thumbnail strip directory: {}", e))?;
+
+    let mut command = Command::new(&ffmpeg_path);
+    command
+        .arg("-i")
+        .arg(&video_path)
+        .arg("-vf")
+        .arg(format!("fps=1/{},scale=160:-2", interval))
+        .arg("-frames:v")
+        .arg(frame_count.to_string())
+        .arg("-q:v")
+        .arg("5")
+        .arg("-y")
+        .arg(strip_dir.join("frame_%04d.jpg"));
+    let output = watched_output_async(&mut command, JobKind::Thumbnail)
+        .await
+        .map_err(|e| format!("Failed to execute ffmpeg: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "FFmpeg thumbnail strip generation failed: {}",
+            stderr
+        ));
+    }
+
+    let mut paths = Vec::new();
+    let mut timestamps = Vec::new();
+    for i in 0..frame_count {
+        let path = strip_dir.join(format!("frame_{:04}.jpg", i + 1));
+        if !path.exists() {
+            break;
+        }
+        paths.push(path.to_string_lossy().to_string());
+        timestamps.push(i as f64 * interval);
+    }
+
+    println!(
+        "[Thumbnail] Generated strip of {} frames for {}",
+        paths.len(),
+        video_path
+    );
+
+    let strip = ThumbnailStrip {
+        paths,
+        timestamps,
+        interval,
+    };
+    media_cache::store(
+        &hash,
+        CacheKind::Thumbnail,
+        &variant,
+        &video_path,
+        strip.paths.clone(),
+        serde_json::to_value(&strip).ok(),
+    );
+
+    Ok(strip)
+}
+
+/// Extract a full-resolution still frame as PNG or JPEG
+/// Format defaults to the output path's extension, falling back to PNG
+#[tauri::command]
+pub async fn export_frame(
+    video_path: String,
+    timestamp: f64,
+    output_path: String,
+    format: Option<String>,
+) -> Result<String, String> {
+    let ffmpeg_path =
+        find_ffmpeg().ok_or_else(|| "FFmpeg not found. Please install FFmpeg.".to_string())?;
+
+    let format = format
+        .or_else(|| {
+            Path::new(&output_path)
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(|e| e.to_string())
+        })
+        .unwrap_or_else(|| "png".to_string())
+        .to_lowercase();
+
+    let mut command = Command::new(&ffmpeg_path);
+    command
+        .arg("-ss")
+        .arg(timestamp.max(0.0).to_string())
+        .arg("-i")
+        .arg(&video_path)
+        .arg("-frames:v")
+        .arg("1");
+
+    match format.as_str() {
+        "png" => {
+            command.arg("-c:v").arg("png");
         }
-    }    Ok(cleaned)
+        "jpg" | "jpeg" => {
+            command.arg("-c:v").arg("mjpeg").arg("-q:v").arg("1");
+        }
+        other => return Err(format!("Unsupported frame format: {}", other)),
+    }
+
+    // A frame left over from an earlier export would hide a failed one
+    match std::fs::remove_file(&output_path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(format!("Failed to replace {}: {}", output_path, e)),
+    }
+
+    command.arg("-update").arg("1").arg("-y").arg(&output_path);
+    let output = watched_output_async(&mut command, JobKind::Thumbnail)
+        .await
+        .map_err(|e| format!("Failed to execute ffmpeg: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("FFmpeg frame export failed: {}", stderr));
+    }
+
+    // FFmpeg exits cleanly without writing anything when seeking past the end
+    if !Path::new(&output_path).exists() {
+        return Err(format!(
+            "No frame was written at {}s; the timestamp may be past the end of the video",
+            timestamp
+        ));
+    }
+
+    println!(
+        "[Thumbnail] Exported frame at {}s to {}",
+        timestamp, output_path
+    );
+    Ok(output_path)
+}
+
+/// Clean up old thumbnails from the media cache
+/// Removes thumbnails unused for longer than the specified age in hours, then
+/// evicts the least recently used ones until they fit in `max_cache_mb`
+#[tauri::command]
+pub async fn cleanup_old_thumbnails(
+    max_age_hours: Option<u64>,
+    max_cache_mb: Option<u64>,
+) -> Result<usize, String> {
+    let max_age = max_age_hours.unwrap_or(24); // Default to 24 hours
+    let max_bytes = max_cache_mb.unwrap_or(DEFAULT_MAX_CACHE_MB) * 1024 * 1024;
+
+    let (cleaned, _) =
+        media_cache::evict(Some(CacheKind::Thumbnail), Some(max_age * 3600), max_bytes);
+    Ok(cleaned)
 }
@@ -0,0 +1,324 @@
+// Speech-to-text transcription
+//
+// Extracts a video's audio with FFmpeg and runs it through an external
+// whisper.cpp binary. Results are cached per file so captions and text-based
+// editing don't re-run the model
+
+use super::ffmpeg_utils::{find_ffmpeg, find_whisper};
+use super::process_runner::{watched_output_async, JobKind};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+
+/// Model used when none is configured
+const DEFAULT_MODEL: &str = "ggml-base.bin";
+
+/// A timed piece of transcribed speech
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    /// Start time (seconds)
+    pub start: f64,
+    /// End time (seconds)
+    pub end: f64,
+    pub text: String,
+}
+
+/// Transcript for a single media file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transcript {
+    pub video_path: String,
+    pub language: String,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+/// Progress payload for the "transcription-progress" event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionProgress {
+    pub video_path: String,
+    /// Percentage complete (0 - 100)
+    pub progress: f64,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WhisperOffsets {
+    from: u64,
+    to: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct WhisperSegment {
+    offsets: WhisperOffsets,
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WhisperOutput {
+    transcription: Vec<WhisperSegment>,
+}
+
+/// Parse whisper.cpp JSON output (`-oj`) into segments
+pub fn parse_whisper_json(content: &str) -> Result<Vec<TranscriptSegment>, String> {
+    let output: WhisperOutput = serde_json::from_str(content)
+        .map_err(|e| format!("Failed to parse whisper output: {}", e))?;
+
+    Ok(output
+        .transcription
+        .into_iter()
+        .map(|segment| TranscriptSegment {
+            start: segment.offsets.from as f64 / 1000.0,
+            end: segment.offsets.to as f64 / 1000.0,
+            text: segment.text.trim().to_string(),
+        })
+        .filter(|segment| !segment.text.is_empty())
+        .collect())
+}
+
+/// Parse a whisper.cpp `-pp` progress line ("... progress = 42%")
+pub fn parse_progress_line(line: &str) -> Option<f64> {
+    let rest = &line[line.find("progress =")? + "progress =".len()..];
+    rest.trim().trim_end_matches('%').trim().parse().ok()
+}
+
+/// Directory holding cached transcripts
+pub fn transcript_cache_dir() -> PathBuf {
+    std::env::temp_dir().join("clipforge_transcripts")
+}
+
+/// Cache file for a video, keyed by path, size, modification time and language
+pub fn transcript_cache_path(video_path: &Path, language: &str) -> Result<PathBuf, String> {
+    let metadata =
+        fs::metadata(video_path).map_err(|e| format!("Failed to read video file: {}", e))?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+
+    let mut hasher = DefaultHasher::new();
+    video_path.hash(&mut hasher);
+    metadata.len().hash(&mut hasher);
+    modified.hash(&mut hasher);
+    language.hash(&mut hasher);
+
+    Ok(transcript_cache_dir().join(format!("{:016x}.json", hasher.finish())))
+}
+
+/// Load a cached transcript for a video, if one exists
+pub fn load_cached_transcript(video_path: &Path, language: &str) -> Option<Transcript> {
+    let cache_path = transcript_cache_path(video_path, language).ok()?;
+    let content = fs::read_to_string(cache_path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Locate the whisper model file
+///
+/// Checks `CLIPFORGE_WHISPER_MODEL`, then `<app data>/models`, then Homebrew's whisper-cpp share dir
+fn find_whisper_model(app: &AppHandle) -> Option<PathBuf> {
+    if let Ok(path) = std::env::var("CLIPFORGE_WHISPER_MODEL") {
+        let path = PathBuf::from(path);
+        if path.exists() {
+            return Some(path);
+        }
+    }
+
+    let mut candidates = Vec::new();
+    if let Ok(data_dir) = app.path().app_data_dir() {
+        candidates.push(data_dir.join("models").join(DEFAULT_MODEL));
+    }
+    candidates.push(PathBuf::from("/opt/homebrew/share/whisper-cpp").join(DEFAULT_MODEL));
+    candidates.push(PathBuf::from("/usr/local/share/whisper-cpp").join(DEFAULT_MODEL));
+
+    candidates.into_iter().find(|path| path.exists())
+}
+
+fn emit_progress(app: &AppHandle, video_path: &str, progress: f64, message: &str) {
+    let _ = app.emit(
+        "transcription-progress",
+        TranscriptionProgress {
+            video_path: video_path.to_string(),
+            progress,
+            message: message.to_string(),
+        },
+    );
+}
+
+/// Transcribe a video's speech into timed segments
+///
+/// `language` is an ISO 639-1 code or "auto". Cached results are returned
+/// immediately unless the file has changed
+#[tauri::command]
+pub async fn transcribe_video(
+    app: AppHandle,
+    path: String,
+    language: Option<String>,
+) -> Result<Transcript, String> {
+    let language = language.unwrap_or_else(|| "auto".to_string());
+    let video_path = Path::new(&path);
+
+    if let Some(transcript) = load_cached_transcript(video_path, &language) {
+        println!("[Transcription] Using cached transcript for {}", path);
+        emit_progress(&app, &path, 100.0, "Loaded cached transcript");
+        return Ok(transcript);
+    }
+
+    let ffmpeg_path =
+        find_ffmpeg().ok_or_else(|| "ffmpeg not found. Please install FFmpeg.".to_string())?;
+    let whisper_path = find_whisper()
+        .ok_or_else(|| "whisper.cpp not found. Please install whisper-cpp.".to_string())?;
+    let model_path = find_whisper_model(&app).ok_or_else(|| {
+        format!(
+            "Whisper model not found. Place {} in the app's models folder or set CLIPFORGE_WHISPER_MODEL.",
+            DEFAULT_MODEL
+        )
+    })?;
+
+    let cache_path = transcript_cache_path(video_path, &language)?;
+    let work_dir = transcript_cache_dir();
+    fs::create_dir_all(&work_dir)
+        .map_err(|e| format!("Failed to create transcript directory: {}", e))?;
+
+    let stem = cache_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("transcript")
+        .to_string();
+    let audio_path = work_dir.join(format!("{}.wav", stem));
+    let output_base = work_dir.join(format!("{}_whisper", stem));
+
+    // whisper.cpp expects 16kHz mono PCM
+    emit_progress(&app, &path, 0.0, "Extracting audio");
+    let mut command = Command::new(&ffmpeg_path);
+    command
+        .arg("-i")
+        .arg(&path)
+        .arg("-vn")
+        .arg("-ac")
+        .arg("1")
+        .arg("-ar")
+        .arg("16000")
+        .arg("-c:a")
+        .arg("pcm_s16le")
+        .arg("-y")
+        .arg(&audio_path);
+    let output = watched_output_async(&mut command, JobKind::Encode)
+        .await
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to extract audio: {}", stderr));
+    }
+
+    emit_progress(&app, &path, 0.0, "Transcribing");
+    let mut child = Command::new(&whisper_path)
+        .arg("-m")
+        .arg(&model_path)
+        .arg("-f")
+        .arg(&audio_path)
+        .arg("-l")
+        .arg(&language)
+        .arg("-oj")
+        .arg("-of")
+        .arg(&output_base)
+        .arg("-pp")
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        // Cancelling the command stops a hung whisper run
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start whisper: {}", e))?;
+
+    // Forward whisper's progress output as events, keeping the rest for errors
+    let mut log = Vec::new();
+    if let Some(stderr) = child.stderr.take() {
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            match parse_progress_line(&line) {
+                Some(progress) => emit_progress(&app, &path, progress, "Transcribing"),
+                None => log.push(line),
+            }
+        }
+    }
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| format!("Failed to wait for whisper: {}", e))?;
+    let _ = fs::remove_file(&audio_path);
+
+    if !status.success() {
+        return Err(format!("Transcription failed: {}", log.join("\n")));
+    }
+
+    let json_path = output_base.with_extension("json");
+    let content = fs::read_to_string(&json_path)
+        .map_err(|e| format!("Failed to read whisper output: {}", e))?;
+    let _ = fs::remove_file(&json_path);
+
+    let transcript = Transcript {
+        video_path: path.clone(),
+        language,
+        segments: parse_whisper_json(&content)?,
+    };
+
+    let serialized = serde_json::to_string(&transcript)
+        .map_err(|e| format!("Failed to serialize transcript: {}", e))?;
+    fs::write(&cache_path, serialized).map_err(|e| format!("Failed to cache transcript: {}", e))?;
+
+    println!(
+        "[Transcription] {} segments transcribed for {}",
+        transcript.segments.len(),
+        path
+    );
+    emit_progress(&app, &path, 100.0, "Transcription complete");
+    Ok(transcript)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_whisper_json() {
+        let json = r#"{
+            "transcription": [
+                {"timestamps": {"from": "00:00:00,000", "to": "00:00:02,500"},
+                 "offsets": {"from": 0, "to": 2500}, "text": " Hello there."},
+                {"timestamps": {"from": "00:00:02,500", "to": "00:00:03,000"},
+                 "offsets": {"from": 2500, "to": 3000}, "text": " "}
+            ]
+        }"#;
+
+        let segments = parse_whisper_json(json).unwrap();
+        assert_eq!(
+            segments,
+            vec![TranscriptSegment {
+                start: 0.0,
+                end: 2.5,
+                text: "Hello there.".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_progress_line() {
+        assert_eq!(
+            parse_progress_line("whisper_print_progress_callback: progress =  42%"),
+            Some(42.0)
+        );
+        assert_eq!(
+            parse_progress_line("whisper_init_from_file: loading model"),
+            None
+        );
+    }
+}
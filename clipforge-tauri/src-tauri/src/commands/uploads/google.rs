@@ -0,0 +1,361 @@
+// YouTube and Google Drive uploads
+//
+// Both use Google's resumable upload protocol: a session is opened with the file
+// metadata, then the file is PUT in chunks. After a failed chunk the session is
+// asked how much it has received and the upload continues from there.
+
+use super::{
+    keychain, retry_delay, UploadDestination, UploadJob, UploadMetadata, UploadResult,
+    MAX_CHUNK_RETRIES,
+};
+use reqwest::header::{CONTENT_RANGE, CONTENT_TYPE, LOCATION, RANGE};
+use reqwest::{Client, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const YOUTUBE_UPLOAD_URL: &str =
+    "https://www.googleapis.com/upload/youtube/v3/videos?uploadType=resumable&part=snippet,status";
+const DRIVE_UPLOAD_URL: &str =
+    "https://www.googleapis.com/upload/drive/v3/files?uploadType=resumable&fields=id,webViewLink";
+
+/// Bytes per chunk; Google requires a multiple of 256 KiB
+const CHUNK_SIZE: u64 = 32 * 256 * 1024;
+
+/// Refresh access tokens this many seconds before they expire
+const TOKEN_EXPIRY_MARGIN_SECS: i64 = 60;
+
+/// OAuth credentials for a Google account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoogleCredentials {
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    pub refresh_token: String,
+    #[serde(default)]
+    pub access_token: Option<String>,
+    /// Access token expiry (Unix seconds)
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+}
+
+/// Server-side state of an upload session
+enum SessionState {
+    /// Bytes received so far
+    Incomplete(u64),
+    /// The upload finished; holds the created resource
+    Complete(serde_json::Value),
+}
+
+/// Get an access token, refreshing (and re-storing) it when it is about to expire
+async fn access_token(
+    client: &Client,
+    destination: UploadDestination,
+    force_refresh: bool,
+) -> Result<String, String> {
+    let account = destination.keychain_account();
+    let mut credentials: GoogleCredentials = keychain::load(account)?
+        .ok_or_else(|| format!("Not signed in to {}", destination.display_name()))?;
+
+    let now = chrono::Utc::now().timestamp();
+    if !force_refresh {
+        if let (Some(token), Some(expires_at)) = (&credentials.access_token, credentials.expires_at)
+        {
+            if expires_at - TOKEN_EXPIRY_MARGIN_SECS > now {
+                return Ok(token.clone());
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct TokenResponse {
+        access_token: String,
+        expires_in: i64,
+    }
+
+    let mut form = vec![
+        ("grant_type", "refresh_token"),
+        ("client_id", credentials.client_id.as_str()),
+        ("refresh_token", credentials.refresh_token.as_str()),
+    ];
+    if let Some(secret) = &credentials.client_secret {
+        form.push(("client_secret", secret.as_str()));
+    }
+
+    let response = client
+        .post(TOKEN_URL)
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to refresh access token: {}", e))?;
+    let token: TokenResponse = error_for_status(response, "Token refresh")
+        .await?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid token response: {}", e))?;
+
+    credentials.access_token = Some(token.access_token.clone());
+    credentials.expires_at = Some(now + token.expires_in);
+    keychain::store(account, &credentials)?;
+
+    Ok(token.access_token)
+}
+
+/// Client for upload sessions. Redirects are not followed because Google answers
+/// each partial chunk with a 308 that means "resume incomplete"
+fn http_client() -> Result<Client, String> {
+    Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// Turn a non-success response into an error carrying the response body
+async fn error_for_status(response: Response, action: &str) -> Result<Response, String> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(format!("{} failed ({}): {}", action, status, body))
+}
+
+/// Bytes the session has received, from a 308 response's `Range: bytes=0-N` header
+fn committed_bytes(response: &Response) -> u64 {
+    response
+        .headers()
+        .get(RANGE)
+        .and_then(|range| range.to_str().ok())
+        .and_then(parse_committed_range)
+        .unwrap_or(0)
+}
+
+fn parse_committed_range(range: &str) -> Option<u64> {
+    let end = range.strip_prefix("bytes=")?.split('-').nth(1)?;
+    end.trim().parse::<u64>().ok().map(|end| end + 1)
+}
+
+async fn start_session(
+    client: &Client,
+    token: &str,
+    url: &str,
+    metadata: &serde_json::Value,
+    job: &UploadJob,
+) -> Result<String, String> {
+    let response = client
+        .post(url)
+        .bearer_auth(token)
+        .header("X-Upload-Content-Type", job.content_type)
+        .header("X-Upload-Content-Length", job.total_bytes)
+        .json(metadata)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to start upload: {}", e))?;
+    let response = error_for_status(response, "Starting upload").await?;
+
+    response
+        .headers()
+        .get(LOCATION)
+        .and_then(|location| location.to_str().ok())
+        .map(|location| location.to_string())
+        .ok_or_else(|| "Upload session URL missing from response".to_string())
+}
+
+async fn query_session(
+    client: &Client,
+    token: &str,
+    session_url: &str,
+    total_bytes: u64,
+) -> Result<SessionState, String> {
+    let response = client
+        .put(session_url)
+        .bearer_auth(token)
+        .header(CONTENT_RANGE, format!("bytes */{}", total_bytes))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to query upload session: {}", e))?;
+
+    if response.status() == StatusCode::PERMANENT_REDIRECT {
+        return Ok(SessionState::Incomplete(committed_bytes(&response)));
+    }
+    let response = error_for_status(response, "Querying upload session").await?;
+    response
+        .json()
+        .await
+        .map(SessionState::Complete)
+        .map_err(|e| format!("Invalid upload response: {}", e))
+}
+
+/// Send the file through an open session and return the created resource
+async fn upload_chunks(
+    client: &Client,
+    mut token: String,
+    session_url: &str,
+    job: &UploadJob,
+) -> Result<serde_json::Value, String> {
+    let mut offset = 0u64;
+    // Most bytes the session has confirmed; retries only reset past this
+    let mut confirmed = 0u64;
+    let mut failures = 0u32;
+
+    loop {
+        job.check_cancelled()?;
+
+        let len = CHUNK_SIZE.min(job.total_bytes - offset);
+        if len == 0 {
+            return Err("Upload session received every byte but never completed".to_string());
+        }
+        let chunk = job.read_chunk(offset, len).await?;
+        let result = client
+            .put(session_url)
+            .bearer_auth(&token)
+            .header(CONTENT_TYPE, job.content_type)
+            .header(
+                CONTENT_RANGE,
+                format!("bytes {}-{}/{}", offset, offset + len - 1, job.total_bytes),
+            )
+            .body(chunk)
+            .send()
+            .await;
+
+        let error = match result {
+            Ok(response) if response.status().is_success() => {
+                job.report(job.total_bytes);
+                return response
+                    .json()
+                    .await
+                    .map_err(|e| format!("Invalid upload response: {}", e));
+            }
+            Ok(response) if response.status() == StatusCode::PERMANENT_REDIRECT => {
+                offset = committed_bytes(&response);
+                job.report(offset);
+                if offset > confirmed {
+                    confirmed = offset;
+                    failures = 0;
+                    continue;
+                }
+                format!("session is still at byte {}", offset)
+            }
+            Ok(response) if response.status() == StatusCode::UNAUTHORIZED => {
+                token = access_token(client, job.destination, true).await?;
+                "access token expired".to_string()
+            }
+            Ok(response)
+                if response.status().is_client_error()
+                    && response.status() != StatusCode::REQUEST_TIMEOUT
+                    && response.status() != StatusCode::TOO_MANY_REQUESTS =>
+            {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                return Err(format!("Upload failed ({}): {}", status, body));
+            }
+            Ok(response) => format!("server returned {}", response.status()),
+            Err(e) => e.to_string(),
+        };
+
+        failures += 1;
+        if failures > MAX_CHUNK_RETRIES {
+            return Err(format!(
+                "Upload failed after {} retries: {}",
+                MAX_CHUNK_RETRIES, error
+            ));
+        }
+        println!(
+            "[Uploads] Chunk at {} failed ({}), retry {}/{}",
+            offset, error, failures, MAX_CHUNK_RETRIES
+        );
+        retry_delay(failures).await;
+
+        // Resume from whatever the session actually received
+        match query_session(client, &token, session_url, job.total_bytes).await {
+            Ok(SessionState::Incomplete(committed)) => offset = committed,
+            Ok(SessionState::Complete(resource)) => {
+                job.report(job.total_bytes);
+                return Ok(resource);
+            }
+            Err(e) => println!("[Uploads] {}", e),
+        }
+    }
+}
+
+fn resource_id(resource: &serde_json::Value) -> Result<String, String> {
+    resource["id"]
+        .as_str()
+        .map(|id| id.to_string())
+        .ok_or_else(|| "Upload response is missing the resource id".to_string())
+}
+
+/// Upload a video to YouTube
+pub async fn upload_youtube(
+    job: &UploadJob,
+    metadata: &UploadMetadata,
+) -> Result<UploadResult, String> {
+    let client = http_client()?;
+    let token = access_token(&client, job.destination, false).await?;
+
+    let video = serde_json::json!({
+        "snippet": {
+            "title": metadata.title.clone().unwrap_or_else(|| job.file_name()),
+            "description": metadata.description.clone().unwrap_or_default(),
+            "tags": metadata.tags,
+        },
+        "status": {
+            "privacyStatus": metadata.privacy.as_deref().unwrap_or("private"),
+        },
+    });
+
+    let session_url = start_session(&client, &token, YOUTUBE_UPLOAD_URL, &video, job).await?;
+    let resource = upload_chunks(&client, token, &session_url, job).await?;
+    let video_id = resource_id(&resource)?;
+
+    Ok(UploadResult {
+        upload_id: job.id.clone(),
+        destination: job.destination,
+        url: Some(format!("https://youtu.be/{}", video_id)),
+        remote_id: video_id,
+    })
+}
+
+/// Upload a file to Google Drive
+pub async fn upload_drive(
+    job: &UploadJob,
+    metadata: &UploadMetadata,
+) -> Result<UploadResult, String> {
+    let client = http_client()?;
+    let token = access_token(&client, job.destination, false).await?;
+
+    let mut file = serde_json::json!({
+        "name": metadata.title.clone().unwrap_or_else(|| job.file_name()),
+    });
+    if let Some(description) = &metadata.description {
+        file["description"] = description.clone().into();
+    }
+    if let Some(folder_id) = &metadata.folder_id {
+        file["parents"] = serde_json::json!([folder_id]);
+    }
+
+    let session_url = start_session(&client, &token, DRIVE_UPLOAD_URL, &file, job).await?;
+    let resource = upload_chunks(&client, token, &session_url, job).await?;
+
+    Ok(UploadResult {
+        upload_id: job.id.clone(),
+        destination: job.destination,
+        remote_id: resource_id(&resource)?,
+        url: resource["webViewLink"]
+            .as_str()
+            .map(|link| link.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_committed_range() {
+        assert_eq!(parse_committed_range("bytes=0-262143"), Some(262144));
+        assert_eq!(parse_committed_range("bytes=0-0"), Some(1));
+        assert_eq!(parse_committed_range("0-262143"), None);
+        assert_eq!(parse_committed_range("bytes=0-"), None);
+    }
+}
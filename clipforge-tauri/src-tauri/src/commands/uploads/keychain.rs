@@ -0,0 +1,42 @@
+// Upload credential storage in the OS keychain
+//
+// Each destination stores one JSON document under the app's keychain service.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Keychain service the upload credentials are stored under
+const KEYCHAIN_SERVICE: &str = "com.nickkenkel.clipforge.uploads";
+
+fn entry(account: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, account)
+        .map_err(|e| format!("Failed to open keychain entry: {}", e))
+}
+
+/// Store credentials for an account, replacing any existing entry
+pub fn store<T: Serialize>(account: &str, credentials: &T) -> Result<(), String> {
+    let json = serde_json::to_string(credentials)
+        .map_err(|e| format!("Failed to serialize credentials: {}", e))?;
+    entry(account)?
+        .set_password(&json)
+        .map_err(|e| format!("Failed to save credentials to keychain: {}", e))
+}
+
+/// Load credentials for an account, or None if nothing is stored
+pub fn load<T: DeserializeOwned>(account: &str) -> Result<Option<T>, String> {
+    match entry(account)?.get_password() {
+        Ok(json) => serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| format!("Stored credentials are invalid: {}", e)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read credentials from keychain: {}", e)),
+    }
+}
+
+/// Remove the credentials for an account
+pub fn delete(account: &str) -> Result<(), String> {
+    match entry(account)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to remove credentials from keychain: {}", e)),
+    }
+}
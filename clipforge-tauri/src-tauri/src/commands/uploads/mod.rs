@@ -0,0 +1,412 @@
+// Direct upload integrations
+//
+// Sends finished exports straight to YouTube, Google Drive or an S3-compatible
+// bucket. Credentials are kept in the OS keychain, and files are sent in chunks
+// that are retried on their own, so a dropped connection resumes where it stopped
+// instead of starting over.
+
+mod google;
+pub(crate) mod keychain;
+mod s3;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+/// Attempts per chunk before an upload is abandoned
+const MAX_CHUNK_RETRIES: u32 = 5;
+
+/// Delay before the first retry; doubles with each further attempt
+const RETRY_BASE_DELAY_MS: u64 = 1000;
+
+/// Bucket prefix that shared recordings are uploaded under
+const SHARE_KEY_PREFIX: &str = "shares";
+
+/// Counter that keeps upload ids unique when uploads start together
+static NEXT_UPLOAD_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Where an export is uploaded to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UploadDestination {
+    Youtube,
+    GoogleDrive,
+    S3,
+}
+
+impl UploadDestination {
+    /// Keychain account holding this destination's credentials
+    fn keychain_account(&self) -> &'static str {
+        match self {
+            UploadDestination::Youtube => "youtube",
+            UploadDestination::GoogleDrive => "google-drive",
+            UploadDestination::S3 => "s3",
+        }
+    }
+
+    fn display_name(&self) -> &'static str {
+        match self {
+            UploadDestination::Youtube => "YouTube",
+            UploadDestination::GoogleDrive => "Google Drive",
+            UploadDestination::S3 => "S3",
+        }
+    }
+}
+
+/// Per-upload details; fields a destination doesn't use are ignored
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UploadMetadata {
+    /// Video title (YouTube) or file name (Drive); defaults to the file name
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    /// YouTube privacy status ("private", "unlisted" or "public"); defaults to private
+    pub privacy: Option<String>,
+    /// Google Drive folder to upload into
+    pub folder_id: Option<String>,
+    /// S3 object key; defaults to the file name
+    pub key: Option<String>,
+}
+
+/// Progress event payload for "upload:progress"
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadProgress {
+    pub upload_id: String,
+    pub destination: UploadDestination,
+    pub bytes_sent: u64,
+    pub total_bytes: u64,
+    /// Fraction complete (0.0 - 1.0)
+    pub progress: f64,
+}
+
+/// A finished upload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadResult {
+    pub upload_id: String,
+    pub destination: UploadDestination,
+    /// Video id, file id or object key at the destination
+    pub remote_id: String,
+    /// Link to the uploaded file, where the destination provides one
+    pub url: Option<String>,
+}
+
+/// A recording shared through a presigned link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedRecording {
+    pub url: String,
+    /// Object key in the bucket
+    pub key: String,
+    /// When the link stops working (Unix seconds)
+    pub expires_at: i64,
+    pub copied_to_clipboard: bool,
+}
+
+/// An upload in progress, shared with the destination implementations
+pub(crate) struct UploadJob {
+    pub id: String,
+    pub destination: UploadDestination,
+    pub path: PathBuf,
+    pub total_bytes: u64,
+    pub content_type: &'static str,
+    cancelled: Arc<AtomicBool>,
+    app: AppHandle,
+}
+
+impl UploadJob {
+    /// File name used when the metadata doesn't name the upload
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "export".to_string())
+    }
+
+    pub fn check_cancelled(&self) -> Result<(), String> {
+        if self.cancelled.load(Ordering::SeqCst) {
+            Err("Upload cancelled".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Emit "upload:progress" with the bytes the destination has confirmed
+    pub fn report(&self, bytes_sent: u64) {
+        let _ = self.app.emit(
+            "upload:progress",
+            UploadProgress {
+                upload_id: self.id.clone(),
+                destination: self.destination,
+                bytes_sent,
+                total_bytes: self.total_bytes,
+                progress: bytes_sent as f64 / self.total_bytes as f64,
+            },
+        );
+    }
+
+    /// Read `len` bytes of the file starting at `offset`
+    pub async fn read_chunk(&self, offset: u64, len: u64) -> Result<Vec<u8>, String> {
+        let mut file = tokio::fs::File::open(&self.path)
+            .await
+            .map_err(|e| format!("Failed to open export: {}", e))?;
+        file.seek(std::io::SeekFrom::Start(offset))
+            .await
+            .map_err(|e| format!("Failed to read export: {}", e))?;
+
+        let mut chunk = vec![0u8; len as usize];
+        file.read_exact(&mut chunk)
+            .await
+            .map_err(|e| format!("Failed to read export: {}", e))?;
+        Ok(chunk)
+    }
+}
+
+/// Wait before retry number `attempt` (1-based)
+async fn retry_delay(attempt: u32) {
+    let delay = RETRY_BASE_DELAY_MS * 2u64.pow(attempt.saturating_sub(1).min(5));
+    tokio::time::sleep(Duration::from_millis(delay)).await;
+}
+
+fn content_type_for(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "mp4" | "m4v" => "video/mp4",
+        "mov" => "video/quicktime",
+        "webm" => "video/webm",
+        "mkv" => "video/x-matroska",
+        "gif" => "image/gif",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Cancellation flags for the uploads in progress
+#[derive(Default)]
+pub struct UploadRegistry {
+    active: HashMap<String, Arc<AtomicBool>>,
+}
+
+impl UploadRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Thread-safe upload registry type
+pub type UploadRegistryState = Arc<Mutex<UploadRegistry>>;
+
+/// Save the credentials for a destination to the keychain
+///
+/// YouTube and Google Drive take `{ clientId, clientSecret?, refreshToken }` from the
+/// OAuth sign-in; S3 takes `{ bucket, region, endpoint?, accessKeyId, secretAccessKey,
+/// shareExpirySecs? }`
+#[tauri::command]
+pub async fn save_upload_credentials(
+    destination: UploadDestination,
+    credentials: serde_json::Value,
+) -> Result<(), String> {
+    let account = destination.keychain_account();
+    let invalid =
+        |e: serde_json::Error| format!("Invalid {} credentials: {}", destination.display_name(), e);
+
+    match destination {
+        UploadDestination::Youtube | UploadDestination::GoogleDrive => keychain::store(
+            account,
+            &serde_json::from_value::<google::GoogleCredentials>(credentials).map_err(invalid)?,
+        ),
+        UploadDestination::S3 => keychain::store(
+            account,
+            &serde_json::from_value::<s3::S3Credentials>(credentials).map_err(invalid)?,
+        ),
+    }
+}
+
+/// Remove the stored credentials for a destination
+#[tauri::command]
+pub async fn delete_upload_credentials(destination: UploadDestination) -> Result<(), String> {
+    keychain::delete(destination.keychain_account())
+}
+
+/// Check whether credentials are stored for a destination
+#[tauri::command]
+pub async fn has_upload_credentials(destination: UploadDestination) -> Result<bool, String> {
+    keychain::load::<serde_json::Value>(destination.keychain_account()).map(|c| c.is_some())
+}
+
+/// Run an upload, registering it so it can be cancelled
+async fn run_upload(
+    path: PathBuf,
+    destination: UploadDestination,
+    metadata: UploadMetadata,
+    app: AppHandle,
+    registry: &UploadRegistryState,
+) -> Result<UploadResult, String> {
+    let total_bytes = std::fs::metadata(&path)
+        .map_err(|e| format!("Export not found: {}", e))?
+        .len();
+    if total_bytes == 0 {
+        return Err("Export file is empty".to_string());
+    }
+
+    let id = format!(
+        "upload_{}_{}",
+        chrono::Utc::now().timestamp_millis(),
+        NEXT_UPLOAD_ID.fetch_add(1, Ordering::Relaxed)
+    );
+    let cancelled = Arc::new(AtomicBool::new(false));
+    registry
+        .lock()
+        .map_err(|e| e.to_string())?
+        .active
+        .insert(id.clone(), cancelled.clone());
+
+    let job = UploadJob {
+        id: id.clone(),
+        destination,
+        content_type: content_type_for(&path),
+        path,
+        total_bytes,
+        cancelled,
+        app,
+    };
+
+    println!(
+        "[Uploads] Uploading {} ({} bytes) to {}",
+        job.path.display(),
+        total_bytes,
+        destination.display_name()
+    );
+    job.report(0);
+
+    let result = match destination {
+        UploadDestination::Youtube => google::upload_youtube(&job, &metadata).await,
+        UploadDestination::GoogleDrive => google::upload_drive(&job, &metadata).await,
+        UploadDestination::S3 => s3::upload(&job, &metadata).await,
+    };
+
+    if let Ok(mut registry) = registry.lock() {
+        registry.active.remove(&id);
+    }
+
+    match &result {
+        Ok(upload) => println!("[Uploads] {} finished: {}", id, upload.remote_id),
+        Err(e) => println!("[Uploads] {} failed: {}", id, e),
+    }
+    result
+}
+
+/// Upload a finished export
+///
+/// Emits "upload:progress" as chunks are confirmed, starting with a 0-byte event
+/// that carries the upload id for `cancel_upload`
+#[tauri::command]
+pub async fn upload_export(
+    path: String,
+    destination: UploadDestination,
+    metadata: Option<UploadMetadata>,
+    app: AppHandle,
+    registry: State<'_, UploadRegistryState>,
+) -> Result<UploadResult, String> {
+    run_upload(
+        PathBuf::from(path),
+        destination,
+        metadata.unwrap_or_default(),
+        app,
+        registry.inner(),
+    )
+    .await
+}
+
+/// Upload a recording to the configured S3 bucket and return a presigned link to it
+///
+/// The link expires after `expires_in_secs`, falling back to the bucket's
+/// `shareExpirySecs` setting and then to 7 days (the longest S3 allows).
+/// Progress is reported through "upload:progress" like `upload_export`
+#[tauri::command]
+pub async fn share_recording(
+    path: String,
+    expires_in_secs: Option<u64>,
+    copy_to_clipboard: Option<bool>,
+    app: AppHandle,
+    registry: State<'_, UploadRegistryState>,
+) -> Result<SharedRecording, String> {
+    let credentials = s3::credentials()?;
+    let expires_in_secs = expires_in_secs
+        .or(credentials.share_expiry_secs)
+        .unwrap_or(s3::MAX_PRESIGN_EXPIRY_SECS)
+        .clamp(s3::MIN_PRESIGN_EXPIRY_SECS, s3::MAX_PRESIGN_EXPIRY_SECS);
+
+    let path = PathBuf::from(path);
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| "Recording path has no file name".to_string())?;
+    let metadata = UploadMetadata {
+        key: Some(format!(
+            "{}/{}-{}",
+            SHARE_KEY_PREFIX,
+            chrono::Utc::now().timestamp_millis(),
+            file_name
+        )),
+        ..UploadMetadata::default()
+    };
+
+    let upload = run_upload(
+        path,
+        UploadDestination::S3,
+        metadata,
+        app.clone(),
+        registry.inner(),
+    )
+    .await?;
+
+    let now = chrono::Utc::now();
+    let url = credentials.presigned_url(
+        &upload.remote_id,
+        expires_in_secs,
+        &now.format("%Y%m%dT%H%M%SZ").to_string(),
+    )?;
+
+    let copied_to_clipboard = copy_to_clipboard.unwrap_or(false)
+        && match app.clipboard().write_text(url.clone()) {
+            Ok(()) => true,
+            Err(e) => {
+                println!("[Uploads] Failed to copy share link: {}", e);
+                false
+            }
+        };
+
+    Ok(SharedRecording {
+        url,
+        key: upload.remote_id,
+        expires_at: now.timestamp() + expires_in_secs as i64,
+        copied_to_clipboard,
+    })
+}
+
+/// Cancel an upload in progress
+#[tauri::command]
+pub async fn cancel_upload(
+    upload_id: String,
+    registry: State<'_, UploadRegistryState>,
+) -> Result<(), String> {
+    let registry = registry.lock().map_err(|e| e.to_string())?;
+    let cancelled = registry
+        .active
+        .get(&upload_id)
+        .ok_or_else(|| format!("No upload in progress with id {}", upload_id))?;
+    cancelled.store(true, Ordering::SeqCst);
+    Ok(())
+}
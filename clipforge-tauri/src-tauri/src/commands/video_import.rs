@@ -1,31 +1,324 @@
-use super::metadata::{extract_metadata, VideoMetadata};
+use super::ffmpeg_utils::find_ffmpeg;
+use super::metadata::{extract_metadata, MediaKind, VideoMetadata};
+use super::process_runner::{watched_output_async_with_lines, JobKind};
+use super::storage::ensure_local;
 use super::thumbnail::generate_thumbnail;
+use super::waveform::get_audio_waveform;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::process::Command;
+use tokio::sync::Semaphore;
 
-#[tauri::command]
-pub async fn import_video(paths: Vec<String>) -> Result<Vec<VideoMetadata>, String> {
-    println!("Importing {} video file(s)", paths.len());
+/// Maximum number of files probed and thumbnailed at once
+pub const MAX_CONCURRENT_IMPORTS: usize = 4;
 
-    let mut metadata_list = Vec::new();
+/// File extensions picked up by folder import
+const MEDIA_EXTENSIONS: [&str; 15] = [
+    "mp4", "mov", "m4v", "mkv", "webm", "avi", "png", "jpg", "jpeg", "gif", "mp3", "wav", "m4a",
+    "aac", "flac",
+];
 
-    for path in paths {
-        // Extract metadata using ffprobe
-        match extract_metadata(path.clone()).await {
-            Ok(mut metadata) => {
-                // Generate thumbnail (use 1 second or 10% of duration, whichever is smaller)
-                let thumbnail_timestamp = (metadata.duration * 0.1).min(1.0).max(0.1);
-                match generate_thumbnail(path.clone(), Some(thumbnail_timestamp)).await {
-                    Ok(thumbnail_path) => {
-                        metadata.thumbnail_path = Some(thumbnail_path);
-                    }
-                    Err(_e) => {
-                        // Continue without thumbnail
-                    }
+/// Whether a file has one of the extensions ClipForge imports
+pub fn is_media_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| MEDIA_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Reason a file can't be edited reliably as-is
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportIssue {
+    /// Frame timing varies (typical of browser/webcam recordings), which breaks trimming
+    VariableFrameRate,
+    /// Container has no duration, so seeking and the timeline don't work
+    MissingDuration,
+    /// Codec is fine but the container isn't playable in the preview (e.g. HEVC in MKV)
+    UnsupportedContainer,
+    /// File has audio but no video stream
+    AudioOnly,
+    /// ffprobe couldn't read the file
+    Unreadable,
+}
+
+/// Conversion that makes a file edit-friendly
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConversionKind {
+    /// Copy streams into an MP4 container without re-encoding
+    Remux,
+    /// Re-encode to constant frame rate H.264/AAC MP4
+    Transcode,
+}
+
+/// Compatibility check result for one file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCheck {
+    pub path: String,
+    pub issues: Vec<ImportIssue>,
+    /// Conversion that resolves the issues, if any
+    pub suggested_conversion: Option<ConversionKind>,
+    /// ffprobe error for unreadable files
+    pub error: Option<String>,
+}
+
+/// Payload for the "import:file-complete" event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportFileComplete {
+    pub path: String,
+    /// Position of the file in the import batch
+    pub index: usize,
+    pub total: usize,
+    pub metadata: Option<VideoMetadata>,
+    pub error: Option<String>,
+}
+
+/// Payload for the "import:directory-complete" event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryImportSummary {
+    pub path: String,
+    pub imported: usize,
+    pub failed: usize,
+}
+
+/// Progress payload for the "import-conversion-progress" event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversionProgress {
+    pub path: String,
+    /// Percentage complete (0 - 100)
+    pub progress: f64,
+}
+
+/// Containers the preview can't play even when the codec is supported
+const REMUX_CONTAINERS: [&str; 3] = ["matroska", "avi", "flv"];
+
+/// Codecs that can be copied into MP4 without re-encoding
+const MP4_VIDEO_CODECS: [&str; 2] = ["h264", "hevc"];
+
+/// Detect problems that would make a file misbehave on the timeline
+pub fn import_issues(metadata: &VideoMetadata) -> Vec<ImportIssue> {
+    let mut issues = Vec::new();
+
+    // Images are looped into a constant frame rate at export
+    if metadata.media_kind == MediaKind::Image {
+        return issues;
+    }
+
+    if metadata.video_codec.is_none() {
+        // Audio files import as audio clips; audio-only video files likely lost their video
+        let audio_file = MediaKind::from_path(Path::new(&metadata.path)) == MediaKind::Audio;
+        if metadata.audio_stream_count > 0 && !audio_file {
+            issues.push(ImportIssue::AudioOnly);
+        }
+        return issues;
+    }
+
+    if metadata.duration <= 0.0 {
+        issues.push(ImportIssue::MissingDuration);
+    }
+
+    // WebM blobs report a 1000fps timebase as their real frame rate
+    let rate_mismatch = metadata.avg_frame_rate > 0.0
+        && (metadata.frame_rate - metadata.avg_frame_rate).abs() > 0.5;
+    if metadata.frame_rate >= 1000.0 || rate_mismatch {
+        issues.push(ImportIssue::VariableFrameRate);
+    }
+
+    let container = metadata.container.as_deref().unwrap_or("");
+    let codec = metadata.video_codec.as_deref().unwrap_or("");
+    if REMUX_CONTAINERS.iter().any(|c| container.contains(c)) && MP4_VIDEO_CODECS.contains(&codec) {
+        issues.push(ImportIssue::UnsupportedContainer);
+    }
+
+    issues
+}
+
+/// Cheapest conversion that resolves all of `issues`
+pub fn suggested_conversion(issues: &[ImportIssue]) -> Option<ConversionKind> {
+    if issues.iter().any(|issue| {
+        matches!(
+            issue,
+            ImportIssue::VariableFrameRate | ImportIssue::MissingDuration | ImportIssue::AudioOnly
+        )
+    }) {
+        Some(ConversionKind::Transcode)
+    } else if issues.contains(&ImportIssue::UnsupportedContainer) {
+        Some(ConversionKind::Remux)
+    } else {
+        None
+    }
+}
+
+/// Parse the output position from an FFmpeg `-progress` line, in seconds
+fn parse_progress_time(line: &str) -> Option<f64> {
+    let micros = line
+        .strip_prefix("out_time_us=")
+        .or_else(|| line.strip_prefix("out_time_ms="))?;
+    micros.trim().parse::<f64>().ok().map(|us| us / 1_000_000.0)
+}
+
+/// Output path for a converted file, next to the original
+fn converted_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("video");
+    path.with_file_name(format!("{}_edit.mp4", stem))
+}
+
+/// Media files in `dir` (and its subfolders when `recursive`), sorted by path
+///
+/// Hidden files and folders are skipped
+pub fn scan_media_files(dir: &Path, recursive: bool) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&current) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let hidden = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.starts_with('.'))
+                .unwrap_or(true);
+            if hidden {
+                continue;
+            }
+
+            if path.is_dir() {
+                if recursive {
+                    pending.push(path);
                 }
+                continue;
+            }
 
-                metadata_list.push(metadata);
+            if is_media_file(&path) {
+                files.push(path);
             }
-            Err(_e) => {
+        }
+    }
+
+    files.sort();
+    files
+}
+
+/// Extract metadata for one file and attach its thumbnail (or waveform for audio)
+pub async fn import_file(path: String) -> Result<VideoMetadata, String> {
+    // iCloud placeholders have to be downloaded before ffprobe can read them
+    ensure_local(Path::new(&path), &|_| {}).await?;
+
+    // Extract metadata using ffprobe
+    let mut metadata = extract_metadata(path.clone()).await?;
+
+    if metadata.media_kind == MediaKind::Audio {
+        // Audio clips show a waveform instead of a thumbnail
+        match get_audio_waveform(path.clone(), None).await {
+            Ok(waveform) => metadata.waveform = Some(waveform),
+            Err(e) => println!("[Import] No waveform for {}: {}", path, e),
+        }
+        return Ok(metadata);
+    }
+
+    // Generate thumbnail (use 1 second or 10% of duration, whichever is smaller)
+    // Images only have a frame at 0
+    let thumbnail_timestamp = if metadata.media_kind == MediaKind::Image {
+        0.0
+    } else {
+        (metadata.duration * 0.1).min(1.0).max(0.1)
+    };
+    match generate_thumbnail(path, Some(thumbnail_timestamp)).await {
+        Ok(thumbnail_path) => {
+            metadata.thumbnail_path = Some(thumbnail_path);
+        }
+        Err(_e) => {
+            // Continue without thumbnail
+        }
+    }
+
+    Ok(metadata)
+}
+
+/// Import files on a bounded worker pool, emitting "import:file-complete" as each finishes
+///
+/// Results are returned in the order of `paths`
+pub async fn import_files_concurrently(
+    app: &AppHandle,
+    paths: Vec<String>,
+) -> Vec<Result<VideoMetadata, String>> {
+    let total = paths.len();
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_IMPORTS));
+
+    let handles: Vec<_> = paths
+        .into_iter()
+        .enumerate()
+        .map(|(index, path)| {
+            let semaphore = semaphore.clone();
+            let app = app.clone();
+
+            tauri::async_runtime::spawn(async move {
+                let _permit = semaphore.acquire_owned().await.map_err(|e| e.to_string())?;
+
+                let downloaded = ensure_local(Path::new(&path), &|progress| {
+                    let _ = app.emit("storage:download-progress", progress);
+                })
+                .await;
+                let result = match downloaded {
+                    Ok(()) => import_file(path.clone()).await,
+                    Err(e) => Err(e.into()),
+                };
+
+                let _ = app.emit(
+                    "import:file-complete",
+                    ImportFileComplete {
+                        path,
+                        index,
+                        total,
+                        metadata: result.as_ref().ok().cloned(),
+                        error: result.as_ref().err().cloned(),
+                    },
+                );
+                result
+            })
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        results.push(
+            handle
+                .await
+                .map_err(|e| format!("Import task failed: {}", e))
+                .and_then(|result| result),
+        );
+    }
+    results
+}
+
+/// Import files concurrently, emitting "import:file-complete" as each one finishes
+#[tauri::command]
+pub async fn import_video(
+    app: AppHandle,
+    paths: Vec<String>,
+) -> Result<Vec<VideoMetadata>, String> {
+    println!("Importing {} video file(s)", paths.len());
+
+    let results = import_files_concurrently(&app, paths.clone()).await;
+
+    let mut metadata_list = Vec::new();
+    for (path, result) in paths.iter().zip(results) {
+        match result {
+            Ok(metadata) => metadata_list.push(metadata),
+            Err(e) => {
                 // Continue with next file rather than failing completely
+                println!("[Import] Skipping {}: {}", path, e);
             }
         }
     }
@@ -37,3 +330,351 @@ pub async fn import_video(paths: Vec<String>) -> Result<Vec<VideoMetadata>, Stri
     println!("Successfully imported {} files", metadata_list.len());
     Ok(metadata_list)
 }
+
+/// Import every media file in a folder
+///
+/// Returns the files found immediately; each is then imported in the background,
+/// reported through "import:file-complete" and finally "import:directory-complete"
+#[tauri::command]
+pub async fn import_directory(
+    app: AppHandle,
+    path: String,
+    recursive: Option<bool>,
+) -> Result<Vec<String>, String> {
+    let dir = Path::new(&path);
+    if !dir.is_dir() {
+        return Err(format!("Not a folder: {}", path));
+    }
+
+    let files: Vec<String> = scan_media_files(dir, recursive.unwrap_or(true))
+        .into_iter()
+        .map(|file| file.to_string_lossy().to_string())
+        .collect();
+    if files.is_empty() {
+        return Err("No media files found in folder".to_string());
+    }
+
+    println!("[Import] Importing {} file(s) from {}", files.len(), path);
+
+    let batch = files.clone();
+    tauri::async_runtime::spawn(async move {
+        let results = import_files_concurrently(&app, batch).await;
+        let imported = results.iter().filter(|result| result.is_ok()).count();
+
+        println!(
+            "[Import] Imported {} of {} file(s) from {}",
+            imported,
+            results.len(),
+            path
+        );
+        let _ = app.emit(
+            "import:directory-complete",
+            DirectoryImportSummary {
+                path,
+                imported,
+                failed: results.len() - imported,
+            },
+        );
+    });
+
+    Ok(files)
+}
+
+/// Check files for problems before import and suggest a conversion for each
+#[tauri::command]
+pub async fn check_import_compatibility(paths: Vec<String>) -> Result<Vec<ImportCheck>, String> {
+    let mut checks = Vec::new();
+
+    for path in paths {
+        let check = match extract_metadata(path.clone()).await {
+            Ok(metadata) => {
+                let issues = import_issues(&metadata);
+                ImportCheck {
+                    path,
+                    suggested_conversion: suggested_conversion(&issues),
+                    issues,
+                    error: None,
+                }
+            }
+            Err(e) => ImportCheck {
+                path,
+                issues: vec![ImportIssue::Unreadable],
+                // A re-encode often recovers files ffprobe can't fully parse
+                suggested_conversion: Some(ConversionKind::Transcode),
+                error: Some(e.to_string()),
+            },
+        };
+        checks.push(check);
+    }
+
+    Ok(checks)
+}
+
+/// Convert a file into an edit-friendly MP4 next to the original and import it
+///
+/// Emits "import-conversion-progress" while FFmpeg runs
+#[tauri::command]
+pub async fn convert_for_editing(
+    app: AppHandle,
+    path: String,
+    kind: ConversionKind,
+) -> Result<VideoMetadata, String> {
+    let ffmpeg_path =
+        find_ffmpeg().ok_or_else(|| "FFmpeg not found. Please install FFmpeg.".to_string())?;
+    let source = extract_metadata(path.clone()).await.ok();
+    let duration = source.as_ref().map(|m| m.duration).unwrap_or(0.0);
+    let output_path = converted_path(Path::new(&path));
+
+    let mut command = Command::new(&ffmpeg_path);
+    command.arg("-v").arg("error");
+
+    let audio_only = kind == ConversionKind::Transcode
+        && source
+            .as_ref()
+            .map(|m| m.video_codec.is_none())
+            .unwrap_or(false);
+    if audio_only {
+        // Give audio-only files a black picture so they behave like any other clip
+        command
+            .arg("-f")
+            .arg("lavfi")
+            .arg("-i")
+            .arg("color=c=black:s=1280x720:r=30");
+    }
+    command.arg("-i").arg(&path);
+
+    match kind {
+        ConversionKind::Remux => {
+            command.arg("-map").arg("0").arg("-c").arg("copy");
+            if source.as_ref().and_then(|m| m.video_codec.as_deref()) == Some("hevc") {
+                // QuickTime/WebKit only play HEVC tagged as hvc1
+                command.arg("-tag:v").arg("hvc1");
+            }
+        }
+        ConversionKind::Transcode => {
+            // Use the average rate as the constant target; timebase rates are meaningless
+            let fps = source
+                .as_ref()
+                .map(|m| m.avg_frame_rate)
+                .filter(|fps| *fps > 0.0 && *fps <= 120.0)
+                .unwrap_or(30.0);
+
+            if audio_only {
+                command
+                    .arg("-map")
+                    .arg("0:v")
+                    .arg("-map")
+                    .arg("1:a")
+                    .arg("-shortest");
+            } else {
+                command.arg("-map").arg("0:v:0").arg("-map").arg("0:a?");
+            }
+            command
+                .arg("-c:v")
+                .arg("libx264")
+                .arg("-preset")
+                .arg("veryfast")
+                .arg("-crf")
+                .arg("18")
+                .arg("-pix_fmt")
+                .arg("yuv420p")
+                .arg("-vsync")
+                .arg("cfr")
+                .arg("-r")
+                .arg(format!("{:.3}", fps))
+                .arg("-c:a")
+                .arg("aac")
+                .arg("-b:a")
+                .arg("192k");
+        }
+    }
+
+    command
+        .arg("-movflags")
+        .arg("+faststart")
+        .arg("-progress")
+        .arg("pipe:1")
+        .arg("-nostats")
+        .arg("-y")
+        .arg(&output_path);
+
+    println!(
+        "[Import] Converting {} ({:?}) to {}",
+        path,
+        kind,
+        output_path.display()
+    );
+
+    let emit_progress = |progress: f64| {
+        let _ = app.emit(
+            "import-conversion-progress",
+            ConversionProgress {
+                path: path.clone(),
+                progress,
+            },
+        );
+    };
+
+    emit_progress(0.0);
+    let output = watched_output_async_with_lines(&mut command, JobKind::Encode, |line| {
+        if let Some(time) = parse_progress_time(line) {
+            if duration > 0.0 {
+                emit_progress((time / duration * 100.0).clamp(0.0, 99.0));
+            }
+        }
+    })
+    .await;
+
+    let failure = match output {
+        Ok(output) if output.status.success() => None,
+        Ok(output) => Some(format!(
+            "FFmpeg conversion failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )),
+        Err(e) => Some(format!("Failed to execute ffmpeg: {}", e)),
+    };
+    if let Some(error) = failure {
+        let _ = std::fs::remove_file(&output_path);
+        return Err(error);
+    }
+    emit_progress(100.0);
+
+    let output_path = output_path.to_string_lossy().to_string();
+    import_file(output_path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_issues_clean_file() {
+        let clean: VideoMetadata = serde_json::from_value(serde_json::json!({
+            "path": "/tmp/clip",
+            "filename": "clip",
+            "duration": 10.0,
+            "width": 1920,
+            "height": 1080,
+            "frame_rate": 30.0,
+            "avg_frame_rate": 30.0,
+            "container": "mov,mp4,m4a,3gp,3g2,mj2",
+            "thumbnail_path": null,
+            "file_size": null,
+            "video_codec": "h264",
+            "audio_stream_count": 1
+        }))
+        .unwrap();
+        assert!(import_issues(&clean).is_empty());
+    }
+
+    #[test]
+    fn test_import_issues_webcam_blob() {
+        let blob: VideoMetadata = serde_json::from_value(serde_json::json!({
+            "path": "/tmp/clip",
+            "filename": "clip",
+            "duration": 10.0,
+            "width": 1920,
+            "height": 1080,
+            "frame_rate": 1000.0,
+            "avg_frame_rate": 29.5,
+            "container": "matroska,webm",
+            "thumbnail_path": null,
+            "file_size": null,
+            "video_codec": "vp8",
+            "audio_stream_count": 1
+        }))
+        .unwrap();
+        let issues = import_issues(&blob);
+
+        assert_eq!(issues, vec![ImportIssue::VariableFrameRate]);
+        assert_eq!(
+            suggested_conversion(&issues),
+            Some(ConversionKind::Transcode)
+        );
+    }
+
+    #[test]
+    fn test_import_issues_hevc_in_mkv() {
+        let mkv: VideoMetadata = serde_json::from_value(serde_json::json!({
+            "path": "/tmp/clip",
+            "filename": "clip",
+            "duration": 10.0,
+            "width": 1920,
+            "height": 1080,
+            "frame_rate": 24.0,
+            "avg_frame_rate": 24.0,
+            "container": "matroska,webm",
+            "thumbnail_path": null,
+            "file_size": null,
+            "video_codec": "hevc",
+            "audio_stream_count": 1
+        }))
+        .unwrap();
+        let issues = import_issues(&mkv);
+
+        assert_eq!(issues, vec![ImportIssue::UnsupportedContainer]);
+        assert_eq!(suggested_conversion(&issues), Some(ConversionKind::Remux));
+    }
+
+    #[test]
+    fn test_import_issues_audio_only() {
+        let audio: VideoMetadata = serde_json::from_value(serde_json::json!({
+            "path": "/tmp/clip",
+            "filename": "clip",
+            "duration": 10.0,
+            "width": 1920,
+            "height": 1080,
+            "frame_rate": 0.0,
+            "avg_frame_rate": 0.0,
+            "container": "mp3",
+            "thumbnail_path": null,
+            "file_size": null,
+            "video_codec": null,
+            "audio_stream_count": 1
+        }))
+        .unwrap();
+        assert_eq!(import_issues(&audio), vec![ImportIssue::AudioOnly]);
+
+        let voiceover: VideoMetadata = serde_json::from_value(serde_json::json!({
+            "path": "/tmp/voiceover.mp3",
+            "filename": "voiceover.mp3",
+            "duration": 10.0,
+            "width": 1920,
+            "height": 1080,
+            "frame_rate": 0.0,
+            "avg_frame_rate": 0.0,
+            "container": "mp3",
+            "thumbnail_path": null,
+            "file_size": null,
+            "video_codec": null,
+            "audio_stream_count": 1
+        }))
+        .unwrap();
+        assert!(import_issues(&voiceover).is_empty());
+    }
+
+    #[test]
+    fn test_scan_media_files() {
+        let dir = std::env::temp_dir().join(format!("clipforge_scan_test_{}", std::process::id()));
+        let nested = dir.join("nested");
+        std::fs::create_dir_all(&nested).unwrap();
+        for file in ["a.mp4", "notes.txt", ".hidden.mov"] {
+            std::fs::write(dir.join(file), b"").unwrap();
+        }
+        std::fs::write(nested.join("b.PNG"), b"").unwrap();
+
+        let flat = scan_media_files(&dir, false);
+        let all = scan_media_files(&dir, true);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(flat, vec![dir.join("a.mp4")]);
+        assert_eq!(all, vec![dir.join("a.mp4"), nested.join("b.PNG")]);
+    }
+
+    #[test]
+    fn test_parse_progress_time() {
+        assert_eq!(parse_progress_time("out_time_us=2500000"), Some(2.5));
+        assert_eq!(parse_progress_time("out_time_ms=1000000"), Some(1.0));
+        assert_eq!(parse_progress_time("progress=continue"), None);
+    }
+}
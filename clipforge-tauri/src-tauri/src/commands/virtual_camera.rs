@@ -0,0 +1,240 @@
+// Virtual camera output
+//
+// Publishes the composited preview frames to a companion process that exposes
+// them as a system camera (a CoreMediaIO camera extension host such as
+// `clipforge-vcam`), so the PiP layout can be picked as a camera in Zoom or Meet.
+//
+// The companion is started with `--name`, `--width`, `--height` and `--fps` and
+// reads frames from stdin, each as a little-endian u32 byte length followed by
+// the JPEG data. Frames are dropped rather than queued when it falls behind.
+
+use super::ffmpeg_utils;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Camera name shown in other applications
+const DEFAULT_CAMERA_NAME: &str = "ClipForge Camera";
+
+/// Frames buffered for the companion before new frames are dropped
+const FRAME_QUEUE_DEPTH: usize = 2;
+
+/// Virtual camera output settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualCameraSettings {
+    pub width: u32,
+    pub height: u32,
+    pub frame_rate: u32,
+    /// Camera name shown in other applications
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Companion executable; searched for on PATH when omitted
+    #[serde(default)]
+    pub helper_path: Option<String>,
+}
+
+/// Virtual camera status reported to the frontend
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualCameraStatus {
+    pub active: bool,
+    pub name: Option<String>,
+    pub frames_sent: u64,
+    pub frames_dropped: u64,
+}
+
+/// Running virtual camera companion and its frame queue
+#[derive(Default)]
+pub struct VirtualCameraOutput {
+    child: Option<Child>,
+    frames: Option<SyncSender<Vec<u8>>>,
+    status: VirtualCameraStatus,
+}
+
+impl VirtualCameraOutput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a JPEG frame for the companion, dropping it if the queue is full
+    pub fn publish(&mut self, jpeg: &[u8]) {
+        let Some(frames) = &self.frames else {
+            return;
+        };
+
+        match frames.try_send(jpeg.to_vec()) {
+            Ok(()) => self.status.frames_sent += 1,
+            Err(TrySendError::Full(_)) => self.status.frames_dropped += 1,
+            Err(TrySendError::Disconnected(_)) => self.stop(),
+        }
+    }
+
+    /// Stop the companion process
+    pub fn stop(&mut self) {
+        // Closing the queue ends the writer thread, which closes the companion's stdin
+        self.frames = None;
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        self.status.active = false;
+    }
+}
+
+impl Drop for VirtualCameraOutput {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Thread-safe virtual camera type
+pub type VirtualCameraState = Arc<Mutex<VirtualCameraOutput>>;
+
+/// Forward a preview frame to the virtual camera, if one is running
+pub fn publish_frame(app: &AppHandle, jpeg: &[u8]) {
+    if let Some(state) = app.try_state::<VirtualCameraState>() {
+        if let Ok(mut output) = state.lock() {
+            output.publish(jpeg);
+        }
+    }
+}
+
+fn write_frames(mut stdin: std::process::ChildStdin, frames: Receiver<Vec<u8>>, app: AppHandle) {
+    for jpeg in frames {
+        let result = stdin
+            .write_all(&(jpeg.len() as u32).to_le_bytes())
+            .and_then(|_| stdin.write_all(&jpeg))
+            .and_then(|_| stdin.flush());
+
+        if let Err(e) = result {
+            println!("[VirtualCamera] Companion stopped accepting frames: {}", e);
+            let _ = app.emit("virtual-camera:stopped", e.to_string());
+            return;
+        }
+    }
+}
+
+/// Start publishing preview frames to a virtual camera
+///
+/// Frames are taken from the running preview (`start_preview_for_source`), so the
+/// camera shows whatever the preview shows. Starting again replaces the previous
+/// companion
+#[tauri::command]
+pub async fn start_virtual_camera(
+    settings: VirtualCameraSettings,
+    app: AppHandle,
+    state: State<'_, VirtualCameraState>,
+) -> Result<VirtualCameraStatus, String> {
+    if settings.width == 0 || settings.height == 0 || settings.frame_rate == 0 {
+        return Err("Virtual camera size and frame rate must be greater than 0".to_string());
+    }
+
+    let helper = match &settings.helper_path {
+        Some(path) => PathBuf::from(path),
+        None => ffmpeg_utils::find_virtual_camera_helper().ok_or_else(|| {
+            "Virtual camera companion (clipforge-vcam) not found. Install it and try again."
+                .to_string()
+        })?,
+    };
+    let name = settings
+        .name
+        .clone()
+        .unwrap_or_else(|| DEFAULT_CAMERA_NAME.to_string());
+
+    let mut output = state.lock().map_err(|e| e.to_string())?;
+    output.stop();
+
+    let mut child = Command::new(&helper)
+        .arg("--name")
+        .arg(&name)
+        .arg("--width")
+        .arg(settings.width.to_string())
+        .arg("--height")
+        .arg(settings.height.to_string())
+        .arg("--fps")
+        .arg(settings.frame_rate.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start virtual camera companion: {}", e))?;
+
+    let stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| "Failed to open virtual camera companion stdin".to_string())?;
+    let (sender, receiver) = mpsc::sync_channel(FRAME_QUEUE_DEPTH);
+    std::thread::spawn(move || write_frames(stdin, receiver, app));
+
+    println!(
+        "[VirtualCamera] Publishing \"{}\" ({}x{} @ {}fps) via {}",
+        name,
+        settings.width,
+        settings.height,
+        settings.frame_rate,
+        helper.display()
+    );
+
+    output.child = Some(child);
+    output.frames = Some(sender);
+    output.status = VirtualCameraStatus {
+        active: true,
+        name: Some(name),
+        frames_sent: 0,
+        frames_dropped: 0,
+    };
+
+    Ok(output.status.clone())
+}
+
+/// Stop publishing to the virtual camera
+#[tauri::command]
+pub async fn stop_virtual_camera(
+    state: State<'_, VirtualCameraState>,
+) -> Result<VirtualCameraStatus, String> {
+    let mut output = state.lock().map_err(|e| e.to_string())?;
+    output.stop();
+    Ok(output.status.clone())
+}
+
+/// Publish a frame composited by the frontend (e.g. the PiP canvas)
+///
+/// `image_data` is a base64-encoded JPEG, the same encoding as `preview-frame` events
+#[tauri::command]
+pub async fn publish_virtual_camera_frame(
+    image_data: String,
+    state: State<'_, VirtualCameraState>,
+) -> Result<(), String> {
+    let jpeg = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, image_data)
+        .map_err(|e| format!("Invalid frame data: {}", e))?;
+
+    let mut output = state.lock().map_err(|e| e.to_string())?;
+    if output.frames.is_none() {
+        return Err("Virtual camera is not running".to_string());
+    }
+    output.publish(&jpeg);
+    Ok(())
+}
+
+/// Get the virtual camera status
+#[tauri::command]
+pub async fn get_virtual_camera_status(
+    state: State<'_, VirtualCameraState>,
+) -> Result<VirtualCameraStatus, String> {
+    let mut output = state.lock().map_err(|e| e.to_string())?;
+
+    // Notice a companion that exited on its own
+    let exited = output
+        .child
+        .as_mut()
+        .is_some_and(|child| matches!(child.try_wait(), Ok(Some(_))));
+    if exited {
+        output.stop();
+    }
+
+    Ok(output.status.clone())
+}
@@ -0,0 +1,306 @@
+// Watch folder
+//
+// Recordings made by other apps (OBS, QuickTime) can flow into the library
+// without a manual import. When `watchFolder` is set in the app settings, new
+// media files in that folder are picked up with notify, imported once they stop
+// growing (metadata plus a thumbnail or waveform, like any other import), added
+// to the recent recordings and announced with a "library:file-discovered" event.
+//
+// Only files created in or renamed into the folder while it is watched are
+// imported; files already there when watching starts are left alone even if
+// they are written to later. A recording that is paused stops growing without
+// being finished, so a settled file is only imported once no process has it
+// open. Files in ClipForge's own library and cache folders are never imported.
+
+use super::app_cache;
+use super::metadata::VideoMetadata;
+use super::recents::{add_recent, RecentKind};
+use super::settings::SettingsState;
+use super::video_import::{import_file, is_media_file};
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// How long a new file's size must stay the same before it is imported
+const FILE_SETTLE: Duration = Duration::from_secs(3);
+
+/// How often files still being written are checked
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The folder being watched; dropping the watcher stops it
+#[derive(Default)]
+pub struct WatchFolder {
+    folder: Option<PathBuf>,
+    watcher: Option<RecommendedWatcher>,
+}
+
+pub type WatchFolderState = Mutex<WatchFolder>;
+
+/// Payload for the "library:file-discovered" event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiscovered {
+    pub path: String,
+    pub metadata: VideoMetadata,
+}
+
+/// New files in the watch folder that may still be being written
+#[derive(Debug, Default)]
+struct PendingFiles {
+    /// Last seen size of each file and when it last changed
+    files: HashMap<PathBuf, (Option<u64>, Instant)>,
+    /// Files already imported, or already in the folder when watching started
+    imported: HashSet<PathBuf>,
+}
+
+impl PendingFiles {
+    /// Pending files for a folder, ignoring the files already in it
+    fn for_folder(folder: &Path) -> Self {
+        let imported = fs::read_dir(folder)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .collect();
+        Self {
+            files: HashMap::new(),
+            imported,
+        }
+    }
+
+    fn add(&mut self, path: PathBuf, now: Instant) {
+        if !self.imported.contains(&path) {
+            self.files.entry(path).or_insert((None, now));
+        }
+    }
+
+    /// Put a settled file back to wait again, e.g. while it is still open
+    fn defer(&mut self, path: PathBuf, now: Instant) {
+        self.imported.remove(&path);
+        self.files.insert(path, (None, now));
+    }
+
+    /// Files whose size has not changed for `FILE_SETTLE`
+    ///
+    /// Files that have disappeared (temp files renamed by the recorder) are dropped.
+    fn settled(&mut self, now: Instant, size_of: impl Fn(&Path) -> Option<u64>) -> Vec<PathBuf> {
+        let mut settled = Vec::new();
+        self.files.retain(|path, (size, changed)| {
+            let Some(current) = size_of(path) else {
+                return false;
+            };
+            if *size != Some(current) {
+                *size = Some(current);
+                *changed = now;
+            } else if current > 0 && now.duration_since(*changed) >= FILE_SETTLE {
+                settled.push(path.clone());
+                return false;
+            }
+            true
+        });
+        self.imported.extend(settled.iter().cloned());
+        settled
+    }
+}
+
+/// Start watching the folder from the saved settings
+pub fn start_watch_folder(app: &AppHandle) {
+    let folder = app.try_state::<SettingsState>().and_then(|settings| {
+        let settings = settings.lock().ok()?;
+        settings.watch_folder.clone()
+    });
+    app.manage(WatchFolderState::default());
+
+    if let Err(e) = set_watch_folder(app, folder.as_deref()) {
+        println!("[WatchFolder] {}", e);
+    }
+}
+
+/// Watch `folder` for new recordings instead of the current one, or stop watching
+pub fn set_watch_folder(app: &AppHandle, folder: Option<&str>) -> Result<(), String> {
+    let Some(state) = app.try_state::<WatchFolderState>() else {
+        return Ok(());
+    };
+    let mut current = state.lock().map_err(|e| e.to_string())?;
+    if current.folder.as_deref() == folder.map(Path::new) {
+        return Ok(());
+    }
+
+    *current = WatchFolder::default();
+    if let Some(folder) = folder {
+        let folder = PathBuf::from(folder);
+        current.watcher = Some(watch(app.clone(), &folder)?);
+        println!("[WatchFolder] Watching {}", folder.display());
+        current.folder = Some(folder);
+    }
+    Ok(())
+}
+
+fn watch(app: AppHandle, folder: &Path) -> Result<RecommendedWatcher, String> {
+    if !folder.is_dir() {
+        return Err(format!("Watch folder {} does not exist", folder.display()));
+    }
+
+    let (tx, rx) = mpsc::channel::<PathBuf>();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+        match result {
+            // Renames cover recorders that move a finished file into place; the
+            // new name is the last path, and a vanished old name is dropped later
+            Ok(event)
+                if matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_))
+                ) =>
+            {
+                for path in event.paths {
+                    let _ = tx.send(path);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => println!("[WatchFolder] Watch error: {}", e),
+        }
+    })
+    .map_err(|e| format!("Failed to create folder watcher: {}", e))?;
+    watcher
+        .watch(folder, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch {}: {}", folder.display(), e))?;
+
+    // Ends when the watcher is dropped and the channel closes
+    let pending = PendingFiles::for_folder(folder);
+    std::thread::spawn(move || process_events(app, rx, pending));
+    Ok(watcher)
+}
+
+/// Whether `path` is in a folder ClipForge writes its own recordings and exports to
+fn is_own_output(app: &AppHandle, path: &Path) -> bool {
+    let library = app.try_state::<SettingsState>().and_then(|settings| {
+        let settings = settings.lock().ok()?;
+        settings.library_path.clone().map(PathBuf::from)
+    });
+    library
+        .into_iter()
+        .chain([app_cache::cache_root()])
+        .any(|dir| path.starts_with(dir))
+}
+
+/// Whether any process has the file open, e.g. a recorder that is paused
+///
+/// Assumes the file is closed when lsof is unavailable.
+fn open_by_another_process(path: &Path) -> bool {
+    Command::new("lsof")
+        .arg("-t")
+        .arg("--")
+        .arg(path)
+        .output()
+        .map(|output| output.status.success() && !output.stdout.is_empty())
+        .unwrap_or(false)
+}
+
+fn process_events(app: AppHandle, rx: Receiver<PathBuf>, mut pending: PendingFiles) {
+    loop {
+        match rx.recv_timeout(POLL_INTERVAL) {
+            Ok(path) => {
+                let hidden = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .map(|name| name.starts_with('.'))
+                    .unwrap_or(true);
+                if !hidden && is_media_file(&path) && !is_own_output(&app, &path) {
+                    pending.add(path, Instant::now());
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        let settled = pending.settled(Instant::now(), |path| {
+            fs::metadata(path)
+                .ok()
+                .filter(|metadata| metadata.is_file())
+                .map(|metadata| metadata.len())
+        });
+        for path in settled {
+            if open_by_another_process(&path) {
+                pending.defer(path, Instant::now());
+            } else {
+                import_discovered(app.clone(), path.to_string_lossy().to_string());
+            }
+        }
+    }
+}
+
+fn import_discovered(app: AppHandle, path: String) {
+    tauri::async_runtime::spawn(async move {
+        match import_file(path.clone()).await {
+            Ok(metadata) => {
+                println!("[WatchFolder] Imported {}", path);
+                add_recent(&app, RecentKind::Recording, &path);
+                let _ = app.emit("library:file-discovered", FileDiscovered { path, metadata });
+            }
+            Err(e) => println!("[WatchFolder] Failed to import {}: {}", path, e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_imported_once_it_stops_growing() {
+        let start = Instant::now();
+        let path = PathBuf::from("/Movies/OBS/recording.mkv");
+        let mut pending = PendingFiles::default();
+        pending.add(path.clone(), start);
+
+        assert!(pending.settled(start, |_| Some(1_000)).is_empty());
+        // Still growing
+        let later = start + FILE_SETTLE;
+        assert!(pending.settled(later, |_| Some(5_000)).is_empty());
+        assert!(pending
+            .settled(later + Duration::from_secs(1), |_| Some(5_000))
+            .is_empty());
+
+        let settled = pending.settled(later + FILE_SETTLE, |_| Some(5_000));
+        assert_eq!(settled, vec![path.clone()]);
+
+        // Later writes to an imported file don't import it again
+        pending.add(path, later + FILE_SETTLE);
+        assert!(pending.files.is_empty());
+    }
+
+    #[test]
+    fn test_existing_files_ignored() {
+        let dir = std::env::temp_dir().join(format!("clipforge_watch_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let existing = dir.join("old.mov");
+        fs::write(&existing, b"frames").unwrap();
+
+        let start = Instant::now();
+        let mut pending = PendingFiles::for_folder(&dir);
+        pending.add(existing, start);
+        pending.add(dir.join("new.mov"), start);
+        assert_eq!(pending.files.len(), 1);
+        assert!(pending.files.contains_key(&dir.join("new.mov")));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_removed_file_dropped() {
+        let start = Instant::now();
+        let mut pending = PendingFiles::default();
+        pending.add(PathBuf::from("/Movies/OBS/recording.tmp.mov"), start);
+
+        assert!(pending.settled(start + FILE_SETTLE, |_| None).is_empty());
+        assert!(pending.files.is_empty());
+    }
+}
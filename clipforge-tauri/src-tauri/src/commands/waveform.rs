@@ -0,0 +1,126 @@
+// Audio waveform extraction
+//
+// Decodes a file's audio to low-rate mono PCM with FFmpeg and reduces it to
+// per-bucket peaks small enough to send to the timeline. Peaks are kept in the
+// media cache so reopening a project doesn't decode the audio again
+
+use super::ffmpeg_utils::find_ffmpeg;
+use super::media_cache::{self, CacheKind};
+use super::process_runner::{watched_output_async, JobKind};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::process::Command;
+
+/// Rate audio is decoded at before peaks are taken (Hz)
+const DECODE_SAMPLE_RATE: u32 = 8000;
+
+/// Downsampled waveform for timeline display
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Waveform {
+    /// Number of peaks per second of audio
+    pub samples_per_second: u32,
+    /// Audio duration in seconds
+    pub duration: f64,
+    /// Peak amplitude per bucket, scaled to 0 - 255
+    pub peaks: Vec<u8>,
+}
+
+/// Reduce signed 16-bit little-endian PCM into peaks of `bucket_size` samples
+pub fn compute_peaks(pcm: &[u8], bucket_size: usize) -> Vec<u8> {
+    let bucket_bytes = bucket_size.max(1) * 2;
+
+    pcm.chunks(bucket_bytes)
+        .map(|bucket| {
+            let peak = bucket
+                .chunks_exact(2)
+                .map(|sample| i16::from_le_bytes([sample[0], sample[1]]).unsigned_abs())
+                .max()
+                .unwrap_or(0);
+            // 32768 maps to 255
+            ((peak as u32 * 255) / 32768) as u8
+        })
+        .collect()
+}
+
+/// Extract waveform peaks from a media file's audio
+#[tauri::command]
+pub async fn get_audio_waveform(
+    video_path: String,
+    samples_per_second: Option<u32>,
+) -> Result<Waveform, String> {
+    let ffmpeg_path =
+        find_ffmpeg().ok_or_else(|| "ffmpeg not found. Please install FFmpeg.".to_string())?;
+    let samples_per_second = samples_per_second.unwrap_or(100).clamp(1, 1000);
+
+    let hash = media_cache::content_hash(Path::new(&video_path))?;
+    let variant = format!("{}sps", samples_per_second);
+    if let Some(waveform) = media_cache::lookup(&hash, CacheKind::Waveform, &variant)
+        .and_then(|item| item.data)
+        .and_then(|data| serde_json::from_value(data).ok())
+    {
+        return Ok(waveform);
+    }
+
+    let mut command = Command::new(&ffmpeg_path);
+    command
+        .arg("-v")
+        .arg("error")
+        .arg("-i")
+        .arg(&video_path)
+        .arg("-vn")
+        .arg("-ac")
+        .arg("1")
+        .arg("-ar")
+        .arg(DECODE_SAMPLE_RATE.to_string())
+        .arg("-f")
+        .arg("s16le")
+        .arg("-");
+    let output = watched_output_async(&mut command, JobKind::Thumbnail)
+        .await
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to decode audio: {}", stderr));
+    }
+
+    let bucket_size = (DECODE_SAMPLE_RATE / samples_per_second) as usize;
+    let peaks = compute_peaks(&output.stdout, bucket_size);
+    let duration = (output.stdout.len() / 2) as f64 / DECODE_SAMPLE_RATE as f64;
+
+    let waveform = Waveform {
+        samples_per_second,
+        duration,
+        peaks,
+    };
+    media_cache::store(
+        &hash,
+        CacheKind::Waveform,
+        &variant,
+        &video_path,
+        Vec::new(),
+        serde_json::to_value(&waveform).ok(),
+    );
+    Ok(waveform)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_peaks() {
+        // Little-endian i16 samples 0, 100, -32768, 5, 16384, 0, 0
+        let data: [u8; 14] = [
+            0x00, 0x00, 0x64, 0x00, 0x00, 0x80, 0x05, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x00,
+        ];
+
+        assert_eq!(compute_peaks(&data, 3), vec![255, 127, 0]);
+    }
+
+    #[test]
+    fn test_compute_peaks_empty() {
+        assert!(compute_peaks(&[], 80).is_empty());
+    }
+}
@@ -0,0 +1,237 @@
+// Webhooks on recording and export lifecycle events
+//
+// Each configured webhook receives a JSON POST when a recording starts or stops
+// and when an export finishes, so automations (Zapier, n8n, scripts) can react.
+// Webhooks with a secret get an `X-ClipForge-Signature: sha256=<hex>` header, the
+// HMAC-SHA256 of the body, so receivers can verify the request came from ClipForge.
+// The configuration, secrets included, is kept in the OS keychain.
+
+use super::uploads::keychain;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::time::Duration;
+
+/// Keychain account holding the webhook configuration
+const WEBHOOKS_ACCOUNT: &str = "webhooks";
+
+/// How long a webhook receiver gets to respond
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Lifecycle events that can trigger a webhook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WebhookEvent {
+    #[serde(rename = "recording.started")]
+    RecordingStarted,
+    #[serde(rename = "recording.stopped")]
+    RecordingStopped,
+    #[serde(rename = "export.finished")]
+    ExportFinished,
+}
+
+/// A configured webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Shared secret used to sign request bodies
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Events to send; all events when empty
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl WebhookConfig {
+    fn wants(&self, event: WebhookEvent) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(&event))
+    }
+}
+
+/// JSON body sent to webhooks
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPayload {
+    pub event: WebhookEvent,
+    /// RFC 3339 time the event happened
+    pub timestamp: String,
+    pub recording_id: Option<String>,
+    pub file_path: Option<String>,
+    /// Duration in seconds
+    pub duration: Option<f64>,
+    /// File size in bytes
+    pub size: Option<u64>,
+}
+
+impl WebhookPayload {
+    /// Build a payload for a file, filling in its size when it exists
+    pub fn new(
+        event: WebhookEvent,
+        recording_id: Option<String>,
+        file_path: Option<String>,
+        duration: Option<f64>,
+    ) -> Self {
+        let size = file_path
+            .as_ref()
+            .and_then(|path| std::fs::metadata(path).ok())
+            .map(|metadata| metadata.len());
+
+        Self {
+            event,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            recording_id,
+            file_path,
+            duration,
+            size,
+        }
+    }
+}
+
+/// HMAC-SHA256 signature header value for a request body
+fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn load_webhooks() -> Result<Vec<WebhookConfig>, String> {
+    keychain::load(WEBHOOKS_ACCOUNT).map(Option::unwrap_or_default)
+}
+
+async fn deliver(
+    client: &reqwest::Client,
+    webhook: &WebhookConfig,
+    payload: &WebhookPayload,
+) -> Result<(), String> {
+    let body = serde_json::to_vec(payload).map_err(|e| e.to_string())?;
+    let event = serde_json::to_value(payload.event)
+        .ok()
+        .and_then(|event| event.as_str().map(|event| event.to_string()))
+        .unwrap_or_default();
+
+    let mut request = client
+        .post(&webhook.url)
+        .timeout(WEBHOOK_TIMEOUT)
+        .header("Content-Type", "application/json")
+        .header("User-Agent", "ClipForge")
+        .header("X-ClipForge-Event", event);
+    if let Some(secret) = &webhook.secret {
+        request = request.header("X-ClipForge-Signature", signature(secret, &body));
+    }
+
+    let response = request
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| format!("Webhook request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Webhook returned {}", response.status()));
+    }
+    Ok(())
+}
+
+/// Send an event to every webhook subscribed to it, in the background
+///
+/// The keychain read can block on a system prompt, so it happens in the
+/// background too rather than on the caller's thread
+pub fn fire(payload: WebhookPayload) {
+    tauri::async_runtime::spawn(async move {
+        let webhooks = match tauri::async_runtime::spawn_blocking(load_webhooks).await {
+            Ok(Ok(webhooks)) => webhooks,
+            Ok(Err(e)) => {
+                println!("[Webhooks] {}", e);
+                return;
+            }
+            Err(e) => {
+                println!("[Webhooks] Failed to load webhooks: {}", e);
+                return;
+            }
+        };
+        let webhooks: Vec<WebhookConfig> = webhooks
+            .into_iter()
+            .filter(|webhook| webhook.wants(payload.event))
+            .collect();
+
+        let client = reqwest::Client::new();
+        for webhook in &webhooks {
+            if let Err(e) = deliver(&client, webhook, &payload).await {
+                println!("[Webhooks] {:?} to {}: {}", payload.event, webhook.url, e);
+            }
+        }
+    });
+}
+
+/// Get the configured webhooks
+#[tauri::command]
+pub async fn get_webhooks() -> Result<Vec<WebhookConfig>, String> {
+    load_webhooks()
+}
+
+/// Replace the configured webhooks
+#[tauri::command]
+pub async fn set_webhooks(webhooks: Vec<WebhookConfig>) -> Result<(), String> {
+    for webhook in &webhooks {
+        let url = reqwest::Url::parse(&webhook.url)
+            .map_err(|e| format!("Invalid webhook URL '{}': {}", webhook.url, e))?;
+        if url.scheme() != "https" && url.scheme() != "http" {
+            return Err(format!(
+                "Webhook URL must use http or https: {}",
+                webhook.url
+            ));
+        }
+    }
+    keychain::store(WEBHOOKS_ACCOUNT, &webhooks)
+}
+
+/// Send a sample "recording.stopped" payload to a webhook and report the result
+#[tauri::command]
+pub async fn test_webhook(webhook: WebhookConfig) -> Result<(), String> {
+    let payload = WebhookPayload {
+        event: WebhookEvent::RecordingStopped,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        recording_id: Some("rec_test".to_string()),
+        file_path: Some("/tmp/clipforge-test.mp4".to_string()),
+        duration: Some(12.5),
+        size: Some(1_048_576),
+    };
+    deliver(&reqwest::Client::new(), &webhook, &payload).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            signature("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let mut webhook = WebhookConfig {
+            url: "https://example.com/hook".to_string(),
+            secret: None,
+            events: vec![],
+            enabled: true,
+        };
+        assert!(webhook.wants(WebhookEvent::ExportFinished));
+
+        webhook.events = vec![WebhookEvent::RecordingStopped];
+        assert!(webhook.wants(WebhookEvent::RecordingStopped));
+        assert!(!webhook.wants(WebhookEvent::RecordingStarted));
+
+        webhook.enabled = false;
+        assert!(!webhook.wants(WebhookEvent::RecordingStopped));
+    }
+}
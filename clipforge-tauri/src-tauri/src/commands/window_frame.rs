@@ -0,0 +1,205 @@
+// Window frame export effect
+//
+// Places the recorded video on a solid, gradient or wallpaper background with
+// padding, rounded corners and a soft drop shadow
+
+use serde::{Deserialize, Serialize};
+
+/// Background drawn behind the framed video
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum FrameBackground {
+    /// Single solid color (FFmpeg color syntax)
+    Solid { color: String },
+    /// Linear gradient between two colors
+    Gradient { from: String, to: String },
+    /// Image file scaled to cover the output
+    Wallpaper { path: String },
+}
+
+impl Default for FrameBackground {
+    fn default() -> Self {
+        FrameBackground::Gradient {
+            from: "0x4F46E5".to_string(),
+            to: "0xDB2777".to_string(),
+        }
+    }
+}
+
+/// Settings for the window frame effect
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WindowFrameSettings {
+    pub background: FrameBackground,
+    /// Padding around the video as a fraction of the output width (0.0 - 0.3)
+    pub padding: f64,
+    /// Corner radius in output pixels
+    pub corner_radius: u32,
+    /// Draw a drop shadow beneath the video
+    pub shadow: bool,
+}
+
+impl Default for WindowFrameSettings {
+    fn default() -> Self {
+        Self {
+            background: FrameBackground::default(),
+            padding: 0.06,
+            corner_radius: 16,
+            shadow: true,
+        }
+    }
+}
+
+impl WindowFrameSettings {
+    /// Extra FFmpeg input arguments needed by the background, if any
+    ///
+    /// Only wallpapers need an input; colors and gradients are generated in the filter graph
+    pub fn background_input_args(&self) -> Option<Vec<String>> {
+        match &self.background {
+            FrameBackground::Wallpaper { path } => Some(vec![
+                "-loop".to_string(),
+                "1".to_string(),
+                "-i".to_string(),
+                path.clone(),
+            ]),
+            _ => None,
+        }
+    }
+
+    /// Size of the inner video box for an output of the given size
+    pub fn inner_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let pad = (width as f64 * self.padding.clamp(0.0, 0.3)).round() as u32;
+        let inner_w = width.saturating_sub(pad * 2).max(2) & !1;
+        let inner_h = height.saturating_sub(pad * 2).max(2) & !1;
+        (inner_w, inner_h)
+    }
+
+    /// Build the filter graph that frames `input` into `output`
+    ///
+    /// `background_input` is the stream label of the wallpaper input when
+    /// `background_input_args` returned arguments
+    pub fn filter_graph(
+        &self,
+        input: &str,
+        background_input: Option<&str>,
+        width: u32,
+        height: u32,
+        fps: f64,
+        output: &str,
+    ) -> String {
+        let (inner_w, inner_h) = self.inner_size(width, height);
+        let mut graph = Vec::new();
+
+        // Background at full output size
+        let background = match (&self.background, background_input) {
+            (FrameBackground::Wallpaper { .. }, Some(label)) => format!(
+                "[{}]scale={w}:{h}:force_original_aspect_ratio=increase,crop={w}:{h},fps={fps},format=yuv420p",
+                label,
+                w = width,
+                h = height,
+                fps = fps
+            ),
+            (FrameBackground::Solid { color }, _) => {
+                format!("color=c={}:s={}x{}:r={}", color, width, height, fps)
+            }
+            (FrameBackground::Gradient { from, to }, _) => format!(
+                "gradients=s={}x{}:c0={}:c1={}:x0=0:y0=0:x1={}:y1={}:r={}:speed=0",
+                width, height, from, to, width, height, fps
+            ),
+            // Wallpaper without an input falls back to black
+            (FrameBackground::Wallpaper { .. }, None) => {
+                format!("color=c=black:s={}x{}:r={}", width, height, fps)
+            }
+        };
+        graph.push(format!("{}[frame_bg]", background));
+
+        // Fit the video into the inner box and cut rounded corners into its alpha
+        let radius = self.corner_radius.min(inner_w.min(inner_h) / 2);
+        let mut window = format!(
+            "[{}]scale={}:{}:force_original_aspect_ratio=decrease,setsar=1,format=rgba",
+            input, inner_w, inner_h
+        );
+        if radius > 0 {
+            window.push_str(&format!(
+                ",geq=r='r(X,Y)':g='g(X,Y)':b='b(X,Y)':a='if(gt(abs(W/2-X),W/2-{r})*gt(abs(H/2-Y),H/2-{r}),if(lte(hypot({r}-(W/2-abs(W/2-X)),{r}-(H/2-abs(H/2-Y))),{r}),255,0),255)'",
+                r = radius
+            ));
+        }
+
+        let mut base = "frame_bg".to_string();
+        if self.shadow {
+            // Blurred, offset copy of the window's alpha mask
+            let blur = (height / 60).max(4);
+            window.push_str(",split[frame_win][frame_shadow_src]");
+            graph.push(window);
+            graph.push(format!(
+                "[frame_shadow_src]colorchannelmixer=rr=0:gg=0:bb=0:aa=0.45,pad=iw+{p}:ih+{p}:{b}:{b}:color=black@0,boxblur={b}:2[frame_shadow]",
+                p = blur * 2,
+                b = blur
+            ));
+            graph.push(format!(
+                "[frame_bg][frame_shadow]overlay=x=(W-w)/2:y=(H-h)/2+{}[frame_base]",
+                blur / 2
+            ));
+            base = "frame_base".to_string();
+        } else {
+            window.push_str("[frame_win]");
+            graph.push(window);
+        }
+
+        graph.push(format!(
+            "[{}][frame_win]overlay=x=(W-w)/2:y=(H-h)/2:shortest=1,format=yuv420p[{}]",
+            base, output
+        ));
+
+        graph.join(";")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inner_size_is_even_and_padded() {
+        let settings = WindowFrameSettings {
+            padding: 0.05,
+            ..Default::default()
+        };
+
+        // 5% of 1920 is 96px on each side
+        assert_eq!(settings.inner_size(1920, 1080), (1728, 888));
+
+        let (w, h) = settings.inner_size(1279, 721);
+        assert_eq!(w % 2, 0);
+        assert_eq!(h % 2, 0);
+    }
+
+    #[test]
+    fn test_wallpaper_requires_input() {
+        let settings = WindowFrameSettings {
+            background: FrameBackground::Wallpaper {
+                path: "/tmp/wall.png".to_string(),
+            },
+            ..Default::default()
+        };
+        let args = settings.background_input_args().unwrap();
+        assert_eq!(args.last().unwrap(), "/tmp/wall.png");
+
+        assert!(WindowFrameSettings::default()
+            .background_input_args()
+            .is_none());
+    }
+
+    #[test]
+    fn test_filter_graph_labels() {
+        let settings = WindowFrameSettings {
+            shadow: false,
+            ..Default::default()
+        };
+        let graph = settings.filter_graph("norm", None, 1920, 1080, 30.0, "outv");
+        assert!(graph.starts_with("gradients=s=1920x1080"));
+        assert!(graph.contains("[norm]scale="));
+        assert!(graph.ends_with("[outv]"));
+    }
+}
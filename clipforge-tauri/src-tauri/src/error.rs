@@ -0,0 +1,267 @@
+// Errors returned to the frontend
+//
+// Commands return `ClipForgeError` instead of bare strings so the frontend can
+// branch on a stable `code` rather than matching message text. Each error
+// serializes as `{ code, message, recoveryHint }`. Helpers that still return
+// `Result<_, String>` convert with `?` in both directions: strings become
+// `ClipForgeError::Other`, and errors become their message.
+
+#[cfg(target_os = "macos")]
+use crate::capture::ffi::BridgeError;
+use crate::commands::recording::RecordingError;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// Error returned by ClipForge commands
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClipForgeError {
+    /// A required tool (FFmpeg, ffprobe) is not installed
+    DependencyMissing(String),
+    /// The user has not granted access to a resource
+    PermissionDenied(String),
+    /// Not enough free space to finish the operation (bytes)
+    DiskSpaceLow { available: u64, required: u64 },
+    /// A file, display or window that should exist does not
+    NotFound(String),
+    /// A file exists but its content is in iCloud and could not be downloaded
+    FileUnavailable(String),
+    /// Arguments or configuration were rejected
+    InvalidInput(String),
+    /// A recording is already running
+    AlreadyRecording,
+    /// The operation needs a recording and none is running
+    NotRecording,
+    /// A camera, microphone or display is not available
+    HardwareUnavailable(String),
+    /// FFmpeg or ffprobe ran and failed
+    Ffmpeg(String),
+    /// Screen or device capture failed to start or stop
+    Capture(String),
+    /// Reading or writing a file failed
+    Io(String),
+    /// Shared state was unavailable (a lock was poisoned)
+    Internal(String),
+    /// Any other failure, carrying its message
+    Other(String),
+}
+
+impl ClipForgeError {
+    /// Stable identifier the frontend can branch on
+    pub fn code(&self) -> &'static str {
+        match self {
+            ClipForgeError::DependencyMissing(_) => "dependency_missing",
+            ClipForgeError::PermissionDenied(_) => "permission_denied",
+            ClipForgeError::DiskSpaceLow { .. } => "disk_space_low",
+            ClipForgeError::NotFound(_) => "not_found",
+            ClipForgeError::FileUnavailable(_) => "file_unavailable",
+            ClipForgeError::InvalidInput(_) => "invalid_input",
+            ClipForgeError::AlreadyRecording => "already_recording",
+            ClipForgeError::NotRecording => "not_recording",
+            ClipForgeError::HardwareUnavailable(_) => "hardware_unavailable",
+            ClipForgeError::Ffmpeg(_) => "ffmpeg_failed",
+            ClipForgeError::Capture(_) => "capture_failed",
+            ClipForgeError::Io(_) => "io_error",
+            ClipForgeError::Internal(_) => "internal",
+            ClipForgeError::Other(_) => "error",
+        }
+    }
+
+    /// What the user can do about it, when there is something
+    pub fn recovery_hint(&self) -> Option<&'static str> {
+        match self {
+            ClipForgeError::DependencyMissing(_) => Some(
+                "Install FFmpeg (for example with `brew install ffmpeg`) and restart ClipForge.",
+            ),
+            ClipForgeError::PermissionDenied(_) => Some(
+                "Open System Settings > Privacy & Security and grant ClipForge access, \
+                 then try again.",
+            ),
+            ClipForgeError::DiskSpaceLow { .. } => {
+                Some("Free up disk space or choose a different location for recordings.")
+            }
+            ClipForgeError::NotFound(_) => {
+                Some("It may have been moved, deleted or closed; choose it again and retry.")
+            }
+            ClipForgeError::FileUnavailable(_) => Some(
+                "Check that you are online and signed in to iCloud, or download the file \
+                 in Finder, then try again.",
+            ),
+            ClipForgeError::AlreadyRecording => Some("Stop the current recording first."),
+            ClipForgeError::NotRecording => Some("Start a recording first."),
+            ClipForgeError::HardwareUnavailable(_) => {
+                Some("Check that the device is connected and not in use by another application.")
+            }
+            ClipForgeError::Internal(_) => Some("Restart ClipForge and try again."),
+            _ => None,
+        }
+    }
+
+    /// Shorthand for the FFmpeg-not-found error
+    pub fn ffmpeg_missing() -> Self {
+        ClipForgeError::DependencyMissing("FFmpeg".to_string())
+    }
+
+    /// Shorthand for the ffprobe-not-found error
+    pub fn ffprobe_missing() -> Self {
+        ClipForgeError::DependencyMissing("ffprobe".to_string())
+    }
+}
+
+impl fmt::Display for ClipForgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClipForgeError::DependencyMissing(tool) => {
+                write!(f, "{} not found. Please install FFmpeg.", tool)
+            }
+            ClipForgeError::PermissionDenied(resource) => {
+                write!(f, "Permission denied for {}", resource)
+            }
+            ClipForgeError::DiskSpaceLow {
+                available,
+                required,
+            } => write!(
+                f,
+                "Insufficient disk space. Available: {} MB, Required: {} MB",
+                available / 1_000_000,
+                required / 1_000_000
+            ),
+            ClipForgeError::AlreadyRecording => write!(f, "A recording is already in progress"),
+            ClipForgeError::NotRecording => write!(f, "No recording in progress"),
+            ClipForgeError::HardwareUnavailable(device) => {
+                write!(f, "{} is not available", device)
+            }
+            ClipForgeError::NotFound(message)
+            | ClipForgeError::FileUnavailable(message)
+            | ClipForgeError::InvalidInput(message)
+            | ClipForgeError::Ffmpeg(message)
+            | ClipForgeError::Capture(message)
+            | ClipForgeError::Io(message)
+            | ClipForgeError::Internal(message)
+            | ClipForgeError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ClipForgeError {}
+
+impl Serialize for ClipForgeError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Payload<'a> {
+            code: &'a str,
+            message: String,
+            recovery_hint: Option<&'a str>,
+        }
+
+        Payload {
+            code: self.code(),
+            message: self.to_string(),
+            recovery_hint: self.recovery_hint(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl From<String> for ClipForgeError {
+    fn from(message: String) -> Self {
+        ClipForgeError::Other(message)
+    }
+}
+
+impl From<&str> for ClipForgeError {
+    fn from(message: &str) -> Self {
+        ClipForgeError::Other(message.to_string())
+    }
+}
+
+impl From<ClipForgeError> for String {
+    fn from(error: ClipForgeError) -> Self {
+        error.to_string()
+    }
+}
+
+impl From<std::io::Error> for ClipForgeError {
+    fn from(error: std::io::Error) -> Self {
+        ClipForgeError::Io(error.to_string())
+    }
+}
+
+impl<T> From<std::sync::PoisonError<T>> for ClipForgeError {
+    fn from(error: std::sync::PoisonError<T>) -> Self {
+        ClipForgeError::Internal(error.to_string())
+    }
+}
+
+impl From<RecordingError> for ClipForgeError {
+    fn from(error: RecordingError) -> Self {
+        match error {
+            RecordingError::PermissionDenied(resource) => {
+                ClipForgeError::PermissionDenied(resource)
+            }
+            RecordingError::DiskSpaceLow {
+                available,
+                required,
+            } => ClipForgeError::DiskSpaceLow {
+                available,
+                required,
+            },
+            RecordingError::IoError(message) => ClipForgeError::Io(message),
+            RecordingError::InvalidConfig(message) | RecordingError::CodecNotSupported(message) => {
+                ClipForgeError::InvalidInput(message)
+            }
+            RecordingError::RecordingInProgress | RecordingError::AlreadyRecording => {
+                ClipForgeError::AlreadyRecording
+            }
+            RecordingError::NoActiveRecording | RecordingError::NotRecording => {
+                ClipForgeError::NotRecording
+            }
+            RecordingError::HardwareUnavailable(device) => {
+                ClipForgeError::HardwareUnavailable(device)
+            }
+            RecordingError::DependencyMissing { dependency, .. } => {
+                ClipForgeError::DependencyMissing(dependency)
+            }
+            RecordingError::CaptureInitFailed(message)
+            | RecordingError::CaptureStopFailed(message) => ClipForgeError::Capture(message),
+            RecordingError::Unknown(message) => ClipForgeError::Other(message),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl From<BridgeError> for ClipForgeError {
+    fn from(error: BridgeError) -> Self {
+        match error {
+            BridgeError::Unavailable => ClipForgeError::HardwareUnavailable(error.to_string()),
+            BridgeError::InvalidConfig(message) => ClipForgeError::InvalidInput(message),
+            BridgeError::NotFound(message) => ClipForgeError::NotFound(message),
+            BridgeError::NotConfigured(message)
+            | BridgeError::Unsupported(message)
+            | BridgeError::Failed(message) => ClipForgeError::Capture(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_code_message_and_hint() {
+        let value = serde_json::to_value(ClipForgeError::NotRecording).unwrap();
+        assert_eq!(value["code"], "not_recording");
+        assert_eq!(value["message"], "No recording in progress");
+        assert_eq!(value["recoveryHint"], "Start a recording first.");
+
+        let value = serde_json::to_value(ClipForgeError::from("boom".to_string())).unwrap();
+        assert_eq!(value["code"], "error");
+        assert!(value["recoveryHint"].is_null());
+    }
+
+    #[test]
+    fn test_string_round_trip() {
+        let message: String = ClipForgeError::ffmpeg_missing().into();
+        assert_eq!(message, "FFmpeg not found. Please install FFmpeg.");
+    }
+}
@@ -3,8 +3,10 @@
 
 use std::sync::{Arc, Mutex};
 use tauri::menu::*;
+use tauri::Manager;
 
 mod commands;
+mod error;
 
 #[cfg(target_os = "macos")]
 mod capture;
@@ -20,9 +22,6 @@ pub fn run() {
     // Initialize recording module and perform startup cleanup
     commands::recording::initialize_recording_module();
 
-    // Initialize recording manager state
-    let recording_manager = Arc::new(Mutex::new(commands::recording::RecordingManager::new()));
-
     // Initialize preview state
     let preview_state = Arc::new(Mutex::new(commands::preview::PreviewState::new()));
 
@@ -30,25 +29,116 @@ pub fn run() {
     let preview_capture_session =
         Arc::new(Mutex::new(commands::preview::PreviewCaptureSession::new()));
 
+    // Initialize autosave state
+    let autosave_state = Arc::new(Mutex::new(commands::autosave::AutosaveManager::new()));
+    let autosave_task_state = autosave_state.clone();
+
+    // Initialize permission watcher state
+    let permission_watcher_state =
+        Arc::new(Mutex::new(commands::permissions::PermissionWatcher::new()));
+
+    // Initialize live source thumbnail state
+    let source_thumbnail_state = Arc::new(Mutex::new(
+        commands::screen_sources::SourceThumbnailRefresher::new(),
+    ));
+
+    // Initialize virtual camera state
+    let virtual_camera_state = Arc::new(Mutex::new(
+        commands::virtual_camera::VirtualCameraOutput::new(),
+    ));
+
+    // Initialize upload registry state
+    let upload_registry = Arc::new(Mutex::new(commands::uploads::UploadRegistry::new()));
+
+    // Initialize export queue state
+    let export_queue = Arc::new(Mutex::new(commands::export_queue::ExportQueue::new()));
+
+    // Initialize background render cache state
+    let render_cache = Arc::new(Mutex::new(commands::render_cache::RenderCache::new()));
+
+    // Initialize control server state
+    let control_server = Arc::new(Mutex::new(commands::control_server::ControlServer::new()));
+
     tauri::Builder::default()
-        .manage(recording_manager)
         .manage(preview_state)
         .manage(preview_capture_session)
+        .manage(autosave_state)
+        .manage(source_thumbnail_state)
+        .manage(permission_watcher_state)
+        .manage(virtual_camera_state)
+        .manage(upload_registry)
+        .manage(export_queue)
+        .manage(render_cache)
+        .manage(control_server)
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .invoke_handler(tauri::generate_handler![
             greet,
             commands::video_import::import_video,
+            commands::video_import::import_directory,
+            commands::video_import::check_import_compatibility,
+            commands::video_import::convert_for_editing,
+            commands::quick_edit::quick_trim,
+            commands::quick_edit::concat_recordings,
+            commands::quick_edit::transform_video,
+            commands::quick_edit::extract_audio,
+            commands::quick_edit::replace_audio,
             commands::metadata::extract_metadata,
             commands::export::export_timeline,
+            commands::export_queue::enqueue_export,
+            commands::export_queue::list_export_jobs,
+            commands::export_queue::cancel_export_job,
+            commands::export_queue::set_export_parallelism,
+            commands::render_cache::update_render_cache,
+            commands::render_cache::get_render_cache_status,
+            commands::dry_run::preview_ffmpeg_command,
+            commands::storage::get_storage_status,
+            commands::storage::download_from_cloud,
+            commands::sharing::copy_to_clipboard,
+            commands::sharing::share_file,
+            commands::interchange::export_project_interchange,
+            commands::projects::save_project,
+            commands::projects::load_project,
+            commands::projects::verify_project_media,
+            commands::projects::relink_media,
+            commands::autosave::update_autosave,
+            commands::autosave::list_project_snapshots,
+            commands::autosave::restore_project_snapshot,
+            commands::analysis::analyze_silence,
+            commands::analysis::detect_scenes,
+            commands::waveform::get_audio_waveform,
+            commands::transcription::transcribe_video,
+            commands::subtitles::export_subtitles,
+            commands::uploads::save_upload_credentials,
+            commands::uploads::delete_upload_credentials,
+            commands::uploads::has_upload_credentials,
+            commands::uploads::upload_export,
+            commands::uploads::cancel_upload,
+            commands::uploads::share_recording,
+            commands::webhooks::get_webhooks,
+            commands::webhooks::set_webhooks,
+            commands::webhooks::test_webhook,
+            commands::control_server::start_control_server,
+            commands::control_server::stop_control_server,
+            commands::control_server::get_control_server_info,
             commands::recording::check_permission,
             commands::recording::request_permission,
+            commands::recording::open_privacy_settings,
+            commands::permissions::start_permission_watcher,
+            commands::permissions::stop_permission_watcher,
             commands::recording::get_recording_state,
             commands::recording::start_recording,
             commands::recording::stop_recording,
+            commands::recording::set_mic_muted,
+            commands::recording::update_recording_encoder,
             commands::recording::pause_recording,
             commands::recording::resume_recording,
+            commands::recording::add_marker,
+            commands::recording::get_recording_markers,
             commands::recording::validate_config,
             commands::recording::get_preset_config,
             commands::recording::list_quality_presets,
@@ -59,16 +149,50 @@ pub fn run() {
             commands::recording::get_disk_space_info,
             commands::recording::get_error_details,
             commands::recording::validate_device_availability,
+            commands::recording::preflight_recording,
             commands::recording::get_long_recording_config,
+            commands::recording::set_long_recording_config,
             commands::recording::validate_long_recording_config,
             commands::recording::save_webcam_recording,
             commands::recording::save_pip_metadata,
             commands::recording::composite_pip_recording,
+            commands::recording::integrity::verify_recording,
+            commands::recording::replay_buffer::start_replay_buffer,
+            commands::recording::replay_buffer::stop_replay_buffer,
+            commands::recording::replay_buffer::save_replay,
+            commands::recording::replay_buffer::get_replay_buffer_status,
+            commands::app_cache::get_cache_directory,
+            commands::app_cache::set_cache_directory,
+            commands::settings::get_settings,
+            commands::settings::update_settings,
+            commands::projects::history::reset_project_history,
+            commands::projects::history::apply_project_operation,
+            commands::projects::history::undo,
+            commands::projects::history::redo,
+            commands::projects::history::get_history_status,
+            commands::projects::history::set_undo_depth,
+            commands::recents::get_recent_projects,
+            commands::recents::get_recent_recordings,
+            commands::recents::clear_recents,
+            commands::onboarding::get_onboarding_status,
+            commands::onboarding::run_setup_step,
+            commands::media_cache::get_cache_stats,
+            commands::media_cache::clear_cache,
             commands::thumbnail::generate_thumbnail,
+            commands::thumbnail::generate_thumbnail_strip,
             commands::thumbnail::cleanup_old_thumbnails,
+            commands::thumbnail::export_frame,
+            commands::proxy::generate_proxy,
+            commands::proxy::get_proxy,
+            commands::proxy::get_proxy_cache_info,
+            commands::proxy::purge_proxy_cache,
             commands::screen_sources::enumerate_sources,
             commands::screen_sources::enumerate_screens,
             commands::screen_sources::enumerate_windows,
+            commands::screen_sources::enumerate_windows_fast,
+            commands::screen_sources::capture_screenshot,
+            commands::screen_sources::start_source_thumbnails,
+            commands::screen_sources::stop_source_thumbnails,
             commands::camera_sources::enumerate_cameras,
             commands::camera_sources::get_default_camera,
             commands::preview::start_preview,
@@ -77,9 +201,19 @@ pub fn run() {
             commands::preview::get_preview_metrics,
             commands::preview::get_preview_settings,
             commands::preview::start_preview_for_source,
-            commands::preview::stop_preview_for_source
+            commands::preview::stop_preview_for_source,
+            commands::preview::get_capture_stats,
+            commands::preview::configure_capture_queue,
+            commands::virtual_camera::start_virtual_camera,
+            commands::virtual_camera::stop_virtual_camera,
+            commands::virtual_camera::publish_virtual_camera_frame,
+            commands::virtual_camera::get_virtual_camera_status
         ])
         .setup(|app| {
+            // Recent projects and recordings feed File > Open Recent
+            let recents_state = commands::recents::load_recents(app.handle());
+            let recent_menu = commands::recents::build_recent_menu(app.handle(), &recents_state)?;
+
             // Create the menu
             let menu = MenuBuilder::new(app)
                 .items(&[
@@ -91,6 +225,7 @@ pub fn run() {
                             &MenuItemBuilder::with_id("open", "Open...")
                                 .accelerator("CmdOrCtrl+O")
                                 .build(app)?,
+                            &recent_menu,
                             &PredefinedMenuItem::separator(app)?,
                             &MenuItemBuilder::with_id("save", "Save")
                                 .accelerator("CmdOrCtrl+S")
@@ -108,8 +243,12 @@ pub fn run() {
                         .build()?,
                     &SubmenuBuilder::new(app, "Edit")
                         .items(&[
-                            &PredefinedMenuItem::undo(app, None)?,
-                            &PredefinedMenuItem::redo(app, None)?,
+                            &MenuItemBuilder::with_id("undo", "Undo")
+                                .accelerator("CmdOrCtrl+Z")
+                                .build(app)?,
+                            &MenuItemBuilder::with_id("redo", "Redo")
+                                .accelerator("CmdOrCtrl+Shift+Z")
+                                .build(app)?,
                             &PredefinedMenuItem::separator(app)?,
                             &PredefinedMenuItem::cut(app, None)?,
                             &PredefinedMenuItem::copy(app, None)?,
@@ -139,6 +278,39 @@ pub fn run() {
 
             // Set the menu for the app
             app.set_menu(menu)?;
+            app.manage(recents_state);
+            app.manage(commands::recents::RecentMenu(recent_menu));
+            app.manage(Arc::new(Mutex::new(
+                commands::projects::history::ProjectHistory::new(),
+            )));
+
+            // Load saved settings before any command can ask for them
+            app.manage(commands::settings::load_settings(app.handle()));
+
+            // Recording state keeps its files under the cache root the settings chose
+            app.manage(Arc::new(Mutex::new(
+                commands::recording::RecordingManager::new(),
+            )));
+            app.manage(Arc::new(Mutex::new(
+                commands::recording::replay_buffer::ReplayBuffer::new(),
+            )));
+
+            // Import recordings other apps save into the watch folder
+            commands::watch_folder::start_watch_folder(app.handle());
+
+            // Periodically snapshot unsaved project state
+            commands::autosave::start_autosave(app.handle().clone(), autosave_task_state);
+
+            // Report camera hot-plugs and fall back if the recording camera goes away
+            commands::camera_sources::start_camera_watcher(app.handle().clone());
+
+            // Map clipforge:// URLs onto the recording commands
+            commands::deep_link::register_deep_links(app.handle());
+
+            // Handle save/export and forward every menu selection as "menu:action"
+            app.on_menu_event(|app, event| {
+                commands::menu::handle_menu_event(app, event.id().as_ref());
+            });
 
             Ok(())
         })
@@ -27,6 +27,71 @@ struct ProcessedFrame {
     let frameNumber: UInt64
 }
 
+/// Color handling for captured frames (matches ColorMode on the Rust side)
+enum CaptureColorMode: Int32 {
+    /// sRGB / BT.709
+    case sdr = 0
+    /// Display P3 wide color
+    case displayP3 = 1
+    /// BT.2020 PQ recordings of SDR content; the 8-bit preview shows the P3 image
+    case pq = 2
+
+    /// Color space frames are delivered and tagged in
+    var colorSpaceName: CFString {
+        switch self {
+        case .sdr: return CGColorSpace.sRGB
+        case .displayP3, .pq: return CGColorSpace.displayP3
+        }
+    }
+}
+
+/// Result codes returned across the FFI boundary (matches BridgeError on the Rust side)
+enum BridgeStatus: Int32 {
+    case success = 1
+    case failed = 0
+    /// The requested display, window or application doesn't exist
+    case notFound = -1
+    /// The stream or content filter hasn't been configured yet
+    case notConfigured = -2
+    /// Requires a newer macOS version
+    case unsupported = -3
+}
+
+/// Capture session state reported to Rust (matches CaptureState on the Rust side)
+enum CaptureSessionState: Int32 {
+    /// Stream configuration or content filter missing
+    case unconfigured = 0
+    /// Configured and ready to start
+    case ready = 1
+    case capturing = 2
+}
+
+/// Which frame gives way when the frame queue is full (matches DropPolicy on the Rust side)
+enum FrameDropPolicy: Int32 {
+    /// Discard the oldest queued frame; live consumers such as the preview
+    case dropOldest = 0
+    /// Discard the incoming frame; ordered consumers such as an encoder
+    case dropNewest = 1
+}
+
+/// Receives raw BGRA frames: (context, width, height, data, length, timestamp, pixelFormat) -> 1 if queued
+typealias FrameSinkPushCallback = @convention(c) (
+    UnsafeRawPointer?, Int, Int, UnsafePointer<UInt8>?, Int, Double, UInt32
+) -> Int32
+
+/// Retains or releases a frame sink context
+typealias FrameSinkContextCallback = @convention(c) (UnsafeRawPointer?) -> Void
+
+/// Raw frame consumer registered from Rust
+///
+/// The context is owned by Rust; the bridge holds one reference from
+/// registration until the sink is replaced or the bridge is deallocated.
+struct FrameSink {
+    let context: UnsafeRawPointer
+    let push: FrameSinkPushCallback
+    let release: FrameSinkContextCallback
+}
+
 /// Represents a processed audio buffer ready for encoding
 @available(macOS 12.3, *)
 struct ProcessedAudioBuffer {
@@ -124,12 +189,25 @@ class ScreenCaptureKitBridge: NSObject {
     /// Default: 0.5 (50% quality, balance between size and quality)
     private var jpegQuality: CGFloat = 0.5
 
+    /// Color space for captured frames and the JPEGs made from them
+    /// Default: SDR (sRGB)
+    private var colorMode: CaptureColorMode = .sdr
+
     /// Frame queue for buffering processed frames
     private var frameQueue: [ProcessedFrame] = []
 
     /// Maximum frame queue size (default: 5 frames)
     private var maxFrameQueueSize: Int = 5
 
+    /// Which frame is discarded when the queue is full
+    private var frameDropPolicy: FrameDropPolicy = .dropOldest
+
+    /// Frames discarded because the queue was full
+    private var droppedFrameCount: UInt64 = 0
+
+    /// Largest number of frames queued at once
+    private var frameQueueHighWater: Int = 0
+
     /// Lock for thread-safe queue access
     private let queueLock = NSLock()
 
@@ -148,9 +226,18 @@ class ScreenCaptureKitBridge: NSObject {
     /// Stores the most recent error message for FFI retrieval
     private var lastErrorMessage: String?
 
+    /// Status code for the most recent error
+    private(set) var lastErrorStatus: BridgeStatus = .failed
+
     /// Debug counter for logging attachment dictionaries when pixel buffers are missing
     private var attachmentDebugCount: Int = 0
 
+    /// Receives raw frames for encoding, if registered
+    private var frameSink: FrameSink?
+
+    /// Lock held while the frame sink is used or replaced, so it is never released mid-push
+    private let frameSinkLock = NSLock()
+
     // MARK: - Initialization
 
     override init() {
@@ -160,6 +247,9 @@ class ScreenCaptureKitBridge: NSObject {
 
     deinit {
         print("[ScreenCaptureKit] Bridge module deallocated")
+        if let sink = frameSink {
+            sink.release(sink.context)
+        }
         if let activeStream = stream {
             Task { @MainActor in
                 do {
@@ -173,6 +263,38 @@ class ScreenCaptureKitBridge: NSObject {
 
     // MARK: - Configuration Methods
 
+    /// Registers the raw frame consumer, releasing any previous one
+    /// - Parameter sink: New frame sink, already retained for the bridge, or nil to clear it
+    func setFrameSink(_ sink: FrameSink?) {
+        frameSinkLock.lock()
+        let previous = frameSink
+        frameSink = sink
+        frameSinkLock.unlock()
+
+        if let previous = previous {
+            previous.release(previous.context)
+        }
+    }
+
+    /// Passes a locked BGRA pixel buffer to the frame sink
+    private func pushToFrameSink(baseAddress: UnsafeMutableRawPointer, width: Int, height: Int, bytesPerRow: Int, timestamp: Double, pixelFormat: OSType) {
+        frameSinkLock.lock()
+        defer { frameSinkLock.unlock() }
+
+        guard let sink = frameSink else { return }
+
+        // Consumers expect tightly packed rows
+        guard bytesPerRow == width * 4 else {
+            if frameCounter == 1 {
+                print("[ScreenCaptureKit Output] ⚠️ Padded rows (\(bytesPerRow) bytes for width \(width)), raw frames not forwarded")
+            }
+            return
+        }
+
+        let data = UnsafePointer(baseAddress.assumingMemoryBound(to: UInt8.self))
+        _ = sink.push(sink.context, width, height, data, bytesPerRow * height, timestamp, pixelFormat)
+    }
+
     /// Configures the frame throttling for preview
     /// - Parameters:
     ///   - captureFrameRate: Capture frame rate (e.g., 60fps)
@@ -195,28 +317,61 @@ class ScreenCaptureKitBridge: NSObject {
         print("[ScreenCaptureKit Config] ✅ JPEG quality configured: \(Int(clampedQuality * 100))%")
     }
 
+    /// Configures the color space used for capture; call before configureStream
+    /// - Parameter mode: Color handling for captured frames
+    func configureColorMode(_ mode: CaptureColorMode) {
+        colorMode = mode
+        print("[ScreenCaptureKit Config] ✅ Color mode configured: \(mode)")
+    }
+
     /// Configures the maximum frame queue size
-    /// - Parameter size: Maximum number of frames to buffer (1-20)
+    /// - Parameter size: Maximum number of frames to buffer (1-120)
     func configureFrameQueueSize(size: Int) {
-        let clampedSize = max(1, min(20, size))
+        let clampedSize = max(1, min(120, size))
         queueLock.lock()
         defer { queueLock.unlock() }
 
         maxFrameQueueSize = clampedSize
+        if frameQueue.count > clampedSize {
+            droppedFrameCount += UInt64(frameQueue.count - clampedSize)
+            frameQueue.removeFirst(frameQueue.count - clampedSize)
+        }
         print("[ScreenCaptureKit Config] ✅ Frame queue size configured: \(clampedSize) frames")
     }
 
+    /// Configures which frame is discarded when the queue is full
+    /// - Parameter policy: Drop policy for the frame queue
+    func configureFrameDropPolicy(_ policy: FrameDropPolicy) {
+        queueLock.lock()
+        defer { queueLock.unlock() }
+
+        frameDropPolicy = policy
+        print("[ScreenCaptureKit Config] ✅ Frame drop policy configured: \(policy)")
+    }
+
+    /// Frame queue statistics since capture started
+    /// - Returns: Capacity, current length, high-water mark and dropped frame count
+    func getFrameQueueStats() -> (capacity: Int, length: Int, highWater: Int, dropped: UInt64) {
+        queueLock.lock()
+        defer { queueLock.unlock() }
+
+        return (maxFrameQueueSize, frameQueue.count, frameQueueHighWater, droppedFrameCount)
+    }
+
     /// Clears any previously recorded error message
     private func clearLastError() {
         lastErrorMessage = nil
+        lastErrorStatus = .failed
     }
 
     /// Records an error message for later retrieval
     /// - Parameters:
     ///   - message: Human readable error description
     ///   - error: Optional underlying error
-    private func recordError(_ message: String, error: Error? = nil) {
+    ///   - status: Status code returned over FFI for this error
+    private func recordError(_ message: String, error: Error? = nil, status: BridgeStatus = .failed) {
         lastErrorMessage = message
+        lastErrorStatus = status
         print("[ScreenCaptureKit Error] \(message)")
 
         if let error = error {
@@ -224,6 +379,14 @@ class ScreenCaptureKitBridge: NSObject {
         }
     }
 
+    /// Current capture session state
+    func getCaptureState() -> CaptureSessionState {
+        if isCapturing {
+            return .capturing
+        }
+        return streamConfiguration != nil && contentFilter != nil ? .ready : .unconfigured
+    }
+
     /// Retrieves and clears the last recorded error message
     /// - Returns: The last error message, if any
     func takeLastErrorMessage() -> String? {
@@ -242,6 +405,16 @@ class ScreenCaptureKitBridge: NSObject {
 
         // Check if queue is full
         if frameQueue.count >= maxFrameQueueSize {
+            droppedFrameCount += 1
+
+            // Keep the queued frames and discard this one
+            if frameDropPolicy == .dropNewest {
+                #if DEBUG
+                print("[ScreenCaptureKit Queue] ⚠️ Queue full, dropped incoming frame #\(frame.frameNumber)")
+                #endif
+                return
+            }
+
             // Drop oldest frame (first in array)
             let droppedFrame = frameQueue.removeFirst()
             #if DEBUG
@@ -251,6 +424,7 @@ class ScreenCaptureKitBridge: NSObject {
 
         // Add new frame to end of queue
         frameQueue.append(frame)
+        frameQueueHighWater = max(frameQueueHighWater, frameQueue.count)
 
         #if DEBUG
         if frame.frameNumber % 15 == 0 {  // Log occasionally
@@ -377,8 +551,8 @@ class ScreenCaptureKitBridge: NSObject {
         // Pixel format - use BGRA for easier processing
         config.pixelFormat = kCVPixelFormatType_32BGRA
 
-        // Color space
-        config.colorSpaceName = CGColorSpace.sRGB
+        // Color space - wide color sources keep their P3 gamut instead of being clipped to sRGB
+        config.colorSpaceName = colorMode.colorSpaceName
 
         // Color matrix - BT.709 for HD content
         config.colorMatrix = kCVImageBufferYCbCrMatrix_ITU_R_709_2
@@ -392,7 +566,7 @@ class ScreenCaptureKitBridge: NSObject {
         self.streamConfiguration = config
         configureFrameThrottling(captureFrameRate: frameRate, previewFrameRate: frameRate)
 
-        print("[ScreenCaptureKit Config] ✅ Stream configured: \(width)x\(height) @ \(frameRate)fps, audio: \(captureAudio)")
+        print("[ScreenCaptureKit Config] ✅ Stream configured: \(width)x\(height) @ \(frameRate)fps, audio: \(captureAudio), color: \(colorMode)")
     }
 
     /// Creates a content filter for capturing a specific display
@@ -406,7 +580,7 @@ class ScreenCaptureKitBridge: NSObject {
 
             // Find the display with matching ID
             guard let display = content.displays.first(where: { $0.displayID == displayID }) else {
-                recordError("Display not found for ID \(displayID)")
+                recordError("Display not found for ID \(displayID)", status: .notFound)
                 return false
             }
 
@@ -433,7 +607,7 @@ class ScreenCaptureKitBridge: NSObject {
 
             // Find the window with matching ID
             guard let window = content.windows.first(where: { $0.windowID == windowID }) else {
-                print("[ScreenCaptureKit Filter] ⚠️ Window not found: \(windowID)")
+                recordError("Window not found for ID \(windowID)", status: .notFound)
                 return false
             }
 
@@ -459,6 +633,44 @@ class ScreenCaptureKitBridge: NSObject {
         }
     }
 
+    /// Creates a content filter whose audio comes only from (or excludes) specific applications
+    /// - Parameters:
+    ///   - displayID: The display the filter is anchored to (0 = first display)
+    ///   - bundleIDs: Bundle identifiers of the applications to match
+    ///   - exclude: True to capture every application except these
+    /// - Returns: True if successful, false otherwise
+    func configureApplicationAudioFilter(displayID: CGDirectDisplayID, bundleIDs: [String], exclude: Bool) async -> Bool {
+        clearLastError()
+        do {
+            let content = try await ContentCache.shared.getContent(excludeDesktopWindows: false)
+
+            let display = content.displays.first(where: { $0.displayID == displayID })
+                ?? content.displays.first
+            guard let resolvedDisplay = display else {
+                recordError("No displays available when configuring application audio filter")
+                return false
+            }
+
+            let applications = content.applications.filter { bundleIDs.contains($0.bundleIdentifier) }
+            if applications.isEmpty && !exclude {
+                recordError("None of the requested applications are running: \(bundleIDs.joined(separator: ", "))", status: .notFound)
+                return false
+            }
+
+            // Per-application audio follows the applications in the filter
+            let filter = exclude
+                ? SCContentFilter(display: resolvedDisplay, excludingApplications: applications, exceptingWindows: [])
+                : SCContentFilter(display: resolvedDisplay, including: applications, exceptingWindows: [])
+            self.contentFilter = filter
+
+            print("[ScreenCaptureKit Filter] ✅ Application audio filter configured (\(exclude ? "excluding" : "only") \(applications.count) apps)")
+            return true
+        } catch {
+            recordError("Failed to configure application audio filter: \(error.localizedDescription)", error: error)
+            return false
+        }
+    }
+
     // MARK: - Stream Control Methods
 
     /// Starts the screen capture stream.
@@ -469,13 +681,13 @@ class ScreenCaptureKitBridge: NSObject {
         clearLastError()
 
         guard let config = streamConfiguration else {
-            recordError("Cannot start: stream configuration not set")
+            recordError("Cannot start: stream configuration not set", status: .notConfigured)
             return false
         }
         print("[ScreenCaptureKit] ✅ Configuration verified")
 
         guard let filter = contentFilter else {
-            recordError("Cannot start: content filter not set")
+            recordError("Cannot start: content filter not set", status: .notConfigured)
             return false
         }
         print("[ScreenCaptureKit] ✅ Content filter verified")
@@ -488,6 +700,10 @@ class ScreenCaptureKitBridge: NSObject {
         // Reset counters and queues
         frameCounter = 0
         clearQueue()
+        queueLock.lock()
+        droppedFrameCount = 0
+        frameQueueHighWater = 0
+        queueLock.unlock()
         clearAudioQueue()
         print("[ScreenCaptureKit] ✅ Frame counter reset")
 
@@ -657,7 +873,8 @@ extension ScreenCaptureKitBridge: @preconcurrency SCStreamOutput {
     ///   - height: Frame height in pixels
     /// - Returns: JPEG compressed data, or nil if compression fails
     private func compressRGBtoJPEG(rgbData: Data, width: Int, height: Int) -> Data? {
-        let colorSpace = CGColorSpaceCreateDeviceRGB()
+        // Tag the JPEG with the capture color space so the webview renders P3 correctly
+        let colorSpace = CGColorSpace(name: colorMode.colorSpaceName) ?? CGColorSpaceCreateDeviceRGB()
         let bitmapInfo = CGBitmapInfo(rawValue: CGImageAlphaInfo.none.rawValue)
 
         // RGB is 3 bytes per pixel
@@ -794,8 +1011,12 @@ extension ScreenCaptureKitBridge: @preconcurrency SCStreamOutput {
         let presentationTime = CMSampleBufferGetPresentationTimeStamp(sampleBuffer)
         let timeSeconds = CMTimeGetSeconds(presentationTime)
 
-        // Skip this frame if throttling is active and it's not time to process
-        if !shouldProcessFrame {
+        // Skip this frame if throttling is active and it's not time to process,
+        // unless raw frames are still wanted for encoding
+        frameSinkLock.lock()
+        let hasFrameSink = frameSink != nil
+        frameSinkLock.unlock()
+        if !shouldProcessFrame && !hasFrameSink {
             return
         }
 
@@ -824,6 +1045,14 @@ extension ScreenCaptureKitBridge: @preconcurrency SCStreamOutput {
         // Get bytes per row (stride) - important for proper data alignment
         let bytesPerRow = CVPixelBufferGetBytesPerRow(pixelBuffer)
 
+        // Forward every frame to the encoder; only throttled frames go on to the preview
+        if hasFrameSink {
+            pushToFrameSink(baseAddress: baseAddress, width: width, height: height, bytesPerRow: bytesPerRow, timestamp: timeSeconds, pixelFormat: pixelFormat)
+        }
+        if !shouldProcessFrame {
+            return
+        }
+
         // Convert BGRA to RGB using Accelerate framework
         guard let rgbData = convertBGRAtoRGB(bgraData: baseAddress, width: width, height: height, bytesPerRow: bytesPerRow) else {
             print("[ScreenCaptureKit Output] ⚠️ Failed to convert BGRA to RGB")
@@ -1151,22 +1380,42 @@ public func screen_capture_bridge_destroy(_ bridge: UnsafeMutableRawPointer?) {
 
 /// Starts capture on a bridge instance
 /// - Parameter bridge: Pointer to the bridge instance
-/// - Returns: 1 if successful, 0 otherwise
+/// - Returns: A BridgeStatus code (1 if successful)
 @_cdecl("screen_capture_bridge_start")
 public func screen_capture_bridge_start(_ bridge: UnsafeMutableRawPointer?) -> Int32 {
     guard let bridge = bridge else {
         print("[ScreenCaptureKit FFI] ERROR: Cannot start capture - null bridge")
-        return 0
+        return BridgeStatus.failed.rawValue
     }
 
     if #available(macOS 12.3, *) {
-        let success: Bool = runOnMainActorAsync {
+        let status: BridgeStatus = runOnMainActorAsync {
             let bridgeInstance = Unmanaged<ScreenCaptureKitBridge>.fromOpaque(bridge).takeUnretainedValue()
-            return await bridgeInstance.startCaptureSession()
+            let success = await bridgeInstance.startCaptureSession()
+            return success ? .success : bridgeInstance.lastErrorStatus
         }
-        return success ? 1 : 0
+        return status.rawValue
     }
-    return 0
+    return BridgeStatus.unsupported.rawValue
+}
+
+/// Gets the capture session state
+/// - Parameter bridge: Pointer to the bridge instance
+/// - Returns: A CaptureSessionState code
+@_cdecl("screen_capture_bridge_get_state")
+public func screen_capture_bridge_get_state(_ bridge: UnsafeMutableRawPointer?) -> Int32 {
+    guard let bridge = bridge else {
+        return CaptureSessionState.unconfigured.rawValue
+    }
+
+    if #available(macOS 12.3, *) {
+        let state: CaptureSessionState = runOnMainActorSync {
+            let bridgeInstance = Unmanaged<ScreenCaptureKitBridge>.fromOpaque(bridge).takeUnretainedValue()
+            return bridgeInstance.getCaptureState()
+        }
+        return state.rawValue
+    }
+    return CaptureSessionState.unconfigured.rawValue
 }
 
 /// Stops capture on a bridge instance
@@ -1231,6 +1480,42 @@ public func screen_capture_bridge_take_last_error(_ bridge: UnsafeMutableRawPoin
     return nil
 }
 
+/// Registers the consumer for raw frames
+/// - Parameters:
+///   - bridge: Pointer to the bridge instance
+///   - context: Opaque consumer context, or null to unregister
+///   - push: Called with each raw BGRA frame
+///   - retain: Called once when the context is registered
+///   - release: Called once when the context is replaced or the bridge is destroyed
+@_cdecl("screen_capture_bridge_set_frame_sink")
+public func screen_capture_bridge_set_frame_sink(
+    _ bridge: UnsafeMutableRawPointer?,
+    _ context: UnsafeRawPointer?,
+    _ push: FrameSinkPushCallback?,
+    _ retain: FrameSinkContextCallback?,
+    _ release: FrameSinkContextCallback?
+) {
+    guard let bridge = bridge else {
+        print("[ScreenCaptureKit FFI] ERROR: Cannot set frame sink - null bridge")
+        return
+    }
+
+    var sink: FrameSink?
+    if let context = context, let push = push, let retain = retain, let release = release {
+        retain(context)
+        sink = FrameSink(context: context, push: push, release: release)
+    }
+
+    if #available(macOS 12.3, *) {
+        runOnMainActorSync {
+            let bridgeInstance = Unmanaged<ScreenCaptureKitBridge>.fromOpaque(bridge).takeUnretainedValue()
+            bridgeInstance.setFrameSink(sink)
+        }
+    } else if let sink = sink {
+        sink.release(sink.context)
+    }
+}
+
 /// Dequeues a processed JPEG frame from the bridge
 /// - Parameters:
 ///   - bridge: Pointer to the bridge instance
@@ -1341,6 +1626,70 @@ public func screen_capture_bridge_clear_frame_queue(_ bridge: UnsafeMutableRawPo
     }
 }
 
+/// Configures the frame queue size and drop policy
+/// - Parameters:
+///   - bridge: Pointer to the bridge instance
+///   - size: Maximum number of frames to buffer (1-120)
+///   - policy: 0 = drop oldest, 1 = drop newest
+@_cdecl("screen_capture_bridge_configure_frame_queue")
+public func screen_capture_bridge_configure_frame_queue(
+    _ bridge: UnsafeMutableRawPointer?,
+    _ size: Int32,
+    _ policy: Int32
+) {
+    guard let bridge = bridge else {
+        print("[ScreenCaptureKit FFI] ERROR: Cannot configure frame queue - null bridge")
+        return
+    }
+
+    if #available(macOS 12.3, *) {
+        runOnMainActorSync {
+            let bridgeInstance = Unmanaged<ScreenCaptureKitBridge>.fromOpaque(bridge).takeUnretainedValue()
+            bridgeInstance.configureFrameQueueSize(size: Int(size))
+            bridgeInstance.configureFrameDropPolicy(FrameDropPolicy(rawValue: policy) ?? .dropOldest)
+        }
+    }
+}
+
+/// Gets frame queue statistics since capture started
+/// - Parameters:
+///   - bridge: Pointer to the bridge instance
+///   - outCapacity: Pointer to store the queue capacity
+///   - outLength: Pointer to store the current queue length
+///   - outHighWater: Pointer to store the largest queue length seen
+///   - outDropped: Pointer to store the number of dropped frames
+/// - Returns: 1 if successful, 0 otherwise
+@_cdecl("screen_capture_bridge_get_frame_queue_stats")
+public func screen_capture_bridge_get_frame_queue_stats(
+    _ bridge: UnsafeMutableRawPointer?,
+    _ outCapacity: UnsafeMutablePointer<Int32>?,
+    _ outLength: UnsafeMutablePointer<Int32>?,
+    _ outHighWater: UnsafeMutablePointer<Int32>?,
+    _ outDropped: UnsafeMutablePointer<UInt64>?
+) -> Int32 {
+    guard let bridge = bridge,
+          let outCapacity = outCapacity,
+          let outLength = outLength,
+          let outHighWater = outHighWater,
+          let outDropped = outDropped else {
+        return 0
+    }
+
+    if #available(macOS 12.3, *) {
+        let stats = runOnMainActorSync {
+            let bridgeInstance = Unmanaged<ScreenCaptureKitBridge>.fromOpaque(bridge).takeUnretainedValue()
+            return bridgeInstance.getFrameQueueStats()
+        }
+        outCapacity.pointee = Int32(stats.capacity)
+        outLength.pointee = Int32(stats.length)
+        outHighWater.pointee = Int32(stats.highWater)
+        outDropped.pointee = stats.dropped
+        return 1
+    }
+
+    return 0
+}
+
 /// Configures the stream for capture
 /// - Parameters:
 ///   - bridge: Pointer to the bridge instance
@@ -1374,11 +1723,33 @@ public func screen_capture_bridge_configure_stream(
     }
 }
 
+/// Configures the color space used for capture
+/// - Parameters:
+///   - bridge: Pointer to the bridge instance
+///   - mode: 0 = SDR, 1 = Display P3, 2 = BT.2020 PQ
+@_cdecl("screen_capture_bridge_configure_color_mode")
+public func screen_capture_bridge_configure_color_mode(
+    _ bridge: UnsafeMutableRawPointer?,
+    _ mode: Int32
+) {
+    guard let bridge = bridge else {
+        print("[ScreenCaptureKit FFI] ERROR: Cannot configure color mode - null bridge")
+        return
+    }
+
+    if #available(macOS 12.3, *) {
+        runOnMainActorSync {
+            let bridgeInstance = Unmanaged<ScreenCaptureKitBridge>.fromOpaque(bridge).takeUnretainedValue()
+            bridgeInstance.configureColorMode(CaptureColorMode(rawValue: mode) ?? .sdr)
+        }
+    }
+}
+
 /// Configures the content filter to capture a specific display
 /// - Parameters:
 ///   - bridge: Pointer to the bridge instance
 ///   - displayID: The display ID to capture
-/// - Returns: 1 if successful, 0 otherwise
+/// - Returns: A BridgeStatus code (1 if successful)
 @_cdecl("screen_capture_bridge_configure_display")
 public func screen_capture_bridge_configure_display(
     _ bridge: UnsafeMutableRawPointer?,
@@ -1386,18 +1757,19 @@ public func screen_capture_bridge_configure_display(
 ) -> Int32 {
     guard let bridge = bridge else {
         print("[ScreenCaptureKit FFI] ERROR: Cannot configure display - null bridge")
-        return 0
+        return BridgeStatus.failed.rawValue
     }
 
     if #available(macOS 12.3, *) {
-        let success: Bool = runOnMainActorAsync {
+        let status: BridgeStatus = runOnMainActorAsync {
             let bridgeInstance = Unmanaged<ScreenCaptureKitBridge>.fromOpaque(bridge).takeUnretainedValue()
-            return await bridgeInstance.configureDisplayFilter(displayID: displayID)
+            let success = await bridgeInstance.configureDisplayFilter(displayID: displayID)
+            return success ? .success : bridgeInstance.lastErrorStatus
         }
-        return success ? 1 : 0
+        return status.rawValue
     } else {
         print("[ScreenCaptureKit FFI] ERROR: ScreenCaptureKit not available")
-        return 0
+        return BridgeStatus.unsupported.rawValue
     }
 }
 
@@ -1405,7 +1777,7 @@ public func screen_capture_bridge_configure_display(
 /// - Parameters:
 ///   - bridge: Pointer to the bridge instance
 ///   - windowID: The window ID to capture
-/// - Returns: 1 if successful, 0 otherwise
+/// - Returns: A BridgeStatus code (1 if successful)
 @_cdecl("screen_capture_bridge_configure_window")
 public func screen_capture_bridge_configure_window(
     _ bridge: UnsafeMutableRawPointer?,
@@ -1413,18 +1785,59 @@ public func screen_capture_bridge_configure_window(
 ) -> Int32 {
     guard let bridge = bridge else {
         print("[ScreenCaptureKit FFI] ERROR: Cannot configure window - null bridge")
-        return 0
+        return BridgeStatus.failed.rawValue
     }
 
     if #available(macOS 12.3, *) {
-        let success: Bool = runOnMainActorAsync {
+        let status: BridgeStatus = runOnMainActorAsync {
             let bridgeInstance = Unmanaged<ScreenCaptureKitBridge>.fromOpaque(bridge).takeUnretainedValue()
-            return await bridgeInstance.configureWindowFilter(windowID: windowID)
+            let success = await bridgeInstance.configureWindowFilter(windowID: windowID)
+            return success ? .success : bridgeInstance.lastErrorStatus
         }
-        return success ? 1 : 0
+        return status.rawValue
     } else {
         print("[ScreenCaptureKit FFI] ERROR: ScreenCaptureKit not available")
-        return 0
+        return BridgeStatus.unsupported.rawValue
+    }
+}
+
+/// Configures the content filter to capture audio from specific applications
+/// - Parameters:
+///   - bridge: Pointer to the bridge instance
+///   - displayID: The display the filter is anchored to (0 = first display)
+///   - bundleIDs: Newline-separated bundle identifiers
+///   - exclude: 1 to capture every application except these, 0 to capture only these
+/// - Returns: A BridgeStatus code (1 if successful)
+@_cdecl("screen_capture_bridge_configure_app_audio")
+public func screen_capture_bridge_configure_app_audio(
+    _ bridge: UnsafeMutableRawPointer?,
+    _ displayID: UInt32,
+    _ bundleIDs: UnsafePointer<CChar>?,
+    _ exclude: UInt8
+) -> Int32 {
+    guard let bridge = bridge, let bundleIDs = bundleIDs else {
+        print("[ScreenCaptureKit FFI] ERROR: Cannot configure application audio - null pointer")
+        return BridgeStatus.failed.rawValue
+    }
+
+    let identifiers = String(cString: bundleIDs)
+        .split(separator: "\n")
+        .map { String($0) }
+
+    if #available(macOS 13.0, *) {
+        let status: BridgeStatus = runOnMainActorAsync {
+            let bridgeInstance = Unmanaged<ScreenCaptureKitBridge>.fromOpaque(bridge).takeUnretainedValue()
+            let success = await bridgeInstance.configureApplicationAudioFilter(
+                displayID: displayID,
+                bundleIDs: identifiers,
+                exclude: exclude != 0
+            )
+            return success ? .success : bridgeInstance.lastErrorStatus
+        }
+        return status.rawValue
+    } else {
+        print("[ScreenCaptureKit FFI] ERROR: Per-application audio requires macOS 13.0 or later")
+        return BridgeStatus.unsupported.rawValue
     }
 }
 
@@ -1454,14 +1867,16 @@ public struct CDisplayInfo {
     public var x: Int32
     public var y: Int32
     public var isPrimary: UInt8  // boolean as u8
+    public var scaleFactor: Double  // pixels per point
 
-    public init(displayID: UInt32, width: UInt32, height: UInt32, x: Int32, y: Int32, isPrimary: Bool) {
+    public init(displayID: UInt32, width: UInt32, height: UInt32, x: Int32, y: Int32, isPrimary: Bool, scaleFactor: Double) {
         self.displayID = displayID
         self.width = width
         self.height = height
         self.x = x
         self.y = y
         self.isPrimary = isPrimary ? 1 : 0
+        self.scaleFactor = scaleFactor
     }
 }
 
@@ -1527,13 +1942,20 @@ public func screen_capture_enumerate_displays(
                     // Get display bounds
                     let frame = display.frame
 
+                    // SCDisplay sizes are in points; the display mode knows the backing pixels
+                    var scaleFactor = 1.0
+                    if let mode = CGDisplayCopyDisplayMode(display.displayID), mode.width > 0 {
+                        scaleFactor = Double(mode.pixelWidth) / Double(mode.width)
+                    }
+
                     return CDisplayInfo(
                         displayID: display.displayID,
                         width: UInt32(display.width),
                         height: UInt32(display.height),
                         x: Int32(frame.origin.x),
                         y: Int32(frame.origin.y),
-                        isPrimary: isPrimary
+                        isPrimary: isPrimary,
+                        scaleFactor: scaleFactor
                     )
                 }
 
@@ -1734,17 +2156,27 @@ public func screen_capture_invalidate_cache() {
 
 // MARK: - Thumbnail Generation Functions
 
+/// Scales a size down so its longest side fits within maxSize, keeping the aspect ratio
+/// Sources smaller than maxSize are left at their own size
+private func thumbnailSize(width: Int, height: Int, maxSize: Int32) -> (width: Int, height: Int) {
+    let longestSide = max(width, height, 1)
+    let scale = min(1.0, Double(maxSize) / Double(longestSide))
+    let scaledWidth = max(1, Int((Double(width) * scale).rounded()))
+    let scaledHeight = max(1, Int((Double(height) * scale).rounded()))
+    return (scaledWidth, scaledHeight)
+}
+
 /// Captures a thumbnail of a display using SCScreenshotManager
 /// - Parameters:
 ///   - displayID: The display ID to capture
-///   - maxWidth: Maximum width for the thumbnail
+///   - maxSize: Maximum length of the thumbnail's longest side
 ///   - outData: Pointer to store the PNG data
 ///   - outLength: Pointer to store the PNG data length
 /// - Returns: 1 if successful, 0 otherwise
 @_cdecl("screen_capture_display_thumbnail")
 public func screen_capture_display_thumbnail(
     _ displayID: UInt32,
-    _ maxWidth: Int32,
+    _ maxSize: Int32,
     _ outData: UnsafeMutablePointer<UnsafeMutablePointer<UInt8>?>?,
     _ outLength: UnsafeMutablePointer<Int32>?
 ) -> Int32 {
@@ -1777,12 +2209,10 @@ public func screen_capture_display_thumbnail(
                 let config = SCStreamConfiguration()
 
                 // Calculate thumbnail dimensions maintaining aspect ratio
-                let aspectRatio = CGFloat(display.height) / CGFloat(display.width)
-                let thumbWidth = min(Int(maxWidth), display.width)
-                let thumbHeight = Int(CGFloat(thumbWidth) * aspectRatio)
+                let size = thumbnailSize(width: display.width, height: display.height, maxSize: maxSize)
 
-                config.width = thumbWidth
-                config.height = thumbHeight
+                config.width = size.width
+                config.height = size.height
                 config.pixelFormat = kCVPixelFormatType_32BGRA
                 config.showsCursor = false
 
@@ -1826,14 +2256,14 @@ public func screen_capture_display_thumbnail(
 /// Captures a thumbnail of a window using SCScreenshotManager
 /// - Parameters:
 ///   - windowID: The window ID to capture
-///   - maxWidth: Maximum width for the thumbnail
+///   - maxSize: Maximum length of the thumbnail's longest side
 ///   - outData: Pointer to store the PNG data
 ///   - outLength: Pointer to store the PNG data length
 /// - Returns: 1 if successful, 0 otherwise
 @_cdecl("screen_capture_window_thumbnail")
 public func screen_capture_window_thumbnail(
     _ windowID: UInt32,
-    _ maxWidth: Int32,
+    _ maxSize: Int32,
     _ outData: UnsafeMutablePointer<UnsafeMutablePointer<UInt8>?>?,
     _ outLength: UnsafeMutablePointer<Int32>?
 ) -> Int32 {
@@ -1859,28 +2289,21 @@ public func screen_capture_window_thumbnail(
                     return
                 }
 
-                // Get a display for the filter (required by SCContentFilter)
-                guard let display = content.displays.first else {
-                    print("[ScreenCaptureKit Thumbnail] No displays available")
-                    semaphore.signal()
-                    return
-                }
-
-                // Create content filter including only this window
-                let filter = SCContentFilter(display: display, including: [window])
+                // Capture the window on its own, whichever display it is on
+                let filter = SCContentFilter(desktopIndependentWindow: window)
 
                 // Configure screenshot settings
                 let config = SCStreamConfiguration()
 
                 // Calculate thumbnail dimensions maintaining aspect ratio
-                let windowWidth = Int(window.frame.width)
-                let windowHeight = Int(window.frame.height)
-                let aspectRatio = CGFloat(windowHeight) / CGFloat(windowWidth)
-                let thumbWidth = min(Int(maxWidth), windowWidth)
-                let thumbHeight = Int(CGFloat(thumbWidth) * aspectRatio)
-
-                config.width = thumbWidth
-                config.height = thumbHeight
+                let size = thumbnailSize(
+                    width: Int(window.frame.width),
+                    height: Int(window.frame.height),
+                    maxSize: maxSize
+                )
+
+                config.width = size.width
+                config.height = size.height
                 config.pixelFormat = kCVPixelFormatType_32BGRA
                 config.showsCursor = false
 
@@ -8,7 +8,7 @@ import PreviewWindow from "./components/PreviewWindow";
 import { useTimeline } from "./hooks/useTimeline";
 import { useMediaLibrary } from "./hooks/useMediaLibrary";
 import { DndContext } from "@dnd-kit/core";
-import { save } from "@tauri-apps/plugin-dialog";
+import { open, save } from "@tauri-apps/plugin-dialog";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 
@@ -32,6 +32,7 @@ function App() {
   const [pipConfig, setPipConfig] = React.useState(null); // Track PiP configuration
   const [isPiPRecording, setIsPiPRecording] = React.useState(false); // Track if PiP recording is active
   const [isPiPPaused, setIsPiPPaused] = React.useState(false); // Track if PiP recording is paused
+  const [projectPath, setProjectPath] = React.useState(null); // Project file, once saved or opened
 
   // Panel visibility state - flexible panel system (2-3 panels)
   const [panelVisibility, setPanelVisibility] = React.useState({
@@ -119,7 +120,8 @@ function App() {
         sourceId: state.source.id,
         width: state.config.width,
         height: state.config.height,
-        frameRate: 15 // 15fps for preview
+        frameRate: 15, // 15fps for preview
+        colorMode: state.config.color_mode
       })
         .then(() => {
           // Preview started successfully
@@ -325,8 +327,16 @@ function App() {
     }
   };
 
+  // Pre-render timeline segments in the background while editing
+  React.useEffect(() => {
+    if (timeline.clips.length === 0) return;
+    invoke('update_render_cache', { clips: toExportClips(timeline.clips) }).catch(() => {
+      // Background rendering is best effort; export renders anything missing
+    });
+  }, [timeline.clips]);
+
   // Handle export
-  const handleExport = async () => {
+  const handleExport = async (defaultPath = 'export.mp4') => {
     if (timeline.clips.length === 0) {
       alert("No clips to export");
       return;
@@ -339,7 +349,7 @@ function App() {
           name: 'Video',
           extensions: ['mp4']
         }],
-        defaultPath: 'export.mp4'
+        defaultPath
       });
 
       if (!filePath) {
@@ -351,18 +361,7 @@ function App() {
       setExportProgress({ current: 0, total: 1, message: 'Starting export...' });
 
       // Prepare clips data for export
-      const clipsData = timeline.clips.map(clip => ({
-        videoPath: clip.videoPath,
-        startTime: clip.startTime,
-        trimStart: clip.trimStart,
-        trimEnd: clip.trimEnd,
-        duration: clip.duration,
-        width: clip.width,
-        height: clip.height,
-        frameRate: clip.frameRate,
-        mediaType: clip.mediaType,
-        pipMetadataPath: clip.pipMetadataPath
-      }));
+      const clipsData = toExportClips(timeline.clips);
 
       // Call Rust export command
       await invoke('export_timeline', {
@@ -379,6 +378,114 @@ function App() {
     }
   };
 
+  // Keep the backend's copy of the project current for File > Save and autosave
+  React.useEffect(() => {
+    invoke('update_autosave', {
+      projectJson: { clips: timeline.clips, mediaItems: mediaLibrary.mediaItems },
+      projectPath
+    }).catch((error) => console.error('Failed to update autosave:', error));
+  }, [timeline.clips, mediaLibrary.mediaItems, projectPath]);
+
+  const projectFilters = [{ name: 'ClipForge Project', extensions: ['clipforge'] }];
+
+  const saveProjectAs = async () => {
+    const filePath = await save({ filters: projectFilters, defaultPath: projectPath || undefined });
+    if (!filePath) return;
+
+    try {
+      await invoke('save_project', {
+        path: filePath,
+        projectJson: { clips: timeline.clips, mediaItems: mediaLibrary.mediaItems }
+      });
+      setProjectPath(filePath);
+    } catch (error) {
+      alert(`Failed to save project: ${error}`);
+    }
+  };
+
+  const openProject = async (filePath) => {
+    try {
+      const project = await invoke('load_project', { path: filePath });
+      timeline.loadClips(project.clips || []);
+      mediaLibrary.loadMediaItems(project.mediaItems || []);
+      setSelectedMedia(null);
+      setProjectPath(filePath);
+    } catch (error) {
+      alert(`Failed to open project: ${error}`);
+    }
+  };
+
+  // Menu actions the backend leaves to the frontend
+  const menuHandlers = React.useRef({});
+  menuHandlers.current = {
+    new: () => {
+      const hasContent = timeline.clips.length > 0 || mediaLibrary.mediaItems.length > 0;
+      if (hasContent && !confirm('Discard the current project and start a new one?')) return;
+      timeline.loadClips([]);
+      mediaLibrary.clearMediaLibrary();
+      setSelectedMedia(null);
+      setProjectPath(null);
+    },
+    open: async () => {
+      const filePath = await open({ multiple: false, filters: projectFilters });
+      if (filePath) openProject(filePath);
+    },
+    // Only reaches here when the project has no file yet
+    save: () => saveProjectAs(),
+    saveAs: () => saveProjectAs(),
+    openRecentProject: (event) => openProject(event.path),
+    export: (event) => handleExport(event.path || undefined),
+    zoomIn: () => timeline.zoom(0.5),
+    zoomOut: () => timeline.zoom(-0.5),
+    // Undo text edits natively, otherwise step the project history, falling back to the timeline
+    undo: async () => {
+      if (document.activeElement?.matches('input, textarea')) {
+        document.execCommand('undo');
+        return;
+      }
+      const status = await invoke("get_history_status");
+      if (status.canUndo) {
+        await invoke("undo");
+      } else {
+        timeline.undo();
+      }
+    },
+    redo: async () => {
+      if (document.activeElement?.matches('input, textarea')) {
+        document.execCommand('redo');
+        return;
+      }
+      const status = await invoke("get_history_status");
+      if (status.canRedo) {
+        await invoke("redo");
+      } else {
+        timeline.redo();
+      }
+    },
+    openRecentRecording: (event) => {
+      invoke("import_video", { paths: [event.path] })
+        .then((result) => mediaLibrary.addMediaItems(result))
+        .catch((error) => alert(`Failed to open recording: ${error}`));
+    },
+  };
+
+  React.useEffect(() => {
+    const unlisten = listen('menu:action', (event) => {
+      const { action, error, handled } = event.payload;
+      if (error) {
+        alert(`${error.message}${error.recoveryHint ? `\n\n${error.recoveryHint}` : ''}`);
+        return;
+      }
+      if (!handled) {
+        menuHandlers.current[action]?.(event.payload);
+      }
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
   // Listen for export progress events
   React.useEffect(() => {
     const unlisten = listen('export-progress', (event) => {
@@ -573,7 +680,7 @@ function App() {
           onDeleteClip={timeline.removeClip}
           onSplitClip={timeline.splitClip}
           hasClipboard={!!timeline.clipboardClip}
-          onExport={handleExport}
+          onExport={() => handleExport()}
           isExporting={isExporting}
         />
       </div>
@@ -614,4 +721,22 @@ function App() {
   );
 }
 
+// Clip fields the export backend reads
+function toExportClips(clips) {
+  return clips.map(clip => ({
+    videoPath: clip.videoPath,
+    startTime: clip.startTime,
+    trimStart: clip.trimStart,
+    trimEnd: clip.trimEnd,
+    duration: clip.duration,
+    width: clip.width,
+    height: clip.height,
+    frameRate: clip.frameRate,
+    mediaType: clip.mediaType,
+    pipMetadataPath: clip.pipMetadataPath,
+    pipOverride: clip.pipOverride,
+    colorCorrection: clip.colorCorrection
+  }));
+}
+
 export default App;
@@ -1,5 +1,6 @@
 import { useState, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { open } from "@tauri-apps/plugin-dialog";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { useDraggable } from "@dnd-kit/core";
@@ -442,6 +443,7 @@ function MediaLibraryPanel({ mediaItems = [], onMediaImport, onMediaSelect, sele
           webcamStream: stream,
           screenRecordingConfig: selectedRecordingSource.config,
           includeSystemAudio: selectedRecordingSource.includeAudio,
+          audioAppFilter: selectedRecordingSource.audioAppFilter,
         });
 
         pipSessionRef.current = {
@@ -482,7 +484,8 @@ function MediaLibraryPanel({ mediaItems = [], onMediaImport, onMediaSelect, sele
         recordingType: 'screen',
         sourceId: selectedRecordingSource.source.id,
         config: selectedRecordingSource.config,
-        includeAudio: selectedRecordingSource.includeAudio
+        includeAudio: selectedRecordingSource.includeAudio,
+        audioAppFilter: selectedRecordingSource.audioAppFilter
       });
       // Notify parent about recording start with source information
       if (onRecordingStateChange) {
@@ -498,7 +501,7 @@ function MediaLibraryPanel({ mediaItems = [], onMediaImport, onMediaSelect, sele
       setMessageType("");
     } catch (err) {
       console.error('[MediaLibraryPanel] Failed to start recording:', err);
-      setMessage(`Failed to start recording: ${err}`);
+      setMessage(`Failed to start recording: ${err.message || err}`);
       setMessageType("error");
       setCountdown(null); // Clear countdown on error
       setTimeout(() => {
@@ -510,6 +513,16 @@ function MediaLibraryPanel({ mediaItems = [], onMediaImport, onMediaSelect, sele
     }
   };
 
+  // The Toggle Recording hotkey starts a recording with the selected source
+  const startRecordingRef = useRef(handleStartRecording);
+  startRecordingRef.current = handleStartRecording;
+  useEffect(() => {
+    const unlisten = listen("hotkey:start-recording", () => startRecordingRef.current());
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
   // Handle changing screen/window selection
   const handleChangeSource = () => {
     setIsRecordingModalOpen(true);
@@ -542,7 +555,7 @@ function MediaLibraryPanel({ mediaItems = [], onMediaImport, onMediaSelect, sele
         setMessageType("");
       } catch (err) {
         console.error('[MediaLibraryPanel] Failed to toggle PiP pause:', err);
-        setMessage(`Failed to ${pipIsPaused ? 'resume' : 'pause'} overlay recording: ${err}`);
+        setMessage(`Failed to ${pipIsPaused ? 'resume' : 'pause'} overlay recording: ${err.message || err}`);
         setMessageType('error');
         setTimeout(() => {
           setMessage('');
@@ -576,7 +589,7 @@ function MediaLibraryPanel({ mediaItems = [], onMediaImport, onMediaSelect, sele
       }
     } catch (err) {
       console.error('[MediaLibraryPanel] Failed to pause/resume recording:', err);
-      setMessage(`Failed to ${isPaused ? 'resume' : 'pause'} recording: ${err}`);
+      setMessage(`Failed to ${isPaused ? 'resume' : 'pause'} recording: ${err.message || err}`);
       setMessageType("error");
       setTimeout(() => {
         setMessage("");
@@ -676,7 +689,7 @@ function MediaLibraryPanel({ mediaItems = [], onMediaImport, onMediaSelect, sele
           onWebcamStreamChange(null);
         }
       }
-      setMessage(`Failed to stop recording: ${err}`);
+      setMessage(`Failed to stop recording: ${err.message || err}`);
       setMessageType("error");
       setTimeout(() => {
         setMessage("");
@@ -988,7 +1001,7 @@ function MediaLibraryPanel({ mediaItems = [], onMediaImport, onMediaSelect, sele
           }
         } catch (err) {
           console.error('[MediaLibraryPanel] Failed to save webcam recording:', err);
-          setMessage(`Failed to save recording: ${err}`);
+          setMessage(`Failed to save recording: ${err.message || err}`);
           setMessageType("error");
           setTimeout(() => {
             setMessage("");
@@ -40,6 +40,26 @@ function ScreenRecordingModal({ isOpen, onClose, onSourceSelect }) {
     }
   }, [isOpen]);
 
+  // Fill in window thumbnails as they are captured in the background
+  useEffect(() => {
+    if (!isOpen) return;
+
+    const unlistenPromise = listen('sources:window-thumbnail', (event) => {
+      const { sourceId, thumbnail } = event.payload;
+      if (!thumbnail) return;
+      setSources(prev => ({
+        ...prev,
+        windows: prev.windows?.map(source =>
+          source.id === sourceId ? { ...source, thumbnail } : source
+        ),
+      }));
+    });
+
+    return () => {
+      unlistenPromise.then(unlisten => unlisten());
+    };
+  }, [isOpen]);
+
   // Start/stop audio monitoring when microphone setting changes
   useEffect(() => {
     if (includeMicrophone && selectedAudioId) {
@@ -56,7 +76,8 @@ function ScreenRecordingModal({ isOpen, onClose, onSourceSelect }) {
 
     try {
       const screens = await invoke('enumerate_screens');
-      const windows = await invoke('enumerate_windows');
+      // Window thumbnails arrive afterwards via sources:window-thumbnail
+      const windows = await invoke('enumerate_windows_fast');
 
       setSources({ screens, windows });
 
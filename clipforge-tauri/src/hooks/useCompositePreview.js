@@ -16,12 +16,12 @@ export function useCompositePreview(screenCanvas, webcamVideo, pipConfig, enable
 
   /**
    * Calculate webcam overlay dimensions and position based on pipConfig
-   * MUST MATCH backend logic in composite_pip_recording (recording/mod.rs:2034-2079)
+   * MUST MATCH backend logic in PipLayout::calculate (commands/pip.rs)
    */
   const calculateOverlayLayout = useCallback((screenWidth, screenHeight, webcamWidth, webcamHeight) => {
     if (!pipConfig) return null;
 
-    // Size multipliers - MUST MATCH backend (recording/mod.rs:2034-2039)
+    // Size multipliers - MUST MATCH backend (commands/pip.rs size_factor)
     const sizeMultipliers = {
       small: 0.12,
       medium: 0.18,
@@ -34,10 +34,12 @@ export function useCompositePreview(screenCanvas, webcamVideo, pipConfig, enable
     let overlayWidth = Math.round(screenWidth * multiplier);
     overlayWidth = Math.max(32, Math.min(overlayWidth, screenWidth));
 
-    // Calculate webcam aspect ratio (default to 16:9 if unknown)
-    const webcamAspect = (webcamWidth && webcamHeight && webcamHeight > 0)
-      ? webcamWidth / webcamHeight
-      : 16.0 / 9.0;
+    // Calculate webcam aspect ratio (default to 16:9 if unknown, square for circles)
+    const webcamAspect = pipConfig.style?.shape === 'circle'
+      ? 1
+      : (webcamWidth && webcamHeight && webcamHeight > 0)
+        ? webcamWidth / webcamHeight
+        : 16.0 / 9.0;
 
     // Calculate overlay height maintaining aspect ratio
     let overlayHeight = Math.round(overlayWidth / webcamAspect);
@@ -51,10 +53,10 @@ export function useCompositePreview(screenCanvas, webcamVideo, pipConfig, enable
       overlayHeight = Math.max(2, overlayHeight - 1);
     }
 
-    // Fixed padding - MUST MATCH backend (recording/mod.rs:2065)
+    // Fixed padding - MUST MATCH backend (commands/pip.rs EDGE_PADDING)
     const padding = 20;
 
-    // Calculate position - MUST MATCH backend (recording/mod.rs:2066-2079)
+    // Calculate position - MUST MATCH backend (commands/pip.rs)
     let x, y;
     switch (pipConfig.position) {
       case 'topLeft':
@@ -76,6 +78,13 @@ export function useCompositePreview(screenCanvas, webcamVideo, pipConfig, enable
         break;
     }
 
+    // Free position - MUST MATCH backend (PipLayout::place_at)
+    if (typeof pipConfig.x === 'number' && typeof pipConfig.y === 'number') {
+      const clamp01 = (value) => Math.max(0, Math.min(1, value));
+      x = Math.round(clamp01(pipConfig.x) * Math.max(0, screenWidth - overlayWidth));
+      y = Math.round(clamp01(pipConfig.y) * Math.max(0, screenHeight - overlayHeight));
+    }
+
     return { x, y, width: overlayWidth, height: overlayHeight };
   }, [pipConfig]);
 
@@ -122,8 +131,13 @@ export function useCompositePreview(screenCanvas, webcamVideo, pipConfig, enable
         // Draw webcam overlay with rounded corners
         ctx.save();
 
-        // Create rounded rectangle path
-        const radius = 8; // Corner radius
+        // Create rounded rectangle path - circles and rounded shapes match the export
+        const shorterSide = Math.min(overlay.width, overlay.height);
+        const radius = pipConfig?.style?.shape === 'circle'
+          ? shorterSide / 2
+          : pipConfig?.style?.shape === 'rounded'
+            ? Math.max(0, Math.min(0.5, pipConfig.style.cornerRadius ?? 0.15)) * shorterSide
+            : 8; // Corner radius
         ctx.beginPath();
         ctx.moveTo(overlay.x + radius, overlay.y);
         ctx.lineTo(overlay.x + overlay.width - radius, overlay.y);
@@ -168,7 +182,7 @@ export function useCompositePreview(screenCanvas, webcamVideo, pipConfig, enable
 
     // Continue animation loop
     animationFrameRef.current = requestAnimationFrame(renderComposite);
-  }, [enabled, screenCanvas, webcamVideo, calculateOverlayLayout]);
+  }, [enabled, screenCanvas, webcamVideo, calculateOverlayLayout, pipConfig]);
 
   // Start/stop compositing based on enabled state
   useEffect(() => {
@@ -61,6 +61,13 @@ export function useMediaLibrary() {
     setMediaItems([]);
   }, []);
 
+  /**
+   * Replace the library with items from a project file
+   */
+  const loadMediaItems = useCallback((items) => {
+    setMediaItems(items);
+  }, []);
+
   return {
     // State
     mediaItems,
@@ -71,5 +78,6 @@ export function useMediaLibrary() {
     markAsUsed,
     getMediaItem,
     clearMediaLibrary,
+    loadMediaItems,
   };
 }
@@ -16,6 +16,7 @@ export function usePiPRecording() {
   const startTimeRef = useRef(null);
   const screenFilePathRef = useRef(null);
   const webcamFilePathRef = useRef(null);
+  const frameRateRef = useRef(null);
   const timerIntervalRef = useRef(null);
 
   // MediaRecorder for webcam
@@ -30,6 +31,7 @@ export function usePiPRecording() {
    * @param {Object} options.pipConfig PiP configuration
    * @param {MediaStream} options.webcamStream Webcam media stream
    * @param {Object} options.screenRecordingConfig Screen recording config
+   * @param {Object} [options.audioAppFilter] Applications whose audio is recorded or left out
    * @returns {Promise<string>} Recording ID
    */
   const startRecording = useCallback(async ({
@@ -38,6 +40,7 @@ export function usePiPRecording() {
     webcamStream,
     screenRecordingConfig,
     includeSystemAudio = true,
+    audioAppFilter,
   }) => {
     if (isRecording) {
       throw new Error('Recording already in progress');
@@ -62,9 +65,12 @@ export function usePiPRecording() {
         sourceId: screenSource.id,
         config: screenRecordingConfig,
         includeAudio: includeSystemAudio,
+        audioAppFilter,
       });
 
       screenFilePathRef.current = screenRecording.file_path;
+      // The webcam file is converted to the screen's frame rate so compositing stays in sync
+      frameRateRef.current = screenRecording.config?.frame_rate ?? null;
       // Step 2: Start webcam recording via MediaRecorder
       await startWebcamRecording(webcamStream);
       // Step 3: Start duration timer
@@ -232,7 +238,8 @@ export function usePiPRecording() {
           const filePath = await invoke('save_webcam_recording', {
             data: Array.from(uint8Array),
             mimeType: recorder.mimeType,
-            duration
+            duration,
+            frameRate: frameRateRef.current
           });
 
           // Reset chunks
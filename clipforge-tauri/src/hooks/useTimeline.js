@@ -63,6 +63,15 @@ export function useTimeline() {
   const canUndo = historyIndex > 0;
   const canRedo = historyIndex < history.length - 1;
 
+  // Replace the timeline with clips from a project file; history starts over
+  const loadClips = useCallback((newClips) => {
+    setClips(newClips);
+    setHistory([]);
+    setHistoryIndex(-1);
+    setSelectedClipId(null);
+    setPlayheadPosition(0);
+  }, []);
+
   // Check if a position would cause collision with existing clips
   const canDropAtPosition = useCallback((position, duration, excludeClipId = null) => {
     const endPosition = position + duration;
@@ -687,6 +696,7 @@ export function useTimeline() {
     // Actions
     addClip,
     addClips,
+    loadClips,
     insertClipWithShift,
     removeClip,
     updateClipPosition,
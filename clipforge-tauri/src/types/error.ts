@@ -0,0 +1,27 @@
+/**
+ * Error returned by backend commands (recording, export, metadata, preview)
+ */
+
+export type ClipForgeErrorCode =
+  | 'dependency_missing'
+  | 'permission_denied'
+  | 'disk_space_low'
+  | 'not_found'
+  | 'invalid_input'
+  | 'already_recording'
+  | 'not_recording'
+  | 'hardware_unavailable'
+  | 'ffmpeg_failed'
+  | 'capture_failed'
+  | 'io_error'
+  | 'internal'
+  | 'error';
+
+export interface ClipForgeError {
+  /** Stable identifier to branch on instead of matching message text */
+  code: ClipForgeErrorCode;
+  /** Human-readable description */
+  message: string;
+  /** What the user can do about it, when there is something */
+  recoveryHint: string | null;
+}
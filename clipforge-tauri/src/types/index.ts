@@ -3,3 +3,4 @@
  */
 
 export type { MediaItem, TimelineClip, LegacyClip } from './media';
+export type { ClipForgeError, ClipForgeErrorCode } from './error';
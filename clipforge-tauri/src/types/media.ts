@@ -1,3 +1,5 @@
+import type { PiPOverride } from './recording';
+
 /**
  * Media Item - Represents a media file in the Media Library staging area
  * Source media that can be added to the timeline multiple times
@@ -30,8 +32,8 @@ export interface MediaItem {
   /** Optional: Frame rate */
   frameRate?: number;
 
-  /** Optional: Type of media - 'standard' or 'pip' */
-  mediaType?: 'standard' | 'pip';
+  /** Optional: Type of media - 'standard', 'pip', 'image' or 'audio' */
+  mediaType?: 'standard' | 'pip' | 'image' | 'audio';
 
   /** Optional: Path to PiP metadata file (for PiP recordings) */
   pipMetadataPath?: string;
@@ -73,11 +75,17 @@ export interface TimelineClip {
   /** Optional: Frame rate */
   frameRate?: number;
 
-  /** Optional: Type of media - 'standard' or 'pip' */
-  mediaType?: 'standard' | 'pip';
+  /** Optional: Type of media - 'standard', 'pip', 'image' or 'audio' */
+  mediaType?: 'standard' | 'pip' | 'image' | 'audio';
 
   /** Optional: Path to PiP metadata file (for PiP recordings) */
   pipMetadataPath?: string;
+
+  /** Optional: Moves or resizes the webcam overlay of this clip (PiP recordings) */
+  pipOverride?: PiPOverride;
+
+  /** Optional: Brightness, contrast, saturation or LUT applied on export */
+  colorCorrection?: ColorCorrection;
 }
 
 /**
@@ -99,3 +107,17 @@ export interface LegacyClip {
 
 // Re-export PiP types from recording module for convenience
 export type { PiPRecordingMetadata, PiPConfiguration } from './recording';
+
+/**
+ * Per-clip color correction applied on export
+ */
+export interface ColorCorrection {
+  /** Added to the luma, -1 to 1 (default 0) */
+  brightness?: number;
+  /** Contrast multiplier, 0 to 2 (default 1) */
+  contrast?: number;
+  /** Saturation multiplier, 0 to 3 (default 1, 0 is grayscale) */
+  saturation?: number;
+  /** Path to a .cube or .3dl LUT applied after the adjustments */
+  lutPath?: string;
+}
@@ -14,6 +14,20 @@ export interface ScreenSource {
   scale_factor: number;
   thumbnail?: string; // base64 encoded PNG
   app_name?: string; // for windows
+  bundle_id?: string; // for windows
+  is_on_screen: boolean;
+}
+
+export type SourceSortOrder = 'system' | 'name' | 'appName' | 'area';
+
+export interface SourceFilter {
+  appName?: string;
+  bundleId?: string;
+  query?: string;
+  minWidth?: number;
+  minHeight?: number;
+  includeOffScreen?: boolean;
+  sort?: SourceSortOrder;
 }
 
 export type RecordingStatus = 'idle' | 'recording' | 'paused' | 'stopping' | 'error';
@@ -31,8 +45,73 @@ export interface RecordingConfig {
   audio_bitrate: number;
   audio_codec: string;
   output_format: string;
+  /** Live stream target (rtmp://, rtmps:// or srt://); a local copy is always recorded */
+  stream_url?: string | null;
+  /** Archival encoding; overrides video_codec and video_bitrate when set */
+  lossless?: LosslessMode | null;
+  /** Color space to record in; 'pq' needs h265 or a lossless mode */
+  color_mode?: ColorMode;
+  /** Name for the finished file, e.g. '{date}_{source}_{preset}'; placeholders: date, time, source, preset, type, id */
+  filename_template?: string | null;
+  /** Global shortcut that mutes the microphone while recording */
+  mic_hotkey?: MicHotkey | null;
+  /** Pause after this many minutes without keyboard or mouse input; resumes on activity */
+  idle_pause_minutes?: number | null;
+  /** Clock burned into the video */
+  timestamp_overlay?: TimestampOverlay | null;
+  /** Seconds cut from the start and end when the recording stops */
+  trim?: RecordingTrim | null;
+  /** Intro and outro joined onto the finished file */
+  stingers?: StingerSettings | null;
+}
+
+export interface Stinger {
+  /** Video or image file */
+  path: string;
+  /** Seconds an image is shown for (default 3); videos play in full */
+  imageDuration?: number;
+}
+
+export type StingerTransition = 'cut' | 'crossfade' | 'fadeThroughBlack';
+
+export interface StingerSettings {
+  intro?: Stinger | null;
+  outro?: Stinger | null;
+  transition?: StingerTransition;
+  /** Seconds, default 0.5; ignored for cuts */
+  transitionDuration?: number;
+}
+
+export interface RecordingTrim {
+  /** Moved forward to the next keyframe (at most 2 seconds later); 0-60 */
+  preRollSeconds?: number;
+  /** 0-60 */
+  postRollSeconds?: number;
 }
 
+export interface TimestampOverlay {
+  /** 'wallClock' shows the local date and time, 'elapsed' the time since the recording started */
+  mode?: TimestampMode;
+  position?: OverlayPosition;
+  /** Text height in output pixels (8-200, default 24) */
+  fontSize?: number;
+}
+
+export type TimestampMode = 'wallClock' | 'elapsed';
+
+export type OverlayPosition = 'topLeft' | 'topRight' | 'bottomLeft' | 'bottomRight';
+
+export interface MicHotkey {
+  /** e.g. 'CmdOrCtrl+Shift+M' */
+  shortcut: string;
+  /** Muted unless the shortcut is held; otherwise each press toggles mute */
+  push_to_talk?: boolean;
+}
+
+export type LosslessMode = 'h264' | 'prores' | 'ffv1';
+
+export type ColorMode = 'sdr' | 'p3' | 'pq';
+
 export interface RecordingState {
   id: string;
   recording_type: RecordingType;
@@ -43,6 +122,26 @@ export interface RecordingState {
   duration: number;
   file_path?: string;
   config: RecordingConfig;
+  /** Microphone silenced with set_mic_muted or the mic hotkey */
+  mic_muted?: boolean;
+  /** Encoding statistics, set once the recording has stopped */
+  summary?: RecordingSummary | null;
+  /** Paused automatically by idle_pause_minutes ("recording:idle-paused") */
+  paused_for_inactivity?: boolean;
+}
+
+export interface RecordingSummary {
+  /** Seconds from start to stop, including pauses */
+  wallDuration: number;
+  /** Duration of the finished file, if it could be probed */
+  encodedDuration: number | null;
+  encodedFrames: number;
+  averageFps: number | null;
+  droppedFrames: number;
+  duplicatedFrames: number;
+  /** Average total bitrate in kbit/s */
+  averageBitrateKbps: number | null;
+  fileSizeBytes: number;
 }
 
 export interface RecordingOptions {
@@ -50,6 +149,14 @@ export interface RecordingOptions {
   source_id: string;
   config?: RecordingConfig;
   include_audio: boolean;
+  /** Record only (or leave out) specific applications' audio (macOS 13+) */
+  audio_app_filter?: AudioAppFilter;
+}
+
+export interface AudioAppFilter {
+  mode: 'only' | 'exclude';
+  /** Bundle identifiers, e.g. "com.google.Chrome" */
+  bundleIds: string[];
 }
 
 // ============================================================================
@@ -95,6 +202,53 @@ export interface PiPConfiguration {
   includeAudio: boolean;
   /** Selected audio device ID for webcam */
   audioDeviceId?: string;
+  /** Free horizontal position, 0 (left) to 1 (right); replaces `position` when set with `y` */
+  x?: number;
+  /** Free vertical position, 0 (top) to 1 (bottom) */
+  y?: number;
+  /** Shape, border and shadow of the overlay */
+  style?: PiPStyle;
+  /** How screen and webcam are arranged on export (default 'overlay') */
+  mode?: PiPMode;
+}
+
+/**
+ * Arrangement of screen and webcam in the composite
+ * - overlay: webcam bubble on top of the screen
+ * - sideBySide: two halves, webcam on the side named by `position`
+ * - vertical: 9:16 stack, screen on top and webcam below (above for top positions)
+ * - cameraInset: full-frame webcam with the screen as the overlay
+ */
+export type PiPMode = 'overlay' | 'sideBySide' | 'vertical' | 'cameraInset';
+
+/**
+ * Outline of the webcam overlay
+ */
+export type PiPShape = 'rectangle' | 'rounded' | 'circle';
+
+/**
+ * How the webcam overlay is drawn on export
+ */
+export interface PiPStyle {
+  shape: PiPShape;
+  /** Corner radius of rounded overlays as a fraction of the shorter side (0 - 0.5) */
+  cornerRadius?: number;
+  /** Border width in pixels (0 - 40) */
+  borderWidth?: number;
+  /** Border color as hex RGB, e.g. '#FFFFFF' */
+  borderColor?: string;
+  /** Draw a drop shadow beneath the overlay */
+  shadow?: boolean;
+}
+
+/**
+ * Per-clip changes to the PiP overlay applied on export
+ */
+export interface PiPOverride {
+  position?: PiPPosition;
+  size?: PiPSize;
+  x?: number;
+  y?: number;
 }
 
 /**
@@ -138,6 +292,20 @@ export interface PiPRecordingMetadata {
     width: number;
     height: number;
   };
+  /** Changes to the overlay over time, applied on export */
+  keyframes?: PiPKeyframe[];
+}
+
+/**
+ * Hides, moves or resizes the PiP overlay between two recording times
+ */
+export interface PiPKeyframe extends PiPOverride {
+  /** Start time in seconds from the start of the recording */
+  start: number;
+  /** End time in seconds */
+  end: number;
+  /** Hide the webcam for this range */
+  hidden?: boolean;
 }
 
 /**
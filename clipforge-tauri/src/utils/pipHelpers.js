@@ -77,14 +77,14 @@ export function getPiPCompositeFFmpegArgs(metadata, outputPath) {
 function calculatePiPCoordinatesForFFmpeg(pipConfig, screenWidth, screenHeight) {
   const { position, size } = pipConfig;
 
-  // Size percentages
+  // Size percentages - MUST MATCH backend (commands/pip.rs size_factor)
   const sizeValues = {
-    small: 0.15,
-    medium: 0.25,
-    large: 0.35,
+    small: 0.12,
+    medium: 0.18,
+    large: 0.24,
   };
 
-  const sizePercent = sizeValues[size] || 0.25;
+  const sizePercent = sizeValues[size] || 0.18;
   const EDGE_PADDING = 20;
 
   // Calculate overlay dimensions
@@ -116,6 +116,13 @@ function calculatePiPCoordinatesForFFmpeg(pipConfig, screenWidth, screenHeight)
       y = screenHeight - overlayHeight - EDGE_PADDING;
   }
 
+  // Free position: 0-1 across the space the overlay can move in
+  if (typeof pipConfig.x === 'number' && typeof pipConfig.y === 'number') {
+    const clamp01 = (value) => Math.max(0, Math.min(1, value));
+    x = Math.round(clamp01(pipConfig.x) * Math.max(0, screenWidth - overlayWidth));
+    y = Math.round(clamp01(pipConfig.y) * Math.max(0, screenHeight - overlayHeight));
+  }
+
   return { x, y, width: overlayWidth, height: overlayHeight };
 }
 